@@ -2,16 +2,18 @@ pub mod well_known;
 
 use crate::{
     ast::well_known::VALIDATOR_ELSE,
+    builtins::{BUILTIN, BYTEARRAY, PRELUDE, STRING},
     expr::{TypedExpr, UntypedExpr},
     line_numbers::LineNumbers,
     parser::token::{Base, Token},
-    tipo::{PatternConstructor, Type, TypeInfo},
+    tipo::{error::Warning, PatternConstructor, Type, TypeInfo},
 };
 use indexmap::IndexMap;
 use miette::Diagnostic;
 use ordinal::Ordinal;
 use owo_colors::{OwoColorize, Stream::Stdout};
 use std::{
+    collections::HashSet,
     fmt::{self, Display},
     ops::Range,
     rc::Rc,
@@ -22,6 +24,8 @@ use vec1::{vec1, Vec1};
 pub const BACKPASS_VARIABLE: &str = "_backpass";
 pub const CAPTURE_VARIABLE: &str = "_capture";
 pub const PIPE_VARIABLE: &str = "_pipe";
+pub const VALIDATOR_REDEEMER_VARIABLE: &str = "__redeemer__";
+pub const VALIDATOR_DATUM_VARIABLE: &str = "__datum__";
 
 pub const ENV_MODULE: &str = "env";
 pub const CONFIG_MODULE: &str = "config";
@@ -63,10 +67,86 @@ impl ModuleKind {
     }
 }
 
+/// Unstable language features, which a module must explicitly opt into with an
+/// `@experimental("feature")` attribute before using them. There are none at the moment.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum Feature {}
+
+impl Feature {
+    pub const ALL: &'static [Feature] = &[];
+
+    pub fn name(&self) -> &'static str {
+        match *self {}
+    }
+
+    pub fn from_name(name: &str) -> Option<Feature> {
+        Feature::ALL
+            .iter()
+            .find(|feature| feature.name() == name)
+            .copied()
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A module attribute, e.g. `@experimental("feature")`, found at the very top of a module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Experimental {
+    pub location: Span,
+    pub feature: Feature,
+}
+
+/// A condition under which a definition is compiled, as given by a `@cfg(...)` attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum Cfg {
+    /// `@cfg(env = "mainnet")`: only compiled for the given environment.
+    Env(String),
+    /// `@cfg(test)`: only compiled when running tests or benchmarks.
+    Test,
+}
+
+impl Cfg {
+    /// Whether the condition holds when compiling for the given environment (falling back to
+    /// the default one), in or out of tests.
+    pub fn holds(&self, env: Option<&str>, test: bool) -> bool {
+        match self {
+            Cfg::Env(name) => name == env.unwrap_or(DEFAULT_ENV_MODULE),
+            Cfg::Test => test,
+        }
+    }
+}
+
+impl Display for Cfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cfg::Env(name) => write!(f, "env = \"{name}\""),
+            Cfg::Test => f.write_str("test"),
+        }
+    }
+}
+
+/// A `@cfg(...)` attribute, attached to the definition starting at `definition`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Conditional {
+    pub location: Span,
+    pub cfg: Cfg,
+    pub definition: usize,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Module<Info, Definitions> {
     pub name: String,
     pub docs: Vec<String>,
+    pub experimental: Vec<Experimental>,
+    pub conditionals: Vec<Conditional>,
+    /// Whether the built-in prelude is in scope without being imported.
+    pub implicit_prelude: bool,
     pub type_info: Info,
     pub definitions: Vec<Definitions>,
     pub lines: LineNumbers,
@@ -100,6 +180,73 @@ impl UntypedModule {
             })
             .collect()
     }
+
+    /// Prepend implicit imports (e.g. from a project-wide prelude) to the module's definitions.
+    /// Modules already imported explicitly are left alone, and a module never imports itself.
+    /// Implicit imports are flagged as such, which spares them from unused-import warnings.
+    pub fn add_implicit_imports(&mut self, imports: &[UntypedUse]) {
+        let implicit = imports
+            .iter()
+            .filter(|import| {
+                let name = import.module.join("/");
+                name != self.name
+                    && !self.definitions().any(|def| {
+                        matches!(def, Definition::Use(Use { module, .. }) if module == &import.module)
+                    })
+            })
+            .map(|import| {
+                Definition::Use(Use {
+                    location: Span::empty(),
+                    implicit: true,
+                    unqualified: import
+                        .unqualified
+                        .iter()
+                        .map(|unqualified| UnqualifiedImport {
+                            location: Span::empty(),
+                            ..unqualified.clone()
+                        })
+                        .collect(),
+                    ..import.clone()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.definitions.splice(0..0, implicit);
+    }
+
+    /// Drop definitions with a `@cfg(...)` attribute that doesn't hold in the given environment
+    /// and context.
+    pub fn retain_configured(&mut self, env: Option<&str>, test: bool) {
+        let excluded = self
+            .conditionals
+            .iter()
+            .filter(|conditional| !conditional.cfg.holds(env, test))
+            .map(|conditional| conditional.definition)
+            .collect::<HashSet<_>>();
+
+        if !excluded.is_empty() {
+            self.definitions
+                .retain(|def| !excluded.contains(&def.location().start));
+        }
+    }
+
+    /// `@cfg(env = "...")` attributes naming neither one of the given environments nor the
+    /// default one; the definitions they're attached to are never compiled, which is most likely
+    /// a typo.
+    pub fn unknown_environments(&self, env_modules: &[String]) -> Vec<Warning> {
+        self.conditionals
+            .iter()
+            .filter_map(|conditional| match &conditional.cfg {
+                Cfg::Env(name) if name != DEFAULT_ENV_MODULE && !env_modules.contains(name) => {
+                    Some(Warning::UnknownEnvironment {
+                        location: conditional.location,
+                        name: name.clone(),
+                    })
+                }
+                Cfg::Env(..) | Cfg::Test => None,
+            })
+            .collect()
+    }
 }
 
 impl TypedModule {
@@ -109,6 +256,131 @@ impl TypedModule {
             .find_map(|definition| definition.find_node(byte_index))
     }
 
+    /// Tuples larger than the given arity found in definitions: in signatures (function
+    /// arguments and return types, constants, type aliases and record fields), pointing at the
+    /// offending annotation when there's one; and as tuple expressions in function bodies and
+    /// constant values.
+    pub fn large_tuples(&self, max_arity: usize) -> Vec<Warning> {
+        fn signature(
+            warnings: &mut Vec<Warning>,
+            max_arity: usize,
+            annotation: Option<&Annotation>,
+            location: Span,
+            tipo: &Type,
+        ) {
+            let arity = tipo.max_tuple_arity();
+            if arity > max_arity {
+                let (location, arity) = annotation
+                    .and_then(|annotation| annotation.find_large_tuple(max_arity))
+                    .unwrap_or((location, arity));
+                warnings.push(Warning::LargeTuple {
+                    location,
+                    arity,
+                    max_arity,
+                });
+            }
+        }
+
+        fn function(warnings: &mut Vec<Warning>, max_arity: usize, fun: &TypedFunction) {
+            for arg in &fun.arguments {
+                signature(
+                    warnings,
+                    max_arity,
+                    arg.annotation.as_ref(),
+                    arg.location,
+                    &arg.tipo,
+                );
+            }
+            signature(
+                warnings,
+                max_arity,
+                fun.return_annotation.as_ref(),
+                fun.location,
+                &fun.return_type,
+            );
+        }
+
+        let mut warnings = vec![];
+
+        let mut bodies = vec![];
+
+        for definition in &self.definitions {
+            match definition {
+                Definition::Fn(fun) => {
+                    function(&mut warnings, max_arity, fun);
+                    bodies.push(&fun.body);
+                }
+                Definition::Validator(validator) => {
+                    for param in &validator.params {
+                        signature(
+                            &mut warnings,
+                            max_arity,
+                            param.annotation.as_ref(),
+                            param.location,
+                            &param.tipo,
+                        );
+                    }
+                    for handler in validator.handlers.iter().chain([&validator.fallback]) {
+                        function(&mut warnings, max_arity, handler);
+                        bodies.push(&handler.body);
+                    }
+                }
+                Definition::ModuleConstant(constant) => {
+                    signature(
+                        &mut warnings,
+                        max_arity,
+                        constant.annotation.as_ref(),
+                        constant.location,
+                        &constant.value.tipo(),
+                    );
+                    bodies.push(&constant.value);
+                }
+                Definition::TypeAlias(alias) => {
+                    signature(
+                        &mut warnings,
+                        max_arity,
+                        Some(&alias.annotation),
+                        alias.location,
+                        &alias.tipo,
+                    );
+                }
+                Definition::DataType(data_type) => {
+                    for constructor in &data_type.constructors {
+                        for arg in &constructor.arguments {
+                            signature(
+                                &mut warnings,
+                                max_arity,
+                                Some(&arg.annotation),
+                                arg.location,
+                                &arg.tipo,
+                            );
+                        }
+                    }
+                }
+                Definition::Test(..) | Definition::Benchmark(..) | Definition::Use(..) => (),
+            }
+        }
+
+        for body in bodies {
+            body.walk(&mut |expr| {
+                if let TypedExpr::Tuple {
+                    location, elems, ..
+                } = expr
+                {
+                    if elems.len() > max_arity {
+                        warnings.push(Warning::LargeTuple {
+                            location: *location,
+                            arity: elems.len(),
+                            max_arity,
+                        });
+                    }
+                }
+            });
+        }
+
+        warnings
+    }
+
     pub fn has_definition(&self, name: &str) -> bool {
         self.definitions.iter().any(|def| match def {
             Definition::Fn(f) => f.public && f.name == name,
@@ -140,7 +412,7 @@ impl TypedModule {
     }
 
     pub fn validate_module_name(&self) -> Result<(), Error> {
-        if self.name == "aiken" || self.name == "aiken/builtin" {
+        if [PRELUDE, BUILTIN, STRING, BYTEARRAY].contains(&self.name.as_str()) {
             return Err(Error::ReservedModuleName {
                 name: self.name.to_string(),
             });
@@ -301,6 +573,9 @@ pub struct Function<T, Expr, Arg> {
     pub return_type: T,
     pub end_position: usize,
     pub on_test_failure: OnTestFailure,
+    /// For tests marked as `@example(function)`, the name of the function (from the same
+    /// module) whose documentation they illustrate.
+    pub example: Option<String>,
 }
 
 impl<T, Expr, Arg> Function<T, Expr, Arg> {
@@ -383,6 +658,7 @@ impl From<UntypedTest> for UntypedFunction {
             return_type: f.return_type,
             body: f.body,
             on_test_failure: f.on_test_failure,
+            example: f.example,
             end_position: f.end_position,
         }
     }
@@ -400,6 +676,7 @@ impl From<TypedTest> for TypedFunction {
             return_type: f.return_type,
             body: f.body,
             on_test_failure: f.on_test_failure,
+            example: f.example,
             end_position: f.end_position,
         }
     }
@@ -481,9 +758,14 @@ pub type UntypedUse = Use<()>;
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Use<PackageName> {
     pub as_name: Option<String>,
+    /// Whether the import was added implicitly (e.g. from a project's prelude) rather than
+    /// written in the module.
+    pub implicit: bool,
     pub location: Span,
     pub module: Vec<String>,
     pub package: PackageName,
+    /// Whether the unqualified imports are re-exported (i.e. 'pub use').
+    pub public: bool,
     pub unqualified: Vec<UnqualifiedImport>,
 }
 
@@ -498,6 +780,10 @@ pub struct ModuleConstant<Expr> {
     pub name: String,
     pub annotation: Option<Annotation>,
     pub value: Expr,
+    /// For constants declared as `@fixture("file")`, the file (under the package's 'fixtures'
+    /// folder) whose content becomes the value. Until it's loaded, the value is an empty
+    /// bytearray.
+    pub fixture: Option<String>,
 }
 
 pub type TypedValidator = Validator<Rc<Type>, TypedArg, TypedExpr>;
@@ -543,6 +829,7 @@ impl UntypedValidator {
                 annotation: None,
                 doc: None,
                 is_validator_param: false,
+                default: None,
             }],
             body: UntypedExpr::fail(None, location),
             doc: None,
@@ -553,6 +840,7 @@ impl UntypedValidator {
             return_annotation: Some(Annotation::boolean(location)),
             return_type: (),
             on_test_failure: OnTestFailure::FailImmediately,
+            example: None,
         }
     }
 }
@@ -575,13 +863,71 @@ impl TypedValidator {
     pub fn into_script_context_handler(&self) -> TypedExpr {
         let var_context = "__context__";
         let var_transaction = "__transaction__";
-        let var_redeemer = "__redeemer__";
+        let var_redeemer = VALIDATOR_REDEEMER_VARIABLE;
         let var_purpose = "__purpose__";
         let var_purpose_arg = "__purpose_arg__";
-        let var_datum = "__datum__";
+        let var_datum = VALIDATOR_DATUM_VARIABLE;
+
+        // A handler exported under several purposes is a single declaration (and so, a single
+        // location) shared by several handlers. Its body is bound once, as a function of its
+        // named arguments, which each of those purposes then calls.
+        let mut shared_handlers: IndexMap<Span, (String, Vec<TypedArg>, Rc<Type>, &TypedExpr)> =
+            IndexMap::new();
+
+        for handler in self.handlers.iter() {
+            let purposes = self
+                .handlers
+                .iter()
+                .filter(|other| other.location == handler.location)
+                .count();
+
+            if purposes > 1 && !shared_handlers.contains_key(&handler.location) {
+                let arguments = handler
+                    .arguments
+                    .iter()
+                    .filter(|arg| arg.get_variable_name().is_some())
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                let tipo = Type::function(
+                    arguments.iter().map(|arg| arg.tipo.clone()).collect(),
+                    Type::bool(),
+                );
+
+                shared_handlers.insert(
+                    handler.location,
+                    (
+                        format!("__{}_handler__", handler.name),
+                        arguments,
+                        tipo,
+                        &handler.body,
+                    ),
+                );
+            }
+        }
 
-        let context_handler = TypedExpr::sequence(&[
-            TypedExpr::let_(
+        let shared_definitions = shared_handlers
+            .values()
+            .map(|(name, arguments, tipo, body)| {
+                TypedExpr::let_(
+                    TypedExpr::Fn {
+                        location: body.location(),
+                        tipo: tipo.clone(),
+                        is_capture: false,
+                        args: arguments.clone(),
+                        body: Box::new((*body).clone()),
+                        return_annotation: None,
+                    },
+                    TypedPattern::var(name),
+                    tipo.clone(),
+                    Span::empty(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let context_handler = TypedExpr::Sequence {
+            location: Span::empty(),
+            expressions: [TypedExpr::let_(
                 TypedExpr::local_var(var_context, Type::script_context(), self.location),
                 TypedPattern::Constructor {
                     is_record: false,
@@ -605,8 +951,10 @@ impl TypedValidator {
                 },
                 Type::script_context(),
                 Span::empty(),
-            ),
-            TypedExpr::When {
+            )]
+            .into_iter()
+            .chain(shared_definitions)
+            .chain([TypedExpr::When {
                 location: Span::empty(),
                 tipo: Type::bool(),
                 subject: TypedExpr::local_var(var_purpose, Type::script_purpose(), Span::empty())
@@ -717,7 +1065,27 @@ impl TypedValidator {
                             ));
                         }
 
-                        then.push(handler.body.clone());
+                        then.push(match shared_handlers.get(&handler.location) {
+                            Some((name, arguments, tipo, _)) => TypedExpr::Call {
+                                location: handler.location,
+                                tipo: Type::bool(),
+                                fun: TypedExpr::local_var(name, tipo.clone(), handler.location)
+                                    .into(),
+                                args: arguments
+                                    .iter()
+                                    .map(|arg| CallArg {
+                                        label: None,
+                                        location: arg.location,
+                                        value: TypedExpr::local_var(
+                                            arg.get_variable_name().unwrap_or_default(),
+                                            arg.tipo.clone(),
+                                            arg.location,
+                                        ),
+                                    })
+                                    .collect(),
+                            },
+                            None => handler.body.clone(),
+                        });
 
                         TypedClause {
                             location: Span::empty(),
@@ -758,8 +1126,9 @@ impl TypedValidator {
                         }
                     }))
                     .collect(),
-            },
-        ]);
+            }])
+            .collect(),
+        };
 
         if self.handlers.is_empty() {
             let fallback = &self.fallback;
@@ -1077,6 +1446,7 @@ pub struct UntypedArg {
     pub annotation: Option<Annotation>,
     pub doc: Option<String>,
     pub is_validator_param: bool,
+    pub default: Option<DefaultValue>,
 }
 
 impl UntypedArg {
@@ -1105,6 +1475,59 @@ impl UntypedArg {
             annotation: self.annotation,
             is_validator_param: self.is_validator_param,
             doc: self.doc,
+            default: self.default,
+        }
+    }
+}
+
+/// A literal value given to an optional trailing function parameter. Call sites omitting the
+/// argument are completed with this value during type-checking.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DefaultValue {
+    Int {
+        location: Span,
+        value: String,
+        base: Base,
+    },
+    ByteArray {
+        location: Span,
+        bytes: Vec<u8>,
+        preferred_format: ByteArrayFormatPreference,
+    },
+}
+
+impl DefaultValue {
+    pub fn location(&self) -> Span {
+        match self {
+            DefaultValue::Int { location, .. } | DefaultValue::ByteArray { location, .. } => {
+                *location
+            }
+        }
+    }
+
+    pub fn tipo(&self) -> Rc<Type> {
+        match self {
+            DefaultValue::Int { .. } => Type::int(),
+            DefaultValue::ByteArray { .. } => Type::byte_array(),
+        }
+    }
+
+    pub fn to_expr(&self, location: Span) -> UntypedExpr {
+        match self {
+            DefaultValue::Int { value, base, .. } => UntypedExpr::UInt {
+                location,
+                value: value.clone(),
+                base: *base,
+            },
+            DefaultValue::ByteArray {
+                bytes,
+                preferred_format,
+                ..
+            } => UntypedExpr::ByteArray {
+                location,
+                bytes: bytes.clone(),
+                preferred_format: *preferred_format,
+            },
         }
     }
 }
@@ -1116,6 +1539,7 @@ pub struct TypedArg {
     pub annotation: Option<Annotation>,
     pub doc: Option<String>,
     pub is_validator_param: bool,
+    pub default: Option<DefaultValue>,
     pub tipo: Rc<Type>,
 }
 
@@ -1131,6 +1555,7 @@ impl TypedArg {
             annotation: None,
             doc: None,
             is_validator_param: false,
+            default: None,
             tipo: tipo.clone(),
         }
     }
@@ -1293,6 +1718,30 @@ pub enum Annotation {
 }
 
 impl Annotation {
+    /// The outermost tuple annotation, within this one, with more elements than the given
+    /// arity; along with its number of elements.
+    pub fn find_large_tuple(&self, max_arity: usize) -> Option<(Span, usize)> {
+        match self {
+            Annotation::Tuple { location, elems } if elems.len() > max_arity => {
+                Some((*location, elems.len()))
+            }
+            Annotation::Tuple { elems, .. } => elems
+                .iter()
+                .find_map(|elem| elem.find_large_tuple(max_arity)),
+            Annotation::Constructor { arguments, .. } => arguments
+                .iter()
+                .find_map(|arg| arg.find_large_tuple(max_arity)),
+            Annotation::Fn { arguments, ret, .. } => arguments
+                .iter()
+                .chain(std::iter::once(ret.as_ref()))
+                .find_map(|arg| arg.find_large_tuple(max_arity)),
+            Annotation::Pair { fst, snd, .. } => fst
+                .find_large_tuple(max_arity)
+                .or_else(|| snd.find_large_tuple(max_arity)),
+            Annotation::Var { .. } | Annotation::Hole { .. } => None,
+        }
+    }
+
     pub fn location(&self) -> Span {
         match self {
             Annotation::Fn { location, .. }
@@ -1494,8 +1943,18 @@ pub enum BinOp {
     AddInt,
     SubInt,
     MultInt,
+    /// Integer division, rounded towards negative infinity (i.e. 'divideInteger').
+    /// Use 'quotient_integer' from 'aiken/builtin' to round towards zero instead.
     DivInt,
+    /// Remainder of a division rounded towards negative infinity; it has the sign of the
+    /// divisor (i.e. 'modInteger'). Use 'remainder_integer' from 'aiken/builtin' for the
+    /// remainder of a truncated division, which has the sign of the dividend.
     ModInt,
+
+    // Concatenation
+    /// Concatenation of two 'String' (i.e. 'appendString') or two 'ByteArray'
+    /// (i.e. 'appendByteString').
+    Concat,
 }
 
 impl From<LogicalOpChainKind> for BinOp {
@@ -1527,7 +1986,8 @@ impl BinOp {
 
             Self::Eq | Self::NotEq | Self::LtInt | Self::LtEqInt | Self::GtEqInt | Self::GtInt => 4,
 
-            // Concatenation operators are typically 5, so we skip it.
+            Self::Concat => 5,
+
             Self::AddInt | Self::SubInt => 6,
 
             Self::MultInt | Self::DivInt | Self::ModInt => 7,
@@ -2278,10 +2738,32 @@ pub struct RecordUpdateSpread {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TraceKind {
     Trace,
+    Debug,
     Todo,
     Error,
 }
 
+/// Severity of user-defined traces; `trace` stands for `Info` whereas `trace.debug` is `Debug`.
+/// Traces below a given severity can be compiled out without touching the source code.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceSeverity {
+    #[default]
+    Debug,
+    Info,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tracing {
     UserDefined(TraceLevel),
@@ -2490,7 +2972,8 @@ pub enum Error {
     #[diagnostic(help(r#"Some module names are reserved for internal use. This the case of:
 
 - aiken: where the prelude is located;
-- aiken/builtin: where I store low-level Plutus builtins.
+- aiken/builtin: where I store low-level Plutus builtins;
+- aiken/string, aiken/bytearray: where I provide helpers to render values as strings.
 
 Note that 'aiken' is also imported by default; but you can refer to it explicitly to disambiguate with a local value that would clash with one from that module."#
     ))]
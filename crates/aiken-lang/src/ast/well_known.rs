@@ -52,6 +52,90 @@ pub const SCRIPT_PURPOSE_CONSTRUCTORS: &[&str] = &[
     SCRIPT_PURPOSE_PROPOSE,
 ];
 
+/// How the ledger names the constructors of `ScriptInfo` (Plutus V3), against the script purpose
+/// each maps to. The order and fields of the constructors come from the vendored ledger spec (see
+/// [`script_purposes`]); this only carries names.
+pub const SCRIPT_PURPOSE_LEDGER_NAMES: &[(&str, &str)] = &[
+    ("MintingScript", SCRIPT_PURPOSE_MINT),
+    ("SpendingScript", SCRIPT_PURPOSE_SPEND),
+    ("RewardingScript", SCRIPT_PURPOSE_WITHDRAW),
+    ("CertifyingScript", SCRIPT_PURPOSE_PUBLISH),
+    ("VotingScript", SCRIPT_PURPOSE_VOTE),
+    ("ProposingScript", SCRIPT_PURPOSE_PROPOSE),
+];
+
+/// How the ledger names the fields of `ScriptContext` (Plutus V3), against our own field names.
+pub const SCRIPT_CONTEXT_LEDGER_NAMES: &[(&str, &str)] = &[
+    ("scriptContextTxInfo", SCRIPT_CONTEXT_TRANSACTION),
+    ("scriptContextRedeemer", SCRIPT_CONTEXT_REDEEMER),
+    ("scriptContextScriptInfo", SCRIPT_CONTEXT_PURPOSE),
+];
+
+/// The script purposes and the fields they carry, in on-chain order. Constructor indices are
+/// positional, so this is derived from the ledger spec rather than maintained by hand.
+pub fn script_purposes() -> Vec<(&'static str, Vec<LedgerField>)> {
+    uplc::tx::ledger_spec::plutus_v3("ScriptInfo")
+        .iter()
+        .map(|constructor| {
+            (
+                from_ledger_name(SCRIPT_PURPOSE_LEDGER_NAMES, &constructor.name),
+                constructor
+                    .fields
+                    .iter()
+                    .map(|field| LedgerField::from_ledger_type(&field.tipo))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// The fields of the script context, in on-chain order, as derived from the ledger spec.
+pub fn script_context_fields() -> Vec<&'static str> {
+    uplc::tx::ledger_spec::plutus_v3("ScriptContext")
+        .iter()
+        .flat_map(|constructor| constructor.fields.iter())
+        .map(|field| from_ledger_name(SCRIPT_CONTEXT_LEDGER_NAMES, &field.name))
+        .collect()
+}
+
+fn from_ledger_name(names: &[(&str, &'static str)], ledger_name: &str) -> &'static str {
+    names
+        .iter()
+        .find(|(name, _)| *name == ledger_name)
+        .map(|(_, ours)| *ours)
+        .unwrap_or_else(|| panic!("no known counterpart to '{ledger_name}' from the ledger spec"))
+}
+
+/// Shape of a field in the ledger's script context, as seen from a validator. Anything not worth
+/// decoding up-front is left as opaque 'Data'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerField {
+    Data,
+    Int,
+    OptionalData,
+}
+
+impl LedgerField {
+    /// Interpret a type as written in the ledger spec (e.g. 'Maybe Datum').
+    pub fn from_ledger_type(tipo: &str) -> Self {
+        if tipo == "Integer" {
+            LedgerField::Int
+        } else if tipo.starts_with("Maybe ") {
+            LedgerField::OptionalData
+        } else {
+            LedgerField::Data
+        }
+    }
+
+    pub fn tipo(&self) -> Rc<Type> {
+        match self {
+            LedgerField::Data => Type::data(),
+            LedgerField::Int => Type::int(),
+            LedgerField::OptionalData => Type::option(Type::data()),
+        }
+    }
+}
+
 pub const VALIDATOR_ELSE: &str = "else";
 
 // ----------------------------------------------------------------------------
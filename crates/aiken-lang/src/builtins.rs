@@ -24,6 +24,8 @@ use uplc::{
 
 pub const PRELUDE: &str = "aiken";
 pub const BUILTIN: &str = "aiken/builtin";
+pub const STRING: &str = "aiken/string";
+pub const BYTEARRAY: &str = "aiken/bytearray";
 
 /// Build a prelude that can be injected
 /// into a compiler pipeline
@@ -37,6 +39,7 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
         values: HashMap::new(),
         accessors: HashMap::new(),
         annotations: HashMap::new(),
+        reexports: HashMap::new(),
     };
 
     // Data
@@ -193,11 +196,10 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
     );
     prelude.types_constructors.insert(
         well_known::SCRIPT_CONTEXT.to_string(),
-        vec![
-            well_known::SCRIPT_CONTEXT_TRANSACTION.to_string(),
-            well_known::SCRIPT_CONTEXT_REDEEMER.to_string(),
-            well_known::SCRIPT_CONTEXT_PURPOSE.to_string(),
-        ],
+        well_known::script_context_fields()
+            .into_iter()
+            .map(|field| field.to_string())
+            .collect(),
     );
 
     // Cardano ScriptPurpose
@@ -210,35 +212,18 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
         well_known::SCRIPT_PURPOSE.to_string(),
         ValueConstructor::known_adt(
             &mut prelude.values,
-            &[
-                (
-                    well_known::SCRIPT_PURPOSE_MINT,
-                    Type::function(vec![Type::data()], Type::script_purpose()),
-                ),
-                (
-                    well_known::SCRIPT_PURPOSE_SPEND,
-                    Type::function(
-                        vec![Type::data(), Type::option(Type::data())],
-                        Type::script_purpose(),
-                    ),
-                ),
-                (
-                    well_known::SCRIPT_PURPOSE_WITHDRAW,
-                    Type::function(vec![Type::data()], Type::script_purpose()),
-                ),
-                (
-                    well_known::SCRIPT_PURPOSE_PUBLISH,
-                    Type::function(vec![Type::int(), Type::data()], Type::script_purpose()),
-                ),
-                (
-                    well_known::SCRIPT_PURPOSE_VOTE,
-                    Type::function(vec![Type::data()], Type::script_purpose()),
-                ),
-                (
-                    well_known::SCRIPT_PURPOSE_PROPOSE,
-                    Type::function(vec![Type::int(), Type::data()], Type::script_purpose()),
-                ),
-            ],
+            &well_known::script_purposes()
+                .iter()
+                .map(|(constructor, fields)| {
+                    (
+                        *constructor,
+                        Type::function(
+                            fields.iter().map(|field| field.tipo()).collect(),
+                            Type::script_purpose(),
+                        ),
+                    )
+                })
+                .collect::<Vec<_>>(),
         ),
     );
 
@@ -449,6 +434,7 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
                     arity: 2,
                     fields: seeded_fields,
                     is_function: false,
+                    defaults: HashMap::new(),
                 }),
                 arity: 2,
                 location: Span::empty(),
@@ -471,6 +457,7 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
                     arity: 2,
                     fields: replayed_fields,
                     is_function: false,
+                    defaults: HashMap::new(),
                 }),
                 arity: 2,
                 location: Span::empty(),
@@ -524,6 +511,7 @@ pub fn plutus(id_gen: &IdGenerator) -> TypeInfo {
         values: HashMap::new(),
         accessors: HashMap::new(),
         annotations: HashMap::new(),
+        reexports: HashMap::new(),
     };
 
     for builtin in DefaultFunction::iter() {
@@ -566,6 +554,74 @@ pub fn plutus(id_gen: &IdGenerator) -> TypeInfo {
     plutus
 }
 
+/// Compiler-provided 'aiken/string' module, e.g. to render values in trace messages without
+/// depending on the standard library. Functions are implemented in 'prelude_functions'.
+pub fn string() -> TypeInfo {
+    intrinsics(
+        STRING,
+        [(
+            "from_int",
+            Type::function(vec![Type::int()], Type::string()),
+        )],
+    )
+}
+
+/// Compiler-provided 'aiken/bytearray' module. Functions are implemented in 'prelude_functions'.
+pub fn bytearray() -> TypeInfo {
+    intrinsics(
+        BYTEARRAY,
+        [(
+            "to_hex",
+            Type::function(vec![Type::byte_array()], Type::string()),
+        )],
+    )
+}
+
+/// Every compiler-provided module, keyed by module name, as expected by the type-checker.
+pub fn modules(id_gen: &IdGenerator) -> HashMap<String, TypeInfo> {
+    HashMap::from([
+        (PRELUDE.to_string(), prelude(id_gen)),
+        (BUILTIN.to_string(), plutus(id_gen)),
+        (STRING.to_string(), string()),
+        (BYTEARRAY.to_string(), bytearray()),
+    ])
+}
+
+fn intrinsics<const N: usize>(module: &str, functions: [(&str, Rc<Type>); N]) -> TypeInfo {
+    let mut info = TypeInfo {
+        name: module.to_string(),
+        package: "".to_string(),
+        kind: ModuleKind::Lib,
+        types: HashMap::new(),
+        types_constructors: HashMap::new(),
+        values: HashMap::new(),
+        accessors: HashMap::new(),
+        annotations: HashMap::new(),
+        reexports: HashMap::new(),
+    };
+
+    for (name, tipo) in functions {
+        let arity = tipo.fn_arity().unwrap_or_default();
+
+        info.values.insert(
+            name.to_string(),
+            ValueConstructor::public(
+                tipo,
+                ValueConstructorVariant::ModuleFn {
+                    name: name.to_string(),
+                    field_map: None,
+                    module: module.to_string(),
+                    arity,
+                    location: Span::empty(),
+                    builtin: None,
+                },
+            ),
+        );
+    }
+
+    info
+}
+
 pub fn from_default_function(builtin: DefaultFunction, id_gen: &IdGenerator) -> ValueConstructor {
     let (tipo, arity) = match builtin {
         DefaultFunction::AddInteger
@@ -1091,12 +1147,14 @@ pub fn prelude_functions(
                 location: Span::empty(),
             },
             is_validator_param: false,
+            default: None,
             doc: None,
             location: Span::empty(),
             annotation: None,
             tipo: Type::data(),
         }],
         on_test_failure: OnTestFailure::FailImmediately,
+        example: None,
         doc: Some(
             indoc::indoc! {
                 r#"
@@ -1155,12 +1213,14 @@ pub fn prelude_functions(
                 location: Span::empty(),
             },
             is_validator_param: false,
+            default: None,
             doc: None,
             location: Span::empty(),
             annotation: None,
             tipo: Type::data(),
         }],
         on_test_failure: OnTestFailure::FailImmediately,
+        example: None,
         doc: Some(
             indoc::indoc! {
                 r#"
@@ -1202,12 +1262,14 @@ pub fn prelude_functions(
                     location: Span::empty(),
                 },
                 is_validator_param: false,
+                default: None,
                 doc: None,
                 location: Span::empty(),
                 annotation: None,
                 tipo: Type::bool(),
             }],
             on_test_failure: OnTestFailure::FailImmediately,
+            example: None,
             doc: Some(
                 indoc::indoc! {
                     r#"
@@ -1259,12 +1321,14 @@ pub fn prelude_functions(
                     location: Span::empty(),
                 },
                 is_validator_param: false,
+                default: None,
                 location: Span::empty(),
                 annotation: None,
                 doc: None,
                 tipo: a_var.clone(),
             }],
             on_test_failure: OnTestFailure::FailImmediately,
+            example: None,
             body: TypedExpr::Var {
                 location: Span::empty(),
                 constructor: ValueConstructor {
@@ -1307,6 +1371,7 @@ pub fn prelude_functions(
         },
         Function {
             on_test_failure: OnTestFailure::FailImmediately,
+            example: None,
             arguments: vec![
                 TypedArg {
                     arg_name: ArgName::Named {
@@ -1315,6 +1380,7 @@ pub fn prelude_functions(
                         location: Span::empty(),
                     },
                     is_validator_param: false,
+                    default: None,
                     location: Span::empty(),
                     annotation: None,
                     doc: None,
@@ -1327,6 +1393,7 @@ pub fn prelude_functions(
                         location: Span::empty(),
                     },
                     is_validator_param: false,
+                    default: None,
                     location: Span::empty(),
                     annotation: None,
                     doc: None,
@@ -1386,6 +1453,7 @@ pub fn prelude_functions(
         },
         Function {
             on_test_failure: OnTestFailure::FailImmediately,
+            example: None,
             arguments: vec![TypedArg {
                 arg_name: ArgName::Named {
                     name: "f".to_string(),
@@ -1393,6 +1461,7 @@ pub fn prelude_functions(
                     location: Span::empty(),
                 },
                 is_validator_param: false,
+                default: None,
                 location: Span::empty(),
                 annotation: None,
                 doc: None,
@@ -1410,6 +1479,7 @@ pub fn prelude_functions(
                             location: Span::empty(),
                         },
                         is_validator_param: false,
+                        default: None,
                         location: Span::empty(),
                         annotation: None,
                         doc: None,
@@ -1422,6 +1492,7 @@ pub fn prelude_functions(
                             location: Span::empty(),
                         },
                         is_validator_param: false,
+                        default: None,
                         location: Span::empty(),
                         annotation: None,
                         doc: None,
@@ -1550,13 +1621,13 @@ pub fn prelude_functions(
                         msb + if msb < 10 {
                           48
                         } else {
-                          55
+                          87
                         },
                         builtin.cons_bytearray(
                           lsb + if lsb < 10 {
                             48
                           } else {
-                            55
+                            87
                           },
                           builder,
                         ),
@@ -1726,6 +1797,46 @@ pub fn prelude_functions(
         ),
     );
 
+    functions.insert(
+        FunctionAccessKey {
+            module_name: STRING.to_string(),
+            function_name: "from_int".to_string(),
+        },
+        aiken_fn!(
+            &module_types,
+            &id_gen,
+            r#"
+                use aiken/builtin
+
+                /// Decimal representation of an integer, e.g. for use in traces.
+                fn string_from_int(i: Int) -> String {
+                  builtin.decode_utf8(from_int(i, #""))
+                }
+            "#
+        ),
+    );
+
+    functions.insert(
+        FunctionAccessKey {
+            module_name: BYTEARRAY.to_string(),
+            function_name: "to_hex".to_string(),
+        },
+        aiken_fn!(
+            &module_types,
+            &id_gen,
+            r#"
+                use aiken/builtin
+
+                /// Base16 (lower-case) representation of a bytearray, e.g. for use in traces.
+                fn bytearray_to_hex(bytes: ByteArray) -> String {
+                  bytes
+                    |> encode_base16(builtin.length_of_bytearray(bytes) - 1, #"")
+                    |> builtin.decode_utf8
+                }
+            "#
+        ),
+    );
+
     functions
 }
 
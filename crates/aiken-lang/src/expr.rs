@@ -337,6 +337,146 @@ impl TypedExpr {
         matches!(self, Self::ErrorTerm { .. })
     }
 
+    /// The value of a literal 'True' or 'False', if that's what the expression is.
+    pub fn literal_bool(&self) -> Option<bool> {
+        match self {
+            Self::Var {
+                name,
+                constructor:
+                    ValueConstructor {
+                        variant: ValueConstructorVariant::Record { .. },
+                        tipo,
+                        ..
+                    },
+                ..
+            } if tipo.is_bool() => match name.as_str() {
+                "True" => Some(true),
+                "False" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_zero_int_literal(&self) -> bool {
+        match self {
+            Self::UInt { value, .. } => value.chars().all(|c| c == '0'),
+            Self::UnOp {
+                op: UnOp::Negate,
+                value,
+                ..
+            } => value.is_zero_int_literal(),
+            _ => false,
+        }
+    }
+
+    pub fn is_negative_int_literal(&self) -> bool {
+        matches!(
+            self,
+            Self::UnOp { op: UnOp::Negate, value, .. } if matches!(value.as_ref(), Self::UInt { .. })
+        )
+    }
+
+    /// Labels of the literal 'fail' and 'todo' expressions found in this expression, in order
+    /// of appearance. Labels built at runtime (e.g. from a variable) are ignored.
+    pub fn failure_labels(&self) -> Vec<&str> {
+        let mut labels = vec![];
+        self.collect_failure_labels(&mut labels);
+        labels
+    }
+
+    fn collect_failure_labels<'a>(&'a self, labels: &mut Vec<&'a str>) {
+        match self {
+            Self::UInt { .. }
+            | Self::String { .. }
+            | Self::ByteArray { .. }
+            | Self::CurvePoint { .. }
+            | Self::ErrorTerm { .. }
+            | Self::Var { .. }
+            | Self::ModuleSelect { .. } => (),
+
+            Self::Trace { then, text, .. } => {
+                if let (Self::ErrorTerm { .. }, Self::String { value, .. }) =
+                    (then.as_ref(), text.as_ref())
+                {
+                    labels.push(value.as_str());
+                }
+                text.collect_failure_labels(labels);
+                then.collect_failure_labels(labels);
+            }
+
+            Self::Sequence { expressions, .. } | Self::Pipeline { expressions, .. } => expressions
+                .iter()
+                .for_each(|expr| expr.collect_failure_labels(labels)),
+
+            Self::Fn { body, .. } => body.collect_failure_labels(labels),
+
+            Self::List { elements, tail, .. } => {
+                elements
+                    .iter()
+                    .for_each(|expr| expr.collect_failure_labels(labels));
+                if let Some(tail) = tail {
+                    tail.collect_failure_labels(labels);
+                }
+            }
+
+            Self::Call { fun, args, .. } => {
+                fun.collect_failure_labels(labels);
+                args.iter()
+                    .for_each(|arg| arg.value.collect_failure_labels(labels));
+            }
+
+            Self::BinOp { left, right, .. } => {
+                left.collect_failure_labels(labels);
+                right.collect_failure_labels(labels);
+            }
+
+            Self::Assignment { value, .. } => value.collect_failure_labels(labels),
+
+            Self::When {
+                subject, clauses, ..
+            } => {
+                subject.collect_failure_labels(labels);
+                clauses
+                    .iter()
+                    .for_each(|clause| clause.then.collect_failure_labels(labels));
+            }
+
+            Self::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                branches.iter().for_each(|branch| {
+                    branch.condition.collect_failure_labels(labels);
+                    branch.body.collect_failure_labels(labels);
+                });
+                final_else.collect_failure_labels(labels);
+            }
+
+            Self::RecordAccess { record, .. } => record.collect_failure_labels(labels),
+
+            Self::Tuple { elems, .. } => elems
+                .iter()
+                .for_each(|expr| expr.collect_failure_labels(labels)),
+
+            Self::Pair { fst, snd, .. } => {
+                fst.collect_failure_labels(labels);
+                snd.collect_failure_labels(labels);
+            }
+
+            Self::TupleIndex { tuple, .. } => tuple.collect_failure_labels(labels),
+
+            Self::RecordUpdate { spread, args, .. } => {
+                spread.collect_failure_labels(labels);
+                args.iter()
+                    .for_each(|arg| arg.value.collect_failure_labels(labels));
+            }
+
+            Self::UnOp { value, .. } => value.collect_failure_labels(labels),
+        }
+    }
+
     /// Returns `true` if the typed expr is [`Assignment`].
     pub fn is_assignment(&self) -> bool {
         matches!(self, Self::Assignment { .. })
@@ -570,6 +710,93 @@ impl TypedExpr {
         }
     }
 
+    /// Visit this expression and all of its sub-expressions, depth-first, parents before
+    /// children.
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a TypedExpr)) {
+        visit(self);
+
+        match self {
+            TypedExpr::ErrorTerm { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::UInt { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::ByteArray { .. }
+            | TypedExpr::ModuleSelect { .. }
+            | TypedExpr::CurvePoint { .. } => {}
+
+            TypedExpr::Trace { text, then, .. } => {
+                text.walk(visit);
+                then.walk(visit);
+            }
+
+            TypedExpr::Pipeline { expressions, .. }
+            | TypedExpr::Sequence { expressions, .. }
+            | TypedExpr::Tuple {
+                elems: expressions, ..
+            } => expressions.iter().for_each(|e| e.walk(visit)),
+
+            TypedExpr::Fn { body, .. } => body.walk(visit),
+
+            TypedExpr::Pair { fst, snd, .. } => {
+                fst.walk(visit);
+                snd.walk(visit);
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                elements.iter().for_each(|e| e.walk(visit));
+                if let Some(tail) = tail {
+                    tail.walk(visit);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                fun.walk(visit);
+                args.iter().for_each(|arg| arg.value.walk(visit));
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                left.walk(visit);
+                right.walk(visit);
+            }
+
+            TypedExpr::Assignment { value, .. } => value.walk(visit),
+
+            TypedExpr::When {
+                subject, clauses, ..
+            } => {
+                subject.walk(visit);
+                clauses.iter().for_each(|clause| clause.then.walk(visit));
+            }
+
+            TypedExpr::RecordAccess {
+                record: expression, ..
+            }
+            | TypedExpr::TupleIndex {
+                tuple: expression, ..
+            }
+            | TypedExpr::UnOp {
+                value: expression, ..
+            } => expression.walk(visit),
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                spread.walk(visit);
+                args.iter().for_each(|arg| arg.value.walk(visit));
+            }
+
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for branch in branches {
+                    branch.condition.walk(visit);
+                    branch.body.walk(visit);
+                }
+                final_else.walk(visit);
+            }
+        }
+    }
+
     pub fn void(location: Span) -> Self {
         TypedExpr::Var {
             name: "Void".to_string(),
@@ -685,6 +912,13 @@ pub enum UntypedExpr {
         value: Box<Self>,
     },
 
+    /// 'assert condition, message': fails with the given message unless the condition holds.
+    Assert {
+        location: Span,
+        condition: Box<Self>,
+        message: Box<Self>,
+    },
+
     When {
         location: Span,
         subject: Box<Self>,
@@ -1369,6 +1603,7 @@ impl UntypedExpr {
                             location: Span::empty(),
                         }),
                         is_validator_param: false,
+                        default: None,
                     });
 
                     ast::CallArg {
@@ -1452,6 +1687,7 @@ impl UntypedExpr {
             Self::PipeLine { expressions, .. } => expressions.last().location(),
             Self::Trace { then, .. } => then.location(),
             Self::TraceIfFalse { location, .. }
+            | Self::Assert { location, .. }
             | Self::Fn { location, .. }
             | Self::Var { location, .. }
             | Self::UInt { location, .. }
@@ -1538,6 +1774,7 @@ impl UntypedExpr {
                     doc: None,
                     annotation,
                     is_validator_param: false,
+                    default: None,
                     by: ArgBy::ByName(arg_name),
                 })
                 .collect(),
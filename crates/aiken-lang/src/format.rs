@@ -1,12 +1,12 @@
 use crate::{
     ast::{
         Annotation, ArgBy, ArgName, ArgVia, AssignmentKind, AssignmentPattern, BinOp,
-        ByteArrayFormatPreference, CallArg, CurveType, DataType, Definition, Function,
-        LogicalOpChainKind, ModuleConstant, OnTestFailure, Pattern, RecordConstructor,
-        RecordConstructorArg, RecordUpdateSpread, Span, TraceKind, TypeAlias, TypedArg,
-        TypedValidator, UnOp, UnqualifiedImport, UntypedArg, UntypedArgVia, UntypedAssignmentKind,
-        UntypedClause, UntypedDefinition, UntypedFunction, UntypedIfBranch, UntypedModule,
-        UntypedPattern, UntypedRecordUpdateArg, Use, Validator, CAPTURE_VARIABLE,
+        ByteArrayFormatPreference, CallArg, Conditional, CurveType, DataType, DefaultValue,
+        Definition, Function, LogicalOpChainKind, ModuleConstant, OnTestFailure, Pattern,
+        RecordConstructor, RecordConstructorArg, RecordUpdateSpread, Span, TraceKind, TypeAlias,
+        TypedArg, TypedValidator, UnOp, UnqualifiedImport, UntypedArg, UntypedArgVia,
+        UntypedAssignmentKind, UntypedClause, UntypedDefinition, UntypedFunction, UntypedIfBranch,
+        UntypedModule, UntypedPattern, UntypedRecordUpdateArg, Use, Validator, CAPTURE_VARIABLE,
     },
     docvec,
     expr::{FnStyle, TypedExpr, UntypedExpr, DEFAULT_ERROR_STR, DEFAULT_TODO_STR},
@@ -27,6 +27,69 @@ pub const INDENT: isize = 2;
 pub const MAX_COLUMNS: isize = 80;
 
 pub fn pretty(writer: &mut String, module: UntypedModule, extra: ModuleExtra, src: &str) {
+    pretty_with_max_columns(writer, module, extra, src, MAX_COLUMNS)
+}
+
+/// Like 'pretty', but breaking lines beyond a given width instead of the default MAX_COLUMNS.
+pub fn pretty_with_max_columns(
+    writer: &mut String,
+    module: UntypedModule,
+    extra: ModuleExtra,
+    src: &str,
+    max_columns: isize,
+) {
+    pretty_with_options(
+        writer,
+        module,
+        extra,
+        src,
+        FormatOptions {
+            max_columns,
+            ..FormatOptions::default()
+        },
+    )
+}
+
+/// Where tests (and benchmarks) end up in a formatted module.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TestPlacement {
+    /// Leave tests where they are; typically next to the definitions they cover.
+    #[default]
+    InPlace,
+    /// Move tests after all other definitions, keeping their relative order.
+    AtEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub max_columns: isize,
+    pub tests: TestPlacement,
+    /// Put every step of a pipeline on its own line as soon as there are two or more pipes,
+    /// even when the pipeline would fit on a single line.
+    pub break_pipelines: bool,
+    /// Pad the patterns of `when` clauses so that their arrows line up, unless one of them
+    /// spans multiple lines.
+    pub align_clause_arrows: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            max_columns: MAX_COLUMNS,
+            tests: TestPlacement::default(),
+            break_pipelines: false,
+            align_clause_arrows: false,
+        }
+    }
+}
+
+pub fn pretty_with_options(
+    writer: &mut String,
+    module: UntypedModule,
+    extra: ModuleExtra,
+    src: &str,
+    options: FormatOptions,
+) {
     let intermediate = Intermediate {
         comments: extra
             .comments
@@ -46,9 +109,9 @@ pub fn pretty(writer: &mut String, module: UntypedModule, extra: ModuleExtra, sr
             .collect(),
     };
 
-    Formatter::with_comments(&intermediate)
+    Formatter::with_comments(&intermediate, options)
         .module(&module)
-        .pretty_print(MAX_COLUMNS, writer);
+        .pretty_print(options.max_columns, writer);
 }
 
 #[derive(Debug)]
@@ -60,12 +123,38 @@ struct Intermediate<'a> {
 }
 
 /// Hayleigh's bane
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Formatter<'a> {
     comments: &'a [Comment<'a>],
     doc_comments: &'a [Comment<'a>],
     module_comments: &'a [Comment<'a>],
     empty_lines: &'a [usize],
+    max_columns: isize,
+    tests: TestPlacement,
+    break_pipelines: bool,
+    align_clause_arrows: bool,
+    // `@cfg(...)` attributes of the module being formatted, printed above their definition.
+    conditionals: Vec<Conditional>,
+    // Shared across a definition, so that generic variables are named consistently in it. Reset
+    // at the start of each documented definition.
+    type_printer: tipo::pretty::Printer,
+}
+
+impl Default for Formatter<'_> {
+    fn default() -> Self {
+        Self {
+            comments: &[],
+            doc_comments: &[],
+            module_comments: &[],
+            empty_lines: &[],
+            max_columns: MAX_COLUMNS,
+            tests: TestPlacement::default(),
+            break_pipelines: false,
+            align_clause_arrows: false,
+            conditionals: Vec::new(),
+            type_printer: tipo::pretty::Printer::new(),
+        }
+    }
 }
 
 impl<'comments> Formatter<'comments> {
@@ -73,12 +162,18 @@ impl<'comments> Formatter<'comments> {
         Default::default()
     }
 
-    fn with_comments(extra: &'comments Intermediate<'comments>) -> Self {
+    fn with_comments(extra: &'comments Intermediate<'comments>, options: FormatOptions) -> Self {
         Self {
             comments: &extra.comments,
             doc_comments: &extra.doc_comments,
             module_comments: &extra.module_comments,
             empty_lines: extra.empty_lines,
+            max_columns: options.max_columns,
+            tests: options.tests,
+            break_pipelines: options.break_pipelines,
+            align_clause_arrows: options.align_clause_arrows,
+            conditionals: Vec::new(),
+            type_printer: tipo::pretty::Printer::new(),
         }
     }
 
@@ -133,6 +228,7 @@ impl<'comments> Formatter<'comments> {
         let mut has_declarations = false;
         let mut imports = Vec::new();
         let mut declarations = Vec::with_capacity(definitions.len());
+        let mut tests = Vec::new();
 
         for def in definitions {
             let start = def.location().start;
@@ -153,9 +249,16 @@ impl<'comments> Formatter<'comments> {
 
                     let comments = self.pop_comments(start);
 
-                    let declaration = self.documented_definition(def);
+                    let declaration = commented(self.documented_definition(def), comments);
 
-                    declarations.push(commented(declaration, comments))
+                    match def {
+                        Definition::Test(..) | Definition::Benchmark(..)
+                            if self.tests == TestPlacement::AtEnd =>
+                        {
+                            tests.push(declaration)
+                        }
+                        _ => declarations.push(declaration),
+                    }
                 }
             }
         }
@@ -170,6 +273,8 @@ impl<'comments> Formatter<'comments> {
             line(),
         );
 
+        declarations.extend(tests);
+
         let declarations = join(declarations, lines(2));
 
         let sep = if has_imports && has_declarations {
@@ -182,6 +287,26 @@ impl<'comments> Formatter<'comments> {
     }
 
     fn module<'a>(&mut self, module: &'a UntypedModule) -> Document<'a> {
+        let experimental = if !module.experimental.is_empty() {
+            let attributes = module
+                .experimental
+                .iter()
+                .map(|experimental| {
+                    let comments = self.pop_comments(experimental.location.start);
+                    commented(
+                        docvec!["@experimental(\"", experimental.feature.name(), "\")"],
+                        comments,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            join(attributes, line()).append(line())
+        } else {
+            nil()
+        };
+
+        self.conditionals.clone_from(&module.conditionals);
+
         let defs = self.definitions(&module.definitions);
 
         // Now that `defs` has been collected, only freestanding comments (//)
@@ -213,7 +338,7 @@ impl<'comments> Formatter<'comments> {
             nil()
         };
 
-        let non_empty = vec![module_comments, defs, doc_comments, comments]
+        let non_empty = vec![module_comments, experimental, defs, doc_comments, comments]
             .into_iter()
             .filter(|doc| !doc.is_empty());
 
@@ -255,8 +380,15 @@ impl<'comments> Formatter<'comments> {
                 body,
                 end_position,
                 on_test_failure,
+                example,
                 ..
-            }) => self.definition_test(name, args, body, *end_position, on_test_failure),
+            }) => {
+                let test = self.definition_test(name, args, body, *end_position, on_test_failure);
+                match example {
+                    Some(function) => docvec!["@example(", function.as_str(), ")", line(), test],
+                    None => test,
+                }
+            }
 
             Definition::Benchmark(Function {
                 name,
@@ -292,6 +424,7 @@ impl<'comments> Formatter<'comments> {
                 name,
                 annotation,
                 value,
+                fixture,
                 ..
             }) => {
                 let head = pub_(*public).append("const ").append(name.as_str());
@@ -300,11 +433,15 @@ impl<'comments> Formatter<'comments> {
                     Some(t) => head.append(": ").append(self.annotation(t)),
                 };
 
-                head.append(" =")
-                    .append(break_("", " "))
-                    .append(self.expr(value, false))
-                    .nest(INDENT)
-                    .group()
+                match fixture {
+                    Some(file) => docvec!["@fixture(\"", file.as_str(), "\")", line(), head],
+                    None => head
+                        .append(" =")
+                        .append(break_("", " "))
+                        .append(self.expr(value, false))
+                        .nest(INDENT)
+                        .group(),
+                }
             }
         }
     }
@@ -315,11 +452,12 @@ impl<'comments> Formatter<'comments> {
             module,
             as_name,
             unqualified,
+            public,
             ..
         }: &'a Use<()>,
     ) -> Document<'a> {
-        "use "
-            .to_doc()
+        pub_(*public)
+            .append("use ")
             .append(Document::String(module.join("/")))
             .append(if unqualified.is_empty() {
                 nil()
@@ -346,11 +484,12 @@ impl<'comments> Formatter<'comments> {
     }
 
     pub fn docs_const_expr<'a>(&mut self, name: &'a str, value: &'a TypedExpr) -> Document<'a> {
-        let mut printer = tipo::pretty::Printer::new();
+        self.type_printer = tipo::pretty::Printer::new();
+
         let doc = name
             .to_doc()
             .append(": ")
-            .append(printer.print(&value.tipo()));
+            .append(self.type_printer.print(&value.tipo()));
 
         // NOTE: Only display the full value for simple expressions.
         let value = self.const_expr(value);
@@ -408,8 +547,25 @@ impl<'comments> Formatter<'comments> {
     }
 
     fn documented_definition<'a>(&mut self, s: &'a UntypedDefinition) -> Document<'a> {
-        let comments = self.doc_comments(s.location().start);
-        comments.append(self.definition(s).group()).group()
+        let start = s.location().start;
+        let comments = self.doc_comments(start);
+        comments
+            .append(self.conditionals(start))
+            .append(self.definition(s).group())
+            .group()
+    }
+
+    fn conditionals<'a>(&self, definition: usize) -> Document<'a> {
+        concat(
+            self.conditionals
+                .iter()
+                .filter(|conditional| conditional.definition == definition)
+                .map(|conditional| {
+                    Document::String(format!("@cfg({})", conditional.cfg))
+                        .append(line())
+                        .force_break()
+                }),
+        )
     }
 
     fn doc_comments<'a>(&mut self, limit: usize) -> Document<'a> {
@@ -523,6 +679,11 @@ impl<'comments> Formatter<'comments> {
         }
         .group();
 
+        doc = match &arg.default {
+            None => doc,
+            Some(default) => doc.append(" = ").append(self.default_value(default)),
+        };
+
         let doc = doc_comments.append(doc.group()).group();
 
         commented(doc, comments)
@@ -691,12 +852,26 @@ impl<'comments> Formatter<'comments> {
 
         let mut handler_docs = vec![];
 
-        for handler in handlers.iter() {
+        // Handlers exported under several purposes are parsed as consecutive copies sharing the
+        // same location; print them back as a single 'purpose | purpose(...)' declaration.
+        let groups = handlers.iter().group_by(|handler| handler.location);
+
+        for (_, group) in &groups {
+            let group = group.collect::<Vec<_>>();
+
+            let (handler, aliases) = group.split_last().expect("groups are never empty");
+
             let fun_comments = self.pop_comments(handler.location.start);
             let fun_doc_comments = self.doc_comments(handler.location.start);
 
-            let first_fn = self
-                .definition_fn(
+            let purposes = concat(
+                aliases
+                    .iter()
+                    .map(|alias| alias.name.as_str().to_doc().append(" | ")),
+            );
+
+            let first_fn = purposes
+                .append(self.definition_fn(
                     &handler.public,
                     &handler.name,
                     &handler.arguments,
@@ -704,7 +879,7 @@ impl<'comments> Formatter<'comments> {
                     &handler.body,
                     handler.end_position,
                     true,
-                )
+                ))
                 .group();
 
             let first_fn = commented(fun_doc_comments.append(first_fn).group(), fun_comments);
@@ -939,6 +1114,17 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    fn default_value<'a>(&mut self, default: &'a DefaultValue) -> Document<'a> {
+        match default {
+            DefaultValue::Int { value, base, .. } => self.int(value, base),
+            DefaultValue::ByteArray {
+                bytes,
+                preferred_format,
+                ..
+            } => self.bytearray(bytes, None, preferred_format),
+        }
+    }
+
     pub fn int<'a>(&mut self, s: &'a str, base: &Base) -> Document<'a> {
         match s.chars().next() {
             Some('-') => Document::Str("-").append(self.uint(&s[1..], base)),
@@ -1125,6 +1311,15 @@ impl<'comments> Formatter<'comments> {
             UntypedExpr::ErrorTerm { .. } => "fail".to_doc(),
 
             UntypedExpr::TraceIfFalse { value, .. } => self.trace_if_false(value),
+
+            UntypedExpr::Assert {
+                condition, message, ..
+            } => "assert "
+                .to_doc()
+                .append(self.expr(condition, false))
+                .append(", ")
+                .append(self.wrap_expr(message))
+                .group(),
         };
 
         commented(document, comments)
@@ -1154,6 +1349,7 @@ impl<'comments> Formatter<'comments> {
     ) -> Document<'a> {
         let (keyword, default_label) = match kind {
             TraceKind::Trace => ("trace", None),
+            TraceKind::Debug => ("trace.debug", None),
             TraceKind::Error => ("fail", Some(DEFAULT_ERROR_STR.to_string())),
             TraceKind::Todo => ("todo", Some(DEFAULT_TODO_STR.to_string())),
         };
@@ -1178,7 +1374,7 @@ impl<'comments> Formatter<'comments> {
 
         match kind {
             TraceKind::Error | TraceKind::Todo => body,
-            TraceKind::Trace => body
+            TraceKind::Trace | TraceKind::Debug => body
                 .append(if self.pop_empty_lines(then.start_byte_index()) {
                     lines(2)
                 } else {
@@ -1397,11 +1593,21 @@ impl<'comments> Formatter<'comments> {
             .append("is {")
             .group();
 
+        let arrow_column = if self.align_clause_arrows {
+            clauses
+                .iter()
+                .map(|clause| patterns_width(&clause.patterns))
+                .collect::<Option<Vec<_>>>()
+                .and_then(|widths| widths.into_iter().max())
+        } else {
+            None
+        };
+
         let clauses_doc = concat(
             clauses
                 .iter()
                 .enumerate()
-                .map(|(i, c)| self.clause(c, i as u32)),
+                .map(|(i, c)| self.clause(c, i as u32, arrow_column)),
         );
 
         subjects_doc
@@ -1437,12 +1643,12 @@ impl<'comments> Formatter<'comments> {
         let right_precedence = right.binop_precedence();
 
         let mut left = self.expr(left, false);
-        if left.fits(MAX_COLUMNS) {
+        if left.fits(self.max_columns) {
             left = left.force_unbroken()
         }
 
         let mut right = self.expr(right, false);
-        if right.fits(MAX_COLUMNS) {
+        if right.fits(self.max_columns) {
             right = right.force_unbroken()
         }
 
@@ -1510,6 +1716,8 @@ impl<'comments> Formatter<'comments> {
         expressions: &'a Vec1<UntypedExpr>,
         one_liner: bool,
     ) -> Document<'a> {
+        let one_liner = one_liner && !(self.break_pipelines && expressions.len() > 2);
+
         let mut docs = Vec::with_capacity(expressions.len() * 3);
 
         let first = expressions.first();
@@ -1835,6 +2043,8 @@ impl<'comments> Formatter<'comments> {
         return_annotation: &'a Option<Annotation>,
         return_type: Rc<Type>,
     ) -> Document<'a> {
+        self.type_printer = tipo::pretty::Printer::new();
+
         let head = name.to_doc().append(self.docs_fn_args(args)).append(" -> ");
 
         let tail = self.type_or_annotation(return_annotation, &return_type);
@@ -1842,7 +2052,11 @@ impl<'comments> Formatter<'comments> {
         let doc = head.append(tail.clone()).group();
 
         // Wrap arguments on multi-lines if they are lengthy.
-        if doc.clone().to_pretty_string(MAX_COLUMNS).contains('\n') {
+        if doc
+            .clone()
+            .to_pretty_string(self.max_columns)
+            .contains('\n')
+        {
             let head = name
                 .to_doc()
                 .append(self.docs_fn_args(args).force_break())
@@ -1859,9 +2073,15 @@ impl<'comments> Formatter<'comments> {
     }
 
     fn docs_fn_arg<'a>(&mut self, arg: &'a TypedArg) -> Document<'a> {
-        self.docs_fn_arg_name(&arg.arg_name)
-            .append(self.type_or_annotation(&arg.annotation, &arg.tipo))
-            .group()
+        let doc = self
+            .docs_fn_arg_name(&arg.arg_name)
+            .append(self.type_or_annotation(&arg.annotation, &arg.tipo));
+
+        match &arg.default {
+            None => doc,
+            Some(default) => doc.append(" = ").append(self.default_value(default)),
+        }
+        .group()
     }
 
     fn docs_fn_arg_name<'a>(&mut self, arg_name: &'a ArgName) -> Document<'a> {
@@ -1879,7 +2099,7 @@ impl<'comments> Formatter<'comments> {
     ) -> Document<'a> {
         match annotation {
             Some(a) => self.annotation(a),
-            None => tipo::pretty::Printer::new().print(type_info),
+            None => self.type_printer.print(type_info),
         }
     }
 
@@ -1954,14 +2174,27 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
-    fn clause<'a>(&mut self, clause: &'a UntypedClause, index: u32) -> Document<'a> {
+    fn clause<'a>(
+        &mut self,
+        clause: &'a UntypedClause,
+        index: u32,
+        arrow_column: Option<usize>,
+    ) -> Document<'a> {
         let space_before = self.pop_empty_lines(clause.location.start);
-        let clause_doc = join(
+        let mut clause_doc = join(
             clause.patterns.iter().map(|p| self.pattern(p)),
             break_(" |", " | "),
         )
         .group();
 
+        if let Some(padding) = arrow_column
+            .zip(patterns_width(&clause.patterns))
+            .map(|(column, width)| column - width)
+            .filter(|padding| *padding > 0)
+        {
+            clause_doc = clause_doc.append(Document::String(" ".repeat(padding)));
+        }
+
         if index == 0 {
             clause_doc
         } else if space_before {
@@ -2154,11 +2387,30 @@ impl<'a> Documentable<'a> for &'a BinOp {
             BinOp::MultInt => "*",
             BinOp::DivInt => "/",
             BinOp::ModInt => "%",
+            BinOp::Concat => "<>",
         }
         .to_doc()
     }
 }
 
+/// Width of the patterns of a clause when printed on a single line, if they fit on one. Comments
+/// are left out, as they are printed on lines of their own.
+fn patterns_width(patterns: &[UntypedPattern]) -> Option<usize> {
+    let doc = join(
+        patterns.iter().map(|p| Formatter::new().pattern(p)),
+        break_(" |", " | "),
+    )
+    .group();
+
+    let rendered = doc.to_pretty_string(isize::MAX);
+
+    if rendered.contains('\n') {
+        None
+    } else {
+        Some(rendered.chars().count())
+    }
+}
+
 pub fn wrap_args<'a, I>(args: I) -> Document<'a>
 where
     I: IntoIterator<Item = (Document<'a>, bool)>,
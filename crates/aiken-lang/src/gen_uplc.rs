@@ -1,7 +1,10 @@
 pub mod air;
 pub mod builder;
 pub mod decision_tree;
+pub mod dump;
 pub mod interner;
+pub mod reachability;
+pub mod source_map;
 pub mod stick_break_set;
 pub mod tree;
 
@@ -26,8 +29,8 @@ use crate::{
         air::ExpectLevel,
         builder::{
             erase_opaque_type_operations, get_generic_variant_name, get_line_columns_by_span,
-            get_src_code_by_span, known_data_to_type, monomorphize, wrap_validator_condition,
-            CodeGenFunction,
+            get_src_code_by_span, get_validator_argument, known_data_to_type, monomorphize,
+            wrap_validator_condition, CodeGenFunction,
         },
     },
     line_numbers::LineNumbers,
@@ -49,6 +52,7 @@ use indexmap::IndexMap;
 use interner::AirInterner;
 use itertools::Itertools;
 use petgraph::{algo, Graph};
+use source_map::{Origin, SourceMap};
 use std::{collections::HashMap, rc::Rc};
 use stick_break_set::{Builtins, TreeSet};
 use tree::Fields;
@@ -57,17 +61,20 @@ use uplc::{
     builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER, EXPECT_ON_LIST},
     builtins::DefaultFunction,
     machine::cost_model::ExBudget,
-    optimize::{aiken_optimize_and_intern, interner::CodeGenInterner, shrinker::NO_INLINE},
+    optimize::{
+        interner::CodeGenInterner, optimize_with, shrinker::NO_INLINE, Pass, DEFAULT_PASSES,
+    },
 };
 
 type Otherwise = Option<AirTree>;
 
+const VALIDATOR_CONTEXT: &str = "__context__";
+
 const DELAY_ERROR: fn() -> AirTree =
     || AirTree::anon_func(vec![], AirTree::error(Type::void(), false), true);
 
 #[derive(Clone)]
 pub struct CodeGenerator<'a> {
-    #[allow(dead_code)]
     plutus_version: PlutusVersion,
     /// immutable index maps
     functions: IndexMap<&'a FunctionAccessKey, &'a TypedFunction>,
@@ -75,8 +82,9 @@ pub struct CodeGenerator<'a> {
     data_types: IndexMap<&'a DataTypeKey, &'a TypedDataType>,
     module_types: IndexMap<&'a str, &'a TypeInfo>,
     module_src: IndexMap<&'a str, &'a (String, LineNumbers)>,
-    /// immutable option
+    /// immutable options
     tracing: TraceLevel,
+    passes: Vec<Pass>,
     /// mutable index maps that are reset
     defined_functions: IndexMap<FunctionAccessKey, ()>,
     special_functions: CodeGenSpecialFuncs,
@@ -86,6 +94,11 @@ pub struct CodeGenerator<'a> {
     /// mutable and reset as well
     interner: AirInterner,
     id_gen: IdGenerator,
+    origins: IndexMap<String, Origin>,
+    /// mutable, but kept across resets so that codes are unique across programs
+    trace_codes: Option<IndexMap<String, usize>>,
+    /// mutable, but kept across resets so that maps are collected for every validator
+    source_maps: Option<IndexMap<String, SourceMap>>,
 }
 
 impl<'a> CodeGenerator<'a> {
@@ -110,12 +123,16 @@ impl<'a> CodeGenerator<'a> {
             module_types,
             module_src,
             tracing: tracing.trace_level(true),
+            passes: DEFAULT_PASSES.to_vec(),
             defined_functions: IndexMap::new(),
             special_functions: CodeGenSpecialFuncs::new(),
             code_gen_functions: IndexMap::new(),
             cyclic_functions: IndexMap::new(),
             interner: AirInterner::new(),
             id_gen: IdGenerator::new(),
+            origins: IndexMap::new(),
+            trace_codes: None,
+            source_maps: None,
         }
     }
 
@@ -125,30 +142,102 @@ impl<'a> CodeGenerator<'a> {
         self.cyclic_functions = IndexMap::new();
         self.interner = AirInterner::new();
         self.id_gen = IdGenerator::new();
+        self.origins = IndexMap::new();
         if reset_special_functions {
             self.special_functions = CodeGenSpecialFuncs::new();
         }
     }
 
+    /// Replace trace messages with short numeric codes in all subsequently generated programs.
+    /// The table mapping codes back to the original messages is available through
+    /// 'trace_codes'.
+    pub fn enable_trace_codes(&mut self) {
+        self.trace_codes.get_or_insert_with(IndexMap::new);
+    }
+
+    /// Record a source map of all subsequently generated validators, available through
+    /// 'source_maps'.
+    pub fn enable_source_maps(&mut self) {
+        self.source_maps.get_or_insert_with(IndexMap::new);
+    }
+
+    /// Run the given optimization passes, in order, on all subsequently generated programs
+    /// instead of the default pipeline.
+    pub fn set_optimization_passes(&mut self, passes: Vec<Pass>) {
+        self.passes = passes;
+    }
+
+    pub fn trace_codes(&self) -> Option<&IndexMap<String, usize>> {
+        self.trace_codes.as_ref()
+    }
+
+    /// Source maps of the validators generated so far, by title (i.e. `module.validator`).
+    pub fn source_maps(&self) -> Option<&IndexMap<String, SourceMap>> {
+        self.source_maps.as_ref()
+    }
+
+    fn trace_message(&mut self, msg: String) -> String {
+        match self.trace_codes.as_mut() {
+            None => msg,
+            Some(codes) => {
+                let code = codes.len();
+                codes.entry(msg).or_insert(code).to_string()
+            }
+        }
+    }
+
     pub fn generate(&mut self, validator: &TypedValidator, module_name: &str) -> Program<Name> {
-        let context_name = "__context__".to_string();
-        let context_name_interned = introduce_name(&mut self.interner, &context_name);
-        validator.params.iter().for_each(|arg| {
-            arg.get_variable_name()
-                .iter()
-                .for_each(|arg_name| self.interner.intern(arg_name.to_string()))
-        });
+        let term = self.validator_term(validator, module_name);
 
-        let air_tree_fun = wrap_validator_condition(
-            self.build(&validator.into_script_context_handler(), module_name, &[]),
-            self.tracing,
-        );
+        let origins = std::mem::take(&mut self.origins);
 
-        let air_tree_fun = AirTree::anon_func(vec![context_name_interned], air_tree_fun, true);
+        let program = self.finalize(term);
 
-        let validator_args_tree = AirTree::no_op(air_tree_fun);
+        if let Some(source_maps) = self.source_maps.as_mut() {
+            let mut source_map = SourceMap::new(&program.term, &origins);
+
+            source_map.entries.insert(
+                0,
+                source_map::Entry {
+                    module: module_name.to_string(),
+                    name: validator.name.clone(),
+                    location: Span {
+                        start: validator.location.start,
+                        end: validator.end_position + 1,
+                    },
+                    term: program.term.clone(),
+                },
+            );
 
-        let full_tree = self.hoist_functions_to_validator(validator_args_tree);
+            source_maps.insert(format!("{module_name}.{}", validator.name), source_map);
+        }
+
+        program
+    }
+
+    /// Like 'generate', but returns the state of the program after each run of the given
+    /// optimization pass in the pipeline, rather than the final program.
+    pub fn generate_after_pass(
+        &mut self,
+        validator: &TypedValidator,
+        module_name: &str,
+        pass: Pass,
+    ) -> Vec<Program<Name>> {
+        let term = self.validator_term(validator, module_name);
+
+        let mut dumps = vec![];
+
+        self.optimize(term, |current, program| {
+            if current == pass {
+                dumps.push(program.clone());
+            }
+        });
+
+        dumps
+    }
+
+    fn validator_term(&mut self, validator: &TypedValidator, module_name: &str) -> Term<Name> {
+        let full_tree = self.validator_tree(validator, module_name);
 
         // optimizations on air tree
 
@@ -158,14 +247,49 @@ impl<'a> CodeGenerator<'a> {
 
         let term = cast_validator_args(term, &validator.params, &self.interner);
 
-        self.interner.pop_text(context_name);
+        self.interner.pop_text(VALIDATOR_CONTEXT.to_string());
         validator.params.iter().for_each(|arg| {
             arg.get_variable_name()
                 .iter()
                 .for_each(|arg_name| self.interner.pop_text(arg_name.to_string()))
         });
 
-        self.finalize(term)
+        term
+    }
+
+    /// Like 'generate', but stops before UPLC code generation and returns the Air stack of the
+    /// validator instead, each instruction paired with its nesting depth.
+    pub fn generate_air(
+        &mut self,
+        validator: &TypedValidator,
+        module_name: &str,
+    ) -> Vec<(usize, Air)> {
+        let air = self.validator_tree(validator, module_name).to_scoped_vec();
+
+        self.reset(true);
+
+        air
+    }
+
+    fn validator_tree(&mut self, validator: &TypedValidator, module_name: &str) -> AirTree {
+        let context_name_interned =
+            introduce_name(&mut self.interner, &VALIDATOR_CONTEXT.to_string());
+        validator.params.iter().for_each(|arg| {
+            arg.get_variable_name()
+                .iter()
+                .for_each(|arg_name| self.interner.intern(arg_name.to_string()))
+        });
+
+        let air_tree_fun = wrap_validator_condition(
+            self.build(&validator.into_script_context_handler(), module_name, &[]),
+            self.tracing,
+        );
+
+        let air_tree_fun = AirTree::anon_func(vec![context_name_interned], air_tree_fun, true);
+
+        let validator_args_tree = AirTree::no_op(air_tree_fun);
+
+        self.hoist_functions_to_validator(validator_args_tree)
     }
 
     pub fn generate_raw(
@@ -215,10 +339,25 @@ impl<'a> CodeGenerator<'a> {
         Program { version, term }
     }
 
-    fn finalize(&mut self, mut term: Term<Name>) -> Program<Name> {
+    fn finalize(&mut self, term: Term<Name>) -> Program<Name> {
+        self.optimize(term, |_, _| {})
+    }
+
+    fn optimize(
+        &mut self,
+        mut term: Term<Name>,
+        after_pass: impl FnMut(Pass, &Program<Name>),
+    ) -> Program<Name> {
         term = self.special_functions.apply_used_functions(term);
 
-        let program = aiken_optimize_and_intern(self.new_program(term));
+        let program = optimize_with(self.new_program(term), &self.passes, after_pass);
+
+        if let Err(errors) = program.verify() {
+            panic!(
+                "Generated an ill-formed program; this is a compiler bug:\n{}",
+                errors.iter().map(|error| format!("  - {error}")).join("\n")
+            );
+        }
 
         // This is very important to call here.
         // If this isn't done, re-using the same instance
@@ -253,13 +392,22 @@ impl<'a> CodeGenerator<'a> {
             let air_value = self.build(value, module_build_name, &[]);
 
             let otherwise_delayed = {
-                let msg = match (self.tracing, kind) {
-                    (TraceLevel::Silent, _) | (_, AssignmentKind::Let { .. }) => "".to_string(),
-                    (TraceLevel::Compact, _) => {
+                let msg = match (self.tracing, kind, get_validator_argument(value, pattern)) {
+                    (TraceLevel::Silent, _, _) | (_, AssignmentKind::Let { .. }, _) => {
+                        "".to_string()
+                    }
+                    (TraceLevel::Compact, _, Some(argument)) => format!(
+                        "{}: invalid {argument}",
+                        get_line_columns_by_span(module_build_name, location, &self.module_src)
+                    ),
+                    (TraceLevel::Verbose, _, Some(argument)) => {
+                        format!("{argument} is not a valid {}", tipo.to_pretty(0))
+                    }
+                    (TraceLevel::Compact, _, None) => {
                         get_line_columns_by_span(module_build_name, location, &self.module_src)
                             .to_string()
                     }
-                    (TraceLevel::Verbose, _) => {
+                    (TraceLevel::Verbose, _, None) => {
                         get_src_code_by_span(module_build_name, location, &self.module_src)
                     }
                 };
@@ -269,6 +417,8 @@ impl<'a> CodeGenerator<'a> {
                 if msg_func_name.is_empty() {
                     None
                 } else {
+                    let msg = self.trace_message(msg);
+
                     self.special_functions.insert_new_function(
                         msg_func_name.clone(),
                         Term::Error.delayed_trace(Term::string(msg)).delay(),
@@ -282,6 +432,19 @@ impl<'a> CodeGenerator<'a> {
             // Intern vars from pattern here
             introduce_pattern(&mut self.interner, pattern);
 
+            if let Pattern::Var { name, .. } = pattern {
+                if self.source_maps.is_some() {
+                    self.origins.insert(
+                        self.interner.lookup_interned(name),
+                        Origin {
+                            module: module_build_name.to_string(),
+                            name: name.clone(),
+                            location: *location,
+                        },
+                    );
+                }
+            }
+
             let (then, context) = context.split_first().unwrap();
 
             let then = self.build(then, module_build_name, context);
@@ -334,6 +497,13 @@ impl<'a> CodeGenerator<'a> {
                             AirTree::var(constructor.clone(), name, "")
                         }
                     }
+                    ValueConstructorVariant::Record {
+                        name: ref constr_name,
+                        arity: 0,
+                        ..
+                    } if !constructor.tipo.is_bool() && !constructor.tipo.is_void() => self
+                        .nullary_constr(constr_name, &constructor.tipo)
+                        .unwrap_or_else(|| AirTree::var(constructor.clone(), name, "")),
                     _ => AirTree::var(constructor.clone(), name, ""),
                 },
 
@@ -540,6 +710,26 @@ impl<'a> CodeGenerator<'a> {
                         )
                     }
                 },
+                TypedExpr::BinOp {
+                    name: name @ (BinOp::DivInt | BinOp::ModInt),
+                    left,
+                    right,
+                    tipo,
+                    location,
+                } if self.tracing != TraceLevel::Silent => {
+                    let left = self.build(left, module_build_name, &[]);
+                    let right = self.build(right, module_build_name, &[]);
+
+                    self.checked_division(
+                        *name,
+                        tipo.clone(),
+                        left,
+                        right,
+                        location,
+                        module_build_name,
+                    )
+                }
+
                 TypedExpr::BinOp {
                     name,
                     left,
@@ -769,6 +959,12 @@ impl<'a> CodeGenerator<'a> {
                         field_map,
                         ..
                     } => {
+                        if *arity == 0 && !tipo.is_bool() && !tipo.is_void() {
+                            if let Some(constr) = self.nullary_constr(name, tipo) {
+                                return constr;
+                            }
+                        }
+
                         let val_constructor = {
                             let data_type = lookup_data_type_by_tipo(&self.data_types, tipo);
 
@@ -944,6 +1140,83 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
+    /// Build a constructor without arguments (e.g. 'None') as a regular constructor application,
+    /// which gets folded into a constant. Returns 'None' when the constructor cannot be resolved
+    /// from the known data-types, in which case callers fall back to a plain variable.
+    fn nullary_constr(&self, constr_name: &str, tipo: &Rc<Type>) -> Option<AirTree> {
+        let data_type = lookup_data_type_by_tipo(&self.data_types, tipo)?;
+
+        let constr_index = data_type
+            .constructors
+            .iter()
+            .position(|constr| constr.name == constr_name)?;
+
+        Some(AirTree::create_constr(constr_index, tipo.clone(), vec![]))
+    }
+
+    /// Division and modulo by zero otherwise crash with the machine's generic
+    /// failure. When compiler traces are enabled, we check the divisor upfront and
+    /// fail with a trace pointing at the offending expression instead.
+    fn checked_division(
+        &mut self,
+        name: BinOp,
+        tipo: Rc<Type>,
+        left: AirTree,
+        right: AirTree,
+        location: &Span,
+        module_build_name: &str,
+    ) -> AirTree {
+        let label = match self.tracing {
+            TraceLevel::Silent => unreachable!("checked division without traces"),
+            TraceLevel::Compact => {
+                get_line_columns_by_span(module_build_name, location, &self.module_src).to_string()
+            }
+            TraceLevel::Verbose => {
+                get_src_code_by_span(module_build_name, location, &self.module_src)
+            }
+        };
+
+        let operation = match name {
+            BinOp::DivInt => "division",
+            BinOp::ModInt => "modulo",
+            _ => unreachable!("checked division on {name:?}"),
+        };
+
+        let dividend = format!("__dividend_span_{}_{}", location.start, location.end);
+        let divisor = format!("__divisor_span_{}_{}", location.start, location.end);
+
+        AirTree::let_assignment(
+            &dividend,
+            left,
+            AirTree::let_assignment(
+                &divisor,
+                right,
+                AirTree::if_branch(
+                    tipo.clone(),
+                    AirTree::binop(
+                        BinOp::Eq,
+                        Type::bool(),
+                        AirTree::local_var(&divisor, Type::int()),
+                        AirTree::int(0),
+                        Type::int(),
+                    ),
+                    AirTree::trace(
+                        AirTree::string(format!("{operation} by zero: {label}")),
+                        tipo.clone(),
+                        AirTree::error(tipo.clone(), false),
+                    ),
+                    AirTree::binop(
+                        name,
+                        tipo,
+                        AirTree::local_var(&dividend, Type::int()),
+                        AirTree::local_var(&divisor, Type::int()),
+                        Type::int(),
+                    ),
+                ),
+            ),
+        )
+    }
+
     pub fn assignment(
         &mut self,
         pattern: &TypedPattern,
@@ -2776,13 +3049,11 @@ impl<'a> CodeGenerator<'a> {
         while let Some((key, variant_name)) = used_functions.pop() {
             defined_functions.push((key.clone(), variant_name.clone()));
 
-            let function_variants = functions_to_hoist
-                .get(&key)
-                .unwrap_or_else(|| panic!("Missing Function Definition"));
+            let function_variants = hoisted_variants(&functions_to_hoist, &key);
 
-            let (tree_path, function) = function_variants
-                .get(&variant_name)
-                .unwrap_or_else(|| panic!("Missing Function Variant Definition"));
+            let (tree_path, function) = function_variants.get(&variant_name).unwrap_or_else(|| {
+                panic!("missing variant '{variant_name}' in hoisted function definitions")
+            });
 
             match function {
                 HoistableFunction::Function { body, deps, params } => {
@@ -2800,13 +3071,14 @@ impl<'a> CodeGenerator<'a> {
                         tree_path,
                     );
 
-                    let function_variants = functions_to_hoist
-                        .get_mut(&key)
-                        .unwrap_or_else(|| panic!("Missing Function Definition"));
+                    let function_variants = hoisted_variants_mut(&mut functions_to_hoist, &key);
 
-                    let (_, function) = function_variants
-                        .get_mut(&variant_name)
-                        .expect("Missing Function Variant Definition");
+                    let (_, function) =
+                        function_variants.get_mut(&variant_name).unwrap_or_else(|| {
+                            panic!(
+                                "missing variant '{variant_name}' in hoisted function definitions"
+                            )
+                        });
 
                     *function = HoistableFunction::Function {
                         body: hoist_body,
@@ -2911,11 +3183,11 @@ impl<'a> CodeGenerator<'a> {
                     (function_list.clone(), index, function_key.clone()),
                 );
 
-                let (tree_path, func) = functions_to_hoist
-                    .get_mut(func_name)
-                    .expect("Missing Function Definition")
-                    .get_mut(variant)
-                    .expect("Missing Function Variant Definition");
+                let (tree_path, func) = hoisted_variants_mut(&mut functions_to_hoist, &func_name)
+                    .get_mut(&variant)
+                    .unwrap_or_else(|| {
+                        panic!("missing variant '{variant}' in hoisted function definitions")
+                    });
 
                 match func {
                     HoistableFunction::Function { params, body, deps } => {
@@ -2971,13 +3243,11 @@ impl<'a> CodeGenerator<'a> {
                 "Sorting dependency attempts exceeded"
             );
 
-            let function_variants = functions_to_hoist_cloned
-                .get(&generic_func)
-                .unwrap_or_else(|| panic!("Missing Function Definition"));
+            let function_variants = hoisted_variants(&functions_to_hoist_cloned, &generic_func);
 
-            let (_, function) = function_variants
-                .get(&variant)
-                .unwrap_or_else(|| panic!("Missing Function Variant Definition"));
+            let (_, function) = function_variants.get(&variant).unwrap_or_else(|| {
+                panic!("missing variant '{variant}' in hoisted function definitions")
+            });
 
             match function {
                 HoistableFunction::Function { deps, .. } => {
@@ -3080,13 +3350,11 @@ impl<'a> CodeGenerator<'a> {
                 continue;
             }
 
-            let function_variants = functions_to_hoist
-                .get(&key)
-                .unwrap_or_else(|| panic!("Missing Function Definition"));
+            let function_variants = hoisted_variants(&functions_to_hoist, &key);
 
-            let (tree_path, function) = function_variants
-                .get(&variant)
-                .unwrap_or_else(|| panic!("Missing Function Variant Definition"));
+            let (tree_path, function) = function_variants.get(&variant).unwrap_or_else(|| {
+                panic!("missing variant '{variant}' in hoisted function definitions")
+            });
 
             self.hoist_function(
                 &mut air_tree,
@@ -3229,13 +3497,14 @@ impl<'a> CodeGenerator<'a> {
         let mut sorted_dep_vec = vec![];
 
         while let Some(dep) = deps_vec.pop() {
-            let function_variants = functions_to_hoist
-                .get(&dep.0)
-                .unwrap_or_else(|| panic!("Missing Function Definition"));
+            let function_variants = hoisted_variants(functions_to_hoist, &dep.0);
 
-            let (_, function) = function_variants
-                .get(&dep.1)
-                .unwrap_or_else(|| panic!("Missing Function Variant Definition"));
+            let (_, function) = function_variants.get(&dep.1).unwrap_or_else(|| {
+                panic!(
+                    "missing variant '{}' in hoisted function definitions",
+                    dep.1
+                )
+            });
 
             match function {
                 HoistableFunction::Function { deps, .. } => {
@@ -3290,13 +3559,11 @@ impl<'a> CodeGenerator<'a> {
                     return then;
                 }
 
-                let dependency = functions_to_hoist
-                    .get(&dep_key)
-                    .unwrap_or_else(|| panic!("Missing Function Definition"));
+                let dependency = hoisted_variants(functions_to_hoist, &dep_key);
 
-                let (dep_path, dep_function) = dependency
-                    .get(&dep_variant)
-                    .unwrap_or_else(|| panic!("Missing Function Variant Definition"));
+                let (dep_path, dep_function) = dependency.get(&dep_variant).unwrap_or_else(|| {
+                    panic!("missing variant '{dep_variant}' in hoisted function definitions")
+                });
 
                 // In the case of zero args, we need to hoist the dependency function to the top of the zero arg function
                 // The dependency we are hoisting should have an equal path to the function we hoisted
@@ -4132,6 +4399,12 @@ impl<'a> CodeGenerator<'a> {
                                         )
                                     }
                                 }
+                                Some(UplcType::List(_)) if builder::is_empty_list(&right) => {
+                                    Term::null_list().apply(left)
+                                }
+                                Some(UplcType::List(_)) if builder::is_empty_list(&left) => {
+                                    Term::null_list().apply(right)
+                                }
                                 Some(UplcType::List(_)) if tipo.is_map() => builtin
                                     .apply(Term::map_data().apply(left))
                                     .apply(Term::map_data().apply(right)),
@@ -4191,6 +4464,10 @@ impl<'a> CodeGenerator<'a> {
                     BinOp::ModInt => Term::Builtin(DefaultFunction::ModInteger)
                         .apply(left)
                         .apply(right),
+                    BinOp::Concat if matches!(uplc_type, Some(UplcType::String)) => {
+                        Term::append_string().apply(left).apply(right)
+                    }
+                    BinOp::Concat => Term::append_bytearray().apply(left).apply(right),
                 };
                 Some(term)
             }
@@ -4203,7 +4480,28 @@ impl<'a> CodeGenerator<'a> {
                 let func_name = if module_name.is_empty() {
                     format!("{func_name}{variant_name}")
                 } else {
-                    format!("{module_name}_{func_name}{variant_name}")
+                    let hoisted = format!("{module_name}_{func_name}{variant_name}");
+
+                    if self.source_maps.is_some() {
+                        if let Some(function) = self.functions.get(&FunctionAccessKey {
+                            module_name: module_name.clone(),
+                            function_name: func_name.clone(),
+                        }) {
+                            self.origins.insert(
+                                hoisted.clone(),
+                                Origin {
+                                    module: module_name,
+                                    name: func_name,
+                                    location: Span {
+                                        start: function.location.start,
+                                        end: function.end_position + 1,
+                                    },
+                                },
+                            );
+                        }
+                    }
+
+                    hoisted
                 };
 
                 match variant {
@@ -5184,7 +5482,13 @@ impl<'a> CodeGenerator<'a> {
                 Some(term)
             }
             Air::Trace { .. } => {
-                let text = arg_stack.pop().unwrap();
+                let text = match arg_stack.pop().unwrap() {
+                    Term::Constant(constant) => match constant.as_ref() {
+                        UplcConstant::String(msg) => Term::string(self.trace_message(msg.clone())),
+                        _ => Term::Constant(constant),
+                    },
+                    text => text,
+                };
 
                 let term = arg_stack.pop().unwrap();
 
@@ -5223,6 +5527,33 @@ impl<'a> CodeGenerator<'a> {
     }
 }
 
+type HoistedVariants = IndexMap<String, (TreePath, HoistableFunction)>;
+
+fn hoisted_variants<'a>(
+    functions_to_hoist: &'a IndexMap<FunctionAccessKey, HoistedVariants>,
+    key: &FunctionAccessKey,
+) -> &'a HoistedVariants {
+    functions_to_hoist
+        .get(key)
+        .unwrap_or_else(|| missing_hoisted_function(key))
+}
+
+fn hoisted_variants_mut<'a>(
+    functions_to_hoist: &'a mut IndexMap<FunctionAccessKey, HoistedVariants>,
+    key: &FunctionAccessKey,
+) -> &'a mut HoistedVariants {
+    functions_to_hoist
+        .get_mut(key)
+        .unwrap_or_else(|| missing_hoisted_function(key))
+}
+
+fn missing_hoisted_function(key: &FunctionAccessKey) -> ! {
+    panic!(
+        "missing hoisted function definition for {}.{}",
+        key.module_name, key.function_name
+    )
+}
+
 fn handle_assigns(
     subject_name: &String,
     subject_tipo: Rc<Type>,
@@ -5271,3 +5602,40 @@ fn handle_assigns(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtins;
+
+    #[test]
+    fn nullary_constr_is_total() {
+        let data_types = builtins::prelude_data_types(&IdGenerator::new());
+
+        let generator = CodeGenerator::new(
+            PlutusVersion::default(),
+            IndexMap::new(),
+            IndexMap::new(),
+            data_types.iter().collect(),
+            IndexMap::new(),
+            IndexMap::new(),
+            Tracing::silent(),
+        );
+
+        let option = Type::option(Type::int());
+
+        assert!(generator.nullary_constr("None", &option).is_some());
+        assert!(generator.nullary_constr("Nope", &option).is_none());
+
+        let unknown = Type::App {
+            public: true,
+            contains_opaque: false,
+            module: "foo".to_string(),
+            name: "Color".to_string(),
+            args: vec![],
+            alias: None,
+        };
+
+        assert!(generator.nullary_constr("Red", &unknown).is_none());
+    }
+}
@@ -6,8 +6,9 @@ use super::{
 use crate::{
     ast::{
         DataTypeKey, FunctionAccessKey, Pattern, Span, TraceLevel, TypedArg, TypedAssignmentKind,
-        TypedDataType, TypedPattern,
+        TypedDataType, TypedPattern, VALIDATOR_DATUM_VARIABLE, VALIDATOR_REDEEMER_VARIABLE,
     },
+    expr::TypedExpr,
     line_numbers::{LineColumn, LineNumbers},
     tipo::{
         check_replaceable_opaque_type, convert_opaque_type, find_and_replace_generics, Type,
@@ -652,7 +653,7 @@ pub fn convert_constants_to_data(constants: Vec<Rc<UplcConstant>>) -> Vec<UplcCo
                                     .into_iter()
                                     .map(|constant| match constant {
                                         UplcConstant::Data(d) => d,
-                                        _ => todo!(),
+                                        _ => unreachable!("constants always convert to data"),
                                     })
                                     .collect_vec();
                                 (inner_constants[0].clone(), inner_constants[1].clone())
@@ -668,7 +669,7 @@ pub fn convert_constants_to_data(constants: Vec<Rc<UplcConstant>>) -> Vec<UplcCo
                             .into_iter()
                             .map(|constant| match constant {
                                 UplcConstant::Data(d) => d,
-                                _ => todo!(),
+                                _ => unreachable!("constants always convert to data"),
                             })
                             .collect_vec();
 
@@ -681,7 +682,7 @@ pub fn convert_constants_to_data(constants: Vec<Rc<UplcConstant>>) -> Vec<UplcCo
                     .into_iter()
                     .map(|constant| match constant {
                         UplcConstant::Data(d) => d,
-                        _ => todo!(),
+                        _ => unreachable!("constants always convert to data"),
                     })
                     .collect_vec();
 
@@ -1146,6 +1147,15 @@ pub fn wrap_validator_condition(air_tree: AirTree, trace: TraceLevel) -> AirTree
     AirTree::if_branch(Type::void(), air_tree, AirTree::void(), otherwise)
 }
 
+/// Whether a term is a literal empty list (or map), which makes equality a simple emptiness check.
+pub fn is_empty_list(term: &Term<Name>) -> bool {
+    if let Term::Constant(c) = term {
+        matches!(c.as_ref(), UplcConstant::ProtoList(_, elems) if elems.is_empty())
+    } else {
+        false
+    }
+}
+
 pub fn extract_constant(term: &Term<Name>) -> Option<Rc<UplcConstant>> {
     let mut constant = None;
 
@@ -1169,6 +1179,27 @@ pub fn extract_constant(term: &Term<Name>) -> Option<Rc<UplcConstant>> {
     constant
 }
 
+/// When an assignment decodes one of the validator's `Data` arguments into its declared type,
+/// describe that argument (e.g. `redeemer 'r'`) so that failure traces can point at it.
+pub fn get_validator_argument(value: &TypedExpr, pattern: &TypedPattern) -> Option<String> {
+    let TypedExpr::Var { name, .. } = value else {
+        return None;
+    };
+
+    let argument = if name == VALIDATOR_REDEEMER_VARIABLE {
+        "redeemer"
+    } else if name == VALIDATOR_DATUM_VARIABLE {
+        "datum"
+    } else {
+        return None;
+    };
+
+    match pattern {
+        Pattern::Var { name, .. } if !name.starts_with('_') => Some(format!("{argument} '{name}'")),
+        _ => Some(argument.to_string()),
+    }
+}
+
 pub fn get_src_code_by_span(
     module_name: &str,
     span: &Span,
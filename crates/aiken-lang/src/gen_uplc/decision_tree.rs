@@ -1362,14 +1362,12 @@ fn highest_occurrence(matrix: &PatternMatrix, column_length: usize) -> Option<us
 
 #[cfg(test)]
 mod tester {
-    use std::collections::HashMap;
-
     use indexmap::IndexMap;
 
     use crate::{
         ast::{
-            Definition, ModuleKind, Span, TraceLevel, Tracing, TypedModule, TypedPattern,
-            UntypedModule,
+            Definition, ModuleKind, Span, TraceLevel, TraceSeverity, Tracing, TypedModule,
+            TypedPattern, UntypedModule,
         },
         builtins,
         expr::TypedExpr,
@@ -1395,9 +1393,7 @@ mod tester {
 
         let mut warnings = vec![];
 
-        let mut module_types = HashMap::new();
-        module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
-        module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+        let mut module_types = builtins::modules(&id_gen);
 
         for (package, module) in extra {
             let mut warnings = vec![];
@@ -1408,6 +1404,7 @@ mod tester {
                     &package,
                     &module_types,
                     Tracing::All(TraceLevel::Verbose),
+                    TraceSeverity::Debug,
                     &mut warnings,
                     None,
                 )
@@ -1421,6 +1418,7 @@ mod tester {
             "test/project",
             &module_types,
             tracing,
+            TraceSeverity::Debug,
             &mut warnings,
             None,
         );
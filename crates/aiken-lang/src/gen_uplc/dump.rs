@@ -0,0 +1,704 @@
+//! A textual dump of the Air stack: one instruction per line, indented according to its scope.
+//!
+//! The dump is meant for inspection and for writing targeted tests against the intermediate
+//! representation. It can be parsed back into an [`AirDump`], whose rendering is identical to the
+//! original text. Types and value constructors are rendered in a human-readable form, so a dump
+//! cannot be turned back into [`Air`] without the type environment it came from.
+
+use super::air::{Air, FunctionVariants};
+use crate::tipo::{Type, ValueConstructorVariant};
+use indexmap::IndexSet;
+use std::{fmt, rc::Rc, str::FromStr};
+
+const INDENT: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AirDump {
+    pub definitions: Vec<DumpedDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpedDefinition {
+    pub name: String,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub depth: usize,
+    pub op: String,
+    pub fields: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid Air dump at line {line}: {reason}")]
+pub struct ParseError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl DumpedDefinition {
+    pub fn new(name: impl Into<String>, air: &[(usize, Air)]) -> Self {
+        Self {
+            name: name.into(),
+            instructions: air
+                .iter()
+                .map(|(depth, air)| Instruction::new(*depth, air))
+                .collect(),
+        }
+    }
+}
+
+impl Instruction {
+    pub fn new(depth: usize, air: &Air) -> Self {
+        let (op, fields) = describe(air);
+
+        Self {
+            depth,
+            op: op.to_string(),
+            fields: fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        }
+    }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl fmt::Display for AirDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ix, definition) in self.definitions.iter().enumerate() {
+            if ix > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{definition}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for DumpedDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.name)?;
+
+        for instruction in self.instructions.iter() {
+            writeln!(f, "{instruction}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{}",
+            "",
+            self.op,
+            indent = (self.depth + 1) * INDENT
+        )?;
+
+        for (key, value) in self.fields.iter() {
+            write!(f, " {key}={}", quote(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for AirDump {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut definitions: Vec<DumpedDefinition> = Vec::new();
+
+        for (ix, line) in src.lines().enumerate() {
+            let line_number = ix + 1;
+
+            let error = |reason: &str| ParseError {
+                line: line_number,
+                reason: reason.to_string(),
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let indent = line.len() - line.trim_start_matches(' ').len();
+
+            if indent == 0 {
+                let name = line
+                    .strip_suffix(':')
+                    .ok_or_else(|| error("expected a definition name followed by ':'"))?;
+
+                definitions.push(DumpedDefinition {
+                    name: name.to_string(),
+                    instructions: vec![],
+                });
+
+                continue;
+            }
+
+            if indent % INDENT != 0 {
+                return Err(error("indentation must be a multiple of two spaces"));
+            }
+
+            let definition = definitions
+                .last_mut()
+                .ok_or_else(|| error("instruction outside of any definition"))?;
+
+            definition
+                .instructions
+                .push(parse_instruction(indent / INDENT - 1, &line[indent..]).map_err(error)?);
+        }
+
+        Ok(AirDump { definitions })
+    }
+}
+
+fn parse_instruction(depth: usize, src: &str) -> Result<Instruction, &'static str> {
+    let (op, mut rest) = src.split_once(' ').unwrap_or((src, ""));
+
+    if op.is_empty() {
+        return Err("missing instruction name");
+    }
+
+    let mut fields = Vec::new();
+
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=').ok_or("expected 'key=value'")?;
+
+        let (value, after) = if let Some(quoted) = after.strip_prefix('"') {
+            unquote(quoted)?
+        } else {
+            let (value, after) = after.split_once(' ').unwrap_or((after, ""));
+            (value.to_string(), after)
+        };
+
+        fields.push((key.to_string(), value));
+
+        rest = after.strip_prefix(' ').unwrap_or(after);
+    }
+
+    Ok(Instruction {
+        depth,
+        op: op.to_string(),
+        fields,
+    })
+}
+
+fn quote(value: &str) -> String {
+    if !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\\')
+    {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+
+    quoted
+}
+
+/// Read a quoted value, whose opening quote has already been consumed. Returns the unescaped
+/// value and whatever follows the closing quote.
+fn unquote(src: &str) -> Result<(String, &str), &'static str> {
+    let mut value = String::new();
+    let mut chars = src.char_indices();
+
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &src[ix + 1..])),
+            '\\' => match chars.next() {
+                Some((_, '"')) => value.push('"'),
+                Some((_, '\\')) => value.push('\\'),
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, 'r')) => value.push('\r'),
+                _ => return Err("invalid escape sequence"),
+            },
+            c => value.push(c),
+        }
+    }
+
+    Err("unterminated quoted value")
+}
+
+fn describe(air: &Air) -> (&'static str, Vec<(&'static str, String)>) {
+    match air {
+        Air::Int { value } => ("Int", vec![("value", value.clone())]),
+        Air::String { value } => ("String", vec![("value", value.clone())]),
+        Air::ByteArray { bytes } => ("ByteArray", vec![("bytes", hex::encode(bytes))]),
+        Air::CurvePoint { point } => ("CurvePoint", vec![("point", hex::encode(point.compress()))]),
+        Air::Bool { value } => ("Bool", vec![("value", value.to_string())]),
+        Air::List {
+            count,
+            tipo: t,
+            tail,
+        } => (
+            "List",
+            vec![
+                ("count", count.to_string()),
+                ("tail", tail.to_string()),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::Tuple { tipo: t, count } => (
+            "Tuple",
+            vec![("count", count.to_string()), ("type", tipo(t))],
+        ),
+        Air::Pair { tipo: t } => ("Pair", vec![("type", tipo(t))]),
+        Air::Void => ("Void", vec![]),
+        Air::Var {
+            constructor,
+            name,
+            variant_name,
+        } => {
+            let origin = match &constructor.variant {
+                ValueConstructorVariant::LocalVariable { .. } => "local".to_string(),
+                ValueConstructorVariant::ModuleConstant { module, name, .. } => {
+                    format!("constant:{module}.{name}")
+                }
+                ValueConstructorVariant::ModuleFn { module, name, .. } => {
+                    format!("fn:{module}.{name}")
+                }
+                ValueConstructorVariant::Record { module, name, .. } => {
+                    format!("record:{module}.{name}")
+                }
+            };
+
+            (
+                "Var",
+                vec![
+                    ("name", name.clone()),
+                    ("variant", variant_name.clone()),
+                    ("origin", origin),
+                    ("type", tipo(&constructor.tipo)),
+                ],
+            )
+        }
+        Air::Call { count, tipo: t } => (
+            "Call",
+            vec![("count", count.to_string()), ("type", tipo(t))],
+        ),
+        Air::DefineFunc {
+            func_name,
+            module_name,
+            variant_name,
+            variant,
+        } => {
+            let mut fields = vec![
+                ("name", format!("{module_name}.{func_name}")),
+                ("variant", variant_name.clone()),
+            ];
+
+            match variant {
+                FunctionVariants::Standard(params) => {
+                    fields.push(("kind", "standard".to_string()));
+                    fields.push(("params", names(params)));
+                }
+                FunctionVariants::Recursive {
+                    params,
+                    recursive_nonstatic_params,
+                } => {
+                    fields.push(("kind", "recursive".to_string()));
+                    fields.push(("params", names(params)));
+                    fields.push(("nonstatic", names(recursive_nonstatic_params)));
+                }
+                FunctionVariants::Cyclic(functions) => {
+                    fields.push(("kind", "cyclic".to_string()));
+                    fields.push((
+                        "params",
+                        functions
+                            .iter()
+                            .map(|params| names(params))
+                            .collect::<Vec<_>>()
+                            .join(";"),
+                    ));
+                }
+            }
+
+            ("DefineFunc", fields)
+        }
+        Air::Fn {
+            params,
+            allow_inline,
+        } => (
+            "Fn",
+            vec![
+                ("params", names(params)),
+                ("inline", allow_inline.to_string()),
+            ],
+        ),
+        Air::Builtin {
+            count,
+            func,
+            tipo: t,
+        } => (
+            "Builtin",
+            vec![
+                ("func", func.to_string()),
+                ("count", count.to_string()),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::BinOp {
+            name,
+            tipo: t,
+            argument_tipo,
+        } => (
+            "BinOp",
+            vec![
+                ("op", format!("{name:?}")),
+                ("type", tipo(t)),
+                ("argument_type", tipo(argument_tipo)),
+            ],
+        ),
+        Air::UnOp { op } => ("UnOp", vec![("op", format!("{op:?}"))]),
+        Air::Let { name } => ("Let", vec![("name", name.clone())]),
+        Air::SoftCastLet { name, tipo: t } => (
+            "SoftCastLet",
+            vec![("name", name.clone()), ("type", tipo(t))],
+        ),
+        Air::CastFromData { tipo: t, full_cast } => (
+            "CastFromData",
+            vec![("type", tipo(t)), ("full", full_cast.to_string())],
+        ),
+        Air::CastToData { tipo: t } => ("CastToData", vec![("type", tipo(t))]),
+        Air::AssertBool { is_true } => ("AssertBool", vec![("is_true", is_true.to_string())]),
+        Air::When {
+            tipo: t,
+            subject_name,
+            subject_tipo,
+        } => (
+            "When",
+            vec![
+                ("subject", subject_name.clone()),
+                ("subject_type", tipo(subject_tipo)),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::Clause {
+            subject_tipo,
+            subject_name,
+        } => (
+            "Clause",
+            vec![
+                ("subject", subject_name.clone()),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::ListClause {
+            subject_tipo,
+            tail_name,
+            next_tail_name,
+        } => (
+            "ListClause",
+            vec![
+                ("tail", tail_name.clone()),
+                (
+                    "next_tail",
+                    next_tail_name
+                        .as_ref()
+                        .map(|(a, b)| format!("{a},{b}"))
+                        .unwrap_or_else(|| "_".to_string()),
+                ),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::WrapClause => ("WrapClause", vec![]),
+        Air::TupleClause {
+            subject_tipo,
+            indices,
+            predefined_indices,
+            subject_name,
+            complex_clause,
+        } => (
+            "TupleClause",
+            vec![
+                ("subject", subject_name.clone()),
+                ("indices", indexed(indices)),
+                ("predefined", indexed(predefined_indices)),
+                ("complex", complex_clause.to_string()),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::PairClause {
+            subject_tipo,
+            subject_name,
+            fst_name,
+            snd_name,
+            complex_clause,
+        } => (
+            "PairClause",
+            vec![
+                ("subject", subject_name.clone()),
+                ("fst", optional(fst_name)),
+                ("snd", optional(snd_name)),
+                ("complex", complex_clause.to_string()),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::ClauseGuard {
+            subject_name,
+            subject_tipo,
+        } => (
+            "ClauseGuard",
+            vec![
+                ("subject", subject_name.clone()),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::ListClauseGuard {
+            subject_tipo,
+            tail_name,
+            next_tail_name,
+            inverse,
+        } => (
+            "ListClauseGuard",
+            vec![
+                ("tail", tail_name.clone()),
+                ("next_tail", optional(next_tail_name)),
+                ("inverse", inverse.to_string()),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::TupleGuard {
+            subject_tipo,
+            indices,
+            subject_name,
+        } => (
+            "TupleGuard",
+            vec![
+                ("subject", subject_name.clone()),
+                ("indices", indexed(indices)),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::PairGuard {
+            subject_tipo,
+            subject_name,
+            fst_name,
+            snd_name,
+        } => (
+            "PairGuard",
+            vec![
+                ("subject", subject_name.clone()),
+                ("fst", optional(fst_name)),
+                ("snd", optional(snd_name)),
+                ("subject_type", tipo(subject_tipo)),
+            ],
+        ),
+        Air::Finally => ("Finally", vec![]),
+        Air::If { tipo: t } => ("If", vec![("type", tipo(t))]),
+        Air::Constr {
+            tag,
+            tipo: t,
+            count,
+        } => (
+            "Constr",
+            vec![
+                ("tag", tag.to_string()),
+                ("count", count.to_string()),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::RecordUpdate {
+            highest_index,
+            indices,
+            tipo: t,
+        } => (
+            "RecordUpdate",
+            vec![
+                ("highest_index", highest_index.to_string()),
+                (
+                    "indices",
+                    indices
+                        .iter()
+                        .map(|(ix, _)| ix.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                ),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::FieldsExpose { indices, is_expect } => (
+            "FieldsExpose",
+            vec![
+                (
+                    "indices",
+                    indices
+                        .iter()
+                        .map(|(ix, name, _)| format!("{ix}:{name}"))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                ),
+                ("is_expect", is_expect.to_string()),
+            ],
+        ),
+        Air::ListAccessor {
+            tipo: t,
+            names: accessed,
+            tail,
+            expect_level,
+        } => (
+            "ListAccessor",
+            vec![
+                ("names", names(accessed)),
+                ("tail", tail.to_string()),
+                ("expect", format!("{expect_level:?}")),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::TupleAccessor {
+            names: accessed,
+            tipo: t,
+            is_expect,
+        } => (
+            "TupleAccessor",
+            vec![
+                ("names", names(accessed)),
+                ("is_expect", is_expect.to_string()),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::PairAccessor {
+            fst,
+            snd,
+            tipo: t,
+            is_expect,
+        } => (
+            "PairAccessor",
+            vec![
+                ("fst", optional(fst)),
+                ("snd", optional(snd)),
+                ("is_expect", is_expect.to_string()),
+                ("type", tipo(t)),
+            ],
+        ),
+        Air::ExtractField { tipo: t } => ("ExtractField", vec![("type", tipo(t))]),
+        Air::ErrorTerm { tipo: t, validator } => (
+            "ErrorTerm",
+            vec![("validator", validator.to_string()), ("type", tipo(t))],
+        ),
+        Air::Trace { tipo: t } => ("Trace", vec![("type", tipo(t))]),
+        Air::NoOp => ("NoOp", vec![]),
+        Air::FieldsEmpty => ("FieldsEmpty", vec![]),
+        Air::ListEmpty => ("ListEmpty", vec![]),
+    }
+}
+
+fn tipo(tipo: &Rc<Type>) -> String {
+    tipo.to_pretty(0)
+}
+
+fn names(names: &[String]) -> String {
+    names.join(",")
+}
+
+fn indexed(indices: &IndexSet<(usize, String)>) -> String {
+    indices
+        .iter()
+        .map(|(ix, name)| format!("{ix}:{name}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn optional(name: &Option<String>) -> String {
+    name.clone().unwrap_or_else(|| "_".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::BinOp;
+
+    fn sample() -> AirDump {
+        AirDump {
+            definitions: vec![DumpedDefinition::new(
+                "foo.bar.spend",
+                &[
+                    (0, Air::Let { name: "x".into() }),
+                    (
+                        1,
+                        Air::String {
+                            value: "hello \"world\"\n".into(),
+                        },
+                    ),
+                    (
+                        1,
+                        Air::BinOp {
+                            name: BinOp::Eq,
+                            tipo: Type::bool(),
+                            argument_tipo: Type::list(Type::int()),
+                        },
+                    ),
+                    (2, Air::Int { value: "1".into() }),
+                    (2, Air::String { value: "".into() }),
+                ],
+            )],
+        }
+    }
+
+    #[test]
+    fn dump_layout() {
+        assert_eq!(
+            sample().to_string(),
+            [
+                "foo.bar.spend:",
+                "  Let name=x",
+                "    String value=\"hello \\\"world\\\"\\n\"",
+                "    BinOp op=Eq type=Bool argument_type=List<Int>",
+                "      Int value=1",
+                "      String value=\"\"",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let dump = sample();
+        let text = dump.to_string();
+
+        let parsed: AirDump = text.parse().unwrap();
+
+        assert_eq!(parsed, dump);
+        assert_eq!(parsed.to_string(), text);
+        assert_eq!(
+            parsed.definitions[0].instructions[1].field("value"),
+            Some("hello \"world\"\n")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("  Int value=1".parse::<AirDump>().is_err());
+        assert!("foo:\n   Int value=1".parse::<AirDump>().is_err());
+        assert!("foo:\n  String value=\"oops".parse::<AirDump>().is_err());
+        assert!("foo:\n  Int value".parse::<AirDump>().is_err());
+    }
+}
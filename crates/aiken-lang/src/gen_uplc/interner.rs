@@ -55,3 +55,18 @@ impl AirInterner {
         }
     }
 }
+
+/// Recover the name a user wrote from one mangled by 'lookup_interned', leaving any other name
+/// untouched.
+pub fn user_facing_name(text: &str) -> &str {
+    match text.rsplit_once("_id_") {
+        Some((name, unique))
+            if !name.is_empty()
+                && !unique.is_empty()
+                && unique.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            name
+        }
+        _ => text,
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    ast::{FunctionAccessKey, TypedFunction},
+    builtins::PRELUDE,
+    expr::TypedExpr,
+    tipo::{ModuleValueConstructor, ValueConstructorVariant},
+};
+use indexmap::{IndexMap, IndexSet};
+
+/// Compute the set of module functions transitively reachable from the given root expressions
+/// (typically validator handlers, tests or benchmarks). Constants are followed too, since their
+/// definition may itself refer to functions. Prelude functions are always considered reachable.
+///
+/// A reference that resolves to neither a function nor a constant keeps its whole module
+/// reachable: pruning errs on the side of caution rather than leaving the code generator without
+/// a definition to hoist.
+pub fn reachable_functions<'a>(
+    roots: impl IntoIterator<Item = &'a TypedExpr>,
+    functions: &IndexMap<FunctionAccessKey, TypedFunction>,
+    constants: &IndexMap<FunctionAccessKey, TypedExpr>,
+) -> IndexSet<FunctionAccessKey> {
+    let mut reachable = functions
+        .keys()
+        .filter(|key| key.module_name.is_empty())
+        .cloned()
+        .collect::<IndexSet<_>>();
+    let mut visited_constants = IndexSet::new();
+    let mut visited_modules = IndexSet::new();
+    let mut stack: Vec<&TypedExpr> = roots.into_iter().collect();
+
+    while let Some(expr) = stack.pop() {
+        let mut references = vec![];
+
+        collect_references(expr, &mut references);
+
+        for key in references {
+            if let Some(function) = functions.get(&key) {
+                if reachable.insert(key) {
+                    stack.push(&function.body);
+                }
+            } else if let Some(constant) = constants.get(&key) {
+                if visited_constants.insert(key) {
+                    stack.push(constant);
+                }
+            } else if visited_modules.insert(key.module_name.clone()) {
+                for (other, function) in functions {
+                    if other.module_name == key.module_name && reachable.insert(other.clone()) {
+                        stack.push(&function.body);
+                    }
+                }
+            }
+        }
+    }
+
+    reachable
+}
+
+fn collect_references(expr: &TypedExpr, references: &mut Vec<FunctionAccessKey>) {
+    match expr {
+        TypedExpr::UInt { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::ByteArray { .. }
+        | TypedExpr::CurvePoint { .. }
+        | TypedExpr::ErrorTerm { .. } => (),
+
+        TypedExpr::Var { constructor, .. } => match &constructor.variant {
+            ValueConstructorVariant::ModuleFn {
+                builtin: Some(_), ..
+            } => (),
+            ValueConstructorVariant::ModuleFn { name, module, .. }
+            | ValueConstructorVariant::ModuleConstant { name, module, .. } => {
+                references.push(access_key(module, name))
+            }
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::Record { .. } => (),
+        },
+
+        TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Fn { module, name, .. }
+                | ModuleValueConstructor::Constant { module, name, .. },
+            ..
+        } => references.push(access_key(module, name)),
+
+        TypedExpr::ModuleSelect { .. } => (),
+
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            expressions
+                .iter()
+                .for_each(|expr| collect_references(expr, references));
+        }
+
+        TypedExpr::Fn { body, .. } => collect_references(body, references),
+
+        TypedExpr::List { elements, tail, .. } => {
+            elements
+                .iter()
+                .for_each(|expr| collect_references(expr, references));
+            if let Some(tail) = tail {
+                collect_references(tail, references);
+            }
+        }
+
+        TypedExpr::Call { fun, args, .. } => {
+            collect_references(fun, references);
+            args.iter()
+                .for_each(|arg| collect_references(&arg.value, references));
+        }
+
+        TypedExpr::BinOp { left, right, .. } => {
+            collect_references(left, references);
+            collect_references(right, references);
+        }
+
+        TypedExpr::Assignment { value, .. } => collect_references(value, references),
+
+        TypedExpr::Trace { then, text, .. } => {
+            collect_references(text, references);
+            collect_references(then, references);
+        }
+
+        TypedExpr::When {
+            subject, clauses, ..
+        } => {
+            collect_references(subject, references);
+            clauses
+                .iter()
+                .for_each(|clause| collect_references(&clause.then, references));
+        }
+
+        TypedExpr::If {
+            branches,
+            final_else,
+            ..
+        } => {
+            branches.iter().for_each(|branch| {
+                collect_references(&branch.condition, references);
+                collect_references(&branch.body, references);
+            });
+            collect_references(final_else, references);
+        }
+
+        TypedExpr::RecordAccess { record, .. } => collect_references(record, references),
+
+        TypedExpr::Tuple { elems, .. } => {
+            elems
+                .iter()
+                .for_each(|expr| collect_references(expr, references));
+        }
+
+        TypedExpr::Pair { fst, snd, .. } => {
+            collect_references(fst, references);
+            collect_references(snd, references);
+        }
+
+        TypedExpr::TupleIndex { tuple, .. } => collect_references(tuple, references),
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            collect_references(spread, references);
+            args.iter()
+                .for_each(|arg| collect_references(&arg.value, references));
+        }
+
+        TypedExpr::UnOp { value, .. } => collect_references(value, references),
+    }
+}
+
+// NOTE: Prelude functions are registered under an empty module name; see the equivalent
+// transformation in the code generator.
+fn access_key(module: &str, name: &str) -> FunctionAccessKey {
+    FunctionAccessKey {
+        module_name: if module == PRELUDE {
+            String::new()
+        } else {
+            module.to_string()
+        },
+        function_name: name.to_string(),
+    }
+}
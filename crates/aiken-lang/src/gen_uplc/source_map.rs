@@ -0,0 +1,85 @@
+//! A source map of generated programs, telling which UPLC each source definition and `let`
+//! binding compiled to. While generating code, the generator records the (unique) name under
+//! which it binds each function and variable; those names are then looked up in the optimized
+//! program, where whatever survived inlining is still bound as `(\name -> ...) value`.
+
+use crate::ast::Span;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use uplc::ast::{Name, Term};
+
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub module: String,
+    pub name: String,
+    /// The whole definition for functions and validators; the assignment for variables.
+    pub location: Span,
+    pub term: Term<Name>,
+}
+
+/// Where a name bound in generated code comes from.
+#[derive(Debug, Clone)]
+pub struct Origin {
+    pub module: String,
+    pub name: String,
+    pub location: Span,
+}
+
+impl SourceMap {
+    /// Collect the values bound, in the given (optimized) term, to the names of the given
+    /// origins. Names which were inlined away by optimizations have no entry.
+    pub fn new(term: &Term<Name>, origins: &IndexMap<String, Origin>) -> Self {
+        let mut entries = Vec::new();
+
+        collect(term, origins, &mut HashSet::new(), &mut entries);
+
+        SourceMap { entries }
+    }
+}
+
+fn collect(
+    term: &Term<Name>,
+    origins: &IndexMap<String, Origin>,
+    seen: &mut HashSet<String>,
+    entries: &mut Vec<Entry>,
+) {
+    match term {
+        Term::Apply { function, argument } => {
+            if let Term::Lambda { parameter_name, .. } = function.as_ref() {
+                // Recursive functions re-bind their own name inside their body; only the
+                // outermost binding holds the definition.
+                if let Some(origin) = origins.get(&parameter_name.text) {
+                    if seen.insert(parameter_name.text.clone()) {
+                        entries.push(Entry {
+                            module: origin.module.clone(),
+                            name: origin.name.clone(),
+                            location: origin.location,
+                            term: argument.as_ref().clone(),
+                        });
+                    }
+                }
+            }
+
+            collect(function, origins, seen, entries);
+            collect(argument, origins, seen, entries);
+        }
+        Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => {
+            collect(body, origins, seen, entries)
+        }
+        Term::Constr { fields, .. } => fields
+            .iter()
+            .for_each(|field| collect(field, origins, seen, entries)),
+        Term::Case { constr, branches } => {
+            collect(constr, origins, seen, entries);
+            branches
+                .iter()
+                .for_each(|branch| collect(branch, origins, seen, entries));
+        }
+        Term::Var(_) | Term::Constant(_) | Term::Error | Term::Builtin(_) => (),
+    }
+}
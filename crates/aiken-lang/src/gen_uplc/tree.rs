@@ -845,17 +845,25 @@ impl AirTree {
     }
 
     pub fn to_vec(&self) -> Vec<Air> {
+        self.to_scoped_vec()
+            .into_iter()
+            .map(|(_, air)| air)
+            .collect()
+    }
+
+    /// Flatten the tree into a stack of Air instructions, each paired with its nesting depth.
+    pub fn to_scoped_vec(&self) -> Vec<(usize, Air)> {
         let mut air_vec = vec![];
-        self.create_air_vec(&mut air_vec);
+        self.create_air_vec(&mut air_vec, 0);
         air_vec
     }
 
-    fn create_air_vec(&self, air_vec: &mut Vec<Air>) {
+    fn create_air_vec(&self, air_vec: &mut Vec<(usize, Air)>, depth: usize) {
         match self {
             AirTree::Let { name, value, then } => {
-                air_vec.push(Air::Let { name: name.clone() });
-                value.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((depth, Air::Let { name: name.clone() }));
+                value.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
             }
             AirTree::SoftCastLet {
                 name,
@@ -864,13 +872,16 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::SoftCastLet {
-                    name: name.clone(),
-                    tipo: tipo.clone(),
-                });
-                value.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::SoftCastLet {
+                        name: name.clone(),
+                        tipo: tipo.clone(),
+                    },
+                ));
+                value.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::DefineFunc {
                 func_name,
@@ -892,14 +903,17 @@ impl AirTree {
                     FunctionVariants::Standard(params.clone())
                 };
 
-                air_vec.push(Air::DefineFunc {
-                    func_name: func_name.clone(),
-                    module_name: module_name.clone(),
-                    variant_name: variant_name.clone(),
-                    variant,
-                });
-                func_body.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::DefineFunc {
+                        func_name: func_name.clone(),
+                        module_name: module_name.clone(),
+                        variant_name: variant_name.clone(),
+                        variant,
+                    },
+                ));
+                func_body.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
             }
             AirTree::DefineCyclicFuncs {
                 func_name,
@@ -915,17 +929,20 @@ impl AirTree {
                         .collect_vec(),
                 );
 
-                air_vec.push(Air::DefineFunc {
-                    func_name: func_name.clone(),
-                    module_name: module_name.clone(),
-                    variant_name: variant_name.clone(),
-                    variant,
-                });
+                air_vec.push((
+                    depth,
+                    Air::DefineFunc {
+                        func_name: func_name.clone(),
+                        module_name: module_name.clone(),
+                        variant_name: variant_name.clone(),
+                        variant,
+                    },
+                ));
 
                 for (_, func_body) in contained_functions {
-                    func_body.create_air_vec(air_vec);
+                    func_body.create_air_vec(air_vec, depth + 1);
                 }
-                then.create_air_vec(air_vec);
+                then.create_air_vec(air_vec, depth + 1);
             }
             AirTree::AssertBool {
                 is_true,
@@ -933,11 +950,11 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::AssertBool { is_true: *is_true });
+                air_vec.push((depth, Air::AssertBool { is_true: *is_true }));
 
-                value.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                value.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::FieldsExpose {
                 indices,
@@ -946,15 +963,18 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::FieldsExpose {
-                    indices: indices.clone(),
-                    is_expect: *is_expect,
-                });
-
-                record.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::FieldsExpose {
+                        indices: indices.clone(),
+                        is_expect: *is_expect,
+                    },
+                ));
+
+                record.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
                 if *is_expect {
-                    otherwise.create_air_vec(air_vec);
+                    otherwise.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::ListAccessor {
@@ -966,17 +986,20 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::ListAccessor {
-                    tipo: tipo.clone(),
-                    names: names.clone(),
-                    tail: *tail,
-                    expect_level: *expect_level,
-                });
-
-                list.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::ListAccessor {
+                        tipo: tipo.clone(),
+                        names: names.clone(),
+                        tail: *tail,
+                        expect_level: *expect_level,
+                    },
+                ));
+
+                list.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
                 if matches!(expect_level, ExpectLevel::Full | ExpectLevel::Items) {
-                    otherwise.create_air_vec(air_vec);
+                    otherwise.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::TupleAccessor {
@@ -987,16 +1010,19 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::TupleAccessor {
-                    names: names.clone(),
-                    tipo: tipo.clone(),
-                    is_expect: *is_expect,
-                });
-
-                tuple.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::TupleAccessor {
+                        names: names.clone(),
+                        tipo: tipo.clone(),
+                        is_expect: *is_expect,
+                    },
+                ));
+
+                tuple.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
                 if *is_expect {
-                    otherwise.create_air_vec(air_vec);
+                    otherwise.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::PairAccessor {
@@ -1008,17 +1034,20 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::PairAccessor {
-                    fst: fst.clone(),
-                    snd: snd.clone(),
-                    tipo: tipo.clone(),
-                    is_expect: *is_expect,
-                });
-
-                pair.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::PairAccessor {
+                        fst: fst.clone(),
+                        snd: snd.clone(),
+                        tipo: tipo.clone(),
+                        is_expect: *is_expect,
+                    },
+                ));
+
+                pair.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
                 if *is_expect {
-                    otherwise.create_air_vec(air_vec);
+                    otherwise.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::FieldsEmpty {
@@ -1026,81 +1055,104 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::FieldsEmpty);
+                air_vec.push((depth, Air::FieldsEmpty));
 
-                constr.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                constr.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::ListEmpty {
                 list,
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::ListEmpty);
+                air_vec.push((depth, Air::ListEmpty));
 
-                list.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                list.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::NoOp { then } => {
-                air_vec.push(Air::NoOp);
-                then.create_air_vec(air_vec);
-            }
-            AirTree::Int { value } => air_vec.push(Air::Int {
-                value: value.clone(),
-            }),
-
-            AirTree::String { value } => air_vec.push(Air::String {
-                value: value.clone(),
-            }),
-            AirTree::ByteArray { bytes } => air_vec.push(Air::ByteArray {
-                bytes: bytes.clone(),
-            }),
-            AirTree::CurvePoint { point } => air_vec.push(Air::CurvePoint { point: *point }),
-            AirTree::Bool { value } => air_vec.push(Air::Bool { value: *value }),
+                air_vec.push((depth, Air::NoOp));
+                then.create_air_vec(air_vec, depth + 1);
+            }
+            AirTree::Int { value } => air_vec.push((
+                depth,
+                Air::Int {
+                    value: value.clone(),
+                },
+            )),
+
+            AirTree::String { value } => air_vec.push((
+                depth,
+                Air::String {
+                    value: value.clone(),
+                },
+            )),
+            AirTree::ByteArray { bytes } => air_vec.push((
+                depth,
+                Air::ByteArray {
+                    bytes: bytes.clone(),
+                },
+            )),
+            AirTree::CurvePoint { point } => {
+                air_vec.push((depth, Air::CurvePoint { point: *point }))
+            }
+            AirTree::Bool { value } => air_vec.push((depth, Air::Bool { value: *value })),
             AirTree::List { tipo, tail, items } => {
-                air_vec.push(Air::List {
-                    count: items.len(),
-                    tipo: tipo.clone(),
-                    tail: *tail,
-                });
+                air_vec.push((
+                    depth,
+                    Air::List {
+                        count: items.len(),
+                        tipo: tipo.clone(),
+                        tail: *tail,
+                    },
+                ));
                 for item in items {
-                    item.create_air_vec(air_vec);
+                    item.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::Tuple { tipo, items } => {
-                air_vec.push(Air::Tuple {
-                    tipo: tipo.clone(),
-                    count: items.len(),
-                });
+                air_vec.push((
+                    depth,
+                    Air::Tuple {
+                        tipo: tipo.clone(),
+                        count: items.len(),
+                    },
+                ));
                 for item in items {
-                    item.create_air_vec(air_vec);
+                    item.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::Pair { tipo, fst, snd } => {
-                air_vec.push(Air::Pair { tipo: tipo.clone() });
-                fst.create_air_vec(air_vec);
-                snd.create_air_vec(air_vec);
+                air_vec.push((depth, Air::Pair { tipo: tipo.clone() }));
+                fst.create_air_vec(air_vec, depth + 1);
+                snd.create_air_vec(air_vec, depth + 1);
             }
-            AirTree::Void => air_vec.push(Air::Void),
+            AirTree::Void => air_vec.push((depth, Air::Void)),
             AirTree::Var {
                 constructor,
                 name,
                 variant_name,
-            } => air_vec.push(Air::Var {
-                constructor: constructor.clone(),
-                name: name.clone(),
-                variant_name: variant_name.clone(),
-            }),
+            } => air_vec.push((
+                depth,
+                Air::Var {
+                    constructor: constructor.clone(),
+                    name: name.clone(),
+                    variant_name: variant_name.clone(),
+                },
+            )),
             AirTree::Call { tipo, func, args } => {
-                air_vec.push(Air::Call {
-                    count: args.len(),
-                    tipo: tipo.clone(),
-                });
-                func.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::Call {
+                        count: args.len(),
+                        tipo: tipo.clone(),
+                    },
+                ));
+                func.create_air_vec(air_vec, depth + 1);
                 for arg in args {
-                    arg.create_air_vec(air_vec);
+                    arg.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::Fn {
@@ -1108,21 +1160,27 @@ impl AirTree {
                 func_body,
                 allow_inline,
             } => {
-                air_vec.push(Air::Fn {
-                    params: params.clone(),
-                    allow_inline: *allow_inline,
-                });
-                func_body.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::Fn {
+                        params: params.clone(),
+                        allow_inline: *allow_inline,
+                    },
+                ));
+                func_body.create_air_vec(air_vec, depth + 1);
             }
             AirTree::Builtin { func, tipo, args } => {
-                air_vec.push(Air::Builtin {
-                    count: args.len(),
-                    func: *func,
-                    tipo: tipo.clone(),
-                });
+                air_vec.push((
+                    depth,
+                    Air::Builtin {
+                        count: args.len(),
+                        func: *func,
+                        tipo: tipo.clone(),
+                    },
+                ));
 
                 for arg in args {
-                    arg.create_air_vec(air_vec);
+                    arg.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::BinOp {
@@ -1132,33 +1190,39 @@ impl AirTree {
                 right,
                 argument_tipo,
             } => {
-                air_vec.push(Air::BinOp {
-                    name: *name,
-                    tipo: tipo.clone(),
-                    argument_tipo: argument_tipo.clone(),
-                });
-                left.create_air_vec(air_vec);
-                right.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::BinOp {
+                        name: *name,
+                        tipo: tipo.clone(),
+                        argument_tipo: argument_tipo.clone(),
+                    },
+                ));
+                left.create_air_vec(air_vec, depth + 1);
+                right.create_air_vec(air_vec, depth + 1);
             }
             AirTree::UnOp { op, arg } => {
-                air_vec.push(Air::UnOp { op: *op });
-                arg.create_air_vec(air_vec);
+                air_vec.push((depth, Air::UnOp { op: *op }));
+                arg.create_air_vec(air_vec, depth + 1);
             }
             AirTree::CastFromData {
                 tipo,
                 value,
                 full_cast,
             } => {
-                air_vec.push(Air::CastFromData {
-                    tipo: tipo.clone(),
-                    full_cast: *full_cast,
-                });
+                air_vec.push((
+                    depth,
+                    Air::CastFromData {
+                        tipo: tipo.clone(),
+                        full_cast: *full_cast,
+                    },
+                ));
 
-                value.create_air_vec(air_vec);
+                value.create_air_vec(air_vec, depth + 1);
             }
             AirTree::CastToData { tipo, value } => {
-                air_vec.push(Air::CastToData { tipo: tipo.clone() });
-                value.create_air_vec(air_vec);
+                air_vec.push((depth, Air::CastToData { tipo: tipo.clone() }));
+                value.create_air_vec(air_vec, depth + 1);
             }
             AirTree::When {
                 tipo,
@@ -1167,13 +1231,16 @@ impl AirTree {
                 subject_tipo,
                 clauses,
             } => {
-                air_vec.push(Air::When {
-                    tipo: tipo.clone(),
-                    subject_name: subject_name.clone(),
-                    subject_tipo: subject_tipo.clone(),
-                });
-                subject.create_air_vec(air_vec);
-                clauses.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::When {
+                        tipo: tipo.clone(),
+                        subject_name: subject_name.clone(),
+                        subject_tipo: subject_tipo.clone(),
+                    },
+                ));
+                subject.create_air_vec(air_vec, depth + 1);
+                clauses.create_air_vec(air_vec, depth + 1);
             }
             AirTree::Clause {
                 subject_tipo,
@@ -1182,13 +1249,16 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::Clause {
-                    subject_tipo: subject_tipo.clone(),
-                    subject_name: subject_name.clone(),
-                });
-                pattern.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::Clause {
+                        subject_tipo: subject_tipo.clone(),
+                        subject_name: subject_name.clone(),
+                    },
+                ));
+                pattern.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::ListClause {
                 subject_tipo,
@@ -1198,13 +1268,16 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::ListClause {
-                    subject_tipo: subject_tipo.clone(),
-                    tail_name: tail_name.clone(),
-                    next_tail_name: next_tail_name.clone(),
-                });
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::ListClause {
+                        subject_tipo: subject_tipo.clone(),
+                        tail_name: tail_name.clone(),
+                        next_tail_name: next_tail_name.clone(),
+                    },
+                ));
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::If {
                 tipo,
@@ -1212,19 +1285,22 @@ impl AirTree {
                 then,
                 otherwise,
             } => {
-                air_vec.push(Air::If { tipo: tipo.clone() });
-                pattern.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
-                otherwise.create_air_vec(air_vec);
+                air_vec.push((depth, Air::If { tipo: tipo.clone() }));
+                pattern.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
+                otherwise.create_air_vec(air_vec, depth + 1);
             }
             AirTree::Constr { tag, tipo, args } => {
-                air_vec.push(Air::Constr {
-                    tag: *tag,
-                    tipo: tipo.clone(),
-                    count: args.len(),
-                });
+                air_vec.push((
+                    depth,
+                    Air::Constr {
+                        tag: *tag,
+                        tipo: tipo.clone(),
+                        count: args.len(),
+                    },
+                ));
                 for arg in args {
-                    arg.create_air_vec(air_vec);
+                    arg.create_air_vec(air_vec, depth + 1);
                 }
             }
             AirTree::RecordUpdate {
@@ -1234,31 +1310,37 @@ impl AirTree {
                 record,
                 args,
             } => {
-                air_vec.push(Air::RecordUpdate {
-                    highest_index: *highest_index,
-                    indices: indices.clone(),
-                    tipo: tipo.clone(),
-                });
-                record.create_air_vec(air_vec);
+                air_vec.push((
+                    depth,
+                    Air::RecordUpdate {
+                        highest_index: *highest_index,
+                        indices: indices.clone(),
+                        tipo: tipo.clone(),
+                    },
+                ));
+                record.create_air_vec(air_vec, depth + 1);
                 for arg in args {
-                    arg.create_air_vec(air_vec);
+                    arg.create_air_vec(air_vec, depth + 1);
                 }
             }
-            AirTree::ErrorTerm { tipo, validator } => air_vec.push(Air::ErrorTerm {
-                tipo: tipo.clone(),
-                validator: *validator,
-            }),
+            AirTree::ErrorTerm { tipo, validator } => air_vec.push((
+                depth,
+                Air::ErrorTerm {
+                    tipo: tipo.clone(),
+                    validator: *validator,
+                },
+            )),
             AirTree::Trace { tipo, msg, then } => {
-                air_vec.push(Air::Trace { tipo: tipo.clone() });
-                msg.create_air_vec(air_vec);
-                then.create_air_vec(air_vec);
+                air_vec.push((depth, Air::Trace { tipo: tipo.clone() }));
+                msg.create_air_vec(air_vec, depth + 1);
+                then.create_air_vec(air_vec, depth + 1);
             }
             AirTree::ExtractField {
                 tipo,
                 arg: args_list,
             } => {
-                air_vec.push(Air::ExtractField { tipo: tipo.clone() });
-                args_list.create_air_vec(air_vec);
+                air_vec.push((depth, Air::ExtractField { tipo: tipo.clone() }));
+                args_list.create_air_vec(air_vec, depth + 1);
             }
         }
     }
@@ -0,0 +1,327 @@
+//! Editor grammars, generated from the lexer's own token definitions so that syntax
+//! highlighting can't drift away from what the compiler actually accepts.
+
+use crate::parser::token::{Token, KEYWORDS, OPERATORS};
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The semantic token types reported by the language server, in legend order: clients receive
+/// tokens as indexes into this list.
+pub const SEMANTIC_TOKEN_TYPES: &[&str] = &[
+    "namespace",
+    "type",
+    "typeParameter",
+    "enumMember",
+    "function",
+    "parameter",
+    "variable",
+    "property",
+];
+
+/// The semantic token modifiers reported by the language server, in legend order: clients
+/// receive modifiers as a bitset over this list.
+pub const SEMANTIC_TOKEN_MODIFIERS: &[&str] = &["declaration", "readonly", "defaultLibrary"];
+
+/// A semantic tokens legend, shaped as expected by LSP clients.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Legend {
+    pub token_types: Vec<&'static str>,
+    pub token_modifiers: Vec<&'static str>,
+}
+
+pub fn legend() -> Legend {
+    Legend {
+        token_types: SEMANTIC_TOKEN_TYPES.to_vec(),
+        token_modifiers: SEMANTIC_TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// A TextMate grammar, as understood by VSCode and most other editors.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextMate {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub name: &'static str,
+    pub scope_name: &'static str,
+    pub file_types: Vec<&'static str>,
+    pub patterns: Vec<Rule>,
+    pub repository: BTreeMap<&'static str, Rule>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Rule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub matches: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub begin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<Rule>,
+}
+
+impl Rule {
+    fn include(key: &str) -> Self {
+        Rule {
+            include: Some(format!("#{key}")),
+            ..Rule::default()
+        }
+    }
+
+    fn matches(name: &str, regex: impl Into<String>) -> Self {
+        Rule {
+            name: Some(name.to_string()),
+            matches: Some(regex.into()),
+            ..Rule::default()
+        }
+    }
+
+    fn region(name: &str, begin: &str, end: &str, patterns: Vec<Rule>) -> Self {
+        Rule {
+            name: Some(name.to_string()),
+            begin: Some(begin.to_string()),
+            end: Some(end.to_string()),
+            patterns,
+            ..Rule::default()
+        }
+    }
+
+    fn group(patterns: Vec<Rule>) -> Self {
+        Rule {
+            patterns,
+            ..Rule::default()
+        }
+    }
+}
+
+pub fn textmate() -> TextMate {
+    // Order matters: the first matching rule wins, so comments must come before operators
+    // (because of '/') and strings before bytestrings and attributes (because of '@').
+    let repository = BTreeMap::from([
+        (
+            "comments",
+            Rule::group(vec![
+                Rule::matches("comment.line.documentation.module.aiken", "////.*$"),
+                Rule::matches("comment.line.documentation.aiken", "///.*$"),
+                Rule::matches("comment.line.double-slash.aiken", "//.*$"),
+            ]),
+        ),
+        (
+            "strings",
+            Rule::group(vec![
+                Rule::region(
+                    "string.quoted.double.aiken",
+                    "@\"",
+                    "\"",
+                    vec![Rule::include("escapes")],
+                ),
+                Rule::region(
+                    "string.quoted.double.bytearray.aiken",
+                    "#?\"",
+                    "\"",
+                    vec![Rule::include("escapes")],
+                ),
+            ]),
+        ),
+        (
+            "attributes",
+            Rule::matches("storage.modifier.attribute.aiken", r"@[a-z_][a-z0-9_]*\b"),
+        ),
+        (
+            // Mirrors the escape sequences recognised by the lexer.
+            "escapes",
+            Rule::matches("constant.character.escape.aiken", r#"\\[\\"nrt0]"#),
+        ),
+        (
+            "numbers",
+            Rule::group(vec![
+                Rule::matches("constant.numeric.hex.aiken", r"\b0x[0-9a-fA-F]+\b"),
+                Rule::matches("constant.numeric.ordinal.aiken", r"\b[0-9]+(st|nd|rd|th)\b"),
+                Rule::matches("constant.numeric.integer.aiken", r"\b[0-9][0-9_]*\b"),
+            ]),
+        ),
+        ("keywords", Rule::group(keyword_rules())),
+        ("operators", Rule::group(operator_rules())),
+        (
+            "names",
+            Rule::group(vec![
+                Rule::matches("entity.name.type.aiken", r"\b[A-Z][a-zA-Z0-9_]*\b"),
+                Rule::matches("entity.name.function.aiken", r"\b[a-z_][a-z0-9_]*(?=\s*\()"),
+                Rule::matches("entity.name.namespace.aiken", r"\b[a-z_][a-z0-9_]*(?=\.)"),
+                Rule::matches("variable.other.aiken", r"\b[a-z_][a-z0-9_]*\b"),
+            ]),
+        ),
+    ]);
+
+    TextMate {
+        schema: "https://raw.githubusercontent.com/martinring/tmlanguage/master/tmlanguage.json",
+        name: "aiken",
+        scope_name: "source.aiken",
+        file_types: vec!["ak"],
+        patterns: [
+            "comments",
+            "strings",
+            "attributes",
+            "numbers",
+            "keywords",
+            "operators",
+            "names",
+        ]
+        .into_iter()
+        .map(Rule::include)
+        .collect(),
+        repository,
+    }
+}
+
+/// The TextMate scope of a keyword token.
+fn keyword_scope(token: &Token) -> &'static str {
+    match token {
+        Token::If | Token::Else | Token::When | Token::Is | Token::Expect => {
+            "keyword.control.aiken"
+        }
+        Token::Fail | Token::Todo | Token::Trace | Token::Once => "keyword.control.flow.aiken",
+        Token::And | Token::Or => "keyword.operator.logical.aiken",
+        Token::Fn
+        | Token::Const
+        | Token::Type
+        | Token::Test
+        | Token::Benchmark
+        | Token::Validator
+        | Token::Let => "storage.type.aiken",
+        Token::Pub | Token::Opaque => "storage.modifier.aiken",
+        _ => "keyword.other.aiken",
+    }
+}
+
+/// The TextMate scope of an operator (or punctuation) token.
+fn operator_scope(token: &Token) -> &'static str {
+    match token {
+        Token::Comma => "punctuation.separator.comma.aiken",
+        Token::Colon => "punctuation.separator.colon.aiken",
+        Token::Dot | Token::DotDot => "punctuation.accessor.aiken",
+        Token::Hash => "punctuation.definition.bytearray.aiken",
+        Token::RArrow | Token::LArrow => "keyword.operator.arrow.aiken",
+        Token::Pipe => "keyword.operator.pipe.aiken",
+        Token::EqualEqual
+        | Token::NotEqual
+        | Token::Less
+        | Token::LessEqual
+        | Token::Greater
+        | Token::GreaterEqual => "keyword.operator.comparison.aiken",
+        Token::AmperAmper | Token::VbarVbar | Token::Bang | Token::Question => {
+            "keyword.operator.logical.aiken"
+        }
+        Token::Equal => "keyword.operator.assignment.aiken",
+        _ => "keyword.operator.aiken",
+    }
+}
+
+fn keyword_rules() -> Vec<Rule> {
+    KEYWORDS
+        .iter()
+        .into_group_map_by(|(_, token)| keyword_scope(token))
+        .into_iter()
+        .sorted_by_key(|(scope, _)| *scope)
+        .map(|(scope, keywords)| {
+            let alternatives = keywords.iter().map(|(keyword, _)| *keyword).join("|");
+            Rule::matches(scope, format!(r"\b({alternatives})\b"))
+        })
+        .collect()
+}
+
+fn operator_rules() -> Vec<Rule> {
+    // One rule per operator, longest first: TextMate picks the first rule matching at a given
+    // position, so an operator must never come after one of its prefixes (e.g. '|' and '|>'),
+    // even when they belong to different scopes.
+    OPERATORS
+        .iter()
+        .sorted_by_key(|(op, _)| std::cmp::Reverse(op.len()))
+        .map(|(op, token)| Rule::matches(operator_scope(token), escape(op)))
+        .collect()
+}
+
+/// Escape regex metacharacters. Only those: escaping e.g. '<' would turn it into a word
+/// boundary for some regex engines.
+fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if r"\.+*?()|[]{}^$#&-~".contains(c) {
+                format!(r"\{c}")
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keyword_scope, operator_scope, textmate, Rule};
+    use crate::parser::token::{KEYWORDS, OPERATORS};
+    use regex::Regex;
+
+    /// The scope and regex of every 'match' rule under the given one, in the order an editor
+    /// tries them.
+    fn match_rules(rule: &Rule) -> Vec<(String, Regex)> {
+        rule.matches
+            .iter()
+            .map(|regex| {
+                (
+                    rule.name.clone().unwrap_or_default(),
+                    Regex::new(&format!("^(?:{regex})")).unwrap(),
+                )
+            })
+            .chain(rule.patterns.iter().flat_map(match_rules))
+            .collect()
+    }
+
+    /// What an editor highlights at the start of 'text': the scope of the first rule matching
+    /// there, and the text it matches.
+    fn highlight<'a>(rules: &[(String, Regex)], text: &'a str) -> Option<(String, &'a str)> {
+        rules.iter().find_map(|(scope, regex)| {
+            regex
+                .find(text)
+                .map(|found| (scope.clone(), found.as_str()))
+        })
+    }
+
+    #[test]
+    fn keywords_follow_the_lexer() {
+        let rules = match_rules(&textmate().repository["keywords"]);
+
+        for (keyword, token) in KEYWORDS {
+            assert_eq!(
+                highlight(&rules, keyword),
+                Some((keyword_scope(token).to_string(), *keyword)),
+                "keyword {keyword}"
+            );
+        }
+
+        // Only whole words are keywords.
+        assert_eq!(highlight(&rules, "iffy"), None);
+        assert_eq!(highlight(&rules, "letter"), None);
+    }
+
+    #[test]
+    fn operators_follow_the_lexer() {
+        let rules = match_rules(&textmate().repository["operators"]);
+
+        for (op, token) in OPERATORS {
+            assert_eq!(
+                highlight(&rules, op),
+                Some((operator_scope(token).to_string(), *op)),
+                "operator {op}"
+            );
+        }
+
+        assert_eq!(rules.len(), OPERATORS.len());
+    }
+}
@@ -9,6 +9,7 @@ pub mod error;
 pub mod expr;
 pub mod format;
 pub mod gen_uplc;
+pub mod grammar;
 pub mod levenshtein;
 pub mod line_numbers;
 pub mod parser;
@@ -32,6 +33,12 @@ impl IdGenerator {
     pub fn next(&self) -> u64 {
         self.id.fetch_add(1, Ordering::Relaxed)
     }
+
+    /// Make sure that ids generated from now on are greater than the given one; for example,
+    /// past the ids found in modules type-checked by another generator.
+    pub fn skip_past(&self, id: u64) {
+        self.id.fetch_max(id + 1, Ordering::Relaxed);
+    }
 }
 
 #[macro_export]
@@ -51,6 +58,7 @@ macro_rules! aiken_fn {
                 module_name,
                 $module_types,
                 $crate::ast::Tracing::silent(),
+                $crate::ast::TraceSeverity::Debug,
                 &mut warnings,
                 None,
             )
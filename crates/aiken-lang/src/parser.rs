@@ -1,7 +1,9 @@
 mod annotation;
 pub mod chain;
+mod conditional;
 pub mod definition;
 pub mod error;
+mod experimental;
 pub mod expr;
 pub mod extra;
 pub mod lexer;
@@ -13,8 +15,10 @@ mod utils;
 use crate::{ast, line_numbers::LineNumbers};
 pub use annotation::parser as annotation;
 use chumsky::prelude::*;
+use conditional::parser as conditional;
 pub use definition::{import::parser as import, parser as definition};
 use error::ParseError;
+pub use experimental::parser as experimental;
 pub use expr::parser as expression;
 use extra::ModuleExtra;
 use indexmap::IndexMap;
@@ -34,7 +38,7 @@ pub fn module(
             let mut store = IndexMap::new();
 
             for import in imports.into_iter() {
-                let key = (import.module, import.as_name);
+                let key = (import.module, import.as_name, import.public);
                 match store.remove(&key) {
                     None => {
                         store.insert(key, (import.location, import.unqualified));
@@ -50,24 +54,39 @@ pub fn module(
 
             store
                 .into_iter()
-                .map(|((module, as_name), (location, unqualified))| {
+                .map(|((module, as_name, public), (location, unqualified))| {
                     ast::Definition::Use(ast::Use {
                         module,
                         as_name,
                         location,
                         unqualified,
                         package: (),
+                        public,
+                        implicit: false,
                     })
                 })
                 .collect::<Vec<ast::UntypedDefinition>>()
         })
-        .then(definition().repeated())
+        .then(conditional().repeated().then(definition()).repeated())
         .map(|(imports, others)| {
             let mut defs = Vec::new();
+            let mut conditionals = Vec::new();
             defs.extend(imports);
-            defs.extend(others);
-            defs
-        })
+            for (cfgs, def) in others {
+                let definition = def.location().start;
+                conditionals.extend(cfgs.into_iter().map(|(cfg, location)| ast::Conditional {
+                    location,
+                    cfg,
+                    definition,
+                }));
+                defs.push(def);
+            }
+            (defs, conditionals)
+        });
+
+    let (experimental, (definitions, conditionals)) = experimental()
+        .repeated()
+        .then(definitions)
         .then_ignore(end())
         .parse(stream)?;
 
@@ -78,6 +97,9 @@ pub fn module(
         lines,
         definitions,
         docs: vec![],
+        experimental: experimental.into_iter().flatten().collect(),
+        conditionals,
+        implicit_prelude: true,
         name: "".to_string(),
         type_info: (),
     };
@@ -87,7 +109,8 @@ pub fn module(
 
 #[cfg(test)]
 mod tests {
-    use crate::assert_module;
+    use super::error::{ErrorKind, ParseError};
+    use crate::{assert_module, ast::ModuleKind};
 
     #[test]
     fn merge_imports() {
@@ -164,4 +187,17 @@ mod tests {
             "#
         );
     }
+
+    #[test]
+    fn unknown_experimental_feature() {
+        let errors = super::module(r#"@experimental("telepathy")"#, ModuleKind::Lib).unwrap_err();
+
+        assert!(matches!(
+            errors.as_slice(),
+            [ParseError {
+                kind: ErrorKind::UnknownExperimentalFeature { name },
+                ..
+            }] if name == "telepathy"
+        ));
+    }
 }
@@ -0,0 +1,24 @@
+use super::{error::ParseError, token::Token};
+use crate::ast;
+use chumsky::prelude::*;
+
+/// A definition attribute restricting when it is compiled, e.g. `@cfg(test)` or
+/// `@cfg(env = "mainnet")`.
+pub fn parser() -> impl Parser<Token, (ast::Cfg, ast::Span), Error = ParseError> {
+    just(Token::At)
+        .ignore_then(select! {Token::Name { name } if name == "cfg" => name})
+        .ignore_then(
+            choice((
+                just(Token::Test).to(ast::Cfg::Test),
+                select! {Token::Name { name } if name == "env" => name}
+                    .ignore_then(just(Token::Equal))
+                    .ignore_then(select! {Token::ByteString { value } => value})
+                    .map(ast::Cfg::Env),
+            ))
+            .delimited_by(
+                choice((just(Token::LeftParen), just(Token::NewLineLeftParen))),
+                just(Token::RightParen),
+            ),
+        )
+        .map_with_span(|cfg, span| (cfg, span))
+}
@@ -1,10 +1,43 @@
 use crate::{
-    ast,
+    ast::{self, ByteArrayFormatPreference},
+    expr::UntypedExpr,
     parser::{annotation, error::ParseError, expr::pure_expression, token::Token, utils},
 };
 use chumsky::prelude::*;
 
 pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError> {
+    let value = just(Token::Equal).ignore_then(recursive(|sequence| {
+        recursive(|expression| pure_expression(sequence.clone(), expression))
+            .then(sequence.repeated())
+            .foldl(|current, next| current.append_in_sequence(next))
+    }));
+
+    choice((
+        fixture().then(head()).map_with_span(|(file, head), span| {
+            let value = UntypedExpr::ByteArray {
+                location: span,
+                bytes: vec![],
+                preferred_format: ByteArrayFormatPreference::HexadecimalString,
+            };
+            (head, value, Some(file))
+        }),
+        head().then(value).map(|(head, value)| (head, value, None)),
+    ))
+    .map_with_span(|(((public, name), annotation), value, fixture), span| {
+        ast::UntypedDefinition::ModuleConstant(ast::ModuleConstant {
+            doc: None,
+            location: span,
+            public,
+            name,
+            annotation,
+            value,
+            fixture,
+        })
+    })
+}
+
+#[allow(clippy::type_complexity)]
+fn head() -> impl Parser<Token, ((bool, String), Option<ast::Annotation>), Error = ParseError> {
     utils::optional_flag(Token::Pub)
         .then_ignore(just(Token::Const))
         .then(select! {Token::Name{name} => name})
@@ -13,22 +46,16 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
                 .ignore_then(annotation::parser())
                 .or_not(),
         )
-        .then_ignore(just(Token::Equal))
-        .then(recursive(|sequence| {
-            recursive(|expression| pure_expression(sequence.clone(), expression))
-                .then(sequence.repeated())
-                .foldl(|current, next| current.append_in_sequence(next))
-        }))
-        .map_with_span(|(((public, name), annotation), value), span| {
-            ast::UntypedDefinition::ModuleConstant(ast::ModuleConstant {
-                doc: None,
-                location: span,
-                public,
-                name,
-                annotation,
-                value,
-            })
-        })
+}
+
+/// An attribute loading the value of a constant from a file, e.g. `@fixture("tx.cbor")`.
+fn fixture() -> impl Parser<Token, String, Error = ParseError> {
+    just(Token::At)
+        .ignore_then(select! {Token::Name { name } if name == "fixture" => name})
+        .ignore_then(select! {Token::ByteString { value } => value}.delimited_by(
+            choice((just(Token::LeftParen), just(Token::NewLineLeftParen))),
+            just(Token::RightParen),
+        ))
 }
 
 #[cfg(test)]
@@ -45,6 +72,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fixture_constant() {
+        assert_definition!(
+            r#"
+            @fixture("tx.cbor")
+            pub const tx: ByteArray
+            "#
+        );
+    }
+
     #[test]
     fn g2_element_constant() {
         assert_definition!(
@@ -1,7 +1,7 @@
 use crate::{
     ast,
     expr::UntypedExpr,
-    parser::{annotation, error::ParseError, expr, pattern, token::Token, utils},
+    parser::{annotation, error::ParseError, expr, literal, pattern, token::Token, utils},
 };
 use chumsky::prelude::*;
 
@@ -11,6 +11,8 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
         .then(select! {Token::Name {name} => name})
         .then(
             param(false)
+                .then(just(Token::Equal).ignore_then(default_value()).or_not())
+                .map(|(arg, default)| ast::UntypedArg { default, ..arg })
                 .separated_by(just(Token::Comma))
                 .allow_trailing()
                 .delimited_by(just(Token::LeftParen), just(Token::RightParen))
@@ -41,6 +43,7 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
                     return_annotation,
                     return_type: (),
                     on_test_failure: ast::OnTestFailure::FailImmediately,
+                    example: None,
                 })
             },
         )
@@ -88,10 +91,28 @@ pub fn param(is_validator_param: bool) -> impl Parser<Token, ast::UntypedArg, Er
         annotation,
         doc: None,
         is_validator_param,
+        default: None,
         by,
     })
 }
 
+pub fn default_value() -> impl Parser<Token, ast::DefaultValue, Error = ParseError> {
+    choice((
+        literal::int().map_with_span(|(value, base), location| ast::DefaultValue::Int {
+            location,
+            value,
+            base,
+        }),
+        literal::bytearray(|bytes, preferred_format, _curve, location, _emit| {
+            ast::DefaultValue::ByteArray {
+                location,
+                bytes,
+                preferred_format,
+            }
+        }),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::assert_definition;
@@ -41,15 +41,21 @@ pub fn parser() -> impl Parser<Token, ast::UntypedUse, Error = ParseError> {
         .then(unqualified_imports)
         .then(as_name);
 
-    just(Token::Use).ignore_then(module_path).map_with_span(
-        |((module, unqualified), as_name), span| ast::Use {
-            module,
-            as_name,
-            unqualified: unqualified.unwrap_or_default(),
-            package: (),
-            location: span,
-        },
-    )
+    just(Token::Pub)
+        .or_not()
+        .then_ignore(just(Token::Use))
+        .then(module_path)
+        .map_with_span(
+            |(public, ((module, unqualified), as_name)), span| ast::Use {
+                module,
+                as_name,
+                unqualified: unqualified.unwrap_or_default(),
+                package: (),
+                public: public.is_some(),
+                implicit: false,
+                location: span,
+            },
+        )
 }
 
 #[cfg(test)]
@@ -74,4 +80,9 @@ mod tests {
     fn import_alias() {
         assert_import!("use aiken/list as foo");
     }
+
+    #[test]
+    fn import_public() {
+        assert_import!("pub use aiken/list.{map, Thing}");
+    }
 }
@@ -5,7 +5,25 @@ use crate::{
 use chumsky::prelude::*;
 
 pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError> {
-    crate::parser::definition::test_like::parser(Token::Test)
+    example()
+        .or_not()
+        .then(crate::parser::definition::test_like::parser(Token::Test))
+        .map(|(example, mut definition)| {
+            if let ast::UntypedDefinition::Test(test) = &mut definition {
+                test.example = example;
+            }
+            definition
+        })
+}
+
+/// An attribute marking a test as an example of a function, e.g. `@example(map)`.
+fn example() -> impl Parser<Token, String, Error = ParseError> {
+    just(Token::At)
+        .ignore_then(select! {Token::Name { name } if name == "example" => name})
+        .ignore_then(select! {Token::Name { name } => name}.delimited_by(
+            choice((just(Token::LeftParen), just(Token::NewLineLeftParen))),
+            just(Token::RightParen),
+        ))
 }
 
 #[cfg(test)]
@@ -23,6 +41,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn def_test_example() {
+        assert_definition!(
+            r#"
+            @example(foo)
+            test foo_twice() {
+                True
+            }
+            "#
+        );
+    }
+
     #[test]
     fn def_test_fail() {
         assert_definition!(
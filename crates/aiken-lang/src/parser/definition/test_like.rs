@@ -49,6 +49,7 @@ pub fn parser(keyword: Token) -> impl Parser<Token, ast::UntypedDefinition, Erro
                     return_annotation: None,
                     return_type: (),
                     on_test_failure: fail.unwrap_or(OnTestFailure::FailImmediately),
+                    example: None,
                 }),
                 Token::Benchmark => ast::UntypedDefinition::Benchmark(ast::Function {
                     arguments,
@@ -61,6 +62,7 @@ pub fn parser(keyword: Token) -> impl Parser<Token, ast::UntypedDefinition, Erro
                     return_annotation: None,
                     return_type: (),
                     on_test_failure: fail.unwrap_or(OnTestFailure::FailImmediately),
+                    example: None,
                 }),
                 _ => unreachable!("Only Test and Benchmark tokens are supported"),
             },
@@ -96,6 +98,7 @@ pub fn via() -> impl Parser<Token, ast::UntypedArgVia, Error = ParseError> {
             location,
             doc: None,
             is_validator_param: false,
+            default: None,
         },
         via,
     })
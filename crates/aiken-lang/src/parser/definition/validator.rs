@@ -20,14 +20,25 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
         // so far: validator my_validator(arg1: Whatever)
         .then(
             select! {Token::Name {name} => name}
+                .separated_by(just(Token::Vbar))
+                .at_least(1)
                 .then(args_and_body())
-                .map_with_span(|(name, mut function), span| {
-                    function.name = name;
-                    function.location.start = span.start;
-
-                    function
+                // A handler may be exported under several purposes at once (e.g. 'mint | withdraw'),
+                // in which case it is declared once per purpose, all sharing the same location. The
+                // type-checker and code generator rely on it to check and compile the body once.
+                .map_with_span(|(names, function), span| {
+                    names
+                        .into_iter()
+                        .map(|name| {
+                            let mut function = function.clone();
+                            function.name = name;
+                            function.location.start = span.start;
+                            function
+                        })
+                        .collect::<Vec<_>>()
                 })
                 .repeated()
+                .flatten()
                 .then(
                     just(Token::Else)
                         .ignore_then(args_and_body().map_with_span(|mut function, span| {
@@ -104,6 +115,7 @@ pub fn args_and_body() -> impl Parser<Token, ast::UntypedFunction, Error = Parse
                         .or(Some(ast::Annotation::boolean(location))),
                     return_type: (),
                     on_test_failure: ast::OnTestFailure::FailImmediately,
+                    example: None,
                 }
             },
         )
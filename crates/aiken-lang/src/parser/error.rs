@@ -1,5 +1,5 @@
 use crate::{
-    ast::{CurveType, Span},
+    ast::{CurveType, Feature, Span},
     parser::token::Token,
 };
 use indoc::formatdoc;
@@ -159,6 +159,26 @@ impl ParseError {
         }
     }
 
+    pub fn list_pattern_elements_after_spread(span: Span) -> Self {
+        Self {
+            kind: ErrorKind::ListPatternElementsAfterSpread,
+            span,
+            while_parsing: None,
+            expected: HashSet::new(),
+            label: Some("unexpected elements after spread"),
+        }
+    }
+
+    pub fn unknown_experimental_feature(name: String, span: Span) -> Self {
+        Self {
+            kind: ErrorKind::UnknownExperimentalFeature { name },
+            span,
+            while_parsing: None,
+            expected: HashSet::new(),
+            label: Some("unknown feature"),
+        }
+    }
+
     pub fn match_string(span: Span) -> Self {
         Self {
             kind: ErrorKind::PatternMatchOnString,
@@ -292,6 +312,32 @@ pub enum ErrorKind {
         "You can pattern-match on bytearrays but not on strings. Note that I can parse utf-8 encoded bytearrays just fine, so you probably want to drop the extra '@' and only manipulate bytearrays wherever you need to. On-chain, strings shall be avoided as much as possible."
     ))]
     PatternMatchOnString,
+
+    #[error("I stumbled upon list elements after a spread pattern.")]
+    #[diagnostic(help("{}", formatdoc! {
+        r#"A list pattern can only capture the rest of a list at the very end, as in `[first, second, ..rest]`. Matching on the last elements of a list requires traversing it entirely, which I won't do implicitly. If you need to look at the end of a list, match on the reversed list instead (e.g. with `list.reverse`) or destructure it recursively.
+        "#
+    }))]
+    ListPatternElementsAfterSpread,
+
+    #[error("I don't know any experimental feature named '{}'.", .name.if_supports_color(Stdout, |s| s.purple()))]
+    #[diagnostic(help("{}", fmt_available_features()))]
+    UnknownExperimentalFeature { name: String },
+}
+
+fn fmt_available_features() -> String {
+    if Feature::ALL.is_empty() {
+        return "There are no experimental features at the moment.".to_string();
+    }
+
+    format!(
+        "Experimental features currently available are: {}.",
+        Feature::ALL
+            .iter()
+            .map(|feature| format!("'{feature}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
 }
 
 fn fmt_curve_type(curve: &CurveType) -> String {
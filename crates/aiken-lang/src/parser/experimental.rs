@@ -0,0 +1,27 @@
+use super::{error::ParseError, token::Token};
+use crate::ast;
+use chumsky::prelude::*;
+
+/// A module attribute opting into an unstable language feature, e.g. `@experimental("feature")`.
+/// Unknown features are reported, and then ignored.
+pub fn parser() -> impl Parser<Token, Option<ast::Experimental>, Error = ParseError> {
+    just(Token::At)
+        .ignore_then(select! {Token::Name { name } if name == "experimental" => name})
+        .ignore_then(
+            select! {Token::ByteString { value } => value}
+                .map_with_span(|value, span| (value, span))
+                .delimited_by(
+                    choice((just(Token::LeftParen), just(Token::NewLineLeftParen))),
+                    just(Token::RightParen),
+                ),
+        )
+        .validate(
+            |(name, span), location, emit| match ast::Feature::from_name(&name) {
+                Some(feature) => Some(ast::Experimental { location, feature }),
+                None => {
+                    emit(ParseError::unknown_experimental_feature(name, span));
+                    None
+                }
+            },
+        )
+}
@@ -26,7 +26,9 @@ pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
 
         let arg_annotation = match name {
             Or | And => Some(ast::Annotation::boolean(location)),
-            Eq | NotEq => None,
+            // Concatenation isn't available as an anonymous operator since its operands may
+            // either be 'String' or 'ByteArray'.
+            Eq | NotEq | Concat => None,
             LtInt | LtEqInt | GtInt | GtEqInt | AddInt | SubInt | MultInt | DivInt | ModInt => {
                 Some(ast::Annotation::int(location))
             }
@@ -37,6 +39,7 @@ pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
                 Some(ast::Annotation::boolean(location))
             }
             AddInt | SubInt | MultInt | DivInt | ModInt => Some(ast::Annotation::int(location)),
+            Concat => None,
         };
 
         let arguments = vec![
@@ -47,6 +50,7 @@ pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
                     location,
                 }),
                 is_validator_param: false,
+                default: None,
                 annotation: arg_annotation.clone(),
                 doc: None,
                 location,
@@ -58,6 +62,7 @@ pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
                     location,
                 }),
                 is_validator_param: false,
+                default: None,
                 annotation: arg_annotation,
                 doc: None,
                 location,
@@ -50,6 +50,7 @@ pub fn params() -> impl Parser<Token, ast::UntypedArg, Error = ParseError> {
     .then(just(Token::Colon).ignore_then(annotation()).or_not())
     .map_with_span(|(by, annotation), span| ast::UntypedArg {
         is_validator_param: false,
+        default: None,
         location: span,
         annotation,
         doc: None,
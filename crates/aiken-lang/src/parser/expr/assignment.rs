@@ -32,6 +32,32 @@ pub fn let_(
         })
 }
 
+/// `use x <- f(args)` is an alternative notation for `let x <- f(args)`.
+pub fn use_(
+    r: Recursive<'_, Token, UntypedExpr, ParseError>,
+) -> impl Parser<Token, UntypedExpr, Error = ParseError> + '_ {
+    just(Token::Use)
+        .ignore_then(assignment_patterns())
+        .then_ignore(just(Token::LArrow))
+        .then(r.clone())
+        .validate(move |(patterns, value), span, emit| {
+            if matches!(value, UntypedExpr::Assignment { .. }) {
+                emit(ParseError::invalid_assignment_right_hand_side(span))
+            }
+
+            let patterns = patterns
+                .try_into()
+                .expect("We use at_least(1) so this should never be empty");
+
+            UntypedExpr::Assignment {
+                location: span,
+                value: Box::new(value),
+                patterns,
+                kind: ast::AssignmentKind::Let { backpassing: true },
+            }
+        })
+}
+
 fn assignment_patterns() -> impl Parser<Token, Vec<ast::AssignmentPattern>, Error = ParseError> {
     assignment_pattern()
         .separated_by(just(Token::Comma))
@@ -98,6 +124,11 @@ mod tests {
         assert_expr!("let thing = [ 1, 2, a ]");
     }
 
+    #[test]
+    fn use_backpassing() {
+        assert_expr!("use thing <- foo(a)");
+    }
+
     #[test]
     fn expect() {
         assert_expr!("expect Some(x) = something.field");
@@ -65,6 +65,7 @@ pub fn chain_start<'a>(
         when(expression.clone()),
         assignment::let_(expression.clone()),
         assignment::expect(expression.clone()),
+        assignment::use_(expression.clone()),
         if_else(sequence, expression.clone()),
     ))
 }
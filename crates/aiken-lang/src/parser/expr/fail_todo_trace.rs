@@ -26,8 +26,27 @@ pub fn parser<'a>(
         just(Token::Fail)
             .ignore_then(message)
             .map_with_span(UntypedExpr::fail),
+        // NOTE: 'assert' isn't a reserved keyword, so it remains usable as an identifier.
+        select! { Token::Name { name } if name == "assert" => () }
+            .ignore_then(expression.clone())
+            .then_ignore(just(Token::Comma))
+            .then(choice((string::hybrid(), expression.clone())))
+            .map_with_span(|(condition, message), location| UntypedExpr::Assert {
+                location,
+                condition: Box::new(condition),
+                message: Box::new(message),
+            }),
         just(Token::Trace)
-            .ignore_then(choice((string::hybrid(), expression.clone())))
+            .ignore_then(
+                just(Token::Dot)
+                    .ignore_then(select! {
+                        Token::Name { name } if name == "debug" => TraceKind::Debug,
+                        Token::Name { name } if name == "info" => TraceKind::Trace,
+                    })
+                    .or_not()
+                    .map(|kind| kind.unwrap_or(TraceKind::Trace)),
+            )
+            .then(choice((string::hybrid(), expression.clone())))
             .then(
                 choice((just(Token::Colon), just(Token::Comma)))
                     .then(
@@ -50,8 +69,8 @@ pub fn parser<'a>(
             )
             .then(sequence.clone().or_not())
             .map_with_span(
-                |((label, arguments), continuation), span| UntypedExpr::Trace {
-                    kind: TraceKind::Trace,
+                |(((kind, label), arguments), continuation), span| UntypedExpr::Trace {
+                    kind,
                     location: span,
                     then: Box::new(continuation.unwrap_or_else(|| UntypedExpr::todo(None, span))),
                     label: Box::new(label),
@@ -65,6 +84,24 @@ pub fn parser<'a>(
 mod tests {
     use crate::assert_expr;
 
+    #[test]
+    fn assert_basic() {
+        assert_expr!(
+            r#"
+            assert x > 0, @"x must be positive"
+            "#
+        );
+    }
+
+    #[test]
+    fn assert_sugar() {
+        assert_expr!(
+            r#"
+            assert x > 0, "x must be positive"
+            "#
+        );
+    }
+
     #[test]
     fn error_basic() {
         assert_expr!(
@@ -121,6 +121,20 @@ pub fn pure_expression<'a>(
         })
         .boxed();
 
+    // Concatenation
+    let op = just(Token::LessGreater).to(ast::BinOp::Concat);
+
+    let concatenation = sum
+        .clone()
+        .then(op.then(sum).repeated())
+        .foldl(|a, (op, b)| UntypedExpr::BinOp {
+            location: a.location().union(b.location()),
+            name: op,
+            left: Box::new(a),
+            right: Box::new(b),
+        })
+        .boxed();
+
     // Comparison
     let op = choice((
         just(Token::EqualEqual).to(ast::BinOp::Eq),
@@ -131,9 +145,9 @@ pub fn pure_expression<'a>(
         just(Token::GreaterEqual).to(ast::BinOp::GtEqInt),
     ));
 
-    let comparison = sum
+    let comparison = concatenation
         .clone()
-        .then(op.then(sum).repeated())
+        .then(op.then(concatenation).repeated())
         .foldl(|a, (op, b)| UntypedExpr::BinOp {
             location: a.location().union(b.location()),
             name: op,
@@ -1,7 +1,7 @@
 use super::{
     error::ParseError,
     extra::ModuleExtra,
-    token::{Base, Token},
+    token::{Base, Token, KEYWORDS, OPERATORS},
 };
 use crate::ast::Span;
 use chumsky::prelude::*;
@@ -155,35 +155,12 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
             }
         });
 
-    let op = choice((
-        just("==").to(Token::EqualEqual),
-        just('=').to(Token::Equal),
-        just("..").to(Token::DotDot),
-        just('.').to(Token::Dot),
-        just("!=").to(Token::NotEqual),
-        just('!').to(Token::Bang),
-        just('?').to(Token::Question),
-        just("<-").to(Token::LArrow),
-        just("->").to(Token::RArrow),
-        choice((
-            just("<=").to(Token::LessEqual),
-            just('<').to(Token::Less),
-            just(">=").to(Token::GreaterEqual),
-            just('>').to(Token::Greater),
-        )),
-        just('+').to(Token::Plus),
-        just('-').to(Token::Minus),
-        just('*').to(Token::Star),
-        just('/').to(Token::Slash),
-        just('%').to(Token::Percent),
-        just("|>").to(Token::Pipe),
-        just(',').to(Token::Comma),
-        just(':').to(Token::Colon),
-        just("||").to(Token::VbarVbar),
-        just('|').to(Token::Vbar),
-        just("&&").to(Token::AmperAmper),
-        just('#').to(Token::Hash),
-    ));
+    let op = choice(
+        OPERATORS
+            .iter()
+            .map(|(op, token)| just(*op).to(token.clone()))
+            .collect::<Vec<_>>(),
+    );
 
     let grouping = choice((
         just('(').to(Token::LeftParen),
@@ -211,6 +188,10 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
         .map(|value| Token::String { value })
         .labelled("string");
 
+    // Introduces module attributes, e.g. '@experimental'. Must come after strings, which also
+    // start with '@'.
+    let at = just('@').to(Token::At);
+
     let bytestring = just('"')
         .ignore_then(filter(|c| *c != '\\' && *c != '"').or(escape).repeated())
         .then_ignore(just('"'))
@@ -218,49 +199,21 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
         .map(|value| Token::ByteString { value })
         .labelled("bytestring");
 
-    let keyword = text::ident().map(|s: String| match s.as_str() {
-        "trace" => Token::Trace,
-        // TODO: remove this in a future release
-        "error" => Token::Fail,
-        "fail" => Token::Fail,
-        "once" => Token::Once,
-        "as" => Token::As,
-        "and" => Token::And,
-        "or" => Token::Or,
-        "expect" => Token::Expect,
-        "const" => Token::Const,
-        "fn" => Token::Fn,
-        "test" => Token::Test,
-        "if" => Token::If,
-        "else" => Token::Else,
-        "is" => Token::Is,
-        "let" => Token::Let,
-        "opaque" => Token::Opaque,
-        "pub" => Token::Pub,
-        "use" => Token::Use,
-        "todo" => Token::Todo,
-        "type" => Token::Type,
-        "when" => Token::When,
-        "validator" => Token::Validator,
-        "via" => Token::Via,
-        "bench" => Token::Benchmark,
-        _ => {
-            if s.chars().next().is_some_and(|c| c.is_uppercase()) {
-                Token::UpName {
-                    // TODO: do not allow _ in upname
-                    name: s,
-                }
-            } else if s.starts_with('_') {
-                Token::DiscardName {
-                    // TODO: do not allow uppercase letters in discard name
-                    name: s,
-                }
-            } else {
-                Token::Name {
-                    // TODO: do not allow uppercase letters in name
-                    name: s,
-                }
-            }
+    let keyword = text::ident().map(|s: String| {
+        match KEYWORDS.iter().find(|(keyword, _)| *keyword == s) {
+            Some((_, token)) => token.clone(),
+            None if s.chars().next().is_some_and(|c| c.is_uppercase()) => Token::UpName {
+                // TODO: do not allow _ in upname
+                name: s,
+            },
+            None if s.starts_with('_') => Token::DiscardName {
+                // TODO: do not allow uppercase letters in discard name
+                name: s,
+            },
+            None => Token::Name {
+                // TODO: do not allow uppercase letters in name
+                name: s,
+            },
         }
     });
 
@@ -299,7 +252,7 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
         comment_parser(Token::DocComment),
         comment_parser(Token::Comment),
         choice((
-            ordinal, keyword, int, op, newlines, grouping, bytestring, string,
+            ordinal, keyword, int, op, newlines, grouping, bytestring, string, at,
         ))
         .or(any().map(Token::Error).validate(|t, span, emit| {
             emit(ParseError::expected_input_found(
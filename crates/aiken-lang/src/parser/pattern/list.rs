@@ -17,6 +17,24 @@ pub fn parser(
             just(Token::Comma).ignore_then(
                 just(Token::DotDot)
                     .ignore_then(expression.clone().or_not())
+                    .then(
+                        just(Token::Comma)
+                            .ignore_then(
+                                expression
+                                    .clone()
+                                    .separated_by(just(Token::Comma))
+                                    .at_least(1)
+                                    .allow_trailing(),
+                            )
+                            .or_not(),
+                    )
+                    .validate(|(tail, trailing), span: ast::Span, emit| {
+                        if trailing.is_some() {
+                            emit(ParseError::list_pattern_elements_after_spread(span));
+                        }
+
+                        tail
+                    })
                     .or_not(),
             ),
             just(Token::Comma).ignored().or_not().map(|_| None),
@@ -56,3 +74,19 @@ pub fn parser(
             }
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_expr;
+
+    #[test]
+    fn pattern_list_elements_after_spread() {
+        assert_expr!(
+            r#"
+            when xs is {
+              [a, .., b] -> True
+            }
+            "#
+        );
+    }
+}
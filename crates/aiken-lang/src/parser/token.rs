@@ -35,6 +35,8 @@ pub enum Token {
     LessEqual,
     GreaterEqual,
     Percent,
+    // String & ByteArray Operators
+    LessGreater, // '<>'
     // ByteString Operators
     PlusDot,         // '+.'
     MinusDot,        // '-.'
@@ -47,6 +49,7 @@ pub enum Token {
     // Other Punctuation
     Colon,
     Comma,
+    At,       // '@'
     Hash,     // '#'
     Bang,     // '!'
     Question, // '?'
@@ -95,6 +98,67 @@ pub enum Token {
     Via,
 }
 
+/// Reserved words, and the token each one lexes to. This is the source of truth for the lexer
+/// as well as for the editor grammars generated from it.
+pub const KEYWORDS: &[(&str, Token)] = &[
+    ("trace", Token::Trace),
+    // TODO: remove this in a future release
+    ("error", Token::Fail),
+    ("fail", Token::Fail),
+    ("once", Token::Once),
+    ("as", Token::As),
+    ("and", Token::And),
+    ("or", Token::Or),
+    ("expect", Token::Expect),
+    ("const", Token::Const),
+    ("fn", Token::Fn),
+    ("test", Token::Test),
+    ("if", Token::If),
+    ("else", Token::Else),
+    ("is", Token::Is),
+    ("let", Token::Let),
+    ("opaque", Token::Opaque),
+    ("pub", Token::Pub),
+    ("use", Token::Use),
+    ("todo", Token::Todo),
+    ("type", Token::Type),
+    ("when", Token::When),
+    ("validator", Token::Validator),
+    ("via", Token::Via),
+    ("bench", Token::Benchmark),
+];
+
+/// Operators and punctuation, and the token each one lexes to. Order matters: the lexer tries
+/// them in turn, so an operator must come before any of its prefixes.
+pub const OPERATORS: &[(&str, Token)] = &[
+    ("==", Token::EqualEqual),
+    ("=", Token::Equal),
+    ("..", Token::DotDot),
+    (".", Token::Dot),
+    ("!=", Token::NotEqual),
+    ("!", Token::Bang),
+    ("?", Token::Question),
+    ("<-", Token::LArrow),
+    ("->", Token::RArrow),
+    ("<>", Token::LessGreater),
+    ("<=", Token::LessEqual),
+    ("<", Token::Less),
+    (">=", Token::GreaterEqual),
+    (">", Token::Greater),
+    ("+", Token::Plus),
+    ("-", Token::Minus),
+    ("*", Token::Star),
+    ("/", Token::Slash),
+    ("%", Token::Percent),
+    ("|>", Token::Pipe),
+    (",", Token::Comma),
+    (":", Token::Colon),
+    ("||", Token::VbarVbar),
+    ("|", Token::Vbar),
+    ("&&", Token::AmperAmper),
+    ("#", Token::Hash),
+];
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let index_str;
@@ -130,6 +194,7 @@ impl fmt::Display for Token {
             Token::LessEqual => "<=",
             Token::GreaterEqual => ">=",
             Token::Percent => "%",
+            Token::LessGreater => "<>",
             Token::PlusDot => "+.",
             Token::MinusDot => "-.",
             Token::StarDot => "*.",
@@ -140,6 +205,7 @@ impl fmt::Display for Token {
             Token::GreaterEqualDot => ">=.",
             Token::Colon => ":",
             Token::Comma => ",",
+            Token::At => "@",
             Token::Hash => "#",
             Token::Bang => "!",
             Token::Equal => "=",
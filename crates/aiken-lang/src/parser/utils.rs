@@ -155,13 +155,18 @@ macro_rules! assert_import {
 #[macro_export]
 macro_rules! assert_format {
     ($code:expr) => {
+        $crate::assert_format!($code, $crate::format::FormatOptions::default());
+    };
+    ($code:expr, $options:expr) => {
         let src = indoc::indoc! { $code };
 
+        let options = $options;
+
         let (module, extra) =
             $crate::parser::module(src, $crate::ast::ModuleKind::Lib).expect("Failed to parse code");
 
         let mut out = String::new();
-        $crate::format::pretty(&mut out, module, extra, &src);
+        $crate::format::pretty_with_options(&mut out, module, extra, &src, options);
 
         insta::with_settings!({
             description => concat!("Code:\n\n", indoc::indoc! { $code }),
@@ -174,7 +179,7 @@ macro_rules! assert_format {
         // Check if formatting is imdepotent
         let (module2, extra2) = $crate::parser::module(&out, $crate::ast::ModuleKind::Lib).unwrap();
         let mut out2 = String::new();
-        $crate::format::pretty(&mut out2, module2, extra2, &out);
+        $crate::format::pretty_with_options(&mut out2, module2, extra2, &out, options);
         pretty_assertions::assert_eq!(out, out2, "formatting isn't idempotent");
     };
 }
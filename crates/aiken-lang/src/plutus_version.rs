@@ -1,5 +1,7 @@
 use pallas_primitives::conway::Language;
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use uplc::builtins::DefaultFunction;
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -33,7 +35,7 @@ impl From<&PlutusVersion> for Language {
 impl From<Language> for PlutusVersion {
     fn from(value: Language) -> Self {
         match value {
-            Language::PlutusV1 => PlutusVersion::V2,
+            Language::PlutusV1 => PlutusVersion::V1,
             Language::PlutusV2 => PlutusVersion::V2,
             Language::PlutusV3 => PlutusVersion::V3,
         }
@@ -48,4 +50,72 @@ impl PlutusVersion {
             PlutusVersion::V3 => "PlutusScriptV3".to_string(),
         }
     }
+
+    /// Whether the given builtin is available on-chain for scripts of this version. Builtins
+    /// are introduced through hard-forks, and only ever for the latest language version.
+    pub fn supports_builtin(&self, builtin: &DefaultFunction) -> bool {
+        use DefaultFunction::*;
+
+        match builtin {
+            SerialiseData | VerifyEcdsaSecp256k1Signature | VerifySchnorrSecp256k1Signature => {
+                *self != PlutusVersion::V1
+            }
+            Bls12_381_G1_Add
+            | Bls12_381_G1_Neg
+            | Bls12_381_G1_ScalarMul
+            | Bls12_381_G1_Equal
+            | Bls12_381_G1_Compress
+            | Bls12_381_G1_Uncompress
+            | Bls12_381_G1_HashToGroup
+            | Bls12_381_G2_Add
+            | Bls12_381_G2_Neg
+            | Bls12_381_G2_ScalarMul
+            | Bls12_381_G2_Equal
+            | Bls12_381_G2_Compress
+            | Bls12_381_G2_Uncompress
+            | Bls12_381_G2_HashToGroup
+            | Bls12_381_MillerLoop
+            | Bls12_381_MulMlResult
+            | Bls12_381_FinalVerify
+            | Keccak_256
+            | Blake2b_224
+            | IntegerToByteString
+            | ByteStringToInteger
+            | AndByteString
+            | OrByteString
+            | XorByteString
+            | ComplementByteString
+            | ReadBit
+            | WriteBits
+            | ReplicateByte
+            | ShiftByteString
+            | RotateByteString
+            | CountSetBits
+            | FindFirstSetBit
+            | Ripemd_160 => *self == PlutusVersion::V3,
+            _ => true,
+        }
+    }
+}
+
+impl Display for PlutusVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlutusVersion::V1 => write!(f, "Plutus V1"),
+            PlutusVersion::V2 => write!(f, "Plutus V2"),
+            PlutusVersion::V3 => write!(f, "Plutus V3"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_round_trip() {
+        for version in [PlutusVersion::V1, PlutusVersion::V2, PlutusVersion::V3] {
+            assert_eq!(PlutusVersion::from(Language::from(version)), version);
+        }
+    }
 }
@@ -2,7 +2,7 @@ use crate::{
     ast::{BinOp, DataTypeKey, IfBranch, OnTestFailure, Span, TypedArg, TypedDataType, TypedTest},
     expr::{TypedExpr, UntypedExpr},
     format::Formatter,
-    gen_uplc::CodeGenerator,
+    gen_uplc::{interner::user_facing_name, CodeGenerator},
     plutus_version::PlutusVersion,
     tipo::{convert_opaque_type, Type},
 };
@@ -10,7 +10,10 @@ use cryptoxide::{blake2b::Blake2b, digest::Digest};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use owo_colors::{OwoColorize, Stream, Stream::Stderr};
-use pallas_primitives::alonzo::{Constr, PlutusData};
+use pallas_primitives::{
+    alonzo::{Constr, PlutusData},
+    conway::Language,
+};
 use patricia_tree::PatriciaMap;
 use std::{
     borrow::Borrow,
@@ -20,11 +23,11 @@ use std::{
     ops::Deref,
     path::PathBuf,
     rc::Rc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use uplc::{
     ast::{Constant, Data, Name, NamedDeBruijn, Program, Term},
-    machine::{cost_model::ExBudget, eval_result::EvalResult},
+    machine::{cost_model::ExBudget, eval_result::EvalResult, Machine},
 };
 use vec1::{vec1, Vec1};
 
@@ -34,6 +37,120 @@ pub enum RunnableKind {
     Bench,
 }
 
+/// ----- EvalSettings ---------------------------------------------------------
+///
+/// The ledger language and cost model under which tests and benchmarks are
+/// evaluated. Without explicit cost model parameters, the machine uses its
+/// built-in defaults for the given Plutus version.
+///
+#[derive(Debug, Clone, Default)]
+pub struct EvalSettings {
+    pub plutus_version: PlutusVersion,
+    pub cost_model: Option<Vec<i64>>,
+    pub timeout: Option<Duration>,
+    pub pooled: bool,
+    deadline: Option<Instant>,
+}
+
+impl EvalSettings {
+    pub fn new(plutus_version: PlutusVersion) -> Self {
+        Self {
+            plutus_version,
+            cost_model: None,
+            timeout: None,
+            pooled: false,
+            deadline: None,
+        }
+    }
+
+    pub fn with_cost_model(self, cost_model: Option<Vec<i64>>) -> Self {
+        Self { cost_model, ..self }
+    }
+
+    pub fn with_timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Recycle the machine's allocations across evaluations, which pays off when evaluating the
+    /// same program over and over, as property tests do.
+    pub fn with_pooled_allocation(self, pooled: bool) -> Self {
+        Self { pooled, ..self }
+    }
+
+    /// Settings for a single runnable starting now: every evaluation made with them is aborted
+    /// once the timeout has elapsed, so that a runaway test fails on its own instead of stalling
+    /// the whole run.
+    pub fn start_clock(&self) -> Self {
+        Self {
+            deadline: self.timeout.map(|timeout| Instant::now() + timeout),
+            ..self.clone()
+        }
+    }
+
+    pub fn eval(&self, program: Program<NamedDeBruijn>) -> EvalResult {
+        let language: Language = (&self.plutus_version).into();
+
+        let machine = Machine::for_language(&language, self.cost_model.as_deref(), ExBudget::max())
+            .with_deadline(self.deadline);
+
+        program.eval_with(if self.pooled {
+            machine.pooled()
+        } else {
+            machine
+        })
+    }
+}
+
+/// Variables are told apart by their uniques (and, once evaluated, by De Bruijn indices), so
+/// their text is free to change. Restore names written by users, so that machine errors and
+/// traces cite recognizable names rather than interner artifacts. This is done once, when a
+/// test's programs are generated, rather than on every evaluation.
+fn with_user_facing_names(program: Program<Name>) -> Program<Name> {
+    fn rename(term: &mut Term<Name>) {
+        match term {
+            Term::Var(name) => {
+                if let Some(text) = rename_text(&name.text) {
+                    Rc::make_mut(name).text = text;
+                }
+            }
+            Term::Lambda {
+                parameter_name,
+                body,
+            } => {
+                if let Some(text) = rename_text(&parameter_name.text) {
+                    Rc::make_mut(parameter_name).text = text;
+                }
+                rename(Rc::make_mut(body));
+            }
+            Term::Delay(inner) | Term::Force(inner) => rename(Rc::make_mut(inner)),
+            Term::Apply { function, argument } => {
+                rename(Rc::make_mut(function));
+                rename(Rc::make_mut(argument));
+            }
+            Term::Constr { fields, .. } => fields.iter_mut().for_each(rename),
+            Term::Case { constr, branches } => {
+                rename(Rc::make_mut(constr));
+                branches.iter_mut().for_each(rename);
+            }
+            Term::Constant(_) | Term::Builtin(_) | Term::Error => (),
+        }
+    }
+
+    fn rename_text(text: &str) -> Option<String> {
+        let name = user_facing_name(text);
+        (name.len() != text.len()).then(|| name.to_string())
+    }
+
+    let mut term = program.term;
+
+    rename(&mut term);
+
+    Program {
+        version: program.version,
+        term,
+    }
+}
+
 /// ----- Test -----------------------------------------------------------------
 ///
 /// Aiken supports two kinds of tests: unit and property. A unit test is a simply
@@ -67,7 +184,7 @@ impl Test {
         module_name: String,
         input_path: PathBuf,
     ) -> Test {
-        let program = generator.generate_raw(&test.body, &[], &module_name);
+        let program = with_user_facing_names(generator.generate_raw(&test.body, &[], &module_name));
 
         let assertion = match test.body.try_into() {
             Err(..) => None,
@@ -145,19 +262,20 @@ impl Test {
 
             let stripped_type_info = convert_opaque_type(&type_info, generator.data_types(), true);
 
-            let program = generator.clone().generate_raw(
+            let program = with_user_facing_names(generator.clone().generate_raw(
                 &test.body,
                 &[TypedArg {
                     tipo: stripped_type_info.clone(),
                     ..parameter.clone().into()
                 }],
                 &module_name,
-            );
+            ));
 
             // NOTE: We need not to pass any parameter to the fuzzer/sampler here because the fuzzer
             // argument is a Data constructor which needs not any conversion. So we can just safely
             // apply onto it later.
-            let generator_program = generator.clone().generate_raw(&via, &[], &module_name);
+            let generator_program =
+                with_user_facing_names(generator.clone().generate_raw(&via, &[], &module_name));
 
             match kind {
                 RunnableKind::Bench => Test::Benchmark(Benchmark {
@@ -192,15 +310,15 @@ impl Test {
         self,
         seed: u32,
         max_success: usize,
-        plutus_version: &PlutusVersion,
+        settings: &EvalSettings,
     ) -> TestResult<(Constant, Rc<Type>), PlutusData> {
         match self {
-            Test::UnitTest(unit_test) => TestResult::UnitTestResult(unit_test.run(plutus_version)),
+            Test::UnitTest(unit_test) => TestResult::UnitTestResult(unit_test.run(settings)),
             Test::PropertyTest(property_test) => {
-                TestResult::PropertyTestResult(property_test.run(seed, max_success, plutus_version))
+                TestResult::PropertyTestResult(property_test.run(seed, max_success, settings))
             }
             Test::Benchmark(benchmark) => {
-                TestResult::BenchmarkResult(benchmark.run(seed, max_success, plutus_version))
+                TestResult::BenchmarkResult(benchmark.run(seed, max_success, settings))
             }
         }
     }
@@ -221,10 +339,11 @@ pub struct UnitTest {
 unsafe impl Send for UnitTest {}
 
 impl UnitTest {
-    pub fn run(self, plutus_version: &PlutusVersion) -> UnitTestResult<(Constant, Rc<Type>)> {
-        let mut eval_result = Program::<NamedDeBruijn>::try_from(self.program.clone())
-            .unwrap()
-            .eval_version(ExBudget::max(), &plutus_version.into());
+    pub fn run(self, settings: &EvalSettings) -> UnitTestResult<(Constant, Rc<Type>)> {
+        let settings = &settings.start_clock();
+
+        let mut eval_result =
+            settings.eval(Program::<NamedDeBruijn>::try_from(self.program.clone()).unwrap());
 
         let success = !eval_result.failed(match self.on_test_failure {
             OnTestFailure::SucceedEventually | OnTestFailure::SucceedImmediately => true,
@@ -325,36 +444,34 @@ impl PropertyTest {
         self,
         seed: u32,
         n: usize,
-        plutus_version: &PlutusVersion,
+        settings: &EvalSettings,
     ) -> PropertyTestResult<PlutusData> {
+        let settings = &settings.start_clock();
+
         let mut labels = BTreeMap::new();
         let mut remaining = n;
 
-        let (traces, counterexample, iterations) = match self.run_n_times(
-            &mut remaining,
-            Prng::from_seed(seed),
-            &mut labels,
-            plutus_version,
-        ) {
-            Ok(None) => (Vec::new(), Ok(None), n),
-            Ok(Some(counterexample)) => (
-                self.eval(&counterexample.value, plutus_version)
-                    .logs()
-                    .into_iter()
-                    .filter(|s| PropertyTest::extract_label(s).is_none())
-                    .collect(),
-                Ok(Some(counterexample.value)),
-                n - remaining,
-            ),
-            Err(FuzzerError { traces, uplc_error }) => (
-                traces
-                    .into_iter()
-                    .filter(|s| PropertyTest::extract_label(s).is_none())
-                    .collect(),
-                Err(uplc_error),
-                n - remaining + 1,
-            ),
-        };
+        let (traces, counterexample, iterations) =
+            match self.run_n_times(&mut remaining, Prng::from_seed(seed), &mut labels, settings) {
+                Ok(None) => (Vec::new(), Ok(None), n),
+                Ok(Some(counterexample)) => (
+                    self.eval(&counterexample.value, settings)
+                        .logs()
+                        .into_iter()
+                        .filter(|s| PropertyTest::extract_label(s).is_none())
+                        .collect(),
+                    Ok(Some(counterexample.value)),
+                    n - remaining,
+                ),
+                Err(FuzzerError { traces, uplc_error }) => (
+                    traces
+                        .into_iter()
+                        .filter(|s| PropertyTest::extract_label(s).is_none())
+                        .collect(),
+                    Err(uplc_error),
+                    n - remaining + 1,
+                ),
+            };
 
         PropertyTestResult {
             test: self,
@@ -370,13 +487,13 @@ impl PropertyTest {
         remaining: &mut usize,
         initial_prng: Prng,
         labels: &mut BTreeMap<String, usize>,
-        plutus_version: &'a PlutusVersion,
+        settings: &'a EvalSettings,
     ) -> Result<Option<Counterexample<'a>>, FuzzerError> {
         let mut prng = initial_prng;
         let mut counterexample = None;
 
         while *remaining > 0 && counterexample.is_none() {
-            (prng, counterexample) = self.run_once(prng, labels, plutus_version)?;
+            (prng, counterexample) = self.run_once(prng, labels, settings)?;
             *remaining -= 1;
         }
 
@@ -387,7 +504,7 @@ impl PropertyTest {
         &'a self,
         prng: Prng,
         labels: &mut BTreeMap<String, usize>,
-        plutus_version: &'a PlutusVersion,
+        settings: &'a EvalSettings,
     ) -> Result<(Prng, Option<Counterexample<'a>>), FuzzerError> {
         use OnTestFailure::*;
 
@@ -395,7 +512,16 @@ impl PropertyTest {
             .sample(&self.fuzzer.program)?
             .expect("A seeded PRNG returned 'None' which indicates a fuzzer is ill-formed and implemented wrongly; please contact library's authors.");
 
-        let mut result = self.eval(&value, plutus_version);
+        let mut result = self.eval(&value, settings);
+
+        // Past the deadline, every remaining evaluation would time out as well; so there's no
+        // point in looking for (or simplifying) a counterexample.
+        if result.timed_out() {
+            return Err(FuzzerError {
+                traces: result.logs(),
+                uplc_error: uplc::machine::Error::TimedOut,
+            });
+        }
 
         for s in result.logs() {
             // NOTE: There may be other log outputs that interefere with labels. So *by
@@ -427,7 +553,7 @@ impl PropertyTest {
                         Err(..) => Status::Invalid,
                         Ok(None) => Status::Invalid,
                         Ok(Some((_, value))) => {
-                            let result = self.eval(&value, plutus_version);
+                            let result = self.eval(&value, settings);
 
                             let is_failure = result.failed(false);
 
@@ -463,12 +589,10 @@ impl PropertyTest {
         }
     }
 
-    pub fn eval(&self, value: &PlutusData, plutus_version: &PlutusVersion) -> EvalResult {
+    pub fn eval(&self, value: &PlutusData, settings: &EvalSettings) -> EvalResult {
         let program = self.program.apply_data(value.clone());
 
-        Program::<NamedDeBruijn>::try_from(program)
-            .unwrap()
-            .eval_version(ExBudget::max(), &plutus_version.into())
+        settings.eval(Program::<NamedDeBruijn>::try_from(program).unwrap())
     }
 
     fn extract_label(s: &str) -> Option<String> {
@@ -532,12 +656,9 @@ unsafe impl Send for Benchmark {}
 impl Benchmark {
     pub const DEFAULT_MAX_SIZE: usize = 30;
 
-    pub fn run(
-        self,
-        seed: u32,
-        max_size: usize,
-        plutus_version: &PlutusVersion,
-    ) -> BenchmarkResult {
+    pub fn run(self, seed: u32, max_size: usize, settings: &EvalSettings) -> BenchmarkResult {
+        let settings = &settings.start_clock();
+
         let mut measures = Vec::with_capacity(max_size);
         let mut prng = Prng::from_seed(seed);
         let mut error = None;
@@ -556,7 +677,7 @@ impl Benchmark {
 
                 Ok(Some((new_prng, value))) => {
                     prng = new_prng;
-                    let mut result = self.eval(&value, plutus_version);
+                    let mut result = self.eval(&value, settings);
                     match result.result() {
                         Ok(_) => measures.push((size, result.cost())),
                         Err(uplc_error) => {
@@ -587,12 +708,10 @@ impl Benchmark {
         }
     }
 
-    pub fn eval(&self, value: &PlutusData, plutus_version: &PlutusVersion) -> EvalResult {
+    pub fn eval(&self, value: &PlutusData, settings: &EvalSettings) -> EvalResult {
         let program = self.program.apply_data(value.clone());
 
-        Program::<NamedDeBruijn>::try_from(program)
-            .unwrap()
-            .eval_version(ExBudget::max(), &plutus_version.into())
+        settings.eval(Program::<NamedDeBruijn>::try_from(program).unwrap())
     }
 }
 
@@ -1553,4 +1672,23 @@ mod test {
         assert_eq!(called.borrow().deref().to_owned(), 5, "execution calls");
         assert_eq!(cache.size(), 4, "cache size");
     }
+
+    #[test]
+    fn test_user_facing_names() {
+        assert_eq!(user_facing_name("self_id_42"), "self");
+        assert_eq!(user_facing_name("aiken/list_foldr"), "aiken/list_foldr");
+        assert_eq!(user_facing_name("my_id_card"), "my_id_card");
+        assert_eq!(user_facing_name("_id_1"), "_id_1");
+
+        // Both binders end up named 'x', yet the variable still refers to the outer one.
+        let program =
+            uplc::parser::program("(program 1.0.0 (lam x_id_3 (lam x_id_5 x_id_3)))").unwrap();
+
+        let program: Program<NamedDeBruijn> = with_user_facing_names(program).try_into().unwrap();
+
+        assert_eq!(
+            program.to_pretty(),
+            "(program 1.0.0 (lam x_0 (lam x_0 x_2)))"
+        );
+    }
 }
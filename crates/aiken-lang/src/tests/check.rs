@@ -1,15 +1,15 @@
 use crate::{
     ast::{
-        Definition, ModuleKind, Pattern, TraceLevel, Tracing, TypedModule, UntypedModule,
-        UntypedPattern,
+        well_known, Definition, ModuleKind, Pattern, TraceLevel, TraceSeverity, Tracing,
+        TypedModule, UntypedModule, UntypedPattern,
     },
     builtins,
     expr::{CallArg, Span, TypedExpr},
+    format::Formatter,
     parser,
     tipo::error::{Error, UnifyErrorSituation, Warning},
     IdGenerator,
 };
-use std::collections::HashMap;
 
 fn parse(source_code: &str) -> UntypedModule {
     let kind = ModuleKind::Lib;
@@ -27,9 +27,7 @@ fn check_module(
 
     let mut warnings = vec![];
 
-    let mut module_types = HashMap::new();
-    module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
-    module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+    let mut module_types = builtins::modules(&id_gen);
 
     for (package, module) in extra {
         let mut warnings = vec![];
@@ -40,6 +38,7 @@ fn check_module(
                 &package,
                 &module_types,
                 Tracing::All(TraceLevel::Verbose),
+                TraceSeverity::Debug,
                 &mut warnings,
                 None,
             )
@@ -53,6 +52,7 @@ fn check_module(
         "test/project",
         &module_types,
         tracing,
+        TraceSeverity::Debug,
         &mut warnings,
         None,
     );
@@ -216,6 +216,37 @@ fn illegal_function_comparison() {
     ))
 }
 
+#[test]
+fn illegal_function_comparison_through_inference() {
+    let source_code = r#"
+    fn foo() -> Bool {
+      let is_same = fn(a, b) { a == b }
+      is_same(not, not)
+    }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IllegalComparison { .. }))
+    ))
+}
+
+#[test]
+fn illegal_function_comparison_in_list() {
+    let source_code = r#"
+    fn foo() -> Bool {
+      let fns = []
+      let bools = fns == []
+      bools || fns == [not]
+    }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IllegalComparison { .. }))
+    ))
+}
+
 #[test]
 fn illegal_inhabitants_returned() {
     let source_code = r#"
@@ -457,6 +488,100 @@ fn exhaustiveness_simple() {
     ))
 }
 
+#[test]
+fn let_on_multi_constructor_type() {
+    let source_code = r#"
+        type Shape {
+          Circle(Int)
+          Square(Int)
+          Triangle
+        }
+
+        fn foo(shape: Shape) {
+          let Circle(radius) = shape
+          radius
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::NotExhaustivePatternMatch {
+                unmatched,
+                is_let: true,
+                ..
+            }
+        )) if unmatched.len() == 2
+    ))
+}
+
+#[test]
+fn expect_on_multi_constructor_type() {
+    let source_code = r#"
+        type Shape {
+          Circle(Int)
+          Square(Int)
+          Triangle
+        }
+
+        fn foo(shape: Shape) {
+          expect Circle(radius) = shape
+          radius
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn record_update_spread_from_call() {
+    let source_code = r#"
+        type Settings {
+          fee: Int,
+          owner: ByteArray,
+          limit: Int,
+        }
+
+        fn defaults() -> Settings {
+          Settings { fee: 1, owner: #"", limit: 100 }
+        }
+
+        fn custom() -> Settings {
+          Settings { ..defaults(), fee: 2 }
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn record_update_spread_from_call_wrong_type() {
+    let source_code = r#"
+        type Settings {
+          fee: Int,
+          owner: ByteArray,
+        }
+
+        type Other {
+          fee: Int,
+        }
+
+        fn defaults() -> Other {
+          Other { fee: 1 }
+        }
+
+        fn custom() -> Settings {
+          Settings { ..defaults(), fee: 2 }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
 #[test]
 fn validator_args_no_annotation() {
     let source_code = r#"
@@ -1014,6 +1139,149 @@ fn anonymous_function_dupicate_args() {
     ))
 }
 
+#[test]
+fn anonymous_function_arg_annotation_mismatch() {
+    let source_code = r#"
+        fn reduce(list: List<Int>, f: fn(Int, Int) -> Int, zero: Int) -> Int {
+          todo
+        }
+
+        pub fn foo() {
+          reduce([1, 2, 3], fn(acc: ByteArray, n: Int) { n }, 0)
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
+#[test]
+fn anonymous_function_return_annotation_mismatch() {
+    let source_code = r#"
+        pub fn foo() {
+          let f = fn(n: Int) -> ByteArray { n + 1 }
+          f(14)
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: Some(UnifyErrorSituation::ReturnAnnotationMismatch),
+                ..
+            }
+        ))
+    ))
+}
+
+#[test]
+fn return_annotation_mismatch_points_at_annotation() {
+    let source_code = r#"
+        pub fn foo() -> Int {
+          #"00"
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: Some(UnifyErrorSituation::ReturnAnnotationMismatch),
+                ref notes,
+                ..
+            }
+        )) if notes.len() == 1 && notes[0].label() == Some("expected because of this annotation")
+    ))
+}
+
+#[test]
+fn type_mismatch_shows_what_aliases_stand_for() {
+    let source_code = r#"
+        type PolicyId = ByteArray
+
+        pub fn foo(policy_id: PolicyId) -> Int {
+          policy_id
+        }
+    "#;
+
+    let (_, error) = check(parse(source_code)).unwrap_err();
+
+    let help = miette::Diagnostic::help(&error).unwrap().to_string();
+
+    // Colors depend on the terminal, so leave them out of the snapshot.
+    let help = regex::Regex::new("\x1b\\[[0-9;]*m")
+        .unwrap()
+        .replace_all(&help, "")
+        .into_owned();
+
+    insta::assert_snapshot!(help);
+}
+
+#[test]
+fn let_annotation_mismatch_points_at_annotation() {
+    let source_code = r#"
+        pub fn foo() {
+          let x: Int = #"00"
+          x
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify { ref notes, .. }
+        )) if notes.len() == 1 && notes[0].label() == Some("expected because of this annotation")
+    ))
+}
+
+#[test]
+fn case_clause_mismatch_points_at_first_branch() {
+    let source_code = r#"
+        pub fn foo(n: Int) {
+          when n is {
+            0 -> True
+            _ -> 42
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: Some(UnifyErrorSituation::CaseClauseMismatch),
+                ref notes,
+                ..
+            }
+        )) if notes.len() == 1 && notes[0].label() == Some("expected because of this branch")
+    ))
+}
+
+#[test]
+fn anonymous_function_return_annotation_against_expected() {
+    let source_code = r#"
+        fn reduce(list: List<Int>, f: fn(Int, Int) -> Int, zero: Int) -> Int {
+          todo
+        }
+
+        pub fn foo() {
+          reduce([1, 2, 3], fn(acc, n) -> Bool { acc == n }, 0)
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
 #[test]
 fn assignement_last_expr_when() {
     let source_code = r#"
@@ -1771,82 +2039,276 @@ fn pipe_wrong_arity_fully_saturated_return_fn() {
 }
 
 #[test]
-fn fuzzer_ok_basic() {
+fn pipe_capture_any_position() {
     let source_code = r#"
-        fn int() -> Fuzzer<Int> { todo }
-        test prop(n via int()) { True }
+        fn f(_a: ByteArray, _b: Int, _c: Bool) -> Int {
+            todo
+        }
+
+        test foo() {
+            (14 |> f(#"", _, True)) == 0
+        }
     "#;
 
     assert!(check(parse(source_code)).is_ok());
 }
 
 #[test]
-fn sampler_ok_basic() {
+fn pipe_capture_wrong_type() {
     let source_code = r#"
-        fn int() -> Sampler<Int> { todo }
-        bench prop(n via int()) { True }
+        fn f(_a: ByteArray, _b: Int, _c: Bool) -> Int {
+            todo
+        }
+
+        test foo() {
+            (True |> f(#"", _, True)) == 0
+        }
     "#;
 
-    assert!(check(parse(source_code)).is_ok());
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
 }
 
 #[test]
-fn fuzzer_ok_explicit() {
+fn default_parameters_filled_at_call_site() {
     let source_code = r#"
-        fn int(prng: PRNG) -> Option<(PRNG, Int)> { todo }
-        test prop(n via int) { Void }
+        fn f(a: Int, b: Int = 1, c: ByteArray = #"00") -> Int {
+            todo
+        }
+
+        test foo() {
+            and {
+                f(14) == 0,
+                f(14, 42) == 0,
+                f(14, c: #"ff") == 0,
+                (14 |> f) == 0,
+            }
+        }
     "#;
 
     assert!(check(parse(source_code)).is_ok());
 }
 
 #[test]
-fn fuzzer_ok_list() {
+fn default_parameters_through_pipe_call() {
     let source_code = r#"
-        fn int() -> Fuzzer<Int> { todo }
-        fn list(a: Fuzzer<a>) -> Fuzzer<List<a>> { todo }
+        fn f(a: Int, b: Int = 1, c: ByteArray = #"00") -> Int {
+            todo
+        }
 
-        test prop(xs via list(int())) { True }
+        test foo() {
+            and {
+                (14 |> f()) == 0,
+                (14 |> f(42)) == 0,
+                (14 |> f(c: #"ff")) == 0,
+            }
+        }
     "#;
 
     assert!(check(parse(source_code)).is_ok());
 }
 
 #[test]
-fn fuzzer_err_unbound() {
+fn default_parameters_kept_on_typed_arguments() {
     let source_code = r#"
-        fn any() -> Fuzzer<a> { todo }
-        fn list(a: Fuzzer<a>) -> Fuzzer<List<a>> { todo }
-
-        test prop(xs via list(any())) { todo }
+        pub fn f(a: Int, b: Int = 0x10, c: ByteArray = #"00") -> Int {
+            todo
+        }
     "#;
 
-    assert!(matches!(
-        check(parse(source_code)),
-        Err((_, Error::GenericLeftAtBoundary { .. }))
-    ))
-}
+    let (_, module) = check(parse(source_code)).expect("failed to type-check");
 
-#[test]
-fn fuzzer_err_unify_1() {
-    let source_code = r#"
-        test prop(xs via Void) { todo }
-    "#;
+    let def = module
+        .definitions()
+        .find_map(|def| match def {
+            Definition::Fn(f) => Some(f),
+            _ => None,
+        })
+        .unwrap();
 
-    assert!(matches!(
-        check(parse(source_code)),
-        Err((
-            _,
-            Error::CouldNotUnify {
-                situation: None,
-                ..
-            }
-        ))
-    ))
+    assert!(def.arguments[0].default.is_none());
+    assert!(def.arguments[1].default.is_some());
+    assert!(def.arguments[2].default.is_some());
+
+    let signature = Formatter::new()
+        .docs_fn_signature(
+            &def.name,
+            &def.arguments,
+            &def.return_annotation,
+            def.return_type.clone(),
+        )
+        .to_pretty_string(80);
+
+    assert_eq!(
+        signature,
+        r#"f(a: Int, b: Int = 0x10, c: ByteArray = #"00") -> Int"#
+    );
 }
 
 #[test]
-fn fuzzer_err_unify_2() {
+fn docs_name_generic_variables_per_definition() {
+    let source_code = r#"
+        pub fn first(x, y) {
+            x
+        }
+
+        pub fn second(x, y) {
+            y
+        }
+    "#;
+
+    let (_, module) = check(parse(source_code)).expect("failed to type-check");
+
+    let mut formatter = Formatter::new();
+
+    let signatures = module
+        .definitions()
+        .filter_map(|def| match def {
+            Definition::Fn(f) => Some(
+                formatter
+                    .docs_fn_signature(
+                        &f.name,
+                        &f.arguments,
+                        &f.return_annotation,
+                        f.return_type.clone(),
+                    )
+                    .to_pretty_string(80),
+            ),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        signatures,
+        vec!["first(x: a, y: b) -> a", "second(x: a, y: b) -> b"]
+    );
+}
+
+#[test]
+fn default_parameters_wrong_type() {
+    let source_code = r#"
+        fn f(a: Int, b: ByteArray = 1) -> Int {
+            todo
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
+#[test]
+fn default_parameters_not_trailing() {
+    let source_code = r#"
+        fn f(a: Int = 1, b: Int) -> Int {
+            todo
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::NonTrailingDefaultParameter { .. }))
+    ))
+}
+
+#[test]
+fn default_parameters_missing_required() {
+    let source_code = r#"
+        fn f(a: Int, b: Int, c: Int = 1) -> Int {
+            todo
+        }
+
+        test foo() {
+            f(14) == 0
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IncorrectFieldsArity { .. }))
+    ))
+}
+
+#[test]
+fn fuzzer_ok_basic() {
+    let source_code = r#"
+        fn int() -> Fuzzer<Int> { todo }
+        test prop(n via int()) { True }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn sampler_ok_basic() {
+    let source_code = r#"
+        fn int() -> Sampler<Int> { todo }
+        bench prop(n via int()) { True }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn fuzzer_ok_explicit() {
+    let source_code = r#"
+        fn int(prng: PRNG) -> Option<(PRNG, Int)> { todo }
+        test prop(n via int) { Void }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn fuzzer_ok_list() {
+    let source_code = r#"
+        fn int() -> Fuzzer<Int> { todo }
+        fn list(a: Fuzzer<a>) -> Fuzzer<List<a>> { todo }
+
+        test prop(xs via list(int())) { True }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn fuzzer_err_unbound() {
+    let source_code = r#"
+        fn any() -> Fuzzer<a> { todo }
+        fn list(a: Fuzzer<a>) -> Fuzzer<List<a>> { todo }
+
+        test prop(xs via list(any())) { todo }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::GenericLeftAtBoundary { .. }))
+    ))
+}
+
+#[test]
+fn fuzzer_err_unify_1() {
+    let source_code = r#"
+        test prop(xs via Void) { todo }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: None,
+                ..
+            }
+        ))
+    ))
+}
+
+#[test]
+fn fuzzer_err_unify_2() {
     let source_code = r#"
         fn any() -> Fuzzer<a> { todo }
         test prop(xs via any) { todo }
@@ -2168,133 +2630,1130 @@ fn allow_expect_into_monomorphic_type_from_data_with_pattern() {
 }
 
 #[test]
-fn forbid_expect_into_generic_type_from_data_with_pattern() {
+fn forbid_expect_into_generic_type_from_data_with_pattern() {
+    let source_code = r#"
+        fn bar(n: Data) {
+          expect Some(a) = n
+          a
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CastDataNoAnn { .. }))
+    ))
+}
+
+#[test]
+fn allow_generic_expect_without_typecast() {
+    let source_code = r#"
+        pub fn unwrap(opt: Option<a>) -> a {
+          expect Some(a) = opt
+          a
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn allow_expect_into_custom_type_from_data_no_annotation() {
+    let source_code = r#"
+        type OrderDatum {
+            requested_handle: ByteArray,
+            amount: Int,
+            other: Bool,
+        }
+
+        fn foo(datum: Data) {
+            expect OrderDatum { requested_handle, .. } = datum
+            requested_handle
+        }
+
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn forbid_expect_from_arbitrary_type() {
+    let source_code = r#"
+        type Foo {
+          x: Int
+        }
+
+        type Bar {
+          y: Int
+        }
+
+        fn bar(f: Foo) {
+          expect b: Bar = f
+          Void
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
+#[test]
+fn allow_expect_into_opaque_type_constructor_without_typecasting_in_module() {
+    let source_code = r#"
+        opaque type Thing {
+          Foo(Int)
+          Bar(Int)
+        }
+
+        fn bar(thing: Thing) {
+          expect Foo(a) = thing
+          a
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn forbid_importing_or_using_opaque_constructors() {
+    let dependency = r#"
+        pub opaque type Thing {
+          Foo(Int)
+          Bar(Int)
+        }
+    "#;
+
+    let source_code = r#"
+        use foo/thing.{Thing, Foo}
+
+        fn bar(thing: Thing) {
+          expect Foo(a) = thing
+          a
+        }
+    "#;
+
+    assert!(matches!(
+        check_with_deps(
+            parse(source_code),
+            vec![("foo/thing".to_string(), parse(dependency))],
+        ),
+        Err((_, Error::UnknownModuleField { .. })),
+    ));
+
+    let source_code = r#"
+        use foo/thing.{Thing}
+
+        fn bar(thing: Thing) {
+          expect Foo(a) = thing
+          a
+        }
+    "#;
+
+    assert!(matches!(
+        check_with_deps(
+            parse(source_code),
+            vec![("foo/thing".to_string(), parse(dependency))],
+        ),
+        Err((_, Error::UnknownTypeConstructor { .. })),
+    ));
+}
+
+#[test]
+fn module_alias_shadowing_another_module() {
+    let dependency = r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#;
+
+    let source_code = r#"
+        use foo/thing as other
+
+        test foo() {
+          other.double(2) == 4
+        }
+    "#;
+
+    let (warnings, _) = check_with_deps(
+        parse(source_code),
+        vec![
+            ("foo/thing".to_string(), parse(dependency)),
+            ("bar/other".to_string(), parse(dependency)),
+        ],
+    )
+    .unwrap();
+
+    assert!(matches!(
+        &warnings[..],
+        [Warning::ModuleAliasShadowsModule { alias, aliased, shadowed, .. }]
+            if alias == "other" && aliased == "foo/thing" && shadowed == "bar/other"
+    ));
+}
+
+#[test]
+fn module_alias_not_shadowing_anything() {
+    let dependency = r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#;
+
+    let source_code = r#"
+        use foo/thing as t
+        use bar/other.{double} as other
+
+        test foo() {
+          t.double(2) == double(2)
+        }
+    "#;
+
+    let (warnings, _) = check_with_deps(
+        parse(source_code),
+        vec![
+            ("foo/thing".to_string(), parse(dependency)),
+            ("bar/other".to_string(), parse(dependency)),
+        ],
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn reexport_values_and_types() {
+    let dependency = r#"
+        pub type Shape {
+          Square(Int)
+        }
+
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#;
+
+    let source_code = r#"
+        pub use foo/shapes.{Shape, double as twice}
+    "#;
+
+    let (warnings, module) = check_with_deps(
+        parse(source_code),
+        vec![("foo/shapes".to_string(), parse(dependency))],
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+    assert!(module.type_info.values.contains_key("twice"));
+    assert!(module.type_info.types.contains_key("Shape"));
+    assert!(module.type_info.values.contains_key("Square"));
+    assert_eq!(
+        module.type_info.reexports.get("twice"),
+        Some(&"foo/shapes".to_string())
+    );
+}
+
+#[test]
+fn reexport_clashing_with_local_value() {
+    let dependency = r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#;
+
+    let source_code = r#"
+        pub use foo/math.{double}
+
+        fn double(n: Int) -> Int {
+          n + n
+        }
+    "#;
+
+    assert!(matches!(
+        check_with_deps(
+            parse(source_code),
+            vec![("foo/math".to_string(), parse(dependency))],
+        ),
+        Err((_, Error::DuplicateName { name, .. })) if name == "double"
+    ))
+}
+
+#[test]
+fn reexport_clashing_with_local_type() {
+    let dependency = r#"
+        pub type Shape {
+          Square(Int)
+        }
+    "#;
+
+    let source_code = r#"
+        pub use foo/shapes.{Shape}
+
+        pub type Shape {
+          Circle(Int)
+        }
+    "#;
+
+    assert!(matches!(
+        check_with_deps(
+            parse(source_code),
+            vec![("foo/shapes".to_string(), parse(dependency))],
+        ),
+        Err((_, Error::DuplicateTypeName { name, .. })) if name == "Shape"
+    ))
+}
+
+#[test]
+fn use_reexported_constructors() {
+    let dependency = r#"
+        pub type Shape {
+          Square(Int)
+        }
+    "#;
+
+    let reexporter = r#"
+        pub use foo/shapes.{Shape}
+    "#;
+
+    let source_code = r#"
+        use foo/prelude.{Shape}
+
+        test foo() {
+          let shape: Shape = prelude.Square(2)
+          shape == prelude.Square(2)
+        }
+    "#;
+
+    let (warnings, _) = check_with_deps(
+        parse(source_code),
+        vec![
+            ("foo/shapes".to_string(), parse(dependency)),
+            ("foo/prelude".to_string(), parse(reexporter)),
+        ],
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn use_reexported_values() {
+    let dependency = r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#;
+
+    let reexporter = r#"
+        pub use foo/math.{double}
+    "#;
+
+    let source_code = r#"
+        use foo/prelude.{double}
+
+        test foo() {
+          double(2) == 4
+        }
+    "#;
+
+    let (warnings, _) = check_with_deps(
+        parse(source_code),
+        vec![
+            ("foo/math".to_string(), parse(dependency)),
+            ("foo/prelude".to_string(), parse(reexporter)),
+        ],
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn reexport_entire_module() {
+    let dependency = r#"
+        pub fn double(n: Int) -> Int {
+          n * 2
+        }
+    "#;
+
+    let source_code = r#"
+        pub use foo/math
+    "#;
+
+    assert!(matches!(
+        check_with_deps(
+            parse(source_code),
+            vec![("foo/math".to_string(), parse(dependency))],
+        ),
+        Err((_, Error::ModuleReExported { .. }))
+    ))
+}
+
+#[test]
+fn field_access_through_nested_generic_alias() {
+    let source_code = r#"
+        pub type Box<a> {
+          inner: a,
+        }
+
+        pub type Nested<a> = Box<Box<a>>
+
+        pub type Labelled<a, b> = Box<(a, b)>
+
+        pub fn unwrap(n: Nested<Int>) -> Int {
+          n.inner.inner
+        }
+
+        pub fn label(l: Labelled<a, b>) -> a {
+          let (first, _) = l.inner
+          first
+        }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn field_access_on_module_constant() {
+    let source_code = r#"
+        pub type Point {
+          x: Int,
+          y: Int,
+        }
+
+        pub const origin: Point = Point { x: 0, y: 0 }
+
+        test foo() {
+          origin.x == origin.y
+        }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn field_access_on_imported_constant_and_alias() {
+    let dependency = r#"
+        pub type Box<a> {
+          inner: a,
+        }
+
+        pub type Boxes<a> = Box<List<a>>
+
+        pub const empty: Boxes<Int> = Box { inner: [] }
+    "#;
+
+    let source_code = r#"
+        use foo/box.{Boxes}
+
+        pub fn contents(b: Boxes<Int>) -> List<Int> {
+          b.inner
+        }
+
+        test foo() {
+          contents(box.empty) == box.empty.inner
+        }
+    "#;
+
+    let mut dependency = parse(dependency);
+    dependency.name = "foo/box".to_string();
+
+    let (warnings, _) = check_with_deps(
+        parse(source_code),
+        vec![("foo/box".to_string(), dependency)],
+    )
+    .unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn field_access_shared_by_all_constructors() {
+    let source_code = r#"
+        pub type Shape {
+          Circle { id: Int, radius: Int }
+          Square { id: Int, side: Int }
+        }
+
+        pub fn id(shape: Shape) -> Int {
+          shape.id
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn field_access_not_shared_by_all_constructors() {
+    let source_code = r#"
+        pub type Shape {
+          Circle { id: Int, radius: Int }
+          Square { id: Int, side: Int }
+        }
+
+        pub fn radius(shape: Shape) -> Int {
+          shape.radius
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::UnknownRecordField { .. }))
+    ))
+}
+
+#[test]
+fn field_access_shared_at_different_positions() {
+    let source_code = r#"
+        pub type Shape {
+          Circle { id: Int, radius: Int }
+          Square { side: Int, id: Int }
+        }
+
+        pub fn id(shape: Shape) -> Int {
+          shape.id
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::UnknownRecordField { .. }))
+    ))
+}
+
+#[test]
+fn field_access_shared_with_different_types() {
+    let source_code = r#"
+        pub type Shape {
+          Circle { id: Int, radius: Int }
+          Square { id: ByteArray, side: Int }
+        }
+
+        pub fn id(shape: Shape) -> Int {
+          shape.id
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::UnknownRecordField { .. }))
+    ))
+}
+
+#[test]
+fn warn_floored_division_with_negative_operand() {
+    let source_code = r#"
+        pub fn half(n: Int) -> Int {
+          n / -2
+        }
+
+        pub fn parity() -> Int {
+          -7 % 2
+        }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(matches!(
+        warnings.as_slice(),
+        [
+            Warning::FlooredDivisionWithNegativeOperand { operator: div, .. },
+            Warning::FlooredDivisionWithNegativeOperand { operator: modulo, .. },
+        ] if div == "/" && modulo == "%"
+    ));
+}
+
+#[test]
+fn no_warning_floored_division_with_same_signs() {
+    let source_code = r#"
+        pub fn foo(n: Int) -> Int {
+          n / 2 + -7 % -2
+        }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn division_by_zero() {
+    let source_code = r#"
+        pub fn foo(n: Int) -> Int {
+          n / 0
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::DivisionByZero { .. }))
+    ))
+}
+
+#[test]
+fn modulo_by_zero() {
+    let source_code = r#"
+        pub fn foo(n: Int) -> Int {
+          n % -0x00
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::DivisionByZero { .. }))
+    ))
+}
+
+#[test]
+fn division_by_non_zero() {
+    let source_code = r#"
+        pub fn foo(n: Int) -> Int {
+          n / 10 + n % 100
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn division_by_bound_zero_left_to_runtime() {
+    let source_code = r#"
+        const zero = 0
+
+        pub fn foo(n: Int) -> Int {
+          let also_zero = 0
+          n / zero + n % also_zero
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn assert_with_message() {
+    let source_code = r#"
+        pub fn positive(n: Int) -> Int {
+          assert n > 0, @"n must be positive"
+          n
+        }
+
+        test foo() {
+          assert positive(1) == 1, "positive(1) is 1"
+        }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn assert_non_boolean_condition() {
+    let source_code = r#"
+        test foo() {
+          assert 42, @"not a boolean"
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
+#[test]
+fn assert_remains_a_valid_identifier() {
+    let source_code = r#"
+        fn assert(condition: Bool) -> Bool {
+          condition
+        }
+
+        test foo() {
+          assert(True)
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn warn_if_literal_conditions() {
+    let source_code = r#"
+        pub fn foo(n: Int) -> Int {
+          if False {
+            0
+          } else if n > 0 {
+            1
+          } else if True {
+            2
+          } else {
+            3
+          }
+        }
+    "#;
+
+    let (warnings, module) = check(parse(source_code)).unwrap();
+
+    assert!(matches!(
+        warnings.as_slice(),
+        [
+            Warning::LiteralIfCondition { always: false, .. },
+            Warning::LiteralIfCondition { always: true, .. },
+        ]
+    ));
+
+    // Only the 'n > 0' branch remains, with the body of the 'True' branch as final else.
+    let body = module
+        .definitions()
+        .find_map(|def| match def {
+            Definition::Fn(f) => Some(&f.body),
+            _ => None,
+        })
+        .unwrap();
+
+    assert!(matches!(
+        body,
+        TypedExpr::If { branches, final_else, .. }
+            if branches.len() == 1 && matches!(final_else.as_ref(), TypedExpr::UInt { value, .. } if value == "2")
+    ));
+}
+
+#[test]
+fn fold_if_always_true() {
+    let source_code = r#"
+        pub fn foo() -> Int {
+          if True {
+            1
+          } else {
+            2
+          }
+        }
+    "#;
+
+    let (warnings, module) = check(parse(source_code)).unwrap();
+
+    assert!(matches!(
+        warnings.as_slice(),
+        [Warning::LiteralIfCondition { always: true, .. }]
+    ));
+
+    assert!(module.definitions().any(|def| matches!(
+        def,
+        Definition::Fn(f) if matches!(&f.body, TypedExpr::UInt { value, .. } if value == "1")
+    )));
+}
+
+#[test]
+fn script_purposes_follow_ledger_layout() {
+    let prelude = builtins::prelude(&IdGenerator::new());
+
+    assert_eq!(
+        prelude.types_constructors[well_known::SCRIPT_PURPOSE],
+        well_known::SCRIPT_PURPOSE_CONSTRUCTORS,
+        "script purposes must be declared in the ledger's order"
+    );
+
+    let script_info = uplc::tx::ledger_spec::plutus_v3("ScriptInfo");
+
+    assert_eq!(
+        script_info.len(),
+        well_known::SCRIPT_PURPOSE_CONSTRUCTORS.len()
+    );
+
+    for (constructor, purpose) in script_info
+        .iter()
+        .zip(well_known::SCRIPT_PURPOSE_CONSTRUCTORS)
+    {
+        assert_eq!(
+            prelude.values[*purpose].tipo.fn_arity(),
+            Some(constructor.fields.len()),
+            "{purpose} vs {}",
+            constructor.name
+        );
+    }
+
+    assert_eq!(
+        prelude.types_constructors[well_known::SCRIPT_CONTEXT],
+        [
+            well_known::SCRIPT_CONTEXT_TRANSACTION,
+            well_known::SCRIPT_CONTEXT_REDEEMER,
+            well_known::SCRIPT_CONTEXT_PURPOSE,
+        ]
+    );
+}
+
+#[test]
+fn validator_multi_purpose_handler() {
+    let source_code = r#"
+      validator foo {
+        mint | withdraw(_redeemer: Data, _target: Data, _self: Data) {
+          True
+        }
+      }
+    "#;
+
+    let (warnings, module) = check_validator(parse(source_code)).expect("should type-check");
+
+    assert!(warnings.is_empty(), "no warnings: {warnings:#?}");
+
+    let Some(Definition::Validator(validator)) = module.definitions().next() else {
+        unreachable!("module should contain a validator")
+    };
+
+    assert_eq!(
+        validator
+            .handlers
+            .iter()
+            .map(|handler| handler.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["mint", "withdraw"]
+    );
+}
+
+#[test]
+fn validator_multi_purpose_handler_checked_once() {
+    let source_code = r#"
+      validator foo {
+        mint | withdraw(redeemer: Data, _target: Data, _self: Data) {
+          let unused = 42
+          redeemer == redeemer
+        }
+      }
+    "#;
+
+    let (warnings, _) = check_validator(parse(source_code)).expect("should type-check");
+
+    assert!(
+        matches!(
+            warnings.as_slice(),
+            [Warning::UnusedVariable { name, .. }] if name == "unused"
+        ),
+        "{warnings:#?}"
+    );
+}
+
+#[test]
+fn validator_multi_purpose_handler_arity_mismatch() {
+    let source_code = r#"
+      validator foo {
+        mint | spend(_redeemer: Data, _target: Data, _self: Data) {
+          True
+        }
+      }
+    "#;
+
+    assert!(matches!(
+        check_validator(parse(source_code)),
+        Err((_, Error::IncorrectValidatorArity { .. }))
+    ))
+}
+
+#[test]
+fn failure_labels_collects_literal_fail_and_todo() {
+    let source_code = r#"
+        fn foo(n: Int) -> Bool {
+          if n > 0 {
+            fail @"positive"
+          } else if n < 0 {
+            todo @"negative"
+          } else {
+            when n is {
+              0 -> True
+              _ -> fail
+            }
+          }
+        }
+    "#;
+
+    let (_, ast) = check(parse(source_code)).expect("should type-check");
+
+    let labels = ast
+        .definitions()
+        .find_map(|def| match def {
+            Definition::Fn(func) => Some(func.body.failure_labels()),
+            _ => None,
+        })
+        .expect("should have a function");
+
+    assert_eq!(labels, vec!["positive", "negative"]);
+}
+
+#[test]
+fn prelude_shadowing() {
+    let source_code = r#"
+        pub fn identity(a: a) -> a {
+          a
+        }
+
+        pub const flip: Int = 42
+
+        pub type Action {
+          Mint
+          Burn
+        }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(
+        matches!(
+            &warnings[..],
+            [
+                Warning::PreludeShadowed { name: identity, .. },
+                Warning::PreludeShadowed { name: flip, .. },
+            ] if identity == "identity" && flip == "flip"
+        ),
+        "{warnings:#?}"
+    );
+}
+
+#[test]
+fn reserved_identifier_in_pattern() {
+    let source_code = r#"
+        fn foo(xs: List<Int>) -> Int {
+          when xs is {
+            [x, ..__tail_0] -> x
+            [] -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::ReservedIdentifier { name, .. })) if name == "__tail_0"
+    ))
+}
+
+#[test]
+fn reserved_identifier_in_argument() {
+    let source_code = r#"
+        fn foo(__other_clauses_delayed: Int) -> Int {
+          42
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::ReservedIdentifier { name, .. })) if name == "__other_clauses_delayed"
+    ))
+}
+
+#[test]
+fn trace_formatting_helpers() {
+    let source_code = r#"
+        use aiken/bytearray
+        use aiken/string.{from_int}
+
+        fn foo(n: Int, bytes: ByteArray) -> Bool {
+          trace string.from_int(n)
+          trace bytearray.to_hex(bytes)
+          from_int(n) != @""
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn trace_formatting_helpers_are_not_in_prelude() {
+    let source_code = r#"
+        fn foo(n: Int) -> String {
+          string_from_int(n)
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::UnknownVariable { .. }))
+    ))
+}
+
+#[test]
+fn concat_strings_and_bytearrays() {
+    let source_code = r#"
+        fn greet(name: String) -> String {
+          @"Hello, " <> name <> @"!"
+        }
+
+        fn prefix(bytes: ByteArray) -> ByteArray {
+          #"ff" <> bytes
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn concat_illegal_operands() {
     let source_code = r#"
-        fn bar(n: Data) {
-          expect Some(a) = n
-          a
+        fn foo(a: Int, b: Int) -> Int {
+          a <> b
         }
     "#;
 
     assert!(matches!(
         check(parse(source_code)),
-        Err((_, Error::CastDataNoAnn { .. }))
+        Err((_, Error::CouldNotUnify { .. }))
     ))
 }
 
 #[test]
-fn allow_generic_expect_without_typecast() {
+fn concat_mixed_operands() {
     let source_code = r#"
-        pub fn unwrap(opt: Option<a>) -> a {
-          expect Some(a) = opt
-          a
+        fn foo(a: String, b: ByteArray) {
+          a <> b
         }
     "#;
 
-    assert!(check(parse(source_code)).is_ok())
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
 }
 
 #[test]
-fn allow_expect_into_custom_type_from_data_no_annotation() {
+fn concat_literals_is_folded() {
     let source_code = r#"
-        type OrderDatum {
-            requested_handle: ByteArray,
-            amount: Int,
-            other: Bool,
-        }
+        const greeting: String = @"Hello, " <> @"World!"
 
-        fn foo(datum: Data) {
-            expect OrderDatum { requested_handle, .. } = datum
-            requested_handle
+        fn foo() -> ByteArray {
+          #"0102" <> #"03"
         }
-
     "#;
 
-    assert!(check(parse(source_code)).is_ok())
+    let (_, ast) = check(parse(source_code)).unwrap();
+
+    let body = ast
+        .definitions()
+        .find_map(|def| match def {
+            Definition::Fn(func) => Some(&func.body),
+            _ => None,
+        })
+        .unwrap();
+
+    assert!(
+        matches!(body, TypedExpr::ByteArray { bytes, .. } if bytes == &[1, 2, 3]),
+        "{body:#?}"
+    );
 }
 
 #[test]
-fn forbid_expect_from_arbitrary_type() {
+fn large_tuples_in_signatures() {
     let source_code = r#"
-        type Foo {
-          x: Int
-        }
-
-        type Bar {
-          y: Int
+        fn small(pair: (Int, Int)) -> Int {
+          pair.1st
         }
 
-        fn bar(f: Foo) {
-          expect b: Bar = f
-          Void
+        fn large(triples: List<(Int, Int, Int)>) -> Int {
+          0
         }
     "#;
 
-    assert!(matches!(
-        check(parse(source_code)),
-        Err((_, Error::CouldNotUnify { .. }))
-    ))
+    let (_, ast) = check(parse(source_code)).unwrap();
+
+    let warnings = ast.large_tuples(2);
+
+    let start = source_code.find("(Int, Int, Int)").unwrap();
+
+    assert!(
+        matches!(
+            &warnings[..],
+            [Warning::LargeTuple {
+                arity: 3,
+                max_arity: 2,
+                location,
+            }] if location.start == start && location.end == start + "(Int, Int, Int)".len()
+        ),
+        "{warnings:#?}"
+    )
 }
 
 #[test]
-fn allow_expect_into_opaque_type_constructor_without_typecasting_in_module() {
+fn large_tuples_in_expressions() {
     let source_code = r#"
-        opaque type Thing {
-          Foo(Int)
-          Bar(Int)
-        }
-
-        fn bar(thing: Thing) {
-          expect Foo(a) = thing
+        fn large() -> Int {
+          let (a, _, _) = (1, 2, 3)
           a
         }
     "#;
 
-    assert!(check(parse(source_code)).is_ok());
+    let (_, ast) = check(parse(source_code)).unwrap();
+
+    let warnings = ast.large_tuples(2);
+
+    let start = source_code.find("(1, 2, 3)").unwrap();
+
+    assert!(
+        matches!(
+            &warnings[..],
+            [Warning::LargeTuple {
+                arity: 3,
+                max_arity: 2,
+                location,
+            }] if location.start == start
+        ),
+        "{warnings:#?}"
+    )
 }
 
 #[test]
-fn forbid_importing_or_using_opaque_constructors() {
-    let dependency = r#"
-        pub opaque type Thing {
-          Foo(Int)
-          Bar(Int)
-        }
-    "#;
-
+fn fold_if_always_false() {
     let source_code = r#"
-        use foo/thing.{Thing, Foo}
-
-        fn bar(thing: Thing) {
-          expect Foo(a) = thing
-          a
+        pub fn foo() -> Int {
+          if False {
+            1
+          } else if False {
+            2
+          } else {
+            3
+          }
         }
     "#;
 
+    let (warnings, module) = check(parse(source_code)).unwrap();
+
     assert!(matches!(
-        check_with_deps(
-            parse(source_code),
-            vec![("foo/thing".to_string(), parse(dependency))],
-        ),
-        Err((_, Error::UnknownModuleField { .. })),
+        warnings.as_slice(),
+        [
+            Warning::LiteralIfCondition { always: false, .. },
+            Warning::LiteralIfCondition { always: false, .. },
+        ]
     ));
 
-    let source_code = r#"
-        use foo/thing.{Thing}
+    assert!(module.definitions().any(|def| matches!(
+        def,
+        Definition::Fn(f) if matches!(&f.body, TypedExpr::UInt { value, .. } if value == "3")
+    )));
+}
 
-        fn bar(thing: Thing) {
-          expect Foo(a) = thing
-          a
+#[test]
+fn do_not_fold_if_bound_conditions() {
+    let source_code = r#"
+        pub fn foo() -> Int {
+          let cond = True
+          if cond {
+            1
+          } else {
+            2
+          }
         }
     "#;
 
-    assert!(matches!(
-        check_with_deps(
-            parse(source_code),
-            vec![("foo/thing".to_string(), parse(dependency))],
-        ),
-        Err((_, Error::UnknownTypeConstructor { .. })),
-    ));
+    let (warnings, module) = check(parse(source_code)).unwrap();
+
+    assert!(warnings.is_empty());
+
+    assert!(module.definitions().any(|def| matches!(
+        def,
+        Definition::Fn(f) if matches!(
+            &f.body,
+            TypedExpr::Sequence { expressions, .. }
+                if matches!(expressions.last(), Some(TypedExpr::If { branches, .. }) if branches.len() == 1)
+        )
+    )));
 }
 
 #[test]
@@ -3064,6 +4523,113 @@ fn test_return_illegal() {
     ))
 }
 
+#[test]
+fn test_example() {
+    let source_code = r#"
+        fn double(n: Int) -> Int {
+            n * 2
+        }
+
+        @example(double)
+        test double_is_sum() {
+            double(21) == 21 + 21
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn test_example_unknown_function() {
+    let source_code = r#"
+        @example(triple)
+        test triple_is_sum() {
+            True
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::UnknownExampleFunction { name, .. })) if name == "triple"
+    ))
+}
+
+#[test]
+fn cfg_env() {
+    let source_code = r#"
+        @cfg(env = "mainnet")
+        pub const network_id = 1
+
+        @cfg(env = "default")
+        pub const network_id = 0
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::DuplicateConstName { .. }))
+    ));
+
+    let mut ast = parse(source_code);
+    ast.retain_configured(Some("mainnet"), false);
+    assert!(check(ast).is_ok());
+
+    let mut ast = parse(source_code);
+    ast.retain_configured(None, false);
+    assert!(check(ast).is_ok());
+}
+
+#[test]
+fn cfg_test() {
+    let source_code = r#"
+        @cfg(test)
+        fn fake_datum() -> Data {
+            Void
+        }
+
+        @cfg(test)
+        test datum_is_void() {
+            fake_datum() == Void
+        }
+
+        test always_true() {
+            True
+        }
+    "#;
+
+    let mut ast = parse(source_code);
+    ast.retain_configured(None, true);
+    assert_eq!(ast.definitions.len(), 3);
+    assert!(check(ast).is_ok());
+
+    let mut ast = parse(source_code);
+    ast.retain_configured(None, false);
+    assert_eq!(ast.definitions.len(), 1);
+    assert!(check(ast).is_ok());
+}
+
+#[test]
+fn cfg_unknown_environment() {
+    let source_code = r#"
+        @cfg(env = "mainet")
+        pub const network_id = 1
+
+        @cfg(env = "default")
+        pub const network_id = 0
+
+        @cfg(test)
+        fn fake_datum() -> Data {
+            Void
+        }
+    "#;
+
+    let env_modules = ["mainnet".to_string()];
+
+    assert!(matches!(
+        parse(source_code).unknown_environments(&env_modules)[..],
+        [Warning::UnknownEnvironment { ref name, .. }] if name == "mainet"
+    ));
+}
+
 #[test]
 fn validator_by_name() {
     let source_code = r#"
@@ -3662,3 +5228,156 @@ fn unused_record_fields_4() {
         }
     );
 }
+
+#[test]
+fn todo_unconditionally_reached_by_handler() {
+    let source_code = r#"
+      validator foo {
+        mint(_r, _p, _tx) {
+          trace @"minting"
+          todo
+        }
+
+        spend(_d: Option<Data>, r: Int, _oref, _tx) {
+          if r > 0 {
+            todo
+          } else {
+            False
+          }
+        }
+      }
+    "#;
+
+    let (warnings, _) = check_validator(parse(source_code)).unwrap();
+
+    assert!(matches!(
+        &warnings[..],
+        [
+            Warning::UnconditionalTodo { handler, .. },
+            Warning::Todo { .. },
+        ] if handler == "mint"
+    ))
+}
+
+#[test]
+fn expect_on_impossible_constructor() {
+    let source_code = r#"
+      pub fn foo() -> Int {
+        expect Some(x) = None
+        x
+      }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(matches!(
+        &warnings[..],
+        [Warning::ImpossibleExpect { expected, found, .. }] if expected == "'Some'" && found == "'None'"
+    ))
+}
+
+#[test]
+fn expect_on_impossible_list_length() {
+    let source_code = r#"
+      pub fn foo() -> Int {
+        expect [a, b, ..] = [1]
+        a + b
+      }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(matches!(&warnings[..], [Warning::ImpossibleExpect { .. }]))
+}
+
+#[test]
+fn record_pattern_punning_with_spread() {
+    let source_code = r#"
+      type Point {
+        x: Int,
+        y: Int,
+        z: Int,
+      }
+
+      pub fn sum(point: Point) -> Int {
+        let Point { x, z, .. } = point
+        x + z
+      }
+
+      pub fn first(points: List<Point>) -> Int {
+        when points is {
+          [Point { y, .. }, ..] -> y
+          _ -> 0
+        }
+      }
+    "#;
+
+    let (warnings, _) = check(parse(source_code)).unwrap();
+
+    assert!(warnings.is_empty(), "{warnings:#?}");
+}
+
+#[test]
+fn hidden_prelude() {
+    let source_code = r#"
+        fn foo(n: Int) -> Option<Int> {
+          Some(n)
+        }
+    "#;
+
+    let mut ast = parse(source_code);
+    ast.implicit_prelude = false;
+
+    assert!(matches!(check(ast), Err((_, Error::UnknownType { .. }))))
+}
+
+#[test]
+fn hidden_prelude_imported_explicitly() {
+    let source_code = r#"
+        use aiken.{Int, Option, Some}
+
+        pub fn foo(n: Int) -> Option<Int> {
+          expect n > 0
+          Some(n)
+        }
+    "#;
+
+    let mut ast = parse(source_code);
+    ast.implicit_prelude = false;
+
+    assert!(check(ast).is_ok())
+}
+
+#[test]
+fn walk_visits_parents_before_children() {
+    let source_code = r#"
+        fn foo(x: Int) -> Int {
+          let y = x + 1
+          y * 2
+        }
+    "#;
+
+    let (_, ast) = check(parse(source_code)).unwrap();
+
+    let Some(Definition::Fn(def)) = ast.definitions.first() else {
+        unreachable!("first definition isn't a function");
+    };
+
+    let mut visited = vec![];
+
+    def.body.walk(&mut |expr| {
+        visited.push(match expr {
+            TypedExpr::Sequence { .. } => "sequence".to_string(),
+            TypedExpr::Assignment { .. } => "let".to_string(),
+            TypedExpr::BinOp { .. } => "binop".to_string(),
+            TypedExpr::Var { name, .. } => name.clone(),
+            TypedExpr::UInt { value, .. } => value.clone(),
+            _ => unreachable!("unexpected expression {expr:#?}"),
+        })
+    });
+
+    assert_eq!(
+        visited,
+        ["sequence", "let", "binop", "x", "1", "binop", "y", "2"]
+    );
+}
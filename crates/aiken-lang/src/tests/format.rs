@@ -360,6 +360,104 @@ fn format_imports() {
     );
 }
 
+#[test]
+fn format_public_imports() {
+    assert_format!(
+        r#"
+        use aiken/list
+        pub use aiken/math.{max, abs}
+    "#
+    );
+}
+
+#[test]
+fn format_assert() {
+    assert_format!(
+        r#"
+        test foo() {
+          let x = 42
+          assert x > 0,   @"x must be positive"
+          assert x < 100, "x must be small"
+          x == 42
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_validator_multi_purpose_handler() {
+    assert_format!(
+        r#"
+            validator foo {
+              mint|withdraw(redeemer: Data, _target: Data, _self) {
+                redeemer == redeemer
+              }
+
+              spend(_datum, _redeemer, _output_reference, _self) {
+                True
+              }
+            }
+        "#
+    );
+}
+
+#[test]
+fn format_concat() {
+    assert_format!(
+        r#"
+            fn greet(name: String) -> String {
+              @"Hello, "<>name<>@"!"
+            }
+
+            fn is_prefixed(bytes: ByteArray) -> Bool {
+              #"ff"<>bytes == bytes
+            }
+        "#
+    );
+}
+
+#[test]
+fn format_test_example() {
+    assert_format!(
+        r#"
+            fn double(n: Int) -> Int {
+              n * 2
+            }
+
+            /// Doubling is the same as adding a number to itself.
+            @example( double )
+            test double_is_sum() {
+              double(21) == 21 + 21
+            }
+        "#
+    );
+}
+
+#[test]
+fn format_fixture_constant() {
+    assert_format!(
+        r#"
+            /// A transaction taken from the chain.
+            @fixture( "tx.cbor" )
+            const tx:ByteArray
+        "#
+    );
+}
+
+#[test]
+fn format_cfg() {
+    assert_format!(
+        r#"
+            /// Identifier of the network validators are compiled for.
+            @cfg( env = "mainnet" )
+            pub const network_id = 1
+
+            @cfg(test)
+            fn fake_datum() -> Data { Void }
+        "#
+    );
+}
+
 #[test]
 fn format_merge_imports() {
     assert_format!(
@@ -964,6 +1062,39 @@ fn format_anon_fn_pattern() {
     );
 }
 
+#[test]
+fn format_anon_fn_annotations() {
+    assert_format!(
+        r#"
+        pub fn main() {
+            let add = fn (acc:Int,n : Int)->Int { todo }
+            todo
+        }
+        "#
+    );
+}
+
+#[test]
+fn format_default_parameters() {
+    assert_format!(
+        r#"
+        fn foo(a: Int, b: Int=1, c: ByteArray   = #"00", d = -0x2a)  { a + b }
+        "#
+    );
+}
+
+#[test]
+fn format_use_backpassing() {
+    assert_format!(
+        r#"
+        fn foo() {
+          use x <- bar(a)
+          x
+        }
+        "#
+    );
+}
+
 #[test]
 fn format_validator_pattern() {
     assert_format!(
@@ -1002,6 +1133,31 @@ fn format_variadic_trace() {
     );
 }
 
+#[test]
+fn format_trace_severity() {
+    assert_format!(
+        r#"
+        fn foo() {
+            trace.debug @"foo": @"bar"
+            trace.info @"baz"
+            Void
+        }
+        "#
+    );
+}
+
+#[test]
+fn format_record_pattern_punning() {
+    assert_format!(
+        r#"
+        fn foo(point) {
+            let Point { x: x, y, .. } = point
+            x + y
+        }
+        "#
+    );
+}
+
 #[test]
 fn format_pattern_bytearray() {
     assert_format!(
@@ -1449,3 +1605,148 @@ fn capture_right_hand_side_assign() {
         "#
     );
 }
+
+#[test]
+fn format_with_max_columns() {
+    use crate::{ast::ModuleKind, format, parser};
+
+    fn pretty(src: &str) -> String {
+        let (module, extra) = parser::module(src, ModuleKind::Lib).expect("Failed to parse code");
+        let mut out = String::new();
+        format::pretty_with_max_columns(&mut out, module, extra, src, 16);
+        out
+    }
+
+    let src = indoc::indoc! {r#"
+        fn foo() {
+          a |> b |> c |> d
+        }
+    "#};
+
+    let out = pretty(src);
+
+    pretty_assertions::assert_eq!(out, "fn foo() {\n  a\n    |> b\n    |> c\n    |> d\n}\n");
+
+    pretty_assertions::assert_eq!(pretty(&out), out, "formatting isn't idempotent");
+}
+
+#[test]
+fn format_tests_stay_next_to_definitions() {
+    assert_format!(
+        r#"
+        use aiken/list
+
+        fn double(n: Int) -> Int {
+          n * 2
+        }
+
+        /// Doubling twice is quadrupling.
+        test double_twice() {
+          double(double(1)) == 4
+        }
+
+        validator foo {
+          mint(_redeemer: Data, _policy_id: ByteArray, _self: Data) {
+            True
+          }
+
+          else(_) {
+            fail
+          }
+        }
+
+        test foo_mints() {
+          True
+        }
+
+        fn triple(n: Int) -> Int {
+          n * 3
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_tests_grouped_at_end() {
+    assert_format!(
+        r#"
+        use aiken/list
+
+        fn double(n: Int) -> Int {
+          n * 2
+        }
+
+        /// Doubling twice is quadrupling.
+        test double_twice() {
+          double(double(1)) == 4
+        }
+
+        validator foo {
+          mint(_redeemer: Data, _policy_id: ByteArray, _self: Data) {
+            True
+          }
+
+          else(_) {
+            fail
+          }
+        }
+
+        test foo_mints() {
+          True
+        }
+
+        fn triple(n: Int) -> Int {
+          n * 3
+        }
+    "#,
+        crate::format::FormatOptions {
+            tests: crate::format::TestPlacement::AtEnd,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn format_break_pipelines() {
+    assert_format!(
+        r#"
+        fn foo() {
+          a |> b
+        }
+
+        fn bar() {
+          a |> b |> c(1, 2)
+        }
+    "#,
+        crate::format::FormatOptions {
+            break_pipelines: true,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn format_align_clause_arrows() {
+    assert_format!(
+        r#"
+        fn foo(x) {
+          when x is {
+            0 -> "zero"
+            1 | 2 -> "small"
+            _ -> "large"
+          }
+        }
+
+        fn bar(x) {
+          when x is {
+            Some(Pair(a, b)) -> a + b
+            None -> 0
+          }
+        }
+    "#,
+        crate::format::FormatOptions {
+            align_clause_arrows: true,
+            ..Default::default()
+        }
+    );
+}
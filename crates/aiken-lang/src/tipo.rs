@@ -11,6 +11,7 @@ use itertools::Itertools;
 use std::{cell::RefCell, collections::HashMap, ops::Deref, rc::Rc};
 use uplc::{ast::Type as UplcType, builtins::DefaultFunction};
 
+mod always_fails;
 pub(crate) mod environment;
 pub mod error;
 mod exhaustive;
@@ -398,6 +399,34 @@ impl Type {
         }
     }
 
+    /// Number of elements of the largest tuple found anywhere in this type; 0 if none.
+    pub fn max_tuple_arity(&self) -> usize {
+        match self {
+            Self::App { args, .. } => args
+                .iter()
+                .map(|arg| arg.max_tuple_arity())
+                .max()
+                .unwrap_or(0),
+            Self::Fn { args, ret, .. } => args
+                .iter()
+                .chain(std::iter::once(ret))
+                .map(|arg| arg.max_tuple_arity())
+                .max()
+                .unwrap_or(0),
+            Self::Var { tipo, .. } => match tipo.borrow().deref() {
+                TypeVar::Link { tipo } => tipo.max_tuple_arity(),
+                TypeVar::Unbound { .. } | TypeVar::Generic { .. } => 0,
+            },
+            Self::Tuple { elems, .. } => elems
+                .iter()
+                .map(|elem| elem.max_tuple_arity())
+                .max()
+                .unwrap_or(0)
+                .max(elems.len()),
+            Self::Pair { fst, snd, .. } => fst.max_tuple_arity().max(snd.max_tuple_arity()),
+        }
+    }
+
     pub fn is_pair(&self) -> bool {
         match self {
             Self::Var { tipo, .. } => tipo.borrow().is_pair(),
@@ -1336,6 +1365,8 @@ pub struct TypeInfo {
     pub values: HashMap<String, ValueConstructor>,
     pub accessors: HashMap<String, AccessorsMap>,
     pub annotations: HashMap<Annotation, Rc<Type>>,
+    /// Values and types re-exported with 'pub use', mapped to the module defining them.
+    pub reexports: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -0,0 +1,281 @@
+use super::{error::Warning, ModuleValueConstructor, ValueConstructorVariant};
+use crate::{
+    ast::{AssignmentKind, BinOp, Definition, Pattern, Span, TypedDefinition},
+    expr::TypedExpr,
+};
+
+/// A post-typing analysis looking for expressions that are guaranteed to fail at runtime:
+///
+/// - `todo` evaluated unconditionally by a validator handler;
+/// - `expect` whose pattern can never match the value on its right-hand side.
+///
+/// Warnings about a `todo` always reached by a handler supersede the generic 'todo' warning
+/// emitted during type-checking.
+pub fn check(definitions: &[TypedDefinition], warnings: &mut Vec<Warning>) {
+    for definition in definitions {
+        match definition {
+            Definition::Fn(function) => impossible_expects(&function.body, warnings),
+            Definition::Test(function) | Definition::Benchmark(function) => {
+                impossible_expects(&function.body, warnings)
+            }
+            Definition::Validator(validator) => {
+                for handler in validator.handlers.iter() {
+                    impossible_expects(&handler.body, warnings);
+
+                    if let Some(location) = unconditional_failure(&handler.body) {
+                        unconditional_todo(location, &handler.name, warnings);
+                    }
+                }
+
+                impossible_expects(&validator.fallback.body, warnings);
+            }
+            Definition::ModuleConstant(constant) => impossible_expects(&constant.value, warnings),
+            Definition::TypeAlias(..) | Definition::DataType(..) | Definition::Use(..) => (),
+        }
+    }
+}
+
+fn unconditional_todo(location: Span, handler: &str, warnings: &mut [Warning]) {
+    if let Some(warning) = warnings.iter_mut().find(
+        |warning| matches!(warning, Warning::Todo { location: todo, .. } if *todo == location),
+    ) {
+        *warning = Warning::UnconditionalTodo {
+            location,
+            handler: handler.to_string(),
+        };
+    }
+}
+
+/// Find an error term that is evaluated on every path through the given expression, if any.
+/// This doesn't look into branches (when/if), lazy operands (and/or) nor function bodies.
+fn unconditional_failure(expr: &TypedExpr) -> Option<Span> {
+    match expr {
+        TypedExpr::ErrorTerm { location, .. } => Some(*location),
+
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            expressions.iter().find_map(unconditional_failure)
+        }
+
+        TypedExpr::Trace { text, then, .. } => {
+            unconditional_failure(text).or_else(|| unconditional_failure(then))
+        }
+
+        TypedExpr::Assignment { value, .. } => unconditional_failure(value),
+
+        TypedExpr::Call { fun, args, .. } => args
+            .iter()
+            .find_map(|arg| unconditional_failure(&arg.value))
+            .or_else(|| unconditional_failure(fun)),
+
+        TypedExpr::BinOp {
+            name: BinOp::And | BinOp::Or,
+            left,
+            ..
+        } => unconditional_failure(left),
+
+        TypedExpr::BinOp { left, right, .. } => {
+            unconditional_failure(left).or_else(|| unconditional_failure(right))
+        }
+
+        TypedExpr::When { subject, .. } => unconditional_failure(subject),
+
+        TypedExpr::If { branches, .. } => unconditional_failure(&branches.first().condition),
+
+        TypedExpr::List { elements, tail, .. } => elements
+            .iter()
+            .chain(tail.as_deref())
+            .find_map(unconditional_failure),
+
+        TypedExpr::Tuple { elems, .. } => elems.iter().find_map(unconditional_failure),
+
+        TypedExpr::Pair { fst, snd, .. } => {
+            unconditional_failure(fst).or_else(|| unconditional_failure(snd))
+        }
+
+        TypedExpr::RecordAccess { record, .. } => unconditional_failure(record),
+
+        TypedExpr::TupleIndex { tuple, .. } => unconditional_failure(tuple),
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            unconditional_failure(spread).or_else(|| {
+                args.iter()
+                    .find_map(|arg| unconditional_failure(&arg.value))
+            })
+        }
+
+        TypedExpr::UnOp { value, .. } => unconditional_failure(value),
+
+        TypedExpr::UInt { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::ByteArray { .. }
+        | TypedExpr::CurvePoint { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::Fn { .. }
+        | TypedExpr::ModuleSelect { .. } => None,
+    }
+}
+
+fn impossible_expects(expr: &TypedExpr, warnings: &mut Vec<Warning>) {
+    match expr {
+        TypedExpr::Assignment {
+            value,
+            pattern,
+            kind: AssignmentKind::Expect { .. },
+            ..
+        } => {
+            if let Some((expected, found)) = mismatch(pattern, value) {
+                warnings.push(Warning::ImpossibleExpect {
+                    location: pattern.location(),
+                    value_location: value.location(),
+                    expected,
+                    found,
+                });
+            }
+            impossible_expects(value, warnings);
+        }
+
+        TypedExpr::Assignment { value, .. } => impossible_expects(value, warnings),
+
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            expressions
+                .iter()
+                .for_each(|expr| impossible_expects(expr, warnings));
+        }
+
+        TypedExpr::Fn { body, .. } => impossible_expects(body, warnings),
+
+        TypedExpr::List { elements, tail, .. } => elements
+            .iter()
+            .chain(tail.as_deref())
+            .for_each(|expr| impossible_expects(expr, warnings)),
+
+        TypedExpr::Call { fun, args, .. } => {
+            impossible_expects(fun, warnings);
+            args.iter()
+                .for_each(|arg| impossible_expects(&arg.value, warnings));
+        }
+
+        TypedExpr::BinOp { left, right, .. } => {
+            impossible_expects(left, warnings);
+            impossible_expects(right, warnings);
+        }
+
+        TypedExpr::Trace { then, text, .. } => {
+            impossible_expects(text, warnings);
+            impossible_expects(then, warnings);
+        }
+
+        TypedExpr::When {
+            subject, clauses, ..
+        } => {
+            impossible_expects(subject, warnings);
+            clauses
+                .iter()
+                .for_each(|clause| impossible_expects(&clause.then, warnings));
+        }
+
+        TypedExpr::If {
+            branches,
+            final_else,
+            ..
+        } => {
+            branches.iter().for_each(|branch| {
+                impossible_expects(&branch.condition, warnings);
+                impossible_expects(&branch.body, warnings);
+            });
+            impossible_expects(final_else, warnings);
+        }
+
+        TypedExpr::Tuple { elems, .. } => elems
+            .iter()
+            .for_each(|expr| impossible_expects(expr, warnings)),
+
+        TypedExpr::Pair { fst, snd, .. } => {
+            impossible_expects(fst, warnings);
+            impossible_expects(snd, warnings);
+        }
+
+        TypedExpr::RecordAccess { record: value, .. }
+        | TypedExpr::TupleIndex { tuple: value, .. }
+        | TypedExpr::UnOp { value, .. } => impossible_expects(value, warnings),
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            impossible_expects(spread, warnings);
+            args.iter()
+                .for_each(|arg| impossible_expects(&arg.value, warnings));
+        }
+
+        TypedExpr::UInt { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::ByteArray { .. }
+        | TypedExpr::CurvePoint { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::ErrorTerm { .. } => (),
+    }
+}
+
+/// Describe, when it is statically known, why a pattern can never match a value. Only literal
+/// constructors and lists are considered; anything else may or may not match at runtime.
+fn mismatch<A, B>(pattern: &Pattern<A, B>, value: &TypedExpr) -> Option<(String, String)> {
+    match (pattern, value) {
+        (Pattern::Assign { pattern, .. }, _) => mismatch(pattern, value),
+
+        (Pattern::Constructor { name, .. }, _) => {
+            let constructor = constructor_name(value)?;
+            if constructor != name.as_str() {
+                Some((format!("'{name}'"), format!("'{constructor}'")))
+            } else {
+                None
+            }
+        }
+
+        (
+            Pattern::List {
+                elements: expected,
+                tail,
+                ..
+            },
+            TypedExpr::List {
+                elements: found,
+                tail: None,
+                ..
+            },
+        ) => {
+            let impossible = match tail {
+                None => expected.len() != found.len(),
+                Some(..) => expected.len() > found.len(),
+            };
+
+            if impossible {
+                Some((
+                    format!(
+                        "a list of {}{} element(s)",
+                        if tail.is_some() { "at least " } else { "" },
+                        expected.len()
+                    ),
+                    format!("a list of {} element(s)", found.len()),
+                ))
+            } else {
+                None
+            }
+        }
+
+        _ => None,
+    }
+}
+
+fn constructor_name(value: &TypedExpr) -> Option<&str> {
+    match value {
+        TypedExpr::Var { constructor, .. } => match &constructor.variant {
+            ValueConstructorVariant::Record { name, .. } => Some(name),
+            _ => None,
+        },
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Record { name, .. },
+            ..
+        } => Some(name),
+        TypedExpr::Call { fun, .. } => constructor_name(fun),
+        _ => None,
+    }
+}
@@ -7,10 +7,10 @@ use super::{
 };
 use crate::{
     ast::{
-        self, Annotation, CallArg, DataType, Definition, Function, ModuleConstant, ModuleKind,
-        RecordConstructor, RecordConstructorArg, Span, TypeAlias, TypedDefinition, TypedFunction,
-        TypedPattern, TypedValidator, UnqualifiedImport, UntypedArg, UntypedDefinition,
-        UntypedFunction, Use, Validator, PIPE_VARIABLE,
+        self, well_known, Annotation, CallArg, DataType, Definition, Function, ModuleConstant,
+        ModuleKind, RecordConstructor, RecordConstructorArg, Span, TraceSeverity, TypeAlias,
+        TypedDefinition, TypedFunction, TypedPattern, TypedValidator, UnqualifiedImport,
+        UntypedArg, UntypedDefinition, UntypedFunction, Use, Validator, PIPE_VARIABLE,
     },
     tipo::{fields::FieldMap, TypeAliasAnnotation},
     IdGenerator,
@@ -86,6 +86,9 @@ pub struct Environment<'a> {
     /// The user-defined target environment referred to as the module 'env'.
     pub target_env: Option<&'a str>,
 
+    /// User-defined traces below this severity are compiled out.
+    pub trace_severity: TraceSeverity,
+
     /// Warnings
     pub warnings: &'a mut Vec<Warning>,
 }
@@ -263,6 +266,7 @@ impl<'a> Environment<'a> {
                 return_type,
                 end_position,
                 on_test_failure,
+                example,
             }) => {
                 // Lookup the inferred function information
                 let function = self
@@ -308,6 +312,7 @@ impl<'a> Environment<'a> {
                     body,
                     end_position,
                     on_test_failure,
+                    example,
                 })
             }
             Definition::Validator(Validator {
@@ -774,6 +779,7 @@ impl<'a> Environment<'a> {
         importable_modules: &'a HashMap<String, TypeInfo>,
         warnings: &'a mut Vec<Warning>,
         target_env: Option<&'a str>,
+        trace_severity: TraceSeverity,
     ) -> Self {
         let prelude = importable_modules
             .get("aiken")
@@ -803,9 +809,26 @@ impl<'a> Environment<'a> {
             entity_usages: vec![HashMap::new()],
             validator_params: HashSet::new(),
             target_env,
+            trace_severity,
         }
     }
 
+    /// Take the built-in prelude out of scope, so that its types and values must be imported
+    /// (e.g. `use aiken.{Option, Some, None}`) like any other module's. Booleans and 'Void' are
+    /// kept, as the language itself desugars to them.
+    pub fn hide_prelude(&mut self) {
+        let kept = |name: &String| {
+            [well_known::BOOL, well_known::VOID].contains(&name.as_str())
+                || well_known::BOOL_CONSTRUCTORS.contains(&name.as_str())
+                || well_known::VOID_CONSTRUCTORS.contains(&name.as_str())
+        };
+
+        self.module_types.retain(|name, _| kept(name));
+        self.module_types_constructors.retain(|name, _| kept(name));
+        self.accessors.retain(|name, _| kept(name));
+        self.scope.retain(|name, _| kept(name));
+    }
+
     /// Create a new generic type that can stand in for any type.
     pub fn new_generic_var(&mut self) -> Rc<Type> {
         Type::generic_var(self.next_uid())
@@ -835,6 +858,31 @@ impl<'a> Environment<'a> {
         self.previous_id
     }
 
+    /// Warn when a module alias is also the (unaliased) name of another known module, e.g.
+    /// `use foo/bar as list` while there's an `aiken/collection/list` around.
+    fn warn_on_shadowing_alias(&mut self, alias: &str, module: &[String], location: Span) {
+        let aliased = module.join("/");
+
+        if module.last().map(String::as_str) == Some(alias) {
+            return;
+        }
+
+        let shadowed = self
+            .importable_modules
+            .keys()
+            .filter(|name| *name != &aliased && name.rsplit('/').next() == Some(alias))
+            .min();
+
+        if let Some(shadowed) = shadowed {
+            self.warnings.push(Warning::ModuleAliasShadowsModule {
+                location,
+                alias: alias.to_string(),
+                aliased,
+                shadowed: shadowed.clone(),
+            });
+        }
+    }
+
     #[allow(clippy::result_large_err)]
     pub fn register_import(&mut self, def: &UntypedDefinition) -> Result<(), Error> {
         match def {
@@ -844,9 +892,18 @@ impl<'a> Environment<'a> {
                 unqualified,
                 location,
                 package: _,
+                public,
+                implicit,
             }) => {
                 let module_info = self.find_module(module, *location)?;
 
+                if *public && unqualified.is_empty() {
+                    return Err(Error::ModuleReExported {
+                        location: *location,
+                        name: module.join("/"),
+                    });
+                }
+
                 if module_info.kind.is_validator()
                     && (self.current_kind.is_lib() || self.current_kind.is_env())
                 {
@@ -863,6 +920,10 @@ impl<'a> Environment<'a> {
                     .expect("Typer could not identify module name.")
                     .clone();
 
+                if let Some(alias) = as_name.as_ref().filter(|_| !implicit) {
+                    self.warn_on_shadowing_alias(alias, module, *location);
+                }
+
                 // Insert unqualified imports into scope
                 for UnqualifiedImport {
                     name,
@@ -959,9 +1020,15 @@ impl<'a> Environment<'a> {
                                 .collect(),
                         });
                     }
+
+                    // Re-exported imports are part of the module's interface, and implicit
+                    // ones weren't written by anyone, so neither ever count as unused.
+                    if *public || *implicit {
+                        self.increment_usage(imported_name);
+                    }
                 }
 
-                if unqualified.is_empty() {
+                if unqualified.is_empty() && !implicit {
                     // When the module has no unqualified imports, we track its usage
                     // so we can warn if not used by the end of the type checking
                     self.unused_modules.insert(module_name.clone(), *location);
@@ -1216,6 +1283,19 @@ impl<'a> Environment<'a> {
 
         for (i, arg) in arguments.iter().enumerate() {
             field_map.insert(arg.arg_name(i).get_label(), i, &arg.location)?;
+
+            match (&arg.default, field_map.defaults.values().next()) {
+                (Some(default), _) => {
+                    field_map.defaults.insert(i, default.clone());
+                }
+                (None, Some(default)) => {
+                    return Err(Error::NonTrailingDefaultParameter {
+                        location: arg.location,
+                        default_location: default.location(),
+                    });
+                }
+                (None, None) => (),
+            }
         }
         let field_map = field_map.into_option();
 
@@ -1267,6 +1347,8 @@ impl<'a> Environment<'a> {
     ) -> Result<(), Error> {
         match def {
             Definition::Fn(fun) => {
+                self.warn_on_prelude_shadowing(&fun.name, fun.location);
+
                 self.register_function(
                     &fun.name,
                     &fun.arguments,
@@ -1450,8 +1532,8 @@ impl<'a> Environment<'a> {
                     .tipo
                     .clone();
 
-                // If the custom type only has a single constructor then we can access the
-                // fields using the record.field syntax, so store any fields accessors.
+                // Fields shared by all constructors can be accessed using the record.field
+                // syntax, so store any fields accessors.
                 if let Some(accessors) = self.custom_type_accessors(constructors, &mut hydrator)? {
                     let map = AccessorsMap {
                         public: (*public && !*opaque),
@@ -1534,6 +1616,8 @@ impl<'a> Environment<'a> {
 
             Definition::ModuleConstant(ModuleConstant { name, location, .. }) => {
                 assert_unique_const_name(names, name, location)?;
+
+                self.warn_on_prelude_shadowing(name, *location);
             }
 
             Definition::Use { .. } | Definition::TypeAlias { .. } => {}
@@ -1541,6 +1625,20 @@ impl<'a> Environment<'a> {
         Ok(())
     }
 
+    fn warn_on_prelude_shadowing(&mut self, name: &str, location: Span) {
+        let prelude = self
+            .importable_modules
+            .get("aiken")
+            .expect("Unable to find prelude in importable modules");
+
+        if prelude.values.contains_key(name) {
+            self.warnings.push(Warning::PreludeShadowed {
+                location,
+                name: name.to_string(),
+            });
+        }
+    }
+
     /// Unify two types that should be the same.
     /// Any unbound type variables will be linked to the other type as they are the same.
     ///
@@ -1628,6 +1726,7 @@ impl<'a> Environment<'a> {
                     given: rhs,
                     situation: None,
                     rigid_type_names: HashMap::new(),
+                    notes: vec![],
                 }),
             };
         }
@@ -1729,6 +1828,7 @@ impl<'a> Environment<'a> {
                             given: rhs.clone(),
                             situation: None,
                             rigid_type_names: HashMap::new(),
+                            notes: vec![],
                         })?;
                 }
                 self.unify(retrn1.clone(), retrn2.clone(), location, false)
@@ -1738,6 +1838,7 @@ impl<'a> Environment<'a> {
                         given: rhs.clone(),
                         situation: None,
                         rigid_type_names: HashMap::new(),
+                        notes: vec![],
                     })
             }
 
@@ -1747,6 +1848,7 @@ impl<'a> Environment<'a> {
                 given: rhs.clone(),
                 situation: None,
                 rigid_type_names: HashMap::new(),
+                notes: vec![],
             }),
         }
     }
@@ -1987,6 +2089,7 @@ fn unify_enclosed_type(e1: Rc<Type>, e2: Rc<Type>, result: Result<(), Error>) ->
             situation,
             location,
             rigid_type_names,
+            notes,
             ..
         }) => Err(Error::CouldNotUnify {
             expected: e1,
@@ -1994,6 +2097,7 @@ fn unify_enclosed_type(e1: Rc<Type>, e2: Rc<Type>, result: Result<(), Error>) ->
             situation,
             location,
             rigid_type_names,
+            notes,
         }),
 
         _ => result,
@@ -2016,6 +2120,20 @@ fn assert_unique_type_name<'a>(
     }
 }
 
+/// Names starting with a double underscore are reserved for the code generator, which uses them
+/// for intermediate bindings (e.g. `__tail_0`, `__constr_fields_1`).
+#[allow(clippy::result_large_err)]
+pub(crate) fn assert_not_reserved_identifier(name: &str, location: Span) -> Result<(), Error> {
+    if name.starts_with("__") {
+        Err(Error::ReservedIdentifier {
+            location,
+            name: name.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
 #[allow(clippy::result_large_err)]
 fn assert_unique_value_name<'a>(
     names: &mut HashMap<String, &'a Span>,
@@ -2073,22 +2191,31 @@ fn get_compatible_record_fields<A>(
 ) -> Vec<(usize, &str, &Annotation)> {
     let mut compatible = vec![];
 
-    if constructors.len() > 1 {
-        return compatible;
-    }
-
-    let first = match constructors.first() {
-        Some(first) => first,
+    let (first, others) = match constructors.split_first() {
+        Some(split) => split,
         None => return compatible,
     };
 
-    for (index, first_argument) in first.arguments.iter().enumerate() {
+    'next_argument: for (index, first_argument) in first.arguments.iter().enumerate() {
         // Fields without labels do not have accessors
         let label = match first_argument.label.as_ref() {
             Some(label) => label.as_str(),
             None => continue,
         };
 
+        // With several constructors, a field is only accessible when every constructor has it,
+        // with the same label and type at the same position.
+        for constructor in others {
+            match constructor.arguments.get(index) {
+                Some(argument)
+                    if argument.label.as_deref() == Some(label)
+                        && argument
+                            .annotation
+                            .is_logically_equal(&first_argument.annotation) => {}
+                _ => continue 'next_argument,
+            }
+        }
+
         compatible.push((index, label, &first_argument.annotation))
     }
 
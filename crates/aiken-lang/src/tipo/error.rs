@@ -1,4 +1,7 @@
-use super::Type;
+use super::{
+    pretty::{Printer, TypeDiff},
+    Type,
+};
 use crate::{
     ast::{Annotation, BinOp, CallArg, LogicalOpChainKind, Span, UntypedFunction, UntypedPattern},
     error::ExtraData,
@@ -89,6 +92,8 @@ pub enum Error {
         given: Rc<Type>,
         situation: Option<UnifyErrorSituation>,
         rigid_type_names: HashMap<u64, String>,
+        #[label(collection)]
+        notes: Vec<LabeledSpan>,
     },
 
     #[error("I almost got caught in an infinite cycle of type definitions.\n")]
@@ -116,6 +121,22 @@ pub enum Error {
         label: String,
     },
 
+    #[error(
+        "I found an identifier starting with '{}': {}\n",
+        "__".if_supports_color(Stdout, |s| s.yellow()),
+        name.if_supports_color(Stdout, |s| s.purple()),
+    )]
+    #[diagnostic(code("illegal::identifier"))]
+    #[diagnostic(help(
+        "Identifiers starting with a double underscore are reserved for names generated by the compiler. Use a single '{discard}' to discard a value.",
+        discard = "_".if_supports_color(Stdout, |s| s.yellow())
+    ))]
+    ReservedIdentifier {
+        #[label("reserved")]
+        location: Span,
+        name: String,
+    },
+
     #[error("I found two declarations for the constant '{}'.\n", name.purple())]
     #[diagnostic(code("duplicate::constant"))]
     #[diagnostic(help(
@@ -525,22 +546,9 @@ If you really meant to return that last expression, try to replace it with the f
     )]
     #[diagnostic(url("https://aiken-lang.org/language-tour/control-flow#matching"))]
     #[diagnostic(code("non_exhaustive_pattern_match"))]
-    #[diagnostic(help(r#"Let bindings and when clauses must be exhaustive -- that is, they must cover all possible cases of the type they match. In {keyword_when}/{keyword_is} pattern-match, it is recommended to have an explicit branch for each constructor as it prevents future silly mistakes when adding new constructors to a type. However, you can also use the wildcard '{discard}' as a last branch to match any remaining result.
-
-In this particular instance, the following cases are unmatched:
-
-{missing}"#
-        , discard = "_".if_supports_color(Stdout, |s| s.yellow())
-        , keyword_is = "is".if_supports_color(Stdout, |s| s.purple())
-        , keyword_when = "when".if_supports_color(Stdout, |s| s.purple())
-        , missing = unmatched
-            .iter()
-            .map(|s| format!("─▶ {s}"))
-            .collect::<Vec<_>>()
-            .join("\n")
-    ))]
+    #[diagnostic(help("{}", suggest_exhaustive_match(*is_let, unmatched)))]
     NotExhaustivePatternMatch {
-        #[label("{}", if *is_let { "use when/is" } else { "non-exhaustive" })]
+        #[label("{}", if *is_let { "use expect or when/is" } else { "non-exhaustive" })]
         location: Span,
         unmatched: Vec<String>,
         is_let: bool,
@@ -560,6 +568,18 @@ In this particular instance, the following cases are unmatched:
         tipo: Rc<Type>,
     },
 
+    #[error("I discovered a parameter without default value after one with a default value.\n")]
+    #[diagnostic(code("unexpected::default_parameter"))]
+    #[diagnostic(help(r#"Parameters with default values can be omitted at call sites, but only if they come last. Otherwise, the remaining arguments would be ambiguous.
+
+To fix this, you'll need to either move this parameter before the ones with default values, or give it a default value too."#))]
+    NonTrailingDefaultParameter {
+        #[label("no default value")]
+        location: Span,
+        #[label("default value")]
+        default_location: Span,
+    },
+
     #[error("I discovered a positional argument after a label argument.\n")]
     #[diagnostic(url("https://aiken-lang.org/language-tour/functions#labeled-arguments"))]
     #[diagnostic(code("unexpected::positional_argument"))]
@@ -867,7 +887,7 @@ Perhaps, try the following:
     #[diagnostic(code("unknown::record_field"))]
     #[diagnostic(help(
         "{}",
-        suggest_neighbor(label, fields.iter(), "Did you forget to make it public?\nNote also that record access is only supported on types with a single constructor.")
+        suggest_neighbor(label, fields.iter(), "Did you forget to make it public?\nNote also that, on types with several constructors, record access is only supported for fields shared by all constructors (same label, type and position).")
     ))]
     UnknownRecordField {
         #[label("unknown field")]
@@ -964,6 +984,35 @@ The best thing to do from here is to remove it."#))]
         name: String,
     },
 
+    #[error("I caught a division by a literal zero.\n")]
+    #[diagnostic(code("arithmetic::division_by_zero"))]
+    #[diagnostic(help(
+        "Dividing by zero (with either {} or {}) always fails at runtime. If that's what you want, use {} instead to make it explicit.\n\nNote that I only look for literal zeros: a divisor that is zero through a constant or a variable still fails at runtime only.",
+        "/".if_supports_color(Stdout, |s| s.purple()),
+        "%".if_supports_color(Stdout, |s| s.purple()),
+        "fail".if_supports_color(Stdout, |s| s.purple()),
+    ))]
+    DivisionByZero {
+        #[label("literal zero")]
+        location: Span,
+    },
+
+    #[error(
+        "I found a '{}' without anything to re-export: '{}'\n",
+        "pub use".if_supports_color(Stdout, |s| s.cyan()),
+        name.if_supports_color(Stdout, |s| s.purple())
+    )]
+    #[diagnostic(code("illegal::re_export"))]
+    #[diagnostic(help(
+        "Only values and types can be re-exported, not entire modules. List what you want to re-export between braces, e.g. {}.",
+        format!("pub use {name}.{{foo, Bar}}").if_supports_color(Stdout, |s| s.cyan())
+    ))]
+    ModuleReExported {
+        #[label("nothing to re-export")]
+        location: Span,
+        name: String,
+    },
+
     #[error(
         "A validator must return {}.\n",
         "Bool"
@@ -1049,6 +1098,19 @@ The best thing to do from here is to remove it."#))]
         location: Span,
     },
 
+    #[error("I found an example referring to an unknown function: {name}.\n")]
+    #[diagnostic(code("unknown::example"))]
+    #[diagnostic(help(
+        "Tests marked as {example} illustrate a function defined in the same module, and are shown in its documentation. Yet, there's no function named {name} in this module.",
+        example = "@example(...)".if_supports_color(Stderr, |s| s.purple()),
+        name = name.if_supports_color(Stderr, |s| s.yellow()),
+    ))]
+    UnknownExampleFunction {
+        #[label("unknown function")]
+        location: Span,
+        name: String,
+    },
+
     #[error("I choked on a generic type left in an outward-facing interface.\n")]
     #[diagnostic(code("illegal::generic_in_abi"))]
     #[diagnostic(help(
@@ -1114,6 +1176,7 @@ impl ExtraData for Error {
             | Error::CouldNotUnify { .. }
             | Error::CyclicTypeDefinitions { .. }
             | Error::DuplicateArgument { .. }
+            | Error::ReservedIdentifier { .. }
             | Error::DuplicateConstName { .. }
             | Error::DuplicateField { .. }
             | Error::DuplicateImport { .. }
@@ -1137,6 +1200,7 @@ impl ExtraData for Error {
             | Error::MissingVarInAlternativePattern { .. }
             | Error::NotIndexable { .. }
             | Error::NotExhaustivePatternMatch { .. }
+            | Error::NonTrailingDefaultParameter { .. }
             | Error::NotFn { .. }
             | Error::PositionalArgumentAfterLabeled { .. }
             | Error::PrivateTypeLeak { .. }
@@ -1157,8 +1221,11 @@ impl ExtraData for Error {
             | Error::UnnecessarySpreadOperator { .. }
             | Error::UpdateMultiConstructorType { .. }
             | Error::ValidatorImported { .. }
+            | Error::ModuleReExported { .. }
+            | Error::DivisionByZero { .. }
             | Error::IncorrectTestArity { .. }
             | Error::IllegalTestType { .. }
+            | Error::UnknownExampleFunction { .. }
             | Error::GenericLeftAtBoundary { .. }
             | Error::UnexpectedMultiPatternAssignment { .. }
             | Error::ExpectOnOpaqueType { .. }
@@ -1200,12 +1267,14 @@ impl Error {
                 given,
                 situation: note,
                 rigid_type_names,
+                notes,
             } => Error::CouldNotUnify {
                 location,
                 expected: given,
                 given: expected,
                 situation: note,
                 rigid_type_names,
+                notes,
             },
             other => other,
         }
@@ -1239,6 +1308,7 @@ impl Error {
                 given,
                 location,
                 rigid_type_names,
+                notes,
                 ..
             } => Self::CouldNotUnify {
                 expected,
@@ -1246,10 +1316,31 @@ impl Error {
                 situation: Some(situation),
                 location,
                 rigid_type_names,
+                notes,
             },
             other => other,
         }
     }
+
+    /// Point at another place in the source explaining what was expected, e.g. an annotation
+    /// or a previously inferred expression. Notes on the very location of the error are dropped.
+    pub fn with_unify_error_note(mut self, note_location: Span, message: &str) -> Self {
+        if let Error::CouldNotUnify {
+            location,
+            ref mut notes,
+            ..
+        } = self
+        {
+            if note_location != location {
+                notes.push(LabeledSpan::new_with_span(
+                    Some(message.to_string()),
+                    note_location,
+                ));
+            }
+        }
+
+        self
+    }
 }
 
 fn suggest_neighbor<'a>(
@@ -1330,14 +1421,42 @@ fn suggest_constructor_pattern(
         .to_pretty_string(70)
 }
 
+/// Width beyond which mismatching types are shown as a diff rather than in full.
+const LARGE_TYPE_WIDTH: usize = 40;
+
 fn suggest_unify(
     expected: &Type,
     given: &Type,
     situation: &Option<UnifyErrorSituation>,
     rigid_type_names: &HashMap<u64, String>,
 ) -> String {
-    let expected_str = expected.to_pretty_with_names(rigid_type_names.clone(), 0);
-    let given_str = given.to_pretty_with_names(rigid_type_names.clone(), 0);
+    // A single printer for both types, so that a same generic variable gets a same name in
+    // each. Types are indented as in the messages below, should they wrap.
+    let mut printer = Printer::new();
+    printer.with_names(rigid_type_names.clone());
+
+    let expected_str = printer.pretty_print(expected, 4).trim_start().to_string();
+    let given_str = printer.pretty_print(given, 4).trim_start().to_string();
+
+    // Large types are only shown by their differences, which are otherwise easy to miss.
+    let diff = if expected_str != given_str
+        && expected_str.len().max(given_str.len()) > LARGE_TYPE_WIDTH
+    {
+        printer.diff(expected, given, 4)
+    } else {
+        None
+    };
+
+    printer.expand_aliases();
+
+    let (expected_aka, given_aka) = if diff.is_some() {
+        (String::new(), String::new())
+    } else {
+        (
+            aka(&mut printer, expected, &expected_str),
+            aka(&mut printer, given, &given_str),
+        )
+    };
 
     let (expected, given) = match (expected, given) {
         (
@@ -1375,15 +1494,27 @@ fn suggest_unify(
                 ),
             )
         }
-        _ => (
-            expected_str
-                .if_supports_color(Stdout, |s| s.green())
-                .to_string(),
-            given_str.if_supports_color(Stdout, |s| s.red()).to_string(),
-        ),
+        _ => {
+            let (expected_str, given_str) = match diff {
+                Some(ref diff) => (diff.left.trim_start(), diff.right.trim_start()),
+                None => (expected_str.as_str(), given_str.as_str()),
+            };
+
+            (
+                expected_str
+                    .if_supports_color(Stdout, |s| s.green())
+                    .to_string(),
+                given_str.if_supports_color(Stdout, |s| s.red()).to_string(),
+            )
+        }
     };
 
-    match situation {
+    let (expected, given) = (
+        format!("{expected}{expected_aka}"),
+        format!("{given}{given_aka}"),
+    );
+
+    let explanation = match situation {
         Some(UnifyErrorSituation::CaseClauseMismatch) => formatdoc! {
             r#"While comparing branches from a '{keyword_when}/{keyword_is}' expression, I realized not all branches have the same type.
 
@@ -1489,6 +1620,68 @@ fn suggest_unify(
             expected,
             given
         },
+    };
+
+    match diff {
+        Some(TypeDiff {
+            divergence: (expected, given),
+            ..
+        }) => formatdoc! {
+            r#"{}
+
+               Parts common to both types are shown as '_'. They first differ on {} versus {}."#,
+            explanation.trim_end(),
+            expected.if_supports_color(Stdout, |s| s.green()),
+            given.if_supports_color(Stdout, |s| s.red()),
+        },
+        None => explanation,
+    }
+}
+
+/// What a type stands for when it is, or contains, a type alias; so that domain types (e.g.
+/// 'PolicyId') show under their name without hiding their actual structure.
+fn aka(printer: &mut Printer, tipo: &Type, printed: &str) -> String {
+    let expanded = printer.pretty_print(tipo, 4).trim_start().to_string();
+
+    if expanded == printed {
+        String::new()
+    } else {
+        format!(" (aka {expanded})")
+            .if_supports_color(Stdout, |s| s.dimmed())
+            .to_string()
+    }
+}
+
+fn suggest_exhaustive_match(is_let: bool, unmatched: &[String]) -> String {
+    let missing = unmatched
+        .iter()
+        .map(|s| format!("─▶ {s}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if is_let {
+        formatdoc! {
+            r#"Let bindings must be exhaustive -- that is, they must cover all possible cases of the type they match. When a type has more than one constructor, you can either use '{keyword_expect}' instead of '{keyword_let}' if you know the value can only ever be of the expected shape (and accept that the program halts otherwise), or use a '{keyword_when}/{keyword_is}' expression to handle every case.
+
+               In this particular instance, the following cases are unmatched:
+
+               {missing}"#
+            , keyword_expect = "expect".if_supports_color(Stdout, |s| s.yellow())
+            , keyword_let = "let".if_supports_color(Stdout, |s| s.yellow())
+            , keyword_is = "is".if_supports_color(Stdout, |s| s.purple())
+            , keyword_when = "when".if_supports_color(Stdout, |s| s.purple())
+        }
+    } else {
+        formatdoc! {
+            r#"When clauses must be exhaustive -- that is, they must cover all possible cases of the type they match. In {keyword_when}/{keyword_is} pattern-match, it is recommended to have an explicit branch for each constructor as it prevents future silly mistakes when adding new constructors to a type. However, you can also use the wildcard '{discard}' as a last branch to match any remaining result.
+
+               In this particular instance, the following cases are unmatched:
+
+               {missing}"#
+            , discard = "_".if_supports_color(Stdout, |s| s.yellow())
+            , keyword_is = "is".if_supports_color(Stdout, |s| s.purple())
+            , keyword_when = "when".if_supports_color(Stdout, |s| s.purple())
+        }
     }
 }
 
@@ -1642,6 +1835,43 @@ pub enum Warning {
         tipo: Rc<Type>,
     },
 
+    #[error(
+        "I found a {} always reached by the {} handler.",
+        "todo".if_supports_color(Stderr, |s| s.purple()),
+        handler.if_supports_color(Stderr, |s| s.yellow()),
+    )]
+    #[diagnostic(help(
+        "Every execution of this handler goes through this {keyword_todo}, which halts the evaluation. As it stands, the validator will reject any transaction.",
+        keyword_todo = "todo".if_supports_color(Stderr, |s| s.purple()),
+    ))]
+    #[diagnostic(code("todo::unconditional"))]
+    UnconditionalTodo {
+        #[label("always fails")]
+        location: Span,
+        handler: String,
+    },
+
+    #[error(
+        "I found an {} {}",
+        "expect".if_supports_color(Stderr, |s| s.purple()),
+        "that can never succeed.".if_supports_color(Stderr, |s| s.yellow()),
+    )]
+    #[diagnostic(help(
+        "The pattern expects {expected} but the value is always {found}. This {keyword_expect} will therefore always fail at runtime.",
+        expected = expected.if_supports_color(Stderr, |s| s.bold()),
+        found = found.if_supports_color(Stderr, |s| s.bold()),
+        keyword_expect = "expect".if_supports_color(Stderr, |s| s.purple()),
+    ))]
+    #[diagnostic(code("expect::impossible"))]
+    ImpossibleExpect {
+        #[label("never matches")]
+        location: Span,
+        #[label("always {found}")]
+        value_location: Span,
+        expected: String,
+        found: String,
+    },
+
     #[error("I found a type hole in an annotation.")]
     #[diagnostic(code("unexpected::type_hole"))]
     UnexpectedTypeHole {
@@ -1678,6 +1908,104 @@ pub enum Warning {
         name: String,
     },
 
+    #[error(
+        "I found an if condition that is always {}.",
+        if *always { "True" } else { "False" }.if_supports_color(Stderr, |s| s.purple())
+    )]
+    #[diagnostic(help(
+        "{}. Is it a leftover debug toggle? I've left the dead code out of the compiled program, but you probably want to remove it from the source as well.",
+        if *always {
+            "Any branch coming after it is never taken"
+        } else {
+            "This branch is never taken"
+        }
+    ))]
+    #[diagnostic(code("if::literal_condition"))]
+    LiteralIfCondition {
+        #[label("always {}", if *always { "True" } else { "False" })]
+        location: Span,
+        always: bool,
+    },
+
+    #[error("I found a division with a negative operand, which rounds towards negative infinity.")]
+    #[diagnostic(help(
+        "Integer division ({div}) and modulo ({modulo}) floor their result: for example, {floored}. If you meant to round towards zero instead (i.e. {truncated}), use {quotient} or {remainder} from {builtin}.",
+        div = "/".if_supports_color(Stderr, |s| s.purple()),
+        modulo = "%".if_supports_color(Stderr, |s| s.purple()),
+        floored = "-7 / 2 == -4 and -7 % 2 == 1".if_supports_color(Stderr, |s| s.bold()),
+        truncated = "-7 / 2 == -3 and -7 % 2 == -1".if_supports_color(Stderr, |s| s.bold()),
+        quotient = "quotient_integer".if_supports_color(Stderr, |s| s.cyan()),
+        remainder = "remainder_integer".if_supports_color(Stderr, |s| s.cyan()),
+        builtin = "aiken/builtin".if_supports_color(Stderr, |s| s.purple()),
+    ))]
+    #[diagnostic(code("arithmetic::floored_division"))]
+    FlooredDivisionWithNegativeOperand {
+        #[label("'{operator}' rounds towards negative infinity")]
+        location: Span,
+        operator: String,
+    },
+
+    #[error(
+        "I found a conditional definition for an unknown environment: {}",
+        name.if_supports_color(Stderr, |s| s.purple()),
+    )]
+    #[diagnostic(help(
+        "Environments are the modules found in the 'env' folder, and none of them is called {name}. The definition is therefore never compiled; did you make a typo?",
+        name = name.if_supports_color(Stderr, |s| s.purple()),
+    ))]
+    #[diagnostic(code("cfg::unknown_env"))]
+    UnknownEnvironment {
+        #[label("unknown environment")]
+        location: Span,
+        name: String,
+    },
+
+    #[error("I found a tuple with {arity} elements.")]
+    #[diagnostic(help(
+        "Elements of a tuple are accessed by walking through them one by one, which gets costly as tuples grow larger than {max_arity} elements. Consider using a record with named fields instead; it is also easier to read!"
+    ))]
+    #[diagnostic(code("tuple::arity"))]
+    LargeTuple {
+        #[label("{arity}-tuple")]
+        location: Span,
+        arity: usize,
+        max_arity: usize,
+    },
+
+    #[error(
+        "I noticed a definition shadowing a prelude value: {}",
+        name.if_supports_color(Stderr, |s| s.default_color()),
+    )]
+    #[diagnostic(help(
+        "{name} is already defined in the prelude and available everywhere without import. Besides being confusing for readers, the compiler refers to some prelude definitions by name when generating code. Consider picking another name.",
+        name = name.if_supports_color(Stderr, |s| s.purple()),
+    ))]
+    #[diagnostic(code("shadowing::prelude"))]
+    PreludeShadowed {
+        #[label("shadows the prelude")]
+        location: Span,
+        name: String,
+    },
+
+    #[error(
+        "I noticed a module alias shadowing another module: {}",
+        alias.if_supports_color(Stderr, |s| s.default_color()),
+    )]
+    #[diagnostic(help(
+        "{alias} now refers to {aliased}, while it usually refers to {shadowed}. Consider picking another alias to avoid any confusion.",
+        alias = alias.if_supports_color(Stderr, |s| s.purple()),
+        aliased = aliased.if_supports_color(Stderr, |s| s.bold()),
+        shadowed = shadowed.if_supports_color(Stderr, |s| s.bold()),
+    ))]
+    #[diagnostic(code("import::alias_shadows_module"))]
+    ModuleAliasShadowsModule {
+        #[label("shadows {shadowed}")]
+        location: Span,
+        alias: String,
+        aliased: String,
+        shadowed: String,
+    },
+
     #[error(
         "I discovered an unused imported value: {}",
         name.if_supports_color(Stderr, |s| s.default_color()),
@@ -1868,6 +2196,8 @@ impl ExtraData for Warning {
             | Warning::SingleConstructorExpect { .. }
             | Warning::SingleWhenClause { .. }
             | Warning::Todo { .. }
+            | Warning::UnconditionalTodo { .. }
+            | Warning::ImpossibleExpect { .. }
             | Warning::UnexpectedTypeHole { .. }
             | Warning::UnusedConstructor { .. }
             | Warning::UnusedPrivateFunction { .. }
@@ -1876,6 +2206,12 @@ impl ExtraData for Warning {
             | Warning::UnusedVariable { .. }
             | Warning::DiscardedLetAssignment { .. }
             | Warning::ValidatorInLibraryModule { .. }
+            | Warning::ModuleAliasShadowsModule { .. }
+            | Warning::PreludeShadowed { .. }
+            | Warning::LargeTuple { .. }
+            | Warning::UnknownEnvironment { .. }
+            | Warning::FlooredDivisionWithNegativeOperand { .. }
+            | Warning::LiteralIfCondition { .. }
             | Warning::UseWhenInstead { .. } => None,
             Warning::Utf8ByteArrayIsValidHexString { value, .. } => Some(value.clone()),
             Warning::UnusedImportedModule { location, .. } => {
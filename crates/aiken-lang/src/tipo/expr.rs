@@ -1,6 +1,7 @@
 use super::{
     environment::{
-        assert_no_labeled_arguments, collapse_links, generalise, EntityKind, Environment,
+        assert_no_labeled_arguments, assert_not_reserved_identifier, collapse_links, generalise,
+        EntityKind, Environment,
     },
     error::{Error, Warning},
     hydrator::Hydrator,
@@ -12,10 +13,10 @@ use crate::{
     ast::{
         self, Annotation, ArgName, AssignmentKind, AssignmentPattern, BinOp, Bls12_381Point,
         ByteArrayFormatPreference, CallArg, Curve, Function, IfBranch, LogicalOpChainKind, Pattern,
-        RecordUpdateSpread, Span, TraceKind, TraceLevel, Tracing, TypedArg, TypedCallArg,
-        TypedClause, TypedIfBranch, TypedPattern, TypedRecordUpdateArg, TypedValidator, UnOp,
-        UntypedArg, UntypedAssignmentKind, UntypedClause, UntypedFunction, UntypedIfBranch,
-        UntypedPattern, UntypedRecordUpdateArg,
+        RecordUpdateSpread, Span, TraceKind, TraceLevel, TraceSeverity, Tracing, TypedArg,
+        TypedCallArg, TypedClause, TypedIfBranch, TypedPattern, TypedRecordUpdateArg,
+        TypedValidator, UnOp, UntypedArg, UntypedAssignmentKind, UntypedClause, UntypedFunction,
+        UntypedIfBranch, UntypedPattern, UntypedRecordUpdateArg,
     },
     builtins::{from_default_function, BUILTIN},
     expr::{FnStyle, TypedExpr, UntypedExpr},
@@ -54,6 +55,7 @@ pub(crate) fn infer_function(
         return_annotation,
         end_position,
         on_test_failure,
+        example,
         return_type: _,
     } = fun;
 
@@ -102,6 +104,13 @@ pub(crate) fn infer_function(
         .function_types()
         .unwrap_or_else(|| panic!("Preregistered type for fn {name} was not a fn"));
 
+    // Ensure default values match the type of their parameter
+    for (arg, tipo) in arguments.iter().zip(&args_types) {
+        if let Some(default) = &arg.default {
+            environment.unify(tipo.clone(), default.tipo(), default.location(), false)?;
+        }
+    }
+
     let warnings = environment.warnings.clone();
 
     // ━━━ open new scope ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
@@ -123,8 +132,12 @@ pub(crate) fn infer_function(
     expr_typer.not_yet_inferred = BTreeSet::from_iter(hydrators.keys().cloned());
 
     // Infer the type using the preregistered args + return types as a starting point
-    let inferred =
-        expr_typer.infer_fn_with_known_types(arguments, body.to_owned(), Some(return_type));
+    let inferred = expr_typer.infer_fn_with_known_types(
+        arguments,
+        body.to_owned(),
+        Some(return_type),
+        return_annotation.as_ref(),
+    );
 
     // We try to always perform a deep-first inferrence. So callee are inferred before callers,
     // since this provides better -- and necessary -- information in particular with regards to
@@ -160,6 +173,8 @@ pub(crate) fn infer_function(
 
     let (arguments, body, return_type) = inferred?;
 
+    expr_typer.check_comparisons()?;
+
     let args_types = arguments.iter().map(|a| a.tipo.clone()).collect();
 
     let tipo = Type::function(args_types, return_type);
@@ -206,6 +221,7 @@ pub(crate) fn infer_function(
             .expect("Could not find return type for fn"),
         body,
         on_test_failure: on_test_failure.clone(),
+        example: example.clone(),
         end_position: *end_position,
     };
 
@@ -234,6 +250,11 @@ pub(crate) struct ExprTyper<'a, 'b> {
     // to determine whether it is safe to generalise this expression after
     // it has been inferred.
     pub(crate) ungeneralised_function_used: bool,
+
+    // Operand types of equality comparisons, along with the location of the comparison. Those
+    // types may still be partially unknown when the comparison is inferred (e.g. arguments of an
+    // anonymous function), so they are checked again once the whole definition is inferred.
+    pub(crate) comparisons: Vec<(Rc<Type>, Span)>,
 }
 
 impl<'a, 'b> ExprTyper<'a, 'b> {
@@ -244,9 +265,23 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             environment,
             tracing,
             ungeneralised_function_used: false,
+            comparisons: vec![],
         }
     }
 
+    /// Ensure that no equality comparison ends up comparing functions (or values holding
+    /// functions), now that the types of the operands are known. These would otherwise compile to
+    /// 'equalsData' on non-data terms, and fail at runtime.
+    #[allow(clippy::result_large_err)]
+    pub fn check_comparisons(&mut self) -> Result<(), Error> {
+        for (tipo, location) in std::mem::take(&mut self.comparisons) {
+            ensure_serialisable(false, tipo, location)
+                .map_err(|_| Error::IllegalComparison { location })?;
+        }
+
+        Ok(())
+    }
+
     #[allow(clippy::result_large_err)]
     fn check_when_exhaustiveness(
         &mut self,
@@ -295,7 +330,11 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         // Check to see if the function accepts labelled arguments
         match self.get_field_map(&fun, location)? {
             // The fun has a field map so labelled arguments may be present and need to be reordered.
-            Some(field_map) => field_map.reorder(&mut args, location)?,
+            // Omitted trailing arguments are completed with their default values, if any.
+            Some(field_map) => {
+                field_map.fill_defaults(&mut args, location, |default| default.to_expr(location));
+                field_map.reorder(&mut args, location)?
+            }
 
             // The fun has no field map and so we error if arguments have been labelled
             None => assert_no_labeled_arguments(&args)
@@ -388,11 +427,11 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             }
         };
 
-        self.infer_fn_with_known_types(arguments, body, return_type)
+        self.infer_fn_with_known_types(arguments, body, return_type, return_annotation.as_ref())
     }
 
     #[allow(clippy::result_large_err)]
-    fn get_field_map(
+    pub(crate) fn get_field_map(
         &mut self,
         constructor: &TypedExpr,
         location: Span,
@@ -577,9 +616,40 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             UntypedExpr::TraceIfFalse { value, location } => {
                 self.infer_trace_if_false(*value, location)
             }
+
+            UntypedExpr::Assert {
+                location,
+                condition,
+                message,
+            } => self.infer_assert(*condition, *message, location),
         }
     }
 
+    /// An assertion is a mere 'if condition { Void } else { fail message }'.
+    #[allow(clippy::result_large_err)]
+    fn infer_assert(
+        &mut self,
+        condition: UntypedExpr,
+        message: UntypedExpr,
+        location: Span,
+    ) -> Result<TypedExpr, Error> {
+        let condition_location = condition.location();
+
+        self.infer_if(
+            vec1::vec1![IfBranch {
+                condition,
+                body: UntypedExpr::Var {
+                    location,
+                    name: "Void".to_string(),
+                },
+                is: None,
+                location: condition_location,
+            }],
+            UntypedExpr::fail(Some(message), location),
+            location,
+        )
+    }
+
     #[allow(clippy::result_large_err)]
     fn infer_bytearray(
         &mut self,
@@ -730,6 +800,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 for tipo in &[left.tipo(), right.tipo()] {
                     ensure_serialisable(false, tipo.clone(), location)
                         .map_err(|_| Error::IllegalComparison { location })?;
+
+                    self.comparisons.push((tipo.clone(), location));
                 }
 
                 return Ok(TypedExpr::BinOp {
@@ -740,6 +812,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     right: Box::new(right),
                 });
             }
+            BinOp::Concat => return self.infer_concat(left, right, location),
             BinOp::And => (Type::bool(), Type::bool()),
             BinOp::Or => (Type::bool(), Type::bool()),
             BinOp::LtInt => (Type::int(), Type::bool()),
@@ -773,6 +846,30 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         )
         .map_err(|e| e.operator_situation(name))?;
 
+        // Dividing by a literal zero is bound to fail, no need to wait for runtime to find out.
+        if matches!(name, BinOp::DivInt | BinOp::ModInt) && right.is_zero_int_literal() {
+            return Err(Error::DivisionByZero {
+                location: right.location(),
+            });
+        }
+
+        // Flooring and truncating divisions only disagree when operands have different signs.
+        if matches!(name, BinOp::DivInt | BinOp::ModInt)
+            && left.is_negative_int_literal() != right.is_negative_int_literal()
+        {
+            self.environment
+                .warnings
+                .push(Warning::FlooredDivisionWithNegativeOperand {
+                    location,
+                    operator: if matches!(name, BinOp::DivInt) {
+                        "/"
+                    } else {
+                        "%"
+                    }
+                    .to_string(),
+                });
+        }
+
         Ok(TypedExpr::BinOp {
             location,
             name,
@@ -782,6 +879,72 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         })
     }
 
+    /// Operands of '<>' must both be either 'String' or 'ByteArray'. When both are literals, the
+    /// concatenation is performed right away.
+    #[allow(clippy::result_large_err)]
+    fn infer_concat(
+        &mut self,
+        left: UntypedExpr,
+        right: UntypedExpr,
+        location: Span,
+    ) -> Result<TypedExpr, Error> {
+        let left = self.infer(left)?;
+
+        let right = self.infer(right)?;
+
+        self.unify(
+            left.tipo(),
+            right.tipo(),
+            right.type_defining_location(),
+            false,
+        )
+        .map_err(|e| e.operator_situation(BinOp::Concat))?;
+
+        let tipo = left.tipo();
+
+        if !tipo.is_string() {
+            self.unify(
+                Type::byte_array(),
+                tipo.clone(),
+                left.type_defining_location(),
+                false,
+            )
+            .map_err(|e| e.operator_situation(BinOp::Concat))?;
+        }
+
+        match (left, right) {
+            (TypedExpr::String { value: left, .. }, TypedExpr::String { value: right, .. }) => {
+                Ok(TypedExpr::String {
+                    location,
+                    tipo,
+                    value: format!("{left}{right}"),
+                })
+            }
+
+            (
+                TypedExpr::ByteArray {
+                    bytes: left,
+                    preferred_format,
+                    ..
+                },
+                TypedExpr::ByteArray { bytes: right, .. },
+            ) => Ok(TypedExpr::ByteArray {
+                location,
+                tipo,
+                bytes: [left, right].concat(),
+                preferred_format,
+            }),
+
+            (left, right) => Ok(TypedExpr::BinOp {
+                location,
+                name: BinOp::Concat,
+                tipo,
+                left: Box::new(left),
+                right: Box::new(right),
+            }),
+        }
+    }
+
     #[allow(clippy::result_large_err)]
     fn infer_record_update(
         &mut self,
@@ -1198,8 +1361,12 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             fields,
         };
 
+        // Aliases and generic instantiations are links to the actual record type, which is the
+        // one holding the accessors.
+        let record_type = collapse_links(record.tipo());
+
         // Check to see if it's a Type that can have accessible fields
-        let accessors = match collapse_links(record.tipo()).as_ref() {
+        let accessors = match record_type.as_ref() {
             // A type in the current module which may have fields
             Type::App { module, name, .. } if module == self.environment.current_module => {
                 self.environment.accessors.get(name)
@@ -1248,7 +1415,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             false,
         )?;
 
-        if let Type::App { name, .. } = record.tipo().as_ref() {
+        if let Type::App { name, .. } = record_type.as_ref() {
             self.environment.increment_usage(name);
         };
 
@@ -1276,6 +1443,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             location,
             doc,
             is_validator_param,
+            default,
         } = untyped_arg;
 
         let tipo = annotation
@@ -1301,6 +1469,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             tipo,
             is_validator_param,
             doc,
+            default,
         };
 
         Ok((typed_arg, extra_assignment))
@@ -1332,7 +1501,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 value_typ.clone(),
                 typed_value.type_defining_location(),
                 (kind.is_let() && ann_typ.is_data()) || kind.is_expect() || kind.if_is(),
-            )?;
+            )
+            .map_err(|e| {
+                e.with_unify_error_note(ann.location(), "expected because of this annotation")
+            })?;
 
             value_typ = ann_typ.clone();
 
@@ -1671,11 +1843,42 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             false,
         )?;
 
-        Ok(TypedExpr::If {
-            location,
-            branches: typed_branches,
-            final_else: Box::new(typed_final_else),
-            tipo: first_body_type,
+        // Branches guarded by a literal boolean are either never taken, or shadow every branch
+        // after them. Either way, that's likely a leftover and there's no need to generate code
+        // for dead branches.
+        let mut branches = Vec::with_capacity(typed_branches.len());
+        let mut final_else = typed_final_else;
+
+        for branch in typed_branches {
+            let literal = match branch.is {
+                None => branch.condition.literal_bool(),
+                Some(..) => None,
+            };
+
+            match literal {
+                Some(always) => {
+                    self.environment.warnings.push(Warning::LiteralIfCondition {
+                        location: branch.condition.location(),
+                        always,
+                    });
+
+                    if always {
+                        final_else = branch.body;
+                        break;
+                    }
+                }
+                None => branches.push(branch),
+            }
+        }
+
+        Ok(match Vec1::try_from_vec(branches) {
+            Ok(branches) => TypedExpr::If {
+                location,
+                branches,
+                final_else: Box::new(final_else),
+                tipo: first_body_type,
+            },
+            Err(..) => final_else,
         })
     }
 
@@ -1786,6 +1989,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         args: Vec<TypedArg>,
         body: UntypedExpr,
         return_type: Option<Rc<Type>>,
+        return_annotation: Option<&Annotation>,
     ) -> Result<(Vec<TypedArg>, TypedExpr, Rc<Type>), Error> {
         let location = body.location();
 
@@ -1819,7 +2023,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                             arg.location,
                         );
                     }
-                    ArgName::Named { .. } | ArgName::Discarded { .. } => (),
+                    ArgName::Discarded { name, location, .. } => {
+                        assert_not_reserved_identifier(name, *location)?
+                    }
+                    ArgName::Named { .. } => (),
                 };
             }
 
@@ -1845,8 +2052,17 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     return_type.is_data(),
                 )
                 .map_err(|e| {
-                    e.return_annotation_mismatch()
-                        .with_unify_error_rigid_names(&body_rigid_names)
+                    let e = e
+                        .return_annotation_mismatch()
+                        .with_unify_error_rigid_names(&body_rigid_names);
+
+                    match return_annotation {
+                        Some(annotation) => e.with_unify_error_note(
+                            annotation.location(),
+                            "expected because of this annotation",
+                        ),
+                        None => e,
+                    }
                 })?;
 
                 Type::with_alias(body.tipo(), return_type.alias())
@@ -2417,6 +2633,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
         let then = self.infer(then)?;
 
+        if kind == TraceKind::Debug && self.environment.trace_severity > TraceSeverity::Debug {
+            return Ok(then);
+        }
+
         let tipo = then.tipo();
 
         if let TraceKind::Todo = kind {
@@ -2614,7 +2834,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let subject_type = typed_subject.tipo();
         let return_type = self.new_unbound_var();
 
-        let mut typed_clauses = Vec::new();
+        let mut typed_clauses: Vec<TypedClause> = Vec::new();
         for clause in clauses {
             for typed_clause in self.infer_clause(clause, &subject_type)? {
                 self.unify(
@@ -2623,7 +2843,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     typed_clause.location(),
                     false,
                 )
-                .map_err(|e| e.case_clause_mismatch())?;
+                .map_err(|e| match typed_clauses.first() {
+                    Some(first) => e.case_clause_mismatch().with_unify_error_note(
+                        first.then.type_defining_location(),
+                        "expected because of this branch",
+                    ),
+                    None => e.case_clause_mismatch(),
+                })?;
 
                 typed_clauses.push(typed_clause)
             }
@@ -2732,6 +2958,7 @@ fn assert_no_assignment(expr: &UntypedExpr) -> Result<(), Error> {
         | UntypedExpr::Var { .. }
         | UntypedExpr::LogicalOpChain { .. }
         | UntypedExpr::TraceIfFalse { .. }
+        | UntypedExpr::Assert { .. }
         | UntypedExpr::When { .. }
         | UntypedExpr::CurvePoint { .. } => Ok(()),
     }
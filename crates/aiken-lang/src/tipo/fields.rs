@@ -1,5 +1,5 @@
 use super::error::{Error, UnknownLabels};
-use crate::ast::{CallArg, Span};
+use crate::ast::{CallArg, DefaultValue, Span};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
@@ -8,6 +8,7 @@ pub struct FieldMap {
     pub arity: usize,
     pub fields: HashMap<String, (usize, Span)>,
     pub is_function: bool,
+    pub defaults: HashMap<usize, DefaultValue>,
 }
 
 impl FieldMap {
@@ -16,6 +17,7 @@ impl FieldMap {
             arity,
             fields: HashMap::new(),
             is_function,
+            defaults: HashMap::new(),
         }
     }
 
@@ -49,6 +51,45 @@ impl FieldMap {
         }
     }
 
+    /// Complete an argument list with the default values of omitted trailing parameters. The list
+    /// is left untouched if any omitted parameter has no default value, so that the arity
+    /// mismatch gets reported when reordering.
+    pub fn fill_defaults<A>(
+        &self,
+        args: &mut Vec<CallArg<A>>,
+        location: Span,
+        into: impl Fn(&DefaultValue) -> A,
+    ) {
+        if self.defaults.is_empty() || args.len() >= self.arity {
+            return;
+        }
+
+        let positional = args.iter().take_while(|arg| arg.label.is_none()).count();
+
+        let supplied: HashSet<String> = args.iter().filter_map(|arg| arg.label.clone()).collect();
+
+        let missing = self
+            .fields
+            .iter()
+            .filter(|(label, (index, _))| *index >= positional && !supplied.contains(*label))
+            .map(|(label, (index, _))| (*index, label))
+            .sorted()
+            .collect::<Vec<_>>();
+
+        if missing
+            .iter()
+            .all(|(index, _)| self.defaults.contains_key(index))
+        {
+            for (index, label) in missing {
+                args.push(CallArg {
+                    label: Some(label.clone()),
+                    location,
+                    value: into(&self.defaults[&index]),
+                });
+            }
+        }
+    }
+
     /// Reorder an argument list so that labelled fields supplied out-of-order are
     /// in the correct order.
     #[allow(clippy::result_large_err)]
@@ -156,3 +197,77 @@ impl FieldMap {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_map(labels: &[&str]) -> FieldMap {
+        let mut field_map = FieldMap::new(labels.len(), true);
+        for (index, label) in labels.iter().enumerate() {
+            field_map
+                .insert(label.to_string(), index, &Span::empty())
+                .unwrap();
+        }
+        field_map
+    }
+
+    fn arg(label: Option<&str>, value: usize) -> CallArg<usize> {
+        CallArg {
+            label: label.map(str::to_string),
+            location: Span::create(value, 1),
+            value,
+        }
+    }
+
+    fn values(args: &[CallArg<usize>]) -> Vec<usize> {
+        args.iter().map(|arg| arg.value).collect()
+    }
+
+    #[test]
+    fn reorder_all_labeled() {
+        let mut args = vec![arg(Some("c"), 2), arg(Some("a"), 0), arg(Some("b"), 1)];
+
+        field_map(&["a", "b", "c"])
+            .reorder(&mut args, Span::empty())
+            .unwrap();
+
+        assert_eq!(values(&args), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn reorder_positional_then_labeled() {
+        let mut args = vec![
+            arg(None, 0),
+            arg(Some("d"), 3),
+            arg(Some("b"), 1),
+            arg(Some("c"), 2),
+        ];
+
+        field_map(&["a", "b", "c", "d"])
+            .reorder(&mut args, Span::empty())
+            .unwrap();
+
+        assert_eq!(values(&args), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn reorder_rejects_positional_after_labeled() {
+        let mut args = vec![arg(Some("b"), 1), arg(None, 0)];
+
+        assert!(matches!(
+            field_map(&["a", "b"]).reorder(&mut args, Span::empty()),
+            Err(Error::PositionalArgumentAfterLabeled { .. })
+        ));
+    }
+
+    #[test]
+    fn reorder_rejects_duplicate_label() {
+        let mut args = vec![arg(Some("b"), 1), arg(Some("b"), 0)];
+
+        assert!(matches!(
+            field_map(&["a", "b"]).reorder(&mut args, Span::empty()),
+            Err(Error::DuplicateArgument { .. })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use super::{
+    always_fails,
     environment::{EntityKind, Environment},
     error::{Error, UnifyErrorSituation, Warning},
     expr::ExprTyper,
@@ -8,9 +9,10 @@ use super::{
 use crate::{
     ast::{
         Annotation, ArgBy, ArgName, ArgVia, DataType, Definition, Function, ModuleConstant,
-        ModuleKind, RecordConstructor, RecordConstructorArg, Tracing, TypeAlias, TypedArg,
-        TypedDefinition, TypedModule, TypedValidator, UntypedArg, UntypedDefinition, UntypedModule,
-        UntypedPattern, UntypedValidator, Use, Validator,
+        ModuleKind, RecordConstructor, RecordConstructorArg, TraceSeverity, Tracing, TypeAlias,
+        TypedArg, TypedDefinition, TypedFunction, TypedModule, TypedValidator, UnqualifiedImport,
+        UntypedArg, UntypedDefinition, UntypedModule, UntypedPattern, UntypedValidator, Use,
+        Validator,
     },
     expr::{TypedExpr, UntypedAssignmentKind, UntypedExpr},
     parser::token::Token,
@@ -29,13 +31,25 @@ impl UntypedModule {
         package: &str,
         modules: &HashMap<String, TypeInfo>,
         tracing: Tracing,
+        trace_severity: TraceSeverity,
         warnings: &mut Vec<Warning>,
         env: Option<&str>,
     ) -> Result<TypedModule, Error> {
         let module_name = self.name.clone();
         let docs = std::mem::take(&mut self.docs);
-        let mut environment =
-            Environment::new(id_gen.clone(), &module_name, &kind, modules, warnings, env);
+        let mut environment = Environment::new(
+            id_gen.clone(),
+            &module_name,
+            &kind,
+            modules,
+            warnings,
+            env,
+            trace_severity,
+        );
+
+        if !self.implicit_prelude {
+            environment.hide_prelude();
+        }
 
         let mut type_names = HashMap::with_capacity(self.definitions.len());
         let mut value_names = HashMap::with_capacity(self.definitions.len());
@@ -100,7 +114,10 @@ impl UntypedModule {
         let definitions = definitions
             .into_iter()
             .map(|def| environment.generalise_definition(def, &module_name))
-            .collect();
+            .collect::<Vec<_>>();
+
+        // Look for expressions that are bound to fail at runtime
+        always_fails::check(&definitions, &mut environment.warnings);
 
         // Generate warnings for unused items
         environment.warnings.retain(|warning| match warning {
@@ -122,6 +139,96 @@ impl UntypedModule {
             .accessors
             .retain(|_, accessors| accessors.public);
 
+        // Add re-exported values and types back into the public interface
+        let mut reexports = HashMap::new();
+        for def in definitions.iter() {
+            if let Definition::Use(Use {
+                public: true,
+                module,
+                unqualified,
+                ..
+            }) = def
+            {
+                let origin = module.join("/");
+                let module_info = environment
+                    .importable_modules
+                    .get(&origin)
+                    .expect("re-exported module was found during inference");
+
+                for UnqualifiedImport {
+                    name,
+                    as_name,
+                    location,
+                } in unqualified
+                {
+                    let exported_name = as_name.as_ref().unwrap_or(name);
+
+                    if let Some(value) = module_info.values.get(name) {
+                        if let Some(previous_location) = value_names.get(exported_name) {
+                            return Err(Error::DuplicateName {
+                                location: *location,
+                                previous_location: **previous_location,
+                                name: exported_name.clone(),
+                            });
+                        }
+
+                        environment
+                            .module_values
+                            .insert(exported_name.clone(), value.clone());
+                    }
+
+                    if let Some(tipo) = module_info.types.get(name) {
+                        if let Some(previous_location) = type_names.get(exported_name.as_str()) {
+                            return Err(Error::DuplicateTypeName {
+                                location: *location,
+                                previous_location: **previous_location,
+                                name: exported_name.clone(),
+                            });
+                        }
+
+                        environment
+                            .module_types
+                            .insert(exported_name.clone(), tipo.clone());
+                    }
+
+                    if let Some(constructors) = module_info.types_constructors.get(name) {
+                        // Constructors go along with their type, under their own names.
+                        for constructor in constructors {
+                            let Some(value) = module_info.values.get(constructor) else {
+                                continue;
+                            };
+
+                            if let Some(previous_location) = value_names.get(constructor) {
+                                return Err(Error::DuplicateName {
+                                    location: *location,
+                                    previous_location: **previous_location,
+                                    name: constructor.clone(),
+                                });
+                            }
+
+                            environment
+                                .module_values
+                                .insert(constructor.clone(), value.clone());
+
+                            reexports.insert(constructor.clone(), origin.clone());
+                        }
+
+                        environment
+                            .module_types_constructors
+                            .insert(exported_name.clone(), constructors.clone());
+                    }
+
+                    if let Some(accessors) = module_info.accessors.get(name) {
+                        environment
+                            .accessors
+                            .insert(exported_name.clone(), accessors.clone());
+                    }
+
+                    reexports.insert(exported_name.clone(), origin.clone());
+                }
+            }
+        }
+
         // Ensure no exported values have private types in their type signature
         for value in environment.module_values.values() {
             if let Some(leaked) = value.tipo.find_private_type() {
@@ -143,6 +250,9 @@ impl UntypedModule {
 
         Ok(TypedModule {
             docs,
+            experimental: self.experimental,
+            conditionals: self.conditionals,
+            implicit_prelude: self.implicit_prelude,
             name: module_name.clone(),
             definitions,
             kind,
@@ -154,6 +264,7 @@ impl UntypedModule {
                 values,
                 accessors,
                 annotations,
+                reexports,
                 kind,
                 package: package.to_string(),
             },
@@ -194,67 +305,87 @@ fn infer_definition(
 
                 put_params_in_scope(&fallback_name, environment, &params);
 
-                let mut typed_handlers = vec![];
+                let mut typed_handlers: Vec<TypedFunction> = vec![];
 
                 for mut handler in handlers {
-                    let typed_fun = environment.in_new_scope(|environment| {
-                        let temp_params = params.iter().cloned().chain(handler.arguments);
-                        handler.arguments = temp_params.collect();
-
-                        let handler_name = TypedValidator::handler_name(&name, &handler.name);
-
-                        let old_name = handler.name;
-                        handler.name = handler_name;
-
-                        let mut typed_fun =
-                            infer_function(&handler, module_name, hydrators, environment, tracing)?;
-
-                        typed_fun.name = old_name;
-
-                        if !typed_fun.return_type.is_bool() {
-                            return Err(Error::ValidatorMustReturnBool {
-                                return_type: typed_fun.return_type.clone(),
-                                location: typed_fun.location,
-                            });
+                    // A handler exported under several purposes (e.g. 'mint | withdraw') is parsed
+                    // as copies of one declaration sharing its location. Its body is only
+                    // type-checked once; further purposes merely check the resulting signature.
+                    let shared = typed_handlers
+                        .last()
+                        .filter(|previous| previous.location == handler.location)
+                        .cloned();
+
+                    let mut typed_fun = match shared {
+                        Some(mut typed_fun) => {
+                            typed_fun.name = handler.name;
+                            typed_fun
                         }
+                        None => environment.in_new_scope(|environment| {
+                            let temp_params = params.iter().cloned().chain(handler.arguments);
+                            handler.arguments = temp_params.collect();
+
+                            let handler_name = TypedValidator::handler_name(&name, &handler.name);
+
+                            let old_name = handler.name;
+                            handler.name = handler_name;
+
+                            let mut typed_fun = infer_function(
+                                &handler,
+                                module_name,
+                                hydrators,
+                                environment,
+                                tracing,
+                            )?;
+
+                            typed_fun.name = old_name;
+
+                            if !typed_fun.return_type.is_bool() {
+                                return Err(Error::ValidatorMustReturnBool {
+                                    return_type: typed_fun.return_type.clone(),
+                                    location: typed_fun.location,
+                                });
+                            }
 
-                        typed_fun.arguments.drain(0..params_length);
+                            typed_fun.arguments.drain(0..params_length);
 
-                        if !typed_fun.has_valid_purpose_name() {
-                            return Err(Error::UnknownPurpose {
-                                location: typed_fun
-                                    .location
-                                    .map(|start, _end| (start, start + typed_fun.name.len())),
-                                available_purposes: TypedValidator::available_handler_names(),
-                            });
-                        }
+                            Ok(typed_fun)
+                        })?,
+                    };
 
-                        if typed_fun.arguments.len() != typed_fun.validator_arity() {
-                            return Err(Error::IncorrectValidatorArity {
-                                count: typed_fun.arguments.len() as u32,
-                                expected: typed_fun.validator_arity() as u32,
-                                location: typed_fun.location,
-                            });
-                        }
+                    if !typed_fun.has_valid_purpose_name() {
+                        return Err(Error::UnknownPurpose {
+                            location: typed_fun
+                                .location
+                                .map(|start, _end| (start, start + typed_fun.name.len())),
+                            available_purposes: TypedValidator::available_handler_names(),
+                        });
+                    }
 
-                        if typed_fun.is_spend() && !typed_fun.arguments[0].tipo.is_option() {
-                            return Err(Error::CouldNotUnify {
-                                location: typed_fun.arguments[0].location,
-                                expected: Type::option(typed_fun.arguments[0].tipo.clone()),
-                                given: typed_fun.arguments[0].tipo.clone(),
-                                situation: None,
-                                rigid_type_names: Default::default(),
-                            });
-                        }
+                    if typed_fun.arguments.len() != typed_fun.validator_arity() {
+                        return Err(Error::IncorrectValidatorArity {
+                            count: typed_fun.arguments.len() as u32,
+                            expected: typed_fun.validator_arity() as u32,
+                            location: typed_fun.location,
+                        });
+                    }
 
-                        for arg in typed_fun.arguments.iter_mut() {
-                            if arg.tipo.is_unbound() {
-                                arg.tipo = Type::data();
-                            }
-                        }
+                    if typed_fun.is_spend() && !typed_fun.arguments[0].tipo.is_option() {
+                        return Err(Error::CouldNotUnify {
+                            location: typed_fun.arguments[0].location,
+                            expected: Type::option(typed_fun.arguments[0].tipo.clone()),
+                            given: typed_fun.arguments[0].tipo.clone(),
+                            situation: None,
+                            rigid_type_names: Default::default(),
+                            notes: vec![],
+                        });
+                    }
 
-                        Ok(typed_fun)
-                    })?;
+                    for arg in typed_fun.arguments.iter_mut() {
+                        if arg.tipo.is_unbound() {
+                            arg.tipo = Type::data();
+                        }
+                    }
 
                     typed_handlers.push(typed_fun);
                 }
@@ -334,6 +465,15 @@ fn infer_definition(
         }
 
         Definition::Test(f) => {
+            if let Some(name) = &f.example {
+                if !environment.module_functions.contains_key(name) {
+                    return Err(Error::UnknownExampleFunction {
+                        location: f.location,
+                        name: name.clone(),
+                    });
+                }
+            }
+
             let (typed_via, annotation) = match f.arguments.first() {
                 Some(arg) => {
                     if f.arguments.len() > 1 {
@@ -403,6 +543,7 @@ fn infer_definition(
                 return_type: typed_f.return_type,
                 body: typed_f.body,
                 on_test_failure: typed_f.on_test_failure,
+                example: typed_f.example,
                 end_position: typed_f.end_position,
             }))
         }
@@ -456,6 +597,7 @@ fn infer_definition(
                 return_type: typed_f.return_type,
                 body: typed_f.body,
                 on_test_failure: typed_f.on_test_failure,
+                example: typed_f.example,
                 end_position: typed_f.end_position,
             }))
         }
@@ -605,6 +747,8 @@ fn infer_definition(
             as_name,
             unqualified,
             package: _,
+            public,
+            implicit,
         }) => {
             let module_info = environment.find_module(&module, location)?;
 
@@ -614,6 +758,8 @@ fn infer_definition(
                 as_name,
                 unqualified,
                 package: module_info.package.clone(),
+                public,
+                implicit,
             }))
         }
 
@@ -624,8 +770,11 @@ fn infer_definition(
             annotation,
             public,
             value,
+            fixture,
         }) => {
-            let typed_assignment = ExprTyper::new(environment, tracing).infer_assignment(
+            let mut expr_typer = ExprTyper::new(environment, tracing);
+
+            let typed_assignment = expr_typer.infer_assignment(
                 UntypedPattern::Var {
                     location,
                     name: name.clone(),
@@ -636,6 +785,8 @@ fn infer_definition(
                 location,
             )?;
 
+            expr_typer.check_comparisons()?;
+
             // NOTE: The assignment above is only a convenient way to create the TypedExpression
             // that will be reduced at compile-time. We must increment its usage to not
             // automatically trigger a warning since we are virtually creating a block with a
@@ -680,6 +831,7 @@ fn infer_definition(
                 annotation,
                 public,
                 value: *typed_expr,
+                fixture,
             }))
         }
     }
@@ -736,6 +888,7 @@ where
                 given: provided_inner_type.clone(),
                 situation: Some(UnifyErrorSituation::FuzzerAnnotationMismatch),
                 rigid_type_names: hydrator.rigid_names(),
+                notes: vec![],
             });
         }
     }
@@ -779,6 +932,7 @@ fn infer_fuzzer(
         given: tipo.clone(),
         situation: None,
         rigid_type_names: HashMap::new(),
+        notes: vec![],
     };
 
     match tipo.borrow() {
@@ -856,6 +1010,7 @@ fn infer_sampler(
         given: tipo.clone(),
         situation: None,
         rigid_type_names: HashMap::new(),
+        notes: vec![],
     };
 
     match tipo.borrow() {
@@ -1,7 +1,10 @@
 //! Type inference and checking of patterns used in case expressions
 //! and variables bindings.
 use super::{
-    environment::{assert_no_labeled_arguments, collapse_links, EntityKind, Environment},
+    environment::{
+        assert_no_labeled_arguments, assert_not_reserved_identifier, collapse_links, EntityKind,
+        Environment,
+    },
     error::{Error, Warning},
     hydrator::Hydrator,
     PatternConstructor, Type, ValueConstructorVariant,
@@ -146,6 +149,8 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
     ) -> Result<TypedPattern, Error> {
         match pattern {
             Pattern::Discard { name, location } => {
+                assert_not_reserved_identifier(&name, location)?;
+
                 if warn_on_discard {
                     // Register declaration for the unused variable detection
                     self.environment
@@ -254,6 +259,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                     situation: None,
                     location,
                     rigid_type_names: HashMap::new(),
+                    notes: vec![],
                 }),
             },
 
@@ -294,6 +300,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                     situation: None,
                     location,
                     rigid_type_names: HashMap::new(),
+                    notes: vec![],
                 }),
             },
 
@@ -360,6 +367,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                         situation: None,
                         location,
                         rigid_type_names: HashMap::new(),
+                        notes: vec![],
                     })
                 }
             },
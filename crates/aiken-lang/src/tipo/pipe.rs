@@ -4,8 +4,8 @@ use super::{
     Type, ValueConstructor, ValueConstructorVariant,
 };
 use crate::{
-    ast::{AssignmentKind, CallArg, Pattern, Span, PIPE_VARIABLE},
-    expr::{TypedExpr, UntypedExpr},
+    ast::{AssignmentKind, CallArg, Pattern, Span, CAPTURE_VARIABLE, PIPE_VARIABLE},
+    expr::{FnStyle, TypedExpr, UntypedExpr},
 };
 use std::{ops::Deref, rc::Rc};
 use vec1::Vec1;
@@ -103,8 +103,31 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
                     }
                 }
 
-                // right(left)
-                call => self.infer_apply_pipe(call)?,
+                // left |> right(..args, _, ..args)
+                UntypedExpr::Fn {
+                    fn_style: FnStyle::Capture,
+                    arguments: holes,
+                    body,
+                    ..
+                } if holes.len() == 1 => self.infer_fill_capture_pipe(*body)?,
+
+                call => {
+                    let fun = self.expr_typer.infer(call)?;
+
+                    let has_defaults = self
+                        .expr_typer
+                        .get_field_map(&fun, fun.location())?
+                        .is_some_and(|field_map| !field_map.defaults.is_empty());
+
+                    if has_defaults {
+                        // Rewrite as right(left, ..defaults)
+                        let location = fun.location();
+                        self.infer_insert_pipe(fun, vec![], location)?
+                    } else {
+                        // right(left)
+                        self.infer_apply_pipe(fun)?
+                    }
+                }
             };
 
             if i + 2 == self.size {
@@ -194,6 +217,37 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
         self.expressions.push(assignment);
     }
 
+    /// Infer a |> b(..c, _, ..d) as b(..c, a, ..d), substituting the value on the left hand
+    /// side of the pipe for the capture hole instead of applying a capture lambda.
+    #[allow(clippy::result_large_err)]
+    fn infer_fill_capture_pipe(&mut self, body: UntypedExpr) -> Result<TypedExpr, Error> {
+        let UntypedExpr::Call {
+            fun,
+            arguments,
+            location,
+        } = body
+        else {
+            unreachable!("function capture found not to have a function call body")
+        };
+
+        let arguments = arguments
+            .into_iter()
+            .map(|arg| match arg.value {
+                UntypedExpr::Var { ref name, .. } if name.contains(CAPTURE_VARIABLE) => CallArg {
+                    value: self.untyped_left_hand_value_variable(),
+                    ..arg
+                },
+                _ => arg,
+            })
+            .collect();
+
+        self.expr_typer.infer(UntypedExpr::Call {
+            fun,
+            arguments,
+            location,
+        })
+    }
+
     /// Attempt to infer a |> b(..c) as b(..c)(a)
     #[allow(clippy::result_large_err)]
     fn infer_apply_to_call_pipe(
@@ -253,8 +307,8 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
 
     /// Attempt to infer a |> b as b(a)
     #[allow(clippy::result_large_err)]
-    fn infer_apply_pipe(&mut self, func: UntypedExpr) -> Result<TypedExpr, Error> {
-        let func = Box::new(self.expr_typer.infer(func)?);
+    fn infer_apply_pipe(&mut self, func: TypedExpr) -> Result<TypedExpr, Error> {
+        let func = Box::new(func);
         let return_type = self.expr_typer.new_unbound_var();
 
         // Ensure that the function accepts one argument of the correct type
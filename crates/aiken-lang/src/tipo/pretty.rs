@@ -9,13 +9,24 @@ use std::{collections::HashMap, rc::Rc};
 
 const INDENT: isize = 2;
 
+/// Two types printed side by side, with the parts they have in common elided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDiff {
+    pub left: String,
+    pub right: String,
+    /// The first (leftmost) parts on which both types differ.
+    pub divergence: (String, String),
+}
+
 // TODO: use references instead of cloning strings and vectors
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Printer {
     names: HashMap<u64, String>,
     uid: u64,
     // A mapping of printd type names to the module that they are defined in.
     printed_types: HashMap<String, String>,
+    // Print the types aliases stand for, instead of the aliases themselves.
+    expand_aliases: bool,
 }
 
 impl Printer {
@@ -27,20 +38,172 @@ impl Printer {
         self.names = names;
     }
 
+    pub fn expand_aliases(&mut self) {
+        self.expand_aliases = true;
+    }
+
     /// Render a Type as a well formatted string.
     ///
     pub fn pretty_print(&mut self, typ: &Type, initial_indent: usize) -> String {
-        let mut buffer = String::with_capacity(initial_indent);
+        self.pretty_print_with_width(typ, initial_indent, format::MAX_COLUMNS)
+    }
+
+    /// Like 'pretty_print', but wrapping (e.g. long function types) beyond the given width.
+    pub fn pretty_print_with_width(
+        &mut self,
+        typ: &Type,
+        initial_indent: usize,
+        max_columns: isize,
+    ) -> String {
+        let doc = self.print(typ);
+        render(doc, initial_indent, max_columns)
+    }
+
+    /// Compare two types structurally, replacing with '_' the parts they have in common so
+    /// that only their differences remain. Returns nothing when the types already differ at
+    /// their root, as there's then nothing to elide.
+    ///
+    /// Records are nominal: two instances of a same record type share their fields, and may
+    /// only differ on their type arguments. So records are compared argument by argument, like
+    /// functions, tuples and pairs.
+    pub fn diff(&mut self, left: &Type, right: &Type, initial_indent: usize) -> Option<TypeDiff> {
+        let mut divergence = None;
+        let mut elided = false;
+
+        let (left, right) = self.diff_docs(left, right, &mut divergence, &mut elided);
+
+        Some(TypeDiff {
+            left: render(left, initial_indent, format::MAX_COLUMNS),
+            right: render(right, initial_indent, format::MAX_COLUMNS),
+            divergence: divergence.filter(|_| elided)?,
+        })
+    }
+
+    fn diff_docs<'a>(
+        &mut self,
+        left: &Type,
+        right: &Type,
+        divergence: &mut Option<(String, String)>,
+        elided: &mut bool,
+    ) -> (Document<'a>, Document<'a>) {
+        if let Some(left) = unlink(left) {
+            return self.diff_docs(&left, right, divergence, elided);
+        }
+
+        if let Some(right) = unlink(right) {
+            return self.diff_docs(left, &right, divergence, elided);
+        }
+
+        let left_str = self.print(left).to_pretty_string(isize::MAX);
+        let right_str = self.print(right).to_pretty_string(isize::MAX);
+
+        if left_str == right_str {
+            *elided = true;
+            return ("_".to_doc(), "_".to_doc());
+        }
+
+        match (left, right) {
+            (
+                Type::App {
+                    name, module, args, ..
+                },
+                Type::App {
+                    name: right_name,
+                    module: right_module,
+                    args: right_args,
+                    ..
+                },
+            ) if name == right_name
+                && module == right_module
+                && !args.is_empty()
+                && args.len() == right_args.len() =>
+            {
+                let (left_args, right_args) = self.diff_args(args, right_args, divergence, elided);
+
+                let name = self.type_name(name, module);
+
+                (
+                    name.clone().append("<").append(left_args).append(">"),
+                    name.append("<").append(right_args).append(">"),
+                )
+            }
+
+            (
+                Type::Fn { args, ret, .. },
+                Type::Fn {
+                    args: right_args,
+                    ret: right_ret,
+                    ..
+                },
+            ) if args.len() == right_args.len() => {
+                let (left_args, right_args) = self.diff_args(args, right_args, divergence, elided);
+                let (left_ret, right_ret) = self.diff_docs(ret, right_ret, divergence, elided);
+
+                let function = |args: Document<'a>, ret: Document<'a>| {
+                    "fn("
+                        .to_doc()
+                        .append(args)
+                        .append(") ->")
+                        .append(break_("", " ").append(ret).nest(INDENT).group())
+                };
+
+                (
+                    function(left_args, left_ret),
+                    function(right_args, right_ret),
+                )
+            }
+
+            (
+                Type::Tuple { elems, .. },
+                Type::Tuple {
+                    elems: right_elems, ..
+                },
+            ) if elems.len() == right_elems.len() => {
+                let (left, right) = self.diff_args(elems, right_elems, divergence, elided);
+                (left.surround("(", ")"), right.surround("(", ")"))
+            }
 
-        for _ in 0..initial_indent {
-            buffer.push(' ');
+            (
+                Type::Pair { fst, snd, .. },
+                Type::Pair {
+                    fst: right_fst,
+                    snd: right_snd,
+                    ..
+                },
+            ) => {
+                let (left, right) = self.diff_args(
+                    &[fst.clone(), snd.clone()],
+                    &[right_fst.clone(), right_snd.clone()],
+                    divergence,
+                    elided,
+                );
+                (left.surround("Pair<", ">"), right.surround("Pair<", ">"))
+            }
+
+            _ => {
+                divergence.get_or_insert((left_str.clone(), right_str.clone()));
+                (Document::String(left_str), Document::String(right_str))
+            }
         }
+    }
+
+    fn diff_args<'a>(
+        &mut self,
+        left: &[Rc<Type>],
+        right: &[Rc<Type>],
+        divergence: &mut Option<(String, String)>,
+        elided: &mut bool,
+    ) -> (Document<'a>, Document<'a>) {
+        let (left, right): (Vec<_>, Vec<_>) = left
+            .iter()
+            .zip(right)
+            .map(|(left, right)| {
+                let (left, right) = self.diff_docs(left, right, divergence, elided);
+                (left.group(), right.group())
+            })
+            .unzip();
 
-        buffer
-            .to_doc()
-            .append(self.print(typ))
-            .nest(initial_indent as isize)
-            .to_pretty_string(format::MAX_COLUMNS)
+        (wrap_args(left), wrap_args(right))
     }
 
     // TODO: have this function return a Document that borrows from the Type.
@@ -52,7 +215,7 @@ impl Printer {
             parameters,
             annotation,
             module: _,
-        }) = typ.alias().as_deref()
+        }) = typ.alias().as_deref().filter(|_| !self.expand_aliases)
         {
             if let Some(resolved_parameters) = resolve_alias(parameters, annotation, typ) {
                 return self.type_alias_doc(typ, alias.to_string(), resolved_parameters);
@@ -63,12 +226,7 @@ impl Printer {
             Type::App {
                 name, args, module, ..
             } => {
-                let doc = if self.name_clashes_if_unqualified(name, module) {
-                    qualify_type_name(module, name)
-                } else {
-                    self.printed_types.insert(name.clone(), module.clone());
-                    Document::String(name.clone())
-                };
+                let doc = self.type_name(name, module);
                 if args.is_empty() {
                     doc
                 } else {
@@ -126,10 +284,22 @@ impl Printer {
         }
     }
 
-    fn name_clashes_if_unqualified(&mut self, tipo: &String, module: &String) -> bool {
+    /// The name of a type, qualified by its module if another type of the same name was
+    /// printed before.
+    fn type_name<'a>(&mut self, name: &str, module: &str) -> Document<'a> {
+        if self.name_clashes_if_unqualified(name, module) {
+            qualify_type_name(module, name)
+        } else {
+            self.printed_types
+                .insert(name.to_string(), module.to_string());
+            Document::String(name.to_string())
+        }
+    }
+
+    fn name_clashes_if_unqualified(&mut self, tipo: &str, module: &str) -> bool {
         match self.printed_types.get(tipo) {
             None => false,
-            Some(previous_module) if module == previous_module => false,
+            Some(previous_module) if previous_module == module => false,
             Some(_different_module) => true,
         }
     }
@@ -163,7 +333,19 @@ impl Printer {
         }
     }
 
+    /// The next name for a generic variable, skipping over names already given (e.g. the
+    /// ones of rigid type variables) so that two distinct variables never print the same.
     fn next_letter(&mut self) -> String {
+        loop {
+            let name = self.nth_letter();
+
+            if !self.names.values().any(|n| n == &name) {
+                return name;
+            }
+        }
+    }
+
+    fn nth_letter(&mut self) -> String {
         let alphabet_length = 26;
         let char_offset = 97;
         let mut chars = vec![];
@@ -190,20 +372,49 @@ impl Printer {
     }
 
     fn args_to_aiken_doc<'a>(&mut self, args: &[Rc<Type>]) -> Document<'a> {
-        if args.is_empty() {
-            return nil();
-        }
+        let args = args.iter().map(|t| self.print(t).group()).collect();
 
-        let args = concat(Itertools::intersperse(
-            args.iter().map(|t| self.print(t).group()),
+        wrap_args(args)
+    }
+}
+
+fn wrap_args<'a>(args: Vec<Document<'a>>) -> Document<'a> {
+    if args.is_empty() {
+        return nil();
+    }
+
+    break_("", "")
+        .append(concat(Itertools::intersperse(
+            args.into_iter(),
             break_(",", ", "),
-        ));
+        )))
+        .nest(INDENT)
+        .append(break_(",", ""))
+        .group()
+}
 
-        break_("", "")
-            .append(args)
-            .nest(INDENT)
-            .append(break_(",", ""))
-            .group()
+fn render(doc: Document<'_>, initial_indent: usize, max_columns: isize) -> String {
+    let mut buffer = String::with_capacity(initial_indent);
+
+    for _ in 0..initial_indent {
+        buffer.push(' ');
+    }
+
+    buffer
+        .to_doc()
+        .append(doc)
+        .nest(initial_indent as isize)
+        .to_pretty_string(max_columns)
+}
+
+/// The type a type variable is linked to, if any.
+fn unlink(typ: &Type) -> Option<Rc<Type>> {
+    match typ {
+        Type::Var { tipo, .. } => match &*tipo.borrow() {
+            TypeVar::Link { tipo } => Some(tipo.clone()),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
@@ -692,6 +903,17 @@ mod tests {
         assert_string!(Type::sampler(Type::int()), "Sampler<Int>");
     }
 
+    #[test]
+    fn expand_aliases_test() {
+        let mut printer = Printer::new();
+        printer.expand_aliases();
+
+        assert_eq!(
+            printer.pretty_print(&Type::sampler(Type::int()), 0),
+            "fn(Int) -> fn(PRNG) -> Option<(PRNG, Int)>"
+        );
+    }
+
     #[test]
     fn function_test() {
         assert_eq!(
@@ -708,6 +930,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn generic_names_are_shared_across_types() {
+        let mut printer = Printer::new();
+        printer.with_names(HashMap::from([(0, "a".to_string())]));
+
+        assert_eq!(
+            printer.pretty_print(
+                &Type::function(vec![Type::generic_var(0)], Type::generic_var(1)),
+                0
+            ),
+            "fn(a) -> b"
+        );
+
+        assert_eq!(
+            printer.pretty_print(&Type::list(Type::generic_var(1)), 0),
+            "List<b>"
+        );
+
+        assert_eq!(
+            printer.pretty_print(&Type::list(Type::generic_var(2)), 0),
+            "List<c>"
+        );
+    }
+
+    #[test]
+    fn long_function_types_wrap() {
+        let typ = Type::function(
+            vec![Type::list(Type::int()), Type::byte_array(), Type::data()],
+            Type::function(vec![Type::int()], Type::bool()),
+        );
+
+        assert_eq!(
+            Printer::new().pretty_print_with_width(&typ, 0, 80),
+            "fn(List<Int>, ByteArray, Data) -> fn(Int) -> Bool"
+        );
+
+        assert_eq!(
+            Printer::new().pretty_print_with_width(&typ, 0, 30),
+            indoc::indoc! {"
+                fn(List<Int>, ByteArray, Data) ->
+                  fn(Int) -> Bool"}
+        );
+
+        assert_eq!(
+            Printer::new().pretty_print_with_width(&typ, 0, 25),
+            indoc::indoc! {"
+                fn(
+                  List<Int>,
+                  ByteArray,
+                  Data,
+                ) -> fn(Int) -> Bool"}
+        );
+    }
+
+    #[test]
+    fn diff_test() {
+        let left = Type::list(Type::tuple(vec![
+            Type::int(),
+            Type::byte_array(),
+            Type::option(Type::int()),
+        ]));
+
+        let right = Type::list(Type::tuple(vec![
+            Type::int(),
+            Type::byte_array(),
+            Type::option(Type::bool()),
+        ]));
+
+        assert_eq!(
+            Printer::new().diff(&left, &right, 0),
+            Some(TypeDiff {
+                left: "List<(_, _, Option<Int>)>".to_string(),
+                right: "List<(_, _, Option<Bool>)>".to_string(),
+                divergence: ("Int".to_string(), "Bool".to_string()),
+            })
+        );
+
+        assert_eq!(
+            Printer::new().diff(&Type::list(Type::int()), &Type::list(Type::bool()), 0),
+            None
+        );
+
+        assert_eq!(Printer::new().diff(&left, &Type::int(), 0), None);
+    }
+
+    #[test]
+    fn diff_qualifies_clashing_names() {
+        let datum = |module: &str, args: Vec<Rc<Type>>| {
+            Rc::new(Type::App {
+                module: module.to_string(),
+                name: "Datum".to_string(),
+                public: true,
+                contains_opaque: false,
+                args,
+                alias: None,
+            })
+        };
+
+        let left = Type::tuple(vec![datum("b", vec![]), datum("a", vec![Type::int()])]);
+
+        let right = Type::tuple(vec![datum("b", vec![]), datum("a", vec![Type::bool()])]);
+
+        assert_eq!(
+            Printer::new().diff(&left, &right, 0),
+            Some(TypeDiff {
+                left: "(_, a.Datum<Int>)".to_string(),
+                right: "(_, a.Datum<Bool>)".to_string(),
+                divergence: ("Int".to_string(), "Bool".to_string()),
+            })
+        );
+    }
+
     fn pretty_print(typ: Rc<Type>) -> String {
         Printer::new().pretty_print(&typ, 0)
     }
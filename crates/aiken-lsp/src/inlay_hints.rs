@@ -0,0 +1,250 @@
+use crate::utils::{single_line_type, span_to_lsp_range};
+use aiken_lang::{
+    ast::{CallArg, Definition, Pattern, Span, TypedDefinition, TypedPattern},
+    expr::TypedExpr,
+    line_numbers::LineNumbers,
+    tipo::{fields::FieldMap, ModuleValueConstructor, ValueConstructorVariant},
+};
+use aiken_project::module::CheckedModule;
+
+/// Inlay hints for a module: the inferred type of let-bindings which aren't annotated, and the
+/// name of parameters at call sites. The field maps of functions defined in other modules are
+/// obtained through 'resolve', from a module and function name.
+pub fn inlay_hints(
+    module: &CheckedModule,
+    resolve: impl Fn(&str, &str) -> Option<FieldMap>,
+) -> Vec<lsp_types::InlayHint> {
+    let line_numbers = LineNumbers::new(&module.code);
+
+    let mut hints = Vec::new();
+
+    for body in module.ast.definitions().flat_map(bodies) {
+        body.walk(&mut |expr| match expr {
+            TypedExpr::Assignment { pattern, value, .. } => {
+                if let Some(hint) = type_hint(&module.code, pattern, value) {
+                    hints.push(hint);
+                }
+            }
+            TypedExpr::Call { fun, args, .. } => {
+                let Some(field_map) = field_map(fun, &resolve) else {
+                    return;
+                };
+
+                for (index, arg) in args.iter().enumerate() {
+                    if let Some(hint) = parameter_hint(&module.code, &field_map, index, arg) {
+                        hints.push(hint);
+                    }
+                }
+            }
+            _ => {}
+        });
+    }
+
+    hints
+        .into_iter()
+        .map(|(offset, label, kind)| lsp_types::InlayHint {
+            position: span_to_lsp_range(Span::create(offset, 0), &line_numbers).start,
+            label: lsp_types::InlayHintLabel::String(label),
+            kind: Some(kind),
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: Some(kind == lsp_types::InlayHintKind::PARAMETER),
+            data: None,
+        })
+        .collect()
+}
+
+type Hint = (usize, String, lsp_types::InlayHintKind);
+
+fn bodies(definition: &TypedDefinition) -> Vec<&TypedExpr> {
+    match definition {
+        Definition::Fn(function) => vec![&function.body],
+        Definition::Test(test) | Definition::Benchmark(test) => test
+            .arguments
+            .iter()
+            .map(|arg| &arg.via)
+            .chain(std::iter::once(&test.body))
+            .collect(),
+        Definition::Validator(validator) => validator
+            .handlers
+            .iter()
+            .chain(std::iter::once(&validator.fallback))
+            .map(|handler| &handler.body)
+            .collect(),
+        Definition::ModuleConstant(constant) => vec![&constant.value],
+        Definition::TypeAlias(_) | Definition::DataType(_) | Definition::Use(_) => vec![],
+    }
+}
+
+/// The inferred type of a variable bound without annotation, shown after its name.
+fn type_hint(code: &str, pattern: &TypedPattern, value: &TypedExpr) -> Option<Hint> {
+    let Pattern::Var { location, name } = pattern else {
+        return None;
+    };
+
+    // Assignments introduced by the compiler (e.g. for pipes) don't appear in the source.
+    if code.get(location.start..location.end) != Some(name.as_str()) {
+        return None;
+    }
+
+    let is_annotated = code[location.end..].trim_start().starts_with(':');
+
+    if is_annotated {
+        return None;
+    }
+
+    Some((
+        location.end,
+        format!(": {}", single_line_type(&value.tipo())),
+        lsp_types::InlayHintKind::TYPE,
+    ))
+}
+
+/// The name of the parameter an argument is given for, shown before the argument. Arguments
+/// already labelled, or given as a variable of the same name, are left alone.
+fn parameter_hint(
+    code: &str,
+    field_map: &FieldMap,
+    index: usize,
+    arg: &CallArg<TypedExpr>,
+) -> Option<Hint> {
+    let location = arg.value.location();
+
+    let is_labelled = arg.location.start < location.start;
+
+    // Arguments introduced by the compiler (e.g. the piped value, or captures) don't appear
+    // in the source.
+    let is_implicit = match &arg.value {
+        TypedExpr::Var { name, location, .. } => {
+            code.get(location.start..location.end) != Some(name.as_str())
+        }
+        _ => false,
+    };
+
+    if is_labelled || is_implicit {
+        return None;
+    }
+
+    let (label, _) = field_map
+        .fields
+        .iter()
+        .find(|(_, (position, _))| *position == index)?;
+
+    let is_same_name = matches!(&arg.value, TypedExpr::Var { name, .. } if name == label);
+
+    if is_same_name || label.starts_with('_') {
+        return None;
+    }
+
+    Some((
+        location.start,
+        format!("{label}:"),
+        lsp_types::InlayHintKind::PARAMETER,
+    ))
+}
+
+fn field_map(
+    fun: &TypedExpr,
+    resolve: &impl Fn(&str, &str) -> Option<FieldMap>,
+) -> Option<FieldMap> {
+    match fun {
+        TypedExpr::Var { constructor, .. } => match &constructor.variant {
+            ValueConstructorVariant::ModuleFn { field_map, .. }
+            | ValueConstructorVariant::Record { field_map, .. } => field_map.clone(),
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::ModuleConstant { .. } => None,
+        },
+        TypedExpr::ModuleSelect { constructor, .. } => match constructor {
+            ModuleValueConstructor::Record { field_map, .. } => field_map.clone(),
+            ModuleValueConstructor::Fn { module, name, .. } => resolve(module, name),
+            ModuleValueConstructor::Constant { .. } => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inlay_hints;
+    use crate::tests::checked_module;
+    use indoc::indoc;
+
+    fn hints(code: &str) -> Vec<(u32, u32, String)> {
+        inlay_hints(&checked_module(code), |_, _| None)
+            .into_iter()
+            .map(|hint| {
+                let lsp_types::InlayHintLabel::String(label) = hint.label else {
+                    unreachable!("labels are plain strings");
+                };
+
+                (hint.position.line, hint.position.character, label)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn let_bindings_and_calls() {
+        let code = indoc! {r#"
+            fn add(left: Int, right: Int) -> Int {
+              left + right
+            }
+
+            pub fn foo(x: Int) -> Int {
+              let y = add(x, 1)
+              let z: Int = add(y, right: 2)
+              add(z, z)
+            }
+        "#};
+
+        assert_eq!(
+            hints(code),
+            vec![
+                (5, 7, ": Int".to_string()),
+                (5, 14, "left:".to_string()),
+                (5, 17, "right:".to_string()),
+                (6, 19, "left:".to_string()),
+                (7, 6, "left:".to_string()),
+                (7, 9, "right:".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn pipes() {
+        let code = indoc! {r#"
+            fn add(left: Int, right: Int) -> Int {
+              left + right
+            }
+
+            pub fn foo(x: Int) -> Int {
+              x |> add(1)
+            }
+        "#};
+
+        // Neither the piped value nor the variable it's bound to appear in the source.
+        assert_eq!(hints(code), vec![(5, 11, "right:".to_string())]);
+    }
+
+    #[test]
+    fn long_types_on_one_line() {
+        let code = indoc! {r#"
+            pub fn foo(x: Int) -> Int {
+              let compose =
+                fn(_f: fn(List<Int>, ByteArray, Data, Option<ByteArray>) -> Int, g: fn(Int) -> Int) {
+                  g
+                }
+              compose(fn(_, _, _, _) { 0 }, fn(y) { y })(x)
+            }
+        "#};
+
+        assert_eq!(
+            hints(code)[0],
+            (
+                1,
+                13,
+                ": fn(fn(List<Int>, ByteArray, Data, Option<ByteArray>) -> Int, fn(Int) -> Int) -> fn(Int) -> Int".to_string()
+            )
+        );
+    }
+}
@@ -1,18 +1,26 @@
 use crate::server::Server;
+use aiken_lang::grammar::{SEMANTIC_TOKEN_MODIFIERS, SEMANTIC_TOKEN_TYPES};
 use aiken_project::{config::Config, paths};
 use error::Error;
 use lsp_server::Connection;
-use std::env;
+use std::{env, path::PathBuf};
 
 mod cast;
 mod edits;
 pub mod error;
+mod inlay_hints;
 mod quickfix;
+mod semantic_tokens;
 pub mod server;
+mod symbols;
+#[cfg(test)]
+mod tests;
 mod utils;
 
+/// Start the language server on stdio. Hovering a builtin shows its costs according to the
+/// cost model found in the given protocol parameters, if any; the built-in one otherwise.
 #[allow(clippy::result_large_err)]
-pub fn start() -> Result<(), Error> {
+pub fn start(cost_model: Option<PathBuf>) -> Result<(), Error> {
     tracing::info!("Aiken language server starting");
 
     // Forcibly disable colors on outputs for LSP
@@ -40,7 +48,7 @@ pub fn start() -> Result<(), Error> {
     let initialization_params = connection.initialize(server_capabilities)?;
     let initialize_params = serde_json::from_value(initialization_params)?;
 
-    let mut server = Server::new(initialize_params, config, root);
+    let mut server = Server::new(initialize_params, config, root, cost_model);
 
     server.listen(connection)?;
 
@@ -65,7 +73,28 @@ fn capabilities() -> lsp_types::ServerCapabilities {
         code_action_provider: Some(lsp_types::CodeActionProviderCapability::Simple(true)),
         document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
         definition_provider: Some(lsp_types::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp_types::OneOf::Left(true)),
         hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
+        inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+        semantic_tokens_provider: Some(
+            lsp_types::SemanticTokensOptions {
+                legend: lsp_types::SemanticTokensLegend {
+                    token_types: SEMANTIC_TOKEN_TYPES
+                        .iter()
+                        .map(|token_type| lsp_types::SemanticTokenType::new(token_type))
+                        .collect(),
+                    token_modifiers: SEMANTIC_TOKEN_MODIFIERS
+                        .iter()
+                        .map(|modifier| lsp_types::SemanticTokenModifier::new(modifier))
+                        .collect(),
+                },
+                full: Some(lsp_types::SemanticTokensFullOptions::Bool(true)),
+                range: None,
+                work_done_progress_options: lsp_types::WorkDoneProgressOptions::default(),
+            }
+            .into(),
+        ),
         text_document_sync: Some(lsp_types::TextDocumentSyncCapability::Options(
             lsp_types::TextDocumentSyncOptions {
                 open_close: None,
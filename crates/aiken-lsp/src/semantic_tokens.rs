@@ -0,0 +1,692 @@
+use crate::utils::find_word;
+use aiken_lang::{
+    ast::{
+        Annotation, ArgName, CallArg, Definition, Function, Pattern, RecordConstructor, Span,
+        TypedArg, TypedDefinition, TypedPattern, Use,
+    },
+    expr::TypedExpr,
+    grammar::{SEMANTIC_TOKEN_MODIFIERS, SEMANTIC_TOKEN_TYPES},
+    line_numbers::LineNumbers,
+    tipo::{ModuleValueConstructor, Type, ValueConstructorVariant},
+};
+use aiken_project::module::CheckedModule;
+use itertools::Itertools;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Namespace,
+    Type,
+    TypeParameter,
+    EnumMember,
+    Function,
+    Parameter,
+    Variable,
+    Property,
+}
+
+impl Kind {
+    fn legend_index(self) -> u32 {
+        let name = match self {
+            Kind::Namespace => "namespace",
+            Kind::Type => "type",
+            Kind::TypeParameter => "typeParameter",
+            Kind::EnumMember => "enumMember",
+            Kind::Function => "function",
+            Kind::Parameter => "parameter",
+            Kind::Variable => "variable",
+            Kind::Property => "property",
+        };
+
+        legend_position(SEMANTIC_TOKEN_TYPES, name)
+    }
+}
+
+const DECLARATION: &str = "declaration";
+const READONLY: &str = "readonly";
+const DEFAULT_LIBRARY: &str = "defaultLibrary";
+
+fn legend_position(legend: &[&str], name: &str) -> u32 {
+    legend
+        .iter()
+        .position(|entry| *entry == name)
+        .unwrap_or_else(|| panic!("'{name}' is missing from the semantic tokens legend")) as u32
+}
+
+/// Classify every name of a module (types, constructors, functions, modules, generics...)
+/// using the typed AST, and encode them as LSP semantic tokens.
+pub fn semantic_tokens(module: &CheckedModule) -> Vec<lsp_types::SemanticToken> {
+    let mut collector = Collector {
+        code: &module.code,
+        tokens: Vec::new(),
+    };
+
+    for definition in module.ast.definitions() {
+        collector.definition(definition);
+    }
+
+    encode(collector.tokens, &module.code)
+}
+
+struct Collector<'a> {
+    code: &'a str,
+    tokens: Vec<(Span, Kind, &'static [&'static str])>,
+}
+
+impl Collector<'_> {
+    /// Record a token, provided the source at that location is indeed the given name. The typed
+    /// AST contains nodes introduced by the compiler (e.g. for pipes or captures) whose location
+    /// points at some other piece of code; those are skipped.
+    fn push(&mut self, span: Span, name: &str, kind: Kind, modifiers: &'static [&'static str]) {
+        if self.code.get(span.start..span.end) == Some(name) {
+            self.tokens.push((span, kind, modifiers));
+        }
+    }
+
+    /// Record the first occurrence of a word within a span, for nodes that only know the
+    /// location of the whole construct they belong to.
+    fn push_word(
+        &mut self,
+        within: Span,
+        word: &str,
+        kind: Kind,
+        modifiers: &'static [&'static str],
+    ) {
+        if let Some(span) = find_word(self.code, within, word) {
+            self.push(span, word, kind, modifiers);
+        }
+    }
+
+    fn definition(&mut self, definition: &TypedDefinition) {
+        match definition {
+            Definition::Fn(function) => {
+                self.function(function, function.arguments.iter());
+            }
+
+            Definition::Test(test) | Definition::Benchmark(test) => {
+                self.function(test, test.arguments.iter().map(|arg| &arg.arg));
+
+                for arg in &test.arguments {
+                    self.expr(&arg.via);
+                }
+            }
+
+            Definition::Validator(validator) => {
+                self.push_word(
+                    validator.location,
+                    &validator.name,
+                    Kind::Type,
+                    &[DECLARATION],
+                );
+
+                for param in &validator.params {
+                    self.arg(param);
+                }
+
+                for handler in validator
+                    .handlers
+                    .iter()
+                    .chain(std::iter::once(&validator.fallback))
+                {
+                    self.function(handler, handler.arguments.iter());
+                }
+            }
+
+            Definition::TypeAlias(alias) => {
+                self.push_word(alias.location, &alias.alias, Kind::Type, &[DECLARATION]);
+                self.annotation(&alias.annotation);
+            }
+
+            Definition::DataType(data_type) => {
+                self.push_word(
+                    data_type.location,
+                    &data_type.name,
+                    Kind::Type,
+                    &[DECLARATION],
+                );
+
+                for constructor in &data_type.constructors {
+                    self.constructor(constructor);
+                }
+            }
+
+            Definition::ModuleConstant(constant) => {
+                self.push_word(
+                    constant.location,
+                    &constant.name,
+                    Kind::Variable,
+                    &[DECLARATION, READONLY],
+                );
+
+                if let Some(annotation) = &constant.annotation {
+                    self.annotation(annotation);
+                }
+
+                self.expr(&constant.value);
+            }
+
+            Definition::Use(import) => self.import(import),
+        }
+    }
+
+    fn function<'a, T, Arg: 'a>(
+        &mut self,
+        function: &Function<T, TypedExpr, Arg>,
+        arguments: impl Iterator<Item = &'a TypedArg>,
+    ) {
+        self.push_word(
+            function.location,
+            &function.name,
+            Kind::Function,
+            &[DECLARATION],
+        );
+
+        for arg in arguments {
+            self.arg(arg);
+        }
+
+        if let Some(annotation) = &function.return_annotation {
+            self.annotation(annotation);
+        }
+
+        self.expr(&function.body);
+    }
+
+    fn arg(&mut self, arg: &TypedArg) {
+        if let ArgName::Named { name, location, .. } = &arg.arg_name {
+            let span = Span {
+                start: location.end.saturating_sub(name.len()),
+                end: location.end,
+            };
+
+            self.push(span, name, Kind::Parameter, &[DECLARATION]);
+        }
+
+        if let Some(annotation) = &arg.annotation {
+            self.annotation(annotation);
+        }
+    }
+
+    fn constructor(&mut self, constructor: &RecordConstructor<Rc<Type>>) {
+        self.push(
+            Span {
+                start: constructor.location.start,
+                end: constructor.location.start + constructor.name.len(),
+            },
+            &constructor.name,
+            Kind::EnumMember,
+            &[DECLARATION],
+        );
+
+        for arg in &constructor.arguments {
+            if let Some(label) = &arg.label {
+                self.push_word(arg.location, label, Kind::Property, &[DECLARATION]);
+            }
+
+            self.annotation(&arg.annotation);
+        }
+    }
+
+    fn import(&mut self, import: &Use<String>) {
+        let mut within = import.location;
+
+        for segment in &import.module {
+            if let Some(span) = find_word(self.code, within, segment) {
+                self.push(span, segment, Kind::Namespace, &[]);
+                within.start = span.end;
+            }
+        }
+
+        if let Some(as_name) = &import.as_name {
+            self.push_word(within, as_name, Kind::Namespace, &[]);
+        }
+
+        for unqualified in &import.unqualified {
+            let kind = if unqualified.name.starts_with(char::is_uppercase) {
+                Kind::Type
+            } else {
+                Kind::Function
+            };
+
+            self.push_word(unqualified.location, &unqualified.name, kind, &[]);
+
+            if let Some(as_name) = &unqualified.as_name {
+                self.push_word(
+                    Span {
+                        start: unqualified.location.start + unqualified.name.len(),
+                        end: unqualified.location.end,
+                    },
+                    as_name,
+                    kind,
+                    &[],
+                );
+            }
+        }
+    }
+
+    fn annotation(&mut self, annotation: &Annotation) {
+        match annotation {
+            Annotation::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                let mut within = *location;
+
+                if let Some(module) = module {
+                    if let Some(span) = find_word(self.code, within, module) {
+                        self.push(span, module, Kind::Namespace, &[]);
+                        within.start = span.end;
+                    }
+                }
+
+                self.push_word(within, name, Kind::Type, &[]);
+
+                for argument in arguments {
+                    self.annotation(argument);
+                }
+            }
+            Annotation::Fn { arguments, ret, .. } => {
+                for argument in arguments {
+                    self.annotation(argument);
+                }
+                self.annotation(ret);
+            }
+            Annotation::Var { location, name } => {
+                self.push(*location, name, Kind::TypeParameter, &[]);
+            }
+            Annotation::Hole { .. } => {}
+            Annotation::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.annotation(elem);
+                }
+            }
+            Annotation::Pair { fst, snd, .. } => {
+                self.annotation(fst);
+                self.annotation(snd);
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern) {
+        match pattern {
+            Pattern::Int { .. } | Pattern::ByteArray { .. } | Pattern::Discard { .. } => {}
+            Pattern::Var { location, name } => {
+                self.push(*location, name, Kind::Variable, &[DECLARATION]);
+            }
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                self.pattern(pattern);
+                self.push_word(
+                    Span {
+                        start: pattern.location().end,
+                        end: location.end.max(pattern.location().end),
+                    },
+                    name,
+                    Kind::Variable,
+                    &[DECLARATION],
+                );
+            }
+            Pattern::List { elements, tail, .. } => {
+                for element in elements {
+                    self.pattern(element);
+                }
+                if let Some(tail) = tail {
+                    self.pattern(tail);
+                }
+            }
+            Pattern::Constructor {
+                location,
+                name,
+                arguments,
+                module,
+                ..
+            } => {
+                let mut within = *location;
+
+                if let Some(module) = module {
+                    if let Some(span) = find_word(self.code, within, module) {
+                        self.push(span, module, Kind::Namespace, &[]);
+                        within.start = span.end;
+                    }
+                }
+
+                self.push_word(within, name, Kind::EnumMember, &[]);
+
+                for CallArg { value, .. } in arguments {
+                    self.pattern(value);
+                }
+            }
+            Pattern::Pair { fst, snd, .. } => {
+                self.pattern(fst);
+                self.pattern(snd);
+            }
+            Pattern::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.pattern(elem);
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &TypedExpr) {
+        match expr {
+            TypedExpr::UInt { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::ByteArray { .. }
+            | TypedExpr::CurvePoint { .. }
+            | TypedExpr::ErrorTerm { .. } => {}
+
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => {
+                let (kind, modifiers): (Kind, &'static [&'static str]) = match &constructor.variant
+                {
+                    ValueConstructorVariant::LocalVariable { .. }
+                        if constructor.tipo.is_function() =>
+                    {
+                        (Kind::Function, &[])
+                    }
+                    ValueConstructorVariant::LocalVariable { .. } => (Kind::Variable, &[]),
+                    ValueConstructorVariant::ModuleConstant { .. } => (Kind::Variable, &[READONLY]),
+                    ValueConstructorVariant::ModuleFn {
+                        builtin: Some(..), ..
+                    } => (Kind::Function, &[DEFAULT_LIBRARY]),
+                    ValueConstructorVariant::ModuleFn { .. } => (Kind::Function, &[]),
+                    ValueConstructorVariant::Record { .. } => (Kind::EnumMember, &[]),
+                };
+
+                self.push(*location, name, kind, modifiers);
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_alias,
+                constructor,
+                ..
+            } => {
+                self.push(
+                    Span {
+                        start: location.start,
+                        end: location.start + module_alias.len(),
+                    },
+                    module_alias,
+                    Kind::Namespace,
+                    &[],
+                );
+
+                let (kind, modifiers): (Kind, &'static [&'static str]) = match constructor {
+                    ModuleValueConstructor::Record { .. } => (Kind::EnumMember, &[]),
+                    ModuleValueConstructor::Fn { .. } => (Kind::Function, &[]),
+                    ModuleValueConstructor::Constant { .. } => (Kind::Variable, &[READONLY]),
+                };
+
+                self.push(
+                    Span {
+                        start: location.end.saturating_sub(label.len()),
+                        end: location.end,
+                    },
+                    label,
+                    kind,
+                    modifiers,
+                );
+            }
+
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                for expression in expressions {
+                    self.expr(expression);
+                }
+            }
+
+            TypedExpr::Fn {
+                args,
+                body,
+                return_annotation,
+                ..
+            } => {
+                for arg in args {
+                    self.arg(arg);
+                }
+                if let Some(annotation) = return_annotation {
+                    self.annotation(annotation);
+                }
+                self.expr(body);
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.expr(element);
+                }
+                if let Some(tail) = tail {
+                    self.expr(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expr(fun);
+                for arg in args {
+                    self.expr(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+
+            TypedExpr::Assignment { value, pattern, .. } => {
+                self.pattern(pattern);
+                self.expr(value);
+            }
+
+            TypedExpr::Trace { then, text, .. } => {
+                self.expr(text);
+                self.expr(then);
+            }
+
+            TypedExpr::When {
+                subject, clauses, ..
+            } => {
+                self.expr(subject);
+                for clause in clauses {
+                    self.pattern(&clause.pattern);
+                    self.expr(&clause.then);
+                }
+            }
+
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for branch in branches {
+                    self.expr(&branch.condition);
+                    if let Some((pattern, _)) = &branch.is {
+                        self.pattern(pattern);
+                    }
+                    self.expr(&branch.body);
+                }
+                self.expr(final_else);
+            }
+
+            TypedExpr::RecordAccess {
+                location,
+                label,
+                record,
+                ..
+            } => {
+                self.expr(record);
+                self.push(
+                    Span {
+                        start: location.end.saturating_sub(label.len()),
+                        end: location.end,
+                    },
+                    label,
+                    Kind::Property,
+                    &[],
+                );
+            }
+
+            TypedExpr::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.expr(elem);
+                }
+            }
+
+            TypedExpr::Pair { fst, snd, .. } => {
+                self.expr(fst);
+                self.expr(snd);
+            }
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expr(tuple),
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expr(spread);
+                for arg in args {
+                    self.push_word(arg.location, &arg.label, Kind::Property, &[]);
+                    self.expr(&arg.value);
+                }
+            }
+
+            TypedExpr::UnOp { value, .. } => self.expr(value),
+        }
+    }
+}
+
+/// Encode tokens relatively to one another, as mandated by the LSP specification. Tokens must
+/// be sorted and may not overlap, so duplicates (e.g. from desugared code) are dropped. Columns
+/// and lengths are counted in UTF-16 code units, like every LSP position.
+fn encode(
+    tokens: Vec<(Span, Kind, &'static [&'static str])>,
+    code: &str,
+) -> Vec<lsp_types::SemanticToken> {
+    let line_numbers = LineNumbers::new(code);
+
+    let utf16_len = |start: usize, end: usize| code[start..end].encode_utf16().count() as u32;
+
+    let mut encoded = Vec::new();
+
+    let mut previous_end = 0;
+    let (mut previous_line, mut previous_column) = (0, 0);
+
+    for (span, kind, modifiers) in tokens
+        .into_iter()
+        .sorted_by_key(|(span, _, _)| (span.start, span.end))
+    {
+        if span.start < previous_end {
+            continue;
+        }
+
+        let Some(position) = line_numbers.line_and_column_number(span.start) else {
+            continue;
+        };
+
+        let line = position.line as u32 - 1;
+        let column = utf16_len(span.start + 1 - position.column, span.start);
+
+        encoded.push(lsp_types::SemanticToken {
+            delta_line: line - previous_line,
+            delta_start: if line == previous_line {
+                column - previous_column
+            } else {
+                column
+            },
+            length: utf16_len(span.start, span.end),
+            token_type: kind.legend_index(),
+            token_modifiers_bitset: modifiers.iter().fold(0, |bitset, modifier| {
+                bitset | (1 << legend_position(SEMANTIC_TOKEN_MODIFIERS, modifier))
+            }),
+        });
+
+        previous_end = span.end;
+        previous_line = line;
+        previous_column = column;
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::semantic_tokens;
+    use crate::tests::checked_module;
+    use aiken_lang::grammar::SEMANTIC_TOKEN_TYPES;
+    use indoc::indoc;
+
+    /// Decode tokens into absolute (line, column, length, type) tuples.
+    fn decode(code: &str) -> Vec<(u32, u32, u32, &'static str)> {
+        let mut line = 0;
+        let mut column = 0;
+
+        semantic_tokens(&checked_module(code))
+            .into_iter()
+            .map(|token| {
+                if token.delta_line > 0 {
+                    column = 0;
+                }
+
+                line += token.delta_line;
+                column += token.delta_start;
+
+                (
+                    line,
+                    column,
+                    token.length,
+                    SEMANTIC_TOKEN_TYPES[token.token_type as usize],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn classify_names() {
+        let code = indoc! {r#"
+            pub type Answer {
+              Yes
+              No
+            }
+
+            pub fn check(answer: Answer) -> Bool {
+              answer == Yes
+            }
+        "#};
+
+        assert_eq!(
+            decode(code),
+            vec![
+                (0, 9, 6, "type"),
+                (1, 2, 3, "enumMember"),
+                (2, 2, 2, "enumMember"),
+                (5, 7, 5, "function"),
+                (5, 13, 6, "parameter"),
+                (5, 21, 6, "type"),
+                (5, 32, 4, "type"),
+                (6, 2, 6, "variable"),
+                (6, 12, 3, "enumMember"),
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_in_utf16_code_units() {
+        let code = indoc! {r#"
+            fn pick(_greeting: String, name: String) -> String {
+              name
+            }
+
+            pub fn greet(name: String) -> String {
+              pick(@"héllo 👋", name)
+            }
+        "#};
+
+        // 'name' comes after 'é' (2 bytes, 1 code unit) and '👋' (4 bytes, 2 code units).
+        assert_eq!(decode(code).last(), Some(&(5, 20, 4, "variable")));
+    }
+}
@@ -2,25 +2,32 @@ use self::lsp_project::LspProject;
 use crate::{
     cast::{cast_notification, cast_request},
     error::Error as ServerError,
+    inlay_hints::inlay_hints,
     quickfix,
     quickfix::Quickfix,
+    semantic_tokens::semantic_tokens,
+    symbols::{document_symbols, workspace_symbols},
     utils::{
-        path_to_uri, span_to_lsp_range, text_edit_replace, uri_to_module_name,
-        COMPILING_PROGRESS_TOKEN, CREATE_COMPILING_PROGRESS_TOKEN,
+        path_to_uri, span_to_lsp_range, text_edit_replace, uri_to_module_name, uri_to_path,
+        COMPILING_PROGRESS_TOKEN, CREATE_COMPILING_PROGRESS_TOKEN, HOVER_MAX_COLUMNS,
     },
 };
 use aiken_lang::{
-    ast::{Definition, Located, ModuleKind, Span, Use},
+    ast::{Definition, DefinitionLocation, Located, ModuleKind, Span, Use},
     error::ExtraData,
+    expr::TypedExpr,
     line_numbers::LineNumbers,
     parser,
-    tipo::pretty::Printer,
+    tipo::{pretty::Printer, ValueConstructorVariant},
 };
 use aiken_project::{
     config::{self, Config},
+    cost_model,
     error::{Error as ProjectError, GetSource},
     module::CheckedModule,
+    vfs::{OsFs, OverlayFs, Vfs},
 };
+use crossbeam_channel::RecvTimeoutError;
 use indoc::formatdoc;
 use itertools::Itertools;
 use lsp_server::{Connection, Message};
@@ -30,21 +37,30 @@ use lsp_types::{
         Notification, Progress, PublishDiagnostics, ShowMessage,
     },
     request::{
-        CodeActionRequest, Completion, Formatting, GotoDefinition, HoverRequest, Request,
-        WorkDoneProgressCreate,
+        CodeActionRequest, Completion, DocumentSymbolRequest, Formatting, GotoDefinition,
+        HoverRequest, InlayHintRequest, Request, SemanticTokensFullRequest, WorkDoneProgressCreate,
+        WorkspaceSymbolRequest,
     },
     DocumentFormattingParams, InitializeParams, TextEdit,
 };
 use miette::Diagnostic;
 use std::{
     collections::{HashMap, HashSet},
-    fs,
     path::{Path, PathBuf},
+    time::Duration,
+};
+use uplc::{
+    builtins::DefaultFunction,
+    machine::cost_model::{initialize_cost_model, CostModel},
 };
 
 pub mod lsp_project;
 pub mod telemetry;
 
+/// How long edits must settle before recompiling, so that typing doesn't trigger a compilation
+/// per keystroke.
+const EDIT_DEBOUNCE: Duration = Duration::from_millis(200);
+
 #[allow(dead_code)]
 pub struct Server {
     // Project root directory
@@ -52,8 +68,8 @@ pub struct Server {
 
     config: Option<config::Config>,
 
-    /// Files that have been edited in memory
-    edited: HashMap<String, String>,
+    /// Files that have been edited in memory, overlaid on the disk content seen by the compiler
+    edited: OverlayFs,
 
     initialize_params: InitializeParams,
 
@@ -71,6 +87,13 @@ pub struct Server {
 
     /// An instance of a LspProject
     compiler: Option<LspProject>,
+
+    /// Whether files were edited since the last compilation
+    pending_edits: bool,
+
+    /// Protocol parameters to read the cost model shown on hover from, instead of the built-in
+    /// one
+    cost_model: Option<PathBuf>,
 }
 
 impl Server {
@@ -102,6 +125,8 @@ impl Server {
     fn compile(&mut self, connection: &Connection) -> Result<(), ServerError> {
         self.notify_client_of_compilation_start(connection)?;
 
+        self.pending_edits = false;
+
         if let Some(compiler) = self.compiler.as_mut() {
             let result = compiler.compile();
 
@@ -145,7 +170,9 @@ impl Server {
 
     fn create_new_compiler(&mut self) {
         if let Some(config) = self.config.as_ref() {
-            let compiler = LspProject::new(config.clone(), self.root.clone(), telemetry::Lsp);
+            let mut compiler = LspProject::new(config.clone(), self.root.clone(), telemetry::Lsp);
+
+            compiler.project.set_vfs(self.edited.clone());
 
             self.compiler = Some(compiler);
         }
@@ -156,39 +183,24 @@ impl Server {
         params: DocumentFormattingParams,
     ) -> Result<Vec<TextEdit>, Vec<ProjectError>> {
         let path = params.text_document.uri.path();
-        let mut new_text = String::new();
 
-        match self.edited.get(path) {
-            Some(src) => {
-                let (module, extra) = parser::module(src, ModuleKind::Lib).map_err(|errs| {
-                    aiken_project::error::Error::from_parse_errors(errs, Path::new(path), src)
-                })?;
+        // Prefer the editor's unsaved content over what's on disk.
+        let src = self
+            .edited
+            .read_to_string(&uri_to_path(&params.text_document.uri))
+            .map_err(ProjectError::from)?;
 
-                aiken_lang::format::pretty(&mut new_text, module, extra, src);
-            }
-            None => {
-                let src = {
-                    #[cfg(not(target_os = "windows"))]
-                    {
-                        fs::read_to_string(path).map_err(ProjectError::from)?
-                    }
-                    #[cfg(target_os = "windows")]
-                    {
-                        let temp = match urlencoding::decode(path) {
-                            Ok(decoded) => decoded.to_string(),
-                            Err(_) => path.to_owned(),
-                        };
-                        fs::read_to_string(temp.trim_start_matches("/"))
-                            .map_err(ProjectError::from)?
-                    }
-                };
+        let (module, extra) = parser::module(&src, ModuleKind::Lib).map_err(|errs| {
+            aiken_project::error::Error::from_parse_errors(errs, Path::new(path), &src)
+        })?;
 
-                let (module, extra) = parser::module(&src, ModuleKind::Lib).map_err(|errs| {
-                    aiken_project::error::Error::from_parse_errors(errs, Path::new(path), &src)
-                })?;
+        let mut new_text = String::new();
 
-                aiken_lang::format::pretty(&mut new_text, module, extra, &src);
-            }
+        aiken_lang::format::pretty(&mut new_text, module, extra, &src);
+
+        // Avoid touching the buffer (and moving the cursor around) when there's nothing to do.
+        if new_text == src {
+            return Ok(vec![]);
         }
 
         Ok(vec![text_edit_replace(new_text)])
@@ -204,7 +216,7 @@ impl Server {
             DidSaveTextDocument::METHOD => {
                 let params = cast_notification::<DidSaveTextDocument>(notification)?;
 
-                self.edited.remove(params.text_document.uri.path());
+                self.edited.forget(&uri_to_path(&params.text_document.uri));
 
                 self.compile(connection)?;
 
@@ -216,20 +228,24 @@ impl Server {
             DidChangeTextDocument::METHOD => {
                 let params = cast_notification::<DidChangeTextDocument>(notification)?;
 
-                // A file has changed in the editor so store a copy of the new content in memory
-                let path = params.text_document.uri.path().to_string();
+                // A file has changed in the editor so store a copy of the new content in memory,
+                // and recompile so that diagnostics reflect what's in the editor.
+                let path = uri_to_path(&params.text_document.uri);
 
                 if let Some(changes) = params.content_changes.into_iter().next() {
                     self.edited.insert(path, changes.text);
                 }
 
+                // Compiled once edits settle; see 'listen'.
+                self.pending_edits = true;
+
                 Ok(())
             }
 
             DidCloseTextDocument::METHOD => {
                 let params = cast_notification::<DidCloseTextDocument>(notification)?;
 
-                self.edited.remove(params.text_document.uri.path());
+                self.edited.forget(&uri_to_path(&params.text_document.uri));
 
                 Ok(())
             }
@@ -319,6 +335,76 @@ impl Server {
                 })
             }
 
+            DocumentSymbolRequest::METHOD => {
+                let params = cast_request::<DocumentSymbolRequest>(request)?;
+
+                let symbols = self
+                    .module_for_uri(&params.text_document.uri)
+                    .map(|module| {
+                        lsp_types::DocumentSymbolResponse::Nested(document_symbols(module))
+                    });
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(symbols)?),
+                })
+            }
+
+            WorkspaceSymbolRequest::METHOD => {
+                let params = cast_request::<WorkspaceSymbolRequest>(request)?;
+
+                let symbols = self.compiler.as_ref().map(|compiler| {
+                    let modules = compiler.modules.iter().filter_map(|(name, module)| {
+                        let source = compiler.sources.get(name)?;
+                        let uri = path_to_uri(PathBuf::from(&source.path)).ok()?;
+                        Some((module, uri))
+                    });
+
+                    lsp_types::WorkspaceSymbolResponse::Flat(workspace_symbols(
+                        modules,
+                        &params.query,
+                    ))
+                });
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(symbols)?),
+                })
+            }
+
+            InlayHintRequest::METHOD => {
+                let params = cast_request::<InlayHintRequest>(request)?;
+
+                let hints = self.inlay_hints(params);
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(hints)?),
+                })
+            }
+
+            SemanticTokensFullRequest::METHOD => {
+                let params = cast_request::<SemanticTokensFullRequest>(request)?;
+
+                let tokens = self
+                    .module_for_uri(&params.text_document.uri)
+                    .map(|module| {
+                        lsp_types::SemanticTokensResult::Tokens(lsp_types::SemanticTokens {
+                            result_id: None,
+                            data: semantic_tokens(module),
+                        })
+                    });
+
+                Ok(lsp_server::Response {
+                    id,
+                    error: None,
+                    result: Some(serde_json::to_value(tokens)?),
+                })
+            }
+
             Completion::METHOD => {
                 let params = cast_request::<Completion>(request).expect("cast Completion");
 
@@ -451,7 +537,12 @@ impl Server {
             None => return Ok(None),
         };
 
-        let location = match node.definition_location() {
+        let location = match node {
+            Located::Expression(expression) => self.definition_location(expression),
+            _ => node.definition_location(),
+        };
+
+        let location = match location {
             Some(location) => location,
             None => return Ok(None),
         };
@@ -481,6 +572,25 @@ impl Server {
         Ok(Some(lsp_types::Location { uri, range }))
     }
 
+    /// Where the value behind an expression is defined. Values selected from a module
+    /// re-exporting them resolve to the module they come from.
+    fn definition_location<'a>(
+        &'a self,
+        expression: &'a TypedExpr,
+    ) -> Option<DefinitionLocation<'a>> {
+        let location = expression.definition_location()?;
+
+        match (expression, &self.compiler) {
+            (TypedExpr::ModuleSelect { label, .. }, Some(compiler)) => Some(DefinitionLocation {
+                module: location
+                    .module
+                    .map(|module| compiler.defining_module(module, label)),
+                span: location.span,
+            }),
+            _ => Some(location),
+        }
+    }
+
     fn node_at_position(
         &self,
         params: &lsp_types::TextDocumentPositionParams,
@@ -499,6 +609,34 @@ impl Server {
         Some((line_numbers, node))
     }
 
+    fn inlay_hints(&self, params: lsp_types::InlayHintParams) -> Option<Vec<lsp_types::InlayHint>> {
+        let compiler = self.compiler.as_ref()?;
+
+        let module = self.module_for_uri(&params.text_document.uri)?;
+
+        let resolve = |module: &str, name: &str| {
+            let value = compiler
+                .modules
+                .get(module)?
+                .ast
+                .type_info
+                .values
+                .get(name)?;
+
+            match &value.variant {
+                ValueConstructorVariant::ModuleFn { field_map, .. } => field_map.clone(),
+                _ => None,
+            }
+        };
+
+        let hints = inlay_hints(module, resolve)
+            .into_iter()
+            .filter(|hint| params.range.start <= hint.position && hint.position <= params.range.end)
+            .collect();
+
+        Some(hints)
+    }
+
     fn module_for_uri(&self, uri: &url::Url) -> Option<&CheckedModule> {
         self.compiler.as_ref().and_then(|compiler| {
             let module_name = uri_to_module_name(uri, &self.root).expect("uri to module name");
@@ -518,10 +656,21 @@ impl Server {
             None => return Ok(None),
         };
 
+        let builtin = match &found {
+            Located::Expression(TypedExpr::Var { constructor, .. }) => match constructor.variant {
+                ValueConstructorVariant::ModuleFn { builtin, .. } => builtin,
+                _ => None,
+            },
+            Located::Expression(TypedExpr::ModuleSelect {
+                module_name, label, ..
+            }) if module_name == "aiken/builtin" => DefaultFunction::from_aiken_name(label),
+            _ => None,
+        };
+
         let (location, definition_location, tipo) = match found {
             Located::Expression(expression) => (
                 expression.location(),
-                expression.definition_location(),
+                self.definition_location(expression),
                 Some(expression.tipo()),
             ),
             Located::Pattern(pattern, tipo) => (pattern.location(), None, Some(tipo)),
@@ -550,15 +699,19 @@ impl Server {
 
         // Show the type of the hovered node to the user
         let type_ = match tipo {
-            Some(t) => Printer::new().pretty_print(t.as_ref(), 0),
+            Some(t) => Printer::new().pretty_print_with_width(t.as_ref(), 0, HOVER_MAX_COLUMNS),
             None => "?".to_string(),
         };
 
+        let costs = builtin
+            .map(|builtin| self.builtin_costs(builtin))
+            .unwrap_or_default();
+
         let contents = formatdoc! {r#"
             ```aiken
             {type_}
             ```
-            {doc}
+            {costs}{doc}
         "#};
 
         Ok(Some(lsp_types::Hover {
@@ -567,6 +720,50 @@ impl Server {
         }))
     }
 
+    /// The costing functions of a builtin, for the Plutus version the project targets and the
+    /// cost model given with '--cost-model', if any.
+    fn builtin_costs(&self, builtin: DefaultFunction) -> String {
+        let plutus = self
+            .config
+            .as_ref()
+            .map(|config| config.plutus)
+            .unwrap_or_default();
+
+        let language = plutus.into();
+
+        let (model, source) = match &self.cost_model {
+            Some(path) => match cost_model::from_protocol_parameters(&self.edited, path, plutus) {
+                Ok(costs) => (
+                    initialize_cost_model(&language, &costs),
+                    format!("cost model from {}", path.display()),
+                ),
+                Err(error) => {
+                    tracing::warn!("invalid cost model: {error}");
+                    (
+                        CostModel::for_language(&language),
+                        format!("built-in cost model, {} is invalid", path.display()),
+                    )
+                }
+            },
+            None => (
+                CostModel::for_language(&language),
+                "built-in cost model".to_string(),
+            ),
+        };
+
+        let formula = model.builtin_costs.formula(builtin);
+
+        formatdoc! {r#"
+            **Cost** (Plutus {plutus}, {source}, where x, y, z are the sizes of the arguments)
+            - cpu: `{cpu}`
+            - mem: `{mem}`
+
+        "#,
+            cpu = formula.cpu,
+            mem = formula.mem,
+        }
+    }
+
     #[allow(clippy::result_large_err)]
     pub fn listen(&mut self, connection: Connection) -> Result<(), ServerError> {
         self.create_compilation_progress_token(&connection)?;
@@ -576,7 +773,23 @@ impl Server {
         self.compile(&connection)?;
         self.publish_stored_diagnostics(&connection)?;
 
-        for msg in &connection.receiver {
+        loop {
+            let msg = if self.pending_edits {
+                match connection.receiver.recv_timeout(EDIT_DEBOUNCE) {
+                    Ok(msg) => msg,
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.compile_pending_edits(&connection)?;
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            } else {
+                match connection.receiver.recv() {
+                    Ok(msg) => msg,
+                    Err(_) => break,
+                }
+            };
+
             tracing::debug!("Got message: {:#?}", msg);
 
             match msg {
@@ -587,6 +800,9 @@ impl Server {
 
                     tracing::debug!("Get request: {:#?}", req);
 
+                    // Answer from what's currently in the editor.
+                    self.compile_pending_edits(&connection)?;
+
                     let response = self.handle_request(req, &connection)?;
 
                     connection.sender.send(Message::Response(response))?;
@@ -601,20 +817,33 @@ impl Server {
         Ok(())
     }
 
+    #[allow(clippy::result_large_err)]
+    fn compile_pending_edits(&mut self, connection: &Connection) -> Result<(), ServerError> {
+        if self.pending_edits {
+            self.compile(connection)?;
+            self.publish_stored_diagnostics(connection)?;
+        }
+
+        Ok(())
+    }
+
     pub fn new(
         initialize_params: InitializeParams,
         config: Option<config::Config>,
         root: PathBuf,
+        cost_model: Option<PathBuf>,
     ) -> Self {
         let mut server = Server {
             root,
             config,
-            edited: HashMap::new(),
+            edited: OverlayFs::new(OsFs),
             initialize_params,
             published_diagnostics: HashSet::new(),
             stored_diagnostics: HashMap::new(),
             stored_messages: Vec::new(),
             compiler: None,
+            pending_edits: false,
+            cost_model,
         };
 
         server.create_new_compiler();
@@ -690,14 +919,25 @@ impl Server {
             if let Some(labeled_span) = labels.next() {
                 let line_numbers = LineNumbers::new(&src);
 
+                #[cfg(not(target_os = "windows"))]
+                let path = path.canonicalize()?;
+
+                // Secondary labels (e.g. the annotation a mismatch originates from) point at
+                // the same module as the primary one.
+                let related_information = labels
+                    .map(|label| {
+                        Ok(lsp_types::DiagnosticRelatedInformation {
+                            location: lsp_types::Location {
+                                uri: path_to_uri(path.clone())?,
+                                range: span_to_lsp_range(label_span(&label), &line_numbers),
+                            },
+                            message: label.label().unwrap_or_default().to_string(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ServerError>>()?;
+
                 let lsp_diagnostic = lsp_types::Diagnostic {
-                    range: span_to_lsp_range(
-                        Span {
-                            start: labeled_span.inner().offset(),
-                            end: labeled_span.inner().offset() + labeled_span.inner().len(),
-                        },
-                        &line_numbers,
-                    ),
+                    range: span_to_lsp_range(label_span(&labeled_span), &line_numbers),
                     severity: Some(severity),
                     code: error.code().map(|c| {
                         lsp_types::NumberOrString::String(
@@ -710,14 +950,15 @@ impl Server {
                     code_description: None,
                     source: None,
                     message,
-                    related_information: None,
+                    related_information: if related_information.is_empty() {
+                        None
+                    } else {
+                        Some(related_information)
+                    },
                     tags: None,
                     data: error.extra_data().map(serde_json::Value::String),
                 };
 
-                #[cfg(not(target_os = "windows"))]
-                let path = path.canonicalize()?;
-
                 self.push_diagnostic(path.clone(), lsp_diagnostic.clone());
 
                 if let Some(hint) = error.help() {
@@ -864,3 +1105,10 @@ impl Server {
         Ok(())
     }
 }
+
+fn label_span(label: &miette::LabeledSpan) -> Span {
+    Span {
+        start: label.inner().offset(),
+        end: label.inner().offset() + label.inner().len(),
+    }
+}
@@ -1,5 +1,8 @@
-use aiken_lang::{ast::Tracing, line_numbers::LineNumbers, test_framework::PropertyTest};
-use aiken_project::{config::Config, error::Error as ProjectError, module::CheckedModule, Project};
+use aiken_lang::{ast::Tracing, line_numbers::LineNumbers};
+use aiken_project::{
+    config::Config, error::Error as ProjectError, module::CheckedModule, options::CheckOptions,
+    Project,
+};
 use std::{collections::HashMap, path::PathBuf};
 
 #[derive(Debug)]
@@ -29,26 +32,23 @@ impl LspProject {
     pub fn compile(&mut self) -> Result<(), Vec<ProjectError>> {
         let checkpoint = self.project.checkpoint();
 
-        let result = self.project.check(
-            true,
-            None,
-            false,
-            false,
-            u32::default(),
-            PropertyTest::DEFAULT_MAX_SUCCESS,
-            Tracing::verbose(),
-            None,
-        );
+        let result = self.project.check(CheckOptions {
+            skip_tests: true,
+            tracing: Tracing::verbose(),
+            ..CheckOptions::default()
+        });
 
         self.project.restore(checkpoint);
 
         let modules = self.project.modules();
 
         for mut module in modules.into_iter() {
+            // Modules only known to the editor (e.g. unsaved new files) have nothing to resolve
+            // on disk.
             let path = module
                 .input_path
                 .canonicalize()
-                .expect("Canonicalize")
+                .unwrap_or_else(|_| module.input_path.clone())
                 .as_os_str()
                 .to_string_lossy()
                 .to_string();
@@ -67,4 +67,18 @@ impl LspProject {
 
         Ok(())
     }
+
+    /// The module actually defining 'name' when reached through 'module', following the chain
+    /// of re-exports ('pub use') back to its origin.
+    pub fn defining_module<'a>(&'a self, mut module: &'a str, name: &str) -> &'a str {
+        while let Some(origin) = self
+            .modules
+            .get(module)
+            .and_then(|checked_module| checked_module.ast.type_info.reexports.get(name))
+        {
+            module = origin;
+        }
+
+        module
+    }
 }
@@ -0,0 +1,330 @@
+use crate::utils::{find_word, single_line_type, span_to_lsp_range};
+use aiken_lang::{
+    ast::{Definition, Span, TypedDefinition},
+    line_numbers::LineNumbers,
+};
+use aiken_project::module::CheckedModule;
+
+/// The outline of a module: its functions, types (and their constructors), constants,
+/// validators (and their handlers), tests and benchmarks.
+pub fn document_symbols(module: &CheckedModule) -> Vec<lsp_types::DocumentSymbol> {
+    let line_numbers = LineNumbers::new(&module.code);
+
+    module
+        .ast
+        .definitions()
+        .filter_map(|definition| symbol(module, &line_numbers, definition))
+        .collect()
+}
+
+/// Symbols of all the given modules whose name fuzzy-matches the query, for project-wide
+/// navigation. Nested symbols (constructors, handlers) are flattened and reported along with
+/// the symbol containing them.
+#[allow(deprecated)]
+pub fn workspace_symbols<'a>(
+    modules: impl Iterator<Item = (&'a CheckedModule, lsp_types::Url)>,
+    query: &str,
+) -> Vec<lsp_types::SymbolInformation> {
+    let mut symbols = Vec::new();
+
+    for (module, uri) in modules {
+        let mut pending = document_symbols(module)
+            .into_iter()
+            .map(|symbol| (symbol, None))
+            .collect::<Vec<_>>();
+
+        while let Some((symbol, container_name)) = pending.pop() {
+            pending.extend(
+                symbol
+                    .children
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .map(|child| (child, Some(symbol.name.clone()))),
+            );
+
+            if fuzzy_match(query, &symbol.name) {
+                symbols.push(lsp_types::SymbolInformation {
+                    name: symbol.name,
+                    kind: symbol.kind,
+                    tags: None,
+                    deprecated: None,
+                    location: lsp_types::Location {
+                        uri: uri.clone(),
+                        range: symbol.selection_range,
+                    },
+                    container_name: container_name.or_else(|| Some(module.name.clone())),
+                });
+            }
+        }
+    }
+
+    symbols.sort_by(|a, b| a.name.cmp(&b.name));
+
+    symbols
+}
+
+/// Whether all characters of the query appear in order in the name, ignoring case.
+fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| name.any(|n| n == c))
+}
+
+fn symbol(
+    module: &CheckedModule,
+    line_numbers: &LineNumbers,
+    definition: &TypedDefinition,
+) -> Option<lsp_types::DocumentSymbol> {
+    let signature = |name: &str| {
+        module
+            .ast
+            .type_info
+            .values
+            .get(name)
+            .map(|value| single_line_type(&value.tipo))
+    };
+
+    let new = |name: &str, kind, location: Span, detail: Option<String>, children| {
+        document_symbol(module, line_numbers, name, kind, location, detail, children)
+    };
+
+    match definition {
+        Definition::Fn(function) => Some(new(
+            &function.name,
+            lsp_types::SymbolKind::FUNCTION,
+            whole(function.location, function.end_position),
+            signature(&function.name),
+            None,
+        )),
+
+        Definition::Test(test) => Some(new(
+            &test.name,
+            lsp_types::SymbolKind::METHOD,
+            whole(test.location, test.end_position),
+            Some("test".to_string()),
+            None,
+        )),
+
+        Definition::Benchmark(bench) => Some(new(
+            &bench.name,
+            lsp_types::SymbolKind::METHOD,
+            whole(bench.location, bench.end_position),
+            Some("bench".to_string()),
+            None,
+        )),
+
+        Definition::Validator(validator) => {
+            let handlers = validator
+                .handlers
+                .iter()
+                .map(|handler| {
+                    new(
+                        &handler.name,
+                        lsp_types::SymbolKind::METHOD,
+                        handler.location,
+                        None,
+                        None,
+                    )
+                })
+                .collect();
+
+            Some(new(
+                &validator.name,
+                lsp_types::SymbolKind::CLASS,
+                whole(validator.location, validator.end_position),
+                Some("validator".to_string()),
+                Some(handlers),
+            ))
+        }
+
+        Definition::DataType(data_type) => {
+            let constructors = data_type
+                .constructors
+                .iter()
+                .map(|constructor| {
+                    new(
+                        &constructor.name,
+                        lsp_types::SymbolKind::ENUM_MEMBER,
+                        constructor.location,
+                        None,
+                        None,
+                    )
+                })
+                .collect();
+
+            Some(new(
+                &data_type.name,
+                lsp_types::SymbolKind::ENUM,
+                data_type.location,
+                None,
+                Some(constructors),
+            ))
+        }
+
+        Definition::TypeAlias(alias) => Some(new(
+            &alias.alias,
+            lsp_types::SymbolKind::STRUCT,
+            alias.location,
+            Some(single_line_type(&alias.tipo)),
+            None,
+        )),
+
+        Definition::ModuleConstant(constant) => Some(new(
+            &constant.name,
+            lsp_types::SymbolKind::CONSTANT,
+            constant.location,
+            signature(&constant.name),
+            None,
+        )),
+
+        Definition::Use(_) => None,
+    }
+}
+
+/// The span of a definition including its body, since its location only covers its head.
+fn whole(location: Span, end_position: usize) -> Span {
+    Span {
+        start: location.start,
+        end: end_position + 1,
+    }
+}
+
+#[allow(deprecated)]
+fn document_symbol(
+    module: &CheckedModule,
+    line_numbers: &LineNumbers,
+    name: &str,
+    kind: lsp_types::SymbolKind,
+    location: Span,
+    detail: Option<String>,
+    children: Option<Vec<lsp_types::DocumentSymbol>>,
+) -> lsp_types::DocumentSymbol {
+    let range = span_to_lsp_range(location, line_numbers);
+
+    let selection_range = find_word(&module.code, location, name)
+        .map(|span| span_to_lsp_range(span, line_numbers))
+        .unwrap_or(range);
+
+    lsp_types::DocumentSymbol {
+        name: name.to_string(),
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{document_symbols, workspace_symbols};
+    use crate::tests::checked_module;
+    use indoc::indoc;
+    use lsp_types::SymbolKind;
+
+    const CODE: &str = indoc! {r#"
+        pub type Answer {
+          Yes
+          No
+        }
+
+        pub const limit: Int = 42
+
+        pub fn check(answer: Answer) -> Bool {
+          answer == Yes
+        }
+
+        test check_yes() {
+          check(Yes)
+        }
+    "#};
+
+    fn outline(
+        symbols: &[lsp_types::DocumentSymbol],
+    ) -> Vec<(String, SymbolKind, u32, u32, Option<String>)> {
+        symbols
+            .iter()
+            .flat_map(|symbol| {
+                std::iter::once((
+                    symbol.name.clone(),
+                    symbol.kind,
+                    symbol.selection_range.start.line,
+                    symbol.selection_range.start.character,
+                    symbol.detail.clone(),
+                ))
+                .chain(outline(symbol.children.as_deref().unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn document_outline() {
+        assert_eq!(
+            outline(&document_symbols(&checked_module(CODE))),
+            vec![
+                ("Answer".to_string(), SymbolKind::ENUM, 0, 9, None),
+                ("Yes".to_string(), SymbolKind::ENUM_MEMBER, 1, 2, None),
+                ("No".to_string(), SymbolKind::ENUM_MEMBER, 2, 2, None),
+                (
+                    "limit".to_string(),
+                    SymbolKind::CONSTANT,
+                    5,
+                    10,
+                    Some("Int".to_string())
+                ),
+                (
+                    "check".to_string(),
+                    SymbolKind::FUNCTION,
+                    7,
+                    7,
+                    Some("fn(Answer) -> Bool".to_string())
+                ),
+                (
+                    "check_yes".to_string(),
+                    SymbolKind::METHOD,
+                    11,
+                    5,
+                    Some("test".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_search() {
+        let module = checked_module(CODE);
+
+        let uri = lsp_types::Url::parse("file:///in-memory/project/lib/foo.ak").unwrap();
+
+        let found = |query: &str| {
+            workspace_symbols(std::iter::once((&module, uri.clone())), query)
+                .into_iter()
+                .map(|symbol| (symbol.name, symbol.container_name.unwrap_or_default()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            found("yes"),
+            vec![
+                ("Yes".to_string(), "Answer".to_string()),
+                ("check_yes".to_string(), "foo".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            found("CHK"),
+            vec![
+                ("check".to_string(), "foo".to_string()),
+                ("check_yes".to_string(), "foo".to_string()),
+            ]
+        );
+
+        assert!(found("nope").is_empty());
+    }
+}
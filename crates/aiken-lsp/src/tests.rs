@@ -0,0 +1,33 @@
+use crate::server::{lsp_project::LspProject, telemetry};
+use aiken_project::{
+    config::Config, module::CheckedModule, package_name::PackageName, vfs::OverlayFs,
+};
+use std::path::PathBuf;
+
+/// Compile a project made of a single 'lib/foo.ak' module, the way the language server does,
+/// and return that module.
+pub fn checked_module(code: &str) -> CheckedModule {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = OverlayFs::in_memory();
+
+    vfs.insert(root.join("lib/foo.ak"), code);
+
+    let config = Config::with_dependencies(
+        &PackageName {
+            owner: "test".to_string(),
+            repo: "project".to_string(),
+        },
+        vec![],
+    );
+
+    let mut project = LspProject::new(config, root, telemetry::Lsp);
+
+    project.project.set_vfs(vfs);
+
+    project
+        .compile()
+        .unwrap_or_else(|errors| panic!("{errors:#?}"));
+
+    project.modules.remove("foo").expect("module 'foo'")
+}
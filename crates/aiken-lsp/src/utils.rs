@@ -1,5 +1,9 @@
 use crate::error::Error;
-use aiken_lang::{ast::Span, line_numbers::LineNumbers};
+use aiken_lang::{
+    ast::Span,
+    line_numbers::LineNumbers,
+    tipo::{pretty::Printer, Type},
+};
 use itertools::Itertools;
 use lsp_types::TextEdit;
 use std::path::{Path, PathBuf};
@@ -8,6 +12,15 @@ use urlencoding::decode;
 pub const COMPILING_PROGRESS_TOKEN: &str = "compiling-aiken";
 pub const CREATE_COMPILING_PROGRESS_TOKEN: &str = "create-compiling-progress-token";
 
+/// Types shown in hovers wrap beyond this width, as editors display hovers in narrow popups.
+pub const HOVER_MAX_COLUMNS: isize = 60;
+
+/// Render a type on a single line, for labels that can't span several lines such as inlay
+/// hints or symbol details.
+pub fn single_line_type(tipo: &Type) -> String {
+    Printer::new().pretty_print_with_width(tipo, 0, isize::MAX)
+}
+
 pub fn text_edit_replace(new_text: String) -> TextEdit {
     TextEdit {
         range: lsp_types::Range {
@@ -55,8 +68,9 @@ pub fn span_to_lsp_range(location: Span, line_numbers: &LineNumbers) -> lsp_type
     }
 }
 
-pub fn uri_to_module_name(uri: &url::Url, root: &Path) -> Option<String> {
-    let path = if cfg!(target_os = "windows") {
+/// The path of the file behind a 'file://' URI.
+pub fn uri_to_path(uri: &url::Url) -> PathBuf {
+    if cfg!(target_os = "windows") {
         let mut uri_path = decode(&uri.path().replace('/', "\\"))
             .expect("Invalid formatting")
             .to_string();
@@ -71,7 +85,11 @@ pub fn uri_to_module_name(uri: &url::Url, root: &Path) -> Option<String> {
         PathBuf::from(uri_path)
     } else {
         PathBuf::from(uri.path())
-    };
+    }
+}
+
+pub fn uri_to_module_name(uri: &url::Url, root: &Path) -> Option<String> {
+    let path = uri_to_path(uri);
 
     let components = path
         .strip_prefix(root)
@@ -87,3 +105,22 @@ pub fn uri_to_module_name(uri: &url::Url, root: &Path) -> Option<String> {
 
     Some(module_name)
 }
+
+/// The first occurrence of a whole word within a span of the source code.
+pub fn find_word(code: &str, within: Span, word: &str) -> Option<Span> {
+    let haystack = code.get(within.start..within.end)?;
+
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    haystack
+        .match_indices(word)
+        .find(|(offset, _)| {
+            let before = haystack[..*offset].chars().next_back();
+            let after = haystack[offset + word.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+        .map(|(offset, _)| Span {
+            start: within.start + offset,
+            end: within.start + offset + word.len(),
+        })
+}
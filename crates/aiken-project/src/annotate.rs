@@ -0,0 +1,208 @@
+//! An HTML report laying out the source of each validator, and of every function it uses, line
+//! by line next to the UPLC they compiled to; so that auditors can review what actually runs
+//! on-chain.
+
+use aiken_lang::gen_uplc::source_map::{Entry, SourceMap};
+use std::fmt::Write;
+
+const STYLE: &str = r#"<style>
+body { font-family: sans-serif; margin: 2rem; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2rem; }
+td { vertical-align: top; padding: 0 0.5rem; }
+td.line { color: #999; text-align: right; user-select: none; width: 3rem; }
+td.source { border-right: 1px solid #ccc; width: 45%; }
+td.uplc { background: #f6f6f6; }
+pre { margin: 0; font-size: 0.85rem; }
+td.uplc pre { max-height: 40rem; overflow: auto; }
+td.uplc span.name { color: #999; font-size: 0.75rem; }
+</style>"#;
+
+/// A validator, with the source code of the modules its source map points into.
+pub struct Annotated<'a> {
+    pub title: String,
+    pub source_map: &'a SourceMap,
+    pub sources: Vec<(&'a str, &'a str)>,
+}
+
+impl Annotated<'_> {
+    fn source(&self, module: &str) -> Option<&str> {
+        self.sources
+            .iter()
+            .find(|(name, _)| *name == module)
+            .map(|(_, code)| *code)
+    }
+
+    /// Entries not nested in another entry of the same module, each along with the entries
+    /// nested in it (itself included), in source order.
+    fn blocks(&self) -> Vec<(&Entry, Vec<&Entry>)> {
+        let entries = &self.source_map.entries;
+
+        let encloses = |(i, outer): (usize, &Entry), (j, inner): (usize, &Entry)| {
+            outer.module == inner.module
+                && outer.location.start <= inner.location.start
+                && inner.location.end <= outer.location.end
+                // Among identical spans, the first entry encloses the others.
+                && (outer.location != inner.location || i < j)
+        };
+
+        entries
+            .iter()
+            .enumerate()
+            .filter(|&(j, inner)| {
+                !entries
+                    .iter()
+                    .enumerate()
+                    .any(|(i, outer)| i != j && encloses((i, outer), (j, inner)))
+            })
+            .map(|(i, outer)| {
+                let mut nested = entries
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, inner)| i == j || encloses((i, outer), (j, inner)))
+                    .map(|(_, inner)| inner)
+                    .collect::<Vec<_>>();
+
+                nested.sort_by_key(|entry| entry.location.start);
+
+                (outer, nested)
+            })
+            .collect()
+    }
+}
+
+pub fn to_html(validators: &[Annotated<'_>]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Annotated validators</title>\n{STYLE}\n</head>\n<body>\n"
+    );
+
+    for validator in validators {
+        let _ = writeln!(html, "<h2>{}</h2>", escape(&validator.title));
+
+        for (block, entries) in validator.blocks() {
+            let Some(code) = validator.source(&block.module) else {
+                continue;
+            };
+
+            let _ = writeln!(
+                html,
+                "<h3>{}.{}</h3>\n<table>",
+                escape(&block.module),
+                escape(&block.name)
+            );
+
+            let line_of = |offset: usize| code[..offset.min(code.len())].matches('\n').count() + 1;
+
+            let first_line = line_of(block.location.start);
+
+            let lines = code
+                .get(block.location.start..block.location.end.min(code.len()))
+                .unwrap_or_default()
+                .lines();
+
+            for (ix, line) in lines.enumerate() {
+                let _ = write!(
+                    html,
+                    "<tr><td class=\"line\">{}</td><td class=\"source\"><pre>{}</pre></td><td class=\"uplc\">",
+                    first_line + ix,
+                    escape(line)
+                );
+
+                for entry in entries
+                    .iter()
+                    .filter(|entry| line_of(entry.location.start) == first_line + ix)
+                {
+                    let _ = write!(
+                        html,
+                        "<span class=\"name\">{}</span><pre>{}</pre>",
+                        escape(&entry.name),
+                        escape(&entry.term.to_pretty())
+                    );
+                }
+
+                html.push_str("</td></tr>\n");
+            }
+
+            html.push_str("</table>\n");
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aiken_lang::ast::Span;
+    use uplc::ast::{Name, Term};
+
+    fn entry(name: &str, start: usize, end: usize, term: Term<Name>) -> Entry {
+        Entry {
+            module: "foo".to_string(),
+            name: name.to_string(),
+            location: Span { start, end },
+            term,
+        }
+    }
+
+    #[test]
+    fn lines_next_to_their_uplc() {
+        let code = "use aiken/list\n\nfn double(n) {\n  let m = n * 2\n  m\n}\n";
+
+        let source_map = SourceMap {
+            entries: vec![
+                entry("double", 16, code.len() - 1, Term::Error),
+                entry("m", 33, 46, Term::integer(2.into())),
+            ],
+        };
+
+        let html = to_html(&[Annotated {
+            title: "foo.bar.spend".to_string(),
+            source_map: &source_map,
+            sources: vec![("foo", code)],
+        }]);
+
+        assert_eq!(html.matches("<h3>").count(), 1);
+        assert!(html.contains(&format!(
+            "<tr><td class=\"line\">3</td><td class=\"source\"><pre>fn double(n) {{</pre></td><td class=\"uplc\"><span class=\"name\">double</span><pre>{}</pre></td></tr>",
+            escape(&Term::<Name>::Error.to_pretty())
+        )));
+        assert!(html.contains(&format!(
+            "<tr><td class=\"line\">4</td><td class=\"source\"><pre>  let m = n * 2</pre></td><td class=\"uplc\"><span class=\"name\">m</span><pre>{}</pre></td></tr>",
+            escape(&Term::<Name>::integer(2.into()).to_pretty())
+        )));
+        assert!(html.contains(
+            "<tr><td class=\"line\">6</td><td class=\"source\"><pre>}</pre></td><td class=\"uplc\"></td></tr>"
+        ));
+    }
+
+    #[test]
+    fn unrelated_entries_in_their_own_block() {
+        let code = "fn double(n) {\n  n * 2\n}\n\nfn triple(n) {\n  n * 3\n}\n";
+
+        let source_map = SourceMap {
+            entries: vec![
+                entry("triple", 26, code.len() - 1, Term::Error),
+                entry("double", 0, 25, Term::Error),
+            ],
+        };
+
+        let html = to_html(&[Annotated {
+            title: "foo.bar.spend".to_string(),
+            source_map: &source_map,
+            sources: vec![("foo", code)],
+        }]);
+
+        assert_eq!(html.matches("<h3>").count(), 2);
+        assert!(html.contains("<h3>foo.triple</h3>"));
+        assert!(html.contains("<h3>foo.double</h3>"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Reproducible tar archives of build artifacts, for releasing contract bundles. Entries are
+//! sorted by path and carry no timestamp, owner or permission of the machine that built them;
+//! so identical artifacts always yield byte-for-byte identical archives (and hashes).
+
+use pallas_crypto::hash::Hasher;
+use serde::Serialize;
+use std::{collections::BTreeMap, io};
+
+const BLOCK: usize = 512;
+
+/// Name of the manifest bundled alongside the artifacts.
+pub const MANIFEST: &str = "manifest.json";
+
+/// Every entry gets this modification time: the epoch.
+pub const MTIME: u64 = 0;
+
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub name: String,
+    pub version: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: usize,
+    pub blake2b_256: String,
+}
+
+impl Manifest {
+    pub fn new(name: String, version: String, files: &BTreeMap<String, Vec<u8>>) -> Self {
+        Manifest {
+            name,
+            version,
+            files: files
+                .iter()
+                .map(|(path, bytes)| ManifestEntry {
+                    path: path.clone(),
+                    size: bytes.len(),
+                    blake2b_256: Hasher::<256>::hash(bytes).to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Bundle the given files, along with their manifest, into a ustar archive.
+pub fn bundle(manifest: &Manifest, files: &BTreeMap<String, Vec<u8>>) -> io::Result<Vec<u8>> {
+    let mut archive = Vec::new();
+
+    append(&mut archive, MANIFEST, manifest.to_json().as_bytes())?;
+
+    for (path, bytes) in files {
+        append(&mut archive, path, bytes)?;
+    }
+
+    // The end of an archive is marked by two empty blocks.
+    archive.resize(archive.len() + 2 * BLOCK, 0);
+
+    Ok(archive)
+}
+
+fn append(archive: &mut Vec<u8>, path: &str, bytes: &[u8]) -> io::Result<()> {
+    let mut header = [0; BLOCK];
+
+    let (prefix, name) = split_path(path)?;
+
+    header[0..name.len()].copy_from_slice(name.as_bytes());
+    octal(&mut header[100..108], 0o644);
+    octal(&mut header[108..116], 0);
+    octal(&mut header[116..124], 0);
+    octal(&mut header[124..136], bytes.len() as u64);
+    octal(&mut header[136..148], MTIME);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is computed with its own field filled with spaces.
+    header[148..156].fill(b' ');
+    let checksum = header.iter().map(|byte| *byte as u64).sum::<u64>();
+    octal(&mut header[148..155], checksum);
+
+    archive.extend_from_slice(&header);
+    archive.extend_from_slice(bytes);
+    archive.resize(archive.len().next_multiple_of(BLOCK), 0);
+
+    Ok(())
+}
+
+/// Names longer than 100 bytes are split, on a separator, into a prefix and a name.
+fn split_path(path: &str) -> io::Result<(&str, &str)> {
+    if path.len() <= 100 {
+        return Ok(("", path));
+    }
+
+    path.match_indices('/')
+        .map(|(ix, _)| (&path[..ix], &path[ix + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100 && !name.is_empty())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("path too long to be archived: {path}"),
+            )
+        })
+}
+
+/// A zero-padded octal number, terminated by a NUL byte.
+fn octal(field: &mut [u8], n: u64) {
+    let width = field.len() - 1;
+    field[..width].copy_from_slice(format!("{n:0width$o}").as_bytes());
+    field[width] = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> BTreeMap<String, Vec<u8>> {
+        BTreeMap::from([
+            ("plutus.json".to_string(), b"{}".to_vec()),
+            (
+                "artifacts/validators.uplc".to_string(),
+                b"(program 1.1.0 (con unit ()))".to_vec(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn reproducible_and_sorted() {
+        let manifest = Manifest::new("aiken/foo".to_string(), "1.0.0".to_string(), &files());
+
+        let archive = bundle(&manifest, &files()).unwrap();
+
+        assert_eq!(archive, bundle(&manifest, &files()).unwrap());
+        assert_eq!(archive.len() % BLOCK, 0);
+
+        let names = archive
+            .chunks(BLOCK)
+            .filter(|block| &block[257..262] == b"ustar")
+            .map(|block| {
+                let end = block[..100].iter().position(|b| *b == 0).unwrap_or(100);
+                String::from_utf8(block[..end].to_vec()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![MANIFEST, "artifacts/validators.uplc", "plutus.json"]
+        );
+
+        let header = &archive[..BLOCK];
+        assert_eq!(&header[136..148], b"00000000000\0");
+
+        let checksum = header
+            .iter()
+            .enumerate()
+            .map(|(ix, byte)| {
+                u64::from(if (148..156).contains(&ix) {
+                    b' '
+                } else {
+                    *byte
+                })
+            })
+            .sum::<u64>();
+        assert_eq!(&header[148..155], format!("{checksum:06o}\0").as_bytes());
+    }
+
+    #[test]
+    fn long_paths() {
+        let path = format!("{}/{}", "a".repeat(120), "b".repeat(90));
+
+        assert_eq!(
+            split_path(&path).unwrap(),
+            ("a".repeat(120).as_str(), "b".repeat(90).as_str())
+        );
+        assert!(split_path(&"c".repeat(101)).is_err());
+    }
+}
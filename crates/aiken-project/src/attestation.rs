@@ -0,0 +1,307 @@
+//! Provenance of a build: what went into it (compiler, options, sources and dependencies)
+//! alongside digests of what came out of it. An attestation lets a third party, e.g. an
+//! auditor, rebuild a project and check that they obtain the exact same validators.
+
+use crate::{blueprint::audit, error::Error, module::CheckedModule};
+use aiken_lang::{
+    ast::{Definition, ModuleConstant, TraceLevel, Tracing},
+    expr::TypedExpr,
+};
+use pallas_crypto::hash::Hasher;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path, path::PathBuf};
+use uplc::optimize::Pass;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation {
+    /// Compiler version, including the commit it was built from.
+    pub compiler: String,
+    pub project: String,
+    pub version: String,
+    pub options: BuildOptions,
+    /// Digest of the project's own modules.
+    pub sources: String,
+    pub dependencies: BTreeMap<String, Dependency>,
+    pub artifacts: Artifacts,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildOptions {
+    pub trace_filter: String,
+    pub trace_level: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Vec<String>>,
+    pub trace_codes: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimization_passes: Option<Vec<Pass>>,
+    pub prelude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Digest of the package's modules, as compiled.
+    pub checksum: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifacts {
+    /// Digest of the blueprint file, as written.
+    pub blueprint: String,
+    /// Hash of each validator's compiled code, by title.
+    pub validators: BTreeMap<String, String>,
+    /// Digest of the trace codes table, when trace codes are enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traces: Option<String>,
+}
+
+impl Attestation {
+    /// Where the attestation of a blueprint is written, next to it.
+    pub fn path(blueprint_path: &Path) -> PathBuf {
+        blueprint_path.with_extension("attestation.json")
+    }
+
+    pub fn parse(path: &Path, json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| Error::InvalidAttestation {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Everything that differs between this attestation and the one of a rebuild, as
+    /// human-readable lines. An empty list means that the rebuild is faithful.
+    pub fn differences(&self, rebuilt: &Attestation) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        let mut compare = |what: String, expected: Option<&str>, actual: Option<&str>| {
+            if expected != actual {
+                differences.push(format!(
+                    "{what}: expected {}, got {}",
+                    expected.unwrap_or("nothing"),
+                    actual.unwrap_or("nothing")
+                ));
+            }
+        };
+
+        compare(
+            "compiler".to_string(),
+            Some(&self.compiler),
+            Some(&rebuilt.compiler),
+        );
+
+        compare(
+            "sources".to_string(),
+            Some(&self.sources),
+            Some(&rebuilt.sources),
+        );
+
+        for name in keys(&self.dependencies, &rebuilt.dependencies) {
+            let (expected, actual) = (self.dependencies.get(name), rebuilt.dependencies.get(name));
+
+            compare(
+                format!("dependency {name}"),
+                expected.map(|dep| dep.checksum.as_str()),
+                actual.map(|dep| dep.checksum.as_str()),
+            );
+        }
+
+        for title in keys(&self.artifacts.validators, &rebuilt.artifacts.validators) {
+            compare(
+                format!("validator {title}"),
+                self.artifacts.validators.get(title).map(String::as_str),
+                rebuilt.artifacts.validators.get(title).map(String::as_str),
+            );
+        }
+
+        compare(
+            "trace codes".to_string(),
+            self.artifacts.traces.as_deref(),
+            rebuilt.artifacts.traces.as_deref(),
+        );
+
+        compare(
+            "blueprint".to_string(),
+            Some(&self.artifacts.blueprint),
+            Some(&rebuilt.artifacts.blueprint),
+        );
+
+        differences
+    }
+}
+
+impl BuildOptions {
+    pub fn new(
+        tracing: Tracing,
+        env: Option<String>,
+        filter: Option<Vec<String>>,
+        trace_codes: bool,
+        optimization_passes: Option<Vec<Pass>>,
+        prelude: Vec<String>,
+        metadata: Option<String>,
+    ) -> Self {
+        let (trace_filter, trace_level) = match tracing {
+            Tracing::UserDefined(level) => ("user-defined", level),
+            Tracing::CompilerGenerated(level) => ("compiler-generated", level),
+            Tracing::All(level) => ("all", level),
+        };
+
+        BuildOptions {
+            trace_filter: trace_filter.to_string(),
+            trace_level: trace_level.to_string(),
+            env,
+            filter,
+            trace_codes,
+            optimization_passes,
+            prelude,
+            metadata,
+        }
+    }
+
+    /// The tracing options recorded, if they make sense.
+    pub fn tracing(&self) -> Option<Tracing> {
+        let level = match self.trace_level.as_str() {
+            "silent" => TraceLevel::Silent,
+            "compact" => TraceLevel::Compact,
+            "verbose" => TraceLevel::Verbose,
+            _ => return None,
+        };
+
+        match self.trace_filter.as_str() {
+            "user-defined" => Some(Tracing::UserDefined(level)),
+            "compiler-generated" => Some(Tracing::CompilerGenerated(level)),
+            "all" => Some(Tracing::All(level)),
+            _ => None,
+        }
+    }
+}
+
+/// Digest of a set of modules, independent of the order in which they're given. The content
+/// of the fixtures they load is covered as well, since it ends up in the code.
+pub fn checksum<'a>(modules: impl Iterator<Item = &'a CheckedModule>) -> String {
+    let modules = modules.collect::<Vec<_>>();
+
+    let fixtures = modules
+        .iter()
+        .flat_map(|module| module.ast.definitions())
+        .filter_map(|definition| match definition {
+            Definition::ModuleConstant(ModuleConstant {
+                fixture: Some(file),
+                value: TypedExpr::ByteArray { bytes, .. },
+                ..
+            }) => Some((format!("fixtures/{file}"), hex::encode(bytes))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    digest(
+        modules
+            .iter()
+            .map(|module| (module.name.as_str(), module.code.as_str()))
+            .chain(
+                fixtures
+                    .iter()
+                    .map(|(file, bytes)| (file.as_str(), bytes.as_str())),
+            ),
+    )
+}
+
+/// Digest of a set of modules given by name and source code, as for 'checksum'.
+pub fn digest<'a>(modules: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut modules = modules.collect::<Vec<_>>();
+
+    modules.sort();
+
+    let preimage = modules
+        .into_iter()
+        .map(|(name, code)| format!("{name}\0{code}"))
+        .collect::<Vec<_>>()
+        .join("\0");
+
+    Hasher::<256>::hash(preimage.as_bytes()).to_string()
+}
+
+/// Digests of a freshly written blueprint (and trace codes table, if any).
+pub fn artifacts(blueprint: &str, traces: Option<&str>) -> Result<Artifacts, Error> {
+    Ok(Artifacts {
+        blueprint: Hasher::<256>::hash(blueprint.as_bytes()).to_string(),
+        validators: audit::hashes(blueprint)?,
+        traces: traces.map(|traces| Hasher::<256>::hash(traces.as_bytes()).to_string()),
+    })
+}
+
+fn keys<'a, V>(left: &'a BTreeMap<String, V>, right: &'a BTreeMap<String, V>) -> Vec<&'a String> {
+    let mut keys = left.keys().chain(right.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracing_roundtrip() {
+        for tracing in [
+            Tracing::UserDefined(TraceLevel::Compact),
+            Tracing::CompilerGenerated(TraceLevel::Verbose),
+            Tracing::All(TraceLevel::Silent),
+        ] {
+            let options = BuildOptions::new(tracing, None, None, false, None, vec![], None);
+            assert_eq!(options.tracing(), Some(tracing));
+        }
+    }
+
+    #[test]
+    fn differences_between_attestations() {
+        let attestation = Attestation {
+            compiler: "v1.1.3+abcdef".to_string(),
+            project: "foo/bar".to_string(),
+            version: "0.0.0".to_string(),
+            options: BuildOptions::new(Tracing::silent(), None, None, false, None, vec![], None),
+            sources: "00".to_string(),
+            dependencies: BTreeMap::from([(
+                "aiken-lang/stdlib".to_string(),
+                Dependency {
+                    version: Some("v2.2.0".to_string()),
+                    checksum: "01".to_string(),
+                },
+            )]),
+            artifacts: Artifacts {
+                blueprint: "02".to_string(),
+                validators: BTreeMap::from([("foo.bar.spend".to_string(), "03".to_string())]),
+                traces: None,
+            },
+        };
+
+        assert!(attestation.differences(&attestation).is_empty());
+
+        let mut rebuilt = attestation.clone();
+        rebuilt.dependencies.clear();
+        rebuilt
+            .artifacts
+            .validators
+            .insert("foo.bar.spend".to_string(), "04".to_string());
+
+        assert_eq!(
+            attestation.differences(&rebuilt),
+            vec![
+                "dependency aiken-lang/stdlib: expected 01, got nothing".to_string(),
+                "validator foo.bar.spend: expected 03, got 04".to_string(),
+            ]
+        );
+    }
+}
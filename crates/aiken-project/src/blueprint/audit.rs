@@ -0,0 +1,234 @@
+use super::error::Error;
+use crate::serialization;
+use aiken_lang::plutus_version::PlutusVersion;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use uplc::ast::SerializableProgram;
+
+/// A validator whose compiled code decodes, re-encodes and re-hashes to exactly what the
+/// blueprint says.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedArtifact {
+    pub title: String,
+    pub size: usize,
+    pub hash: String,
+}
+
+/// A difference between two blueprints, as far as compiled code is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactDifference {
+    OnlyInLeft {
+        title: String,
+    },
+    OnlyInRight {
+        title: String,
+    },
+    Changed {
+        title: String,
+        left: String,
+        right: String,
+    },
+}
+
+struct RawArtifact {
+    title: String,
+    compiled_code: String,
+    hash: String,
+}
+
+/// Re-decode the compiled code of each validator of a blueprint, then serialize and hash it
+/// anew to make sure it's byte-for-byte identical to what's recorded in the blueprint.
+pub fn verify(blueprint: &str) -> Result<Vec<VerifiedArtifact>, Error> {
+    let blueprint: Value =
+        serde_json::from_str(blueprint).map_err(|_| Error::InvalidOrMissingFile)?;
+
+    let plutus_version: PlutusVersion =
+        serde_json::from_value(blueprint["preamble"]["plutusVersion"].clone())
+            .map_err(|_| Error::InvalidOrMissingFile)?;
+
+    artifacts(&blueprint)?
+        .into_iter()
+        .map(|artifact| {
+            let mismatch = |reason: String| Error::NonReproducibleArtifact {
+                title: artifact.title.clone(),
+                reason,
+            };
+
+            let program = serialization::from_compiled_code(&artifact.compiled_code)
+                .map_err(|e| mismatch(format!("its compiled code doesn't decode: {e}")))?;
+
+            let program = match plutus_version {
+                PlutusVersion::V1 => SerializableProgram::PlutusV1Program(program),
+                PlutusVersion::V2 => SerializableProgram::PlutusV2Program(program),
+                PlutusVersion::V3 => SerializableProgram::PlutusV3Program(program),
+            };
+
+            let (compiled_code, hash) = program.compiled_code_and_hash();
+
+            if compiled_code != artifact.compiled_code {
+                return Err(mismatch(
+                    "re-encoding its compiled code yields different bytes.".to_string(),
+                ));
+            }
+
+            if hash.to_string() != artifact.hash {
+                return Err(mismatch(format!(
+                    "its compiled code hashes to {hash}, not {}.",
+                    artifact.hash
+                )));
+            }
+
+            Ok(VerifiedArtifact {
+                size: compiled_code.len() / 2,
+                title: artifact.title,
+                hash: artifact.hash,
+            })
+        })
+        .collect()
+}
+
+/// The hash of each validator's compiled code, by title, as recorded in a blueprint.
+pub fn hashes(blueprint: &str) -> Result<BTreeMap<String, String>, Error> {
+    let blueprint: Value =
+        serde_json::from_str(blueprint).map_err(|_| Error::InvalidOrMissingFile)?;
+
+    Ok(artifacts(&blueprint)?
+        .into_iter()
+        .map(|artifact| (artifact.title, artifact.hash))
+        .collect())
+}
+
+/// Compare the compiled code of two blueprints, validator by validator (matched by title).
+pub fn compare(left: &str, right: &str) -> Result<Vec<ArtifactDifference>, Error> {
+    let left = hashes(left)?;
+    let mut right = hashes(right)?;
+
+    let mut differences = Vec::new();
+
+    for (title, left_hash) in left {
+        match right.remove(&title) {
+            None => differences.push(ArtifactDifference::OnlyInLeft { title }),
+            Some(right_hash) if right_hash != left_hash => {
+                differences.push(ArtifactDifference::Changed {
+                    title,
+                    left: left_hash,
+                    right: right_hash,
+                })
+            }
+            Some(..) => (),
+        }
+    }
+
+    differences.extend(
+        right
+            .into_keys()
+            .map(|title| ArtifactDifference::OnlyInRight { title }),
+    );
+
+    Ok(differences)
+}
+
+fn artifacts(blueprint: &Value) -> Result<Vec<RawArtifact>, Error> {
+    blueprint["validators"]
+        .as_array()
+        .ok_or(Error::InvalidOrMissingFile)?
+        .iter()
+        .map(|validator| {
+            let field = |name: &str| {
+                validator[name]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or(Error::InvalidOrMissingFile)
+            };
+
+            Ok(RawArtifact {
+                title: field("title")?,
+                compiled_code: field("compiledCode")?,
+                hash: field("hash")?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uplc::ast::{DeBruijn, Program};
+
+    fn blueprint(validators: &[(&str, &str)]) -> String {
+        let validators = validators
+            .iter()
+            .map(|(title, source)| {
+                let program: Program<DeBruijn> =
+                    uplc::parser::program(source).unwrap().try_into().unwrap();
+
+                let mut validator =
+                    serde_json::to_value(SerializableProgram::PlutusV3Program(program)).unwrap();
+
+                validator["title"] = json!(title);
+
+                validator
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "preamble": {
+                "title": "foo/bar",
+                "version": "0.0.0",
+                "plutusVersion": "v3",
+            },
+            "validators": validators,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn verify_roundtrip() {
+        let blueprint = blueprint(&[("foo.bar.spend", "(program 1.1.0 (con integer 42))")]);
+
+        let verified = verify(&blueprint).unwrap();
+
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified[0].title, "foo.bar.spend");
+    }
+
+    #[test]
+    fn verify_tampered_hash() {
+        let blueprint = blueprint(&[("foo.bar.spend", "(program 1.1.0 (con integer 42))")]);
+
+        let mut value: Value = serde_json::from_str(&blueprint).unwrap();
+        value["validators"][0]["hash"] = json!("00".repeat(28));
+
+        assert!(matches!(
+            verify(&value.to_string()),
+            Err(Error::NonReproducibleArtifact { .. })
+        ));
+    }
+
+    #[test]
+    fn compare_blueprints() {
+        let left = blueprint(&[
+            ("foo.bar.spend", "(program 1.1.0 (con integer 42))"),
+            ("foo.baz.mint", "(program 1.1.0 (con integer 1))"),
+        ]);
+
+        let right = blueprint(&[
+            ("foo.bar.spend", "(program 1.1.0 (con integer 43))"),
+            ("foo.qux.mint", "(program 1.1.0 (con integer 1))"),
+        ]);
+
+        let differences = compare(&left, &right).unwrap();
+
+        assert!(matches!(
+            differences.as_slice(),
+            [
+                ArtifactDifference::Changed { title: changed, .. },
+                ArtifactDifference::OnlyInLeft { title: left },
+                ArtifactDifference::OnlyInRight { title: right },
+            ] if changed == "foo.bar.spend" && left == "foo.baz.mint" && right == "foo.qux.mint"
+        ));
+
+        assert!(compare(&left, &left).unwrap().is_empty());
+    }
+}
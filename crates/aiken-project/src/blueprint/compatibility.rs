@@ -0,0 +1,456 @@
+//! Compatibility between two builds of a same contract: what an updated blueprint changes to
+//! the interface of its validators (datums, redeemers and parameters) and to their hashes.
+//!
+//! Schema changes are assessed from the perspective of existing data, e.g. datums already
+//! locked on-chain or redeemers built by off-chain code: a change is breaking if some data
+//! accepted by the old schema isn't accepted by the new one anymore.
+
+use super::{
+    definitions::{Definitions, Reference},
+    parameter::Parameter,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Breaking,
+    Compatible,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub validator: String,
+    pub severity: Severity,
+    pub description: String,
+}
+
+/// All changes between an old and a new blueprint, validator by validator (matched by title).
+pub fn diff(old: &Blueprint, new: &Blueprint) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for old_validator in &old.validators {
+        let validator = old_validator.title.clone();
+
+        let Some(new_validator) = new.validators.iter().find(|v| v.title == validator) else {
+            changes.push(Change {
+                validator,
+                severity: Severity::Breaking,
+                description: "validator removed".to_string(),
+            });
+            continue;
+        };
+
+        let mut comparator = Comparator::new(&old.definitions, &new.definitions);
+
+        let (_, old_hash) = old_validator.program.compiled_code_and_hash();
+        let (_, new_hash) = new_validator.program.compiled_code_and_hash();
+
+        if old_hash != new_hash {
+            comparator.breaking(format!(
+                "script hash changed from {old_hash} to {new_hash}; so does any address or policy id derived from it"
+            ));
+        }
+
+        comparator.parameter("datum", &old_validator.datum, &new_validator.datum);
+
+        comparator.parameter("redeemer", &old_validator.redeemer, &new_validator.redeemer);
+
+        if old_validator.parameters.len() != new_validator.parameters.len() {
+            comparator.breaking(format!(
+                "expects {} parameter(s) instead of {}",
+                new_validator.parameters.len(),
+                old_validator.parameters.len()
+            ));
+        } else {
+            for (ix, (old_param, new_param)) in old_validator
+                .parameters
+                .iter()
+                .zip(&new_validator.parameters)
+                .enumerate()
+            {
+                let path = match &new_param.title {
+                    Some(title) => format!("parameter '{title}'"),
+                    None => format!("parameter #{}", ix + 1),
+                };
+
+                comparator.declaration(&path, &old_param.schema, &new_param.schema);
+            }
+        }
+
+        changes.extend(
+            comparator
+                .changes
+                .into_iter()
+                .map(|(severity, description)| Change {
+                    validator: validator.clone(),
+                    severity,
+                    description,
+                }),
+        );
+    }
+
+    changes.extend(
+        new.validators
+            .iter()
+            .filter(|new_validator| {
+                !old.validators
+                    .iter()
+                    .any(|old_validator| old_validator.title == new_validator.title)
+            })
+            .map(|new_validator| Change {
+                validator: new_validator.title.clone(),
+                severity: Severity::Compatible,
+                description: "validator added".to_string(),
+            }),
+    );
+
+    changes
+}
+
+struct Comparator<'a> {
+    old_definitions: &'a Definitions<Annotated<Schema>>,
+    new_definitions: &'a Definitions<Annotated<Schema>>,
+    // Pairs of references already compared, so that recursive types are visited only once.
+    visited: BTreeSet<(Reference, Reference)>,
+    changes: Vec<(Severity, String)>,
+}
+
+impl<'a> Comparator<'a> {
+    fn new(
+        old_definitions: &'a Definitions<Annotated<Schema>>,
+        new_definitions: &'a Definitions<Annotated<Schema>>,
+    ) -> Self {
+        Comparator {
+            old_definitions,
+            new_definitions,
+            visited: BTreeSet::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    fn breaking(&mut self, description: String) {
+        self.changes.push((Severity::Breaking, description));
+    }
+
+    fn compatible(&mut self, description: String) {
+        self.changes.push((Severity::Compatible, description));
+    }
+
+    fn parameter(&mut self, path: &str, old: &Option<Parameter>, new: &Option<Parameter>) {
+        match (old, new) {
+            (None, None) => (),
+            (Some(..), None) => self.breaking(format!("{path}: no longer specified")),
+            (None, Some(..)) => self.breaking(format!("{path}: now specified")),
+            (Some(old), Some(new)) => self.declaration(path, &old.schema, &new.schema),
+        }
+    }
+
+    /// Whether both declarations need comparing; that is, unless they're references already
+    /// compared (or being compared) to one another.
+    fn visit<T>(&mut self, old: &Declaration<T>, new: &Declaration<T>) -> bool {
+        match (old.reference(), new.reference()) {
+            (Some(old), Some(new)) => self.visited.insert((old.clone(), new.clone())),
+            _ => true,
+        }
+    }
+
+    fn declaration(&mut self, path: &str, old: &Declaration<Schema>, new: &Declaration<Schema>) {
+        if !self.visit(old, new) {
+            return;
+        }
+
+        match (
+            old.schema(self.old_definitions),
+            new.schema(self.new_definitions),
+        ) {
+            (Some(old), Some(new)) => self.schema(path, old, new),
+            _ => self.unresolved(path, old.reference(), new.reference()),
+        }
+    }
+
+    fn data_declaration(&mut self, path: &str, old: &Declaration<Data>, new: &Declaration<Data>) {
+        if !self.visit(old, new) {
+            return;
+        }
+
+        match (
+            old.schema(self.old_definitions),
+            new.schema(self.new_definitions),
+        ) {
+            (Some(old), Some(new)) => self.data(path, old, new),
+            _ => self.unresolved(path, old.reference(), new.reference()),
+        }
+    }
+
+    fn unresolved(&mut self, path: &str, old: Option<&Reference>, new: Option<&Reference>) {
+        let reference = old.or(new).map(|r| r.to_string()).unwrap_or_default();
+        self.breaking(format!(
+            "{path}: schema can't be compared, as '{reference}' is missing from definitions"
+        ));
+    }
+
+    fn schema(&mut self, path: &str, old: &Schema, new: &Schema) {
+        match (old, new) {
+            (Schema::Data(old), Schema::Data(new)) => self.data(path, old, new),
+            (Schema::Pair(old_fst, old_snd), Schema::Pair(new_fst, new_snd)) => {
+                self.declaration(&format!("{path}.1st"), old_fst, new_fst);
+                self.declaration(&format!("{path}.2nd"), old_snd, new_snd);
+            }
+            (Schema::List(old), Schema::List(new)) => self.items(path, old, new, Self::declaration),
+            (Schema::Unit, Schema::Unit)
+            | (Schema::Boolean, Schema::Boolean)
+            | (Schema::Integer, Schema::Integer)
+            | (Schema::Bytes, Schema::Bytes)
+            | (Schema::String, Schema::String) => (),
+            _ => self.breaking(format!(
+                "{path}: changed from {} to {}",
+                describe_schema(old),
+                describe_schema(new)
+            )),
+        }
+    }
+
+    fn data(&mut self, path: &str, old: &Data, new: &Data) {
+        match (old, new) {
+            (Data::Opaque, Data::Opaque)
+            | (Data::Integer, Data::Integer)
+            | (Data::Bytes, Data::Bytes) => (),
+            (_, Data::Opaque) => self.compatible(format!(
+                "{path}: now accepts any data instead of {}",
+                describe_data(old)
+            )),
+            (Data::List(old), Data::List(new)) => {
+                self.items(path, old, new, Self::data_declaration)
+            }
+            (Data::Map(old_keys, old_values), Data::Map(new_keys, new_values)) => {
+                self.data_declaration(&format!("{path}.keys"), old_keys, new_keys);
+                self.data_declaration(&format!("{path}.values"), old_values, new_values);
+            }
+            (Data::AnyOf(old), Data::AnyOf(new)) => self.constructors(path, old, new),
+            _ => self.breaking(format!(
+                "{path}: changed from {} to {}",
+                describe_data(old),
+                describe_data(new)
+            )),
+        }
+    }
+
+    fn items<T>(
+        &mut self,
+        path: &str,
+        old: &Items<T>,
+        new: &Items<T>,
+        compare: fn(&mut Self, &str, &Declaration<T>, &Declaration<T>),
+    ) {
+        match (old, new) {
+            (Items::One(old), Items::One(new)) => compare(self, &format!("{path}[]"), old, new),
+            (Items::Many(old), Items::Many(new)) if old.len() == new.len() => {
+                for (ix, (old, new)) in old.iter().zip(new).enumerate() {
+                    compare(self, &format!("{path}.{ix}"), old, new);
+                }
+            }
+            _ => self.breaking(format!("{path}: list elements changed shape")),
+        }
+    }
+
+    fn constructors(
+        &mut self,
+        path: &str,
+        old: &[Annotated<Constructor>],
+        new: &[Annotated<Constructor>],
+    ) {
+        for old_constructor in old {
+            let name = constructor_name(old_constructor);
+
+            let Some(new_constructor) = new
+                .iter()
+                .find(|c| c.annotated.index == old_constructor.annotated.index)
+            else {
+                self.breaking(format!("{path}: constructor {name} removed"));
+                continue;
+            };
+
+            let new_name = constructor_name(new_constructor);
+
+            if name != new_name {
+                self.compatible(format!("{path}: constructor {name} renamed to {new_name}"));
+            }
+
+            let (old_fields, new_fields) = (
+                &old_constructor.annotated.fields,
+                &new_constructor.annotated.fields,
+            );
+
+            if old_fields.len() != new_fields.len() {
+                self.breaking(format!(
+                    "{path}: constructor {new_name} has {} field(s) instead of {}",
+                    new_fields.len(),
+                    old_fields.len()
+                ));
+                continue;
+            }
+
+            for (ix, (old_field, new_field)) in old_fields.iter().zip(new_fields).enumerate() {
+                let field = new_field.title.clone().unwrap_or_else(|| ix.to_string());
+
+                self.data_declaration(
+                    &format!("{path}.{new_name}.{field}"),
+                    &old_field.annotated,
+                    &new_field.annotated,
+                );
+            }
+        }
+
+        for new_constructor in new {
+            if !old
+                .iter()
+                .any(|c| c.annotated.index == new_constructor.annotated.index)
+            {
+                self.compatible(format!(
+                    "{path}: constructor {} added",
+                    constructor_name(new_constructor)
+                ));
+            }
+        }
+    }
+}
+
+fn constructor_name(constructor: &Annotated<Constructor>) -> String {
+    match &constructor.title {
+        Some(title) => title.clone(),
+        None => format!("#{}", constructor.annotated.index),
+    }
+}
+
+fn describe_schema(schema: &Schema) -> String {
+    match schema {
+        Schema::Unit => "unit".to_string(),
+        Schema::Boolean => "boolean".to_string(),
+        Schema::Integer => "integer".to_string(),
+        Schema::Bytes => "bytes".to_string(),
+        Schema::String => "string".to_string(),
+        Schema::Pair(..) => "pair".to_string(),
+        Schema::List(..) => "list".to_string(),
+        Schema::Data(data) => describe_data(data),
+    }
+}
+
+fn describe_data(data: &Data) -> String {
+    match data {
+        Data::Integer => "integer".to_string(),
+        Data::Bytes => "bytes".to_string(),
+        Data::List(..) => "list".to_string(),
+        Data::Map(..) => "map".to_string(),
+        Data::AnyOf(constructors) => format!("{} constructor(s)", constructors.len()),
+        Data::Opaque => "any data".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constructor(title: &str, index: usize, fields: Vec<Data>) -> Annotated<Constructor> {
+        Annotated {
+            title: Some(title.to_string()),
+            description: None,
+            annotated: Constructor {
+                index,
+                fields: fields
+                    .into_iter()
+                    .map(|field| Declaration::Inline(Box::new(field)).into())
+                    .collect(),
+            },
+        }
+    }
+
+    fn compare(old: Data, new: Data) -> Vec<(Severity, String)> {
+        let definitions = Definitions::new();
+        let mut comparator = Comparator::new(&definitions, &definitions);
+        comparator.data("datum", &old, &new);
+        comparator.changes
+    }
+
+    #[test]
+    fn identical_schemas() {
+        let schema = Data::AnyOf(vec![constructor("Foo", 0, vec![Data::Integer])]);
+        assert!(compare(schema.clone(), schema).is_empty());
+    }
+
+    #[test]
+    fn added_constructor_is_compatible() {
+        assert_eq!(
+            compare(
+                Data::AnyOf(vec![constructor("Foo", 0, vec![])]),
+                Data::AnyOf(vec![
+                    constructor("Foo", 0, vec![]),
+                    constructor("Bar", 1, vec![]),
+                ]),
+            ),
+            vec![(
+                Severity::Compatible,
+                "datum: constructor Bar added".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn changed_field_is_breaking() {
+        assert_eq!(
+            compare(
+                Data::AnyOf(vec![constructor(
+                    "Foo",
+                    0,
+                    vec![Data::Integer, Data::Bytes]
+                )]),
+                Data::AnyOf(vec![constructor(
+                    "Foo",
+                    0,
+                    vec![Data::Integer, Data::Integer]
+                )]),
+            ),
+            vec![(
+                Severity::Breaking,
+                "datum.Foo.1: changed from bytes to integer".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn removed_constructor_is_breaking() {
+        assert_eq!(
+            compare(
+                Data::AnyOf(vec![
+                    constructor("Foo", 0, vec![]),
+                    constructor("Bar", 1, vec![]),
+                ]),
+                Data::AnyOf(vec![constructor("Foo", 0, vec![])]),
+            ),
+            vec![(
+                Severity::Breaking,
+                "datum: constructor Bar removed".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn opaque_data_accepts_anything() {
+        assert_eq!(
+            compare(Data::Integer, Data::Opaque),
+            vec![(
+                Severity::Compatible,
+                "datum: now accepts any data instead of integer".to_string()
+            )]
+        );
+
+        assert_eq!(
+            compare(Data::Opaque, Data::Integer),
+            vec![(
+                Severity::Breaking,
+                "datum: changed from any data to integer".to_string()
+            )]
+        );
+    }
+}
@@ -2,12 +2,12 @@ use super::{
     definitions::Reference,
     schema::{self, Schema},
 };
-use aiken_lang::ast::Span;
+use aiken_lang::{ast::Span, plutus_version::PlutusVersion};
 use miette::{Diagnostic, NamedSource};
 use owo_colors::{OwoColorize, Stream::Stdout};
 use pallas_codec::minicbor as cbor;
 use std::fmt::Debug;
-use uplc::ast::Constant;
+use uplc::{ast::Constant, builtins::DefaultFunction};
 
 #[derive(Debug, thiserror::Error, Diagnostic)]
 pub enum Error {
@@ -22,6 +22,26 @@ pub enum Error {
         source_code: NamedSource<String>,
     },
 
+    #[error(
+        "I found a validator relying on {builtin}, which isn't available to {plutus_version} scripts.",
+        builtin = builtin.aiken_name().if_supports_color(Stdout, |s| s.purple()),
+    )]
+    #[diagnostic(code("aiken::blueprint::unsupported_builtin"))]
+    #[diagnostic(help(
+        "Builtins are introduced by hard-forks and only ever made available to the latest Plutus version. Either target a more recent version in your {aiken_toml} (e.g. {plutus}) or avoid using {builtin} (directly or through a dependency).",
+        aiken_toml = "aiken.toml".if_supports_color(Stdout, |s| s.bold()),
+        plutus = "plutus = \"v3\"".if_supports_color(Stdout, |s| s.yellow()),
+        builtin = builtin.aiken_name().if_supports_color(Stdout, |s| s.purple()),
+    ))]
+    UnsupportedBuiltin {
+        builtin: DefaultFunction,
+        plutus_version: PlutusVersion,
+        #[label("compiled for {plutus_version}")]
+        location: Span,
+        #[source_code]
+        source_code: NamedSource<String>,
+    },
+
     #[error("Invalid or missing project's blueprint file.")]
     #[diagnostic(code("aiken::blueprint::missing"))]
     #[diagnostic(help(
@@ -117,6 +137,18 @@ pub enum Error {
     #[diagnostic(code("aiken::blueprint::parse::parameter"))]
     #[diagnostic(help("{hint}"))]
     MalformedParameter { hint: String },
+
+    #[error(
+        "I couldn't reproduce the compiled code of {title}.",
+        title = title.if_supports_color(Stdout, |s| s.purple())
+    )]
+    #[diagnostic(code("aiken::blueprint::artifact::mismatch"))]
+    #[diagnostic(help("Decoding it back, {reason}"))]
+    NonReproducibleArtifact { title: String, reason: String },
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Serialization(#[from] crate::serialization::Error),
 }
 
 unsafe impl Send for Error {}
@@ -1,8 +1,11 @@
+pub mod audit;
+pub mod compatibility;
 pub mod definitions;
 pub mod error;
 mod memo_program;
 pub mod parameter;
 pub mod schema;
+pub mod summary;
 pub mod validator;
 
 use crate::{
@@ -61,6 +64,7 @@ impl Blueprint {
         config: &Config,
         modules: &CheckedModules,
         generator: &mut CodeGenerator,
+        filter: Option<&[String]>,
     ) -> Result<Self, Error> {
         let preamble = config.into();
 
@@ -68,9 +72,16 @@ impl Blueprint {
 
         let validators: Result<Vec<_>, Error> = modules
             .validators()
+            .filter(|(module, def)| {
+                Self::is_selected(filter, &format!("{}.{}", module.name, def.name), true)
+            })
             .flat_map(|(validator, def)| {
                 Validator::from_checked_module(modules, generator, validator, def, &config.plutus)
                     .into_iter()
+                    .filter(|result| match result {
+                        Ok(schema) => Self::is_selected(filter, &schema.title, false),
+                        Err(..) => true,
+                    })
                     .map(|result| {
                         result.map(|mut schema| {
                             definitions.merge(&mut schema.definitions);
@@ -91,6 +102,55 @@ impl Blueprint {
 }
 
 impl Blueprint {
+    /// Fold a blueprint built from the validators selected by a filter into a previous one:
+    /// selected validators are replaced (or dropped, when they no longer exist), new ones are
+    /// appended and the others are kept as they were.
+    pub fn merge_into(mut self, previous: Blueprint, filter: &[String]) -> Self {
+        let mut fresh = std::mem::take(&mut self.validators);
+
+        let mut validators = Vec::with_capacity(previous.validators.len());
+
+        for validator in previous.validators {
+            if !Self::is_selected(Some(filter), &validator.title, false) {
+                validators.push(validator);
+            } else if let Some(ix) = fresh.iter().position(|v| v.title == validator.title) {
+                validators.push(fresh.remove(ix));
+            }
+        }
+
+        validators.append(&mut fresh);
+
+        let mut definitions = previous.definitions;
+        definitions.merge(&mut self.definitions);
+
+        Blueprint {
+            preamble: self.preamble,
+            validators,
+            definitions,
+        }
+    }
+
+    /// Whether the validator (or handler) designated by the given title is selected by any of
+    /// the filters; no filter selects everything. A filter selects any title it is a
+    /// dot-separated prefix of, so that `my_module` and `my_module.spend` both select
+    /// `my_module.spend.else`. With `partial`, a title is also selected when it is a prefix of a
+    /// filter, which is handy to pre-select validators before knowing their handlers.
+    pub fn is_selected(filter: Option<&[String]>, title: &str, partial: bool) -> bool {
+        let is_prefix = |prefix: &str, of: &str| {
+            of == prefix
+                || of
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        };
+
+        match filter {
+            None => true,
+            Some(filter) => filter
+                .iter()
+                .any(|f| is_prefix(f, title) || (partial && is_prefix(title, f))),
+        }
+    }
+
     pub fn lookup(
         &self,
         want_module_name: Option<&str>,
@@ -190,6 +250,20 @@ mod tests {
     use serde_json::{self, json};
     use std::collections::HashMap;
 
+    #[test]
+    fn select_validators_by_prefix() {
+        let filter = vec!["foo/bar.spend".to_string()];
+        let filter = Some(filter.as_slice());
+
+        assert!(Blueprint::is_selected(None, "foo/bar.mint.mint", false));
+        assert!(Blueprint::is_selected(filter, "foo/bar.spend", false));
+        assert!(Blueprint::is_selected(filter, "foo/bar.spend.else", false));
+        assert!(!Blueprint::is_selected(filter, "foo/bar.spender", false));
+        assert!(!Blueprint::is_selected(filter, "foo/bar", false));
+        assert!(Blueprint::is_selected(filter, "foo/bar", true));
+        assert!(!Blueprint::is_selected(filter, "foo/baz.spend", true));
+    }
+
     #[test]
     fn serialize_no_description() {
         let blueprint = Blueprint {
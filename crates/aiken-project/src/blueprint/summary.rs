@@ -0,0 +1,169 @@
+use super::Blueprint;
+use crate::serialization;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Size and hash of a compiled validator, as recorded in the build folder so that the next
+/// build can tell how sizes evolved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorSize {
+    pub title: String,
+    pub size: usize,
+    pub hash: String,
+}
+
+/// A line of the table shown at the end of a build.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeSummary {
+    pub title: String,
+    pub size: usize,
+    pub hash: String,
+    /// The size of the validator in the previous build, if it existed back then.
+    pub previous: Option<usize>,
+}
+
+impl SizeSummary {
+    /// Change of size since the previous build, as a percentage. There's none when the
+    /// validator is new, or was empty back then.
+    pub fn delta(&self) -> Option<f64> {
+        self.previous
+            .filter(|previous| *previous > 0)
+            .map(|previous| (self.size as f64 - previous as f64) * 100.0 / previous as f64)
+    }
+
+    pub fn delta_to_string(&self) -> String {
+        match (self.previous, self.delta()) {
+            (None, _) => "new".to_string(),
+            (Some(_), None) => "n/a".to_string(),
+            (Some(_), Some(delta)) => format!("{delta:+.1}%"),
+        }
+    }
+}
+
+pub fn sizes(blueprint: &Blueprint) -> Result<Vec<ValidatorSize>, serialization::Error> {
+    blueprint
+        .validators
+        .iter()
+        .map(|validator| {
+            let (compiled_code, hash) = serialization::to_compiled_code(&validator.program)?;
+            Ok(ValidatorSize {
+                title: validator.title.clone(),
+                size: compiled_code.len() / 2,
+                hash: hash.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The sizes to record after a build. A filtered build only gives the sizes of the validators
+/// it selected; the others keep their previously recorded ones.
+pub fn merge(
+    current: Vec<ValidatorSize>,
+    previous: &[ValidatorSize],
+    filtered: bool,
+) -> Vec<ValidatorSize> {
+    if !filtered {
+        return current;
+    }
+
+    let mut merged = previous
+        .iter()
+        .filter(|validator| current.iter().all(|built| built.title != validator.title))
+        .cloned()
+        .chain(current)
+        .collect::<Vec<_>>();
+
+    merged.sort_by(|left, right| left.title.cmp(&right.title));
+
+    merged
+}
+
+pub fn summarize(current: &[ValidatorSize], previous: &[ValidatorSize]) -> Vec<SizeSummary> {
+    let previous = previous
+        .iter()
+        .map(|validator| (validator.title.as_str(), validator.size))
+        .collect::<BTreeMap<_, _>>();
+
+    current
+        .iter()
+        .map(|validator| SizeSummary {
+            title: validator.title.clone(),
+            size: validator.size,
+            hash: validator.hash.clone(),
+            previous: previous.get(validator.title.as_str()).copied(),
+        })
+        .collect()
+}
+
+pub fn to_markdown(summary: &[SizeSummary]) -> String {
+    let mut markdown =
+        String::from("| Validator | Size (bytes) | Δ | Hash |\n|---|---:|---:|---|\n");
+
+    for validator in summary {
+        let _ = writeln!(
+            markdown,
+            "| {} | {} | {} | `{}` |",
+            validator.title,
+            validator.size,
+            validator.delta_to_string(),
+            validator.hash,
+        );
+    }
+
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(title: &str, size: usize) -> ValidatorSize {
+        ValidatorSize {
+            title: title.to_string(),
+            size,
+            hash: "00".repeat(28),
+        }
+    }
+
+    #[test]
+    fn summarize_against_previous_build() {
+        let summary = summarize(
+            &[size("foo.bar.spend", 90), size("foo.baz.mint", 50)],
+            &[size("foo.bar.spend", 100), size("foo.qux.mint", 10)],
+        );
+
+        assert_eq!(
+            summary
+                .iter()
+                .map(SizeSummary::delta_to_string)
+                .collect::<Vec<_>>(),
+            vec!["-10.0%", "new"]
+        );
+
+        assert_eq!(
+            summarize(&[size("foo.bar.spend", 90)], &[size("foo.bar.spend", 0)])[0]
+                .delta_to_string(),
+            "n/a"
+        );
+
+        assert_eq!(
+            to_markdown(&summary).lines().nth(2),
+            Some(format!("| foo.bar.spend | 90 | -10.0% | `{}` |", "00".repeat(28)).as_str())
+        );
+    }
+
+    #[test]
+    fn merge_filtered_builds() {
+        let previous = [size("foo.bar.spend", 100), size("foo.qux.mint", 10)];
+
+        assert_eq!(
+            merge(vec![size("foo.bar.spend", 90)], &previous, true),
+            vec![size("foo.bar.spend", 90), size("foo.qux.mint", 10)]
+        );
+
+        assert_eq!(
+            merge(vec![size("foo.bar.spend", 90)], &previous, false),
+            vec![size("foo.bar.spend", 90)]
+        );
+    }
+}
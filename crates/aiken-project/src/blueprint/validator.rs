@@ -5,7 +5,10 @@ use super::{
     parameter::Parameter,
     schema::{Annotated, Data, Declaration, Schema},
 };
-use crate::module::{CheckedModule, CheckedModules};
+use crate::{
+    module::{CheckedModule, CheckedModules},
+    serialization,
+};
 use aiken_lang::{
     ast::{well_known, Annotation, TypedArg, TypedFunction, TypedValidator},
     gen_uplc::CodeGenerator,
@@ -37,7 +40,7 @@ pub struct Validator {
     #[serde(default)]
     pub parameters: Vec<Parameter>,
 
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "serialization::serialize_program")]
     pub program: SerializableProgram,
 
     #[serde(skip_serializing_if = "Definitions::is_empty")]
@@ -154,6 +157,7 @@ impl Validator {
                                     annotation: Some(annotation),
                                     doc: datum.doc.clone(),
                                     is_validator_param: datum.is_validator_param,
+                                    default: datum.default.clone(),
                                     tipo:  args.first().expect("Option always have a single type argument.").clone()
                                 }),
                                 &mut definitions,
@@ -212,6 +216,25 @@ impl Validator {
             )
             .replace_pairs_with_data_lists();
 
+        let program = program.get(generator, def, &module.name);
+
+        if let Some(builtin) = program
+            .term
+            .builtins()
+            .into_iter()
+            .find(|builtin| !plutus_version.supports_builtin(builtin))
+        {
+            return Err(Error::UnsupportedBuiltin {
+                builtin,
+                plutus_version: *plutus_version,
+                location: def.location,
+                source_code: NamedSource::new(
+                    module.input_path.display().to_string(),
+                    module.code.clone(),
+                ),
+            });
+        }
+
         Ok(Validator {
             title: format!("{}.{}.{}", &module.name, &def.name, &func.name,),
             description: func.doc.clone(),
@@ -222,7 +245,7 @@ impl Validator {
                 PlutusVersion::V1 => SerializableProgram::PlutusV1Program,
                 PlutusVersion::V2 => SerializableProgram::PlutusV2Program,
                 PlutusVersion::V3 => SerializableProgram::PlutusV3Program,
-            }(program.get(generator, def, &module.name)),
+            }(program),
             definitions,
         })
     }
@@ -328,7 +351,7 @@ mod tests {
         tipo::Type,
     };
     use std::collections::HashMap;
-    use uplc::ast as uplc_ast;
+    use uplc::{ast as uplc_ast, builtins::DefaultFunction};
 
     macro_rules! assert_validator {
         ($code:expr) => {
@@ -1100,4 +1123,62 @@ mod tests {
 
         assert!(matches!(param.validate(&definitions, &term), Ok { .. }))
     }
+
+    fn compile_for(code: &str, plutus_version: PlutusVersion) -> Result<Validator, Error> {
+        let mut project = TestProject::new();
+
+        let modules = CheckedModules::singleton(project.check(project.parse(code)));
+
+        let mut generator = project.new_generator(Tracing::silent());
+
+        let (validator, def) = modules
+            .validators()
+            .next()
+            .expect("source code did no yield any validator");
+
+        Validator::from_checked_module(&modules, &mut generator, validator, def, &plutus_version)
+            .into_iter()
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn unsupported_builtin() {
+        let code = indoc::indoc! {r#"
+            use aiken/builtin
+
+            validator thing {
+              mint(redeemer: Int, policy_id: ByteArray, transaction: Data) {
+                builtin.integer_to_bytearray(True, 0, redeemer) == policy_id
+              }
+            }
+        "#};
+
+        assert!(matches!(
+            compile_for(code, PlutusVersion::V2),
+            Err(Error::UnsupportedBuiltin {
+                builtin: DefaultFunction::IntegerToByteString,
+                plutus_version: PlutusVersion::V2,
+                ..
+            })
+        ));
+
+        assert!(compile_for(code, PlutusVersion::V3).is_ok());
+    }
+
+    #[test]
+    fn supported_builtins_on_older_versions() {
+        let code = indoc::indoc! {r#"
+            use aiken/builtin
+
+            validator thing {
+              mint(redeemer: ByteArray, policy_id: ByteArray, transaction: Data) {
+                builtin.blake2b_256(redeemer) == policy_id
+              }
+            }
+        "#};
+
+        assert!(compile_for(code, PlutusVersion::V1).is_ok());
+        assert!(compile_for(code, PlutusVersion::V2).is_ok());
+    }
 }
@@ -0,0 +1,256 @@
+//! A user-level cache of type-checked dependency modules, shared by every project on the
+//! machine (and across CI runs, provided the cache directory is persisted).
+//!
+//! Entries are addressed by checksum: a module's key covers its source, its package, the
+//! compiler version, the tracing options (which shape the typed AST), the build environment
+//! (which decides what `@cfg(...)` attributes keep) and, recursively, the keys of the modules it
+//! imports. A change anywhere upstream therefore yields a different key, and entries never need
+//! to be invalidated.
+//!
+//! Type variables of a cached module were numbered by whichever build type-checked it. Entries
+//! thus record where that numbering stopped, and loading one moves the current numbering past it
+//! so that fresh type variables never collide with the module's.
+
+use crate::{
+    module::{CheckedModule, ParsedModule},
+    paths,
+    vfs::Vfs,
+};
+use aiken_lang::{
+    ast::{Definition, ModuleConstant, TraceSeverity, Tracing, DEFAULT_ENV_MODULE},
+    expr::UntypedExpr,
+    version::compiler_version,
+    IdGenerator,
+};
+use pallas_crypto::hash::Hasher;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+
+#[derive(Serialize, Deserialize)]
+struct Entry<M> {
+    /// An id greater than any type variable's in the module.
+    next_id: u64,
+    module: M,
+}
+
+/// Compute the cache key of a dependency module, given the keys of the modules it may import.
+/// Imports missing from 'known' are compiler-provided modules (e.g. 'aiken/builtin'), already
+/// covered by the compiler version.
+pub fn key(
+    module: &ParsedModule,
+    known: &HashMap<String, String>,
+    tracing: Tracing,
+    trace_severity: TraceSeverity,
+    env: Option<&str>,
+) -> String {
+    let mut imports = module
+        .ast
+        .dependencies(&[])
+        .into_iter()
+        .filter_map(|import| known.get(&import).map(|key| format!("{import}@{key}")))
+        .collect::<Vec<_>>();
+
+    imports.sort();
+    imports.dedup();
+
+    let preimage = [
+        compiler_version(true),
+        format!("{tracing:?}"),
+        format!("{trace_severity:?}"),
+        module.package.clone(),
+        module.name.clone(),
+        format!("{:?}", module.kind),
+        imports.join(","),
+        module.code.clone(),
+        // Fixtures are loaded into the module before it gets here.
+        fixtures(module),
+        // What's left after applying `@cfg(...)` attributes depends on the environment.
+        env.unwrap_or(DEFAULT_ENV_MODULE).to_string(),
+    ]
+    .join("\0");
+
+    Hasher::<256>::hash(preimage.as_bytes()).to_string()
+}
+
+/// The content of every fixture loaded by the module, along with the file it comes from.
+fn fixtures(module: &ParsedModule) -> String {
+    module
+        .ast
+        .definitions()
+        .filter_map(|definition| match definition {
+            Definition::ModuleConstant(ModuleConstant {
+                fixture: Some(file),
+                value: UntypedExpr::ByteArray { bytes, .. },
+                ..
+            }) => Some(format!("{file}={}", hex::encode(bytes))),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Fetch a previously type-checked module from the cache, if any, and move the given generator
+/// past its type variables. Corrupted or mismatching entries are treated as missing.
+pub fn load(
+    vfs: &dyn Vfs,
+    key: &str,
+    module: &ParsedModule,
+    id_gen: &IdGenerator,
+) -> Option<CheckedModule> {
+    let bytes = vfs.read(&entry(key)).ok()?;
+
+    let Entry {
+        next_id,
+        module: mut checked,
+    } = ciborium::from_reader::<Entry<CheckedModule>, _>(&bytes[..]).ok()?;
+
+    if checked.name != module.name || checked.package != module.package {
+        return None;
+    }
+
+    // The entry may have been produced from another project; point back at this one's copy.
+    checked.input_path = module.path.clone();
+
+    id_gen.skip_past(next_id);
+
+    Some(checked)
+}
+
+/// Store a type-checked module in the cache, along with the state of the generator it was
+/// type-checked with. This is best-effort: failing to write the cache never fails a build.
+pub fn store(vfs: &dyn Vfs, key: &str, module: &CheckedModule, id_gen: &IdGenerator) {
+    let dir = paths::build_cache();
+
+    if vfs.create_dir_all(&dir).is_err() {
+        return;
+    }
+
+    // Write then rename, so that concurrent builds never observe a partially written entry.
+    let tmp = dir.join(format!("{key}.{}.tmp", std::process::id()));
+
+    let mut bytes = vec![];
+
+    if ciborium::into_writer(
+        &Entry {
+            next_id: id_gen.next(),
+            module,
+        },
+        &mut bytes,
+    )
+    .is_err()
+    {
+        return;
+    }
+
+    if vfs.write(&tmp, &bytes).is_ok() && vfs.rename(&tmp, &entry(key)).is_err() {
+        let _ = vfs.remove_file(&tmp);
+    }
+}
+
+fn entry(key: &str) -> PathBuf {
+    paths::build_cache().join(format!("{key}.cbor"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::OverlayFs;
+    use aiken_lang::{ast::ModuleKind, parser};
+
+    fn module(name: &str, code: &str) -> ParsedModule {
+        let (ast, extra) = parser::module(code, ModuleKind::Lib).expect("failed to parse module");
+
+        ParsedModule {
+            path: PathBuf::from(format!("build/packages/acme-lib/lib/{name}.ak")),
+            name: name.to_string(),
+            code: code.to_string(),
+            kind: ModuleKind::Lib,
+            package: "acme/lib".to_string(),
+            internal: false,
+            ast,
+            extra,
+        }
+    }
+
+    fn key_of(module: &ParsedModule, known: &HashMap<String, String>) -> String {
+        key(module, known, Tracing::silent(), TraceSeverity::Debug, None)
+    }
+
+    #[test]
+    fn key_is_stable() {
+        let foo = module("acme/foo", "pub fn answer() -> Int {\n  42\n}\n");
+
+        assert_eq!(key_of(&foo, &HashMap::new()), key_of(&foo, &HashMap::new()));
+    }
+
+    #[test]
+    fn key_covers_sources_and_options() {
+        let foo = module("acme/foo", "pub fn answer() -> Int {\n  42\n}\n");
+        let key = key_of(&foo, &HashMap::new());
+
+        let changed = module("acme/foo", "pub fn answer() -> Int {\n  14\n}\n");
+        assert_ne!(key, key_of(&changed, &HashMap::new()));
+
+        let traced = super::key(
+            &foo,
+            &HashMap::new(),
+            Tracing::verbose(),
+            TraceSeverity::Debug,
+            None,
+        );
+        assert_ne!(key, traced);
+
+        let other_env = super::key(
+            &foo,
+            &HashMap::new(),
+            Tracing::silent(),
+            TraceSeverity::Debug,
+            Some("preprod"),
+        );
+        assert_ne!(key, other_env);
+    }
+
+    #[test]
+    fn key_covers_imported_modules() {
+        let bar = module(
+            "acme/bar",
+            "use acme/foo\n\npub fn answer() -> Int {\n  foo.answer()\n}\n",
+        );
+
+        let before = key_of(
+            &bar,
+            &HashMap::from([("acme/foo".to_string(), "a".to_string())]),
+        );
+        let after = key_of(
+            &bar,
+            &HashMap::from([("acme/foo".to_string(), "b".to_string())]),
+        );
+
+        assert_ne!(before, after);
+
+        // Modules that aren't imported don't matter.
+        let unrelated = key_of(
+            &bar,
+            &HashMap::from([
+                ("acme/foo".to_string(), "a".to_string()),
+                ("acme/baz".to_string(), "c".to_string()),
+            ]),
+        );
+
+        assert_eq!(before, unrelated);
+    }
+
+    #[test]
+    fn corrupted_entries_are_missing() {
+        let vfs = OverlayFs::in_memory();
+
+        let foo = module("acme/foo", "pub fn answer() -> Int {\n  42\n}\n");
+        let key = key_of(&foo, &HashMap::new());
+
+        assert!(load(&vfs, &key, &foo, &IdGenerator::new()).is_none());
+
+        vfs.write(&entry(&key), b"not cbor").unwrap();
+
+        assert!(load(&vfs, &key, &foo, &IdGenerator::new()).is_none());
+    }
+}
@@ -1,10 +1,18 @@
 use crate::{
-    error::TomlLoadingContext, github::repo::LatestRelease, package_name::PackageName, paths, Error,
+    error::TomlLoadingContext,
+    github::repo::LatestRelease,
+    package_name::PackageName,
+    paths, settings, toolchain,
+    vfs::{OsFs, Vfs},
+    Error,
 };
 use aiken_lang::{
-    ast::{Annotation, ByteArrayFormatPreference, ModuleConstant, Span, UntypedDefinition},
+    ast::{
+        Annotation, ByteArrayFormatPreference, Definition, ModuleConstant, ModuleKind, Span,
+        TraceSeverity, UntypedDefinition, UntypedUse,
+    },
     expr::UntypedExpr,
-    parser::token::Base,
+    parser::{self, token::Base},
 };
 pub use aiken_lang::{plutus_version::PlutusVersion, version::compiler_version};
 use miette::NamedSource;
@@ -14,7 +22,13 @@ use serde::{
     ser::{self, SerializeSeq, SerializeStruct},
     Deserialize, Serialize,
 };
-use std::{collections::BTreeMap, fmt::Display, fs, io, path::Path};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+use uplc::optimize::Pass;
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Config {
@@ -26,6 +40,14 @@ pub struct Config {
         default = "default_version"
     )]
     pub compiler: Version,
+    /// Compiler version that commands run from within the project are handed over to, when
+    /// installed (see 'aiken toolchain'). Unlike 'compiler', this is an explicit pin.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "validate_toolchain"
+    )]
+    pub toolchain: Option<String>,
     #[serde(default, deserialize_with = "validate_v3_only")]
     pub plutus: PlutusVersion,
     pub license: Option<String>,
@@ -34,10 +56,133 @@ pub struct Config {
     pub repository: Option<Repository>,
     #[serde(default)]
     pub dependencies: Vec<Dependency>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<PackageName, String>,
+    /// Fail on conflicting requirements between dependencies, instead of warning about them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_dependencies: bool,
+    #[serde(default, skip_serializing_if = "TraceConfig::is_default")]
+    pub trace: TraceConfig,
+    #[serde(default, skip_serializing_if = "ArtifactsConfig::is_default")]
+    pub artifacts: ArtifactsConfig,
+    /// Imports implicitly added to every module of the project, written as they would be after
+    /// a `use` keyword (e.g. "aiken/collection/list" or "cardano/assets.{Value}").
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "validate_prelude"
+    )]
+    pub prelude: Vec<String>,
+    /// Modules that can only be imported from within this package. A trailing '/*' matches
+    /// every module nested under a path (e.g. "aiken/internal/*").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub internal_modules: Vec<String>,
+    /// Tuples with more elements than this, in the signature of any definition of the project,
+    /// trigger a warning.
+    #[serde(
+        default = "default_max_tuple_arity",
+        skip_serializing_if = "is_default_max_tuple_arity"
+    )]
+    pub max_tuple_arity: usize,
+    /// Optimization passes to run, in order, when building validators. Defaults to the
+    /// compiler's own pipeline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimization_passes: Option<Vec<Pass>>,
     #[serde(default)]
     pub config: BTreeMap<String, BTreeMap<String, SimpleExpr>>,
 }
 
+/// Minimum severity of user-defined traces kept in the compiled code, when checking (i.e.
+/// tests & benchmarks) and when building validators respectively.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TraceConfig {
+    #[serde(default)]
+    pub check: TraceSeverity,
+    #[serde(default)]
+    pub build: TraceSeverity,
+}
+
+impl TraceConfig {
+    fn is_default(&self) -> bool {
+        self == &TraceConfig::default()
+    }
+}
+
+/// Where, and under which names, 'aiken build' writes per-validator artifacts (e.g. UPLC dumps).
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ArtifactsConfig {
+    /// A sub-directory of the project, which 'aiken build' may clean up.
+    #[serde(
+        default = "default_artifacts_directory",
+        deserialize_with = "validate_artifacts_directory"
+    )]
+    pub directory: String,
+    #[serde(default)]
+    pub naming: ArtifactNaming,
+    /// Whether to group artifacts in one sub-directory per purpose (e.g. 'spend/', 'mint/').
+    #[serde(default)]
+    pub nest_by_purpose: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactNaming {
+    /// Named after the validator's title, i.e. '{module}.{validator}.{purpose}'.
+    #[default]
+    Title,
+    /// Named after the script hash.
+    Hash,
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        ArtifactsConfig {
+            directory: default_artifacts_directory(),
+            naming: ArtifactNaming::default(),
+            nest_by_purpose: false,
+        }
+    }
+}
+
+impl ArtifactsConfig {
+    fn is_default(&self) -> bool {
+        self == &ArtifactsConfig::default()
+    }
+
+    /// Location of the artifact for the validator of the given title and hash, relative to the
+    /// project's root.
+    pub fn path(&self, title: &str, hash: &str, extension: &str) -> PathBuf {
+        let mut path = PathBuf::from(&self.directory);
+
+        if self.nest_by_purpose {
+            if let Some((_, purpose)) = title.rsplit_once('.') {
+                path.push(purpose);
+            }
+        }
+
+        let name = match self.naming {
+            ArtifactNaming::Title => title,
+            ArtifactNaming::Hash => hash,
+        };
+
+        path.push(format!("{name}.{extension}"));
+
+        path
+    }
+}
+
+fn default_max_tuple_arity() -> usize {
+    6
+}
+
+fn is_default_max_tuple_arity(max_tuple_arity: &usize) -> bool {
+    *max_tuple_arity == default_max_tuple_arity()
+}
+
+fn default_artifacts_directory() -> String {
+    "artifacts".to_string()
+}
+
 #[derive(Clone, Debug)]
 pub enum SimpleExpr {
     Int(i64),
@@ -133,6 +278,7 @@ impl SimpleExpr {
             name: identifier.to_string(),
             annotation: Some(annotation),
             value,
+            fixture: None,
         })
     }
 }
@@ -287,10 +433,68 @@ pub enum Platform {
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
+#[serde(try_from = "DependencyFields", into = "DependencyFields")]
 pub struct Dependency {
     pub name: PackageName,
     pub version: String,
+    /// Where the package is downloaded from. Path dependencies are never downloaded, so they
+    /// don't declare any.
     pub source: Platform,
+    /// A directory holding the package, relative to the project root. Such dependencies are
+    /// compiled straight from their sources, and never downloaded.
+    pub path: Option<PathBuf>,
+}
+
+impl Dependency {
+    /// See 'Package::root', for a dependency as declared in 'aiken.toml'.
+    pub fn root(&self, project_root: &Path) -> PathBuf {
+        match &self.path {
+            Some(path) => project_root.join(path),
+            None => project_root.join(paths::build_deps_package(&self.name)),
+        }
+    }
+}
+
+/// A dependency as written in 'aiken.toml', where the source is only required from packages
+/// that get downloaded.
+#[derive(Deserialize, Serialize)]
+struct DependencyFields {
+    name: PackageName,
+    version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Platform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+}
+
+impl TryFrom<DependencyFields> for Dependency {
+    type Error = String;
+
+    fn try_from(fields: DependencyFields) -> Result<Self, Self::Error> {
+        let source = match (fields.source, &fields.path) {
+            (Some(source), _) => source,
+            (None, Some(_)) => Platform::Github,
+            (None, None) => return Err("missing field `source`".to_string()),
+        };
+
+        Ok(Dependency {
+            name: fields.name,
+            version: fields.version,
+            source,
+            path: fields.path,
+        })
+    }
+}
+
+impl From<Dependency> for DependencyFields {
+    fn from(dependency: Dependency) -> Self {
+        DependencyFields {
+            name: dependency.name,
+            version: dependency.version,
+            source: dependency.path.is_none().then_some(dependency.source),
+            path: dependency.path,
+        }
+    }
 }
 
 impl Display for Platform {
@@ -303,12 +507,41 @@ impl Display for Platform {
     }
 }
 
+/// The most recent release of the standard library known to work with this version of the
+/// compiler. Used whenever the stdlib version can't be determined otherwise, or when a project
+/// tracks a moving branch of the stdlib.
+pub const COMPATIBLE_STDLIB_VERSION: &str = "v2.2.0";
+
 impl Config {
+    /// A new project depending on the latest release of the standard library, looked up online
+    /// unless offline.
     pub fn default(name: &PackageName) -> Self {
+        Self::with_dependencies(
+            name,
+            vec![Dependency {
+                name: PackageName {
+                    owner: "aiken-lang".to_string(),
+                    repo: "stdlib".to_string(),
+                },
+                version: match (!settings::offline())
+                    .then(|| LatestRelease::of("aiken-lang/stdlib"))
+                {
+                    Some(Ok(stdlib)) => stdlib.tag_name,
+                    _ => COMPATIBLE_STDLIB_VERSION.to_string(),
+                },
+                source: Platform::Github,
+                path: None,
+            }],
+        )
+    }
+
+    /// Like 'default', but with the given dependencies; so without going online.
+    pub fn with_dependencies(name: &PackageName, dependencies: Vec<Dependency>) -> Self {
         Config {
             name: name.clone(),
             version: "0.0.0".to_string(),
             compiler: default_version(),
+            toolchain: None,
             plutus: PlutusVersion::default(),
             license: Some("Apache-2.0".to_string()),
             description: format!("Aiken contracts for project '{name}'"),
@@ -317,21 +550,31 @@ impl Config {
                 project: name.repo.clone(),
                 platform: Platform::Github,
             }),
-            dependencies: vec![Dependency {
-                name: PackageName {
-                    owner: "aiken-lang".to_string(),
-                    repo: "stdlib".to_string(),
-                },
-                version: match LatestRelease::of("aiken-lang/stdlib") {
-                    Ok(stdlib) => stdlib.tag_name,
-                    _ => "1.5.0".to_string(),
-                },
-                source: Platform::Github,
-            }],
+            dependencies,
+            overrides: BTreeMap::new(),
+            strict_dependencies: false,
+            trace: TraceConfig::default(),
+            artifacts: ArtifactsConfig::default(),
+            prelude: Vec::new(),
+            internal_modules: Vec::new(),
+            max_tuple_arity: default_max_tuple_arity(),
+            optimization_passes: None,
             config: BTreeMap::new(),
         }
     }
 
+    /// Where the sources of a (direct or transitive) dependency are found.
+    pub fn dependency_root(&self, project_root: &Path, name: &PackageName) -> PathBuf {
+        match self
+            .dependencies
+            .iter()
+            .find(|dependency| &dependency.name == name)
+        {
+            Some(dependency) => dependency.root(project_root),
+            None => project_root.join(paths::build_deps_package(name)),
+        }
+    }
+
     pub fn save(&self, dir: &Path) -> Result<(), io::Error> {
         let aiken_toml_path = dir.join(paths::project_config());
         let aiken_toml = toml::to_string_pretty(self).unwrap();
@@ -339,10 +582,17 @@ impl Config {
     }
 
     pub fn load(dir: &Path) -> Result<Config, Error> {
+        Self::load_with(&OsFs, dir)
+    }
+
+    /// Load the configuration of the project rooted at 'dir', as seen by the given file system.
+    pub fn load_with(vfs: &dyn Vfs, dir: &Path) -> Result<Config, Error> {
         let config_path = dir.join(paths::project_config());
-        let raw_config = fs::read_to_string(&config_path).map_err(|_| Error::MissingManifest {
-            path: dir.to_path_buf(),
-        })?;
+        let raw_config = vfs
+            .read_to_string(&config_path)
+            .map_err(|_| Error::MissingManifest {
+                path: dir.to_path_buf(),
+            })?;
 
         let result: Self = toml::from_str(&raw_config).map_err(|e| Error::TomlLoading {
             ctx: TomlLoadingContext::Project,
@@ -360,6 +610,24 @@ impl Config {
         Ok(result)
     }
 
+    pub fn implicit_imports(&self) -> Vec<UntypedUse> {
+        self.prelude
+            .iter()
+            .map(|import| parse_import(import).expect("prelude validated on load"))
+            .collect()
+    }
+
+    pub fn is_internal_module(&self, module: &str) -> bool {
+        self.internal_modules
+            .iter()
+            .any(|pattern| match pattern.strip_suffix("/*") {
+                Some(prefix) => module
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/')),
+                None => pattern == module,
+            })
+    }
+
     pub fn insert(mut self, dependency: &Dependency, and_replace: bool) -> Option<Self> {
         for existing in self.dependencies.iter_mut() {
             if existing.name == dependency.name {
@@ -388,6 +656,71 @@ where
     }
 }
 
+fn parse_import(import: &str) -> Option<UntypedUse> {
+    let (module, _) = parser::module(&format!("use {import}"), ModuleKind::Lib).ok()?;
+
+    match module.definitions.as_slice() {
+        [Definition::Use(import)] => Some(import.clone()),
+        _ => None,
+    }
+}
+
+fn validate_prelude<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let imports = Vec::<String>::deserialize(deserializer)?;
+
+    for import in imports.iter() {
+        if parse_import(import).is_none() {
+            return Err(serde::de::Error::custom(format!(
+                "invalid prelude import '{import}'; expected something like 'aiken/collection/list' or 'cardano/assets.{{Value}}'"
+            )));
+        }
+    }
+
+    Ok(imports)
+}
+
+fn validate_toolchain<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let version = String::deserialize(deserializer)?;
+
+    if toolchain::parse_version(&version).is_none() {
+        return Err(serde::de::Error::custom(format!(
+            "invalid toolchain '{version}'; expected a compiler version such as 'v1.1.0'"
+        )));
+    }
+
+    Ok(Some(version))
+}
+
+fn validate_artifacts_directory<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let directory = String::deserialize(deserializer)?;
+
+    let components = Path::new(&directory).components().collect::<Vec<_>>();
+
+    let is_sub_directory = components
+        .iter()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        && components
+            .iter()
+            .any(|component| matches!(component, Component::Normal(_)));
+
+    if is_sub_directory {
+        Ok(directory)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "invalid artifacts directory '{directory}'; expected a sub-directory of the project, like 'artifacts'"
+        )))
+    }
+}
+
 mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
@@ -452,4 +785,176 @@ mod tests {
 
         }
     }
+
+    #[test]
+    fn path_dependencies() {
+        let dependencies: BTreeMap<String, Vec<Dependency>> = toml::from_str(
+            r#"
+            [[dependencies]]
+            name = "foo/bar-library"
+            version = "0.0.0"
+            source = "github"
+            path = "../library"
+
+            [[dependencies]]
+            name = "aiken-lang/stdlib"
+            version = "v2.2.0"
+            source = "github"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            dependencies["dependencies"]
+                .iter()
+                .map(|dependency| dependency.path.clone())
+                .collect::<Vec<_>>(),
+            vec![Some(PathBuf::from("../library")), None]
+        );
+
+        assert!(!toml::to_string(&dependencies["dependencies"][0])
+            .unwrap()
+            .contains("source"));
+
+        let dependencies: BTreeMap<String, Vec<Dependency>> = toml::from_str(
+            r#"
+            [[dependencies]]
+            name = "foo/bar-library"
+            version = "0.0.0"
+            path = "../library"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            dependencies["dependencies"][0].path,
+            Some(PathBuf::from("../library"))
+        );
+
+        assert!(toml::from_str::<BTreeMap<String, Vec<Dependency>>>(
+            r#"
+            [[dependencies]]
+            name = "foo/bar-library"
+            version = "0.0.0"
+            "#,
+        )
+        .is_err());
+
+        assert!(!toml::to_string(&dependencies["dependencies"][1])
+            .unwrap()
+            .contains("path"));
+    }
+
+    #[test]
+    fn parse_prelude_imports() {
+        let import = parse_import("cardano/assets.{Value, PolicyId as Policy}").unwrap();
+        assert_eq!(import.module, vec!["cardano", "assets"]);
+        assert_eq!(import.unqualified.len(), 2);
+
+        assert!(parse_import("aiken/collection/list as list").is_some());
+        assert!(parse_import("aiken/collection/list\nfn foo() { 1 }").is_none());
+        assert!(parse_import("not a module").is_none());
+    }
+
+    #[test]
+    fn match_internal_modules() {
+        let mut config = Config::with_dependencies(
+            &PackageName {
+                owner: "aiken-lang".to_string(),
+                repo: "stdlib".to_string(),
+            },
+            vec![],
+        );
+
+        config.internal_modules = vec!["aiken/internal/*".to_string(), "aiken/utils".to_string()];
+
+        assert!(config.is_internal_module("aiken/utils"));
+        assert!(config.is_internal_module("aiken/internal/list"));
+        assert!(config.is_internal_module("aiken/internal/list/foo"));
+        assert!(!config.is_internal_module("aiken/internal"));
+        assert!(!config.is_internal_module("aiken/internals/list"));
+        assert!(!config.is_internal_module("aiken/utils/list"));
+    }
+
+    #[test]
+    fn toolchain_is_a_version() {
+        #[derive(Deserialize)]
+        struct TestConfig {
+            #[serde(default, deserialize_with = "validate_toolchain")]
+            toolchain: Option<String>,
+        }
+
+        let parse = |toml: &str| toml::from_str::<TestConfig>(toml).map(|config| config.toolchain);
+
+        assert_eq!(parse("").unwrap(), None);
+        assert_eq!(
+            parse(r#"toolchain = "v1.1.0""#).unwrap(),
+            Some("v1.1.0".to_string())
+        );
+        assert!(parse(r#"toolchain = "1.1.0-alpha""#).is_ok());
+        assert!(parse(r#"toolchain = "latest""#).is_err());
+        assert!(parse(r#"toolchain = "v1.1""#).is_err());
+    }
+
+    #[test]
+    fn artifacts_directory_within_project() {
+        let parse = |directory: &str| {
+            toml::from_str::<ArtifactsConfig>(&format!("directory = {directory:?}"))
+                .map(|artifacts| artifacts.directory)
+        };
+
+        assert_eq!(parse("artifacts").unwrap(), "artifacts");
+        assert_eq!(parse("./build/scripts").unwrap(), "./build/scripts");
+        assert!(parse(".").is_err());
+        assert!(parse("").is_err());
+        assert!(parse("..").is_err());
+        assert!(parse("scripts/../..").is_err());
+        assert!(parse("/tmp/artifacts").is_err());
+    }
+
+    #[test]
+    fn artifact_paths() {
+        let title = "foo.bar.spend";
+        let hash = "cafe";
+
+        let mut artifacts = ArtifactsConfig::default();
+        assert_eq!(
+            artifacts.path(title, hash, "uplc"),
+            PathBuf::from("artifacts/foo.bar.spend.uplc")
+        );
+
+        artifacts.nest_by_purpose = true;
+        artifacts.naming = ArtifactNaming::Hash;
+        artifacts.directory = "build/scripts".to_string();
+        assert_eq!(
+            artifacts.path(title, hash, "uplc"),
+            PathBuf::from("build/scripts/spend/cafe.uplc")
+        );
+    }
+
+    #[test]
+    fn trace_severities() {
+        #[derive(Deserialize)]
+        struct TestConfig {
+            trace: TraceConfig,
+        }
+
+        let config: TestConfig = toml::from_str(
+            r#"
+            [trace]
+            build = "info"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.trace,
+            TraceConfig {
+                check: TraceSeverity::Debug,
+                build: TraceSeverity::Info,
+            }
+        );
+
+        assert!(toml::from_str::<TestConfig>("[trace]\ncheck = \"warn\"\n").is_err());
+    }
 }
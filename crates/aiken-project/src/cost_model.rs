@@ -0,0 +1,108 @@
+use crate::{error::Error, vfs::Vfs};
+use aiken_lang::plutus_version::PlutusVersion;
+use serde_json::Value;
+use std::path::Path;
+
+/// Load the cost model parameters for a given Plutus version out of a protocol-parameters JSON
+/// file. We support both the format returned by `cardano-cli query protocol-parameters` (under
+/// `costModels`) and the one returned by Blockfrost (under `cost_models_raw`).
+pub fn from_protocol_parameters(
+    vfs: &dyn Vfs,
+    path: &Path,
+    plutus_version: PlutusVersion,
+) -> Result<Vec<i64>, Error> {
+    let invalid = |reason: String| Error::InvalidCostModel {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let contents = vfs.read_to_string(path).map_err(|error| Error::FileIo {
+        error,
+        path: path.to_path_buf(),
+    })?;
+
+    let params: Value = serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()))?;
+
+    select(&params, plutus_version).map_err(invalid)
+}
+
+fn select(params: &Value, plutus_version: PlutusVersion) -> Result<Vec<i64>, String> {
+    let cost_models = ["costModels", "cost_models_raw"]
+        .iter()
+        .find_map(|field| params.get(field))
+        .ok_or_else(|| "there's no 'costModels' field.".to_string())?;
+
+    let language = language_key(plutus_version);
+
+    let costs = cost_models
+        .get(language)
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("there's no cost model for {language}."))?
+        .iter()
+        .map(Value::as_i64)
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| format!("the {language} cost model contains non-integers."))?;
+
+    let expected = expected_len(plutus_version);
+
+    if costs.len() < expected {
+        return Err(format!(
+            "the {language} cost model has {} parameters, but I need at least {expected}.",
+            costs.len(),
+        ));
+    }
+
+    Ok(costs)
+}
+
+fn language_key(plutus_version: PlutusVersion) -> &'static str {
+    match plutus_version {
+        PlutusVersion::V1 => "PlutusV1",
+        PlutusVersion::V2 => "PlutusV2",
+        PlutusVersion::V3 => "PlutusV3",
+    }
+}
+
+fn expected_len(plutus_version: PlutusVersion) -> usize {
+    match plutus_version {
+        PlutusVersion::V1 => 166,
+        PlutusVersion::V2 => 175,
+        PlutusVersion::V3 => 297,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cardano_cli_format() {
+        let costs = vec![1; 297];
+        let params = json!({ "costModels": { "PlutusV3": costs.clone() } });
+
+        assert_eq!(select(&params, PlutusVersion::V3), Ok(costs));
+    }
+
+    #[test]
+    fn blockfrost_format() {
+        let costs = vec![1; 175];
+        let params = json!({ "cost_models_raw": { "PlutusV2": costs.clone() } });
+
+        assert_eq!(select(&params, PlutusVersion::V2), Ok(costs));
+    }
+
+    #[test]
+    fn missing_language() {
+        let params = json!({ "costModels": { "PlutusV2": vec![1; 175] } });
+
+        assert!(select(&params, PlutusVersion::V3).is_err());
+    }
+
+    #[test]
+    fn too_few_parameters() {
+        let params = json!({ "costModels": { "PlutusV3": vec![1; 10] } });
+
+        assert!(select(&params, PlutusVersion::V3).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    error::{Error, TomlLoadingContext},
+    vfs::Vfs,
+};
+use aiken_lang::{ast::Span, test_framework::TestResult};
+use miette::NamedSource;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use uplc::machine::cost_model::ExBudget;
+
+/// What to do with the budgets spent by unit tests once they've run.
+#[derive(Debug, Clone)]
+pub enum CostsGuard {
+    /// Snapshot the budgets to the given file.
+    Record(PathBuf),
+    /// Compare the budgets against a previous snapshot, failing on any test exceeding its
+    /// recorded budget by more than the given tolerance (a percentage).
+    Verify { path: PathBuf, tolerance: u32 },
+}
+
+/// A snapshot of the execution units spent by each unit test, indexed by 'module.test'.
+#[derive(Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct CostsLock {
+    tests: BTreeMap<String, Budget>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub mem: i64,
+    pub cpu: i64,
+}
+
+impl From<ExBudget> for Budget {
+    fn from(budget: ExBudget) -> Self {
+        Self {
+            mem: budget.mem,
+            cpu: budget.cpu,
+        }
+    }
+}
+
+impl Budget {
+    fn exceeds(&self, recorded: &Budget, tolerance: u32) -> bool {
+        let allowed = |n: i64| n + n * i64::from(tolerance) / 100;
+        self.mem > allowed(recorded.mem) || self.cpu > allowed(recorded.cpu)
+    }
+}
+
+impl CostsLock {
+    pub fn from_results<U, T>(results: &[TestResult<U, T>]) -> Self {
+        let tests = results
+            .iter()
+            .filter_map(|result| match result {
+                TestResult::UnitTestResult(unit) => Some((
+                    format!("{}.{}", unit.test.module, unit.test.name),
+                    Budget::from(unit.spent_budget),
+                )),
+                TestResult::PropertyTestResult(..) | TestResult::BenchmarkResult(..) => None,
+            })
+            .collect();
+
+        Self { tests }
+    }
+
+    pub fn load(vfs: &dyn Vfs, path: &Path) -> Result<Self, Error> {
+        let src = vfs.read_to_string(path).map_err(|error| Error::FileIo {
+            error,
+            path: path.to_path_buf(),
+        })?;
+
+        toml::from_str(&src).map_err(|e| Error::TomlLoading {
+            ctx: TomlLoadingContext::Costs,
+            path: path.to_path_buf(),
+            src: src.clone(),
+            named: NamedSource::new(path.display().to_string(), src.clone()).into(),
+            location: e.span().map(|range| Span {
+                start: range.start,
+                end: range.end,
+            }),
+            help: e.message().to_string(),
+        })
+    }
+
+    pub fn save(&self, vfs: &dyn Vfs, path: &Path) -> Result<(), Error> {
+        let toml = toml::to_string_pretty(self).expect("costs lock serialization");
+
+        vfs.write(path, toml.as_bytes())
+            .map_err(|error| Error::FileIo {
+                error,
+                path: path.to_path_buf(),
+            })
+    }
+
+    /// Compare a fresh snapshot against this one. Tests that aren't part of the recorded
+    /// snapshot are new, and therefore never count as regressions.
+    pub fn regressions(&self, current: &CostsLock, tolerance: u32) -> Vec<Error> {
+        current
+            .tests
+            .iter()
+            .filter_map(|(test, budget)| {
+                let recorded = self.tests.get(test)?;
+
+                if budget.exceeds(recorded, tolerance) {
+                    Some(Error::CostRegression {
+                        test: test.clone(),
+                        recorded: *recorded,
+                        spent: *budget,
+                        tolerance,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(tests: &[(&str, i64, i64)]) -> CostsLock {
+        CostsLock {
+            tests: tests
+                .iter()
+                .map(|(name, mem, cpu)| {
+                    (
+                        name.to_string(),
+                        Budget {
+                            mem: *mem,
+                            cpu: *cpu,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let costs = lock(&[("foo.bar", 100, 2000), ("foo.baz", 1, 2)]);
+
+        let toml = toml::to_string_pretty(&costs).unwrap();
+
+        assert_eq!(toml::from_str::<CostsLock>(&toml).unwrap(), costs);
+    }
+
+    #[test]
+    fn no_regression_within_tolerance() {
+        let recorded = lock(&[("foo.bar", 100, 2000)]);
+        let current = lock(&[("foo.bar", 105, 2100)]);
+
+        assert!(recorded.regressions(&current, 5).is_empty());
+    }
+
+    #[test]
+    fn regression_beyond_tolerance() {
+        let recorded = lock(&[("foo.bar", 100, 2000)]);
+        let current = lock(&[("foo.bar", 100, 2001)]);
+
+        assert!(matches!(
+            recorded.regressions(&current, 0).as_slice(),
+            [Error::CostRegression { .. }]
+        ));
+    }
+
+    #[test]
+    fn new_tests_are_not_regressions() {
+        let recorded = lock(&[]);
+        let current = lock(&[("foo.bar", 100, 2000)]);
+
+        assert!(recorded.regressions(&current, 0).is_empty());
+    }
+}
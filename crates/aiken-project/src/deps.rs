@@ -1,4 +1,8 @@
-use std::{collections::HashSet, fs, path::Path};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 use aiken_lang::ast::Span;
 use miette::NamedSource;
@@ -6,11 +10,12 @@ use serde::{Deserialize, Serialize};
 use tokio::time::Instant;
 
 use crate::{
-    config::{Config, Dependency},
-    error::{Error, TomlLoadingContext},
+    config::{Config, Dependency, Platform},
+    error::{Error, TomlLoadingContext, Warning},
     package_name::PackageName,
     paths,
     telemetry::{DownloadSource, Event, EventListener},
+    vfs::Vfs,
 };
 
 use self::{
@@ -32,16 +37,18 @@ pub struct LocalPackages {
 }
 
 impl LocalPackages {
-    pub fn load(root_path: &Path) -> Result<Self, Error> {
+    pub fn load(vfs: &dyn Vfs, root_path: &Path) -> Result<Self, Error> {
         let path = root_path.join(paths::packages_toml());
 
-        if !path.exists() {
-            return Ok(Self {
-                packages: Vec::new(),
-            });
-        }
-
-        let src = fs::read_to_string(&path)?;
+        let src = match vfs.read_to_string(&path) {
+            Ok(src) => src,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    packages: Vec::new(),
+                })
+            }
+            Err(error) => return Err(Error::FileIo { error, path }),
+        };
 
         let result: Self = toml::from_str(&src).map_err(|e| Error::TomlLoading {
             ctx: TomlLoadingContext::Package,
@@ -59,21 +66,23 @@ impl LocalPackages {
         Ok(result)
     }
 
-    pub fn save(&self, root_path: &Path) -> Result<(), Error> {
+    pub fn save(&self, vfs: &dyn Vfs, root_path: &Path) -> Result<(), Error> {
         let packages_path = root_path.join(paths::packages());
         let path = root_path.join(paths::packages_toml());
 
-        if !packages_path.exists() {
-            fs::create_dir_all(&packages_path)?;
-        }
+        vfs.create_dir_all(&packages_path)
+            .map_err(|error| Error::FileIo {
+                error,
+                path: packages_path,
+            })?;
 
         let toml = toml::to_string(&self).expect("packages.toml serialization");
 
-        fs::write(path, toml)?;
-
-        Ok(())
+        vfs.write(&path, toml.as_bytes())
+            .map_err(|error| Error::FileIo { error, path })
     }
 
+    /// Downloaded packages live on disk (see 'Downloader'), and so are they removed from it.
     fn remove_extra_packages(&self, manifest: &Manifest, root_path: &Path) -> Result<(), Error> {
         for (package, _version) in self.extra_local_packages(manifest) {
             let path = root_path.join(paths::build_deps_package(&package));
@@ -85,6 +94,9 @@ impl LocalPackages {
 
         Ok(())
     }
+
+    /// Downloaded packages that are no longer part of the manifest. Path dependencies are
+    /// sources of their own, and never count as such.
     pub fn extra_local_packages(&self, manifest: &Manifest) -> Vec<(PackageName, String)> {
         let manifest_packages: HashSet<_> = manifest
             .packages
@@ -94,7 +106,9 @@ impl LocalPackages {
 
         self.packages
             .iter()
-            .filter(|dep| !manifest_packages.contains(&(&dep.name, &dep.version)))
+            .filter(|dep| {
+                dep.path.is_none() && !manifest_packages.contains(&(&dep.name, &dep.version))
+            })
             .map(|dep| (dep.name.clone(), dep.version.clone()))
             .collect()
     }
@@ -108,6 +122,7 @@ impl LocalPackages {
             .iter()
             .filter(|p| {
                 &p.name != root
+                    && p.path.is_none()
                     && !matches!(
                         self.packages.iter().find(|p2| p2.name == p.name),
                         Some(Dependency { version, .. }) if paths::is_git_sha_or_tag(version) && &p.version == version,
@@ -127,41 +142,58 @@ impl From<&Manifest> for LocalPackages {
                     name: p.name.clone(),
                     version: p.version.clone(),
                     source: p.source,
+                    path: p.path.clone(),
                 })
                 .collect(),
         }
     }
 }
 
-pub fn download<T>(event_listener: &T, root_path: &Path, config: &Config) -> Result<Manifest, Error>
+/// Resolve the project's dependencies, downloading whichever packages are missing. The manifest
+/// and the list of local packages are read and written through the given file system; packages
+/// themselves are downloaded on disk, so projects living elsewhere (e.g. in memory) can only
+/// have path dependencies.
+pub fn download<T>(
+    event_listener: &T,
+    vfs: &dyn Vfs,
+    root_path: &Path,
+    config: &Config,
+) -> Result<Manifest, Error>
 where
     T: EventListener,
 {
     let build_path = root_path.join(paths::build());
 
-    if !build_path.is_dir() {
-        fs::create_dir_all(&build_path)?;
-    }
+    vfs.create_dir_all(&build_path)
+        .map_err(|error| Error::FileIo {
+            error,
+            path: build_path.clone(),
+        })?;
 
-    let mut build_lock = fslock::LockFile::open(&build_path.join("aiken-compile.lock"))
-        .expect("Build Lock Creation");
+    // Only a build folder on disk is shared with concurrent builds.
+    let _build_lock = build_path.is_dir().then(|| {
+        let mut build_lock = fslock::LockFile::open(&build_path.join("aiken-compile.lock"))
+            .expect("Build Lock Creation");
 
-    if !build_lock
-        .try_lock_with_pid()
-        .expect("Trying build locking")
-    {
-        event_listener.handle_event(Event::WaitingForBuildDirLock);
+        if !build_lock
+            .try_lock_with_pid()
+            .expect("Trying build locking")
+        {
+            event_listener.handle_event(Event::WaitingForBuildDirLock);
 
-        build_lock.lock_with_pid().expect("Build locking")
-    }
+            build_lock.lock_with_pid().expect("Build locking")
+        }
+
+        build_lock
+    });
 
     let project_name = config.name.clone();
 
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio");
 
-    let (mut manifest, changed) = Manifest::load(event_listener, config, root_path)?;
+    let (mut manifest, changed) = Manifest::load(event_listener, vfs, config, root_path)?;
 
-    let local = LocalPackages::load(root_path)?;
+    let local = LocalPackages::load(vfs, root_path)?;
 
     local.remove_extra_packages(&manifest, root_path)?;
 
@@ -169,23 +201,126 @@ where
         &mut manifest,
         &local,
         project_name,
+        vfs,
         root_path,
         event_listener,
     ))?;
 
     if changed {
-        manifest.save(root_path)?;
+        manifest.save(vfs, root_path)?;
     }
 
-    LocalPackages::from(&manifest).save(root_path)?;
+    LocalPackages::from(&manifest).save(vfs, root_path)?;
 
     Ok(manifest)
 }
 
+/// Requirements declared by a dependency in its own 'aiken.toml'. We only care about the
+/// dependencies here, and remain lenient about anything else so that packages written for
+/// other compiler versions can still be inspected.
+#[derive(Deserialize)]
+struct PackageRequirements {
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+}
+
+/// A resolved dependency, as reported by 'aiken packages list'.
+#[derive(Debug, Clone)]
+pub struct PackageInfo {
+    pub name: PackageName,
+    pub version: String,
+    pub source: Platform,
+    pub path: Option<PathBuf>,
+    /// Digest of the package's modules, as recorded for dependencies in attestations.
+    pub checksum: String,
+    pub license: Option<String>,
+}
+
+/// Metadata declared by a dependency in its own 'aiken.toml', read as leniently as its
+/// requirements.
+#[derive(Deserialize)]
+struct PackageMetadata {
+    license: Option<String>,
+}
+
+pub(crate) fn declared_license(aiken_toml: &str) -> Option<String> {
+    toml::from_str::<PackageMetadata>(aiken_toml)
+        .ok()
+        .and_then(|metadata| metadata.license)
+}
+
+/// Packages are resolved flatly: every package ends up in a single version, which is the one
+/// chosen by the root project. Hence, we look for dependencies that require a different version
+/// of a shared package than the one resolved, unless the root project explicitly settled the
+/// matter through its 'overrides' table. Conflicts are only warnings, unless the project opts
+/// into 'strict_dependencies'.
+pub fn check_conflicts(
+    vfs: &dyn Vfs,
+    root_path: &Path,
+    config: &Config,
+    manifest: &Manifest,
+) -> Result<Vec<Warning>, Vec<Error>> {
+    let conflicts = conflicts(config, manifest, |package| {
+        vfs.read_to_string(&package.root(root_path).join(paths::project_config()))
+            .ok()
+            .and_then(|src| toml::from_str::<PackageRequirements>(&src).ok())
+            .map(|requirements| requirements.dependencies)
+            .unwrap_or_default()
+    });
+
+    if config.strict_dependencies && !conflicts.is_empty() {
+        Err(conflicts
+            .into_iter()
+            .map(|(package, requirers)| Error::DependencyConflict { package, requirers })
+            .collect())
+    } else {
+        Ok(conflicts
+            .into_iter()
+            .map(|(package, requirers)| Warning::DependencyConflict { package, requirers })
+            .collect())
+    }
+}
+
+/// Packages required in more than one version, along with who requires what; given the
+/// dependencies each package declares.
+fn conflicts(
+    config: &Config,
+    manifest: &Manifest,
+    declared: impl Fn(&Package) -> Vec<Dependency>,
+) -> Vec<(PackageName, Vec<(PackageName, String)>)> {
+    let mut requirements: BTreeMap<PackageName, Vec<(PackageName, String)>> = BTreeMap::new();
+
+    for package in manifest.packages.iter() {
+        requirements
+            .entry(package.name.clone())
+            .or_default()
+            .push((config.name.clone(), package.version.clone()));
+    }
+
+    for package in manifest.packages.iter() {
+        for dependency in declared(package) {
+            if let Some(requirers) = requirements.get_mut(&dependency.name) {
+                requirers.push((package.name.clone(), dependency.version));
+            }
+        }
+    }
+
+    requirements
+        .into_iter()
+        .filter(|(package, requirers)| {
+            !config.overrides.contains_key(package)
+                && requirers
+                    .iter()
+                    .any(|(_, version)| version != &requirers[0].1)
+        })
+        .collect()
+}
+
 async fn fetch_missing_packages<T>(
     manifest: &mut Manifest,
     local: &LocalPackages,
     project_name: PackageName,
+    vfs: &dyn Vfs,
     root_path: &Path,
     event_listener: &T,
 ) -> Result<(), Error>
@@ -237,5 +372,159 @@ where
         }
     }
 
-    manifest.save(root_path)
+    manifest.save(vfs, root_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::OverlayFs;
+
+    fn package(name: &str, version: &str) -> Package {
+        Package {
+            name: name.parse().unwrap(),
+            version: version.to_string(),
+            requirements: vec![],
+            source: Platform::Github,
+            path: None,
+        }
+    }
+
+    fn dependency(name: &str, version: &str) -> Dependency {
+        Dependency {
+            name: name.parse().unwrap(),
+            version: version.to_string(),
+            source: Platform::Github,
+            path: None,
+        }
+    }
+
+    fn setup() -> (Config, Manifest) {
+        let config = Config::with_dependencies(
+            &"test/project".parse().unwrap(),
+            vec![dependency("aiken-lang/stdlib", "v2")],
+        );
+
+        let manifest = Manifest {
+            requirements: vec![],
+            packages: vec![
+                package("aiken-lang/stdlib", "v2"),
+                package("acme/lib", "v1"),
+            ],
+            overrides: BTreeMap::new(),
+            etags: BTreeMap::new(),
+        };
+
+        (config, manifest)
+    }
+
+    fn declared(package: &Package) -> Vec<Dependency> {
+        if package.name.repo == "lib" {
+            vec![dependency("aiken-lang/stdlib", "v1")]
+        } else {
+            vec![]
+        }
+    }
+
+    #[test]
+    fn conflicting_requirements() {
+        let (config, manifest) = setup();
+
+        assert_eq!(
+            conflicts(&config, &manifest, declared),
+            vec![(
+                "aiken-lang/stdlib".parse().unwrap(),
+                vec![
+                    ("test/project".parse().unwrap(), "v2".to_string()),
+                    ("acme/lib".parse().unwrap(), "v1".to_string()),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn agreeing_requirements() {
+        let (config, manifest) = setup();
+
+        assert!(conflicts(&config, &manifest, |package| {
+            declared(package)
+                .into_iter()
+                .map(|dependency| Dependency {
+                    version: "v2".to_string(),
+                    ..dependency
+                })
+                .collect()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn overridden_requirements() {
+        let (mut config, manifest) = setup();
+
+        config
+            .overrides
+            .insert("aiken-lang/stdlib".parse().unwrap(), "v2".to_string());
+
+        assert!(conflicts(&config, &manifest, declared).is_empty());
+    }
+
+    #[test]
+    fn conflicts_are_warnings_unless_strict() {
+        let (mut config, mut manifest) = setup();
+
+        let root = PathBuf::from("/in-memory/project");
+        let vfs = OverlayFs::in_memory();
+        vfs.insert(
+            root.join("lib").join(paths::project_config()),
+            indoc::indoc! {r#"
+                name = "acme/lib"
+                version = "1.0.0"
+
+                [[dependencies]]
+                name = "aiken-lang/stdlib"
+                version = "v1"
+                source = "github"
+            "#},
+        );
+
+        manifest.packages[1].path = Some(PathBuf::from("lib"));
+
+        let warnings = check_conflicts(&vfs, &root, &config, &manifest).unwrap();
+        assert!(matches!(
+            warnings.as_slice(),
+            [Warning::DependencyConflict { package, .. }] if package.repo == "stdlib"
+        ));
+
+        config.strict_dependencies = true;
+        let errors = check_conflicts(&vfs, &root, &config, &manifest).unwrap_err();
+        assert!(matches!(
+            errors.as_slice(),
+            [Error::DependencyConflict { package, .. }] if package.repo == "stdlib"
+        ));
+    }
+
+    #[test]
+    fn declared_license_of_package() {
+        assert_eq!(
+            declared_license(indoc::indoc! {r#"
+                name = "acme/lib"
+                version = "1.0.0"
+                license = "Apache-2.0"
+
+                [[dependencies]]
+                name = "aiken-lang/stdlib"
+                version = "v1"
+                source = "github"
+            "#}),
+            Some("Apache-2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn declared_license_missing_or_unreadable() {
+        assert_eq!(declared_license("name = \"acme/lib\"\n"), None);
+        assert_eq!(declared_license("license = 42\n"), None);
+        assert_eq!(declared_license("not toml at all"), None);
+    }
 }
@@ -12,6 +12,7 @@ use crate::{
     error::Error,
     package_name::PackageName,
     paths::{self, CacheKey},
+    settings,
     telemetry::EventListener,
 };
 
@@ -81,14 +82,23 @@ impl<'a> Downloader<'a> {
         }
 
         if zipball_path.is_file() {
+            tracing::debug!("using cached {}", zipball_path.display());
             return Ok(false);
         }
 
+        if settings::offline() {
+            return Err(Error::Offline {
+                action: format!("download {} {}", package.name, package.version),
+            });
+        }
+
         let url = format!(
             "https://api.github.com/repos/{}/{}/zipball/{}",
             package.name.owner, package.name.repo, package.version
         );
 
+        tracing::debug!("downloading {url}");
+
         let response = self
             .http
             .get(url)
@@ -3,23 +3,26 @@ use miette::NamedSource;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
-    fs,
-    path::Path,
+    io,
+    path::{Path, PathBuf},
     time::{Duration, SystemTime},
 };
 
 use crate::{
-    config::{Config, Dependency, Platform},
+    config::{Config, Dependency, Platform, COMPATIBLE_STDLIB_VERSION},
     error::{Error, TomlLoadingContext},
     package_name::PackageName,
     paths,
     telemetry::{Event, EventListener},
+    vfs::Vfs,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Manifest {
     pub requirements: Vec<Dependency>,
     pub packages: Vec<Package>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<PackageName, String>,
     #[serde(default)]
     pub etags: BTreeMap<String, (SystemTime, String)>,
 }
@@ -27,6 +30,7 @@ pub struct Manifest {
 impl Manifest {
     pub fn load<T>(
         event_listener: &T,
+        vfs: &dyn Vfs,
         config: &Config,
         root_path: &Path,
     ) -> Result<(Self, bool), Error>
@@ -35,16 +39,20 @@ impl Manifest {
     {
         let manifest_path = root_path.join(paths::manifest());
 
-        // If there's no manifest (or we have been asked not to use it) then resolve
-        // the versions anew
-        let should_resolve = !manifest_path.exists();
-
-        if should_resolve {
-            let manifest = resolve_versions(config, event_listener)?;
-            return Ok((manifest, true));
-        }
-
-        let toml = fs::read_to_string(&manifest_path)?;
+        let toml = match vfs.read_to_string(&manifest_path) {
+            Ok(toml) => toml,
+            // If there's no manifest then resolve the versions anew
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let manifest = resolve_versions(config, event_listener)?;
+                return Ok((manifest, true));
+            }
+            Err(error) => {
+                return Err(Error::FileIo {
+                    error,
+                    path: manifest_path,
+                })
+            }
+        };
 
         let manifest: Self = toml::from_str(&toml).map_err(|e| Error::TomlLoading {
             ctx: TomlLoadingContext::Manifest,
@@ -61,7 +69,7 @@ impl Manifest {
 
         // If the config is unchanged since the manifest was written then it is up
         // to date so we can return it unmodified.
-        if manifest.requirements == config.dependencies {
+        if manifest.requirements == config.dependencies && manifest.overrides == config.overrides {
             Ok((manifest, false))
         } else {
             let manifest = resolve_versions(config, event_listener)?;
@@ -69,7 +77,7 @@ impl Manifest {
         }
     }
 
-    pub fn save(&self, root_path: &Path) -> Result<(), Error> {
+    pub fn save(&self, vfs: &dyn Vfs, root_path: &Path) -> Result<(), Error> {
         let manifest_path = root_path.join(paths::manifest());
 
         let mut toml = toml::to_string(&self).expect("aiken.lock serialization");
@@ -79,9 +87,11 @@ impl Manifest {
             "# This file was generated by Aiken\n# You typically do not need to edit this file\n\n",
         );
 
-        fs::write(manifest_path, toml)?;
-
-        Ok(())
+        vfs.write(&manifest_path, toml.as_bytes())
+            .map_err(|error| Error::FileIo {
+                error,
+                path: manifest_path,
+            })
     }
 
     pub fn lookup_etag(&self, package: &Package) -> Option<String> {
@@ -119,6 +129,19 @@ pub struct Package {
     pub version: String,
     pub requirements: Vec<String>,
     pub source: Platform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl Package {
+    /// Where the package sources are found: in the build folder for downloaded packages, or
+    /// at their own location for path dependencies.
+    pub fn root(&self, project_root: &Path) -> PathBuf {
+        match &self.path {
+            Some(path) => project_root.join(path),
+            None => project_root.join(paths::build_deps_package(&self.name)),
+        }
+    }
 }
 
 fn resolve_versions<T>(config: &Config, event_listener: &T) -> Result<Manifest, Error>
@@ -133,14 +156,116 @@ where
             .iter()
             .map(|dep| Package {
                 name: dep.name.clone(),
-                version: dep.version.clone(),
+                version: match config.overrides.get(&dep.name) {
+                    Some(version) => version.clone(),
+                    None => pinned_version(dep, event_listener),
+                },
                 requirements: vec![],
                 source: dep.source,
+                path: dep.path.clone(),
             })
             .collect(),
         requirements: config.dependencies.clone(),
+        overrides: config.overrides.clone(),
         etags: BTreeMap::new(),
     };
 
     Ok(manifest)
 }
+
+/// The stdlib's main branch moves independently of the compiler and regularly introduces
+/// changes that require a newer compiler. So rather than silently following it, we pin it to
+/// the last release known to be compatible, which then gets recorded in the lockfile.
+fn pinned_version<T>(dep: &Dependency, event_listener: &T) -> String
+where
+    T: EventListener,
+{
+    if dep.name.owner == "aiken-lang" && dep.name.repo == "stdlib" && dep.version == "main" {
+        event_listener.handle_event(Event::PinningStdlib {
+            requested: dep.version.clone(),
+            version: COMPATIBLE_STDLIB_VERSION.to_string(),
+        });
+
+        COMPATIBLE_STDLIB_VERSION.to_string()
+    } else {
+        dep.version.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records the versions the stdlib got pinned to.
+    #[derive(Default)]
+    struct Pins(RefCell<Vec<(String, String)>>);
+
+    impl EventListener for Pins {
+        fn handle_event(&self, event: Event) {
+            if let Event::PinningStdlib { requested, version } = event {
+                self.0.borrow_mut().push((requested, version));
+            }
+        }
+    }
+
+    fn dependency(name: &str, version: &str) -> Dependency {
+        Dependency {
+            name: name.parse().unwrap(),
+            version: version.to_string(),
+            source: Platform::Github,
+            path: None,
+        }
+    }
+
+    fn config(dependencies: Vec<Dependency>) -> Config {
+        Config::with_dependencies(&"test/project".parse().unwrap(), dependencies)
+    }
+
+    #[test]
+    fn pin_stdlib_main() {
+        let config = config(vec![
+            dependency("aiken-lang/stdlib", "main"),
+            dependency("acme/lib", "main"),
+        ]);
+
+        let pins = Pins::default();
+
+        let manifest = resolve_versions(&config, &pins).unwrap();
+
+        assert_eq!(manifest.packages[0].version, COMPATIBLE_STDLIB_VERSION);
+        assert_eq!(manifest.packages[1].version, "main");
+        assert_eq!(manifest.requirements, config.dependencies);
+        assert_eq!(
+            pins.0.into_inner(),
+            vec![("main".to_string(), COMPATIBLE_STDLIB_VERSION.to_string())]
+        );
+    }
+
+    #[test]
+    fn keep_stdlib_releases() {
+        let config = config(vec![dependency("aiken-lang/stdlib", "v2.1.0")]);
+
+        let pins = Pins::default();
+
+        let manifest = resolve_versions(&config, &pins).unwrap();
+
+        assert_eq!(manifest.packages[0].version, "v2.1.0");
+        assert!(pins.0.into_inner().is_empty());
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_pins() {
+        let mut config = config(vec![dependency("aiken-lang/stdlib", "main")]);
+        config
+            .overrides
+            .insert("aiken-lang/stdlib".parse().unwrap(), "v2.0.0".to_string());
+
+        let pins = Pins::default();
+
+        let manifest = resolve_versions(&config, &pins).unwrap();
+
+        assert_eq!(manifest.packages[0].version, "v2.0.0");
+        assert!(pins.0.into_inner().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     config::{Config, Repository},
     module::CheckedModule,
+    vfs::Vfs,
 };
 use aiken_lang::{
     ast::{
@@ -27,6 +28,7 @@ const MAX_COLUMNS: isize = 80;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub mod link_tree;
+pub mod serve;
 pub mod source_links;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -104,7 +106,12 @@ impl DocLink {
 /// The documentation is built using template files located at the root of this crate.
 /// With the documentation, we also build a client-side search index to ease navigation
 /// across multiple modules.
-pub fn generate_all(root: &Path, config: &Config, modules: Vec<&CheckedModule>) -> Vec<DocFile> {
+pub fn generate_all(
+    vfs: &dyn Vfs,
+    root: &Path,
+    config: &Config,
+    modules: Vec<&CheckedModule>,
+) -> Vec<DocFile> {
     let timestamp = new_timestamp();
     let modules_links = generate_modules_links(&modules);
 
@@ -142,8 +149,12 @@ pub fn generate_all(root: &Path, config: &Config, modules: Vec<&CheckedModule>)
     }
 
     output_files.extend(generate_static_assets(search_indexes));
+    let readme = vfs
+        .read_to_string(&root.join("README.md"))
+        .unwrap_or_default();
+
     output_files.push(generate_readme(
-        root,
+        &readme,
         config,
         &modules_links,
         &source,
@@ -195,7 +206,7 @@ fn generate_module(
         .ast
         .definitions
         .iter()
-        .flat_map(|def| DocFunction::from_definition(def, &source_linker))
+        .flat_map(|def| DocFunction::from_definition(def, module, &source_linker))
         .collect();
 
     functions.iter().for_each(|(_, function)| {
@@ -253,10 +264,29 @@ fn generate_module(
         search_indexes.push(SearchIndex::from_module(module));
     }
 
+    // Re-exports, linking back to the modules defining them
+    let breadcrumbs = to_breadcrumbs(&module.name);
+    let mut documentation = module.ast.docs.iter().join("\n");
+    if !module.ast.type_info.reexports.is_empty() {
+        documentation.push_str("\n\n## Re-exports\n\n");
+        documentation.push_str(
+            &module
+                .ast
+                .type_info
+                .reexports
+                .iter()
+                .sorted()
+                .map(|(name, origin)| {
+                    format!("- `{name}` from [{origin}]({breadcrumbs}/{origin}.html)")
+                })
+                .join("\n"),
+        );
+    }
+
     let module = ModuleTemplate {
         aiken_version: VERSION,
-        breadcrumbs: to_breadcrumbs(&module.name),
-        documentation: render_markdown(&module.ast.docs.iter().join("\n")),
+        breadcrumbs,
+        documentation: render_markdown(&documentation),
         modules,
         project_name: &config.name.repo.to_string(),
         page_title: &format!("{} - {}", module.name, config.name),
@@ -375,7 +405,7 @@ fn generate_static_assets(search_indexes: Vec<SearchIndex>) -> Vec<DocFile> {
 }
 
 fn generate_readme(
-    root: &Path,
+    content: &str,
     config: &Config,
     modules: &[DocLink],
     source: &DocLink,
@@ -383,8 +413,6 @@ fn generate_readme(
 ) -> DocFile {
     let path = PathBuf::from("index.html");
 
-    let content = std::fs::read_to_string(root.join("README.md")).unwrap_or_default();
-
     let template = PageTemplate {
         aiken_version: VERSION,
         breadcrumbs: ".",
@@ -392,7 +420,7 @@ fn generate_readme(
         project_name: &config.name.repo.to_string(),
         page_title: &config.name.to_string(),
         project_version: &config.version.to_string(),
-        content: render_markdown(&content),
+        content: render_markdown(content),
         source,
         timestamp: &timestamp.as_secs().to_string(),
     };
@@ -509,11 +537,13 @@ struct DocFunction {
     documentation: String,
     raw_documentation: String,
     source_url: String,
+    examples: Vec<String>,
 }
 
 impl DocFunction {
     fn from_definition(
         def: &TypedDefinition,
+        module: &CheckedModule,
         source_linker: &source_links::SourceLinker,
     ) -> Option<(Span, Self)> {
         match def {
@@ -537,6 +567,7 @@ impl DocFunction {
                         .to_pretty_string(MAX_COLUMNS),
                     source_url: source_linker
                         .url(func_def.location.map_end(|_| func_def.end_position)),
+                    examples: examples(module, &func_def.name),
                 },
             )),
             _ => None,
@@ -544,6 +575,22 @@ impl DocFunction {
     }
 }
 
+/// The source of tests marked as `@example(...)` of the given function.
+fn examples(module: &CheckedModule, function: &str) -> Vec<String> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::Test(test) if test.example.as_deref() == Some(function) => module
+                .code
+                .get(test.location.start..=test.end_position)
+                .map(str::to_string),
+            _ => None,
+        })
+        .collect()
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
 struct DocConstant {
     name: String,
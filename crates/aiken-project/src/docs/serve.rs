@@ -0,0 +1,220 @@
+//! A tiny HTTP server to preview generated documentation locally. Pages are served as-is
+//! from disk, with a small script injected into each of them which polls the server and
+//! reloads the page whenever the documentation gets regenerated.
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+};
+
+/// Path polled by pages to know whether they should reload.
+const REVISION_PATH: &str = "/__revision";
+
+const LIVE_RELOAD: &str = r#"<script>
+(function () {
+  let revision = null;
+  setInterval(async () => {
+    try {
+      const current = await (await fetch("/__revision", { cache: "no-store" })).text();
+      if (revision !== null && current !== revision) {
+        location.reload();
+      }
+      revision = current;
+    } catch (_) {}
+  }, 1000);
+})();
+</script>"#;
+
+/// A handle on a running documentation server. Bumping the revision makes every open
+/// page reload.
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub address: String,
+    root: Arc<RwLock<Option<PathBuf>>>,
+    revision: Arc<AtomicU64>,
+}
+
+impl Server {
+    /// Start listening in a background thread. Nothing is served until a first directory is
+    /// given with 'reload'.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+
+        let server = Server {
+            address: format!("http://{}", listener.local_addr()?),
+            root: Arc::new(RwLock::new(None)),
+            revision: Arc::new(AtomicU64::new(0)),
+        };
+
+        let root = server.root.clone();
+        let revision = server.revision.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let root = root.read().ok().and_then(|root| root.clone());
+                let revision = revision.clone();
+                thread::spawn(move || {
+                    let _ = handle(stream, root.as_deref(), revision.load(Ordering::SeqCst));
+                });
+            }
+        });
+
+        Ok(server)
+    }
+
+    /// Serve files from the given directory from now on, and reload every open page.
+    pub fn reload(&self, root: PathBuf) {
+        if let Ok(mut served) = self.root.write() {
+            *served = Some(root);
+        }
+
+        self.revision.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn handle(mut stream: TcpStream, root: Option<&Path>, revision: u64) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => {
+            let path = target.split(['?', '#']).next().unwrap_or_default();
+
+            if path == REVISION_PATH {
+                ("200 OK", "text/plain", revision.to_string().into_bytes())
+            } else {
+                match root
+                    .and_then(|root| resolve(root, path))
+                    .and_then(|file| Some((fs::read(&file).ok()?, file)))
+                {
+                    Some((content, file)) => {
+                        let content_type = content_type(&file);
+                        let content = if content_type.starts_with("text/html") {
+                            inject_live_reload(&String::from_utf8_lossy(&content)).into_bytes()
+                        } else {
+                            content
+                        };
+                        ("200 OK", content_type, content)
+                    }
+                    None => ("404 Not Found", "text/plain", b"Not found".to_vec()),
+                }
+            }
+        }
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            b"Method not allowed".to_vec(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+
+    stream.write_all(&body)?;
+
+    stream.flush()
+}
+
+/// Map a request path onto a file under the root, refusing to escape it. Directories are
+/// resolved to their 'index.html'.
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let mut file = root.to_path_buf();
+
+    for component in Path::new(&percent_decode(path)?).components() {
+        match component {
+            Component::Normal(segment) => file.push(segment),
+            Component::RootDir | Component::CurDir => (),
+            Component::ParentDir | Component::Prefix(..) => return None,
+        }
+    }
+
+    if file.is_dir() {
+        file.push("index.html");
+    }
+
+    file.is_file().then_some(file)
+}
+
+fn percent_decode(path: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut chars = path.bytes();
+
+    while let Some(byte) = chars.next() {
+        if byte == b'%' {
+            let hex = [chars.next()?, chars.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+fn inject_live_reload(html: &str) -> String {
+    match html.rfind("</body>") {
+        Some(ix) => format!("{}{LIVE_RELOAD}{}", &html[..ix], &html[ix..]),
+        None => format!("{html}{LIVE_RELOAD}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_stays_within_root() {
+        let root = std::env::temp_dir().join(format!("aiken-docs-serve-{}", std::process::id()));
+        fs::create_dir_all(root.join("aiken")).unwrap();
+        fs::write(root.join("index.html"), "").unwrap();
+        fs::write(root.join("aiken/list.html"), "").unwrap();
+
+        assert_eq!(resolve(&root, "/"), Some(root.join("index.html")));
+        assert_eq!(
+            resolve(&root, "/aiken/list.html"),
+            Some(root.join("aiken/list.html"))
+        );
+        assert_eq!(
+            resolve(&root, "/aiken%2Flist.html"),
+            Some(root.join("aiken/list.html"))
+        );
+        assert_eq!(resolve(&root, "/../index.html"), None);
+        assert_eq!(resolve(&root, "/aiken/missing.html"), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn inject_before_closing_body() {
+        assert_eq!(
+            inject_live_reload("<html><body>foo</body></html>"),
+            format!("<html><body>foo{LIVE_RELOAD}</body></html>")
+        );
+    }
+}
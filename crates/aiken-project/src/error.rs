@@ -1,8 +1,12 @@
-use crate::{blueprint, deps::manifest::Package, package_name::PackageName};
+use crate::{
+    blueprint, costs::Budget, deps::manifest::Package, package_name::PackageName, settings,
+};
 use aiken_lang::{
     ast::{self, Span},
     error::ExtraData,
+    line_numbers::{LineColumn, LineNumbers},
     parser::error::ParseError,
+    plutus_version::PlutusVersion,
     test_framework::{BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult},
     tipo,
 };
@@ -25,6 +29,7 @@ pub enum TomlLoadingContext {
     Project,
     Manifest,
     Package,
+    Costs,
 }
 
 impl fmt::Display for TomlLoadingContext {
@@ -33,6 +38,7 @@ impl fmt::Display for TomlLoadingContext {
             TomlLoadingContext::Project => write!(f, "project"),
             TomlLoadingContext::Manifest => write!(f, "manifest"),
             TomlLoadingContext::Package => write!(f, "package"),
+            TomlLoadingContext::Costs => write!(f, "costs"),
         }
     }
 }
@@ -87,6 +93,49 @@ pub enum Error {
     #[error("I couldn't find any 'aiken.toml' manifest in {path}.")]
     MissingManifest { path: PathBuf },
 
+    #[error(
+        "I found an import of '{}', which is internal to {}.",
+        module.if_supports_color(Stderr, |s| s.yellow()),
+        package.if_supports_color(Stderr, |s| s.bright_blue())
+    )]
+    InternalModuleImported {
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+        module: String,
+        package: String,
+        alternatives: Vec<String>,
+    },
+
+    #[error(
+        "I found a validator, but the project targets {}.",
+        plutus.if_supports_color(Stderr, |s| s.yellow())
+    )]
+    UnsupportedPlutusVersion {
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+        validator: String,
+        plutus: PlutusVersion,
+    },
+
+    #[error(
+        "I found two validators that would both compile to {}.",
+        title.if_supports_color(Stderr, |s| s.yellow())
+    )]
+    ValidatorNameCollision {
+        title: String,
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+        first_path: PathBuf,
+        first_src: String,
+        first_location: Span,
+    },
+
     #[error("I just found a cycle in module hierarchy!")]
     ImportCycle { modules: Vec<String> },
 
@@ -131,6 +180,21 @@ pub enum Error {
     )]
     UnableToResolvePackage { package: Package },
 
+    #[error(
+        "I found conflicting requirements for {}.",
+        package.if_supports_color(Stderr, |s| s.bright_blue())
+    )]
+    DependencyConflict {
+        package: PackageName,
+        requirers: Vec<(PackageName, String)>,
+    },
+
+    #[error("I found some gaps in the package's metadata preventing it from being published.")]
+    IncompletePackageMetadata { problems: Vec<String> },
+
+    #[error("I found public definitions without documentation.")]
+    UndocumentedDefinitions { definitions: Vec<String> },
+
     #[error("I couldn't parse the provided stake address.")]
     MalformedStakeAddress {
         error: Option<pallas_addresses::Error>,
@@ -151,6 +215,66 @@ pub enum Error {
         known_modules: Vec<String>,
     },
 
+    #[error("I couldn't load a cost model from {}.", path.display())]
+    InvalidCostModel { path: PathBuf, reason: String },
+
+    #[error("{test} spent more than its recorded budget.")]
+    CostRegression {
+        test: String,
+        recorded: Budget,
+        spent: Budget,
+        tolerance: u32,
+    },
+
+    #[error("I couldn't find any dependency named '{name}'.")]
+    UnknownDependency {
+        name: PackageName,
+        known_dependencies: Vec<String>,
+    },
+
+    #[error("I couldn't list the files changed since '{git_ref}'.")]
+    GitDiff { git_ref: String, message: String },
+
+    #[error("I couldn't install the toolchain {version}.")]
+    Toolchain { version: String, reason: String },
+
+    #[error("I couldn't read the attestation at {}.", path.display())]
+    InvalidAttestation { path: PathBuf, reason: String },
+
+    #[error("The build doesn't match its attestation ({}).", path.display())]
+    AttestationMismatch {
+        path: PathBuf,
+        differences: Vec<String>,
+    },
+
+    #[error("I need network access to {action}, but I'm offline.")]
+    Offline { action: String },
+
+    #[error(
+        "I couldn't load the fixture '{}': {error}.",
+        file.if_supports_color(Stderr, |s| s.yellow())
+    )]
+    MissingFixture {
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+        file: String,
+        error: String,
+    },
+
+    #[error(
+        "I won't load the fixture '{}' from outside of the 'fixtures' folder.",
+        file.if_supports_color(Stderr, |s| s.yellow())
+    )]
+    FixtureOutsidePackage {
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+        file: String,
+    },
+
     #[error("I located conditional modules under 'env', but no default one!")]
     NoDefaultEnvironment,
 }
@@ -248,6 +372,22 @@ impl ExtraData for Error {
             | Error::NoValidatorNotFound { .. }
             | Error::MoreThanOneValidatorFound { .. }
             | Error::Module { .. }
+            | Error::DependencyConflict { .. }
+            | Error::IncompletePackageMetadata { .. }
+            | Error::UndocumentedDefinitions { .. }
+            | Error::InvalidCostModel { .. }
+            | Error::CostRegression { .. }
+            | Error::UnknownDependency { .. }
+            | Error::InternalModuleImported { .. }
+            | Error::UnsupportedPlutusVersion { .. }
+            | Error::ValidatorNameCollision { .. }
+            | Error::GitDiff { .. }
+            | Error::Toolchain { .. }
+            | Error::InvalidAttestation { .. }
+            | Error::AttestationMismatch { .. }
+            | Error::Offline { .. }
+            | Error::MissingFixture { .. }
+            | Error::FixtureOutsidePackage { .. }
             | Error::NoDefaultEnvironment { .. }
             | Error::ModuleNotFound { .. }
             | Error::ExportNotFound { .. } => None,
@@ -280,12 +420,28 @@ impl GetSource for Error {
             | Error::MoreThanOneValidatorFound { .. }
             | Error::ModuleNotFound { .. }
             | Error::ExportNotFound { .. }
+            | Error::DependencyConflict { .. }
+            | Error::IncompletePackageMetadata { .. }
+            | Error::UndocumentedDefinitions { .. }
+            | Error::InvalidCostModel { .. }
+            | Error::CostRegression { .. }
+            | Error::UnknownDependency { .. }
+            | Error::GitDiff { .. }
+            | Error::Toolchain { .. }
+            | Error::InvalidAttestation { .. }
+            | Error::AttestationMismatch { .. }
+            | Error::Offline { .. }
             | Error::NoDefaultEnvironment { .. }
             | Error::Module { .. } => None,
             Error::DuplicateModule { second: path, .. }
             | Error::MissingManifest { path }
             | Error::TomlLoading { path, .. }
             | Error::Parse { path, .. }
+            | Error::InternalModuleImported { path, .. }
+            | Error::MissingFixture { path, .. }
+            | Error::FixtureOutsidePackage { path, .. }
+            | Error::UnsupportedPlutusVersion { path, .. }
+            | Error::ValidatorNameCollision { path, .. }
             | Error::Type { path, .. }
             | Error::TestFailure { path, .. } => Some(path.to_path_buf()),
         }
@@ -309,14 +465,30 @@ impl GetSource for Error {
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
             | Error::NoValidatorNotFound { .. }
+            | Error::DependencyConflict { .. }
+            | Error::IncompletePackageMetadata { .. }
+            | Error::UndocumentedDefinitions { .. }
+            | Error::InvalidCostModel { .. }
+            | Error::CostRegression { .. }
+            | Error::UnknownDependency { .. }
+            | Error::GitDiff { .. }
+            | Error::Toolchain { .. }
+            | Error::InvalidAttestation { .. }
+            | Error::AttestationMismatch { .. }
+            | Error::Offline { .. }
             | Error::NoDefaultEnvironment { .. }
             | Error::MoreThanOneValidatorFound { .. }
             | Error::ModuleNotFound { .. }
             | Error::ExportNotFound { .. }
             | Error::Module { .. } => None,
-            Error::TomlLoading { src, .. } | Error::Parse { src, .. } | Error::Type { src, .. } => {
-                Some(src.to_string())
-            }
+            Error::TomlLoading { src, .. }
+            | Error::Parse { src, .. }
+            | Error::Type { src, .. }
+            | Error::InternalModuleImported { src, .. }
+            | Error::MissingFixture { src, .. }
+            | Error::FixtureOutsidePackage { src, .. }
+            | Error::UnsupportedPlutusVersion { src, .. }
+            | Error::ValidatorNameCollision { src, .. } => Some(src.to_string()),
         }
     }
 }
@@ -367,6 +539,32 @@ impl Diagnostic for Error {
             Error::MoreThanOneValidatorFound { .. } => None,
             Error::ExportNotFound { .. } => None,
             Error::ModuleNotFound { .. } => None,
+            Error::DependencyConflict { .. } => Some(boxed(Box::new("aiken::packages::conflict"))),
+            Error::IncompletePackageMetadata { .. } => {
+                Some(boxed(Box::new("aiken::packages::publish::metadata")))
+            }
+            Error::UndocumentedDefinitions { .. } => {
+                Some(boxed(Box::new("aiken::packages::publish::docs")))
+            }
+            Error::InvalidCostModel { .. } => Some(boxed(Box::new("aiken::check::cost_model"))),
+            Error::CostRegression { .. } => Some(boxed(Box::new("aiken::check::costs"))),
+            Error::UnknownDependency { .. } => None,
+            Error::InternalModuleImported { .. } => {
+                Some(boxed(Box::new("aiken::module::internal")))
+            }
+            Error::UnsupportedPlutusVersion { .. } => {
+                Some(boxed(Box::new("aiken::validator::plutus_version")))
+            }
+            Error::ValidatorNameCollision { .. } => {
+                Some(boxed(Box::new("aiken::validator::name_collision")))
+            }
+            Error::GitDiff { .. } => Some(boxed(Box::new("aiken::check::changed_since"))),
+            Error::Toolchain { .. } => Some(boxed(Box::new("aiken::toolchain::install"))),
+            Error::InvalidAttestation { .. } => Some(boxed(Box::new("aiken::verify::invalid"))),
+            Error::AttestationMismatch { .. } => Some(boxed(Box::new("aiken::verify::mismatch"))),
+            Error::Offline { .. } => Some(boxed(Box::new("aiken::offline"))),
+            Error::MissingFixture { .. } => Some(boxed(Box::new("aiken::fixture"))),
+            Error::FixtureOutsidePackage { .. } => Some(boxed(Box::new("aiken::fixture::outside"))),
             Error::NoDefaultEnvironment { .. } => None,
             Error::Module(e) => e.code().map(boxed),
         }
@@ -391,6 +589,116 @@ impl Diagnostic for Error {
             Error::MissingManifest { .. } => Some(Box::new(
                 "Try running `aiken new <REPOSITORY/PROJECT>` to initialise a project with an example manifest.",
             )),
+            Error::DependencyConflict { package, requirers } => {
+                Some(Box::new(conflict_help(package, requirers)))
+            }
+            Error::IncompletePackageMetadata { problems } => Some(Box::new(format!(
+                "Fix the following in your aiken.toml:\n\n{}",
+                problems
+                    .iter()
+                    .map(|problem| format!("→ {problem}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
+            Error::UndocumentedDefinitions { definitions } => Some(Box::new(format!(
+                "Published packages are meant to be consumed by others, so every public definition must come with a doc comment (i.e. '///'). These ones don't:\n\n{}",
+                definitions
+                    .iter()
+                    .map(|definition| format!(
+                        "→ {}",
+                        definition.if_supports_color(Stdout, |s| s.purple())
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
+            Error::InvalidCostModel { reason, .. } => Some(Box::new(format!(
+                "I was expecting protocol parameters in JSON, with integer cost models per Plutus version under 'costModels', but {reason}"
+            ))),
+            Error::CostRegression {
+                recorded,
+                spent,
+                tolerance,
+                ..
+            } => Some(Box::new(format!(
+                "recorded: mem {}, cpu {}\n   spent: mem {}, cpu {}\n\nThe allowed tolerance is {tolerance}%. If the increase is expected, record the costs again with --record-costs.",
+                recorded.mem, recorded.cpu, spent.mem, spent.cpu,
+            ))),
+            Error::UnknownDependency {
+                known_dependencies, ..
+            } if known_dependencies.is_empty() => Some(Box::new(
+                "The project doesn't have any dependency. Perhaps you haven't built it yet?",
+            )),
+            Error::UnknownDependency {
+                known_dependencies, ..
+            } => Some(Box::new(format!(
+                "I know about the following dependencies:\n{}",
+                known_dependencies
+                    .iter()
+                    .map(|s| format!("─▶ {}", s.if_supports_color(Stdout, |s| s.purple())))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
+            Error::InternalModuleImported {
+                package,
+                alternatives,
+                ..
+            } => Some(Box::new(if alternatives.is_empty() {
+                format!(
+                    "Internal modules can only be imported from within their own package. Look for a public module of {package} exposing what you need."
+                )
+            } else {
+                format!(
+                    "Internal modules can only be imported from within their own package. Try importing from {} instead, which re-exports from it.",
+                    alternatives
+                        .iter()
+                        .map(|name| name.if_supports_color(Stdout, |s| s.purple()).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })),
+            Error::UnsupportedPlutusVersion { .. } => Some(Box::new(format!(
+                "Validators are compiled against the script context of {}, whose layout differs from earlier versions. Set 'plutus = \"v3\"' in your aiken.toml.",
+                PlutusVersion::V3,
+            ))),
+            Error::ValidatorNameCollision {
+                first_path,
+                first_src,
+                first_location,
+                ..
+            } => {
+                let first = match LineNumbers::new(first_src).line_and_column_number(first_location.start) {
+                    Some(LineColumn { line, column }) => format!("{}:{line}:{column}", first_path.display()),
+                    None => first_path.display().to_string(),
+                };
+                Some(Box::new(format!(
+                    "The other one is defined at {}. Both would end up under the same title in the blueprint and overwrite each other's artifacts; this typically happens with modules whose names only differ by case, or that are reachable through a symbolic link. Rename or remove one of them.",
+                    first.if_supports_color(Stderr, |s| s.yellow()),
+                )))
+            }
+            Error::GitDiff { message, .. } => Some(Box::new(format!(
+                "Make sure the project lives in a git repository and that the ref exists. Git said:\n\n{}",
+                message.if_supports_color(Stderr, |s| s.red())
+            ))),
+            Error::Toolchain { reason, .. } => Some(Box::new(reason)),
+            Error::InvalidAttestation { reason, .. } => Some(Box::new(reason)),
+            Error::AttestationMismatch { differences, .. } => Some(Box::new(format!(
+                "Rebuilding the project with the attested options yields different results:\n\n{}",
+                differences
+                    .iter()
+                    .map(|difference| format!("  - {difference}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
+            Error::Offline { .. } => Some(Box::new(format!(
+                "Network access is disabled through {}. Unset it, or make sure that what's needed is already in cache.",
+                settings::OFFLINE.if_supports_color(Stderr, |s| s.yellow())
+            ))),
+            Error::MissingFixture { .. } => Some(Box::new(
+                "Fixtures are looked up in the 'fixtures' folder at the root of the package declaring them. Files are embedded as they are, except those ending in '.hex' (e.g. 'tx.cbor.hex') which must contain hex-encoded bytes.",
+            )),
+            Error::FixtureOutsidePackage { .. } => Some(Box::new(
+                "Fixtures must be given by a relative path within the 'fixtures' folder of the package declaring them, so that dependencies can't embed arbitrary files from the machine building them.",
+            )),
             Error::NoDefaultEnvironment { .. } => Some(Box::new(
                 "Environment module names are free, but there must be at least one named 'default.ak'.",
             )),
@@ -480,6 +788,46 @@ impl Diagnostic for Error {
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
+            Error::DependencyConflict { .. } => None,
+            Error::IncompletePackageMetadata { .. } => None,
+            Error::UndocumentedDefinitions { .. } => None,
+            Error::InvalidCostModel { .. } => None,
+            Error::CostRegression { .. } => None,
+            Error::UnknownDependency { .. } => None,
+            Error::InternalModuleImported { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("internal".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
+            Error::UnsupportedPlutusVersion { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("validator".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
+            Error::ValidatorNameCollision { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("collides".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
+            Error::GitDiff { .. } => None,
+            Error::Toolchain { .. } => None,
+            Error::InvalidAttestation { .. } => None,
+            Error::AttestationMismatch { .. } => None,
+            Error::Offline { .. } => None,
+            Error::MissingFixture { location, .. }
+            | Error::FixtureOutsidePackage { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("fixture".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
             Error::NoDefaultEnvironment { .. } => None,
             Error::ModuleNotFound { .. } => None,
             Error::Module(e) => e.labels(),
@@ -494,6 +842,22 @@ impl Diagnostic for Error {
             Error::ModuleNotFound { .. } => None,
             Error::ExportNotFound { .. } => None,
             Error::Blueprint(e) => e.source_code(),
+            Error::DependencyConflict { .. } => None,
+            Error::IncompletePackageMetadata { .. } => None,
+            Error::UndocumentedDefinitions { .. } => None,
+            Error::InvalidCostModel { .. } => None,
+            Error::CostRegression { .. } => None,
+            Error::UnknownDependency { .. } => None,
+            Error::InternalModuleImported { named, .. } => Some(named.as_ref()),
+            Error::UnsupportedPlutusVersion { named, .. } => Some(named.as_ref()),
+            Error::ValidatorNameCollision { named, .. } => Some(named.as_ref()),
+            Error::GitDiff { .. } => None,
+            Error::Toolchain { .. } => None,
+            Error::InvalidAttestation { .. } => None,
+            Error::AttestationMismatch { .. } => None,
+            Error::Offline { .. } => None,
+            Error::MissingFixture { named, .. } => Some(named.as_ref()),
+            Error::FixtureOutsidePackage { named, .. } => Some(named.as_ref()),
             Error::NoDefaultEnvironment { .. } => None,
             Error::Parse { named, .. } => Some(named.as_ref()),
             Error::Type { named, .. } => Some(named),
@@ -539,6 +903,22 @@ impl Diagnostic for Error {
             Error::MalformedStakeAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
+            Error::DependencyConflict { .. } => None,
+            Error::IncompletePackageMetadata { .. } => None,
+            Error::UndocumentedDefinitions { .. } => None,
+            Error::InvalidCostModel { .. } => None,
+            Error::CostRegression { .. } => None,
+            Error::UnknownDependency { .. } => None,
+            Error::InternalModuleImported { .. } => None,
+            Error::UnsupportedPlutusVersion { .. } => None,
+            Error::ValidatorNameCollision { .. } => None,
+            Error::GitDiff { .. } => None,
+            Error::Toolchain { .. } => None,
+            Error::InvalidAttestation { .. } => None,
+            Error::AttestationMismatch { .. } => None,
+            Error::Offline { .. } => None,
+            Error::MissingFixture { .. } => None,
+            Error::FixtureOutsidePackage { .. } => None,
             Error::NoDefaultEnvironment { .. } => None,
             Error::Module(e) => e.url(),
         }
@@ -555,6 +935,22 @@ impl Diagnostic for Error {
             Error::Parse { .. } => None,
             Error::Type { error, .. } => error.related(),
             Error::StandardIo(_) => None,
+            Error::DependencyConflict { .. } => None,
+            Error::IncompletePackageMetadata { .. } => None,
+            Error::UndocumentedDefinitions { .. } => None,
+            Error::InvalidCostModel { .. } => None,
+            Error::CostRegression { .. } => None,
+            Error::UnknownDependency { .. } => None,
+            Error::InternalModuleImported { .. } => None,
+            Error::UnsupportedPlutusVersion { .. } => None,
+            Error::ValidatorNameCollision { .. } => None,
+            Error::GitDiff { .. } => None,
+            Error::Toolchain { .. } => None,
+            Error::InvalidAttestation { .. } => None,
+            Error::AttestationMismatch { .. } => None,
+            Error::Offline { .. } => None,
+            Error::MissingFixture { .. } => None,
+            Error::FixtureOutsidePackage { .. } => None,
             Error::NoDefaultEnvironment { .. } => None,
             Error::MissingManifest { .. } => None,
             Error::TomlLoading { .. } => None,
@@ -596,6 +992,14 @@ pub enum Warning {
     NoConfigurationForEnv { env: String },
     #[error("Suspicious test filter (-m) yielding no test scenarios.")]
     SuspiciousTestMatch { test: String },
+    #[error(
+        "I found conflicting requirements for {}.",
+        package.if_supports_color(Stderr, |s| s.bright_blue())
+    )]
+    DependencyConflict {
+        package: PackageName,
+        requirers: Vec<(PackageName, String)>,
+    },
 }
 
 impl ExtraData for Warning {
@@ -606,6 +1010,7 @@ impl ExtraData for Warning {
             | Warning::InvalidModuleName { .. }
             | Warning::CompilerVersionMismatch { .. }
             | Warning::NoConfigurationForEnv { .. }
+            | Warning::DependencyConflict { .. }
             | Warning::SuspiciousTestMatch { .. } => None,
             Warning::Type { warning, .. } => warning.extra_data(),
         }
@@ -620,6 +1025,7 @@ impl GetSource for Warning {
             | Warning::DependencyAlreadyExists { .. }
             | Warning::NoConfigurationForEnv { .. }
             | Warning::CompilerVersionMismatch { .. }
+            | Warning::DependencyConflict { .. }
             | Warning::SuspiciousTestMatch { .. } => None,
         }
     }
@@ -632,6 +1038,7 @@ impl GetSource for Warning {
             | Warning::DependencyAlreadyExists { .. }
             | Warning::NoConfigurationForEnv { .. }
             | Warning::CompilerVersionMismatch { .. }
+            | Warning::DependencyConflict { .. }
             | Warning::SuspiciousTestMatch { .. } => None,
         }
     }
@@ -650,6 +1057,7 @@ impl Diagnostic for Warning {
             | Warning::NoConfigurationForEnv { .. }
             | Warning::DependencyAlreadyExists { .. }
             | Warning::CompilerVersionMismatch { .. }
+            | Warning::DependencyConflict { .. }
             | Warning::SuspiciousTestMatch { .. } => None,
         }
     }
@@ -662,6 +1070,7 @@ impl Diagnostic for Warning {
             | Warning::DependencyAlreadyExists { .. }
             | Warning::NoConfigurationForEnv { .. }
             | Warning::CompilerVersionMismatch { .. }
+            | Warning::DependencyConflict { .. }
             | Warning::SuspiciousTestMatch { .. } => None,
         }
     }
@@ -683,6 +1092,7 @@ impl Diagnostic for Warning {
             Warning::NoConfigurationForEnv { .. } => {
                 Some(Box::new("aiken::project::config::missing::env"))
             }
+            Warning::DependencyConflict { .. } => Some(Box::new("aiken::packages::conflict")),
             Warning::SuspiciousTestMatch { .. } => Some(Box::new("aiken::check::suspicious_match")),
         }
     }
@@ -704,6 +1114,9 @@ impl Diagnostic for Warning {
             Warning::NoConfigurationForEnv { .. } => Some(Box::new(
                 "When configuration keys are missing for a target environment, no 'config' module will be created. This may lead to issues down the line.",
             )),
+            Warning::DependencyConflict { package, requirers } => {
+                Some(Box::new(conflict_help(package, requirers)))
+            }
             Warning::SuspiciousTestMatch { test } => Some(Box::new(
                 format!(
                     "Did you mean to match all tests within a specific module? Like so:\n\n╰─▶ {}",
@@ -799,8 +1212,33 @@ pub struct Unformatted {
     pub output: String,
 }
 
+/// Who requires what, and how to settle it.
+fn conflict_help(package: &PackageName, requirers: &[(PackageName, String)]) -> String {
+    format!(
+        "Dependencies are resolved to a single version, but they disagree on which one:\n\n{}\n\nIf you know one version to work for everyone, settle it with an {overrides} table in your aiken.toml:\n\n{example}",
+        requirers
+            .iter()
+            .map(|(requirer, version)| format!(
+                "→ {} requires {}",
+                requirer.if_supports_color(Stdout, |s| s.bright_blue()),
+                version.if_supports_color(Stdout, |s| s.yellow())
+            ))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        overrides = "[overrides]".if_supports_color(Stdout, |s| s.bold()),
+        example = format!("[overrides]\n\"{package}\" = \"{}\"", requirers[0].1)
+            .if_supports_color(Stdout, |s| s.purple()),
+    )
+}
+
 fn default_miette_handler(context_lines: usize) -> MietteHandler {
-    MietteHandlerOpts::new()
+    let opts = match settings::color() {
+        settings::Color::Always => MietteHandlerOpts::new().color(true),
+        settings::Color::Never => MietteHandlerOpts::new().color(false),
+        settings::Color::Auto => MietteHandlerOpts::new(),
+    };
+
+    opts
         // For better support of terminal themes use the ANSI coloring
         .rgb_colors(RgbColors::Never)
         // If ansi support is disabled in the config disable the eye-candy
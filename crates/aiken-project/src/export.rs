@@ -84,6 +84,7 @@ impl Export {
                     annotation: func.return_annotation.clone(),
                     doc: None,
                     is_validator_param: false,
+                    default: None,
                     tipo: func.return_type.clone(),
                 },
             ),
@@ -1,26 +1,43 @@
 use std::{
-    fs,
     io::Read,
     path::{Path, PathBuf},
     str::FromStr,
 };
 
-use aiken_lang::{ast::ModuleKind, parser};
+use aiken_lang::{ast::ModuleKind, format::FormatOptions, parser};
 
 use crate::{
     error::{Error, Unformatted},
     is_aiken_path,
+    vfs::{OsFs, Vfs},
 };
 
-pub fn run(stdin: bool, check: bool, files: Vec<String>) -> Result<(), Vec<Error>> {
+pub fn run(
+    stdin: bool,
+    check: bool,
+    files: Vec<String>,
+    options: FormatOptions,
+) -> Result<(), Vec<Error>> {
+    run_with(&OsFs, stdin, check, files, options)
+}
+
+/// Like 'run', reading and writing files through the given file system. Directories are still
+/// walked on disk, to honor '.gitignore' files.
+pub fn run_with(
+    vfs: &dyn Vfs,
+    stdin: bool,
+    check: bool,
+    files: Vec<String>,
+    options: FormatOptions,
+) -> Result<(), Vec<Error>> {
     if stdin {
-        process_stdin(check)
+        process_stdin(check, options)
     } else {
-        process_files(check, files)
+        process_files(vfs, check, files, options)
     }
 }
 
-fn process_stdin(check: bool) -> Result<(), Vec<Error>> {
+fn process_stdin(check: bool, options: FormatOptions) -> Result<(), Vec<Error>> {
     let src = read_stdin()?;
 
     let mut out = String::new();
@@ -28,7 +45,7 @@ fn process_stdin(check: bool) -> Result<(), Vec<Error>> {
     let (module, extra) = parser::module(&src, ModuleKind::Lib)
         .map_err(|errs| Error::from_parse_errors(errs, Path::new("<stdin>"), &src))?;
 
-    aiken_lang::format::pretty(&mut out, module, extra, &src);
+    aiken_lang::format::pretty_with_options(&mut out, module, extra, &src, options);
 
     if !check {
         print!("{out}");
@@ -49,16 +66,25 @@ fn process_stdin(check: bool) -> Result<(), Vec<Error>> {
     Ok(())
 }
 
-fn process_files(check: bool, files: Vec<String>) -> Result<(), Vec<Error>> {
+fn process_files(
+    vfs: &dyn Vfs,
+    check: bool,
+    files: Vec<String>,
+    options: FormatOptions,
+) -> Result<(), Vec<Error>> {
     if check {
-        check_files(files)
+        check_files(vfs, files, options)
     } else {
-        format_files(files)
+        format_files(vfs, files, options)
     }
 }
 
-fn check_files(files: Vec<String>) -> Result<(), Vec<Error>> {
-    let problem_files = unformatted_files(files)?;
+fn check_files(
+    vfs: &dyn Vfs,
+    files: Vec<String>,
+    options: FormatOptions,
+) -> Result<(), Vec<Error>> {
+    let problem_files = unformatted_files(vfs, files, options)?;
 
     if problem_files.is_empty() {
         Ok(())
@@ -67,15 +93,27 @@ fn check_files(files: Vec<String>) -> Result<(), Vec<Error>> {
     }
 }
 
-fn format_files(files: Vec<String>) -> Result<(), Vec<Error>> {
-    for file in unformatted_files(files)? {
-        fs::write(file.destination, file.output).map_err(Error::from)?;
+fn format_files(
+    vfs: &dyn Vfs,
+    files: Vec<String>,
+    options: FormatOptions,
+) -> Result<(), Vec<Error>> {
+    for file in unformatted_files(vfs, files, options)? {
+        vfs.write(&file.destination, file.output.as_bytes())
+            .map_err(|error| Error::FileIo {
+                error,
+                path: file.destination,
+            })?;
     }
 
     Ok(())
 }
 
-fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>, Vec<Error>> {
+fn unformatted_files(
+    vfs: &dyn Vfs,
+    files: Vec<String>,
+    options: FormatOptions,
+) -> Result<Vec<Unformatted>, Vec<Error>> {
     let mut problem_files = Vec::with_capacity(files.len());
     let mut errors = vec![];
 
@@ -84,11 +122,11 @@ fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>, Vec<Error>>
 
         if path.is_dir() {
             for path in aiken_files_excluding_gitignore(&path) {
-                if let Err(mut errs) = format_file(&mut problem_files, path) {
+                if let Err(mut errs) = format_file(vfs, &mut problem_files, path, options) {
                     errors.append(&mut errs);
                 };
             }
-        } else if let Err(mut errs) = format_file(&mut problem_files, path) {
+        } else if let Err(mut errs) = format_file(vfs, &mut problem_files, path, options) {
             errors.append(&mut errs);
         }
     }
@@ -100,8 +138,13 @@ fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>, Vec<Error>>
     }
 }
 
-fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<(), Vec<Error>> {
-    let src = fs::read_to_string(&path).map_err(|error| Error::FileIo {
+fn format_file(
+    vfs: &dyn Vfs,
+    problem_files: &mut Vec<Unformatted>,
+    path: PathBuf,
+    options: FormatOptions,
+) -> Result<(), Vec<Error>> {
+    let src = vfs.read_to_string(&path).map_err(|error| Error::FileIo {
         error,
         path: path.clone(),
     })?;
@@ -111,7 +154,7 @@ fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<()
     let (module, extra) = parser::module(&src, ModuleKind::Lib)
         .map_err(|errs| Error::from_parse_errors(errs, &path, &src))?;
 
-    aiken_lang::format::pretty(&mut output, module, extra, &src);
+    aiken_lang::format::pretty_with_options(&mut output, module, extra, &src, options);
 
     if src != output {
         problem_files.push(Unformatted {
@@ -143,3 +186,40 @@ pub fn aiken_files_excluding_gitignore(dir: &Path) -> impl Iterator<Item = PathB
         .map(ignore::DirEntry::into_path)
         .filter(move |d| is_aiken_path(d, dir))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::run_with;
+    use crate::vfs::{OverlayFs, Vfs};
+    use aiken_lang::format::FormatOptions;
+    use std::path::Path;
+
+    #[test]
+    fn format_files_through_vfs() {
+        let vfs = OverlayFs::in_memory();
+
+        let path = Path::new("/in-memory/project/lib/foo.ak");
+
+        vfs.insert(path, "fn  foo( ) {  1 }");
+
+        assert!(run_with(
+            &vfs,
+            false,
+            true,
+            vec![path.display().to_string()],
+            FormatOptions::default()
+        )
+        .is_err());
+
+        assert!(run_with(
+            &vfs,
+            false,
+            false,
+            vec![path.display().to_string()],
+            FormatOptions::default()
+        )
+        .is_ok());
+
+        assert_eq!(vfs.read_to_string(path).unwrap(), "fn foo() {\n  1\n}\n");
+    }
+}
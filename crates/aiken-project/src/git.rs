@@ -0,0 +1,123 @@
+use crate::error::Error;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Files of the repository containing 'root' that differ from the given git ref, including
+/// changes not yet committed and untracked files. Paths are joined onto 'root'.
+pub fn changed_files(root: &Path, git_ref: &str) -> Result<BTreeSet<PathBuf>, Error> {
+    let diff = git(
+        root,
+        git_ref,
+        &["diff", "--name-only", "--relative", git_ref, "--"],
+    )?;
+
+    let untracked = git(
+        root,
+        git_ref,
+        &["ls-files", "--others", "--exclude-standard"],
+    )?;
+
+    Ok(diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        .map(|line| root.join(line))
+        .collect())
+}
+
+fn git(root: &Path, git_ref: &str, args: &[&str]) -> Result<String, Error> {
+    let failure = |message: String| Error::GitDiff {
+        git_ref: git_ref.to_string(),
+        message,
+    };
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map_err(|e| failure(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(failure(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| failure(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changed_files;
+    use crate::error::Error;
+    use std::{
+        collections::BTreeSet,
+        fs,
+        path::{Path, PathBuf},
+        process::Command,
+    };
+
+    fn temp_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aiken-git-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("lib")).unwrap();
+        run(&dir, &["init", "--quiet"]);
+        dir
+    }
+
+    fn run(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args([
+                "-c",
+                "user.name=aiken",
+                "-c",
+                "user.email=aiken@example.com",
+            ])
+            .args(args)
+            .status()
+            .unwrap();
+
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn lists_modified_and_untracked_files() {
+        let root = temp_repo("changes");
+
+        fs::write(root.join("lib/a.ak"), "a").unwrap();
+        fs::write(root.join("lib/b.ak"), "b").unwrap();
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        run(&root, &["add", "."]);
+        run(&root, &["commit", "--quiet", "-m", "init"]);
+
+        fs::write(root.join("lib/a.ak"), "a'").unwrap();
+        fs::write(root.join("lib/c.ak"), "c").unwrap();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::write(root.join("build/ignored.ak"), "ignored").unwrap();
+
+        assert_eq!(
+            changed_files(&root, "HEAD").unwrap(),
+            BTreeSet::from([root.join("lib/a.ak"), root.join("lib/c.ak")]),
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rejects_unknown_refs() {
+        let root = temp_repo("unknown-ref");
+
+        assert!(matches!(
+            changed_files(&root, "no-such-ref"),
+            Err(Error::GitDiff { git_ref, .. }) if git_ref == "no-such-ref"
+        ));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
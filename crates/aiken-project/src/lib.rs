@@ -1,17 +1,31 @@
+pub mod annotate;
+pub mod archive;
+pub mod attestation;
 pub mod blueprint;
+pub mod build_cache;
 pub mod config;
+pub mod cost_model;
+pub mod costs;
 pub mod deps;
 pub mod docs;
 pub mod error;
 pub mod export;
 pub mod format;
+pub mod git;
 pub mod github;
 pub mod module;
 pub mod options;
 pub mod package_name;
 pub mod paths;
 pub mod pretty;
+pub mod publish;
+pub mod runner;
+pub mod scratch;
+pub mod serialization;
+pub mod settings;
 pub mod telemetry;
+pub mod toolchain;
+pub mod vfs;
 pub mod watch;
 
 mod test_framework;
@@ -20,49 +34,62 @@ mod test_framework;
 mod tests;
 
 use crate::{
+    attestation::Attestation,
     blueprint::{
         definitions::Definitions,
         schema::{Annotated, Schema},
+        summary::{self, ValidatorSize},
         Blueprint,
     },
     config::Config,
+    costs::{CostsGuard, CostsLock},
+    deps::{manifest::Manifest, PackageInfo},
     error::{Error, Warning},
     module::{CheckedModule, CheckedModules, ParsedModule, ParsedModules},
-    telemetry::Event,
+    telemetry::{Event, ReportOptions},
 };
 use aiken_lang::{
     ast::{
-        self, DataTypeKey, Definition, FunctionAccessKey, ModuleKind, Tracing, TypedDataType,
-        TypedFunction, UntypedDefinition,
+        self, ByteArrayFormatPreference, DataTypeKey, Definition, FunctionAccessKey,
+        ModuleConstant, ModuleKind, Span, TraceLevel, TraceSeverity, Tracing, TypedDataType,
+        TypedFunction, TypedValidator, UntypedDefinition,
     },
     builtins,
     expr::{TypedExpr, UntypedExpr},
     format::{Formatter, MAX_COLUMNS},
-    gen_uplc::CodeGenerator,
+    gen_uplc::{
+        dump::{AirDump, DumpedDefinition},
+        reachability::reachable_functions,
+        CodeGenerator,
+    },
     line_numbers::LineNumbers,
-    test_framework::{RunnableKind, Test, TestResult},
+    plutus_version::PlutusVersion,
+    test_framework::{EvalSettings, PropertyTest, RunnableKind, Test, TestResult},
     tipo::{Type, TypeInfo},
     utils, IdGenerator,
 };
 use export::Export;
 use indexmap::IndexMap;
+use itertools::Itertools;
 use miette::NamedSource;
-use options::{CodeGenMode, Options};
+use options::{BuildOptions, CheckOptions, CodeGenMode, Options};
 use package_name::PackageName;
 use pallas_addresses::{Address, Network, ShelleyAddress, ShelleyDelegationPart, StakePayload};
 use pallas_primitives::conway::PolicyId;
 use std::{
-    collections::{BTreeSet, HashMap, HashSet},
-    fs::{self, File},
-    io::BufReader,
-    path::{Path, PathBuf},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io::{self, BufRead, Write},
+    path::{Component, Path, PathBuf},
     rc::Rc,
+    time::Duration,
 };
 use telemetry::EventListener;
 use uplc::{
     ast::{Constant, Name, Program},
+    optimize::Pass,
     PlutusData,
 };
+use vfs::{OsFs, Vfs};
 
 #[derive(Debug)]
 pub struct Source {
@@ -101,6 +128,11 @@ where
     constants: IndexMap<FunctionAccessKey, TypedExpr>,
     data_types: IndexMap<DataTypeKey, TypedDataType>,
     module_sources: HashMap<String, (String, LineNumbers)>,
+    vfs: Box<dyn Vfs>,
+    changed_files: Option<BTreeSet<PathBuf>>,
+    affected_modules: Option<BTreeSet<String>>,
+    implicit_prelude: bool,
+    build_cache: bool,
 }
 
 impl<T> Project<T>
@@ -129,10 +161,7 @@ where
     pub fn new_with_config(config: Config, root: PathBuf, event_listener: T) -> Project<T> {
         let id_gen = IdGenerator::new();
 
-        let mut module_types = HashMap::new();
-
-        module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
-        module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+        let mut module_types = builtins::modules(&id_gen);
 
         let functions = builtins::prelude_functions(&id_gen, &module_types);
 
@@ -153,6 +182,11 @@ where
             constants: IndexMap::new(),
             data_types,
             module_sources: HashMap::new(),
+            vfs: Box::new(OsFs),
+            changed_files: None,
+            affected_modules: None,
+            implicit_prelude: true,
+            build_cache: !settings::no_build_cache(),
         }
     }
 
@@ -168,6 +202,53 @@ where
         )
     }
 
+    /// Like 'new_generator', but only hands over to the code generator the functions that are
+    /// transitively reachable from the given roots; unrelated library code is left out entirely.
+    pub fn new_pruned_generator<'a>(
+        &'a self,
+        tracing: Tracing,
+        roots: impl IntoIterator<Item = &'a TypedExpr>,
+    ) -> CodeGenerator<'a> {
+        let reachable = reachable_functions(roots, &self.functions, &self.constants);
+
+        CodeGenerator::new(
+            self.config.plutus,
+            self.functions
+                .iter()
+                .filter(|(key, _)| reachable.contains(*key))
+                .collect(),
+            utils::indexmap::as_ref_values(&self.constants),
+            utils::indexmap::as_ref_values(&self.data_types),
+            utils::indexmap::as_str_ref_values(&self.module_types),
+            utils::indexmap::as_str_ref_values(&self.module_sources),
+            tracing,
+        )
+    }
+
+    /// Ignore both the built-in prelude and the implicit imports configured in the project's
+    /// one, so that modules only see what they explicitly import.
+    pub fn disable_prelude(&mut self) {
+        self.config.prelude.clear();
+        self.implicit_prelude = false;
+    }
+
+    /// Read and write project files through the given file system instead of the disk.
+    pub fn set_vfs(&mut self, vfs: impl Vfs + 'static) {
+        self.vfs = Box::new(vfs);
+    }
+
+    /// Only type-check and test the modules affected by files changed since the given git ref:
+    /// the changed modules and, transitively, the modules importing them.
+    pub fn only_check_changes_since(&mut self, git_ref: &str) -> Result<(), Error> {
+        self.only_check_changes(git::changed_files(&self.root, git_ref)?);
+        Ok(())
+    }
+
+    /// Only type-check and test the modules affected by the given changed files.
+    pub fn only_check_changes(&mut self, changed_files: BTreeSet<PathBuf>) {
+        self.changed_files = Some(changed_files);
+    }
+
     pub fn warnings(&mut self) -> Vec<Warning> {
         std::mem::take(&mut self.warnings)
     }
@@ -199,28 +280,38 @@ where
         }
     }
 
-    pub fn build(
-        &mut self,
-        uplc: bool,
-        tracing: Tracing,
-        blueprint_path: PathBuf,
-        env: Option<String>,
-    ) -> Result<(), Vec<Error>> {
+    pub fn build(&mut self, options: BuildOptions) -> Result<(), Vec<Error>> {
         let options = Options {
-            code_gen_mode: CodeGenMode::Build(uplc),
-            tracing,
-            env,
-            blueprint_path,
+            code_gen_mode: CodeGenMode::Build {
+                uplc_dump: options.uplc,
+                air_dump: options.air,
+                check_artifacts: options.check_artifacts,
+                filter: options.filter,
+                trace_codes: options.trace_codes,
+                optimization_passes: options.optimization_passes,
+                emit_after: options.emit_after,
+                attest: options.attest,
+                metadata: options.metadata,
+                summary: options.summary,
+                annotate: options.annotate,
+                archive: options.archive,
+            },
+            tracing: options.tracing,
+            env: options.env,
+            blueprint_path: options.blueprint_path,
         };
 
         self.compile(options)
     }
 
+    /// Generate the documentation of the project (or of one of its dependencies), and return
+    /// the directory it was written to.
     pub fn docs(
         &mut self,
         destination: Option<PathBuf>,
         include_dependencies: bool,
-    ) -> Result<(), Vec<Error>> {
+        package: Option<PackageName>,
+    ) -> Result<PathBuf, Vec<Error>> {
         self.event_listener
             .handle_event(Event::BuildingDocumentation {
                 root: self.root.clone(),
@@ -234,19 +325,59 @@ where
 
         let mut modules = self.parse_sources(self.config.name.clone())?;
 
-        self.type_check(&mut modules, Tracing::silent(), None, false)?;
+        self.load_fixtures(&self.root, &mut modules)?;
+
+        self.type_check(
+            &mut modules,
+            Tracing::silent(),
+            TraceSeverity::Debug,
+            None,
+            false,
+        )?;
+
+        // Documentation of a dependency is generated from its sources (in the build folder, or
+        // wherever a path dependency lives), and using its own configuration.
+        let (root, config) = match package {
+            None => (self.root.clone(), self.config.clone()),
+            Some(name) => {
+                let project = self.config.name.to_string();
+
+                let known_dependencies = self
+                    .checked_modules
+                    .values()
+                    .map(|m| m.package.clone())
+                    .filter(|package| package != &project)
+                    .collect::<BTreeSet<_>>();
+
+                if !known_dependencies.contains(&name.to_string()) {
+                    return Err(Error::UnknownDependency {
+                        name,
+                        known_dependencies: known_dependencies.into_iter().collect(),
+                    }
+                    .into());
+                }
+
+                let root = self.config.dependency_root(&self.root, &name);
 
-        let destination = destination.unwrap_or_else(|| self.root.join("docs"));
+                let config = Config::load(&root)?;
+
+                (root, config)
+            }
+        };
+
+        let destination = destination.unwrap_or_else(|| root.join("docs"));
 
         self.event_listener.handle_event(Event::GeneratingDocFiles {
             output_path: destination.clone(),
         });
 
+        let documented_package = config.name.to_string();
+
         let modules = self
             .checked_modules
             .values_mut()
             .filter(|CheckedModule { package, .. }| {
-                include_dependencies || package == &self.config.name.to_string()
+                include_dependencies || package == &documented_package
             })
             .map(|m| {
                 m.attach_doc_and_module_comments();
@@ -254,91 +385,829 @@ where
             })
             .collect();
 
-        let doc_files = docs::generate_all(&self.root, &self.config, modules);
+        let doc_files = docs::generate_all(self.vfs.as_ref(), &root, &config, modules);
 
         for file in doc_files {
             let path = destination.join(file.path);
-            fs::create_dir_all(path.parent().unwrap()).map_err(Error::from)?;
-            fs::write(&path, file.content).map_err(Error::from)?;
+            self.vfs
+                .create_dir_all(path.parent().unwrap())
+                .map_err(Error::from)?;
+            self.vfs
+                .write(&path, file.content.as_bytes())
+                .map_err(Error::from)?;
         }
 
-        Ok(())
+        Ok(destination)
     }
 
+    /// Verify that the package is ready to be published: its metadata are complete, it
+    /// type-checks and passes its tests, and all its public definitions are documented. On
+    /// success, package sources and their documentation are bundled into an archive ready for
+    /// distribution.
+    pub fn publish(&mut self, destination: Option<PathBuf>, seed: u32) -> Result<(), Vec<Error>> {
+        publish::check_metadata(&self.config)?;
+
+        let options = Options {
+            tracing: Tracing::All(TraceLevel::Verbose),
+            env: None,
+            code_gen_mode: CodeGenMode::Test {
+                match_tests: None,
+                verbose: false,
+                exact_match: false,
+                seed,
+                property_max_success: PropertyTest::DEFAULT_MAX_SUCCESS,
+                timeout: None,
+                cost_model: None,
+                costs_guard: None,
+                report: ReportOptions::default(),
+            },
+            blueprint_path: self.blueprint_path(None),
+        };
+
+        self.compile(options)?;
+
+        self.checked_modules
+            .values_mut()
+            .for_each(|m| m.attach_doc_and_module_comments());
+
+        let definitions = publish::undocumented(&self.checked_modules, &self.config);
+
+        if !definitions.is_empty() {
+            return Err(Error::UndocumentedDefinitions { definitions }.into());
+        }
+
+        let package = self.config.name.to_string();
+
+        let docs = docs::generate_all(
+            self.vfs.as_ref(),
+            &self.root,
+            &self.config,
+            self.checked_modules
+                .values()
+                .filter(|module| module.package == package)
+                .collect(),
+        );
+
+        let path = destination
+            .unwrap_or_else(|| self.root.join(paths::build()).join("publish"))
+            .join(publish::archive_name(&self.config));
+
+        self.event_listener
+            .handle_event(Event::GeneratingPackageArchive { path: path.clone() });
+
+        publish::archive(self.vfs.as_ref(), &self.root, &path, docs).map_err(Vec::from)
+    }
+
+    pub fn check(&mut self, options: CheckOptions) -> Result<(), Vec<Error>> {
+        let cost_model = options
+            .cost_model
+            .map(|path| {
+                cost_model::from_protocol_parameters(self.vfs.as_ref(), &path, self.config.plutus)
+            })
+            .transpose()?;
+
+        let options = Options {
+            tracing: options.tracing,
+            env: options.env,
+            code_gen_mode: if options.skip_tests {
+                CodeGenMode::NoOp
+            } else {
+                CodeGenMode::Test {
+                    match_tests: options.match_tests,
+                    verbose: options.verbose,
+                    exact_match: options.exact_match,
+                    seed: options.seed,
+                    property_max_success: options.property_max_success,
+                    timeout: options.timeout,
+                    cost_model,
+                    costs_guard: options.costs_guard,
+                    report: options.report,
+                }
+            },
+            blueprint_path: self.blueprint_path(None),
+        };
+
+        self.compile(options)
+    }
+
+    /// Serve test runs over the line-delimited JSON protocol described in 'runner', until the
+    /// input is exhausted or a 'shutdown' request comes in. Compilation errors don't end the
+    /// session: they're reported to the client, which may fix them and ask for a 'reload'.
     #[allow(clippy::too_many_arguments)]
-    pub fn check(
+    pub fn serve_tests(
         &mut self,
-        skip_tests: bool,
-        match_tests: Option<Vec<String>>,
+        input: impl BufRead,
+        mut output: impl Write,
         verbose: bool,
-        exact_match: bool,
         seed: u32,
         property_max_success: usize,
+        timeout: Option<Duration>,
+        tracing: Tracing,
+        env: Option<String>,
+        cost_model: Option<PathBuf>,
+    ) -> Result<(), Vec<Error>> {
+        let cost_model = cost_model
+            .map(|path| {
+                cost_model::from_protocol_parameters(self.vfs.as_ref(), &path, self.config.plutus)
+            })
+            .transpose()?;
+
+        let settings = EvalSettings::new(self.config.plutus)
+            .with_cost_model(cost_model)
+            .with_timeout(timeout)
+            .with_pooled_allocation(true);
+
+        let options = |project: &Self| Options {
+            tracing,
+            env: env.clone(),
+            code_gen_mode: CodeGenMode::NoOp,
+            blueprint_path: project.blueprint_path(None),
+        };
+
+        let mut checkpoint = self.checkpoint();
+
+        let mut errors = self.compile(options(self)).err().unwrap_or_default();
+
+        runner::send(&mut output, runner::ready()).map_err(Error::from)?;
+
+        for line in input.lines() {
+            let line = line.map_err(Error::from)?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let request = match runner::Request::parse(&line) {
+                Ok(request) => request,
+                Err(message) => {
+                    runner::send(
+                        &mut output,
+                        runner::failure(&serde_json::Value::Null, &message, &[]),
+                    )
+                    .map_err(Error::from)?;
+                    continue;
+                }
+            };
+
+            let id = &request.id;
+
+            let message = match request.method {
+                runner::Method::Shutdown => {
+                    runner::send(&mut output, runner::result(id, serde_json::Value::Null))
+                        .map_err(Error::from)?;
+                    break;
+                }
+                runner::Method::Reload => {
+                    self.restore(checkpoint);
+                    checkpoint = self.checkpoint();
+                    self.warnings.clear();
+
+                    errors = self.compile(options(self)).err().unwrap_or_default();
+
+                    if errors.is_empty() {
+                        runner::result(id, serde_json::Value::Null)
+                    } else {
+                        runner::failure(id, "compilation failed", &errors)
+                    }
+                }
+                _ if !errors.is_empty() => runner::failure(id, "compilation failed", &errors),
+                runner::Method::List => match self.collect_tests(
+                    verbose,
+                    request.params.tests,
+                    request.params.exact_match,
+                    tracing,
+                ) {
+                    Ok(tests) => runner::result(id, runner::listing(&tests)),
+                    Err(error) => runner::failure(id, "collecting tests failed", &[error]),
+                },
+                runner::Method::Run => match self.collect_tests(
+                    verbose,
+                    request.params.tests,
+                    request.params.exact_match,
+                    tracing,
+                ) {
+                    Ok(tests) => {
+                        let seed = request.params.seed.unwrap_or(seed);
+
+                        let mut results = Vec::with_capacity(tests.len());
+
+                        let mut streamed = Ok(());
+
+                        self.stream_runnables(
+                            tests,
+                            seed,
+                            property_max_success,
+                            &settings,
+                            |test| {
+                                if streamed.is_ok() {
+                                    streamed =
+                                        runner::send(&mut output, runner::test_event(id, &test));
+                                }
+                                results.push(test);
+                            },
+                        );
+
+                        streamed.map_err(Error::from)?;
+
+                        runner::result(id, runner::summary(seed, &results))
+                    }
+                    Err(error) => runner::failure(id, "collecting tests failed", &[error]),
+                },
+            };
+
+            runner::send(&mut output, message).map_err(Error::from)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn benchmark(
+        &mut self,
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        seed: u32,
+        max_size: usize,
         tracing: Tracing,
         env: Option<String>,
+        cost_model: Option<PathBuf>,
     ) -> Result<(), Vec<Error>> {
+        let cost_model = cost_model
+            .map(|path| {
+                cost_model::from_protocol_parameters(self.vfs.as_ref(), &path, self.config.plutus)
+            })
+            .transpose()?;
+
         let options = Options {
             tracing,
             env,
-            code_gen_mode: if skip_tests {
-                CodeGenMode::NoOp
-            } else {
-                CodeGenMode::Test {
-                    match_tests,
-                    verbose,
-                    exact_match,
-                    seed,
-                    property_max_success,
-                }
+            code_gen_mode: CodeGenMode::Benchmark {
+                match_benchmarks,
+                exact_match,
+                seed,
+                max_size,
+                cost_model,
             },
             blueprint_path: self.blueprint_path(None),
         };
 
-        self.compile(options)
-    }
+        self.compile(options)
+    }
+
+    /// Write the UPLC of every validator to the artifacts directory. Dumps written by the
+    /// previous build and not overwritten by this one are removed, unless only some validators
+    /// were built (in which case the others' dumps are kept around). Returns the dumps written.
+    pub fn dump_uplc(&self, blueprint: &Blueprint, filtered: bool) -> Result<Vec<PathBuf>, Error> {
+        let dir = self.root.join(&self.config.artifacts.directory);
+
+        self.event_listener
+            .handle_event(Event::DumpingUPLC { path: dir.clone() });
+
+        self.vfs.create_dir_all(&dir)?;
+
+        let record = self.root.join(paths::build_artifacts());
+
+        let previous = self
+            .vfs
+            .read_to_string(&record)
+            .ok()
+            .and_then(|json| serde_json::from_str::<BTreeSet<String>>(&json).ok())
+            .unwrap_or_default();
+
+        let mut written = BTreeSet::new();
+
+        let mut dumps = Vec::new();
+
+        for validator in &blueprint.validators {
+            let (_, hash) = serialization::to_compiled_code(&validator.program)
+                .map_err(blueprint::Error::from)?;
+
+            let relative = self
+                .config
+                .artifacts
+                .path(&validator.title, &hash.to_string(), "uplc");
+
+            let path = self.root.join(&relative);
+
+            if let Some(parent) = path.parent() {
+                self.vfs.create_dir_all(parent)?;
+            }
+
+            let program = &validator.program;
+            let program: Program<Name> = program.inner().try_into().unwrap();
+
+            self.vfs
+                .write(&path, program.to_pretty().as_bytes())
+                .map_err(|error| Error::FileIo {
+                    error,
+                    path: path.clone(),
+                })?;
+
+            written.insert(vfs::to_slash(&relative));
+
+            dumps.push(path);
+        }
+
+        if filtered {
+            written.extend(previous);
+        } else {
+            self.clean_stale_artifacts(previous.difference(&written))?;
+        }
+
+        if let Some(parent) = record.parent() {
+            self.vfs.create_dir_all(parent)?;
+        }
+
+        self.vfs
+            .write(
+                &record,
+                serde_json::to_string_pretty(&written).unwrap().as_bytes(),
+            )
+            .map_err(|error| Error::FileIo {
+                error,
+                path: record,
+            })?;
+
+        Ok(dumps)
+    }
+
+    /// Remove UPLC dumps recorded by a previous build but not written by this one (e.g. for
+    /// validators that no longer exist, or written under a different naming scheme or layout),
+    /// along with any directory that this leaves empty. Nothing else is ever touched.
+    fn clean_stale_artifacts<'a>(
+        &self,
+        stale: impl Iterator<Item = &'a String>,
+    ) -> Result<(), Error> {
+        for relative in stale {
+            let path = self.root.join(relative);
+
+            // Records are ours, but let's not trust them blindly.
+            if !Path::new(relative)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                continue;
+            }
+
+            match self.vfs.remove_file(&path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    return Err(Error::FileIo { error, path });
+                }
+                _ => (),
+            }
+
+            for dir in path.ancestors().skip(1) {
+                if dir == self.root || !dir.starts_with(&self.root) || !self.vfs.is_empty_dir(dir) {
+                    break;
+                }
+
+                self.vfs.remove_dir(dir).map_err(|error| Error::FileIo {
+                    error,
+                    path: dir.to_path_buf(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_blueprint(&self, blueprint_path: &Path) -> Result<String, Error> {
+        self.vfs
+            .read_to_string(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile.into())
+    }
+
+    /// Decode the validators of a freshly written blueprint back, and make sure they re-encode
+    /// and re-hash to the exact same bytes.
+    pub fn check_artifacts(&self, blueprint_path: &Path) -> Result<(), Error> {
+        let blueprint = self.read_blueprint(blueprint_path)?;
+
+        let artifacts = blueprint::audit::verify(&blueprint)?;
+
+        self.event_listener.handle_event(Event::VerifiedArtifacts {
+            path: blueprint_path.to_path_buf(),
+            artifacts,
+        });
+
+        Ok(())
+    }
+
+    /// Report the size of each validator, compared to the previous build, and remember them
+    /// for the next one. The table is also written as markdown when a path is given.
+    fn summarize(
+        &self,
+        blueprint: &Blueprint,
+        markdown: Option<PathBuf>,
+        filtered: bool,
+    ) -> Result<(), Error> {
+        let path = self.root.join(paths::build_sizes());
+
+        let previous = self
+            .vfs
+            .read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Vec<ValidatorSize>>(&json).ok())
+            .unwrap_or_default();
+
+        let sizes = summary::sizes(blueprint).map_err(blueprint::Error::from)?;
+
+        let validators = summary::summarize(&sizes, &previous);
+
+        let sizes = summary::merge(sizes, &previous, filtered);
+
+        self.vfs
+            .write(
+                &path,
+                serde_json::to_string_pretty(&sizes).unwrap().as_bytes(),
+            )
+            .map_err(|error| Error::FileIo { error, path })?;
+
+        if let Some(path) = markdown {
+            self.vfs
+                .write(&path, summary::to_markdown(&validators).as_bytes())
+                .map_err(|error| Error::FileIo { error, path })?;
+        }
+
+        self.event_listener
+            .handle_event(Event::BuildSummary { validators });
+
+        Ok(())
+    }
+
+    /// Resolve the project's dependencies, and describe each of them: where it comes from, a
+    /// checksum of its modules and its declared license.
+    pub fn packages(&self) -> Result<Vec<PackageInfo>, Error> {
+        let manifest = deps::download(
+            &self.event_listener,
+            self.vfs.as_ref(),
+            &self.root,
+            &self.config,
+        )?;
+
+        Ok(manifest
+            .packages
+            .into_iter()
+            .map(|package| {
+                let root = package.root(&self.root);
+                let lib = root.join("lib");
+
+                let modules = self
+                    .vfs
+                    .files(&lib)
+                    .into_iter()
+                    .filter(|path| is_aiken_path(path, &lib))
+                    .filter_map(|path| {
+                        let code = self.vfs.read_to_string(&path).ok()?;
+                        Some((self.module_name(&lib, &path), code))
+                    })
+                    .collect::<Vec<_>>();
+
+                PackageInfo {
+                    checksum: attestation::digest(
+                        modules
+                            .iter()
+                            .map(|(name, code)| (name.as_str(), code.as_str())),
+                    ),
+                    license: self
+                        .vfs
+                        .read_to_string(&root.join(paths::project_config()))
+                        .ok()
+                        .and_then(|aiken_toml| deps::declared_license(&aiken_toml)),
+                    name: package.name,
+                    version: package.version,
+                    source: package.source,
+                    path: package.path,
+                }
+            })
+            .collect())
+    }
+
+    /// Record the provenance of a freshly written blueprint, next to it.
+    fn attest(
+        &self,
+        blueprint_path: &Path,
+        options: attestation::BuildOptions,
+        blueprint: &str,
+        trace_codes: Option<&str>,
+    ) -> Result<(), Error> {
+        let path = Attestation::path(blueprint_path);
+
+        self.event_listener
+            .handle_event(Event::GeneratingAttestation { path: path.clone() });
+
+        let project = self.config.name.to_string();
+
+        let versions = self
+            .vfs
+            .read_to_string(&self.root.join(paths::manifest()))
+            .ok()
+            .and_then(|manifest| toml::from_str::<Manifest>(&manifest).ok())
+            .map(|manifest| {
+                manifest
+                    .packages
+                    .into_iter()
+                    .map(|package| (package.name.to_string(), package.version))
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+
+        let dependencies = self
+            .checked_modules
+            .values()
+            .map(|module| module.package.clone())
+            .filter(|package| package != &project)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|package| {
+                let dependency = attestation::Dependency {
+                    version: versions.get(&package).cloned(),
+                    checksum: attestation::checksum(
+                        self.checked_modules
+                            .values()
+                            .filter(|module| module.package == package),
+                    ),
+                };
+
+                (package, dependency)
+            })
+            .collect();
+
+        let attestation = Attestation {
+            compiler: config::compiler_version(true),
+            sources: attestation::checksum(
+                self.checked_modules
+                    .values()
+                    .filter(|module| module.package == project),
+            ),
+            project,
+            version: self.config.version.clone(),
+            options,
+            dependencies,
+            artifacts: attestation::artifacts(blueprint, trace_codes)?,
+        };
+
+        self.vfs
+            .write(&path, attestation.to_json().as_bytes())
+            .map_err(|error| Error::FileIo { error, path })
+    }
+
+    /// Rebuild the project with the options recorded in an attestation, and check that the
+    /// outcome matches it. The rebuilt blueprint (and its own attestation) are written under
+    /// the build folder, leaving the attested ones untouched.
+    pub fn verify(&mut self, attestation_path: &Path) -> Result<(), Vec<Error>> {
+        let attestation = self.read_attestation(attestation_path)?;
+
+        let options = attestation.options.clone();
+
+        let tracing = options.tracing().ok_or_else(|| Error::InvalidAttestation {
+            path: attestation_path.to_path_buf(),
+            reason: format!(
+                "Unknown tracing options: '{}' traces at the '{}' level.",
+                options.trace_filter, options.trace_level
+            ),
+        })?;
+
+        let blueprint_path = self
+            .root
+            .join(paths::build())
+            .join("verify")
+            .join("plutus.json");
+
+        self.vfs.create_dir_all(
+            blueprint_path
+                .parent()
+                .expect("verification blueprint has a parent directory"),
+        )?;
+
+        self.config.prelude = options.prelude;
+
+        self.build(BuildOptions {
+            tracing,
+            blueprint_path: blueprint_path.clone(),
+            env: options.env,
+            filter: options.filter,
+            trace_codes: options.trace_codes,
+            optimization_passes: options.optimization_passes,
+            attest: true,
+            metadata: options.metadata,
+            ..BuildOptions::default()
+        })?;
+
+        let rebuilt = self.read_attestation(&Attestation::path(&blueprint_path))?;
+
+        let differences = attestation.differences(&rebuilt);
+
+        if !differences.is_empty() {
+            return Err(vec![Error::AttestationMismatch {
+                path: attestation_path.to_path_buf(),
+                differences,
+            }]);
+        }
+
+        self.event_listener
+            .handle_event(Event::VerifiedAttestation {
+                path: attestation_path.to_path_buf(),
+            });
+
+        Ok(())
+    }
+
+    fn read_attestation(&self, path: &Path) -> Result<Attestation, Error> {
+        let json = self
+            .vfs
+            .read_to_string(path)
+            .map_err(|error| Error::FileIo {
+                error,
+                path: path.to_path_buf(),
+            })?;
+
+        Attestation::parse(path, &json)
+    }
+
+    /// Write the Air stack of every selected validator to 'validators.air' in the artifacts
+    /// directory, in the format understood by 'aiken_lang::gen_uplc::dump::AirDump'.
+    pub fn dump_air(
+        &self,
+        generator: &mut CodeGenerator<'_>,
+        filter: Option<&[String]>,
+    ) -> Result<PathBuf, Error> {
+        let dir = self.root.join(&self.config.artifacts.directory);
+
+        let path = dir.join("validators.air");
+
+        self.event_listener
+            .handle_event(Event::DumpingAir { path: path.clone() });
+
+        self.vfs.create_dir_all(&dir)?;
+
+        let definitions = self
+            .checked_modules
+            .validators()
+            .filter_map(|(module, validator)| {
+                let name = format!("{}.{}", module.name, validator.name);
+
+                Blueprint::is_selected(filter, &name, true).then(|| {
+                    DumpedDefinition::new(name, &generator.generate_air(validator, &module.name))
+                })
+            })
+            .collect();
+
+        self.vfs
+            .write(&path, AirDump { definitions }.to_string().as_bytes())
+            .map_err(|error| Error::FileIo {
+                error,
+                path: path.clone(),
+            })?;
+
+        Ok(path)
+    }
+
+    /// Write the state of each validator after every run of the given optimization pass to
+    /// 'validators.<pass>.uplc', in the artifacts directory.
+    pub fn dump_after_pass(
+        &self,
+        generator: &mut CodeGenerator<'_>,
+        pass: Pass,
+        filter: Option<&[String]>,
+    ) -> Result<PathBuf, Error> {
+        let dir = self.root.join(&self.config.artifacts.directory);
+
+        let path = dir.join(format!("validators.{pass}.uplc"));
+
+        self.event_listener
+            .handle_event(Event::DumpingUPLC { path: path.clone() });
+
+        self.vfs.create_dir_all(&dir)?;
+
+        let mut dump = String::new();
+
+        for (module, validator) in self.checked_modules.validators() {
+            let name = format!("{}.{}", module.name, validator.name);
+
+            if !Blueprint::is_selected(filter, &name, true) {
+                continue;
+            }
+
+            for (run, program) in generator
+                .generate_after_pass(validator, &module.name, pass)
+                .iter()
+                .enumerate()
+            {
+                dump.push_str(&format!(
+                    "-- {name} (after {pass} #{})\n{}\n\n",
+                    run + 1,
+                    program.to_pretty()
+                ));
+            }
+        }
+
+        self.vfs
+            .write(&path, dump.as_bytes())
+            .map_err(|error| Error::FileIo {
+                error,
+                path: path.clone(),
+            })?;
+
+        Ok(path)
+    }
+
+    /// Bundle the given files, written by this build, into a reproducible tar archive with a
+    /// manifest of their digests. Files are named after their path relative to the project root,
+    /// or after their file name when they live outside of it.
+    fn archive(&self, written: &[PathBuf], path: PathBuf) -> Result<(), Error> {
+        self.event_listener
+            .handle_event(Event::GeneratingArtifactArchive { path: path.clone() });
+
+        let mut files = BTreeMap::new();
+
+        for file in written {
+            let name = match file.strip_prefix(&self.root) {
+                Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+                Err(_) => file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            };
+
+            let bytes = self.vfs.read(file).map_err(|error| Error::FileIo {
+                error,
+                path: file.clone(),
+            })?;
+
+            if name == archive::MANIFEST {
+                return Err(Error::FileIo {
+                    error: io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!("'{name}' is the name of the archive's own manifest"),
+                    ),
+                    path: file.clone(),
+                });
+            }
+
+            if files.insert(name.clone(), bytes).is_some() {
+                return Err(Error::FileIo {
+                    error: io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!("another archived file is also named '{name}'"),
+                    ),
+                    path: file.clone(),
+                });
+            }
+        }
+
+        let manifest = archive::Manifest::new(
+            self.config.name.to_string(),
+            self.config.version.clone(),
+            &files,
+        );
 
-    pub fn benchmark(
-        &mut self,
-        match_benchmarks: Option<Vec<String>>,
-        exact_match: bool,
-        seed: u32,
-        max_size: usize,
-        tracing: Tracing,
-        env: Option<String>,
-    ) -> Result<(), Vec<Error>> {
-        let options = Options {
-            tracing,
-            env,
-            code_gen_mode: CodeGenMode::Benchmark {
-                match_benchmarks,
-                exact_match,
-                seed,
-                max_size,
-            },
-            blueprint_path: self.blueprint_path(None),
-        };
+        let tar = archive::bundle(&manifest, &files).map_err(|error| Error::FileIo {
+            error,
+            path: path.clone(),
+        })?;
 
-        self.compile(options)
+        self.vfs
+            .write(&path, &tar)
+            .map_err(|error| Error::FileIo { error, path })
     }
 
-    pub fn dump_uplc(&self, blueprint: &Blueprint) -> Result<(), Error> {
-        let dir = self.root.join("artifacts");
+    /// Write an HTML report of each validator, laying out its source and the source of every
+    /// function it uses next to the UPLC they compiled to, in the artifacts directory. The
+    /// report covers the validators generated with source maps enabled.
+    pub fn annotate(&self, generator: &CodeGenerator<'_>) -> Result<PathBuf, Error> {
+        let dir = self.root.join(&self.config.artifacts.directory);
+
+        let path = dir.join("validators.annotated.html");
 
         self.event_listener
-            .handle_event(Event::DumpingUPLC { path: dir.clone() });
+            .handle_event(Event::AnnotatingValidators { path: path.clone() });
 
-        fs::create_dir_all(&dir)?;
+        self.vfs.create_dir_all(&dir)?;
 
-        for validator in &blueprint.validators {
-            let path = dir.clone().join(format!("{}.uplc", validator.title));
+        let sources = self
+            .checked_modules
+            .values()
+            .map(|module| (module.name.as_str(), module.code.as_str()))
+            .collect::<Vec<_>>();
 
-            let program = &validator.program;
-            let program: Program<Name> = program.inner().try_into().unwrap();
+        let validators = generator
+            .source_maps()
+            .into_iter()
+            .flatten()
+            .map(|(title, source_map)| annotate::Annotated {
+                title: title.clone(),
+                source_map,
+                sources: sources.clone(),
+            })
+            .collect::<Vec<_>>();
 
-            fs::write(&path, program.to_pretty()).map_err(|error| Error::FileIo { error, path })?;
-        }
+        self.vfs
+            .write(&path, annotate::to_html(&validators).as_bytes())
+            .map_err(|error| Error::FileIo {
+                error,
+                path: path.clone(),
+            })?;
 
-        Ok(())
+        Ok(path)
     }
 
     fn config_definitions(&mut self, env: Option<&str>) -> Option<Vec<UntypedDefinition>> {
@@ -383,10 +1252,38 @@ where
 
         let mut modules = self.parse_sources(self.config.name.clone())?;
 
-        self.type_check(&mut modules, options.tracing, env, true)?;
+        self.load_fixtures(&self.root, &mut modules)?;
+
+        self.affected_modules = self.retain_affected_modules(&mut modules);
+
+        let trace_severity = match options.code_gen_mode {
+            CodeGenMode::Build { .. } => self.config.trace.build,
+            CodeGenMode::Test { .. } | CodeGenMode::Benchmark { .. } | CodeGenMode::NoOp => {
+                self.config.trace.check
+            }
+        };
+
+        self.type_check(&mut modules, options.tracing, trace_severity, env, true)?;
 
         match options.code_gen_mode {
-            CodeGenMode::Build(uplc_dump) => {
+            CodeGenMode::Build {
+                uplc_dump,
+                air_dump,
+                check_artifacts,
+                filter,
+                trace_codes,
+                optimization_passes,
+                emit_after,
+                attest,
+                metadata,
+                summary,
+                annotate,
+                archive,
+            } => {
+                self.check_plutus_version()?;
+
+                self.check_validator_collisions()?;
+
                 self.event_listener
                     .handle_event(Event::GeneratingBlueprint {
                         path: options.blueprint_path.clone(),
@@ -396,28 +1293,175 @@ where
                     m.attach_doc_and_module_comments();
                 });
 
-                let mut generator = self.new_generator(options.tracing);
+                let mut generator = self.new_pruned_generator(
+                    options.tracing,
+                    self.checked_modules
+                        .validators()
+                        .filter(|(module, validator)| {
+                            Blueprint::is_selected(
+                                filter.as_deref(),
+                                &format!("{}.{}", module.name, validator.name),
+                                true,
+                            )
+                        })
+                        .flat_map(|(_, validator)| {
+                            validator
+                                .handlers
+                                .iter()
+                                .chain(std::iter::once(&validator.fallback))
+                                .map(|handler| &handler.body)
+                        }),
+                );
+
+                if trace_codes {
+                    generator.enable_trace_codes();
+                }
+
+                if annotate {
+                    generator.enable_source_maps();
+                }
+
+                let optimization_passes =
+                    optimization_passes.or_else(|| self.config.optimization_passes.clone());
+
+                if let Some(passes) = optimization_passes.clone() {
+                    generator.set_optimization_passes(passes);
+                }
+
+                let mut blueprint = Blueprint::new(
+                    &self.config,
+                    &self.checked_modules,
+                    &mut generator,
+                    filter.as_deref(),
+                )
+                .map_err(Error::Blueprint)?;
+
+                // An empty label stands for the project's name and version.
+                let metadata = metadata.map(|label| {
+                    if label.is_empty() {
+                        format!("{}@{}", self.config.name, self.config.version)
+                    } else {
+                        label
+                    }
+                });
+
+                if let Some(marker) = &metadata {
+                    for validator in blueprint.validators.iter_mut() {
+                        validator.program = validator
+                            .program
+                            .clone()
+                            .map(|program| program.with_marker(marker));
+                    }
+                }
+
+                let trace_codes = generator.trace_codes().map(|codes| {
+                    codes
+                        .iter()
+                        .map(|(msg, code)| {
+                            (code.to_string(), serde_json::Value::from(msg.as_str()))
+                        })
+                        .collect::<serde_json::Map<_, _>>()
+                });
+
+                // Every file written by this build, as opposed to leftovers from previous ones.
+                let mut written = Vec::new();
+
+                if air_dump {
+                    written.push(self.dump_air(&mut generator, filter.as_deref())?);
+                }
+
+                if let Some(pass) = emit_after {
+                    written.push(self.dump_after_pass(&mut generator, pass, filter.as_deref())?);
+                }
 
-                let blueprint = Blueprint::new(&self.config, &self.checked_modules, &mut generator)
-                    .map_err(Error::Blueprint)?;
+                if annotate {
+                    written.push(self.annotate(&generator)?);
+                }
 
                 if blueprint.validators.is_empty() {
                     self.warnings.push(Warning::NoValidators);
                 }
 
                 if uplc_dump {
-                    self.dump_uplc(&blueprint)?;
+                    written.extend(self.dump_uplc(&blueprint, filter.is_some())?);
                 }
 
-                let json = serde_json::to_string_pretty(&blueprint).unwrap();
+                let trace_codes = trace_codes
+                    .map(|trace_codes| serde_json::to_string_pretty(&trace_codes).unwrap());
+
+                if let Some(json) = &trace_codes {
+                    let path = options.blueprint_path.with_extension("traces.json");
+                    self.vfs
+                        .write(&path, json.as_bytes())
+                        .map_err(|error| Error::FileIo {
+                            error,
+                            path: path.clone(),
+                        })?;
+                    written.push(path);
+                }
+
+                // A filtered build only refreshes the validators it selected, leaving the rest
+                // of an existing blueprint untouched.
+                if let Some(filter) = filter.as_deref() {
+                    if let Some(previous) = self
+                        .vfs
+                        .read_to_string(&options.blueprint_path)
+                        .ok()
+                        .and_then(|json| serde_json::from_str::<Blueprint>(&json).ok())
+                    {
+                        blueprint = blueprint.merge_into(previous, filter);
+                    }
+                }
 
-                fs::write(options.blueprint_path.as_path(), json).map_err(|error| {
-                    Error::FileIo {
+                let json = serde_json::to_string_pretty(&blueprint).map_err(Error::Json)?;
+
+                self.vfs
+                    .write(options.blueprint_path.as_path(), json.as_bytes())
+                    .map_err(|error| Error::FileIo {
                         error,
-                        path: options.blueprint_path,
+                        path: options.blueprint_path.clone(),
+                    })?;
+
+                if check_artifacts {
+                    self.check_artifacts(&options.blueprint_path)?;
+                }
+
+                if let Some(path) = &summary {
+                    written.push(path.clone());
+                }
+
+                self.summarize(&blueprint, summary, filter.is_some())?;
+
+                if attest {
+                    let build_options = attestation::BuildOptions::new(
+                        options.tracing,
+                        options.env.clone(),
+                        filter,
+                        trace_codes.is_some(),
+                        optimization_passes,
+                        self.config.prelude.clone(),
+                        metadata,
+                    );
+
+                    self.attest(
+                        &options.blueprint_path,
+                        build_options,
+                        &json,
+                        trace_codes.as_deref(),
+                    )?;
+                }
+
+                if let Some(path) = archive {
+                    written.push(options.blueprint_path.clone());
+
+                    if attest {
+                        written.push(Attestation::path(&options.blueprint_path));
                     }
-                    .into()
-                })
+
+                    self.archive(&written, path)?;
+                }
+
+                Ok(())
             }
             CodeGenMode::Test {
                 match_tests,
@@ -425,7 +1469,13 @@ where
                 exact_match,
                 seed,
                 property_max_success,
+                timeout,
+                cost_model,
+                costs_guard,
+                report,
             } => {
+                let full_run = match_tests.is_none();
+
                 let tests =
                     self.collect_tests(verbose, match_tests, exact_match, options.tracing)?;
 
@@ -433,7 +1483,12 @@ where
                     self.event_listener.handle_event(Event::RunningTests);
                 }
 
-                let tests = self.run_runnables(tests, seed, property_max_success);
+                let settings = EvalSettings::new(self.config.plutus)
+                    .with_cost_model(cost_model)
+                    .with_timeout(timeout)
+                    .with_pooled_allocation(true);
+
+                let tests = self.run_runnables(tests, seed, property_max_success, &settings);
 
                 self.checks_count = if tests.is_empty() {
                     None
@@ -446,7 +1501,7 @@ where
                     }))
                 };
 
-                let errors: Vec<Error> = tests
+                let mut errors: Vec<Error> = tests
                     .iter()
                     .filter_map(|e| {
                         if e.is_success() {
@@ -457,8 +1512,34 @@ where
                     })
                     .collect();
 
-                self.event_listener
-                    .handle_event(Event::FinishedTests { seed, tests });
+                if let Some(costs_guard) = costs_guard {
+                    let costs = CostsLock::from_results(&tests);
+
+                    match costs_guard {
+                        CostsGuard::Record(path) => costs.save(self.vfs.as_ref(), &path)?,
+                        CostsGuard::Verify { path, tolerance } => errors.extend(
+                            CostsLock::load(self.vfs.as_ref(), &path)?
+                                .regressions(&costs, tolerance),
+                        ),
+                    }
+                }
+
+                // Labels only show up in traces when user-defined traces are kept verbatim;
+                // otherwise every failure would look uncovered.
+                let labels_traced = options.tracing.trace_level(false) == TraceLevel::Verbose;
+
+                let uncovered_failures = if full_run && labels_traced && !tests.is_empty() {
+                    self.uncovered_failures(&tests)
+                } else {
+                    vec![]
+                };
+
+                self.event_listener.handle_event(Event::FinishedTests {
+                    seed,
+                    tests,
+                    report,
+                    uncovered_failures,
+                });
 
                 if !errors.is_empty() {
                     Err(errors)
@@ -471,6 +1552,7 @@ where
                 exact_match,
                 seed,
                 max_size,
+                cost_model,
             } => {
                 let verbose = false;
 
@@ -485,7 +1567,9 @@ where
                     self.event_listener.handle_event(Event::RunningBenchmarks);
                 }
 
-                let benchmarks = self.run_runnables(benchmarks, seed, max_size);
+                let settings = EvalSettings::new(self.config.plutus).with_cost_model(cost_model);
+
+                let benchmarks = self.run_runnables(benchmarks, seed, max_size, &settings);
 
                 let errors: Vec<Error> = benchmarks
                     .iter()
@@ -538,9 +1622,7 @@ where
         };
 
         // Read blueprint
-        let blueprint = File::open(blueprint_path)
-            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
-        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+        let blueprint: Blueprint = serde_json::from_str(&self.read_blueprint(blueprint_path)?)?;
 
         // Calculate the address
         let when_too_many =
@@ -581,9 +1663,7 @@ where
         blueprint_path: &Path,
     ) -> Result<PolicyId, Error> {
         // Read blueprint
-        let blueprint = File::open(blueprint_path)
-            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
-        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+        let blueprint: Blueprint = serde_json::from_str(&self.read_blueprint(blueprint_path)?)?;
 
         // Error handlers for ambiguous / missing validators
         let when_too_many =
@@ -654,9 +1734,7 @@ where
         ) -> Result<PlutusData, blueprint::error::Error>,
     {
         // Read blueprint
-        let blueprint = File::open(blueprint_path)
-            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
-        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+        let blueprint: Blueprint = serde_json::from_str(&self.read_blueprint(blueprint_path)?)?;
 
         // Construct parameter
         let when_too_many =
@@ -686,9 +1764,7 @@ where
         param: &PlutusData,
     ) -> Result<Blueprint, Error> {
         // Read blueprint
-        let blueprint = File::open(blueprint_path)
-            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
-        let mut blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+        let mut blueprint: Blueprint = serde_json::from_str(&self.read_blueprint(blueprint_path)?)?;
 
         // Apply parameters
         let when_too_many =
@@ -727,10 +1803,19 @@ where
     }
 
     fn with_dependencies(&mut self, parsed_packages: &mut ParsedModules) -> Result<(), Vec<Error>> {
-        let manifest = deps::download(&self.event_listener, &self.root, &self.config)?;
+        let manifest = deps::download(
+            &self.event_listener,
+            self.vfs.as_ref(),
+            &self.root,
+            &self.config,
+        )?;
+
+        let conflicts =
+            deps::check_conflicts(self.vfs.as_ref(), &self.root, &self.config, &manifest)?;
+        self.warnings.extend(conflicts);
 
         for package in manifest.packages {
-            let lib = self.root.join(paths::build_deps_package(&package.name));
+            let lib = package.root(&self.root);
 
             self.event_listener
                 .handle_event(Event::StartingCompilation {
@@ -743,15 +1828,22 @@ where
 
             let mut parsed_modules = self.parse_sources(package.name)?;
 
+            self.load_fixtures(&lib, &mut parsed_modules)?;
+
+            let package_config = Config::load_with(self.vfs.as_ref(), &lib).map_err(|e| vec![e])?;
+
+            parsed_modules.values_mut().for_each(|parsed_module| {
+                parsed_module.internal = package_config.is_internal_module(&parsed_module.name)
+            });
+
             use rayon::prelude::*;
 
             parsed_modules
                 .par_iter_mut()
                 .for_each(|(_module, parsed_module)| {
-                    parsed_module
-                        .ast
-                        .definitions
-                        .retain(|def| !matches!(def, Definition::Test { .. }))
+                    parsed_module.ast.definitions.retain(|def| {
+                        !matches!(def, Definition::Test { .. } | Definition::Benchmark { .. })
+                    })
                 });
 
             parsed_packages.extend(Into::<HashMap<_, _>>::into(parsed_modules));
@@ -786,12 +1878,141 @@ where
         Ok(())
     }
 
+    /// Narrow down parsed modules to those affected by the changed files, if any were given.
+    /// Changes that can't be traced back to specific modules (e.g. to the project's manifest,
+    /// or deleted modules) conservatively keep everything.
+    fn retain_affected_modules(&self, modules: &mut ParsedModules) -> Option<BTreeSet<String>> {
+        let changed = self.changed_files.as_ref()?;
+
+        let is_source = |path: &Path| {
+            path.extension().is_some_and(|ext| ext == "ak")
+                && ["lib", "validators", "env"]
+                    .iter()
+                    .any(|dir| path.starts_with(self.root.join(dir)))
+        };
+
+        let manifests = [
+            self.root.join(paths::project_config()),
+            self.root.join(paths::manifest()),
+        ];
+
+        let changed_modules = modules
+            .values()
+            .filter(|module| changed.contains(&module.path))
+            .map(|module| module.name.clone())
+            .collect::<BTreeSet<_>>();
+
+        let unknown_sources =
+            changed.iter().filter(|path| is_source(path)).count() > changed_modules.len();
+
+        if unknown_sources || changed.iter().any(|path| manifests.contains(path)) {
+            return None;
+        }
+
+        Some(modules.retain_affected_by(&changed_modules))
+    }
+
     fn read_package_source_files(&mut self, lib: &Path) -> Result<(), Error> {
         self.aiken_files(lib, ModuleKind::Lib)?;
 
         Ok(())
     }
 
+    /// Fill in constants declared with `@fixture("file")` from the 'fixtures' folder of the
+    /// package they belong to. Hex-encoded '.cbor' files are decoded; any other file is
+    /// embedded as-is.
+    fn load_fixtures(&self, root: &Path, modules: &mut ParsedModules) -> Result<(), Vec<Error>> {
+        let fixtures = root.join(paths::fixtures());
+
+        let mut errors = Vec::new();
+
+        for module in modules.values_mut() {
+            for definition in module.ast.definitions.iter_mut() {
+                let Definition::ModuleConstant(ModuleConstant {
+                    fixture: Some(file),
+                    value,
+                    location,
+                    ..
+                }) = definition
+                else {
+                    continue;
+                };
+
+                // Fixtures are confined to the package's own folder: dependencies mustn't get
+                // to embed arbitrary files from the machine building them.
+                let escapes = Path::new(file.as_str()).components().any(|component| {
+                    matches!(
+                        component,
+                        Component::ParentDir | Component::RootDir | Component::Prefix(_)
+                    )
+                });
+
+                if escapes {
+                    errors.push(Error::FixtureOutsidePackage {
+                        path: module.path.clone(),
+                        src: module.code.clone(),
+                        named: NamedSource::new(
+                            module.path.display().to_string(),
+                            module.code.clone(),
+                        )
+                        .into(),
+                        location: *location,
+                        file: file.clone(),
+                    });
+                    continue;
+                }
+
+                let path = fixtures.join(file.as_str());
+
+                // Files are embedded as they are, unless they hold hex-encoded bytes as told by
+                // their extension (e.g. 'tx.cbor.hex').
+                let content = self.vfs.read(&path).and_then(|content| {
+                    if path.extension().is_some_and(|ext| ext == "hex") {
+                        std::str::from_utf8(&content)
+                            .ok()
+                            .and_then(|text| hex::decode(text.trim()).ok())
+                            .ok_or_else(|| {
+                                io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    "its content isn't a valid hex-encoded string",
+                                )
+                            })
+                    } else {
+                        Ok(content)
+                    }
+                });
+
+                match content {
+                    Ok(bytes) => {
+                        *value = UntypedExpr::ByteArray {
+                            location: value.location(),
+                            bytes,
+                            preferred_format: ByteArrayFormatPreference::HexadecimalString,
+                        };
+                    }
+                    Err(error) => errors.push(Error::MissingFixture {
+                        path: module.path.clone(),
+                        src: module.code.clone(),
+                        named: NamedSource::new(
+                            module.path.display().to_string(),
+                            module.code.clone(),
+                        )
+                        .into(),
+                        location: *location,
+                        file: file.clone(),
+                        error: error.to_string(),
+                    }),
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn parse_sources(&mut self, package_name: PackageName) -> Result<ParsedModules, Vec<Error>> {
         use rayon::prelude::*;
 
@@ -821,6 +2042,7 @@ where
                                 path,
                                 extra,
                                 package: package_name.to_string(),
+                                internal: false,
                             };
 
                             let path = module.path.clone();
@@ -919,23 +2141,224 @@ where
         }
     }
 
+    /// Validators are wrapped in a handler decoding the script context as laid out by the
+    /// ledger for Plutus V3; such scripts can't be compiled for any earlier Plutus version.
+    /// Since 'aiken.toml' only accepts Plutus V3, this guards configurations built otherwise
+    /// (e.g. programmatically).
+    fn check_plutus_version(&self) -> Result<(), Vec<Error>> {
+        if self.config.plutus == PlutusVersion::V3 {
+            return Ok(());
+        }
+
+        let errors = self
+            .checked_modules
+            .validators()
+            .map(|(module, validator)| Error::UnsupportedPlutusVersion {
+                path: module.input_path.clone(),
+                src: module.code.clone(),
+                named: NamedSource::new(
+                    module.input_path.display().to_string(),
+                    module.code.clone(),
+                )
+                .into(),
+                location: validator.location,
+                validator: validator.name.clone(),
+                plutus: self.config.plutus,
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Ensure no two validator handlers end up under the same title (and thus, the same
+    /// blueprint entry and artifacts). Titles are compared case-insensitively since they're used
+    /// as file names, and modules are compared by canonical path so that a module reachable
+    /// through a symbolic link doesn't silently compile twice.
+    fn check_validator_collisions(&self) -> Result<(), Vec<Error>> {
+        let mut seen_titles: HashMap<String, (&CheckedModule, Span)> = HashMap::new();
+        let mut seen_sources: HashMap<(PathBuf, &str), (&CheckedModule, Span)> = HashMap::new();
+
+        let mut errors = vec![];
+
+        let mut collision = |title: String,
+                             module: &CheckedModule,
+                             location: Span,
+                             first: (&CheckedModule, Span)| {
+            errors.push(Error::ValidatorNameCollision {
+                title,
+                path: module.input_path.clone(),
+                src: module.code.clone(),
+                named: NamedSource::new(
+                    module.input_path.display().to_string(),
+                    module.code.clone(),
+                )
+                .into(),
+                location,
+                first_path: first.0.input_path.clone(),
+                first_src: first.0.code.clone(),
+                first_location: first.1,
+            })
+        };
+
+        for (module, validator) in self.checked_modules.validators() {
+            let source = self
+                .vfs
+                .canonicalize(&module.input_path)
+                .unwrap_or_else(|_| module.input_path.clone());
+
+            if let Some(first) = seen_sources.get(&(source.clone(), validator.name.as_str())) {
+                collision(
+                    format!("{}.{}", module.name, validator.name),
+                    module,
+                    validator.location,
+                    *first,
+                );
+                continue;
+            }
+
+            seen_sources.insert(
+                (source, validator.name.as_str()),
+                (module, validator.location),
+            );
+
+            for handler in validator
+                .handlers
+                .iter()
+                .chain(std::iter::once(&validator.fallback))
+            {
+                let title = TypedValidator::handler_name(
+                    &format!("{}.{}", module.name, validator.name),
+                    &handler.name,
+                );
+
+                match seen_titles.get(&title.to_lowercase()) {
+                    Some(first) => collision(title, module, handler.location, *first),
+                    None => {
+                        seen_titles.insert(title.to_lowercase(), (module, handler.location));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn type_check(
         &mut self,
         modules: &mut ParsedModules,
         tracing: Tracing,
+        trace_severity: TraceSeverity,
         env: Option<&str>,
         validate_module_name: bool,
     ) -> Result<(), Vec<Error>> {
         let our_modules: BTreeSet<String> = modules.keys().cloned().collect();
 
+        let implicit_imports = self.config.implicit_imports();
+
+        if !implicit_imports.is_empty() {
+            modules.add_implicit_imports(&implicit_imports);
+        }
+
+        if !self.implicit_prelude {
+            modules
+                .values_mut()
+                .for_each(|module| module.ast.implicit_prelude = false);
+        }
+
         self.with_dependencies(modules)?;
 
+        // Environments are listed from the 'env' folder, since not every module necessarily
+        // made it here (e.g. when only checking changes).
+        let env_dir = self.root.join("env");
+        let env_modules = self
+            .vfs
+            .files(&env_dir)
+            .into_iter()
+            .filter(|path| is_aiken_path(path, &env_dir))
+            .map(|path| self.module_name(&env_dir, &path))
+            .collect::<Vec<_>>();
+
+        // Our tests are type-checked in every mode, along with the test-only definitions they
+        // rely on; they only stay out of the generated code. Dependencies' tests are left out
+        // from the start, and so are their test-only definitions.
+        for module in modules.values_mut() {
+            if our_modules.contains(&module.name) {
+                self.warnings.extend(
+                    module
+                        .ast
+                        .unknown_environments(&env_modules)
+                        .into_iter()
+                        .map(|warning| {
+                            Warning::from_type_warning(
+                                warning,
+                                module.path.clone(),
+                                module.code.clone(),
+                            )
+                        }),
+                );
+
+                module.ast.retain_configured(env, true);
+            } else {
+                module.ast.retain_configured(env, false);
+            }
+        }
+
+        modules.check_internal_imports()?;
+
+        let mut cache_keys = HashMap::new();
+
         for name in modules.sequence(&our_modules)? {
             if let Some(module) = modules.remove(&name) {
+                // Dependencies are looked up in (and added to) the shared build cache, unless it
+                // is disabled; our own modules are always type-checked from scratch.
+                let cache_key = (self.build_cache && !our_modules.contains(&name))
+                    .then(|| build_cache::key(&module, &cache_keys, tracing, trace_severity, env));
+
+                if let Some(key) = cache_key {
+                    cache_keys.insert(name.clone(), key.clone());
+
+                    if let Some(checked_module) =
+                        build_cache::load(self.vfs.as_ref(), &key, &module, &self.id_gen)
+                    {
+                        self.module_sources.insert(
+                            checked_module.name.clone(),
+                            (
+                                checked_module.code.clone(),
+                                LineNumbers::new(&checked_module.code),
+                            ),
+                        );
+
+                        self.module_types.insert(
+                            checked_module.name.clone(),
+                            checked_module.ast.type_info.clone(),
+                        );
+
+                        checked_module.ast.register_definitions(
+                            &mut self.functions,
+                            &mut self.constants,
+                            &mut self.data_types,
+                        );
+
+                        self.checked_modules
+                            .insert(checked_module.name.clone(), checked_module);
+
+                        continue;
+                    }
+                }
+
                 let (checked_module, warnings) = module.infer(
                     &self.id_gen,
                     &self.config.name.to_string(),
                     tracing,
+                    trace_severity,
                     env,
                     validate_module_name,
                     &mut self.module_sources,
@@ -949,6 +2372,24 @@ where
                     && checked_module.name.as_str() != ast::CONFIG_MODULE
                 {
                     self.warnings.extend(warnings);
+
+                    self.warnings.extend(
+                        checked_module
+                            .ast
+                            .large_tuples(self.config.max_tuple_arity)
+                            .into_iter()
+                            .map(|warning| {
+                                Warning::from_type_warning(
+                                    warning,
+                                    checked_module.input_path.clone(),
+                                    checked_module.code.clone(),
+                                )
+                            }),
+                    );
+                }
+
+                if let Some(key) = cache_keys.get(&checked_module.name) {
+                    build_cache::store(self.vfs.as_ref(), key, &checked_module, &self.id_gen);
                 }
 
                 self.checked_modules
@@ -959,6 +2400,62 @@ where
         Ok(())
     }
 
+    /// Labels of 'fail' and 'todo' expressions in this package's validators (and in the
+    /// functions of this package they may call) which didn't show up in the traces of any test;
+    /// an approximation of the failure branches actually exercised by the suite. Only meaningful
+    /// when user-defined traces are verbose.
+    fn uncovered_failures(
+        &self,
+        tests: &[TestResult<UntypedExpr, UntypedExpr>],
+    ) -> Vec<(String, Vec<String>)> {
+        let package = self.config.name.to_string();
+
+        let traces = tests
+            .iter()
+            .flat_map(|test| test.traces())
+            .collect::<Vec<_>>();
+
+        self.checked_modules
+            .validators()
+            .filter(|(module, _)| module.package == package)
+            .filter_map(|(module, validator)| {
+                let handlers = validator
+                    .handlers
+                    .iter()
+                    .chain(std::iter::once(&validator.fallback))
+                    .map(|handler| &handler.body)
+                    .collect::<Vec<_>>();
+
+                let helpers =
+                    reachable_functions(handlers.iter().copied(), &self.functions, &self.constants)
+                        .into_iter()
+                        .filter(|key| {
+                            self.checked_modules
+                                .get(&key.module_name)
+                                .is_some_and(|module| module.package == package)
+                        })
+                        .filter_map(|key| self.functions.get(&key))
+                        .map(|function| &function.body)
+                        .collect::<Vec<_>>();
+
+                let uncovered = handlers
+                    .into_iter()
+                    .chain(helpers)
+                    .flat_map(|body| body.failure_labels())
+                    .unique()
+                    .filter(|label| !traces.iter().any(|trace| trace.as_str() == *label))
+                    .map(|label| label.to_string())
+                    .collect::<Vec<_>>();
+
+                if uncovered.is_empty() {
+                    None
+                } else {
+                    Some((format!("{}.{}", module.name, validator.name), uncovered))
+                }
+            })
+            .collect()
+    }
+
     fn collect_test_items(
         &mut self,
         kind: RunnableKind,
@@ -1028,6 +2525,14 @@ where
                 continue;
             }
 
+            if self
+                .affected_modules
+                .as_ref()
+                .is_some_and(|affected| !affected.contains(&checked_module.name))
+            {
+                continue;
+            }
+
             for def in checked_module.ast.definitions() {
                 let func = match (kind, def) {
                     (RunnableKind::Test, Definition::Test(func)) => Some(func),
@@ -1073,7 +2578,15 @@ where
             }
         }
 
-        let mut generator = self.new_generator(tracing);
+        let mut generator = self.new_pruned_generator(
+            tracing,
+            scripts.iter().flat_map(|&(_, _, test)| {
+                test.arguments
+                    .iter()
+                    .map(|arg| &arg.via)
+                    .chain(std::iter::once(&test.body))
+            }),
+        );
 
         let mut tests = Vec::new();
 
@@ -1155,52 +2668,74 @@ where
         tests: Vec<Test>,
         seed: u32,
         max_success: usize,
+        settings: &EvalSettings,
     ) -> Vec<TestResult<UntypedExpr, UntypedExpr>> {
         use rayon::prelude::*;
 
         let data_types = utils::indexmap::as_ref_values(&self.data_types);
 
-        let plutus_version = &self.config.plutus;
-
         tests
             .into_par_iter()
-            .map(|test| test.run(seed, max_success, plutus_version))
+            .map(|test| test.run(seed, max_success, settings))
             .collect::<Vec<TestResult<(Constant, Rc<Type>), PlutusData>>>()
             .into_iter()
             .map(|test| test.reify(&data_types))
             .collect()
     }
 
+    /// Like 'run_runnables', but hand each result over as soon as it's available rather than
+    /// once they're all done. Results come in the order runnables complete.
+    fn stream_runnables(
+        &self,
+        tests: Vec<Test>,
+        seed: u32,
+        max_success: usize,
+        settings: &EvalSettings,
+        mut on_result: impl FnMut(TestResult<UntypedExpr, UntypedExpr>),
+    ) {
+        use rayon::prelude::*;
+
+        let data_types = utils::indexmap::as_ref_values(&self.data_types);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                tests.into_par_iter().for_each_with(sender, |sender, test| {
+                    let _ = sender.send(test.run(seed, max_success, settings));
+                })
+            });
+
+            for result in receiver {
+                on_result(result.reify(&data_types));
+            }
+        });
+    }
+
     fn aiken_files(&mut self, dir: &Path, kind: ModuleKind) -> Result<(), Error> {
         let mut has_default = None;
 
-        walkdir::WalkDir::new(dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(Result::ok)
-            .filter(|e| e.file_type().is_file())
-            .try_for_each(|d| {
-                if has_default.is_none() {
-                    has_default = Some(false);
-                }
+        self.vfs.files(dir).into_iter().try_for_each(|path| {
+            if has_default.is_none() {
+                has_default = Some(false);
+            }
 
-                let path = d.into_path();
-                let keep = is_aiken_path(&path, dir);
+            let keep = is_aiken_path(&path, dir);
 
-                if !keep {
-                    self.warnings
-                        .push(Warning::InvalidModuleName { path: path.clone() });
-                }
+            if !keep {
+                self.warnings
+                    .push(Warning::InvalidModuleName { path: path.clone() });
+            }
 
-                if keep {
-                    if self.module_name(dir, &path).as_str() == ast::DEFAULT_ENV_MODULE {
-                        has_default = Some(true);
-                    }
-                    self.add_module(AddModuleBy::Path(path), dir, kind)
-                } else {
-                    Ok(())
+            if keep {
+                if self.module_name(dir, &path).as_str() == ast::DEFAULT_ENV_MODULE {
+                    has_default = Some(true);
                 }
-            })?;
+                self.add_module(AddModuleBy::Path(path), dir, kind)
+            } else {
+                Ok(())
+            }
+        })?;
 
         if kind == ModuleKind::Env && has_default == Some(false) {
             return Err(Error::NoDefaultEnvironment);
@@ -1218,10 +2753,13 @@ where
         let (name, code, path) = match add_by {
             AddModuleBy::Path(path) => {
                 let name = self.module_name(dir, &path);
-                let code = fs::read_to_string(&path).map_err(|error| Error::FileIo {
-                    path: path.clone(),
-                    error,
-                })?;
+                let code = self
+                    .vfs
+                    .read_to_string(&path)
+                    .map_err(|error| Error::FileIo {
+                        path: path.clone(),
+                        error,
+                    })?;
                 (name, code, path)
             }
             AddModuleBy::Source { name, code } => (name, code, dir.to_path_buf()),
@@ -1249,14 +2787,8 @@ where
         // module
         module_path.set_extension("");
 
-        // Stringify
-        let name = module_path
-            .to_str()
-            .expect("Module name path to str")
-            .to_string();
-
-        // normalise windows paths
-        name.replace('\\', "/").replace('-', "_")
+        // Stringify, normalising windows paths
+        vfs::to_slash(&module_path).replace('-', "_")
     }
 }
 
@@ -2,8 +2,8 @@ use crate::{Error, Warning};
 use aiken_lang::{
     ast::{
         DataType, DataTypeKey, Definition, Function, FunctionAccessKey, Located, ModuleKind,
-        Tracing, TypedDataType, TypedFunction, TypedModule, TypedValidator, UntypedModule,
-        Validator,
+        TraceSeverity, Tracing, TypedDataType, TypedFunction, TypedModule, TypedValidator,
+        UntypedModule, UntypedUse, Use, Validator,
     },
     expr::TypedExpr,
     line_numbers::LineNumbers,
@@ -12,10 +12,11 @@ use aiken_lang::{
     IdGenerator,
 };
 use indexmap::IndexMap;
+use itertools::Itertools;
 use miette::NamedSource;
 use petgraph::{algo, graph::NodeIndex, Direction, Graph};
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     io,
     ops::{Deref, DerefMut},
     path::PathBuf,
@@ -28,6 +29,8 @@ pub struct ParsedModule {
     pub code: String,
     pub kind: ModuleKind,
     pub package: String,
+    /// Whether the module can only be imported from within its own package.
+    pub internal: bool,
     pub ast: UntypedModule,
     pub extra: ModuleExtra,
 }
@@ -45,6 +48,7 @@ impl ParsedModule {
         id_gen: &IdGenerator,
         package: &str,
         tracing: Tracing,
+        trace_severity: TraceSeverity,
         env: Option<&str>,
         validate_module_name: bool,
         module_sources: &mut HashMap<String, (String, LineNumbers)>,
@@ -63,6 +67,7 @@ impl ParsedModule {
                 package,
                 module_types,
                 tracing,
+                trace_severity,
                 &mut warnings,
                 env,
             )
@@ -118,15 +123,154 @@ impl ParsedModules {
         Self(HashMap::new())
     }
 
-    pub fn sequence(&self, our_modules: &BTreeSet<String>) -> Result<Vec<String>, Error> {
-        let env_modules = self
+    /// Ensure no module imports an internal module of another package.
+    pub fn check_internal_imports(&self) -> Result<(), Error> {
+        for module in self.0.values() {
+            for def in module.ast.definitions() {
+                let Definition::Use(Use {
+                    module: path,
+                    location,
+                    ..
+                }) = def
+                else {
+                    continue;
+                };
+
+                let name = path.join("/");
+
+                let Some(imported) = self.0.get(&name) else {
+                    continue;
+                };
+
+                if !imported.internal || imported.package == module.package {
+                    continue;
+                }
+
+                // Public modules of the same package re-exporting from the internal one.
+                let alternatives = self
+                    .0
+                    .values()
+                    .filter(|candidate| {
+                        candidate.package == imported.package
+                            && !candidate.internal
+                            && candidate.ast.definitions().any(|def| {
+                                matches!(
+                                    def,
+                                    Definition::Use(Use { public: true, module, .. })
+                                        if module == path
+                                )
+                            })
+                    })
+                    .map(|candidate| candidate.name.clone())
+                    .sorted()
+                    .collect();
+
+                return Err(Error::InternalModuleImported {
+                    path: module.path.clone(),
+                    src: module.code.clone(),
+                    named: NamedSource::new(module.path.display().to_string(), module.code.clone())
+                        .into(),
+                    location: *location,
+                    module: name,
+                    package: imported.package.clone(),
+                    alternatives,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prepend implicit imports (e.g. from the project's prelude) to every module but
+    /// configuration ones. Modules which a prelude module (transitively) imports, prelude
+    /// modules included, get none: importing the prelude back would form an import cycle.
+    pub fn add_implicit_imports(&mut self, imports: &[UntypedUse]) {
+        let env_modules = self.env_modules();
+
+        let dependencies = self
             .0
+            .values()
+            .map(|m| m.deps_for_graph(&env_modules))
+            .collect::<HashMap<_, _>>();
+
+        let mut reachable = HashSet::new();
+
+        let mut stack = imports
+            .iter()
+            .map(|import| import.module.join("/"))
+            .collect::<Vec<_>>();
+
+        while let Some(name) = stack.pop() {
+            if reachable.insert(name.clone()) {
+                stack.extend(dependencies.get(&name).into_iter().flatten().cloned());
+            }
+        }
+
+        self.0
+            .values_mut()
+            .filter(|module| !module.kind.is_config() && !reachable.contains(&module.name))
+            .for_each(|module| module.ast.add_implicit_imports(imports));
+    }
+
+    fn env_modules(&self) -> Vec<String> {
+        self.0
             .values()
             .filter_map(|m| match m.kind {
                 ModuleKind::Env => Some(m.name.clone()),
                 ModuleKind::Lib | ModuleKind::Validator | ModuleKind::Config => None,
             })
-            .collect::<Vec<String>>();
+            .collect()
+    }
+
+    /// Only keep the modules affected by a change in the given ones (i.e. the changed modules
+    /// themselves and every module transitively importing them), along with the modules they
+    /// need to be type-checked. Returns the names of the affected modules.
+    pub fn retain_affected_by(&mut self, changed: &BTreeSet<String>) -> BTreeSet<String> {
+        let env_modules = self.env_modules();
+
+        let deps: HashMap<String, Vec<String>> = self
+            .0
+            .values()
+            .map(|m| m.deps_for_graph(&env_modules))
+            .collect();
+
+        let mut affected = changed.clone();
+
+        loop {
+            let dependents = deps
+                .iter()
+                .filter(|(name, deps)| {
+                    !affected.contains(*name) && deps.iter().any(|dep| affected.contains(dep))
+                })
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+
+            if dependents.is_empty() {
+                break;
+            }
+
+            affected.extend(dependents);
+        }
+
+        let mut needed = BTreeSet::new();
+
+        let mut queue = affected.iter().cloned().collect::<Vec<_>>();
+
+        while let Some(name) = queue.pop() {
+            if let Some(deps) = deps.get(&name) {
+                if needed.insert(name) {
+                    queue.extend(deps.iter().cloned());
+                }
+            }
+        }
+
+        self.0.retain(|name, _| needed.contains(name));
+
+        affected
+    }
+
+    pub fn sequence(&self, our_modules: &BTreeSet<String>) -> Result<Vec<String>, Error> {
+        let env_modules = self.env_modules();
 
         let inputs = self
             .0
@@ -527,3 +671,145 @@ impl DerefMut for CheckedModules {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aiken_lang::parser;
+
+    fn module(name: &str, imports: &[&str]) -> (String, ParsedModule) {
+        let code = imports
+            .iter()
+            .map(|import| format!("use {import}\n"))
+            .collect::<String>();
+
+        package_module("test/project", name, false, &code)
+    }
+
+    fn package_module(
+        package: &str,
+        name: &str,
+        internal: bool,
+        code: &str,
+    ) -> (String, ParsedModule) {
+        let (ast, extra) = parser::module(code, ModuleKind::Lib).expect("failed to parse module");
+
+        (
+            name.to_string(),
+            ParsedModule {
+                path: PathBuf::from(format!("lib/{name}.ak")),
+                name: name.to_string(),
+                code: code.to_string(),
+                kind: ModuleKind::Lib,
+                package: package.to_string(),
+                internal,
+                ast: UntypedModule {
+                    name: name.to_string(),
+                    ..ast
+                },
+                extra,
+            },
+        )
+    }
+
+    // f -> a -> b -> c <- d, with e on its own; 'aiken/list' stands for a module of another
+    // package, which isn't part of the parsed modules.
+    fn modules() -> ParsedModules {
+        ParsedModules::from(HashMap::from([
+            module("a", &["b"]),
+            module("b", &["c", "aiken/list"]),
+            module("c", &[]),
+            module("d", &["c"]),
+            module("e", &[]),
+            module("f", &["a"]),
+        ]))
+    }
+
+    fn names(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn retained(modules: &ParsedModules) -> BTreeSet<String> {
+        modules.keys().cloned().collect()
+    }
+
+    #[test]
+    fn retain_dependents_of_changed_modules() {
+        let mut modules = modules();
+
+        let affected = modules.retain_affected_by(&names(&["c"]));
+
+        assert_eq!(affected, names(&["a", "b", "c", "d", "f"]));
+        assert_eq!(retained(&modules), names(&["a", "b", "c", "d", "f"]));
+    }
+
+    #[test]
+    fn retain_dependencies_of_affected_modules() {
+        let mut modules = modules();
+
+        let affected = modules.retain_affected_by(&names(&["a"]));
+
+        assert_eq!(affected, names(&["a", "f"]));
+        assert_eq!(retained(&modules), names(&["a", "b", "c", "f"]));
+    }
+
+    #[test]
+    fn retain_nothing_when_nothing_changed() {
+        let mut modules = modules();
+
+        let affected = modules.retain_affected_by(&BTreeSet::new());
+
+        assert!(affected.is_empty());
+        assert!(modules.is_empty());
+    }
+
+    #[test]
+    fn retain_isolated_modules() {
+        let mut modules = modules();
+
+        let affected = modules.retain_affected_by(&names(&["e"]));
+
+        assert_eq!(affected, names(&["e"]));
+        assert_eq!(retained(&modules), names(&["e"]));
+    }
+
+    // 'aiken/internal/list' is internal to the stdlib, which re-exports part of it from
+    // 'aiken/list'.
+    fn stdlib_and(name: &str, code: &str) -> ParsedModules {
+        ParsedModules::from(HashMap::from([
+            package_module(
+                "aiken-lang/stdlib",
+                "aiken/internal/list",
+                true,
+                "pub fn map(xs: List<Int>) -> List<Int> {\n  xs\n}\n",
+            ),
+            package_module(
+                "aiken-lang/stdlib",
+                "aiken/list",
+                false,
+                "pub use aiken/internal/list.{map}\n",
+            ),
+            package_module("test/project", name, false, code),
+        ]))
+    }
+
+    #[test]
+    fn reject_internal_imports_from_other_packages() {
+        let modules = stdlib_and("foo", "use aiken/internal/list\n");
+
+        assert!(matches!(
+            modules.check_internal_imports(),
+            Err(Error::InternalModuleImported { module, package, alternatives, .. })
+                if module == "aiken/internal/list"
+                    && package == "aiken-lang/stdlib"
+                    && alternatives == vec!["aiken/list".to_string()]
+        ));
+    }
+
+    #[test]
+    fn allow_internal_imports_within_their_package() {
+        let modules = stdlib_and("foo", "use aiken/list\n");
+
+        assert!(modules.check_internal_imports().is_ok());
+    }
+}
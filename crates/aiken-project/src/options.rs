@@ -1,6 +1,84 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
-use aiken_lang::ast::Tracing;
+use crate::{costs::CostsGuard, telemetry::ReportOptions};
+use aiken_lang::{ast::Tracing, test_framework::PropertyTest};
+use uplc::optimize::Pass;
+
+/// What to build, and how; see 'Project::build'.
+pub struct BuildOptions {
+    pub uplc: bool,
+    pub air: bool,
+    pub check_artifacts: bool,
+    pub tracing: Tracing,
+    pub blueprint_path: PathBuf,
+    pub env: Option<String>,
+    pub filter: Option<Vec<String>>,
+    pub trace_codes: bool,
+    pub optimization_passes: Option<Vec<Pass>>,
+    pub emit_after: Option<Pass>,
+    pub attest: bool,
+    pub metadata: Option<String>,
+    pub summary: Option<PathBuf>,
+    pub annotate: bool,
+    pub archive: Option<PathBuf>,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            uplc: false,
+            air: false,
+            check_artifacts: false,
+            tracing: Tracing::silent(),
+            blueprint_path: Options::default().blueprint_path,
+            env: None,
+            filter: None,
+            trace_codes: false,
+            optimization_passes: None,
+            emit_after: None,
+            attest: false,
+            metadata: None,
+            summary: None,
+            annotate: false,
+            archive: None,
+        }
+    }
+}
+
+/// What to check, and how tests run; see 'Project::check'.
+pub struct CheckOptions {
+    pub skip_tests: bool,
+    pub match_tests: Option<Vec<String>>,
+    pub verbose: bool,
+    pub exact_match: bool,
+    pub seed: u32,
+    pub property_max_success: usize,
+    pub timeout: Option<Duration>,
+    pub tracing: Tracing,
+    pub env: Option<String>,
+    pub cost_model: Option<PathBuf>,
+    pub costs_guard: Option<CostsGuard>,
+    pub report: ReportOptions,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            skip_tests: false,
+            match_tests: None,
+            verbose: false,
+            exact_match: false,
+            seed: 0,
+            property_max_success: PropertyTest::DEFAULT_MAX_SUCCESS,
+            timeout: None,
+            tracing: Tracing::silent(),
+            env: None,
+            cost_model: None,
+            costs_guard: None,
+            report: ReportOptions::default(),
+        }
+    }
+}
 
 pub struct Options {
     pub code_gen_mode: CodeGenMode,
@@ -27,13 +105,31 @@ pub enum CodeGenMode {
         exact_match: bool,
         seed: u32,
         property_max_success: usize,
+        timeout: Option<Duration>,
+        cost_model: Option<Vec<i64>>,
+        costs_guard: Option<CostsGuard>,
+        report: ReportOptions,
+    },
+    Build {
+        uplc_dump: bool,
+        air_dump: bool,
+        check_artifacts: bool,
+        filter: Option<Vec<String>>,
+        trace_codes: bool,
+        optimization_passes: Option<Vec<Pass>>,
+        emit_after: Option<Pass>,
+        attest: bool,
+        metadata: Option<String>,
+        summary: Option<PathBuf>,
+        annotate: bool,
+        archive: Option<PathBuf>,
     },
-    Build(bool),
     Benchmark {
         match_benchmarks: Option<Vec<String>>,
         exact_match: bool,
         seed: u32,
         max_size: usize,
+        cost_model: Option<Vec<i64>>,
     },
     NoOp,
 }
@@ -3,11 +3,16 @@ use crate::{
     deps::manifest::Manifest,
     error::Error,
     package_name::PackageName,
+    settings,
     telemetry::{Event, EventListener},
 };
+use pallas_crypto::hash::Hasher;
 use regex::Regex;
 use reqwest::Client;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub fn project_config() -> PathBuf {
     PathBuf::from("aiken.toml")
@@ -17,10 +22,25 @@ pub fn manifest() -> PathBuf {
     PathBuf::from("aiken.lock")
 }
 
+/// Files loaded into `@fixture` constants, relative to the root of each package.
+pub fn fixtures() -> PathBuf {
+    PathBuf::from("fixtures")
+}
+
 pub fn build() -> PathBuf {
     PathBuf::from("build")
 }
 
+/// UPLC dumps written by the last build; the only ones the next build may clean up.
+pub fn build_artifacts() -> PathBuf {
+    build().join("artifacts.json")
+}
+
+/// Sizes of the validators of the last build, to compare the next one against.
+pub fn build_sizes() -> PathBuf {
+    build().join("sizes.json")
+}
+
 pub fn packages() -> PathBuf {
     build().join("packages")
 }
@@ -41,10 +61,34 @@ pub fn packages_cache() -> PathBuf {
     default_aiken_cache().join("packages")
 }
 
-pub fn default_aiken_cache() -> PathBuf {
-    dirs::cache_dir()
-        .expect("Failed to determine user cache directory")
+/// Type-checked dependency modules, shared across projects. See 'build_cache'.
+pub fn build_cache() -> PathBuf {
+    default_aiken_cache().join("build")
+}
+
+/// A throwaway project wrapping a standalone module, keyed by the module's (absolute) path so
+/// that subsequent checks of the same file can re-use already downloaded dependencies. The
+/// key is a blake2b digest, so it stays the same across compiler versions.
+pub fn scratch_project(module: &Path) -> PathBuf {
+    let key = Hasher::<64>::hash(module.to_string_lossy().as_bytes());
+    default_aiken_cache().join("scratch").join(key.to_string())
+}
+
+/// Installed compiler toolchains, one directory per version. Unlike caches, these are meant
+/// to stick around.
+pub fn toolchains() -> PathBuf {
+    dirs::data_dir()
+        .expect("Failed to determine user data directory")
         .join("aiken")
+        .join("toolchains")
+}
+
+pub fn default_aiken_cache() -> PathBuf {
+    settings::cache_dir().unwrap_or_else(|| {
+        dirs::cache_dir()
+            .expect("Failed to determine user cache directory")
+            .join("aiken")
+    })
 }
 
 #[derive(Debug)]
@@ -68,6 +112,7 @@ impl CacheKey {
                 Ok(package.version.to_string())
             } else {
                 match manifest.lookup_etag(package) {
+                    None if settings::offline() => new_cache_key_from_cache(package),
                     None => match new_etag_from_network(http, package).await {
                         Err(_) => {
                             event_listener.handle_event(Event::PackageResolveFallback {
@@ -197,4 +242,18 @@ mod tests {
         assert!(!is_git_sha_or_tag("main"), "main branch");
         assert!(!is_git_sha_or_tag("8ba594659468ba"), "not sha");
     }
+
+    #[test]
+    fn scratch_project_per_module() {
+        let foo = scratch_project(Path::new("/home/aiken/foo.ak"));
+
+        assert_eq!(foo, scratch_project(Path::new("/home/aiken/foo.ak")));
+        assert_ne!(foo, scratch_project(Path::new("/home/aiken/bar.ak")));
+        assert_eq!(
+            foo,
+            default_aiken_cache()
+                .join("scratch")
+                .join("97de6e1a59809a18")
+        );
+    }
 }
@@ -0,0 +1,180 @@
+use crate::{
+    config::Config,
+    docs::DocFile,
+    error::Error,
+    module::{CheckedModule, CheckedModules},
+    paths,
+    vfs::{to_slash, Vfs},
+};
+use aiken_lang::ast::{DataType, Definition, Function, ModuleConstant, ModuleKind, TypeAlias};
+use pallas_crypto::hash::Hasher;
+use std::{
+    io::{Cursor, Write},
+    path::Path,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+/// Fields of the 'aiken.toml' that consumers of a package rely on, and which must therefore be
+/// meaningful before a package is published.
+pub fn check_metadata(config: &Config) -> Result<(), Error> {
+    let mut problems = vec![];
+
+    if config.version == "0.0.0" {
+        problems.push("'version' is still set to its default value (0.0.0)".to_string());
+    }
+
+    if config.description.trim().is_empty() {
+        problems.push("'description' is missing".to_string());
+    }
+
+    if config.license.is_none() {
+        problems.push("'license' is missing".to_string());
+    }
+
+    if config.repository.is_none() {
+        problems.push("'repository' is missing".to_string());
+    }
+
+    for dependency in config.dependencies.iter() {
+        if !paths::is_git_sha_or_tag(&dependency.version) {
+            problems.push(format!(
+                "dependency '{}' points at a branch ({}) instead of a tag or commit",
+                dependency.name, dependency.version
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::IncompletePackageMetadata { problems })
+    }
+}
+
+/// Public definitions of the package's library modules which lack documentation.
+pub fn undocumented(modules: &CheckedModules, config: &Config) -> Vec<String> {
+    let package = config.name.to_string();
+
+    modules
+        .values()
+        .filter(
+            |CheckedModule {
+                 kind, package: p, ..
+             }| { p == &package && matches!(kind, ModuleKind::Lib) },
+        )
+        .flat_map(|module| {
+            module.ast.definitions().filter_map(|def| {
+                let name = match def {
+                    Definition::Fn(Function {
+                        public: true,
+                        doc: None,
+                        name,
+                        ..
+                    })
+                    | Definition::TypeAlias(TypeAlias {
+                        public: true,
+                        doc: None,
+                        alias: name,
+                        ..
+                    })
+                    | Definition::DataType(DataType {
+                        public: true,
+                        doc: None,
+                        name,
+                        ..
+                    })
+                    | Definition::ModuleConstant(ModuleConstant {
+                        public: true,
+                        doc: None,
+                        name,
+                        ..
+                    }) => name,
+                    _ => return None,
+                };
+
+                Some(format!("{}.{{{name}}}", module.name))
+            })
+        })
+        .collect()
+}
+
+/// Bundle everything a consumer needs from the package (its manifest, library modules, readme,
+/// license and generated documentation) into a zip archive, alongside a 'CHECKSUMS' file listing
+/// a blake2b-256 digest of each bundled file.
+pub fn archive(vfs: &dyn Vfs, root: &Path, path: &Path, docs: Vec<DocFile>) -> Result<(), Error> {
+    let mut sources = vec![root.join(paths::project_config())];
+
+    sources.extend(vfs.files(root).into_iter().filter(|path| {
+        let is_metadata = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| {
+                let stem = stem.to_uppercase();
+                stem == "README" || stem == "LICENSE"
+            });
+        path.parent() == Some(root) && is_metadata
+    }));
+
+    let lib = root.join("lib");
+
+    sources.extend(
+        vfs.files(&lib)
+            .into_iter()
+            .filter(|path| crate::is_aiken_path(path, &lib)),
+    );
+
+    let mut files = vec![];
+
+    for file in sources {
+        let name = to_slash(
+            file.strip_prefix(root)
+                .expect("archived files are within the project"),
+        );
+
+        let bytes = vfs.read(&file).map_err(|error| Error::FileIo {
+            error,
+            path: file.clone(),
+        })?;
+
+        files.push((name, bytes));
+    }
+
+    for doc in docs {
+        files.push((
+            format!("docs/{}", to_slash(&doc.path)),
+            doc.content.into_bytes(),
+        ));
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    let mut checksums = String::new();
+
+    for (name, bytes) in files {
+        checksums.push_str(&format!("{}  {name}\n", Hasher::<256>::hash(&bytes)));
+
+        zip.start_file(name, FileOptions::default())?;
+        zip.write_all(&bytes)?;
+    }
+
+    zip.start_file("CHECKSUMS", FileOptions::default())?;
+    zip.write_all(checksums.as_bytes())?;
+
+    let bytes = zip.finish()?.into_inner();
+
+    if let Some(parent) = path.parent() {
+        vfs.create_dir_all(parent)?;
+    }
+
+    vfs.write(path, &bytes).map_err(|error| Error::FileIo {
+        error,
+        path: path.to_path_buf(),
+    })
+}
+
+pub fn archive_name(config: &Config) -> String {
+    format!(
+        "{}-{}-{}.zip",
+        config.name.owner, config.name.repo, config.version
+    )
+}
@@ -0,0 +1,180 @@
+//! A line-delimited JSON protocol through which external tools (e.g. test explorers of
+//! editors, or CI dashboards) drive test runs, as served by `aiken check --runner-protocol`.
+//!
+//! Requests are read from stdin, one JSON object per line:
+//!
+//! ```text
+//! {"id": 1, "method": "list", "params": {"tests": ["aiken/list"]}}
+//! {"id": 2, "method": "run", "params": {"tests": ["aiken/list.{map}"], "exact_match": true, "seed": 42}}
+//! {"id": 3, "method": "reload"}
+//! {"id": 4, "method": "shutdown"}
+//! ```
+//!
+//! All params are optional; 'tests' follows the syntax of `aiken check --match-tests`. The 'id'
+//! is echoed back in every message relating to the request, which are written to stdout, one
+//! JSON object per line as well:
+//!
+//! - `{"event": "ready", "protocol": 1, "compiler": "v1.1.3+..."}` once, on start-up;
+//! - `{"id": 2, "event": "test", "test": {...}}` for each test, as soon as it's done running;
+//! - `{"id": 1, "result": ...}` once a request is complete;
+//! - `{"id": 1, "error": {"message": "...", "diagnostics": [...]}}` when it can't be.
+//!
+//! Sources are only type-checked on start-up and on 'reload', so that clients decide when
+//! changes are picked up. The protocol version is bumped on any breaking change.
+
+use crate::{
+    error::{Error, GetSource},
+    telemetry::{fmt_test_json, fmt_test_summary_json},
+};
+use aiken_lang::{
+    expr::UntypedExpr,
+    test_framework::{Test, TestResult},
+    version::compiler_version,
+};
+use miette::Diagnostic;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, Write};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Request {
+    #[serde(default)]
+    pub id: Value,
+    pub method: Method,
+    #[serde(default)]
+    pub params: Params,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// Describe the tests available, without running them.
+    List,
+    /// Run tests, streaming their results.
+    Run,
+    /// Re-read and type-check the project's sources.
+    Reload,
+    Shutdown,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Params {
+    pub tests: Option<Vec<String>>,
+    pub exact_match: bool,
+    pub seed: Option<u32>,
+}
+
+impl Request {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line).map_err(|e| format!("invalid request: {e}"))
+    }
+}
+
+/// Write a message, and flush it right away so that clients see it.
+pub fn send(output: &mut impl Write, message: Value) -> io::Result<()> {
+    writeln!(output, "{message}")?;
+    output.flush()
+}
+
+pub fn ready() -> Value {
+    json!({
+        "event": "ready",
+        "protocol": PROTOCOL_VERSION,
+        "compiler": compiler_version(true),
+    })
+}
+
+pub fn result(id: &Value, result: Value) -> Value {
+    json!({ "id": id, "result": result })
+}
+
+pub fn failure(id: &Value, message: &str, errors: &[Error]) -> Value {
+    json!({
+        "id": id,
+        "error": {
+            "message": message,
+            "diagnostics": errors.iter().map(diagnostic).collect::<Vec<_>>(),
+        },
+    })
+}
+
+pub fn test_event(id: &Value, test: &TestResult<UntypedExpr, UntypedExpr>) -> Value {
+    let mut json = fmt_test_json(test);
+    json["module"] = json!(test.module());
+    json!({ "id": id, "event": "test", "test": json })
+}
+
+pub fn listing(tests: &[Test]) -> Value {
+    let tests = tests
+        .iter()
+        .map(|test| {
+            let (module, name, kind) = match test {
+                Test::UnitTest(test) => (&test.module, &test.name, "unit"),
+                Test::PropertyTest(test) => (&test.module, &test.name, "property"),
+                Test::Benchmark(bench) => (&bench.module, &bench.name, "benchmark"),
+            };
+
+            json!({ "module": module, "name": name, "kind": kind })
+        })
+        .collect::<Vec<_>>();
+
+    json!({ "tests": tests })
+}
+
+pub fn summary(seed: u32, tests: &[TestResult<UntypedExpr, UntypedExpr>]) -> Value {
+    json!({
+        "seed": seed,
+        "summary": fmt_test_summary_json(&tests.iter().collect::<Vec<_>>()),
+    })
+}
+
+fn diagnostic(error: &Error) -> Value {
+    json!({
+        "message": error.to_string(),
+        "code": error.code().map(|code| code.to_string()),
+        "path": error.path(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_minimal_request() {
+        assert_eq!(
+            Request::parse(r#"{"method": "list"}"#),
+            Ok(Request {
+                id: Value::Null,
+                method: Method::List,
+                params: Params::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_run_request() {
+        assert_eq!(
+            Request::parse(
+                r#"{"id": "a", "method": "run", "params": {"tests": ["foo.{bar}"], "seed": 42}}"#
+            ),
+            Ok(Request {
+                id: json!("a"),
+                method: Method::Run,
+                params: Params {
+                    tests: Some(vec!["foo.{bar}".to_string()]),
+                    exact_match: false,
+                    seed: Some(42),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn parse_unknown_method() {
+        assert!(Request::parse(r#"{"id": 1, "method": "explode"}"#).is_err());
+    }
+}
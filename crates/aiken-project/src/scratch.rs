@@ -0,0 +1,131 @@
+//! Standalone modules (e.g. `aiken check scratch.ak`), checked within a throwaway project that
+//! depends on the standard library.
+
+use crate::{config::Config, package_name::PackageName, paths};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Wrap a standalone module into a scratch project living in the cache, so it can be checked
+/// like any other project. The project is kept around between runs to avoid re-downloading
+/// the standard library every time.
+pub fn project(module: &Path) -> io::Result<PathBuf> {
+    let module = fs::canonicalize(module)?;
+
+    let root = paths::scratch_project(&module);
+
+    populate(&root, &module, || {
+        Config::default(&PackageName {
+            owner: "aiken-lang".to_string(),
+            repo: "scratch".to_string(),
+        })
+    })?;
+
+    Ok(root)
+}
+
+/// Make 'module' the only module of the project at 'root', creating the project with the given
+/// configuration if needed.
+fn populate(root: &Path, module: &Path, config: impl FnOnce() -> Config) -> io::Result<()> {
+    let lib = root.join("lib");
+
+    if lib.exists() {
+        fs::remove_dir_all(&lib)?;
+    }
+
+    fs::create_dir_all(&lib)?;
+
+    if !root.join(paths::project_config()).exists() {
+        config().save(root)?;
+    }
+
+    fs::copy(
+        module,
+        lib.join(module.file_name().expect("module path has a file name")),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Dependency, Platform};
+
+    fn scratch_config() -> Config {
+        Config::with_dependencies(
+            &PackageName {
+                owner: "aiken-lang".to_string(),
+                repo: "scratch".to_string(),
+            },
+            vec![Dependency {
+                name: PackageName {
+                    owner: "aiken-lang".to_string(),
+                    repo: "stdlib".to_string(),
+                },
+                version: "v2.2.0".to_string(),
+                source: Platform::Github,
+                path: None,
+            }],
+        )
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aiken-scratch-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn populate_new_project() {
+        let dir = temp_dir("new");
+        let root = dir.join("project");
+
+        fs::write(dir.join("foo.ak"), "test foo() { True }\n").unwrap();
+
+        populate(&root, &dir.join("foo.ak"), scratch_config).unwrap();
+
+        let config = Config::load(&root).unwrap();
+        let lib = fs::read_dir(root.join("lib"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.name.to_string(), "aiken-lang/scratch");
+        assert_eq!(config.dependencies[0].version, "v2.2.0");
+        assert_eq!(lib, vec!["foo.ak"]);
+    }
+
+    #[test]
+    fn populate_existing_project() {
+        let dir = temp_dir("existing");
+        let root = dir.join("project");
+
+        fs::write(dir.join("foo.ak"), "test foo() { True }\n").unwrap();
+        fs::write(dir.join("bar.ak"), "test bar() { True }\n").unwrap();
+
+        populate(&root, &dir.join("foo.ak"), scratch_config).unwrap();
+
+        // Changes to the project's configuration (e.g. a resolved stdlib version) are kept.
+        let mut config = Config::load(&root).unwrap();
+        config.description = "kept".to_string();
+        config.save(&root).unwrap();
+
+        populate(&root, &dir.join("bar.ak"), scratch_config).unwrap();
+
+        let config = Config::load(&root).unwrap();
+        let lib = fs::read_dir(root.join("lib"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.description, "kept");
+        assert_eq!(lib, vec!["bar.ak"]);
+    }
+}
@@ -0,0 +1,201 @@
+use miette::Diagnostic;
+use pallas_crypto::hash::Hash;
+use pallas_primitives::conway::PlutusScript;
+use pallas_traverse::ComputeHash;
+use serde::{ser::SerializeStruct, Serializer};
+use std::{fmt, str::FromStr};
+use uplc::ast::{DeBruijn, Program, SerializableProgram};
+
+/// Binary encodings of built programs. CBOR is what ends up in blueprints and transactions; it
+/// merely wraps the flat bytes in a CBOR byte string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Flat,
+    Cbor,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Encoding::Flat => "flat",
+            Encoding::Cbor => "cbor",
+        })
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(Encoding::Flat),
+            "cbor" => Ok(Encoding::Cbor),
+            _ => Err(format!("unknown encoding '{s}', expected 'flat' or 'cbor'")),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum Error {
+    #[error("I couldn't encode a program as {encoding}: {reason}")]
+    #[diagnostic(code("aiken::serialization::encode"))]
+    Encode { encoding: Encoding, reason: String },
+
+    #[error("I couldn't decode a program from {encoding}: {reason}")]
+    #[diagnostic(code("aiken::serialization::decode"))]
+    #[diagnostic(help(
+        "Make sure the bytes are those of a program, and that the expected encoding is right: compiled code found in blueprints is hex-encoded CBOR."
+    ))]
+    Decode { encoding: Encoding, reason: String },
+}
+
+pub fn serialize(program: &Program<DeBruijn>, encoding: Encoding) -> Result<Vec<u8>, Error> {
+    match encoding {
+        Encoding::Flat => program.to_flat(),
+        Encoding::Cbor => program.to_cbor(),
+    }
+    .map_err(|e| Error::Encode {
+        encoding,
+        reason: e.to_string(),
+    })
+}
+
+pub fn deserialize(bytes: &[u8], encoding: Encoding) -> Result<Program<DeBruijn>, Error> {
+    let decode_error = |reason: String| Error::Decode { encoding, reason };
+
+    match encoding {
+        Encoding::Flat => Program::from_flat(bytes).map_err(|e| decode_error(e.to_string())),
+        Encoding::Cbor => {
+            let mut flat_buffer = Vec::new();
+
+            Program::from_cbor(bytes, &mut flat_buffer).map_err(|e| decode_error(e.to_string()))
+        }
+    }
+}
+
+/// Decode the hex-encoded CBOR of a program, as found under 'compiledCode' in blueprints.
+pub fn from_compiled_code(compiled_code: &str) -> Result<Program<DeBruijn>, Error> {
+    let bytes = hex::decode(compiled_code).map_err(|e| Error::Decode {
+        encoding: Encoding::Cbor,
+        reason: e.to_string(),
+    })?;
+
+    deserialize(&bytes, Encoding::Cbor)
+}
+
+/// Encode a program as found under 'compiledCode' in blueprints: hex-encoded CBOR. Along with
+/// the hash of the script, which depends on the program's Plutus version.
+pub fn to_compiled_code(program: &SerializableProgram) -> Result<(String, Hash<28>), Error> {
+    let cbor = serialize(program.inner(), Encoding::Cbor)?;
+
+    let hash = match program {
+        SerializableProgram::PlutusV1Program(..) => {
+            PlutusScript::<1>(cbor.clone().into()).compute_hash()
+        }
+        SerializableProgram::PlutusV2Program(..) => {
+            PlutusScript::<2>(cbor.clone().into()).compute_hash()
+        }
+        SerializableProgram::PlutusV3Program(..) => {
+            PlutusScript::<3>(cbor.clone().into()).compute_hash()
+        }
+    };
+
+    Ok((hex::encode(cbor), hash))
+}
+
+/// Serialize a program into the 'compiledCode' and 'hash' fields of a blueprint validator.
+pub fn serialize_program<S: Serializer>(
+    program: &SerializableProgram,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let (compiled_code, hash) = to_compiled_code(program).map_err(serde::ser::Error::custom)?;
+
+    let mut s = serializer.serialize_struct("Program<DeBruijn>", 2)?;
+    s.serialize_field("compiledCode", &compiled_code)?;
+    s.serialize_field("hash", &hash)?;
+    s.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize, serialize, to_compiled_code, Encoding};
+    use proptest::prelude::*;
+    use uplc::{
+        ast::{DeBruijn, Name, NamedDeBruijn, Program, SerializableProgram, Term},
+        machine::cost_model::ExBudget,
+    };
+
+    fn arbitrary_term() -> impl Strategy<Value = Term<Name>> {
+        let leaf = prop_oneof![
+            any::<i64>().prop_map(|n| Term::integer(n.into())),
+            Just(Term::Error),
+        ];
+
+        leaf.prop_recursive(4, 32, 3, |inner| {
+            prop_oneof![
+                (inner.clone(), inner.clone())
+                    .prop_map(|(left, right)| Term::add_integer().apply(left).apply(right)),
+                (inner.clone(), inner.clone())
+                    .prop_map(|(left, right)| Term::subtract_integer().apply(left).apply(right)),
+                (inner.clone(), inner.clone())
+                    .prop_map(|(left, right)| Term::multiply_integer().apply(left).apply(right)),
+                (inner.clone(), inner.clone(), inner.clone()).prop_map(|(left, right, then)| {
+                    Term::less_than_integer()
+                        .apply(left)
+                        .apply(right)
+                        .delayed_if_then_else(
+                            then,
+                            Term::var("x").lambda("x").apply(Term::integer(0.into())),
+                        )
+                }),
+            ]
+        })
+    }
+
+    fn eval(program: Program<DeBruijn>) -> (Result<Term<NamedDeBruijn>, String>, ExBudget) {
+        let result = Program::<NamedDeBruijn>::from(program).eval(ExBudget::max());
+
+        (result.result().map_err(|e| e.to_string()), result.cost())
+    }
+
+    proptest! {
+        #[test]
+        fn serialization_roundtrip_preserves_evaluation(term in arbitrary_term()) {
+            let program: Program<DeBruijn> = Program { version: (1, 0, 0), term }
+                .try_into()
+                .unwrap();
+
+            for encoding in [Encoding::Flat, Encoding::Cbor] {
+                let bytes = serialize(&program, encoding).unwrap();
+
+                let decoded = deserialize(&bytes, encoding).unwrap();
+
+                prop_assert_eq!(&decoded, &program);
+                prop_assert_eq!(eval(decoded), eval(program.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn compiled_code_matches_legacy_encoding() {
+        let program: Program<DeBruijn> = Program {
+            version: (1, 1, 0),
+            term: Term::add_integer()
+                .apply(Term::integer(1.into()))
+                .apply(Term::integer(2.into())),
+        }
+        .try_into()
+        .unwrap();
+
+        for program in [
+            SerializableProgram::PlutusV1Program(program.clone()),
+            SerializableProgram::PlutusV2Program(program.clone()),
+            SerializableProgram::PlutusV3Program(program),
+        ] {
+            assert_eq!(
+                to_compiled_code(&program).unwrap(),
+                program.compiled_code_and_hash()
+            );
+        }
+    }
+}
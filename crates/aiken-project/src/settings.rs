@@ -0,0 +1,137 @@
+//! Settings read from environment variables, understood by every command alike so that
+//! containers and CI pipelines can configure Aiken without passing flags around.
+
+use std::{env, io, path::PathBuf};
+use tracing::Level;
+
+/// Directory holding downloaded packages and other caches, instead of the user's cache
+/// directory.
+pub const CACHE_DIR: &str = "AIKEN_CACHE_DIR";
+
+/// Forbid network access when set to a truthy value ('1', 'true', 'yes' or 'on').
+pub const OFFLINE: &str = "AIKEN_OFFLINE";
+
+/// Never reuse nor record type-checked dependencies in the shared build cache when set to a
+/// truthy value.
+pub const NO_BUILD_CACHE: &str = "AIKEN_NO_BUILD_CACHE";
+
+/// Whether to color outputs: 'auto' (the default), 'always' or 'never'.
+pub const COLOR: &str = "AIKEN_COLOR";
+
+/// Most verbose level of logs to write to stderr: 'error', 'warn', 'info', 'debug' or 'trace'.
+/// Only a handful of places log anything: package downloads and the language server.
+pub const LOG: &str = "AIKEN_LOG";
+
+/// A summary of the variables above, as shown by 'aiken --help'.
+pub const HELP: &str = "\
+Environment variables:
+  AIKEN_CACHE_DIR       Directory for downloaded packages and other caches
+  AIKEN_OFFLINE         Never access the network; dependencies must already be in cache [1|0]
+  AIKEN_NO_BUILD_CACHE  Always type-check dependencies from scratch [1|0]
+  AIKEN_COLOR           Color outputs [auto|always|never]
+  AIKEN_LOG             Log package downloads to stderr, up to a level [error|warn|info|debug|trace]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    var(CACHE_DIR).map(PathBuf::from)
+}
+
+pub fn offline() -> bool {
+    var(OFFLINE).is_some_and(|value| parse_flag(&value))
+}
+
+pub fn no_build_cache() -> bool {
+    var(NO_BUILD_CACHE).is_some_and(|value| parse_flag(&value))
+}
+
+pub fn color() -> Color {
+    var(COLOR).map_or(Color::Auto, |value| parse_color(&value))
+}
+
+pub fn log() -> Option<Level> {
+    var(LOG).and_then(|value| parse_level(&value))
+}
+
+fn parse_flag(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+fn parse_color(value: &str) -> Color {
+    match value.trim().to_lowercase().as_str() {
+        "always" => Color::Always,
+        "never" => Color::Never,
+        _ => Color::Auto,
+    }
+}
+
+fn parse_level(value: &str) -> Option<Level> {
+    value.trim().parse().ok()
+}
+
+/// Apply the settings affecting the whole process, i.e. colors and logging. To be called
+/// once, early on.
+pub fn init() {
+    match color() {
+        Color::Always => owo_colors::set_override(true),
+        Color::Never => owo_colors::set_override(false),
+        Color::Auto => (),
+    }
+
+    if let Some(level) = log() {
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(io::stderr)
+            .without_time()
+            .try_init();
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    non_blank(env::var(name).ok())
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_flags() {
+        assert!(parse_flag("True"));
+        assert!(parse_flag(" on "));
+        assert!(!parse_flag("0"));
+        assert!(!parse_flag("nope"));
+    }
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse_color("never"), Color::Never);
+        assert_eq!(parse_color("ALWAYS"), Color::Always);
+        assert_eq!(parse_color("whatever"), Color::Auto);
+    }
+
+    #[test]
+    fn parse_levels() {
+        assert_eq!(parse_level("debug"), Some(Level::DEBUG));
+        assert_eq!(parse_level("WARN"), Some(Level::WARN));
+        assert_eq!(parse_level("loud"), None);
+    }
+
+    #[test]
+    fn blank_values_are_unset() {
+        assert_eq!(non_blank(Some(" ".to_string())), None);
+        assert_eq!(non_blank(Some("x".to_string())), Some("x".to_string()));
+    }
+}
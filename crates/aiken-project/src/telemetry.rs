@@ -1,7 +1,9 @@
+use crate::blueprint::{audit::VerifiedArtifact, summary::SizeSummary};
 use aiken_lang::{
     expr::UntypedExpr,
     test_framework::{BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult},
 };
+pub(crate) use json::{fmt_test_json, fmt_test_summary_json};
 pub use json::{json_schema, Json};
 use std::{
     collections::BTreeMap,
@@ -36,9 +38,34 @@ pub enum Event {
     GeneratingBlueprint {
         path: PathBuf,
     },
+    GeneratingPackageArchive {
+        path: PathBuf,
+    },
     DumpingUPLC {
         path: PathBuf,
     },
+    DumpingAir {
+        path: PathBuf,
+    },
+    AnnotatingValidators {
+        path: PathBuf,
+    },
+    VerifiedArtifacts {
+        path: PathBuf,
+        artifacts: Vec<VerifiedArtifact>,
+    },
+    BuildSummary {
+        validators: Vec<SizeSummary>,
+    },
+    GeneratingAttestation {
+        path: PathBuf,
+    },
+    GeneratingArtifactArchive {
+        path: PathBuf,
+    },
+    VerifiedAttestation {
+        path: PathBuf,
+    },
     GeneratingUPLCFor {
         name: String,
         path: PathBuf,
@@ -52,6 +79,9 @@ pub enum Event {
     FinishedTests {
         seed: u32,
         tests: Vec<TestResult<UntypedExpr, UntypedExpr>>,
+        report: ReportOptions,
+        /// Failure labels ('fail' / 'todo') per validator that no test went through.
+        uncovered_failures: Vec<(String, Vec<String>)>,
     },
     FinishedBenchmarks {
         seed: u32,
@@ -70,11 +100,26 @@ pub enum Event {
         source: DownloadSource,
     },
     ResolvingVersions,
+    PinningStdlib {
+        requested: String,
+        version: String,
+    },
+}
+
+/// Controls how much of each test's output gets shown by the terminal reporter.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReportOptions {
+    /// Maximum number of trace lines shown per test, or all of them when 'None'.
+    pub max_trace_lines: Option<usize>,
+    /// Show counterexamples in full, instead of eliding long or deeply nested values.
+    pub show_full_values: bool,
 }
 
 pub enum EventTarget {
     Json(Json),
     Terminal(Terminal),
+    /// Drop all events; only diagnostics get reported (e.g. when linting).
+    Quiet,
 }
 
 impl Default for EventTarget {
@@ -92,6 +137,7 @@ impl EventListener for EventTarget {
         match self {
             EventTarget::Terminal(term) => term.handle_event(event),
             EventTarget::Json(json) => json.handle_event(event),
+            EventTarget::Quiet => (),
         }
     }
 }
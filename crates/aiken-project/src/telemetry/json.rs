@@ -13,7 +13,12 @@ pub struct Json;
 impl EventListener for Json {
     fn handle_event(&self, event: Event) {
         match event {
-            Event::FinishedTests { seed, tests, .. } => {
+            Event::FinishedTests {
+                seed,
+                tests,
+                uncovered_failures,
+                ..
+            } => {
                 let total = tests.len();
                 let passed = tests.iter().filter(|t| t.is_success()).count();
                 let failed = total - passed;
@@ -36,6 +41,12 @@ impl EventListener for Json {
                             "tests": results.iter().map(|r| fmt_test_json(r)).collect::<Vec<_>>(),
                         })
                     }).collect::<Vec<_>>(),
+                    "uncovered_failures": uncovered_failures.iter().map(|(validator, labels)| {
+                        serde_json::json!({
+                            "validator": validator,
+                            "labels": labels,
+                        })
+                    }).collect::<Vec<_>>(),
                 });
                 println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
             }
@@ -74,7 +85,7 @@ impl EventListener for Json {
     }
 }
 
-fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::Value {
+pub(crate) fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::Value {
     let on_test_failure = match result {
         TestResult::UnitTestResult(UnitTestResult { ref test, .. }) => &test.on_test_failure,
         TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
@@ -136,7 +147,9 @@ fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::V
     test
 }
 
-fn fmt_test_summary_json(tests: &[&TestResult<UntypedExpr, UntypedExpr>]) -> serde_json::Value {
+pub(crate) fn fmt_test_summary_json(
+    tests: &[&TestResult<UntypedExpr, UntypedExpr>],
+) -> serde_json::Value {
     let total = tests.len();
     let passed = tests.iter().filter(|t| t.is_success()).count();
     let failed = total - passed;
@@ -297,6 +310,19 @@ pub fn json_schema() -> serde_json::Value {
                 }
               }
             }
+          },
+          "uncovered_failures": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "properties": {
+                "validator": { "type": "string" },
+                "labels": {
+                  "type": "array",
+                  "items": { "type": "string" }
+                }
+              }
+            }
           }
         },
         "definitions": definitions
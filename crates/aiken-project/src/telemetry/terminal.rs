@@ -1,8 +1,10 @@
-use super::{find_max_execution_units, group_by_module, DownloadSource, Event, EventListener};
+use super::{
+    find_max_execution_units, group_by_module, DownloadSource, Event, EventListener, ReportOptions,
+};
 use crate::pretty;
 use aiken_lang::{
     ast::OnTestFailure,
-    expr::UntypedExpr,
+    expr::{CallArg, UntypedExpr},
     format::Formatter,
     test_framework::{
         AssertionStyleOptions, BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult,
@@ -13,6 +15,18 @@ use rgb::RGB8;
 use std::sync::LazyLock;
 use uplc::machine::cost_model::ExBudget;
 
+/// Bytearrays longer than this (in hex digits) are elided in counterexamples.
+const MAX_BYTEARRAY_DIGITS: usize = 64;
+
+/// Strings longer than this (in characters) are elided in counterexamples.
+const MAX_STRING_CHARS: usize = 64;
+
+/// Lists longer than this only show their first elements in counterexamples.
+const MAX_LIST_ELEMENTS: usize = 8;
+
+/// Lists, tuples, pairs and constructors nested deeper than this are elided in counterexamples.
+const MAX_DEPTH: usize = 5;
+
 static BENCH_PLOT_COLOR: LazyLock<RGB8> = LazyLock::new(|| RGB8 {
     r: 250,
     g: 211,
@@ -78,6 +92,115 @@ impl EventListener for Terminal {
                         .if_supports_color(Stderr, |s| s.bright_blue())
                 );
             }
+            Event::DumpingAir { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "    Exporting"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "Air".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::AnnotatingValidators { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "   Annotating"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "validators".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::VerifiedArtifacts { path, artifacts } => {
+                eprintln!(
+                    "{} {} artifact(s) ({})",
+                    "    Verifying"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    artifacts.len().if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+
+                let width = artifacts.iter().map(|a| a.title.len()).max().unwrap_or(0);
+
+                for artifact in artifacts {
+                    eprintln!(
+                        "      {:<width$} {} {:>6} bytes",
+                        artifact.title,
+                        artifact.hash.if_supports_color(Stderr, |s| s.dimmed()),
+                        artifact.size,
+                    );
+                }
+            }
+            Event::BuildSummary { validators } => {
+                if validators.is_empty() {
+                    return;
+                }
+
+                eprintln!(
+                    "{} {} validator(s)",
+                    "      Summary"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    validators.len().if_supports_color(Stderr, |s| s.bold()),
+                );
+
+                let width = validators.iter().map(|v| v.title.len()).max().unwrap_or(0);
+
+                for validator in validators {
+                    let delta = format!("{:>7}", validator.delta_to_string());
+                    eprintln!(
+                        "      {:<width$} {:>6} bytes {} {}",
+                        validator.title,
+                        validator.size,
+                        match validator.delta() {
+                            Some(delta_value) if delta_value < 0.0 =>
+                                delta.if_supports_color(Stderr, |s| s.green()).to_string(),
+                            Some(delta_value) if delta_value > 0.0 =>
+                                delta.if_supports_color(Stderr, |s| s.red()).to_string(),
+                            _ => delta.if_supports_color(Stderr, |s| s.dimmed()).to_string(),
+                        },
+                        validator.hash.if_supports_color(Stderr, |s| s.dimmed()),
+                    );
+                }
+            }
+            Event::GeneratingAttestation { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "    Attesting"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "build".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::GeneratingArtifactArchive { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "    Archiving"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "artifacts".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::VerifiedAttestation { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "     Verified"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "build against its attestation".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
             Event::GeneratingBlueprint { path } => {
                 eprintln!(
                     "{} {} ({})",
@@ -89,6 +212,17 @@ impl EventListener for Terminal {
                         .if_supports_color(Stderr, |s| s.bright_blue())
                 );
             }
+            Event::GeneratingPackageArchive { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "    Packaging"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "package archive".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
             Event::GeneratingDocFiles { output_path } => {
                 eprintln!(
                     "{} {} to {}",
@@ -173,7 +307,12 @@ impl EventListener for Terminal {
                     "...".if_supports_color(Stderr, |s| s.bold())
                 );
             }
-            Event::FinishedTests { seed, tests } => {
+            Event::FinishedTests {
+                seed,
+                tests,
+                report,
+                uncovered_failures,
+            } => {
                 let (max_mem, max_cpu, max_iter) = find_max_execution_units(&tests);
 
                 for (module, results) in &group_by_module(&tests) {
@@ -184,7 +323,7 @@ impl EventListener for Terminal {
 
                     let tests = results
                         .iter()
-                        .map(|r| fmt_test(r, max_mem, max_cpu, max_iter, true))
+                        .map(|r| fmt_test(r, max_mem, max_cpu, max_iter, &report, true))
                         .collect::<Vec<String>>()
                         .join("\n");
 
@@ -217,6 +356,10 @@ impl EventListener for Terminal {
                     );
                 }
 
+                if !uncovered_failures.is_empty() {
+                    println!("{}", fmt_uncovered_failures(&uncovered_failures));
+                }
+
                 if !tests.is_empty() {
                     println!();
                 }
@@ -263,6 +406,16 @@ impl EventListener for Terminal {
                     msg.if_supports_color(Stderr, |s| s.bold())
                 )
             }
+            Event::PinningStdlib { requested, version } => {
+                eprintln!(
+                    "{} {} {requested} → {}\n        ↳ You're seeing this message because {requested} may contain changes incompatible with this compiler.",
+                    "      Pinning"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "aiken-lang/stdlib".if_supports_color(Stderr, |s| s.bold()),
+                    version.if_supports_color(Stderr, |s| s.yellow()),
+                )
+            }
             Event::ResolvingVersions => {
                 eprintln!(
                     "{} {}",
@@ -292,7 +445,16 @@ impl EventListener for Terminal {
 
                     let benchmarks = results
                         .iter()
-                        .map(|r| fmt_test(r, max_mem, max_cpu, max_iter, true))
+                        .map(|r| {
+                            fmt_test(
+                                r,
+                                max_mem,
+                                max_cpu,
+                                max_iter,
+                                &ReportOptions::default(),
+                                true,
+                            )
+                        })
                         .collect::<Vec<String>>()
                         .join("\n")
                         .chars()
@@ -333,6 +495,7 @@ fn fmt_test(
     max_mem: usize,
     max_cpu: usize,
     max_iter: usize,
+    report: &ReportOptions,
     styled: bool,
 ) -> String {
     // Status
@@ -494,6 +657,15 @@ fn fmt_test(
     // CounterExamples
     if let TestResult::PropertyTestResult(PropertyTestResult { counterexample, .. }) = result {
         match counterexample {
+            Err(uplc::machine::Error::TimedOut) => {
+                test = format!(
+                    "{test}\n{}",
+                    "× timed out before completing all runs"
+                        .if_supports_color(Stderr, |s| s.red())
+                        .if_supports_color(Stderr, |s| s.bold()),
+                );
+            }
+
             Err(err) => {
                 test = format!(
                     "{test}\n{}\n{}",
@@ -531,9 +703,7 @@ fn fmt_test(
                             .if_supports_color(Stderr, |s| s.bold())
                             .to_string()
                     },
-                    &Formatter::new()
-                        .expr(counterexample, false)
-                        .to_pretty_string(60)
+                    &compact_values(counterexample, report)
                         .lines()
                         .map(|line| {
                             format!(
@@ -584,22 +754,151 @@ fn fmt_test(
     }
 
     // Traces
-    if !result.traces().is_empty() {
+    let traces = result.traces();
+    if !traces.is_empty() {
+        let shown = report
+            .max_trace_lines
+            .map_or(traces.len(), |max| max.min(traces.len()));
+
+        let hidden = traces.len() - shown;
+
         test = format!(
-            "{test}\n{title}\n{traces}",
+            "{test}\n{title}\n{traces}{more}",
             title = "· with traces".if_supports_color(Stderr, |s| s.bold()),
-            traces = result
-                .traces()
+            traces = traces[..shown]
                 .iter()
                 .map(|line| { format!("| {line}",) })
                 .collect::<Vec<_>>()
-                .join("\n")
+                .join("\n"),
+            more = if hidden > 0 {
+                format!(
+                    "\n| {}",
+                    format!("… {hidden} more line{}", if hidden > 1 { "s" } else { "" })
+                        .if_supports_color(Stderr, |s| s.dimmed())
+                )
+            } else {
+                String::new()
+            },
         );
     };
 
     test
 }
 
+/// Pretty-print a counterexample, eliding the middle of long bytearrays and strings, the tail of
+/// long lists and deeply nested structures; unless asked to show values in full.
+fn compact_values(value: &UntypedExpr, report: &ReportOptions) -> String {
+    let value = if report.show_full_values {
+        value.clone()
+    } else {
+        compact_expr(value.clone(), 0)
+    };
+
+    Formatter::new().expr(&value, false).to_pretty_string(60)
+}
+
+fn compact_expr(expr: UntypedExpr, depth: usize) -> UntypedExpr {
+    // Elided parts are shown verbatim, as if they were variables.
+    let elided = |location, name: String| UntypedExpr::Var { location, name };
+
+    match expr {
+        UntypedExpr::ByteArray {
+            location, bytes, ..
+        } if bytes.len() * 2 > MAX_BYTEARRAY_DIGITS => {
+            let digits = hex::encode(&bytes);
+            let edge = MAX_BYTEARRAY_DIGITS / 4;
+            elided(
+                location,
+                format!(
+                    "#\"{}…{}\" ({} bytes)",
+                    &digits[..edge],
+                    &digits[digits.len() - edge..],
+                    bytes.len()
+                ),
+            )
+        }
+
+        UntypedExpr::String { location, value } if value.chars().count() > MAX_STRING_CHARS => {
+            let chars = value.chars().collect::<Vec<_>>();
+            let edge = MAX_STRING_CHARS / 4;
+            elided(
+                location,
+                format!(
+                    "@\"{}…{}\" ({} chars)",
+                    chars[..edge].iter().collect::<String>(),
+                    chars[chars.len() - edge..].iter().collect::<String>(),
+                    chars.len()
+                ),
+            )
+        }
+
+        UntypedExpr::List { .. }
+        | UntypedExpr::Tuple { .. }
+        | UntypedExpr::Pair { .. }
+        | UntypedExpr::Call { .. }
+            if depth >= MAX_DEPTH =>
+        {
+            elided(expr.location(), "…".to_string())
+        }
+
+        UntypedExpr::List {
+            location,
+            elements,
+            tail,
+        } => {
+            let hidden = elements.len().saturating_sub(MAX_LIST_ELEMENTS);
+
+            let mut elements = elements
+                .into_iter()
+                .take(MAX_LIST_ELEMENTS)
+                .map(|element| compact_expr(element, depth + 1))
+                .collect::<Vec<_>>();
+
+            if hidden > 0 {
+                elements.push(elided(location, format!("…{hidden} more")));
+            }
+
+            UntypedExpr::List {
+                location,
+                elements,
+                tail: tail.map(|tail| Box::new(compact_expr(*tail, depth + 1))),
+            }
+        }
+
+        UntypedExpr::Tuple { location, elems } => UntypedExpr::Tuple {
+            location,
+            elems: elems
+                .into_iter()
+                .map(|elem| compact_expr(elem, depth + 1))
+                .collect(),
+        },
+
+        UntypedExpr::Pair { location, fst, snd } => UntypedExpr::Pair {
+            location,
+            fst: Box::new(compact_expr(*fst, depth + 1)),
+            snd: Box::new(compact_expr(*snd, depth + 1)),
+        },
+
+        UntypedExpr::Call {
+            location,
+            fun,
+            arguments,
+        } => UntypedExpr::Call {
+            location,
+            fun,
+            arguments: arguments
+                .into_iter()
+                .map(|arg| CallArg {
+                    value: compact_expr(arg.value, depth + 1),
+                    ..arg
+                })
+                .collect(),
+        },
+
+        _ => expr,
+    }
+}
+
 fn fmt_test_summary<T>(tests: &[&TestResult<T, T>], styled: bool) -> String {
     let (n_passed, n_failed) = tests.iter().fold((0, 0), |(n_passed, n_failed), result| {
         if result.is_success() {
@@ -624,6 +923,54 @@ fn fmt_test_summary<T>(tests: &[&TestResult<T, T>], styled: bool) -> String {
     )
 }
 
+fn fmt_uncovered_failures(uncovered_failures: &[(String, Vec<String>)]) -> String {
+    let title = "uncovered failures"
+        .if_supports_color(Stderr, |s| s.bold())
+        .if_supports_color(Stderr, |s| s.yellow())
+        .to_string();
+
+    let content = uncovered_failures
+        .iter()
+        .map(|(validator, labels)| {
+            let labels = labels
+                .iter()
+                .map(|label| {
+                    format!(
+                        "  ↳ {}",
+                        format!("{label:?}").if_supports_color(Stderr, |s| s.yellow())
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            format!(
+                "{}\n{labels}",
+                validator.if_supports_color(Stderr, |s| s.bold())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let n = uncovered_failures
+        .iter()
+        .map(|(_, labels)| labels.len())
+        .sum::<usize>();
+
+    pretty::indent(
+        &pretty::open_box(
+            &title,
+            &content,
+            &format!("{n} failure(s) never reached by any test"),
+            |border| {
+                border
+                    .if_supports_color(Stderr, |s| s.bright_black())
+                    .to_string()
+            },
+        ),
+        4,
+    )
+}
+
 fn plot(color: &RGB8, points: Vec<(f32, f32)>, max_size: usize) -> String {
     use textplots::{Chart, ColorPlot, Shape};
     let mut chart = Chart::new(80, 50, 1.0, max_size as f32);
@@ -634,3 +981,102 @@ fn plot(color: &RGB8, points: Vec<(f32, f32)>, max_size: usize) -> String {
     chart.figures();
     chart.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aiken_lang::{
+        ast::{ByteArrayFormatPreference, Span},
+        parser::token::Base,
+    };
+
+    fn int(n: usize) -> UntypedExpr {
+        UntypedExpr::UInt {
+            location: Span::empty(),
+            value: n.to_string(),
+            base: Base::Decimal {
+                numeric_underscore: false,
+            },
+        }
+    }
+
+    fn list(elements: Vec<UntypedExpr>) -> UntypedExpr {
+        UntypedExpr::List {
+            location: Span::empty(),
+            elements,
+            tail: None,
+        }
+    }
+
+    fn compact(value: &UntypedExpr) -> String {
+        compact_values(value, &ReportOptions::default())
+    }
+
+    fn full(value: &UntypedExpr) -> String {
+        compact_values(
+            value,
+            &ReportOptions {
+                show_full_values: true,
+                ..ReportOptions::default()
+            },
+        )
+    }
+
+    #[test]
+    fn compact_long_bytearrays() {
+        let value = UntypedExpr::ByteArray {
+            location: Span::empty(),
+            bytes: vec![0xff; 64],
+            preferred_format: ByteArrayFormatPreference::HexadecimalString,
+        };
+
+        assert_eq!(
+            compact(&value),
+            format!("#\"{}…{}\" (64 bytes)", "f".repeat(16), "f".repeat(16))
+        );
+        assert_eq!(full(&value), format!("#\"{}\"", "f".repeat(128)));
+    }
+
+    #[test]
+    fn compact_long_strings() {
+        let value = UntypedExpr::String {
+            location: Span::empty(),
+            value: format!("{}{}", "a".repeat(50), "z".repeat(50)),
+        };
+
+        assert_eq!(
+            compact(&value),
+            format!("@\"{}…{}\" (100 chars)", "a".repeat(16), "z".repeat(16))
+        );
+        assert_eq!(
+            full(&value),
+            format!("@\"{}{}\"", "a".repeat(50), "z".repeat(50))
+        );
+    }
+
+    #[test]
+    fn compact_long_lists() {
+        let value = list((0..20).map(int).collect());
+
+        assert_eq!(compact(&value), "[0, 1, 2, 3, 4, 5, 6, 7, …12 more]");
+        assert!(full(&value).contains("19"));
+    }
+
+    #[test]
+    fn compact_deep_structures() {
+        let value = (0..10).fold(int(42), |inner, _| list(vec![inner]));
+
+        assert_eq!(compact(&value), "[[[[[…]]]]]");
+        assert_eq!(full(&value), "[[[[[[[[[[42]]]]]]]]]]");
+    }
+
+    #[test]
+    fn keep_small_values() {
+        let value = UntypedExpr::Tuple {
+            location: Span::empty(),
+            elems: vec![int(1), list(vec![int(2), int(3)])],
+        };
+
+        assert_eq!(compact(&value), full(&value));
+    }
+}
@@ -5,7 +5,9 @@ mod test {
         utils,
     };
     use aiken_lang::{
-        ast::{DataTypeKey, Definition, ModuleKind, TraceLevel, Tracing, TypedDataType},
+        ast::{
+            DataTypeKey, Definition, ModuleKind, TraceLevel, TraceSeverity, Tracing, TypedDataType,
+        },
         builtins,
         expr::UntypedExpr,
         format::Formatter,
@@ -31,9 +33,7 @@ mod test {
 
         let module_name = "";
 
-        let mut module_types = HashMap::new();
-        module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
-        module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+        let mut module_types = builtins::modules(&id_gen);
 
         let mut warnings = vec![];
         let (ast, _) = parser::module(src, TEST_KIND).expect("Failed to parse module");
@@ -44,6 +44,7 @@ mod test {
                 module_name,
                 &module_types,
                 Tracing::All(TraceLevel::Verbose),
+                TraceSeverity::Debug,
                 &mut warnings,
                 None,
             )
@@ -223,16 +224,11 @@ mod test {
 
     fn expect_failure<'a>(
         prop: &'a PropertyTest,
-        plutus_version: &'a PlutusVersion,
+        settings: &'a EvalSettings,
     ) -> Counterexample<'a> {
         let mut labels = BTreeMap::new();
         let mut remaining = PropertyTest::DEFAULT_MAX_SUCCESS;
-        match prop.run_n_times(
-            &mut remaining,
-            Prng::from_seed(42),
-            &mut labels,
-            plutus_version,
-        ) {
+        match prop.run_n_times(&mut remaining, Prng::from_seed(42), &mut labels, settings) {
             Ok(Some(counterexample)) => counterexample,
             _ => panic!("expected property to fail but it didn't."),
         }
@@ -249,7 +245,7 @@ mod test {
         assert!(TestResult::PropertyTestResult::<(), _>(prop.run(
             42,
             PropertyTest::DEFAULT_MAX_SUCCESS,
-            &PlutusVersion::default()
+            &EvalSettings::default()
         ))
         .is_success());
     }
@@ -276,7 +272,7 @@ mod test {
         let result = prop.run(
             42,
             PropertyTest::DEFAULT_MAX_SUCCESS,
-            &PlutusVersion::default(),
+            &EvalSettings::default(),
         );
 
         assert!(
@@ -297,8 +293,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -325,8 +321,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -342,8 +338,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -370,8 +366,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -398,8 +394,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -429,8 +425,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -464,9 +460,9 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
+        let settings = EvalSettings::default();
 
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -500,8 +496,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
@@ -535,8 +531,8 @@ mod test {
             }
         "#});
 
-        let plutus_version = PlutusVersion::default();
-        let mut counterexample = expect_failure(&prop, &plutus_version);
+        let settings = EvalSettings::default();
+        let mut counterexample = expect_failure(&prop, &settings);
 
         counterexample.simplify();
 
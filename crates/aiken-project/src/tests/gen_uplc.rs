@@ -784,6 +784,35 @@ fn acceptance_test_6_equals_tuple() {
     assert_uplc(src, uplc, false, false);
 }
 
+#[test]
+fn acceptance_test_6_equals_empty_list() {
+    let src = r#"
+        fn is_empty(xs: List<Int>) -> Bool {
+          xs == []
+        }
+
+        test foo() {
+          is_empty([]) && [] != [1]
+        }
+    "#;
+
+    let uplc = Term::var("is_empty")
+        .lambda("is_empty")
+        .apply(Term::null_list().apply(Term::var("xs")).lambda("xs"))
+        .apply(Term::empty_list())
+        .delayed_if_then_else(
+            Term::null_list()
+                .apply(Term::list_values(vec![Constant::Data(Data::integer(
+                    1.into(),
+                ))]))
+                .if_then_else(Term::bool(false), Term::bool(true)),
+            Term::bool(false),
+        );
+
+    assert_uplc(src, uplc.clone(), false, true);
+    assert_uplc(src, uplc, false, false);
+}
+
 #[test]
 fn acceptance_test_7_unzip_tuple() {
     let src = r#"
@@ -1298,55 +1327,49 @@ fn acceptance_test_11_map_empty() {
       }
     "#;
 
-    let uplc = Term::equals_data()
-        .apply(
-            Term::list_data().apply(
+    let uplc = Term::null_list().apply(
+        Term::var("map")
+            .lambda("map")
+            .apply(
                 Term::var("map")
+                    .apply(Term::var("map"))
+                    .apply(Term::var("xs"))
                     .lambda("map")
                     .apply(
-                        Term::var("map")
-                            .apply(Term::var("map"))
-                            .apply(Term::var("xs"))
-                            .lambda("map")
-                            .apply(
-                                Term::var("xs")
-                                    .delayed_choose_list(
-                                        Term::empty_list(),
-                                        Term::mk_cons()
-                                            .apply(
-                                                Term::i_data()
-                                                    .apply(Term::var("f").apply(Term::var("x"))),
-                                            )
-                                            .apply(
-                                                Term::var("map")
-                                                    .apply(Term::var("map"))
-                                                    .apply(Term::var("rest")),
-                                            )
-                                            .lambda("rest")
-                                            .apply(Term::tail_list().apply(Term::var("xs")))
-                                            .lambda("x")
-                                            .apply(
-                                                Term::un_i_data().apply(
-                                                    Term::head_list().apply(Term::var("xs")),
-                                                ),
-                                            ),
+                        Term::var("xs")
+                            .delayed_choose_list(
+                                Term::empty_list(),
+                                Term::mk_cons()
+                                    .apply(
+                                        Term::i_data().apply(Term::var("f").apply(Term::var("x"))),
                                     )
-                                    .lambda("xs")
-                                    .lambda("map"),
+                                    .apply(
+                                        Term::var("map")
+                                            .apply(Term::var("map"))
+                                            .apply(Term::var("rest")),
+                                    )
+                                    .lambda("rest")
+                                    .apply(Term::tail_list().apply(Term::var("xs")))
+                                    .lambda("x")
+                                    .apply(
+                                        Term::un_i_data()
+                                            .apply(Term::head_list().apply(Term::var("xs"))),
+                                    ),
                             )
-                            .lambda("f")
-                            .lambda("xs"),
+                            .lambda("xs")
+                            .lambda("map"),
                     )
-                    .apply(Term::empty_list())
-                    .apply(
-                        Term::add_integer()
-                            .apply(Term::var("n"))
-                            .apply(Term::integer(1.into()))
-                            .lambda("n"),
-                    ),
+                    .lambda("f")
+                    .lambda("xs"),
+            )
+            .apply(Term::empty_list())
+            .apply(
+                Term::add_integer()
+                    .apply(Term::var("n"))
+                    .apply(Term::integer(1.into()))
+                    .lambda("n"),
             ),
-        )
-        .apply(Term::list_data().apply(Term::empty_list()));
+    );
 
     assert_uplc(src, uplc.clone(), false, true);
     assert_uplc(src, uplc, false, false);
@@ -2008,83 +2031,75 @@ fn acceptance_test_22_filter_map() {
           }
     "#;
 
-    let uplc = Term::equals_data()
+    let uplc = Term::null_list()
         .apply(
-            Term::list_data().apply(
-                Term::var("filter_map")
-                    .lambda("filter_map")
-                    .apply(
-                        Term::var("foldr")
-                            .apply(Term::var("xs"))
-                            .apply(
-                                Term::equals_integer()
-                                    .apply(Term::integer(1.into()))
-                                    .apply(Term::var("subject_index"))
-                                    .delayed_if_then_else(
-                                        Term::var("ys"),
-                                        Term::mk_cons()
-                                            .apply(Term::i_data().apply(Term::var("y")))
-                                            .apply(Term::var("ys"))
-                                            .lambda("y")
-                                            .apply(
-                                                Term::un_i_data().apply(
-                                                    Term::head_list()
-                                                        .apply(Term::var("subject_fields")),
-                                                ),
-                                            )
-                                            .lambda("subject_fields")
-                                            .apply(
-                                                Term::var(CONSTR_FIELDS_EXPOSER)
-                                                    .apply(Term::var("subject")),
-                                            ),
-                                    )
-                                    .lambda("subject_index")
-                                    .apply(
-                                        Term::var(CONSTR_INDEX_EXPOSER).apply(Term::var("subject")),
-                                    )
-                                    .lambda("subject")
-                                    .apply(Term::var("f").apply(Term::var("x")))
-                                    .lambda("ys")
-                                    .lambda("x"),
-                            )
-                            .apply(Term::empty_list())
-                            .lambda("f")
-                            .lambda("xs"),
-                    )
-                    .lambda("foldr")
-                    .apply(
-                        Term::var("foldr")
-                            .apply(Term::var("foldr"))
-                            .apply(Term::var("xs"))
-                            .lambda("foldr")
-                            .apply(
-                                Term::var("xs")
-                                    .delayed_choose_list(
-                                        Term::var("zero"),
-                                        Term::var("f")
-                                            .apply(Term::var("x"))
-                                            .apply(
-                                                Term::var("foldr")
-                                                    .apply(Term::var("foldr"))
-                                                    .apply(Term::var("rest")),
-                                            )
-                                            .lambda("rest")
-                                            .apply(Term::tail_list().apply(Term::var("xs")))
-                                            .lambda("x")
-                                            .apply(Term::head_list().apply(Term::var("xs"))),
-                                    )
-                                    .lambda("xs")
-                                    .lambda("foldr"),
-                            )
-                            .lambda("zero")
-                            .lambda("f")
-                            .lambda("xs"),
-                    )
-                    .apply(Term::empty_list())
-                    .apply(Term::data(Data::constr(0, vec![Data::integer(42.into())])).lambda("_")),
-            ),
+            Term::var("filter_map")
+                .lambda("filter_map")
+                .apply(
+                    Term::var("foldr")
+                        .apply(Term::var("xs"))
+                        .apply(
+                            Term::equals_integer()
+                                .apply(Term::integer(1.into()))
+                                .apply(Term::var("subject_index"))
+                                .delayed_if_then_else(
+                                    Term::var("ys"),
+                                    Term::mk_cons()
+                                        .apply(Term::i_data().apply(Term::var("y")))
+                                        .apply(Term::var("ys"))
+                                        .lambda("y")
+                                        .apply(Term::un_i_data().apply(
+                                            Term::head_list().apply(Term::var("subject_fields")),
+                                        ))
+                                        .lambda("subject_fields")
+                                        .apply(
+                                            Term::var(CONSTR_FIELDS_EXPOSER)
+                                                .apply(Term::var("subject")),
+                                        ),
+                                )
+                                .lambda("subject_index")
+                                .apply(Term::var(CONSTR_INDEX_EXPOSER).apply(Term::var("subject")))
+                                .lambda("subject")
+                                .apply(Term::var("f").apply(Term::var("x")))
+                                .lambda("ys")
+                                .lambda("x"),
+                        )
+                        .apply(Term::empty_list())
+                        .lambda("f")
+                        .lambda("xs"),
+                )
+                .lambda("foldr")
+                .apply(
+                    Term::var("foldr")
+                        .apply(Term::var("foldr"))
+                        .apply(Term::var("xs"))
+                        .lambda("foldr")
+                        .apply(
+                            Term::var("xs")
+                                .delayed_choose_list(
+                                    Term::var("zero"),
+                                    Term::var("f")
+                                        .apply(Term::var("x"))
+                                        .apply(
+                                            Term::var("foldr")
+                                                .apply(Term::var("foldr"))
+                                                .apply(Term::var("rest")),
+                                        )
+                                        .lambda("rest")
+                                        .apply(Term::tail_list().apply(Term::var("xs")))
+                                        .lambda("x")
+                                        .apply(Term::head_list().apply(Term::var("xs"))),
+                                )
+                                .lambda("xs")
+                                .lambda("foldr"),
+                        )
+                        .lambda("zero")
+                        .lambda("f")
+                        .lambda("xs"),
+                )
+                .apply(Term::empty_list())
+                .apply(Term::data(Data::constr(0, vec![Data::integer(42.into())])).lambda("_")),
         )
-        .apply(Term::list_data().apply(Term::empty_list()))
         .constr_fields_exposer()
         .constr_index_exposer();
 
@@ -2812,32 +2827,57 @@ fn acceptance_test_28_unique_empty_list() {
       }
     "#;
 
-    let uplc = Term::equals_data()
-        .apply(
-            Term::list_data().apply(
-                Term::var("unique")
-                    .lambda("unique")
-                    .apply(Term::var("unique").apply(Term::var("unique")))
-                    .lambda("unique")
+    let uplc = Term::null_list().apply(
+        Term::var("unique")
+            .lambda("unique")
+            .apply(Term::var("unique").apply(Term::var("unique")))
+            .lambda("unique")
+            .apply(
+                Term::var("xs")
+                    .delayed_choose_list(
+                        Term::empty_list(),
+                        Term::mk_cons()
+                            .apply(Term::var("x"))
+                            .apply(
+                                Term::var("unique").apply(Term::var("unique")).apply(
+                                    Term::var("filter").apply(Term::var("rest")).apply(
+                                        Term::equals_data()
+                                            .apply(Term::var("y"))
+                                            .apply(Term::var("x"))
+                                            .if_then_else(Term::bool(false), Term::bool(true))
+                                            .lambda("y"),
+                                    ),
+                                ),
+                            )
+                            .lambda("rest")
+                            .lambda("x")
+                            .apply(Term::head_list().apply(Term::var("xs")))
+                            .apply(Term::tail_list().apply(Term::var("xs"))),
+                    )
+                    .lambda("xs")
+                    .lambda("unique"),
+            )
+            .lambda("filter")
+            .apply(
+                Term::var("filter")
+                    .apply(Term::var("filter"))
+                    .apply(Term::var("xs"))
+                    .lambda("filter")
                     .apply(
                         Term::var("xs")
                             .delayed_choose_list(
                                 Term::empty_list(),
-                                Term::mk_cons()
+                                Term::var("f")
                                     .apply(Term::var("x"))
-                                    .apply(
-                                        Term::var("unique").apply(Term::var("unique")).apply(
-                                            Term::var("filter").apply(Term::var("rest")).apply(
-                                                Term::equals_data()
-                                                    .apply(Term::var("y"))
-                                                    .apply(Term::var("x"))
-                                                    .if_then_else(
-                                                        Term::bool(false),
-                                                        Term::bool(true),
-                                                    )
-                                                    .lambda("y"),
-                                            ),
+                                    .delayed_if_then_else(
+                                        Term::mk_cons().apply(Term::var("x")).apply(
+                                            Term::var("filter")
+                                                .apply(Term::var("filter"))
+                                                .apply(Term::var("rest")),
                                         ),
+                                        Term::var("filter")
+                                            .apply(Term::var("filter"))
+                                            .apply(Term::var("rest")),
                                     )
                                     .lambda("rest")
                                     .lambda("x")
@@ -2845,45 +2885,13 @@ fn acceptance_test_28_unique_empty_list() {
                                     .apply(Term::tail_list().apply(Term::var("xs"))),
                             )
                             .lambda("xs")
-                            .lambda("unique"),
-                    )
-                    .lambda("filter")
-                    .apply(
-                        Term::var("filter")
-                            .apply(Term::var("filter"))
-                            .apply(Term::var("xs"))
-                            .lambda("filter")
-                            .apply(
-                                Term::var("xs")
-                                    .delayed_choose_list(
-                                        Term::empty_list(),
-                                        Term::var("f")
-                                            .apply(Term::var("x"))
-                                            .delayed_if_then_else(
-                                                Term::mk_cons().apply(Term::var("x")).apply(
-                                                    Term::var("filter")
-                                                        .apply(Term::var("filter"))
-                                                        .apply(Term::var("rest")),
-                                                ),
-                                                Term::var("filter")
-                                                    .apply(Term::var("filter"))
-                                                    .apply(Term::var("rest")),
-                                            )
-                                            .lambda("rest")
-                                            .lambda("x")
-                                            .apply(Term::head_list().apply(Term::var("xs")))
-                                            .apply(Term::tail_list().apply(Term::var("xs"))),
-                                    )
-                                    .lambda("xs")
-                                    .lambda("filter"),
-                            )
-                            .lambda("f")
-                            .lambda("xs"),
+                            .lambda("filter"),
                     )
-                    .apply(Term::empty_list()),
-            ),
-        )
-        .apply(Term::data(Data::list(vec![])));
+                    .lambda("f")
+                    .lambda("xs"),
+            )
+            .apply(Term::empty_list()),
+    );
 
     assert_uplc(src, uplc.clone(), false, true);
     assert_uplc(src, uplc, false, false);
@@ -3956,11 +3964,11 @@ fn generic_validator_type_test() {
                     .delay_true_if_then_else(
                         Term::snd_pair()
                             .apply(Term::unconstr_data().apply(Term::Var(target)))
-                            .delay_empty_choose_list(then, Term::var("r:A<B>")),
-                        Term::var("r:A<B>"),
+                            .delay_empty_choose_list(then, Term::var("redeemer'r'isnotavalidA<B>")),
+                        Term::var("redeemer'r'isnotavalidA<B>"),
                     )
             },
-            &Term::var("r:A<B>"),
+            &Term::var("redeemer'r'isnotavalidA<B>"),
         )
     };
 
@@ -3982,7 +3990,7 @@ fn generic_validator_type_test() {
             .delay_empty_choose_list(
                 Term::Var(then_delayed.clone()).force(),
                 if trace {
-                    Term::var("r:A<B>")
+                    Term::var("redeemer'r'isnotavalidA<B>")
                 } else {
                     Term::Error.delay()
                 },
@@ -3991,7 +3999,7 @@ fn generic_validator_type_test() {
 
     let expect_b = |target: Rc<Name>, then: Term<Name>, trace: bool| {
         let error_trace = if trace {
-            Term::var("r:A<B>")
+            Term::var("redeemer'r'isnotavalidA<B>")
         } else {
             Term::Error.delay()
         };
@@ -4037,7 +4045,7 @@ fn generic_validator_type_test() {
 
     let expect_some_a = |redeemer: Rc<Name>, then_delayed: Rc<Name>, trace: bool| {
         let trace_error = if trace {
-            Term::var("r:A<B>")
+            Term::var("redeemer'r'isnotavalidA<B>")
         } else {
             Term::Error.delay()
         };
@@ -4127,7 +4135,7 @@ fn generic_validator_type_test() {
                         when_constructor(1).delay_true_if_then_else(
                             then_2nd,
                             if trace {
-                                Term::var("r:A<B>")
+                                Term::var("redeemer'r'isnotavalidA<B>")
                             } else {
                                 Term::Error.delay()
                             },
@@ -4164,7 +4172,7 @@ fn generic_validator_type_test() {
                             Term::choose_data_constr(
                                 redeemer.clone(),
                                 |_| body_part,
-                                &Term::var("r:A<B>"),
+                                &Term::var("redeemer'r'isnotavalidA<B>"),
                             )
                         } else {
                             body_part
@@ -4219,9 +4227,11 @@ fn generic_validator_type_test() {
 
     assert_uplc(
         src,
-        validator(true)
-            .lambda("r:A<B>")
-            .apply(Term::Error.delayed_trace(Term::string("r: A<B>")).delay()),
+        validator(true).lambda("redeemer'r'isnotavalidA<B>").apply(
+            Term::Error
+                .delayed_trace(Term::string("redeemer 'r' is not a valid A<B>"))
+                .delay(),
+        ),
         false,
         true,
     );
@@ -6449,3 +6459,162 @@ fn dangling_trace_expect_in_trace() {
 
     assert_uplc(src, program, false, true)
 }
+
+#[test]
+fn nullary_constructor_as_constant() {
+    let src = r#"
+        type Color {
+          Red
+          Green
+          Blue
+        }
+
+        test nullary() {
+          Blue == Blue
+        }
+    "#;
+
+    let uplc = Term::equals_data()
+        .apply(Term::data(Data::constr(2, vec![])))
+        .apply(Term::data(Data::constr(2, vec![])));
+
+    assert_uplc(src, uplc.clone(), false, true);
+    assert_uplc(src, uplc, false, false);
+}
+
+#[test]
+fn concat_bytearrays() {
+    // Concatenation of literals is folded during type-checking, so one operand comes from an
+    // argument.
+    let src = r#"
+        fn prefix(bytes: ByteArray) -> ByteArray {
+          #"01" <> bytes
+        }
+
+        test concat() {
+          prefix(#"0203") == #"010203"
+        }
+    "#;
+
+    assert_eq!(eval_test(src, Tracing::silent()), (false, vec![]));
+
+    let src = r#"
+        fn prefix(bytes: ByteArray) -> ByteArray {
+          #"01" <> bytes
+        }
+
+        test concat() {
+          prefix(#"0203") == #"020301"
+        }
+    "#;
+
+    assert_eq!(eval_test(src, Tracing::silent()), (true, vec![]));
+}
+
+#[test]
+fn concat_strings() {
+    let src = r#"
+        fn greet(name: String) -> String {
+          @"Hello, " <> name <> @"!"
+        }
+
+        test concat() {
+          greet(@"World") == @"Hello, World!"
+        }
+    "#;
+
+    assert_eq!(eval_test(src, Tracing::silent()), (false, vec![]));
+}
+
+/// Generate and evaluate the only test of a module, returning whether it failed along with
+/// the traces it emitted.
+fn eval_test(source_code: &str, tracing: Tracing) -> (bool, Vec<String>) {
+    let mut project = TestProject::new();
+
+    let modules = CheckedModules::singleton(project.check(project.parse(source_code)));
+
+    let mut generator = project.new_generator(tracing);
+
+    let checked_module = modules.values().next().unwrap();
+
+    let mut tests = checked_module
+        .ast
+        .definitions()
+        .filter_map(|def| match def {
+            Definition::Test(test) => Some(test),
+            _ => None,
+        });
+
+    let test = tests.next().expect("no test in module");
+
+    assert!(tests.next().is_none(), "more than one test in module");
+
+    let program: Program<DeBruijn> = generator
+        .generate_raw(&test.body, &[], &checked_module.name)
+        .try_into()
+        .unwrap();
+
+    let mut eval = program.eval(ExBudget::max());
+
+    (eval.failed(false), eval.logs())
+}
+
+#[test]
+fn division_by_zero_is_traced() {
+    let src = r#"
+        test division() {
+          let divisor = 0
+          1 / divisor == 1
+        }
+    "#;
+
+    assert_eq!(
+        eval_test(src, Tracing::All(TraceLevel::Verbose)),
+        (true, vec!["division by zero: 1 / divisor".to_string()])
+    );
+
+    let (failed, logs) = eval_test(src, Tracing::All(TraceLevel::Compact));
+    assert!(failed);
+    assert!(
+        matches!(logs.as_slice(), [log] if log.starts_with("division by zero: L")),
+        "{logs:?}"
+    );
+
+    assert_eq!(
+        eval_test(src, Tracing::All(TraceLevel::Silent)),
+        (true, vec![])
+    );
+}
+
+#[test]
+fn modulo_by_zero_is_traced() {
+    let src = r#"
+        test modulo() {
+          let divisor = 0
+          1 % divisor == 1
+        }
+    "#;
+
+    assert_eq!(
+        eval_test(src, Tracing::All(TraceLevel::Verbose)),
+        (true, vec!["modulo by zero: 1 % divisor".to_string()])
+    );
+}
+
+#[test]
+fn checked_division_by_non_zero() {
+    let src = r#"
+        test division() {
+          let divisor = -2
+          and {
+            7 / divisor == -4,
+            7 % divisor == -1,
+          }
+        }
+    "#;
+
+    assert_eq!(
+        eval_test(src, Tracing::All(TraceLevel::Verbose)),
+        (false, vec![])
+    );
+}
@@ -0,0 +1,1887 @@
+use crate::{
+    config::{Config, Dependency, Platform, PlutusVersion, TraceConfig},
+    error::Warning,
+    options::{BuildOptions, CheckOptions},
+    package_name::PackageName,
+    paths,
+    telemetry::{Event, EventListener},
+    vfs::{OverlayFs, Vfs},
+    Project,
+};
+use aiken_lang::{
+    ast::{FunctionAccessKey, TraceSeverity, Tracing},
+    gen_uplc::reachability::reachable_functions,
+    test_framework::TestResult,
+    tipo,
+};
+use std::{
+    collections::BTreeSet,
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use uplc::machine::cost_model::ExBudget;
+
+struct Silent;
+
+impl EventListener for Silent {}
+
+/// Keeps the measures of every benchmark that ran.
+#[derive(Default, Clone)]
+struct Measures(Arc<Mutex<Vec<ExBudget>>>);
+
+impl EventListener for Measures {
+    fn handle_event(&self, event: Event) {
+        if let Event::FinishedBenchmarks { benchmarks, .. } = event {
+            let mut measures = self.0.lock().unwrap();
+
+            for benchmark in benchmarks {
+                if let TestResult::BenchmarkResult(result) = benchmark {
+                    measures.extend(result.measures.into_iter().map(|(_, budget)| budget));
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the traces of every test that ran.
+#[derive(Default, Clone)]
+struct Traces(Arc<Mutex<Vec<String>>>);
+
+impl EventListener for Traces {
+    fn handle_event(&self, event: Event) {
+        if let Event::FinishedTests { tests, .. } = event {
+            let mut traces = self.0.lock().unwrap();
+
+            for test in tests {
+                traces.extend(test.traces().iter().cloned());
+            }
+        }
+    }
+}
+
+/// Keeps the failure labels reported as uncovered by tests.
+#[derive(Default, Clone)]
+struct Uncovered(Arc<Mutex<Vec<(String, Vec<String>)>>>);
+
+impl EventListener for Uncovered {
+    fn handle_event(&self, event: Event) {
+        if let Event::FinishedTests {
+            uncovered_failures, ..
+        } = event
+        {
+            self.0.lock().unwrap().extend(uncovered_failures);
+        }
+    }
+}
+
+fn check(vfs: &OverlayFs, root: &Path) -> Result<(), Vec<crate::error::Error>> {
+    check_changes(vfs, root, None)
+}
+
+fn check_changes(
+    vfs: &OverlayFs,
+    root: &Path,
+    changed_files: Option<BTreeSet<PathBuf>>,
+) -> Result<(), Vec<crate::error::Error>> {
+    let config = Config::load_with(vfs, root).map_err(|e| vec![e])?;
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    if let Some(changed_files) = changed_files {
+        project.only_check_changes(changed_files);
+    }
+
+    project.check(CheckOptions {
+        seed: 42,
+        property_max_success: 10,
+        ..CheckOptions::default()
+    })
+}
+
+fn build(
+    vfs: &OverlayFs,
+    root: &Path,
+    filter: Option<Vec<String>>,
+) -> Result<(), Vec<crate::error::Error>> {
+    let config = Config::load_with(vfs, root).map_err(|e| vec![e])?;
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project.build(BuildOptions {
+        uplc: true,
+        blueprint_path: root.join("plutus.json"),
+        filter,
+        ..BuildOptions::default()
+    })
+}
+
+fn new_project(root: &Path) -> OverlayFs {
+    let vfs = OverlayFs::in_memory();
+
+    let config = Config::with_dependencies(
+        &PackageName {
+            owner: "test".to_string(),
+            repo: "project".to_string(),
+        },
+        vec![],
+    );
+
+    vfs.write(
+        &root.join("aiken.toml"),
+        toml::to_string_pretty(&config).unwrap().as_bytes(),
+    )
+    .unwrap();
+
+    vfs
+}
+
+#[test]
+fn check_project_in_memory() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            pub fn answer() -> Int {
+              42
+            }
+
+            test answer_is_42() {
+              answer() == 42
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_ok());
+
+    // Unsaved edits are picked up by the next compilation.
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            pub fn answer() -> Int {
+              True
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_err());
+}
+
+#[test]
+fn check_fixtures_in_memory() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            @fixture("answer.cbor")
+            const answer: ByteArray
+
+            @fixture("answer.cbor.hex")
+            const answer_hex: ByteArray
+
+            @fixture("answer.json")
+            const answer_json: ByteArray
+
+            test fixtures_are_loaded() {
+              and {
+                answer == #"182a",
+                answer_hex == #"182a",
+                answer_json == "42",
+              }
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_err());
+
+    vfs.insert(root.join("fixtures/answer.cbor"), "\u{18}*");
+    vfs.insert(root.join("fixtures/answer.cbor.hex"), "182a\n");
+    vfs.insert(root.join("fixtures/answer.json"), "42");
+
+    assert!(check(&vfs, &root).is_ok());
+
+    // Only files said to be hex-encoded get decoded, and they must then be.
+    vfs.insert(root.join("fixtures/answer.cbor.hex"), "\u{18}*");
+
+    assert!(matches!(
+        check(&vfs, &root).unwrap_err().as_slice(),
+        [crate::error::Error::MissingFixture { file, .. }] if file == "answer.cbor.hex"
+    ));
+}
+
+#[test]
+fn check_fixtures_outside_of_fixtures_folder() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(root.join("secret"), "s3cr3t");
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            @fixture("../secret")
+            const secret: ByteArray
+        "#},
+    );
+
+    let errors = check(&vfs, &root).unwrap_err();
+
+    assert!(matches!(
+        errors.as_slice(),
+        [crate::error::Error::FixtureOutsidePackage { file, .. }] if file == "../secret"
+    ));
+}
+
+#[test]
+fn build_only_cleans_up_its_own_dumps() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let validator = |name: &str| {
+        format!(
+            "validator {name} {{\n  mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {{\n    True\n  }}\n}}\n"
+        )
+    };
+
+    vfs.insert(root.join("validators/foo.ak"), validator("foo"));
+    vfs.insert(root.join("validators/bar.ak"), validator("bar"));
+    vfs.insert(
+        root.join("artifacts/notes.uplc"),
+        "(program 1.1.0 (con unit ()))",
+    );
+
+    let dump = |title: &str| root.join(format!("artifacts/{title}.uplc"));
+
+    build(&vfs, &root, None).unwrap();
+
+    assert!(vfs.read(&dump("foo.foo.mint")).is_ok());
+    assert!(vfs.read(&dump("bar.bar.mint")).is_ok());
+
+    // Validators left out of a filtered build keep their dumps.
+    build(&vfs, &root, Some(vec!["foo".to_string()])).unwrap();
+
+    assert!(vfs.read(&dump("foo.foo.mint")).is_ok());
+    assert!(vfs.read(&dump("bar.bar.mint")).is_ok());
+
+    vfs.remove_file(&root.join("validators/bar.ak")).unwrap();
+
+    build(&vfs, &root, None).unwrap();
+
+    assert!(vfs.read(&dump("foo.foo.mint")).is_ok());
+    assert!(vfs.read(&dump("bar.bar.mint")).is_err());
+    assert!(vfs.read(&root.join("artifacts/notes.uplc")).is_ok());
+}
+
+#[test]
+fn filtered_build_merges_into_existing_blueprint() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let validator = |name: &str, result: &str| {
+        format!(
+            "validator {name} {{\n  mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {{\n    {result}\n  }}\n}}\n"
+        )
+    };
+
+    vfs.insert(root.join("validators/foo.ak"), validator("foo", "True"));
+    vfs.insert(root.join("validators/bar.ak"), validator("bar", "True"));
+
+    let titles = || -> Vec<String> {
+        let json: serde_json::Value =
+            serde_json::from_str(&vfs.read_to_string(&root.join("plutus.json")).unwrap()).unwrap();
+
+        json["validators"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|validator| validator["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    build(&vfs, &root, None).unwrap();
+
+    let before = vfs.read_to_string(&root.join("plutus.json")).unwrap();
+
+    vfs.insert(root.join("validators/foo.ak"), validator("foo", "False"));
+
+    build(&vfs, &root, Some(vec!["foo".to_string()])).unwrap();
+
+    let after = vfs.read_to_string(&root.join("plutus.json")).unwrap();
+
+    let mut titles = titles();
+    titles.sort();
+
+    assert_eq!(titles, vec!["bar.bar.mint", "foo.foo.mint"]);
+    assert_ne!(
+        before, after,
+        "the selected validator should have been rebuilt"
+    );
+}
+
+fn build_archive(
+    vfs: &OverlayFs,
+    root: &Path,
+    summary: Option<PathBuf>,
+) -> Result<(), Vec<crate::error::Error>> {
+    let config = Config::load_with(vfs, root).map_err(|e| vec![e])?;
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project.build(BuildOptions {
+        uplc: true,
+        blueprint_path: root.join("plutus.json"),
+        summary,
+        archive: Some(root.join("artifacts/bundle.tar")),
+        ..BuildOptions::default()
+    })
+}
+
+fn build_traced(
+    vfs: &OverlayFs,
+    root: &Path,
+    trace_codes: bool,
+) -> Result<(), Vec<crate::error::Error>> {
+    let config = Config::load_with(vfs, root).map_err(|e| vec![e])?;
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project.build(BuildOptions {
+        tracing: Tracing::verbose(),
+        blueprint_path: root.join("plutus.json"),
+        trace_codes,
+        ..BuildOptions::default()
+    })
+}
+
+#[test]
+fn build_with_trace_codes() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+                trace @"checking the redeemer"
+                if redeemer == 42 {
+                  True
+                } else {
+                  trace @"checking the redeemer"
+                  fail @"the redeemer isn't 42"
+                }
+              }
+            }
+        "#},
+    );
+
+    let compiled_code = |vfs: &OverlayFs| {
+        let blueprint: serde_json::Value =
+            serde_json::from_slice(&vfs.read(&root.join("plutus.json")).unwrap()).unwrap();
+        blueprint["validators"][0]["compiledCode"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let message = hex::encode("checking the redeemer");
+
+    assert!(build_traced(&vfs, &root, false).is_ok());
+    assert!(compiled_code(&vfs).contains(&message));
+    assert!(vfs.read(&root.join("plutus.traces.json")).is_err());
+
+    assert!(build_traced(&vfs, &root, true).is_ok());
+    assert!(!compiled_code(&vfs).contains(&message));
+
+    let codes: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_slice(&vfs.read(&root.join("plutus.traces.json")).unwrap()).unwrap();
+
+    let messages = codes
+        .values()
+        .filter_map(|msg| msg.as_str())
+        .collect::<Vec<_>>();
+
+    // Identical messages share a code.
+    assert_eq!(
+        messages
+            .iter()
+            .filter(|msg| **msg == "checking the redeemer")
+            .count(),
+        1
+    );
+    assert!(messages.contains(&"the redeemer isn't 42"));
+    assert!(codes.keys().all(|code| code.parse::<usize>().is_ok()));
+}
+
+#[test]
+fn docs_render_examples() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            /// Double a number.
+            pub fn double(n: Int) -> Int {
+              n * 2
+            }
+
+            @example(double)
+            test double_twice() {
+              double(double(1)) == 4
+            }
+        "#},
+    );
+
+    let mut config = Config::load_with(&vfs, &root).unwrap();
+
+    // Without links to the sources, which would depend on the repository.
+    config.repository = None;
+
+    let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    let destination = project
+        .docs(None, false, None)
+        .unwrap_or_else(|errors| panic!("{errors:#?}"));
+
+    assert_eq!(destination, root.join("docs"));
+
+    let page = vfs.get(&destination.join("foo.html")).unwrap();
+
+    // Only the functions, as the rest of the page changes with the time and compiler version.
+    let start = page.find(r#"<h1 id="module-functions""#).unwrap();
+    let end = start + page[start..].find("</section>").unwrap();
+
+    let functions = page[start..end]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    insta::assert_snapshot!(functions);
+}
+
+fn verify(vfs: &OverlayFs, root: &Path) -> Result<(), Vec<crate::error::Error>> {
+    let config = Config::load_with(vfs, root).map_err(|e| vec![e])?;
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project.verify(&root.join("plutus.attestation.json"))
+}
+
+#[test]
+fn build_with_attestation_then_verify() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+                trace @"checking the redeemer"
+                redeemer == 42
+              }
+            }
+        "#},
+    );
+
+    let config = Config::load_with(&vfs, &root).unwrap();
+
+    let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project
+        .build(BuildOptions {
+            tracing: Tracing::verbose(),
+            blueprint_path: root.join("plutus.json"),
+            trace_codes: true,
+            metadata: Some("release".to_string()),
+            attest: true,
+            ..BuildOptions::default()
+        })
+        .unwrap();
+
+    assert!(vfs.get(&root.join("plutus.attestation.json")).is_some());
+
+    // Verification rebuilds with the recorded options, which must give the same outcome.
+    verify(&vfs, &root).unwrap_or_else(|errors| panic!("{errors:#?}"));
+
+    assert!(vfs.get(&root.join("build/verify/plutus.json")).is_some());
+
+    // While a change to the sources no longer matches the attestation.
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+                trace @"checking the redeemer"
+                redeemer == 43
+              }
+            }
+        "#},
+    );
+
+    assert!(matches!(
+        verify(&vfs, &root).unwrap_err().as_slice(),
+        [crate::error::Error::AttestationMismatch { .. }]
+    ));
+}
+
+#[test]
+fn build_with_annotations() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+                let expected = redeemer * redeemer + 1
+                expected == 42 || expected == redeemer
+              }
+            }
+        "#},
+    );
+
+    let config = Config::load_with(&vfs, &root).unwrap();
+
+    let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    let result = project.build(BuildOptions {
+        blueprint_path: root.join("plutus.json"),
+        annotate: true,
+        ..BuildOptions::default()
+    });
+
+    assert!(result.is_ok());
+
+    let html = String::from_utf8(
+        vfs.read(&root.join("artifacts/validators.annotated.html"))
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert!(html.contains("<h2>foo.foo</h2>"));
+    // The binding is used twice and survives optimizations, on the line it is defined.
+    assert!(html.contains(
+        "<pre>    let expected = redeemer * redeemer + 1</pre></td><td class=\"uplc\"><span class=\"name\">expected</span>"
+    ));
+}
+
+/// Names of the entries of a tar archive, in order.
+fn archived(vfs: &OverlayFs, path: &Path) -> Vec<String> {
+    vfs.read(path)
+        .unwrap()
+        .chunks(512)
+        .filter(|block| &block[257..262] == b"ustar")
+        .map(|block| {
+            let end = block[..100].iter().position(|b| *b == 0).unwrap_or(100);
+            String::from_utf8(block[..end].to_vec()).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn archive_only_holds_files_of_this_build() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        "validator foo {\n  mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {\n    True\n  }\n}\n".to_string(),
+    );
+
+    vfs.insert(
+        root.join("artifacts/leftover.uplc"),
+        "(program 1.1.0 (con unit ()))".to_string(),
+    );
+
+    build_archive(&vfs, &root, None).unwrap();
+
+    let first = vfs.read(&root.join("artifacts/bundle.tar")).unwrap();
+
+    assert_eq!(
+        archived(&vfs, &root.join("artifacts/bundle.tar")),
+        vec![
+            "manifest.json",
+            "artifacts/foo.foo.mint.uplc",
+            "plutus.json"
+        ]
+    );
+
+    // Neither leftovers nor a previous archive change what gets archived.
+    build_archive(&vfs, &root, None).unwrap();
+
+    assert_eq!(vfs.read(&root.join("artifacts/bundle.tar")).unwrap(), first);
+}
+
+#[test]
+fn archive_rejects_clashing_names() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let result = build_archive(&vfs, &root, Some(PathBuf::from("/elsewhere/plutus.json")));
+
+    // The summary, outside of the project, is archived under the same name as the blueprint.
+    assert!(
+        matches!(
+            result.as_deref(),
+            Err([crate::error::Error::FileIo { error, .. }]) if error.kind() == std::io::ErrorKind::AlreadyExists
+        ),
+        "{result:#?}"
+    );
+
+    let result = build_archive(&vfs, &root, Some(root.join("manifest.json")));
+
+    // The summary is archived under the same name as the manifest.
+    assert!(
+        matches!(
+            result.as_deref(),
+            Err([crate::error::Error::FileIo { error, .. }]) if error.kind() == std::io::ErrorKind::AlreadyExists
+        ),
+        "{result:#?}"
+    );
+}
+
+#[test]
+fn build_type_checks_tests_and_test_only_definitions() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(root.join("env/default.ak"), "");
+    vfs.insert(root.join("env/mainnet.ak"), "");
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            @cfg(env = "mainnet")
+            pub const network_id = 1
+
+            @cfg(env = "default")
+            pub const network_id = 0
+
+            @cfg(env = "preprod")
+            pub const network_magic = 1
+
+            @cfg(test)
+            fn fake_datum() -> Data {
+              Void
+            }
+
+            test datum_is_void() {
+              fake_datum() == Void
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_ok());
+
+    let config = Config::load_with(&vfs, &root).unwrap();
+
+    let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project
+        .build(BuildOptions {
+            uplc: true,
+            blueprint_path: root.join("plutus.json"),
+            ..BuildOptions::default()
+        })
+        .unwrap_or_else(|errors| panic!("{errors:#?}"));
+
+    let warnings = project
+        .warnings()
+        .into_iter()
+        .filter_map(|warning| match warning {
+            Warning::Type { warning, .. } => Some(warning),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        matches!(
+            &warnings[..],
+            [tipo::error::Warning::UnknownEnvironment { name, .. }] if name == "preprod"
+        ),
+        "{warnings:#?}"
+    );
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            test datum_is_void() {
+              Void == 42
+            }
+        "#},
+    );
+
+    assert!(build(&vfs, &root, None).is_err());
+}
+
+#[test]
+fn build_hoists_every_reachable_function() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/util.ak"),
+        indoc::indoc! {r#"
+            fn twice(n: Int) -> Int {
+              n + n
+            }
+
+            fn apply(n: Int, f: fn(Int) -> Int) -> Int {
+              f(n)
+            }
+
+            pub fn double(n: Int) -> Int {
+              apply(n, twice)
+            }
+
+            pub fn quadruple(n: Int) -> Int {
+              n |> double |> double(_)
+            }
+
+            pub fn unused(n: Int) -> Int {
+              n * 42
+            }
+
+            pub fn small() -> Fuzzer<Int> {
+              fn(prng: PRNG) -> Option<(PRNG, Int)> { Some((prng, 14)) }
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("lib/tests.ak"),
+        indoc::indoc! {r#"
+            use util
+
+            test quadruple_of_14(n via util.small()) {
+              util.quadruple(n) == 56
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            use util
+
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+                util.quadruple(redeemer) == 56
+              }
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_ok());
+    assert!(build(&vfs, &root, None).is_ok());
+}
+
+#[test]
+fn build_prunes_unreachable_modules() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/reachable.ak"),
+        indoc::indoc! {r#"
+            pub fn is_answer(n: Int) -> Bool {
+              n == 42
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("lib/unreachable.ak"),
+        indoc::indoc! {r#"
+            pub fn is_not_answer(n: Int) -> Bool {
+              n != 42
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            use reachable
+
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _transaction: Data) {
+                reachable.is_answer(redeemer)
+              }
+            }
+        "#},
+    );
+
+    let config = Config::load_with(&vfs, &root).unwrap();
+
+    let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project
+        .check(CheckOptions {
+            skip_tests: true,
+            seed: 42,
+            property_max_success: 10,
+            ..CheckOptions::default()
+        })
+        .unwrap();
+
+    let key = |module_name: &str, function_name: &str| FunctionAccessKey {
+        module_name: module_name.to_string(),
+        function_name: function_name.to_string(),
+    };
+
+    assert!(project
+        .functions
+        .contains_key(&key("unreachable", "is_not_answer")));
+
+    let reachable = reachable_functions(
+        project
+            .checked_modules
+            .validators()
+            .flat_map(|(_, validator)| {
+                validator
+                    .handlers
+                    .iter()
+                    .chain(std::iter::once(&validator.fallback))
+                    .map(|handler| &handler.body)
+            }),
+        &project.functions,
+        &project.constants,
+    );
+
+    assert!(reachable.contains(&key("reachable", "is_answer")));
+    assert!(!reachable.contains(&key("unreachable", "is_not_answer")));
+}
+
+#[test]
+fn trace_formatting_helpers() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            use aiken/bytearray
+            use aiken/string
+
+            test string_from_int() {
+              and {
+                string.from_int(0) == @"0",
+                string.from_int(42) == @"42",
+                string.from_int(-1337) == @"-1337",
+              }
+            }
+
+            test bytearray_to_hex() {
+              and {
+                bytearray.to_hex(#"") == @"",
+                bytearray.to_hex(#"00ff") == @"00ff",
+                bytearray.to_hex("aiken") == @"61696b656e",
+              }
+            }
+
+            test bytearray_to_hex_mismatch() fail {
+              bytearray.to_hex(#"ff") == @"FF"
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_ok());
+}
+
+#[test]
+fn build_multi_purpose_handler() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            validator foo(threshold: Int) {
+              mint | withdraw(redeemer: Int, _target: Data, _self: Data) {
+                redeemer > threshold
+              }
+
+              else(_) {
+                fail
+              }
+            }
+        "#},
+    );
+
+    assert!(build(&vfs, &root, None).is_ok());
+}
+
+#[test]
+fn build_shares_module_constants() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let owner = "deadbeef".repeat(7);
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::formatdoc! {r#"
+            use aiken/builtin
+
+            const owner: ByteArray = #"{owner}"
+
+            validator foo {{
+              mint(redeemer: ByteArray, _policy_id: ByteArray, _transaction: Data) {{
+                redeemer == owner || builtin.append_bytearray(redeemer, owner) == #""
+              }}
+
+              else(_) {{
+                fail
+              }}
+            }}
+        "#},
+    );
+
+    assert!(build(&vfs, &root, None).is_ok());
+
+    let blueprint: serde_json::Value =
+        serde_json::from_slice(&vfs.read(&root.join("plutus.json")).unwrap()).unwrap();
+
+    let compiled_code = blueprint["validators"][0]["compiledCode"].as_str().unwrap();
+
+    assert_eq!(compiled_code.matches(&owner).count(), 1);
+}
+
+#[test]
+fn report_uncovered_failures() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/helpers.ak"),
+        indoc::indoc! {r#"
+            pub fn check_amount(n: Int) -> Bool {
+              if n > 100 {
+                fail @"too large"
+              } else if n < 0 {
+                fail @"negative"
+              } else {
+                True
+              }
+            }
+
+            test too_large() fail {
+              check_amount(101)
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        indoc::indoc! {r#"
+            use helpers
+
+            validator foo {
+              mint(redeemer: Int, _policy_id: ByteArray, _self: Data) {
+                if redeemer == 42 {
+                  fail @"too"
+                } else {
+                  helpers.check_amount(redeemer)
+                }
+              }
+
+              else(_) {
+                fail
+              }
+            }
+        "#},
+    );
+
+    let uncovered = Uncovered::default();
+
+    let mut project = Project::new_with_config(
+        Config::load_with(&vfs, &root).unwrap(),
+        root.clone(),
+        uncovered.clone(),
+    );
+
+    project.set_vfs(vfs.clone());
+
+    project
+        .check(CheckOptions {
+            tracing: Tracing::verbose(),
+            ..CheckOptions::default()
+        })
+        .unwrap();
+
+    // Failures of helpers count as the validator's, and labels must be traced verbatim: "too" is
+    // not covered by "too large".
+    assert_eq!(
+        *uncovered.0.lock().unwrap(),
+        vec![(
+            "foo.foo".to_string(),
+            vec!["too".to_string(), "negative".to_string()]
+        )]
+    );
+}
+
+#[test]
+fn only_validators_require_plutus_v3() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let mut config = Config::load_with(&vfs, &root).unwrap();
+    config.plutus = PlutusVersion::V2;
+    vfs.write(
+        &root.join("aiken.toml"),
+        toml::to_string_pretty(&config).unwrap().as_bytes(),
+    )
+    .unwrap();
+
+    assert!(
+        Config::load_with(&vfs, &root).is_err(),
+        "aiken.toml only accepts Plutus V3"
+    );
+
+    // Configurations built programmatically can still target another version.
+    let build_v2 = |vfs: &OverlayFs| {
+        let mut project = Project::new_with_config(config.clone(), root.clone(), Silent);
+        project.set_vfs(vfs.clone());
+        project.build(BuildOptions {
+            uplc: true,
+            ..BuildOptions::default()
+        })
+    };
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            pub fn answer() -> Int {
+              42
+            }
+
+            test answer_is_42() {
+              answer() == 42
+            }
+        "#},
+    );
+
+    assert!(build_v2(&vfs).is_ok());
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        "validator foo {\n  mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {\n    True\n  }\n}\n",
+    );
+
+    let errors = build_v2(&vfs).unwrap_err();
+
+    assert!(matches!(
+        errors.as_slice(),
+        [crate::error::Error::UnsupportedPlutusVersion { validator, plutus: PlutusVersion::V2, .. }]
+            if validator == "foo"
+    ));
+}
+
+/// A project depending on a library next to it, both in memory. The library has a single
+/// 'library/utils' module.
+fn with_path_dependency(utils: &str) -> (OverlayFs, PathBuf, PackageName) {
+    let vfs = OverlayFs::in_memory();
+
+    let library = PathBuf::from("/in-memory/library");
+    let root = PathBuf::from("/in-memory/contracts");
+
+    let library_name = PackageName {
+        owner: "test".to_string(),
+        repo: "library".to_string(),
+    };
+
+    let mut library_config = Config::with_dependencies(&library_name, vec![]);
+    library_config.license = Some("MIT".to_string());
+    vfs.insert(
+        library.join("aiken.toml"),
+        toml::to_string_pretty(&library_config).unwrap(),
+    );
+    vfs.insert(library.join("lib/library/utils.ak"), utils);
+
+    let config = Config::with_dependencies(
+        &PackageName {
+            owner: "test".to_string(),
+            repo: "contracts".to_string(),
+        },
+        vec![Dependency {
+            name: library_name.clone(),
+            version: "0.0.0".to_string(),
+            source: Platform::Github,
+            path: Some(library),
+        }],
+    );
+    vfs.insert(
+        root.join("aiken.toml"),
+        toml::to_string_pretty(&config).unwrap(),
+    );
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            use library/utils
+
+            test answer_is_42() {
+              utils.answer() == 42
+            }
+        "#},
+    );
+
+    (vfs, root, library_name)
+}
+
+const UTILS: &str = "pub fn answer() -> Int {\n  42\n}\n";
+
+fn cached_modules(vfs: &OverlayFs) -> usize {
+    vfs.files(&paths::build_cache())
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "cbor")
+        })
+        .count()
+}
+
+#[test]
+fn check_with_path_dependency() {
+    let (vfs, root, library_name) = with_path_dependency(UTILS);
+
+    let result = check(&vfs, &root);
+
+    assert!(result.is_ok(), "{result:#?}");
+
+    // Path dependencies are compiled in place, and never copied into the build folder.
+    assert!(vfs
+        .files(&root.join(paths::build_deps_package(&library_name)))
+        .is_empty());
+
+    assert_eq!(cached_modules(&vfs), 1);
+}
+
+#[test]
+fn check_without_build_cache() {
+    let (vfs, root, _) = with_path_dependency(UTILS);
+
+    let config = Config::load_with(&vfs, &root).unwrap();
+
+    let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project.build_cache = false;
+
+    let result = project.check(CheckOptions {
+        seed: 42,
+        property_max_success: 10,
+        ..CheckOptions::default()
+    });
+
+    assert!(result.is_ok(), "{result:#?}");
+    assert_eq!(cached_modules(&vfs), 0);
+}
+
+#[test]
+fn build_cache_skips_past_cached_type_variables() {
+    let (vfs, root, _) = with_path_dependency(UTILS);
+
+    let check_once = || {
+        let config = Config::load_with(&vfs, &root).unwrap();
+
+        let mut project = Project::new_with_config(config, root.clone(), Silent);
+
+        project.set_vfs(vfs.clone());
+
+        project.check(CheckOptions::default()).unwrap();
+
+        project.id_gen.next()
+    };
+
+    // The dependency gets type-checked and stored, then loaded from the cache by a project
+    // which doesn't type-check it, and would otherwise hand out ids already used by it.
+    let first = check_once();
+    let second = check_once();
+
+    assert_eq!(cached_modules(&vfs), 1);
+    assert!(second >= first, "{second} < {first}");
+}
+
+#[test]
+fn packages_describe_dependencies() {
+    let (vfs, root, library_name) = with_path_dependency(UTILS);
+
+    let mut project =
+        Project::new_with_config(Config::load_with(&vfs, &root).unwrap(), root, Silent);
+
+    project.set_vfs(vfs);
+
+    let packages = project.packages().unwrap();
+
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].name, library_name);
+    assert_eq!(packages[0].path, Some(PathBuf::from("/in-memory/library")));
+    assert_eq!(packages[0].license.as_deref(), Some("MIT"));
+    assert_eq!(
+        packages[0].checksum,
+        crate::attestation::digest([("library/utils", UTILS)].into_iter())
+    );
+}
+
+#[test]
+fn benchmark_with_cost_model() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            fn size(n: Int) -> Fuzzer<Int> {
+              fn(prng: PRNG) -> Option<(PRNG, Int)> { Some((prng, n)) }
+            }
+
+            bench increment(n via size) {
+              n + 1
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("params.json"),
+        serde_json::json!({ "costModels": { "PlutusV3": vec![1; 297] } }).to_string(),
+    );
+
+    let benchmark = |cost_model: Option<PathBuf>| {
+        let config = Config::load_with(&vfs, &root).unwrap();
+
+        let measures = Measures::default();
+
+        let mut project = Project::new_with_config(config, root.clone(), measures.clone());
+
+        project.set_vfs(vfs.clone());
+
+        project
+            .benchmark(None, false, 42, 5, Tracing::silent(), None, cost_model)
+            .unwrap();
+
+        let measures = measures.0.lock().unwrap().clone();
+
+        assert!(!measures.is_empty());
+
+        measures
+    };
+
+    assert_ne!(benchmark(None), benchmark(Some(root.join("params.json"))));
+}
+
+#[test]
+fn prelude_never_imports_itself_back() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let mut config = Config::load_with(&vfs, &root).unwrap();
+    config.prelude = vec!["helpers.{double}".to_string()];
+    vfs.insert(
+        root.join("aiken.toml"),
+        toml::to_string_pretty(&config).unwrap(),
+    );
+
+    vfs.insert(
+        root.join("lib/helpers.ak"),
+        indoc::indoc! {r#"
+            use math
+
+            pub fn double(n: Int) -> Int {
+              math.times(n, 2)
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("lib/math.ak"),
+        indoc::indoc! {r#"
+            pub fn times(a: Int, b: Int) -> Int {
+              a * b
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("lib/tests.ak"),
+        indoc::indoc! {r#"
+            test prelude_is_implicit() {
+              double(21) == 42
+            }
+        "#},
+    );
+
+    assert!(check(&vfs, &root).is_ok());
+}
+
+fn new_project_with_unrelated_error(root: &Path) -> OverlayFs {
+    let vfs = new_project(root);
+
+    vfs.insert(
+        root.join("lib/b.ak"),
+        indoc::indoc! {r#"
+            pub fn answer() -> Int {
+              42
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("lib/a.ak"),
+        indoc::indoc! {r#"
+            use b
+
+            test answer() {
+              b.answer() == 42
+            }
+        "#},
+    );
+
+    vfs.insert(
+        root.join("lib/c.ak"),
+        indoc::indoc! {r#"
+            pub fn broken() -> Int {
+              "not an int"
+            }
+        "#},
+    );
+
+    vfs
+}
+
+#[test]
+fn check_changes_skips_unaffected_modules() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project_with_unrelated_error(&root);
+
+    assert!(check(&vfs, &root).is_err());
+
+    let result = check_changes(&vfs, &root, Some(BTreeSet::from([root.join("lib/b.ak")])));
+
+    assert!(result.is_ok(), "{result:#?}");
+
+    let result = check_changes(&vfs, &root, Some(BTreeSet::from([root.join("lib/c.ak")])));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn check_changes_falls_back_to_everything() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project_with_unrelated_error(&root);
+
+    let result = check_changes(&vfs, &root, Some(BTreeSet::from([root.join("aiken.toml")])));
+
+    assert!(result.is_err());
+
+    let result = check_changes(
+        &vfs,
+        &root,
+        Some(BTreeSet::from([
+            root.join("lib/b.ak"),
+            root.join("lib/deleted.ak"),
+        ])),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn check_changes_ignores_other_files() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project_with_unrelated_error(&root);
+
+    let result = check_changes(
+        &vfs,
+        &root,
+        Some(BTreeSet::from([
+            root.join("lib/b.ak"),
+            root.join("README.md"),
+        ])),
+    );
+
+    assert!(result.is_ok(), "{result:#?}");
+}
+
+fn serve_tests(vfs: &OverlayFs, root: &Path, requests: &[&str]) -> Vec<serde_json::Value> {
+    let config = Config::load_with(vfs, root).unwrap();
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    let mut output = Vec::new();
+
+    project
+        .serve_tests(
+            Cursor::new(requests.join("\n")),
+            &mut output,
+            false,
+            42,
+            10,
+            None,
+            Tracing::silent(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn serve_tests_over_runner_protocol() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            test pass() {
+              True
+            }
+
+            test fail() {
+              1 + 1 == 3
+            }
+        "#},
+    );
+
+    let messages = serve_tests(
+        &vfs,
+        &root,
+        &[
+            "not json",
+            "",
+            r#"{"id": 1, "method": "list"}"#,
+            r#"{"id": 2, "method": "run", "params": {"tests": ["foo.{pass}"], "exact_match": true, "seed": 7}}"#,
+            r#"{"id": 3, "method": "reload"}"#,
+            r#"{"id": "last", "method": "shutdown"}"#,
+            r#"{"id": 5, "method": "list"}"#,
+        ],
+    );
+
+    assert_eq!(messages.len(), 7, "{messages:#?}");
+
+    assert_eq!(messages[0]["event"], "ready");
+    assert_eq!(messages[0]["protocol"], crate::runner::PROTOCOL_VERSION);
+
+    assert_eq!(messages[1]["id"], serde_json::Value::Null);
+    assert!(messages[1]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("invalid request"));
+
+    assert_eq!(
+        messages[2],
+        serde_json::json!({
+            "id": 1,
+            "result": {
+                "tests": [
+                    { "module": "foo", "name": "pass", "kind": "unit" },
+                    { "module": "foo", "name": "fail", "kind": "unit" },
+                ],
+            },
+        })
+    );
+
+    assert_eq!(messages[3]["id"], 2);
+    assert_eq!(messages[3]["event"], "test");
+    assert_eq!(messages[3]["test"]["module"], "foo");
+    assert_eq!(messages[3]["test"]["title"], "pass");
+    assert_eq!(messages[3]["test"]["status"], "pass");
+
+    assert_eq!(messages[4]["id"], 2);
+    assert_eq!(messages[4]["result"]["seed"], 7);
+    assert_eq!(messages[4]["result"]["summary"]["total"], 1);
+    assert_eq!(messages[4]["result"]["summary"]["passed"], 1);
+
+    assert_eq!(messages[5], serde_json::json!({ "id": 3, "result": null }));
+
+    assert_eq!(
+        messages[6],
+        serde_json::json!({ "id": "last", "result": null })
+    );
+}
+
+#[test]
+fn serve_tests_reports_compilation_errors() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            test broken() {
+              "not a bool"
+            }
+        "#},
+    );
+
+    let messages = serve_tests(
+        &vfs,
+        &root,
+        &[
+            r#"{"id": 1, "method": "run"}"#,
+            r#"{"id": 2, "method": "reload"}"#,
+        ],
+    );
+
+    assert_eq!(messages.len(), 3, "{messages:#?}");
+
+    assert_eq!(messages[0]["event"], "ready");
+
+    for (message, id) in messages[1..].iter().zip([1, 2]) {
+        assert_eq!(message["id"], id);
+        assert_eq!(message["error"]["message"], "compilation failed");
+        assert!(!message["error"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+}
+
+fn check_traces(vfs: &OverlayFs, root: &Path) -> Vec<String> {
+    let config = Config::load_with(vfs, root).unwrap();
+
+    let traces = Traces::default();
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), traces.clone());
+
+    project.set_vfs(vfs.clone());
+
+    project
+        .check(CheckOptions {
+            seed: 42,
+            property_max_success: 10,
+            tracing: Tracing::verbose(),
+            ..CheckOptions::default()
+        })
+        .unwrap();
+
+    let traces = traces.0.lock().unwrap();
+
+    traces.clone()
+}
+
+fn new_project_with_traces(root: &Path, trace: TraceConfig) -> OverlayFs {
+    let vfs = OverlayFs::in_memory();
+
+    let mut config = Config::with_dependencies(
+        &PackageName {
+            owner: "test".to_string(),
+            repo: "project".to_string(),
+        },
+        vec![],
+    );
+
+    config.trace = trace;
+
+    vfs.write(
+        &root.join("aiken.toml"),
+        toml::to_string_pretty(&config).unwrap().as_bytes(),
+    )
+    .unwrap();
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            test traced() {
+              trace.debug @"debug"
+              trace.info @"info"
+              trace @"default"
+              True
+            }
+        "#},
+    );
+
+    vfs
+}
+
+#[test]
+fn debug_traces_kept_by_default() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project_with_traces(&root, TraceConfig::default());
+
+    assert_eq!(check_traces(&vfs, &root), vec!["debug", "info", "default"]);
+}
+
+#[test]
+fn debug_traces_compiled_out_when_checking() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project_with_traces(
+        &root,
+        TraceConfig {
+            check: TraceSeverity::Info,
+            build: TraceSeverity::Debug,
+        },
+    );
+
+    assert_eq!(check_traces(&vfs, &root), vec!["info", "default"]);
+}
+
+#[test]
+fn debug_traces_kept_when_only_compiled_out_of_builds() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project_with_traces(
+        &root,
+        TraceConfig {
+            check: TraceSeverity::Debug,
+            build: TraceSeverity::Info,
+        },
+    );
+
+    assert_eq!(check_traces(&vfs, &root), vec!["debug", "info", "default"]);
+}
+
+#[test]
+fn build_rejects_validators_colliding_on_title() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let validator = indoc::indoc! {r#"
+        validator bar {
+          mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {
+            True
+          }
+        }
+    "#};
+
+    vfs.insert(root.join("validators/foo.ak"), validator);
+    vfs.insert(root.join("validators/Foo.ak"), validator);
+
+    let errors = build(&vfs, &root, None).unwrap_err();
+
+    // One for the 'mint' handler, one for the fallback.
+    assert_eq!(errors.len(), 2, "{errors:#?}");
+
+    for error in errors {
+        let crate::error::Error::ValidatorNameCollision {
+            title,
+            path,
+            first_path,
+            ..
+        } = error
+        else {
+            panic!("unexpected error: {error:#?}");
+        };
+
+        assert!(title.to_lowercase().starts_with("foo.bar."), "{title}");
+        assert_ne!(path, first_path);
+    }
+}
+
+#[test]
+fn build_accepts_validators_sharing_a_name_across_modules() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let validator = indoc::indoc! {r#"
+        validator bar {
+          mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {
+            True
+          }
+        }
+    "#};
+
+    vfs.insert(root.join("validators/foo.ak"), validator);
+    vfs.insert(root.join("validators/baz.ak"), validator);
+
+    let result = build(&vfs, &root, None);
+
+    assert!(result.is_ok(), "{result:#?}");
+}
+
+fn publish(vfs: &OverlayFs, root: &Path) -> Result<(), Vec<crate::error::Error>> {
+    let config = Config::load_with(vfs, root).map_err(|e| vec![e])?;
+
+    let mut project = Project::new_with_config(config, root.to_path_buf(), Silent);
+
+    project.set_vfs(vfs.clone());
+
+    project.publish(None, 42)
+}
+
+fn new_publishable_project(root: &Path) -> OverlayFs {
+    let vfs = new_project(root);
+
+    let mut config = Config::load_with(&vfs, root).unwrap();
+    config.version = "1.0.0".to_string();
+    vfs.write(
+        &root.join("aiken.toml"),
+        toml::to_string_pretty(&config).unwrap().as_bytes(),
+    )
+    .unwrap();
+
+    vfs
+}
+
+#[test]
+fn publish_requires_complete_metadata() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_project(&root);
+
+    let errors = publish(&vfs, &root).unwrap_err();
+
+    assert!(matches!(
+        errors.as_slice(),
+        [crate::error::Error::IncompletePackageMetadata { problems }]
+            if problems.len() == 1 && problems[0].contains("version")
+    ));
+}
+
+#[test]
+fn publish_requires_documentation() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_publishable_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            /// The answer.
+            pub fn answer() -> Int {
+              42
+            }
+
+            pub fn question() -> String {
+              @"?"
+            }
+
+            fn private() -> Int {
+              14
+            }
+        "#},
+    );
+
+    let errors = publish(&vfs, &root).unwrap_err();
+
+    assert!(matches!(
+        errors.as_slice(),
+        [crate::error::Error::UndocumentedDefinitions { definitions }]
+            if definitions == &["foo.{question}".to_string()]
+    ));
+}
+
+#[test]
+fn publish_bundles_sources_and_docs() {
+    let root = PathBuf::from("/in-memory/project");
+
+    let vfs = new_publishable_project(&root);
+
+    vfs.insert(
+        root.join("lib/foo.ak"),
+        indoc::indoc! {r#"
+            /// The answer.
+            pub fn answer() -> Int {
+              42
+            }
+
+            test answer_is_42() {
+              answer() == 42
+            }
+        "#},
+    );
+
+    vfs.insert(root.join("README.md"), "# project");
+
+    vfs.insert(
+        root.join("validators/foo.ak"),
+        "validator foo {\n  mint(_redeemer: Data, _policy_id: ByteArray, _transaction: Data) {\n    True\n  }\n}\n",
+    );
+
+    assert!(publish(&vfs, &root).is_ok());
+
+    let bytes = vfs
+        .read(&root.join("build/publish/test-project-1.0.0.zip"))
+        .unwrap();
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+    let names = zip.file_names().map(String::from).collect::<Vec<_>>();
+
+    for name in [
+        "aiken.toml",
+        "README.md",
+        "lib/foo.ak",
+        "docs/foo.html",
+        "CHECKSUMS",
+    ] {
+        assert!(names.contains(&name.to_string()), "missing {name}");
+    }
+
+    assert!(!names.iter().any(|name| name.starts_with("validators/")));
+
+    let mut checksums = String::new();
+    std::io::Read::read_to_string(&mut zip.by_name("CHECKSUMS").unwrap(), &mut checksums).unwrap();
+
+    assert_eq!(checksums.lines().count(), names.len() - 1);
+}
@@ -6,8 +6,8 @@ use crate::{
 };
 use aiken_lang::{
     ast::{
-        DataTypeKey, FunctionAccessKey, ModuleKind, TraceLevel, Tracing, TypedDataType,
-        TypedFunction,
+        DataTypeKey, FunctionAccessKey, ModuleKind, TraceLevel, TraceSeverity, Tracing,
+        TypedDataType, TypedFunction,
     },
     expr::TypedExpr,
     gen_uplc::CodeGenerator,
@@ -21,6 +21,7 @@ use indexmap::IndexMap;
 use std::{collections::HashMap, path::PathBuf};
 
 mod gen_uplc;
+mod in_memory;
 
 // TODO: Possible refactor this out of the module and have it used by `Project`. The idea would
 // be to make this struct below the actual project, and wrap it in another metadata struct
@@ -44,9 +45,7 @@ impl TestProject {
             repo: "project".to_owned(),
         };
 
-        let mut module_types = HashMap::new();
-        module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
-        module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+        let module_types = builtins::modules(&id_gen);
 
         let functions = builtins::prelude_functions(&id_gen, &module_types);
         let data_types = builtins::prelude_data_types(&id_gen);
@@ -89,6 +88,7 @@ impl TestProject {
             path: PathBuf::new(),
             extra,
             package: self.package.to_string(),
+            internal: false,
         }
     }
 
@@ -103,6 +103,7 @@ impl TestProject {
                 &self.package.to_string(),
                 &self.module_types,
                 Tracing::All(TraceLevel::Verbose),
+                TraceSeverity::Debug,
                 &mut warnings,
                 None,
             )
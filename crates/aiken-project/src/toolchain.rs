@@ -0,0 +1,325 @@
+//! Installing and pinning compiler versions. Toolchains are release binaries downloaded
+//! from GitHub into a per-user directory, one per version, once checked against the checksum
+//! published alongside them. Projects pin a version through a `.aiken-version` file or,
+//! failing that, the `toolchain` field of their `aiken.toml`. The `compiler` field written by
+//! `aiken new` doesn't pin anything: it only records the version a project was created with.
+
+use crate::{error::Error, github::repo::LatestRelease, paths, settings};
+use cryptoxide::{digest::Digest, sha2::Sha256};
+use reqwest::{blocking::Client, header::USER_AGENT, StatusCode};
+pub use semver::Version;
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use walkdir::WalkDir;
+
+pub const VERSION_FILE: &str = ".aiken-version";
+
+/// Set on toolchains started by another toolchain, so that they don't dispatch again.
+pub const DISPATCHED_ENV_VAR: &str = "AIKEN_TOOLCHAIN_DISPATCHED";
+
+const REPOSITORY: &str = "aiken-lang/aiken";
+
+/// Where a project's compiler version comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pin {
+    VersionFile(PathBuf),
+    Manifest(PathBuf),
+}
+
+/// The executable of an installed toolchain, whether or not it actually exists.
+pub fn binary(version: &Version) -> PathBuf {
+    paths::toolchains()
+        .join(format!("v{version}"))
+        .join(format!("aiken{}", std::env::consts::EXE_SUFFIX))
+}
+
+pub fn is_installed(version: &Version) -> bool {
+    binary(version).is_file()
+}
+
+/// Versions installed so far, from the oldest to the most recent.
+pub fn installed() -> Vec<Version> {
+    let mut versions = fs::read_dir(paths::toolchains())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| parse_version(&entry.file_name().to_string_lossy()))
+        .filter(is_installed)
+        .collect::<Vec<_>>();
+
+    versions.sort();
+
+    versions
+}
+
+/// Parse a version as written by users, with or without a leading 'v'.
+pub fn parse_version(version: &str) -> Option<Version> {
+    Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
+/// The latest version published on GitHub.
+pub fn latest() -> Result<Version, Error> {
+    if settings::offline() {
+        return Err(Error::Offline {
+            action: "look up the latest release".to_string(),
+        });
+    }
+
+    let release = LatestRelease::of(REPOSITORY)?;
+
+    parse_version(&release.tag_name).ok_or_else(|| Error::Toolchain {
+        version: release.tag_name.clone(),
+        reason: "The latest release doesn't carry a valid version.".to_string(),
+    })
+}
+
+/// The compiler version pinned for the project containing the given directory, if any. The
+/// closest directory holding either a version file or a manifest wins; in a same directory,
+/// the version file has precedence over the manifest.
+pub fn pinned(directory: &Path) -> Option<(Version, Pin)> {
+    for dir in directory.ancestors() {
+        let version_file = dir.join(VERSION_FILE);
+
+        if let Ok(content) = fs::read_to_string(&version_file) {
+            return parse_version(&content)
+                .map(|version| (version, Pin::VersionFile(version_file)));
+        }
+
+        let manifest = dir.join(paths::project_config());
+
+        if let Ok(content) = fs::read_to_string(&manifest) {
+            return content
+                .parse::<toml::Table>()
+                .ok()
+                .and_then(|table| parse_version(table.get("toolchain")?.as_str()?))
+                .map(|version| (version, Pin::Manifest(manifest)));
+        }
+    }
+
+    None
+}
+
+/// Pin the given version for the project in the given directory.
+pub fn pin(directory: &Path, version: &Version) -> Result<PathBuf, Error> {
+    let version_file = directory.join(VERSION_FILE);
+
+    fs::write(&version_file, format!("v{version}\n")).map_err(|error| Error::FileIo {
+        error,
+        path: version_file.clone(),
+    })?;
+
+    Ok(version_file)
+}
+
+/// Download and unpack the release of the given version for the current platform. Returns
+/// the path to the installed executable.
+pub fn install(version: &Version) -> Result<PathBuf, Error> {
+    let failure = |reason: String| Error::Toolchain {
+        version: format!("v{version}"),
+        reason,
+    };
+
+    let target = release_target().ok_or_else(|| {
+        failure(format!(
+            "There's no pre-built release for your platform ({}, {}).",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+
+    if settings::offline() {
+        return Err(Error::Offline {
+            action: format!("download the toolchain v{version}"),
+        });
+    }
+
+    let archive = if cfg!(windows) { "zip" } else { "tar.gz" };
+
+    let url = format!(
+        "https://github.com/{REPOSITORY}/releases/download/v{version}/aiken-{target}.{archive}"
+    );
+
+    let bytes = download(&url)?
+        .ok_or_else(|| failure(format!("There's no release v{version} for {target}.")))?;
+
+    // Releases are executed on behalf of the user whenever a project pins them, so they are
+    // never unpacked without matching the checksum published next to them.
+    let checksum = download(&format!("{url}.sha256"))?.ok_or_else(|| {
+        failure(format!(
+            "The release v{version} for {target} doesn't come with a checksum, so I can't verify it."
+        ))
+    })?;
+
+    let expected = String::from_utf8_lossy(&checksum)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let actual = sha256(&bytes);
+
+    if actual != expected {
+        return Err(failure(format!(
+            "The downloaded archive doesn't match its published checksum (expected {expected}, got {actual})."
+        )));
+    }
+
+    let destination = binary(version);
+
+    let directory = destination
+        .parent()
+        .expect("toolchain binary has a parent directory");
+
+    let staging = paths::toolchains().join(format!("v{version}.partial"));
+
+    let _ = fs::remove_dir_all(&staging);
+
+    fs::create_dir_all(&staging).map_err(|error| Error::FileIo {
+        error,
+        path: staging.clone(),
+    })?;
+
+    if cfg!(windows) {
+        zip::ZipArchive::new(Cursor::new(bytes))?.extract(&staging)?;
+    } else {
+        let tarball = staging.join("release.tar.gz");
+
+        fs::write(&tarball, &bytes).map_err(|error| Error::FileIo {
+            error,
+            path: tarball.clone(),
+        })?;
+
+        let output = Command::new("tar")
+            .arg("-xzf")
+            .arg(&tarball)
+            .arg("-C")
+            .arg(&staging)
+            .output()
+            .map_err(|e| failure(format!("I couldn't run 'tar': {e}")))?;
+
+        if !output.status.success() {
+            return Err(failure(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+    }
+
+    let executable = WalkDir::new(&staging)
+        .into_iter()
+        .flatten()
+        .find(|entry| {
+            entry.file_type().is_file()
+                && entry.file_name() == destination.file_name().unwrap_or_default()
+        })
+        .map(|entry| entry.into_path())
+        .ok_or_else(|| {
+            failure("The release archive doesn't contain any executable.".to_string())
+        })?;
+
+    fs::create_dir_all(directory).map_err(|error| Error::FileIo {
+        error,
+        path: directory.to_path_buf(),
+    })?;
+
+    fs::rename(&executable, &destination).map_err(|error| Error::FileIo {
+        error,
+        path: destination.clone(),
+    })?;
+
+    let _ = fs::remove_dir_all(&staging);
+
+    Ok(destination)
+}
+
+/// Fetch a release asset; 'None' when it doesn't exist.
+fn download(url: &str) -> Result<Option<Vec<u8>>, Error> {
+    let response = Client::new().get(url).header(USER_AGENT, "aiken").send()?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    Ok(Some(response.error_for_status()?.bytes()?.to_vec()))
+}
+
+/// Hex-encoded SHA-256 digest, as published by releases.
+fn sha256(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+    hasher.result_str()
+}
+
+/// The target triple under which releases for the current platform are published.
+fn release_target() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("linux", "x86_64") => Some("x86_64-unknown-linux-musl"),
+        ("windows", "x86_64") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_file_takes_precedence_over_manifest() {
+        let root = std::env::temp_dir().join(format!("aiken-toolchain-{}", std::process::id()));
+        let nested = root.join("validators");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(
+            root.join("aiken.toml"),
+            "name = \"foo/bar\"\nversion = \"0.0.0\"\ncompiler = \"v1.0.0\"\ntoolchain = \"v1.1.3\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            pinned(&nested),
+            Some((
+                Version::new(1, 1, 3),
+                Pin::Manifest(root.join("aiken.toml"))
+            ))
+        );
+
+        let version_file = pin(&root, &Version::new(1, 0, 29)).unwrap();
+
+        assert_eq!(
+            pinned(&nested),
+            Some((Version::new(1, 0, 29), Pin::VersionFile(version_file)))
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn compiler_field_does_not_pin() {
+        let root =
+            std::env::temp_dir().join(format!("aiken-toolchain-compiler-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+
+        fs::write(
+            root.join("aiken.toml"),
+            "name = \"foo/bar\"\nversion = \"0.0.0\"\ncompiler = \"v1.1.3\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(pinned(&root), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn sha256_digest() {
+        assert_eq!(
+            sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,478 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// The file system, as seen by the compilation pipeline. Projects go through it for every read
+/// and write, so that they can be compiled from something else than the disk; for example, the
+/// unsaved buffers of an editor.
+///
+/// Dependencies are resolved through it too (their manifest, and the sources of path
+/// dependencies), but the rest of package management (downloading packages, publishing,
+/// installing toolchains) is out of its scope and deliberately works on the actual disk.
+pub trait Vfs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    /// Move a file, replacing whatever file was at the destination.
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    /// Remove a directory, provided it is empty.
+    fn remove_dir(&self, path: &Path) -> io::Result<()>;
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// All files under a directory, recursively. Unreadable entries are skipped, and a missing
+    /// directory has no files.
+    fn files(&self, dir: &Path) -> Vec<PathBuf>;
+
+    /// All directories under a directory (excluding itself), recursively, nested ones first.
+    fn dirs(&self, dir: &Path) -> Vec<PathBuf>;
+
+    fn is_empty_dir(&self, path: &Path) -> bool;
+}
+
+/// The actual file system.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFs;
+
+impl Vfs for OsFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(long_path(path))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(long_path(path))
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(long_path(path), contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(long_path(path))
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(long_path(path))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(long_path(from), long_path(to))
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir(long_path(path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(long_path(path)).map(|path| short_path(&path))
+    }
+
+    fn files(&self, dir: &Path) -> Vec<PathBuf> {
+        walkdir::WalkDir::new(long_path(dir))
+            .follow_links(true)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| short_path(e.path()))
+            .collect()
+    }
+
+    fn dirs(&self, dir: &Path) -> Vec<PathBuf> {
+        walkdir::WalkDir::new(long_path(dir))
+            .contents_first(true)
+            .min_depth(1)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_dir())
+            .map(|e| short_path(e.path()))
+            .collect()
+    }
+
+    fn is_empty_dir(&self, path: &Path) -> bool {
+        fs::read_dir(long_path(path)).is_ok_and(|mut entries| entries.next().is_none())
+    }
+}
+
+/// In-memory files laid over another file system (or over nothing at all), such as the unsaved
+/// buffers of an editor. Reads see overlaid files first; writes and removals only ever affect the
+/// overlay. Clones share the same overlay, so files can be updated after handing a clone over
+/// to a project.
+#[derive(Clone, Default)]
+pub struct OverlayFs {
+    base: Option<Rc<dyn Vfs>>,
+    overlay: Rc<RefCell<Overlay>>,
+}
+
+#[derive(Default)]
+struct Overlay {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    removed: BTreeSet<PathBuf>,
+}
+
+impl OverlayFs {
+    /// An overlay on top of the given file system.
+    pub fn new(base: impl Vfs + 'static) -> Self {
+        OverlayFs {
+            base: Some(Rc::new(base)),
+            overlay: Rc::default(),
+        }
+    }
+
+    /// A file system living entirely in memory.
+    pub fn in_memory() -> Self {
+        OverlayFs::default()
+    }
+
+    /// Overlay a file with the given content.
+    pub fn insert(&self, path: impl Into<PathBuf>, content: impl Into<String>) {
+        let path = path.into();
+        let mut overlay = self.overlay.borrow_mut();
+        overlay.removed.remove(&path);
+        overlay.files.insert(path, content.into().into_bytes());
+    }
+
+    /// Drop any overlaid content for a file, revealing the underlying one again.
+    pub fn forget(&self, path: &Path) {
+        let mut overlay = self.overlay.borrow_mut();
+        overlay.removed.remove(path);
+        overlay.files.remove(path);
+    }
+
+    pub fn get(&self, path: &Path) -> Option<String> {
+        self.get_bytes(path)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn get_bytes(&self, path: &Path) -> Option<Vec<u8>> {
+        self.overlay.borrow().files.get(path).cloned()
+    }
+
+    fn is_removed(&self, path: &Path) -> bool {
+        self.overlay.borrow().removed.contains(path)
+    }
+
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not found", path.display()),
+        )
+    }
+}
+
+impl Vfs for OverlayFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        if let Some(content) = self.get_bytes(path) {
+            return String::from_utf8(content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+        }
+
+        match &self.base {
+            Some(base) if !self.is_removed(path) => base.read_to_string(path),
+            _ => Err(Self::not_found(path)),
+        }
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if let Some(content) = self.get_bytes(path) {
+            return Ok(content);
+        }
+
+        match &self.base {
+            Some(base) if !self.is_removed(path) => base.read(path),
+            _ => Err(Self::not_found(path)),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut overlay = self.overlay.borrow_mut();
+        overlay.removed.remove(path);
+        overlay.files.insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+
+    fn create_dir_all(&self, _path: &Path) -> io::Result<()> {
+        // Directories only exist through the files they contain.
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut overlay = self.overlay.borrow_mut();
+        overlay.files.remove(path);
+        overlay.removed.insert(path.to_path_buf());
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let contents = self.read(from)?;
+        self.write(to, &contents)?;
+        self.remove_file(from)
+    }
+
+    fn remove_dir(&self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        match &self.base {
+            Some(base) if self.get_bytes(path).is_none() => base.canonicalize(path),
+            _ => Ok(path.to_path_buf()),
+        }
+    }
+
+    fn files(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut files = self
+            .base
+            .as_ref()
+            .map(|base| base.files(dir))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| !self.is_removed(path))
+            .collect::<BTreeSet<_>>();
+
+        files.extend(
+            self.overlay
+                .borrow()
+                .files
+                .keys()
+                .filter(|path| path.starts_with(dir))
+                .cloned(),
+        );
+
+        files.into_iter().collect()
+    }
+
+    fn dirs(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut dirs = self
+            .base
+            .as_ref()
+            .map(|base| base.dirs(dir))
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+
+        for path in self.overlay.borrow().files.keys() {
+            dirs.extend(
+                path.ancestors()
+                    .skip(1)
+                    .take_while(|ancestor| ancestor.starts_with(dir) && *ancestor != dir)
+                    .map(Path::to_path_buf),
+            );
+        }
+
+        let mut dirs = dirs.into_iter().collect::<Vec<_>>();
+
+        dirs.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+
+        dirs
+    }
+
+    fn is_empty_dir(&self, path: &Path) -> bool {
+        self.files(path).is_empty() && self.dirs(path).is_empty()
+    }
+}
+
+/// Windows limits paths to 260 characters, unless they are absolute and use the verbatim
+/// ('\\?\') prefix, which also disables the conversion of '/' into '\'. So we add the prefix,
+/// and normalize separators ourselves, for paths that need it. This is a no-op elsewhere.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    const MAX_PATH: usize = 260;
+
+    let raw = path.to_string_lossy();
+
+    if !cfg!(windows) || raw.len() < MAX_PATH || !path.is_absolute() || raw.starts_with(r"\\?\") {
+        return Cow::Borrowed(path);
+    }
+
+    let raw = raw.replace('/', r"\");
+
+    Cow::Owned(PathBuf::from(match raw.strip_prefix(r"\\") {
+        Some(unc) => format!(r"\\?\UNC\{unc}"),
+        None => format!(r"\\?\{raw}"),
+    }))
+}
+
+/// The inverse of 'long_path', so that paths reported back to users look like the ones they
+/// gave us.
+pub fn short_path(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+
+    match raw.strip_prefix(r"\\?\") {
+        Some(rest) if cfg!(windows) => match rest.strip_prefix(r"UNC\") {
+            Some(unc) => PathBuf::from(format!(r"\\{unc}")),
+            None => PathBuf::from(rest),
+        },
+        _ => path.to_path_buf(),
+    }
+}
+
+/// A path relative to some root, with '/' as separator regardless of the platform; which is how
+/// paths end up in module names, blueprints and other artifacts meant to be portable.
+pub fn to_slash(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{long_path, short_path, to_slash, OsFs, OverlayFs, Vfs};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aiken-vfs-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn os_reads_writes_and_lists() {
+        let root = temp_dir("os");
+        let vfs = OsFs;
+
+        vfs.create_dir_all(&root.join("lib/nested")).unwrap();
+        vfs.write(&root.join("lib/a.ak"), b"a").unwrap();
+        vfs.write(&root.join("lib/nested/b.ak"), b"b").unwrap();
+
+        assert_eq!(vfs.read_to_string(&root.join("lib/a.ak")).unwrap(), "a");
+        assert_eq!(vfs.read(&root.join("lib/nested/b.ak")).unwrap(), b"b");
+
+        let mut files = vfs.files(&root.join("lib"));
+        files.sort();
+        assert_eq!(
+            files,
+            vec![root.join("lib/a.ak"), root.join("lib/nested/b.ak")]
+        );
+        assert_eq!(
+            vfs.dirs(&root),
+            vec![root.join("lib/nested"), root.join("lib")]
+        );
+        assert!(vfs.files(&root.join("missing")).is_empty());
+
+        assert!(!vfs.is_empty_dir(&root.join("lib/nested")));
+        vfs.remove_file(&root.join("lib/nested/b.ak")).unwrap();
+        assert!(vfs.is_empty_dir(&root.join("lib/nested")));
+        vfs.remove_dir(&root.join("lib/nested")).unwrap();
+        assert!(vfs.read(&root.join("lib/nested/b.ak")).is_err());
+        assert!(vfs.remove_dir(&root.join("lib")).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_follows_links() {
+        let root = temp_dir("links");
+        let outside = temp_dir("links-outside");
+        let vfs = OsFs;
+
+        vfs.create_dir_all(&root.join("lib")).unwrap();
+        vfs.write(&root.join("lib/a.ak"), b"a").unwrap();
+        vfs.write(&outside.join("b.ak"), b"b").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("lib/shared")).unwrap();
+        std::os::unix::fs::symlink(root.join("lib"), root.join("lib/loop")).unwrap();
+
+        let mut files = vfs.files(&root.join("lib"));
+        files.sort();
+        assert_eq!(
+            files,
+            vec![root.join("lib/a.ak"), root.join("lib/shared/b.ak")]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn overlay_reads_writes_and_lists() {
+        let base = OverlayFs::in_memory();
+        base.insert("/p/lib/a.ak", "a");
+        base.insert("/p/lib/b.ak", "b");
+
+        let vfs = OverlayFs::new(base.clone());
+        vfs.insert("/p/lib/a.ak", "edited");
+        vfs.insert("/p/lib/nested/c.ak", "c");
+
+        assert_eq!(
+            vfs.read_to_string(Path::new("/p/lib/a.ak")).unwrap(),
+            "edited"
+        );
+        assert_eq!(vfs.read_to_string(Path::new("/p/lib/b.ak")).unwrap(), "b");
+        assert_eq!(
+            vfs.files(Path::new("/p/lib")),
+            ["/p/lib/a.ak", "/p/lib/b.ak", "/p/lib/nested/c.ak"].map(PathBuf::from)
+        );
+        assert_eq!(
+            vfs.dirs(Path::new("/p")),
+            ["/p/lib/nested", "/p/lib"].map(PathBuf::from)
+        );
+
+        vfs.remove_file(Path::new("/p/lib/b.ak")).unwrap();
+        vfs.write(Path::new("/p/plutus.json"), b"{}").unwrap();
+        vfs.write(Path::new("/p/build/a.cbor.tmp"), &[0xff, 0x00])
+            .unwrap();
+        vfs.rename(
+            Path::new("/p/build/a.cbor.tmp"),
+            Path::new("/p/build/a.cbor"),
+        )
+        .unwrap();
+        vfs.forget(Path::new("/p/lib/a.ak"));
+
+        assert!(vfs.read_to_string(Path::new("/p/lib/b.ak")).is_err());
+        assert_eq!(vfs.read_to_string(Path::new("/p/lib/a.ak")).unwrap(), "a");
+        assert_eq!(
+            vfs.read_to_string(Path::new("/p/plutus.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            vfs.read(Path::new("/p/build/a.cbor")).unwrap(),
+            [0xff, 0x00]
+        );
+        assert!(vfs.read(Path::new("/p/build/a.cbor.tmp")).is_err());
+        assert!(vfs.read_to_string(Path::new("/p/build/a.cbor")).is_err());
+        assert_eq!(base.read_to_string(Path::new("/p/lib/b.ak")).unwrap(), "b");
+        assert!(base.read_to_string(Path::new("/p/plutus.json")).is_err());
+    }
+
+    #[test]
+    fn to_slash_joins_components() {
+        assert_eq!(to_slash(Path::new("lib/foo/bar.ak")), "lib/foo/bar.ak");
+        assert_eq!(to_slash(Path::new("bar.ak")), "bar.ak");
+    }
+
+    #[test]
+    fn short_paths_are_left_alone() {
+        let path = Path::new("/tmp/project/lib/foo.ak");
+
+        assert_eq!(long_path(path), path);
+        assert_eq!(short_path(&long_path(path)), path);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn long_paths_are_verbatim_on_windows() {
+        let path = format!(r"C:\{}\foo.ak", "a".repeat(300));
+
+        let long = long_path(Path::new(&path));
+
+        assert!(long.to_string_lossy().starts_with(r"\\?\C:\"));
+        assert_eq!(short_path(&long), Path::new(&path));
+    }
+}
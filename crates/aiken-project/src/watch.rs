@@ -92,6 +92,20 @@ pub fn with_project<A>(
     directory: Option<&Path>,
     deny: bool,
     json: bool,
+    action: A,
+) -> miette::Result<()>
+where
+    A: FnMut(&mut Project<EventTarget>) -> Result<(), Vec<crate::error::Error>>,
+{
+    with_project_target(directory, deny, json, EventTarget::default(), action)
+}
+
+/// Like 'with_project', but reporting progress events to the given target.
+pub fn with_project_target<A>(
+    directory: Option<&Path>,
+    deny: bool,
+    json: bool,
+    event_target: EventTarget,
     mut action: A,
 ) -> miette::Result<()>
 where
@@ -107,7 +121,7 @@ where
         current_dir
     };
 
-    let mut project = match Project::new(project_path, EventTarget::default()) {
+    let mut project = match Project::new(project_path, event_target) {
         Ok(p) => Ok(p),
         Err(e) => {
             e.report();
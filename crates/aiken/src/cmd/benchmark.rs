@@ -41,6 +41,11 @@ pub struct Args {
     /// Environment to use for benchmarking
     env: Option<String>,
 
+    /// Protocol parameters (JSON) to read the cost model from when measuring benchmarks. Both
+    /// cardano-cli and Blockfrost formats are supported. Defaults to the built-in cost model.
+    #[clap(long, value_name = "FILEPATH")]
+    cost_model: Option<PathBuf>,
+
     /// Filter traces to be included in the generated program(s).
     ///
     ///   - user-defined:
@@ -78,6 +83,7 @@ pub fn exec(
         seed,
         max_size,
         env,
+        cost_model,
         trace_filter,
         trace_level,
     }: Args,
@@ -101,6 +107,7 @@ pub fn exec(
                     None => Tracing::All(trace_level),
                 },
                 env.clone(),
+                cost_model.clone(),
             )
         },
     );
@@ -0,0 +1,68 @@
+use aiken_project::{
+    blueprint::{
+        audit::{self, ArtifactDifference},
+        error::Error as BlueprintError,
+    },
+    error::Error as ProjectError,
+};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::{fs, path::PathBuf, process};
+
+/// Compare the compiled validators of two blueprints, e.g. to audit that builds are
+/// deterministic. Exits with a non-zero status when they differ.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the first blueprint
+    #[clap(value_name = "FILEPATH")]
+    left: PathBuf,
+
+    /// Path to the second blueprint
+    #[clap(value_name = "FILEPATH")]
+    right: PathBuf,
+}
+
+pub fn exec(Args { left, right }: Args) -> miette::Result<()> {
+    let read = |path: &PathBuf| {
+        fs::read_to_string(path)
+            .map_err(|_| ProjectError::from(BlueprintError::InvalidOrMissingFile))
+    };
+
+    let differences = read(&left)
+        .and_then(|left| read(&right).map(|right| (left, right)))
+        .and_then(|(left, right)| audit::compare(&left, &right).map_err(ProjectError::from));
+
+    let differences = match differences {
+        Ok(differences) => differences,
+        Err(err) => {
+            err.report();
+            process::exit(1)
+        }
+    };
+
+    if differences.is_empty() {
+        println!(
+            "{}",
+            "Both blueprints have identical validators.".if_supports_color(Stdout, |s| s.green())
+        );
+        return Ok(());
+    }
+
+    for difference in differences {
+        match difference {
+            ArtifactDifference::OnlyInLeft { title } => {
+                println!("{} {title}", "-".if_supports_color(Stdout, |s| s.red()))
+            }
+            ArtifactDifference::OnlyInRight { title } => {
+                println!("{} {title}", "+".if_supports_color(Stdout, |s| s.green()))
+            }
+            ArtifactDifference::Changed { title, left, right } => println!(
+                "{} {title}: {} → {}",
+                "~".if_supports_color(Stdout, |s| s.yellow()),
+                left.if_supports_color(Stdout, |s| s.red()),
+                right.if_supports_color(Stdout, |s| s.green()),
+            ),
+        }
+    }
+
+    process::exit(1)
+}
@@ -0,0 +1,81 @@
+use aiken_project::{
+    blueprint::{
+        compatibility::{self, Severity},
+        error::Error as BlueprintError,
+        Blueprint,
+    },
+    error::Error as ProjectError,
+};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::{fs, path::PathBuf, process};
+
+/// Report changes between two builds of a contract which break existing datums, redeemers or
+/// parameters, or change script hashes. Exits with a non-zero status on breaking changes.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the blueprint of the previous build
+    #[clap(value_name = "FILEPATH")]
+    old: PathBuf,
+
+    /// Path to the blueprint of the new build
+    #[clap(value_name = "FILEPATH")]
+    new: PathBuf,
+}
+
+pub fn exec(Args { old, new }: Args) -> miette::Result<()> {
+    let read = |path: &PathBuf| -> Result<Blueprint, ProjectError> {
+        let json = fs::read_to_string(path)
+            .map_err(|_| ProjectError::from(BlueprintError::InvalidOrMissingFile))?;
+
+        Ok(serde_json::from_str(&json)?)
+    };
+
+    let (old, new) = match read(&old).and_then(|old| read(&new).map(|new| (old, new))) {
+        Ok(blueprints) => blueprints,
+        Err(err) => {
+            err.report();
+            process::exit(1)
+        }
+    };
+
+    let changes = compatibility::diff(&old, &new);
+
+    if changes.is_empty() {
+        println!(
+            "{}",
+            "No changes to validators' interfaces or hashes."
+                .if_supports_color(Stdout, |s| s.green())
+        );
+        return Ok(());
+    }
+
+    for change in &changes {
+        let severity = match change.severity {
+            Severity::Breaking => "  breaking"
+                .if_supports_color(Stdout, |s| s.red())
+                .if_supports_color(Stdout, |s| s.bold())
+                .to_string(),
+            Severity::Compatible => "compatible"
+                .if_supports_color(Stdout, |s| s.yellow())
+                .if_supports_color(Stdout, |s| s.bold())
+                .to_string(),
+        };
+
+        println!(
+            "{severity} {}: {}",
+            change
+                .validator
+                .if_supports_color(Stdout, |s| s.bright_blue()),
+            change.description
+        );
+    }
+
+    if changes
+        .iter()
+        .any(|change| change.severity == Severity::Breaking)
+    {
+        process::exit(1)
+    }
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
 pub mod address;
 pub mod apply;
+pub mod compare;
 pub mod convert;
+pub mod diff;
 pub mod hash;
 pub mod policy;
 
@@ -14,6 +16,8 @@ pub enum Cmd {
     Hash(hash::Args),
     Apply(apply::Args),
     Convert(convert::Args),
+    Compare(compare::Args),
+    Diff(diff::Args),
 }
 
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
@@ -23,5 +27,7 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
         Cmd::Hash(args) => hash::exec(args),
         Cmd::Apply(args) => apply::exec(args),
         Cmd::Convert(args) => convert::exec(args),
+        Cmd::Compare(args) => compare::exec(args),
+        Cmd::Diff(args) => diff::exec(args),
     }
 }
@@ -1,7 +1,11 @@
 use aiken_lang::ast::{TraceLevel, Tracing};
-use aiken_project::watch::{self, watch_project, with_project};
+use aiken_project::{
+    options::BuildOptions,
+    watch::{self, watch_project, with_project},
+};
 use clap::builder::{MapValueParser, PossibleValuesParser, TypedValueParser};
 use std::{path::PathBuf, process};
+use uplc::optimize::Pass;
 
 #[derive(clap::Args)]
 /// Build an Aiken project
@@ -17,14 +21,32 @@ pub struct Args {
     #[clap(short, long)]
     watch: bool,
 
-    /// Also dump textual uplc
+    /// Also dump textual uplc, in the artifacts directory configured in aiken.toml
+    /// ('artifacts' by default). Stale dumps from previous builds are removed.
     #[clap(short, long)]
     uplc: bool,
 
+    /// Also dump an intermediate representation of the validators. For now, only 'air' is
+    /// supported: it writes the Air stack of each validator to 'validators.air', in the
+    /// artifacts directory.
+    #[clap(long, value_name = "IR", value_parser = PossibleValuesParser::new(["air"]))]
+    emit: Option<String>,
+
+    /// Once written, decode the blueprint's validators back and make sure they re-encode and
+    /// re-hash to the exact same bytes.
+    #[clap(long)]
+    check_artifacts: bool,
+
     /// Environment to build against.
     #[clap(long)]
     env: Option<String>,
 
+    /// Ignore the implicit imports configured under 'prelude' in aiken.toml, as well as the
+    /// built-in prelude: its types and values (but booleans and Void) must then be imported
+    /// explicitly, e.g. 'use aiken.{Option, Some, None}'.
+    #[clap(long)]
+    no_prelude: bool,
+
     /// Optional relative filepath to the generated Plutus blueprint.
     ///
     /// [default: plutus.json]
@@ -37,6 +59,58 @@ pub struct Args {
     )]
     output: Option<PathBuf>,
 
+    /// Only compile validators matching any of these filters.
+    /// You can select all validators of a module with `--filter my_module`.
+    /// You can select a validator with `--filter my_module.my_validator`.
+    /// You can select a single handler with `--filter my_module.my_validator.spend`.
+    #[clap(long, value_name = "VALIDATOR", verbatim_doc_comment)]
+    filter: Option<Vec<String>>,
+
+    /// Replace trace messages with short numeric codes in the generated program(s), and
+    /// write a table mapping codes back to messages alongside the blueprint.
+    #[clap(long)]
+    trace_codes: bool,
+
+    /// Comma-separated list of optimization passes to run, in order, instead of the default
+    /// pipeline (or the one configured under 'optimization_passes' in aiken.toml).
+    /// Available passes: shrink, shrink-once, peephole, curry, share-constants.
+    #[clap(long, value_name = "PASS", value_delimiter = ',', verbatim_doc_comment)]
+    opt_passes: Option<Vec<Pass>>,
+
+    /// Also dump the state of each validator after every run of the given optimization pass,
+    /// to 'validators.<PASS>.uplc' in the artifacts directory.
+    #[clap(long, value_name = "PASS")]
+    emit_after: Option<Pass>,
+
+    /// Also write a provenance file next to the blueprint ('<blueprint>.attestation.json'),
+    /// recording the compiler version, build options, checksums of the sources and dependencies
+    /// and hashes of the artifacts. Check it later on with 'aiken verify'.
+    #[clap(long)]
+    attest: bool,
+
+    /// Embed a marker in each validator so that deployed scripts can be attributed, as a
+    /// constant term which doesn't change what they do (but does change their hash). The
+    /// marker is the given label or, if none, the project's name and version.
+    #[clap(long, value_name = "LABEL", num_args = 0..=1, default_missing_value = "")]
+    metadata: Option<String>,
+
+    /// Also write the table summarizing validators' sizes, hashes and changes since the
+    /// previous build, as markdown.
+    #[clap(long, value_name = "FILEPATH")]
+    summary: Option<PathBuf>,
+
+    /// Also write an HTML report laying out the source of each validator, and of the functions
+    /// it uses, next to the UPLC they compiled to; to 'validators.annotated.html' in the
+    /// artifacts directory. Functions are shown as generated, before optimizations.
+    #[clap(long)]
+    annotate: bool,
+
+    /// Also bundle the blueprint and every other file written by this build (dumps, traces,
+    /// attestation, ...) into a tar archive, with a manifest of their blake2b-256 digests.
+    /// The archive is reproducible: entries are sorted, and carry a fixed timestamp.
+    #[clap(long, value_name = "FILEPATH")]
+    archive: Option<PathBuf>,
+
     /// Filter traces to be included in the generated program(s).
     ///
     ///   - user-defined:
@@ -77,35 +151,77 @@ pub fn exec(
         deny,
         watch,
         uplc,
+        emit,
+        check_artifacts,
         trace_filter,
         trace_level,
         output,
         env,
+        no_prelude,
+        filter,
+        trace_codes,
+        opt_passes,
+        emit_after,
+        attest,
+        metadata,
+        summary,
+        annotate,
+        archive,
     }: Args,
 ) -> miette::Result<()> {
     let result = if watch {
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
-            p.build(
+            if no_prelude {
+                p.disable_prelude();
+            }
+
+            p.build(BuildOptions {
                 uplc,
-                match trace_filter {
+                air: emit.is_some(),
+                check_artifacts,
+                tracing: match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
                 },
-                p.blueprint_path(output.as_deref()),
-                env.clone(),
-            )
+                blueprint_path: p.blueprint_path(output.as_deref()),
+                env: env.clone(),
+                filter: filter.clone(),
+                trace_codes,
+                optimization_passes: opt_passes.clone(),
+                emit_after,
+                attest,
+                metadata: metadata.clone(),
+                summary: summary.clone(),
+                annotate,
+                archive: archive.clone(),
+            })
         })
     } else {
         with_project(directory.as_deref(), deny, false, |p| {
-            p.build(
+            if no_prelude {
+                p.disable_prelude();
+            }
+
+            p.build(BuildOptions {
                 uplc,
-                match trace_filter {
+                air: emit.is_some(),
+                check_artifacts,
+                tracing: match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
                 },
-                p.blueprint_path(output.as_deref()),
-                env.clone(),
-            )
+                blueprint_path: p.blueprint_path(output.as_deref()),
+                env: env.clone(),
+                filter: filter.clone(),
+                trace_codes,
+                optimization_passes: opt_passes.clone(),
+                emit_after,
+                attest,
+                metadata: metadata.clone(),
+                summary: summary.clone(),
+                annotate,
+                archive: archive.clone(),
+            })
         })
     };
 
@@ -4,14 +4,19 @@ use aiken_lang::{
     test_framework::PropertyTest,
 };
 use aiken_project::{
-    telemetry::json_schema,
-    watch::{self, watch_project, with_project},
+    costs::CostsGuard,
+    options::CheckOptions,
+    scratch,
+    telemetry::{json_schema, EventTarget, ReportOptions},
+    watch::{self, watch_project, with_project_target},
 };
+use miette::IntoDiagnostic;
 use rand::prelude::*;
 use std::{
     io::{self, IsTerminal},
     path::PathBuf,
     process,
+    time::Duration,
 };
 
 #[derive(clap::Args)]
@@ -20,6 +25,9 @@ use std::{
     about = color_print::cstr!(r#"
 Type-check an Aiken project and run any tests found.
 
+When given a path to a single module (i.e. a `.ak` file) instead of a project, the module is
+checked on its own, with the standard library available as a dependency.
+
 Test results are printed as stylized outputs when `stdout` is a TTY-capable terminal. If it
 isn't, (e.g. because you are redirecting the output to a file), test results are printed as
 a JSON structured object. Use `--show-json-schema` to see the whole schema.
@@ -28,7 +36,7 @@ a JSON structured object. Use `--show-json-schema` to see the whole schema.
 "#
 ))]
 pub struct Args {
-    /// Path to project
+    /// Path to project, or to a standalone module
     directory: Option<PathBuf>,
 
     /// Deny warnings; warnings will be treated as errors
@@ -39,6 +47,12 @@ pub struct Args {
     #[clap(short, long)]
     skip_tests: bool,
 
+    /// Lint mode: run only the parser and type-checker, and report their diagnostics without
+    /// any progress output. Code generation and tests are skipped. Combine with --deny to fail
+    /// on warnings, e.g. from a pre-commit hook.
+    #[clap(long, conflicts_with = "watch")]
+    only_warnings: bool,
+
     /// When enabled, also pretty-print test UPLC on failure
     #[clap(long)]
     debug: bool,
@@ -52,6 +66,13 @@ pub struct Args {
     #[clap(long)]
     watch: bool,
 
+    /// Serve test runs to another program (e.g. an editor's test explorer) over a stable,
+    /// line-delimited JSON protocol on stdin/stdout, instead of running all tests at once.
+    /// Requests are 'list', 'run', 'reload' and 'shutdown'; see the 'runner' module of
+    /// aiken-project for the full protocol.
+    #[clap(long, conflicts_with_all = ["watch", "only_warnings", "skip_tests"])]
+    runner_protocol: bool,
+
     /// An initial seed to initialize the pseudo-random generator for property-tests.
     #[clap(long, value_name = "UINT")]
     seed: Option<u32>,
@@ -60,6 +81,11 @@ pub struct Args {
     #[clap(long, default_value_t = PropertyTest::DEFAULT_MAX_SUCCESS, value_name="UINT")]
     max_success: usize,
 
+    /// Maximum time, in seconds, a single test may run for before being aborted and reported as
+    /// failed. Tests aren't timed out unless this is set.
+    #[clap(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
     /// Only run tests if they match any of these strings.
     /// You can match a module with `-m aiken/list` or `-m list`.
     /// You can match a test with `-m "aiken/list.{map}"` or `-m "aiken/option.{flatten_1}"`
@@ -75,6 +101,49 @@ pub struct Args {
     #[clap(long)]
     env: Option<String>,
 
+    /// Ignore the implicit imports configured under 'prelude' in aiken.toml, as well as the
+    /// built-in prelude: its types and values (but booleans and Void) must then be imported
+    /// explicitly, e.g. 'use aiken.{Option, Some, None}'.
+    #[clap(long)]
+    no_prelude: bool,
+
+    /// Only type-check and test modules affected by files changed since the given git ref
+    /// (e.g. 'main' or 'HEAD~1'): the changed modules and those importing them, transitively.
+    #[clap(long, value_name = "GIT_REF")]
+    changed_since: Option<String>,
+
+    /// Protocol parameters (JSON) to read the cost model from when evaluating tests. Both
+    /// cardano-cli and Blockfrost formats are supported. Defaults to the built-in cost model.
+    #[clap(long, value_name = "FILEPATH")]
+    cost_model: Option<PathBuf>,
+
+    /// Snapshot the budget spent by each unit test into the given file.
+    #[clap(long, value_name = "FILEPATH", conflicts_with = "verify_costs")]
+    record_costs: Option<PathBuf>,
+
+    /// Fail when a unit test spends more than its budget recorded in the given file (see
+    /// --record-costs). Tests missing from the file are ignored.
+    #[clap(long, value_name = "FILEPATH")]
+    verify_costs: Option<PathBuf>,
+
+    /// Tolerance, in percent, allowed above recorded budgets when using --verify-costs.
+    #[clap(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 0,
+        requires = "verify_costs"
+    )]
+    cost_tolerance: u32,
+
+    /// Maximum number of trace lines to show per test. All traces are shown by default.
+    #[clap(long, value_name = "UINT")]
+    max_trace_lines: Option<usize>,
+
+    /// Show counterexamples in full instead of eliding long bytearrays, strings and lists, and
+    /// deeply nested structures.
+    #[clap(long)]
+    show_full_values: bool,
+
     /// Filter traces to be included in the generated program(s).
     ///
     ///   - user-defined:
@@ -109,16 +178,27 @@ pub fn exec(
         directory,
         deny,
         skip_tests,
+        only_warnings,
         debug,
         show_json_schema,
         match_tests,
         exact_match,
         watch,
+        runner_protocol,
         trace_filter,
         trace_level,
         seed,
         max_success,
+        timeout,
         env,
+        no_prelude,
+        changed_since,
+        cost_model,
+        record_costs,
+        verify_costs,
+        cost_tolerance,
+        max_trace_lines,
+        show_full_values,
     }: Args,
 ) -> miette::Result<()> {
     if show_json_schema {
@@ -126,45 +206,121 @@ pub fn exec(
         std::process::exit(0);
     }
 
+    let directory = match directory {
+        Some(path) if path.extension().is_some_and(|ext| ext == "ak") => {
+            if watch {
+                miette::bail!("--watch isn't supported when checking a standalone module");
+            }
+            Some(scratch::project(&path).into_diagnostic()?)
+        }
+        directory => directory,
+    };
+
+    let costs_guard = match (record_costs, verify_costs) {
+        (Some(path), _) => Some(CostsGuard::Record(path)),
+        (None, Some(path)) => Some(CostsGuard::Verify {
+            path,
+            tolerance: cost_tolerance,
+        }),
+        (None, None) => None,
+    };
+
+    let report = ReportOptions {
+        max_trace_lines,
+        show_full_values,
+    };
+
+    let timeout = timeout.map(Duration::from_secs);
+
     let mut rng = rand::thread_rng();
 
     let seed = seed.unwrap_or_else(|| rng.gen());
 
     let result = if watch {
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
-            p.check(
+            if no_prelude {
+                p.disable_prelude();
+            }
+
+            if let Some(git_ref) = &changed_since {
+                p.only_check_changes_since(git_ref).map_err(|e| vec![e])?;
+            }
+
+            p.check(CheckOptions {
                 skip_tests,
-                match_tests.clone(),
-                debug,
+                match_tests: match_tests.clone(),
+                verbose: debug,
                 exact_match,
                 seed,
+                property_max_success: max_success,
+                timeout,
+                tracing: match trace_filter {
+                    Some(trace_filter) => trace_filter(trace_level),
+                    None => Tracing::All(trace_level),
+                },
+                env: env.clone(),
+                cost_model: cost_model.clone(),
+                costs_guard: costs_guard.clone(),
+                report,
+            })
+        })
+    } else if runner_protocol {
+        with_project_target(directory.as_deref(), deny, false, EventTarget::Quiet, |p| {
+            if no_prelude {
+                p.disable_prelude();
+            }
+
+            p.serve_tests(
+                io::stdin().lock(),
+                io::stdout(),
+                debug,
+                seed,
                 max_success,
+                timeout,
                 match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
                 },
                 env.clone(),
+                cost_model.clone(),
             )
         })
     } else {
-        with_project(
+        with_project_target(
             directory.as_deref(),
             deny,
-            !io::stdout().is_terminal(),
+            !io::stdout().is_terminal() && !only_warnings,
+            if only_warnings {
+                EventTarget::Quiet
+            } else {
+                EventTarget::default()
+            },
             |p| {
-                p.check(
-                    skip_tests,
-                    match_tests.clone(),
-                    debug,
+                if no_prelude {
+                    p.disable_prelude();
+                }
+
+                if let Some(git_ref) = &changed_since {
+                    p.only_check_changes_since(git_ref).map_err(|e| vec![e])?;
+                }
+
+                p.check(CheckOptions {
+                    skip_tests: skip_tests || only_warnings,
+                    match_tests: match_tests.clone(),
+                    verbose: debug,
                     exact_match,
                     seed,
-                    max_success,
-                    match trace_filter {
+                    property_max_success: max_success,
+                    timeout,
+                    tracing: match trace_filter {
                         Some(trace_filter) => trace_filter(trace_level),
                         None => Tracing::All(trace_level),
                     },
-                    env.clone(),
-                )
+                    env: env.clone(),
+                    cost_model: cost_model.clone(),
+                    costs_guard: costs_guard.clone(),
+                    report,
+                })
             },
         )
     };
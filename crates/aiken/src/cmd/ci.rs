@@ -0,0 +1,124 @@
+use aiken_lang::{ast::Tracing, format::FormatOptions};
+use aiken_project::{
+    options::{BuildOptions, CheckOptions},
+    watch::with_project,
+};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use rand::prelude::*;
+use std::path::PathBuf;
+
+#[derive(clap::Args)]
+#[command(
+    verbatim_doc_comment,
+    about = color_print::cstr!(r#"
+Run every verification step of a project in one pass, as a pre-commit hook or CI job would.
+
+The steps are, in order:
+  1. aiken fmt --check
+  2. aiken check --deny
+  3. aiken build --deny --check-artifacts (which also generates the blueprint)
+
+Every step runs even if a previous one failed, and a summary is printed at the end. The command
+exits with a non-zero status if any step failed.
+"#)
+)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Skip tests; only run the type-checker during the check step
+    #[clap(short, long)]
+    skip_tests: bool,
+
+    /// An initial seed to initialize the pseudo-random generator for property-tests.
+    #[clap(long, value_name = "UINT")]
+    seed: Option<u32>,
+
+    /// Environment to check and build against.
+    #[clap(long)]
+    env: Option<String>,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        skip_tests,
+        seed,
+        env,
+    }: Args,
+) -> miette::Result<()> {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+    let mut steps = vec![];
+
+    let format = aiken_project::format::run(
+        false,
+        true,
+        vec![directory
+            .as_deref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| ".".to_string())],
+        FormatOptions::default(),
+    );
+
+    if let Err(errs) = &format {
+        for err in errs {
+            err.report();
+        }
+    }
+
+    steps.push(("fmt", format.is_ok()));
+
+    let check = with_project(directory.as_deref(), true, false, |p| {
+        p.check(CheckOptions {
+            skip_tests,
+            seed,
+            tracing: Tracing::verbose(),
+            env: env.clone(),
+            ..CheckOptions::default()
+        })
+    });
+
+    steps.push(("check", check.is_ok()));
+
+    let build = with_project(directory.as_deref(), true, false, |p| {
+        p.build(BuildOptions {
+            check_artifacts: true,
+            blueprint_path: p.blueprint_path(None),
+            env: env.clone(),
+            ..BuildOptions::default()
+        })
+    });
+
+    steps.push(("build", build.is_ok()));
+
+    eprintln!();
+
+    for (step, passed) in &steps {
+        eprintln!(
+            "{:>13} {}",
+            step.if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            if *passed {
+                "passed"
+                    .if_supports_color(Stderr, |s| s.green())
+                    .to_string()
+            } else {
+                "failed".if_supports_color(Stderr, |s| s.red()).to_string()
+            },
+        );
+    }
+
+    let failed = steps
+        .iter()
+        .filter(|(_, passed)| !passed)
+        .map(|(step, _)| *step)
+        .collect::<Vec<_>>();
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        // Steps already reported their own errors; this only sets the exit status.
+        Err(miette::miette!("failed steps: {}", failed.join(", ")))
+    }
+}
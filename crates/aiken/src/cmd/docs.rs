@@ -1,4 +1,10 @@
-use aiken_project::watch::{self, watch_project, with_project};
+use aiken_project::{
+    docs::serve::Server,
+    package_name::PackageName,
+    watch::{self, watch_project, with_project},
+};
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stderr};
 use std::{path::PathBuf, process};
 
 #[derive(clap::Args)]
@@ -22,6 +28,23 @@ pub struct Args {
     /// Output directory for the documentation
     #[clap(short = 'o', long)]
     destination: Option<PathBuf>,
+
+    /// Generate the documentation of a dependency (e.g. aiken-lang/stdlib) instead, from its
+    /// sources in the build folder. Defaults to writing into the dependency's own folder.
+    #[clap(
+        long,
+        value_name = "OWNER/REPO",
+        conflicts_with = "include_dependencies"
+    )]
+    package: Option<PackageName>,
+
+    /// Serve the documentation over HTTP, rebuilding it and reloading pages on file changes
+    #[clap(long)]
+    serve: bool,
+
+    /// Port to serve the documentation on
+    #[clap(long, default_value_t = 8080, requires = "serve")]
+    port: u16,
 }
 
 pub fn exec(
@@ -31,15 +54,37 @@ pub fn exec(
         watch,
         destination,
         include_dependencies,
+        package,
+        serve,
+        port,
     }: Args,
 ) -> miette::Result<()> {
-    let result = if watch {
+    let result = if serve {
+        let server = Server::start(port).into_diagnostic()?;
+
+        watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
+            let served = p.docs(destination.clone(), include_dependencies, package.clone())?;
+            server.reload(served);
+            eprintln!(
+                "{} documentation at {}",
+                "      Serving"
+                    .if_supports_color(Stderr, |s| s.bold())
+                    .if_supports_color(Stderr, |s| s.purple()),
+                server
+                    .address
+                    .if_supports_color(Stderr, |s| s.bright_blue()),
+            );
+            Ok(())
+        })
+    } else if watch {
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
-            p.docs(destination.clone(), include_dependencies)
+            p.docs(destination.clone(), include_dependencies, package.clone())
+                .map(|_| ())
         })
     } else {
         with_project(directory.as_deref(), deny, false, |p| {
-            p.docs(destination.clone(), include_dependencies)
+            p.docs(destination.clone(), include_dependencies, package.clone())
+                .map(|_| ())
         })
     };
 
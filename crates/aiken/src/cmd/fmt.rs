@@ -1,3 +1,5 @@
+use aiken_lang::format::{FormatOptions, TestPlacement, MAX_COLUMNS};
+
 #[derive(clap::Args)]
 /// Format an Aiken project
 pub struct Args {
@@ -12,6 +14,23 @@ pub struct Args {
     /// Check if inputs are formatted without changing them
     #[clap(long)]
     check: bool,
+
+    /// Maximum line width before breaking expressions over multiple lines
+    #[clap(long, value_name = "UINT", default_value_t = MAX_COLUMNS)]
+    max_columns: isize,
+
+    /// Move tests and benchmarks after all other definitions of a module, instead of leaving them
+    /// next to the definitions they cover
+    #[clap(long)]
+    tests_at_end: bool,
+
+    /// Put every step of a pipeline on its own line as soon as there are two or more pipes
+    #[clap(long)]
+    break_pipelines: bool,
+
+    /// Line up the arrows of 'when' clauses
+    #[clap(long)]
+    align_clause_arrows: bool,
 }
 
 pub fn exec(
@@ -19,9 +38,24 @@ pub fn exec(
         check,
         stdin,
         files,
+        max_columns,
+        tests_at_end,
+        break_pipelines,
+        align_clause_arrows,
     }: Args,
 ) -> miette::Result<()> {
-    if let Err(errs) = aiken_project::format::run(stdin, check, files) {
+    let options = FormatOptions {
+        max_columns,
+        tests: if tests_at_end {
+            TestPlacement::AtEnd
+        } else {
+            TestPlacement::InPlace
+        },
+        break_pipelines,
+        align_clause_arrows,
+    };
+
+    if let Err(errs) = aiken_project::format::run(stdin, check, files, options) {
         for err in &errs {
             err.report();
         }
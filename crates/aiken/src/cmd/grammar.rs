@@ -0,0 +1,22 @@
+use aiken_lang::grammar;
+use miette::IntoDiagnostic;
+
+/// Print a TextMate grammar for Aiken, generated from the compiler's lexer
+#[derive(clap::Args)]
+pub struct Args {
+    /// Print the legend of semantic tokens reported by the language server instead
+    #[clap(long)]
+    legend: bool,
+}
+
+pub fn exec(Args { legend }: Args) -> miette::Result<()> {
+    let json = if legend {
+        serde_json::to_string_pretty(&grammar::legend())
+    } else {
+        serde_json::to_string_pretty(&grammar::textmate())
+    };
+
+    println!("{}", json.into_diagnostic()?);
+
+    Ok(())
+}
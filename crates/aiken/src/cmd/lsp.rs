@@ -1,4 +1,5 @@
 use miette::IntoDiagnostic;
+use std::path::PathBuf;
 
 #[derive(clap::Args)]
 /// Start the Aiken language server
@@ -6,8 +7,13 @@ pub struct Args {
     /// Run on stdio
     #[clap(long)]
     stdio: bool,
+
+    /// Protocol parameters (JSON) to read the cost model of builtins shown on hover from. Both
+    /// cardano-cli and Blockfrost formats are supported. Defaults to the built-in cost model.
+    #[clap(long, value_name = "FILEPATH")]
+    cost_model: Option<PathBuf>,
 }
 
-pub fn exec(_args: Args) -> miette::Result<()> {
-    aiken_lsp::start().into_diagnostic()
+pub fn exec(Args { cost_model, .. }: Args) -> miette::Result<()> {
+    aiken_lsp::start(cost_model).into_diagnostic()
 }
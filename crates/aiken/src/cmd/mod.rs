@@ -1,26 +1,31 @@
-use aiken_project::config;
+use aiken_project::{config, settings};
 use clap::Parser;
 
 pub mod benchmark;
 pub mod blueprint;
 pub mod build;
 pub mod check;
+pub mod ci;
 // only windows
 #[cfg(not(target_os = "windows"))]
 pub mod completion;
 pub mod docs;
 pub mod export;
 pub mod fmt;
+pub mod grammar;
 pub mod lsp;
 pub mod new;
 pub mod packages;
+pub mod toolchain;
 pub mod tx;
 pub mod uplc;
+pub mod verify;
 
 /// Aiken: a smart-contract language and toolchain for Cardano
 #[derive(Parser)]
 #[clap(version = config::compiler_version(true), about, long_about = None)]
 #[clap(propagate_version = true)]
+#[clap(after_help = settings::HELP)]
 pub enum Cmd {
     New(new::Args),
     Fmt(fmt::Args),
@@ -33,6 +38,7 @@ pub enum Cmd {
 
     #[clap(visible_alias("c"))]
     Check(check::Args),
+    Ci(ci::Args),
     Docs(docs::Args),
     Add(packages::add::Args),
 
@@ -44,16 +50,23 @@ pub enum Cmd {
     #[clap(subcommand)]
     Packages(packages::Cmd),
 
+    #[clap(subcommand)]
+    Toolchain(toolchain::Cmd),
+
     #[clap(subcommand)]
     Tx(tx::Cmd),
 
     #[clap(subcommand)]
     Uplc(uplc::Cmd),
 
+    Verify(verify::Args),
+
     #[cfg(not(target_os = "windows"))]
     #[clap(subcommand)]
     Completion(completion::Cmd),
 
+    Grammar(grammar::Args),
+
     #[clap(hide = true)]
     Lsp(lsp::Args),
 }
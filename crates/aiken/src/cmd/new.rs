@@ -1,5 +1,5 @@
 use aiken_project::{
-    config::{self, Config},
+    config::{self, Config, Dependency, Platform},
     package_name::{self, PackageName},
 };
 use indoc::{formatdoc, indoc};
@@ -11,6 +11,10 @@ use std::{
     str::FromStr,
 };
 
+/// Folders of the packages created with '--workspace'.
+const WORKSPACE_LIBRARY: &str = "library";
+const WORKSPACE_CONTRACTS: &str = "contracts";
+
 #[derive(clap::Args)]
 /// Create a new Aiken project
 pub struct Args {
@@ -19,12 +23,20 @@ pub struct Args {
     /// Library only
     #[clap(long, short)]
     lib: bool,
+    /// A library package and a contracts package depending on it, side by side
+    #[clap(long, conflicts_with = "lib")]
+    workspace: bool,
 }
 
 pub fn exec(args: Args) -> miette::Result<()> {
     let package_name = PackageName::from_str(&args.name).into_diagnostic()?;
-    create_project(args, &package_name)?;
-    print_success_message(&package_name);
+    let workspace = args.workspace;
+    if workspace {
+        create_workspace(&package_name)?;
+    } else {
+        create_project(args, &package_name)?;
+    }
+    print_success_message(&package_name, workspace);
     Ok(())
 }
 
@@ -57,7 +69,71 @@ fn create_project(args: Args, package_name: &PackageName) -> miette::Result<()>
     Ok(())
 }
 
-fn print_success_message(package_name: &PackageName) {
+/// A root folder holding two packages: a library of reusable functions and types, and the
+/// contracts using it through a path dependency.
+fn create_workspace(package_name: &PackageName) -> miette::Result<()> {
+    let root = PathBuf::from(&package_name.repo);
+
+    if root.exists() {
+        Err(package_name::Error::ProjectExists {
+            name: package_name.repo.clone(),
+        })?;
+    }
+
+    let library_name = PackageName {
+        owner: package_name.owner.clone(),
+        repo: format!("{}-library", package_name.repo),
+    };
+
+    let base = Config::default(package_name);
+
+    let library = Config {
+        name: library_name.clone(),
+        description: format!("Aiken library for project '{package_name}'"),
+        ..base.clone()
+    };
+
+    let mut contracts = Config {
+        name: PackageName {
+            owner: package_name.owner.clone(),
+            repo: format!("{}-contracts", package_name.repo),
+        },
+        ..base
+    };
+
+    contracts.dependencies.push(Dependency {
+        name: library_name,
+        version: library.version.clone(),
+        source: Platform::Github,
+        path: Some(PathBuf::from("..").join(WORKSPACE_LIBRARY)),
+    });
+
+    let library_root = root.join(WORKSPACE_LIBRARY);
+    create_lib(&library_root)?;
+    library.save(&library_root).into_diagnostic()?;
+    gitignore(&library_root)?;
+
+    let contracts_root = root.join(WORKSPACE_CONTRACTS);
+    create_lib(&contracts_root)?;
+    create_env(&contracts_root)?;
+    create_validators(&contracts_root)?;
+    contracts.save(&contracts_root).into_diagnostic()?;
+    gitignore(&contracts_root)?;
+
+    workspace_readme(&root, &package_name.repo)?;
+
+    create_workspace_github_action(&root)?;
+
+    Ok(())
+}
+
+fn print_success_message(package_name: &PackageName, workspace: bool) {
+    let folder = if workspace {
+        format!("{}/{WORKSPACE_CONTRACTS}", package_name.repo)
+    } else {
+        package_name.repo.clone()
+    };
+
     eprintln!(
         "\n{}",
         formatdoc! {
@@ -78,7 +154,7 @@ fn print_success_message(package_name: &PackageName) {
                 .if_supports_color(Stderr, |s| s.bold()),
             package = package_name.to_string()
                 .if_supports_color(Stderr, |s| s.bright_blue()),
-            folder = package_name.repo
+            folder = folder
                 .if_supports_color(Stderr, |s| s.bright_blue()),
             aiken = "aiken"
                 .if_supports_color(Stderr, |s| s.purple())
@@ -238,6 +314,103 @@ fn readme(root: &Path, project_name: &str) -> miette::Result<()> {
     ).into_diagnostic()
 }
 
+fn workspace_readme(root: &Path, project_name: &str) -> miette::Result<()> {
+    fs::write(
+        root.join("README.md"),
+        formatdoc! {
+            r#"
+                # {name}
+
+                This project is split in two packages:
+
+                - `{library}`: functions and types meant to be reused, both by the contracts and by other projects, written under `{library}/lib`;
+                - `{contracts}`: the validators (under `{contracts}/validators`), which rely on the library.
+
+                The contracts depend on the library through a _path dependency_ in `{contracts}/aiken.toml`:
+
+                ```toml
+                [[dependencies]]
+                name = "..."
+                version = "0.0.0"
+                path = "../{library}"
+                ```
+
+                Path dependencies are compiled straight from their sources, so any change to the library is picked up by the next build of the contracts. Should you publish the library on its own, remove the `path` and point the `version` at a release instead.
+
+                ## Workflow
+
+                Develop and test the library on its own:
+
+                ```sh
+                cd {library}
+                aiken check
+                aiken docs
+                ```
+
+                Then, build the validators along with the latest version of the library:
+
+                ```sh
+                cd {contracts}
+                aiken check
+                aiken build
+                ```
+
+                ## Resources
+
+                Find more on the [Aiken's user manual](https://aiken-lang.org).
+            "#,
+            name = project_name,
+            library = WORKSPACE_LIBRARY,
+            contracts = WORKSPACE_CONTRACTS,
+        },
+    ).into_diagnostic()
+}
+
+fn create_workspace_github_action(root: &Path) -> miette::Result<()> {
+    let workflows = root.join(".github").join("workflows");
+
+    fs::create_dir_all(&workflows).into_diagnostic()?;
+
+    fs::write(
+        workflows.join("continuous-integration.yml"),
+        formatdoc! {
+            r#"
+            name: Continuous Integration
+
+            on:
+              push:
+                branches: ["main"]
+              pull_request:
+
+            jobs:
+              build:
+                runs-on: ubuntu-latest
+                steps:
+                  - uses: actions/checkout@v3
+                  - uses: aiken-lang/setup-aiken@v1
+                    with:
+                      version: {version}
+                  - run: aiken fmt --check
+                    working-directory: {library}
+                  - run: aiken check -D
+                    working-directory: {library}
+                  - run: aiken fmt --check
+                    working-directory: {contracts}
+                  - run: aiken check -D
+                    working-directory: {contracts}
+                  - run: aiken build
+                    working-directory: {contracts}
+            "#,
+            version = config::compiler_version(false),
+            library = WORKSPACE_LIBRARY,
+            contracts = WORKSPACE_CONTRACTS,
+        },
+    )
+    .into_diagnostic()?;
+
+    Ok(())
+}
+
 fn create_github_action(root: &Path) -> miette::Result<()> {
     let workflows = root.join(".github").join("workflows");
 
@@ -33,6 +33,7 @@ pub fn exec(args: Args) -> miette::Result<()> {
         name: PackageName::from_str(&args.package)?,
         version: args.version,
         source: Platform::Github,
+        path: None,
     };
 
     let config = match Config::load(&root) {
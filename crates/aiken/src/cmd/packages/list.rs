@@ -0,0 +1,70 @@
+use aiken_project::{deps::PackageInfo, watch::with_project};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// List the resolved dependencies of a project
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Also show a checksum of each dependency's modules (as recorded in attestations) and the
+    /// license it declares, e.g. for compliance checks.
+    #[clap(long)]
+    licenses: bool,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        licenses,
+    }: Args,
+) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        let packages = p.packages()?;
+
+        let width = packages
+            .iter()
+            .map(|package| package.name.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        for package in packages {
+            print(&package, width, licenses);
+        }
+
+        Ok(())
+    })
+    .map_err(|_| process::exit(1))
+}
+
+fn print(package: &PackageInfo, width: usize, licenses: bool) {
+    let source = match &package.path {
+        Some(path) => format!("path ({})", path.display()),
+        None => package.source.to_string(),
+    };
+
+    let name = format!("{:<width$}", package.name.to_string());
+
+    let mut line = format!(
+        "{} {:<12} {:<10}",
+        name.if_supports_color(Stdout, |s| s.bright_blue()),
+        package.version,
+        source,
+    );
+
+    if licenses {
+        line.push_str(&format!(
+            " {} {}",
+            package.checksum.if_supports_color(Stdout, |s| s.dimmed()),
+            match &package.license {
+                Some(license) => license.to_string(),
+                None => "unknown"
+                    .if_supports_color(Stdout, |s| s.yellow())
+                    .to_string(),
+            }
+        ));
+    }
+
+    println!("{}", line.trim_end());
+}
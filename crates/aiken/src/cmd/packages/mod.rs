@@ -1,5 +1,7 @@
 pub mod add;
 pub mod clear_cache;
+pub mod list;
+pub mod publish;
 pub mod upgrade;
 
 use clap::Subcommand;
@@ -13,8 +15,14 @@ pub enum Cmd {
     /// Change the version of an installed dependency
     Upgrade(upgrade::Args),
 
+    /// List the resolved dependencies of a project
+    List(list::Args),
+
     /// Clear the system-wide dependencies cache
     ClearCache,
+
+    /// Verify that a library is ready for publishing and package it
+    Publish(publish::Args),
 }
 
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
@@ -22,5 +30,7 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
         Cmd::Add(args) => add::exec(args),
         Cmd::ClearCache => clear_cache::exec(),
         Cmd::Upgrade(args) => upgrade::exec(args),
+        Cmd::List(args) => list::exec(args),
+        Cmd::Publish(args) => publish::exec(args),
     }
 }
@@ -0,0 +1,42 @@
+use aiken_project::watch::with_project;
+use rand::prelude::*;
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// Verify that a library is ready for publishing and package it
+///
+/// This checks that the project's metadata are complete, that it type-checks and passes its
+/// tests, and that every public definition is documented. It then bundles the package and its
+/// generated documentation into a zip archive, along with checksums of each bundled file.
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Deny warnings; warnings will be treated as errors
+    #[clap(short = 'D', long)]
+    deny: bool,
+
+    /// An initial seed to initialize the pseudo-random generator for property-tests.
+    #[clap(long, value_name = "UINT")]
+    seed: Option<u32>,
+
+    /// Output directory for the package archive [default: build/publish]
+    #[clap(short = 'o', long)]
+    destination: Option<PathBuf>,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        deny,
+        seed,
+        destination,
+    }: Args,
+) -> miette::Result<()> {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+    with_project(directory.as_deref(), deny, false, |p| {
+        p.publish(destination.clone(), seed)
+    })
+    .map_err(|_| process::exit(1))
+}
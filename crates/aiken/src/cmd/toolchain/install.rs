@@ -0,0 +1,72 @@
+use aiken_project::{
+    pretty,
+    toolchain::{self, Version},
+};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::process;
+
+#[derive(clap::Args)]
+/// Download and install a compiler version
+pub struct Args {
+    /// The compiler version (e.g. 'v1.1.9'), or 'latest'.
+    version: String,
+
+    /// Download the version again, even if already installed.
+    #[clap(long)]
+    force: bool,
+}
+
+pub fn exec(Args { version, force }: Args) -> miette::Result<()> {
+    let version = resolve(&version)?;
+
+    install(&version, force);
+
+    Ok(())
+}
+
+/// Parse a version given on the command-line, looking up what 'latest' stands for.
+pub fn resolve(version: &str) -> miette::Result<Version> {
+    if version == "latest" {
+        return toolchain::latest().or_else(|e| {
+            e.report();
+            process::exit(1)
+        });
+    }
+
+    toolchain::parse_version(version)
+        .ok_or_else(|| miette::miette!("'{version}' isn't a valid version (e.g. 'v1.1.9')."))
+}
+
+pub fn install(version: &Version, force: bool) {
+    if toolchain::is_installed(version) && !force {
+        eprintln!(
+            "{} v{version} (already installed)",
+            pretty::pad_left("Skipping".to_string(), 13, " ")
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+        );
+        return;
+    }
+
+    eprintln!(
+        "{} v{version}",
+        pretty::pad_left("Downloading".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+    );
+
+    match toolchain::install(version) {
+        Ok(path) => eprintln!(
+            "{} v{version} → {}",
+            pretty::pad_left("Installed".to_string(), 13, " ")
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            path.display()
+                .if_supports_color(Stderr, |s| s.bright_blue()),
+        ),
+        Err(e) => {
+            e.report();
+            process::exit(1)
+        }
+    }
+}
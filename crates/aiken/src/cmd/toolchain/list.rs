@@ -0,0 +1,37 @@
+use aiken_project::{config, toolchain};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::env;
+
+pub fn exec() -> miette::Result<()> {
+    let pinned = env::current_dir()
+        .ok()
+        .and_then(|dir| toolchain::pinned(&dir))
+        .map(|(version, _)| version);
+
+    let current = config::compiler_version(false);
+
+    for version in toolchain::installed() {
+        let name = format!("v{version}");
+
+        let mut notes = Vec::new();
+
+        if pinned.as_ref() == Some(&version) {
+            notes.push("pinned");
+        }
+
+        if name == current {
+            notes.push("current");
+        }
+
+        if notes.is_empty() {
+            println!("{name}");
+        } else {
+            println!(
+                "{name} {}",
+                format!("({})", notes.join(", ")).if_supports_color(Stdout, |s| s.dimmed())
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+pub mod install;
+pub mod list;
+pub mod pin;
+
+use aiken_project::{
+    config, pretty,
+    toolchain::{self, Pin},
+};
+use clap::Subcommand;
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{env, process};
+
+/// Install compiler versions and pin them per project
+#[derive(Subcommand)]
+pub enum Cmd {
+    /// Download and install a compiler version
+    Install(install::Args),
+
+    /// Pin a compiler version for a project, installing it if needed
+    #[clap(name = "use")]
+    Use(pin::Args),
+
+    /// List installed compiler versions
+    List,
+}
+
+pub fn exec(cmd: Cmd) -> miette::Result<()> {
+    match cmd {
+        Cmd::Install(args) => install::exec(args),
+        Cmd::Use(args) => pin::exec(args),
+        Cmd::List => list::exec(),
+    }
+}
+
+/// Hand over to the toolchain pinned by the project in the current directory, when it differs
+/// from this one and is installed. Does nothing otherwise, or when managing toolchains.
+pub fn dispatch() {
+    if env::var_os(toolchain::DISPATCHED_ENV_VAR).is_some()
+        || env::args_os().nth(1).is_some_and(|arg| arg == "toolchain")
+    {
+        return;
+    }
+
+    let Some((version, pin)) = env::current_dir()
+        .ok()
+        .and_then(|dir| toolchain::pinned(&dir))
+    else {
+        return;
+    };
+
+    if config::compiler_version(false) == format!("v{version}") {
+        return;
+    }
+
+    if !toolchain::is_installed(&version) {
+        let (Pin::VersionFile(path) | Pin::Manifest(path)) = pin;
+
+        eprintln!(
+            "{} {} is pinned by {} but isn't installed; run {} to install it.",
+            pretty::pad_left("Warning".to_string(), 13, " ")
+                .if_supports_color(Stderr, |s| s.yellow())
+                .if_supports_color(Stderr, |s| s.bold()),
+            format!("v{version}").if_supports_color(Stderr, |s| s.bright_blue()),
+            path.display(),
+            format!("aiken toolchain install v{version}").if_supports_color(Stderr, |s| s.cyan()),
+        );
+
+        return;
+    }
+
+    let status = process::Command::new(toolchain::binary(&version))
+        .args(env::args_os().skip(1))
+        .env(toolchain::DISPATCHED_ENV_VAR, format!("v{version}"))
+        .status();
+
+    match status {
+        Ok(status) => process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!(
+                "{} couldn't start toolchain v{version}: {e}",
+                pretty::pad_left("Error".to_string(), 13, " ")
+                    .if_supports_color(Stderr, |s| s.red())
+                    .if_supports_color(Stderr, |s| s.bold()),
+            );
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::install;
+use aiken_project::{pretty, toolchain};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// Pin a compiler version for a project, installing it if needed. The version is recorded in
+/// a '.aiken-version' file, taking precedence over the 'toolchain' field of 'aiken.toml'. Any
+/// 'aiken' command run from within the project is then carried out by that version.
+pub struct Args {
+    /// The compiler version (e.g. 'v1.1.9'), or 'latest'.
+    version: String,
+
+    /// Path to the project
+    #[clap(long)]
+    directory: Option<PathBuf>,
+}
+
+pub fn exec(Args { version, directory }: Args) -> miette::Result<()> {
+    let version = install::resolve(&version)?;
+
+    install::install(&version, false);
+
+    let directory = directory.unwrap_or_else(|| PathBuf::from("."));
+
+    match toolchain::pin(&directory, &version) {
+        Ok(path) => {
+            eprintln!(
+                "{} v{version} in {}",
+                pretty::pad_left("Pinned".to_string(), 13, " ")
+                    .if_supports_color(Stderr, |s| s.purple())
+                    .if_supports_color(Stderr, |s| s.bold()),
+                path.display()
+                    .if_supports_color(Stderr, |s| s.bright_blue()),
+            );
+            Ok(())
+        }
+        Err(e) => {
+            e.report();
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use aiken_project::serialization::{self, Encoding};
+use miette::IntoDiagnostic;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+#[derive(clap::Args)]
+/// Convert a compiled program between flat and cbor encodings
+pub struct Args {
+    /// File containing the encoded program
+    input: PathBuf,
+
+    /// Encoding of the input file
+    #[clap(long, default_value = "cbor")]
+    from: Encoding,
+
+    /// Encoding to convert to
+    #[clap(long, default_value = "flat")]
+    to: Encoding,
+
+    /// Input file contents will be hex decoded
+    #[clap(long)]
+    hex_input: bool,
+
+    /// Hex encode the output bytes
+    #[clap(long)]
+    hex: bool,
+}
+
+pub fn exec(
+    Args {
+        input,
+        from,
+        to,
+        hex_input,
+        hex,
+    }: Args,
+) -> miette::Result<()> {
+    let bytes = if hex_input {
+        let hex_bytes = std::fs::read_to_string(&input).into_diagnostic()?;
+
+        hex::decode(hex_bytes.trim()).into_diagnostic()?
+    } else {
+        std::fs::read(&input).into_diagnostic()?
+    };
+
+    let program = serialization::deserialize(&bytes, from)?;
+
+    let bytes = serialization::serialize(&program, to)?;
+
+    let mut stdout = io::stdout();
+
+    if hex {
+        print!("{}", hex::encode(bytes));
+    } else {
+        stdout.write_all(&bytes).into_diagnostic()?;
+    }
+
+    stdout.flush().into_diagnostic()?;
+
+    Ok(())
+}
@@ -1,8 +1,6 @@
-use miette::{Context, IntoDiagnostic};
-use std::{path::PathBuf, println};
-use uplc::ast::{DeBruijn, Name, NamedDeBruijn, Program};
+use std::path::PathBuf;
 
-use super::Format;
+use super::{load_program, Format};
 
 #[derive(clap::Args)]
 /// Decode flat bytes to textual Untyped Plutus Core
@@ -31,50 +29,7 @@ pub fn exec(
         hex,
     }: Args,
 ) -> miette::Result<()> {
-    let bytes = if hex {
-        let hex_bytes = std::fs::read_to_string(&input).into_diagnostic()?;
-
-        hex::decode(hex_bytes.trim()).into_diagnostic()?
-    } else {
-        std::fs::read(&input).into_diagnostic()?
-    };
-
-    let program: Program<Name> = match from {
-        Format::Name => {
-            if cbor {
-                let mut flat_buffer = Vec::new();
-                Program::from_cbor(&bytes, &mut flat_buffer).into_diagnostic()?
-            } else {
-                Program::from_flat(&bytes).into_diagnostic()?
-            }
-        }
-        Format::NamedDebruijn => {
-            let program: Program<NamedDeBruijn> = if cbor {
-                let mut flat_buffer = Vec::new();
-                Program::from_cbor(&bytes, &mut flat_buffer).into_diagnostic()?
-            } else {
-                Program::from_flat(&bytes).into_diagnostic()?
-            };
-
-            program
-                .try_into()
-                .into_diagnostic()
-                .context("failed to decode, maybe try `--cbor`")?
-        }
-        Format::Debruijn => {
-            let program: Program<DeBruijn> = if cbor {
-                let mut flat_buffer = Vec::new();
-                Program::from_cbor(&bytes, &mut flat_buffer).into_diagnostic()?
-            } else {
-                Program::from_flat(&bytes).into_diagnostic()?
-            };
-
-            program
-                .try_into()
-                .into_diagnostic()
-                .context("failed to decode, maybe try `--cbor`")?
-        }
-    };
+    let program = load_program(&input, from, cbor, hex)?;
 
     println!("{}", program.to_pretty());
 
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use super::{load_program, Format};
+
+#[derive(clap::Args)]
+/// Decompile flat bytes to a readable, Aiken-like pseudo-code
+pub struct Args {
+    /// Flat encoded Untyped Plutus Core file
+    input: PathBuf,
+
+    // Format to convert from
+    #[clap(long, default_value = "debruijn")]
+    from: Format,
+
+    /// Input file contains cbor encoded flat bytes
+    #[clap(short, long)]
+    cbor: bool,
+
+    /// Input file contents will be hex decoded
+    #[clap(long)]
+    hex: bool,
+}
+
+pub fn exec(
+    Args {
+        input,
+        from,
+        cbor,
+        hex,
+    }: Args,
+) -> miette::Result<()> {
+    let program = load_program(&input, from, cbor, hex)?;
+
+    println!("{}", program.decompile());
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
+use uplc::{
+    ast::{FakeNamedDeBruijn, Name, NamedDeBruijn, Program},
+    diff::Diff,
+    parser,
+};
+
+#[derive(clap::Args)]
+/// Compare two Untyped Plutus Core programs, showing where their terms differ and whether
+/// they are alpha-equivalent (i.e. equal up to the names of their variables). Exits with a
+/// non-zero status when they aren't.
+pub struct Args {
+    left: PathBuf,
+
+    right: PathBuf,
+
+    /// Both files contain flat bytes
+    #[clap(short, long)]
+    flat: bool,
+
+    /// Both files contain hex-encoded cbor (e.g. from a blueprint)
+    #[clap(short, long)]
+    cbor: bool,
+}
+
+pub fn exec(
+    Args {
+        left,
+        right,
+        flat,
+        cbor,
+    }: Args,
+) -> miette::Result<()> {
+    let left = load(&left, flat, cbor)?;
+    let right = load(&right, flat, cbor)?;
+
+    let diff = Diff::new(&left, &right).into_diagnostic()?;
+
+    print!("{diff}");
+
+    if diff.is_alpha_equivalent() {
+        println!(
+            "{}",
+            "The programs are alpha-equivalent.".if_supports_color(Stdout, |s| s.green())
+        );
+        Ok(())
+    } else {
+        println!(
+            "{}",
+            format!(
+                "The programs differ ({} difference(s)).",
+                diff.differences.len() + usize::from(diff.versions.is_some())
+            )
+            .if_supports_color(Stdout, |s| s.red())
+        );
+        process::exit(1)
+    }
+}
+
+fn load(path: &Path, flat: bool, cbor: bool) -> miette::Result<Program<Name>> {
+    if cbor {
+        let cbor_hex = std::fs::read_to_string(path).into_diagnostic()?;
+
+        let raw_cbor = hex::decode(cbor_hex.trim()).into_diagnostic()?;
+
+        let program = Program::<FakeNamedDeBruijn>::from_cbor(&raw_cbor, &mut Vec::new())
+            .into_diagnostic()?;
+
+        let program: Program<NamedDeBruijn> = program.into();
+
+        Program::<Name>::try_from(program).into_diagnostic()
+    } else if flat {
+        let bytes = std::fs::read(path).into_diagnostic()?;
+
+        let program = Program::<FakeNamedDeBruijn>::from_flat(&bytes).into_diagnostic()?;
+
+        let program: Program<NamedDeBruijn> = program.into();
+
+        Program::<Name>::try_from(program).into_diagnostic()
+    } else {
+        let code = std::fs::read_to_string(path).into_diagnostic()?;
+
+        parser::program(&code).into_diagnostic()
+    }
+}
@@ -9,8 +9,8 @@ use uplc::{
     ast::{FakeNamedDeBruijn, Name, NamedDeBruijn, Program, Term},
     builtins::DefaultFunction,
     machine::{
-        cost_model::{ExBudget, StepKind},
-        TERM_COUNT,
+        cost_model::{CostModel, ExBudget, StepKind},
+        Machine, TERM_COUNT,
     },
     parser,
 };
@@ -29,6 +29,10 @@ pub struct Args {
     #[clap(short, long, default_value_t = false)]
     debug: bool,
 
+    /// Print every builtin application, in order, with its own cpu/mem cost
+    #[clap(long, default_value_t = false)]
+    trace_budget: bool,
+
     /// Arguments to pass to the UPLC program
     args: Vec<String>,
 }
@@ -39,6 +43,7 @@ pub fn exec(
         flat,
         args,
         debug,
+        trace_budget,
         cbor,
     }: Args,
 ) -> miette::Result<()> {
@@ -77,10 +82,34 @@ pub fn exec(
 
     let program = Program::<NamedDeBruijn>::try_from(program).into_diagnostic()?;
 
-    let mut eval_result = if debug {
-        program.eval_debug(ExBudget::default(), &Language::PlutusV3)
+    // Sums-of-products only exist from version 1.1.0, which is also when Plutus V3 semantics kick
+    // in. Older programs are evaluated with the semantics (and costs) of Plutus V2.
+    let language = if program.version >= (1, 1, 0) {
+        Language::PlutusV3
+    } else {
+        Language::PlutusV2
+    };
+
+    let mut eval_result = if trace_budget {
+        let machine = if debug {
+            Machine::new_debug
+        } else {
+            Machine::new
+        };
+
+        program.eval_with(
+            machine(
+                language.clone(),
+                CostModel::for_language(&language),
+                budget,
+                200, //slippage
+            )
+            .trace_builtins(),
+        )
+    } else if debug {
+        program.eval_debug(budget, &language)
     } else {
-        program.eval(budget)
+        program.eval_version(budget, &language)
     };
 
     let cost = eval_result.cost();
@@ -138,6 +167,26 @@ pub fn exec(
                 );
             }
 
+            if let Some(calls) = eval_result.builtin_calls() {
+                println!("---------------BUDGET-----------------");
+
+                let output = calls
+                    .iter()
+                    .map(|call| {
+                        json!({
+                            "fun": call.fun.to_string(),
+                            "cpu": call.cost.cpu,
+                            "mem": call.cost.mem,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&output).into_diagnostic()?
+                );
+            }
+
             Ok(())
         }
         Err(err) => {
@@ -149,6 +198,20 @@ pub fn exec(
                 eprintln!("\nLogs\n----\n{}", logs.join("\n"))
             }
 
+            if let Some(calls) = eval_result.builtin_calls() {
+                eprintln!(
+                    "\nBuiltins\n--------\n{}",
+                    calls
+                        .iter()
+                        .map(|call| format!(
+                            "{} (cpu: {}, memory: {})",
+                            call.fun, call.cost.cpu, call.cost.mem
+                        ))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            }
+
             process::exit(1)
         }
     }
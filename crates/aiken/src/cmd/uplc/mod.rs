@@ -1,10 +1,16 @@
+mod convert;
 mod decode;
+mod decompile;
+mod diff;
 mod encode;
 mod eval;
 mod fmt;
 mod shrink;
 
 use clap::{Subcommand, ValueEnum};
+use miette::{Context, IntoDiagnostic};
+use std::path::Path;
+use uplc::ast::{DeBruijn, Name, NamedDeBruijn, Program};
 
 #[derive(Copy, Clone, ValueEnum)]
 pub(super) enum Format {
@@ -13,6 +19,62 @@ pub(super) enum Format {
     Debruijn,
 }
 
+/// Read a flat-encoded program from a file, possibly wrapped in cbor and/or hex-encoded, with
+/// names in the given format.
+pub(super) fn load_program(
+    input: &Path,
+    from: Format,
+    cbor: bool,
+    hex: bool,
+) -> miette::Result<Program<Name>> {
+    let bytes = if hex {
+        let hex_bytes = std::fs::read_to_string(input).into_diagnostic()?;
+
+        hex::decode(hex_bytes.trim()).into_diagnostic()?
+    } else {
+        std::fs::read(input).into_diagnostic()?
+    };
+
+    let program = match from {
+        Format::Name => {
+            if cbor {
+                let mut flat_buffer = Vec::new();
+                Program::from_cbor(&bytes, &mut flat_buffer).into_diagnostic()?
+            } else {
+                Program::from_flat(&bytes).into_diagnostic()?
+            }
+        }
+        Format::NamedDebruijn => {
+            let program: Program<NamedDeBruijn> = if cbor {
+                let mut flat_buffer = Vec::new();
+                Program::from_cbor(&bytes, &mut flat_buffer).into_diagnostic()?
+            } else {
+                Program::from_flat(&bytes).into_diagnostic()?
+            };
+
+            program
+                .try_into()
+                .into_diagnostic()
+                .context("failed to decode, maybe try `--cbor`")?
+        }
+        Format::Debruijn => {
+            let program: Program<DeBruijn> = if cbor {
+                let mut flat_buffer = Vec::new();
+                Program::from_cbor(&bytes, &mut flat_buffer).into_diagnostic()?
+            } else {
+                Program::from_flat(&bytes).into_diagnostic()?
+            };
+
+            program
+                .try_into()
+                .into_diagnostic()
+                .context("failed to decode, maybe try `--cbor`")?
+        }
+    };
+
+    Ok(program)
+}
+
 /// Commands for working with untyped Plutus-core
 #[derive(Subcommand)]
 pub enum Cmd {
@@ -22,8 +84,11 @@ pub enum Cmd {
     Encode(encode::Args),
     #[clap(alias = "unflat")]
     Decode(decode::Args),
+    Decompile(decompile::Args),
+    Diff(diff::Args),
     #[clap(alias = "optimize")]
     Shrink(shrink::Args),
+    Convert(convert::Args),
 }
 
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
@@ -32,6 +97,9 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
         Cmd::Eval(args) => eval::exec(args),
         Cmd::Encode(args) => encode::exec(args),
         Cmd::Decode(args) => decode::exec(args),
+        Cmd::Decompile(args) => decompile::exec(args),
+        Cmd::Diff(args) => diff::exec(args),
         Cmd::Shrink(args) => shrink::exec(args),
+        Cmd::Convert(args) => convert::exec(args),
     }
 }
@@ -0,0 +1,32 @@
+use aiken_project::{attestation::Attestation, watch::with_project};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// Rebuild an Aiken project and check that it matches an attestation produced by
+/// 'aiken build --attest'
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Path to the attestation to check the build against.
+    ///
+    /// [default: plutus.attestation.json]
+    #[clap(short, long, value_name = "FILEPATH", verbatim_doc_comment)]
+    attestation: Option<PathBuf>,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        attestation,
+    }: Args,
+) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        let path = attestation
+            .clone()
+            .unwrap_or_else(|| Attestation::path(&p.blueprint_path(None)));
+
+        p.verify(&path)
+    })
+    .map_err(|_| process::exit(1))
+}
@@ -1,12 +1,12 @@
-use aiken_project::{config, pretty};
+use aiken_project::{config, pretty, settings};
 #[cfg(not(target_os = "windows"))]
 use cmd::completion;
 use cmd::{
     benchmark,
     blueprint::{self, address},
-    build, check, docs, export, fmt, lsp, new,
+    build, check, ci, docs, export, fmt, grammar, lsp, new,
     packages::{self, add},
-    tx, uplc, Cmd,
+    toolchain, tx, uplc, verify, Cmd,
 };
 use owo_colors::OwoColorize;
 
@@ -15,26 +15,34 @@ mod cmd;
 fn main() -> miette::Result<()> {
     panic_handler();
 
+    settings::init();
+
     #[cfg(target_env = "musl")]
     openssl_probe::init_ssl_cert_env_vars();
 
+    toolchain::dispatch();
+
     match Cmd::default() {
         Cmd::New(args) => new::exec(args),
         Cmd::Fmt(args) => fmt::exec(args),
         Cmd::Build(args) => build::exec(args),
         Cmd::Address(args) => address::exec(args),
         Cmd::Check(args) => check::exec(args),
+        Cmd::Ci(args) => ci::exec(args),
         Cmd::Bench(args) => benchmark::exec(args),
         Cmd::Docs(args) => docs::exec(args),
         Cmd::Add(args) => add::exec(args),
         Cmd::Blueprint(args) => blueprint::exec(args),
         Cmd::Packages(args) => packages::exec(args),
         Cmd::Lsp(args) => lsp::exec(args),
+        Cmd::Toolchain(sub_cmd) => toolchain::exec(sub_cmd),
         Cmd::Tx(sub_cmd) => tx::exec(sub_cmd),
         Cmd::Uplc(sub_cmd) => uplc::exec(sub_cmd),
+        Cmd::Verify(args) => verify::exec(args),
         #[cfg(not(target_os = "windows"))]
         Cmd::Completion(sub_cmd) => completion::exec(sub_cmd),
         Cmd::Export(args) => export::exec(args),
+        Cmd::Grammar(args) => grammar::exec(args),
     }
 }
 
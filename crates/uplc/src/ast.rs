@@ -23,9 +23,11 @@ use serde::{
     ser::{Serialize, SerializeStruct, Serializer},
 };
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display},
     hash::{self, Hash},
     rc::Rc,
+    time::Instant,
 };
 
 /// This represents a program in Untyped Plutus Core.
@@ -264,6 +266,20 @@ impl<'a> Deserialize<'a> for SerializableProgram {
 }
 
 impl Program<DeBruijn> {
+    /// Embed a marker (e.g. a project name and version) in a program without changing what it
+    /// does, so that deployed scripts can be attributed. The program becomes
+    /// `[(lam _ program) (con string "marker")]`, which merely evaluates to the original term.
+    pub fn with_marker(&self, marker: &str) -> Self {
+        Program {
+            version: self.version,
+            term: Term::Lambda {
+                parameter_name: DeBruijn::new(0).into(),
+                body: self.term.clone().into(),
+            }
+            .apply(Term::string(marker)),
+        }
+    }
+
     pub fn address(
         &self,
         network: Network,
@@ -333,6 +349,53 @@ impl<T> Term<T> {
     pub fn is_int(&self) -> bool {
         matches!(self, Term::Constant(c) if matches!(c.as_ref(), &Constant::Integer(_)))
     }
+
+    /// All the builtins referenced anywhere in this term.
+    pub fn builtins(&self) -> BTreeSet<DefaultFunction> {
+        let mut builtins = BTreeSet::new();
+        self.collect_builtins(&mut builtins);
+        builtins
+    }
+
+    /// Whether this term contains `constr` or `case` terms, which only exist from program
+    /// version 1.1.0 onwards.
+    pub fn has_sums_of_products(&self) -> bool {
+        match self {
+            Term::Constr { .. } | Term::Case { .. } => true,
+            Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => {
+                body.has_sums_of_products()
+            }
+            Term::Apply { function, argument } => {
+                function.has_sums_of_products() || argument.has_sums_of_products()
+            }
+            Term::Var(_) | Term::Constant(_) | Term::Error | Term::Builtin(_) => false,
+        }
+    }
+
+    fn collect_builtins(&self, builtins: &mut BTreeSet<DefaultFunction>) {
+        match self {
+            Term::Builtin(func) => {
+                builtins.insert(*func);
+            }
+            Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => {
+                body.collect_builtins(builtins)
+            }
+            Term::Apply { function, argument } => {
+                function.collect_builtins(builtins);
+                argument.collect_builtins(builtins);
+            }
+            Term::Constr { fields, .. } => fields
+                .iter()
+                .for_each(|field| field.collect_builtins(builtins)),
+            Term::Case { constr, branches } => {
+                constr.collect_builtins(builtins);
+                branches
+                    .iter()
+                    .for_each(|branch| branch.collect_builtins(builtins))
+            }
+            Term::Var(_) | Term::Constant(_) | Term::Error => {}
+        }
+    }
 }
 
 impl<T> TryInto<PlutusData> for Term<T> {
@@ -863,7 +926,12 @@ impl Program<NamedDeBruijn> {
 
     /// Evaluate a Program as a specific PlutusVersion
     pub fn eval_version(self, initial_budget: ExBudget, version: &Language) -> EvalResult {
-        let mut machine = Machine::new(version.clone(), CostModel::default(), initial_budget, 200);
+        let mut machine = Machine::new(
+            version.clone(),
+            CostModel::for_language(version),
+            initial_budget,
+            200,
+        );
 
         let term = machine.run(self.term);
 
@@ -902,10 +970,40 @@ impl Program<NamedDeBruijn> {
         )
     }
 
+    /// Like 'eval_as', but defaulting to the language's cost model when no costs are given, and
+    /// aborting with 'TimedOut' once past the deadline, if any.
+    pub fn eval_until(
+        self,
+        version: &Language,
+        costs: Option<&[i64]>,
+        initial_budget: ExBudget,
+        deadline: Option<Instant>,
+    ) -> EvalResult {
+        self.eval_with(
+            Machine::for_language(version, costs, initial_budget).with_deadline(deadline),
+        )
+    }
+
+    /// Evaluate on a machine configured by the caller.
+    pub fn eval_with(self, mut machine: Machine) -> EvalResult {
+        let initial_budget = machine.ex_budget;
+
+        let term = machine.run(self.term);
+
+        EvalResult::new(
+            term,
+            machine.ex_budget,
+            initial_budget,
+            machine.logs,
+            machine.spend_counter.map(|i| i.into()),
+        )
+        .with_builtin_calls(machine.builtin_calls)
+    }
+
     pub fn eval_debug(self, initial_budget: ExBudget, version: &Language) -> EvalResult {
         let mut machine = Machine::new_debug(
             version.clone(),
-            CostModel::default(),
+            CostModel::for_language(version),
             initial_budget,
             200, //slippage
         );
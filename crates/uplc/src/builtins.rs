@@ -1,7 +1,7 @@
 use crate::ast::Term;
 use pallas_codec::flat::de;
 use std::{fmt::Display, rc::Rc, str::FromStr};
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 
 /// All the possible builtin functions in Untyped Plutus Core.
 #[repr(u8)]
@@ -534,6 +534,11 @@ impl Display for DefaultFunction {
 }
 
 impl DefaultFunction {
+    /// The builtin exposed under the given name in 'aiken/builtin', if any.
+    pub fn from_aiken_name(name: &str) -> Option<Self> {
+        DefaultFunction::iter().find(|builtin| builtin.aiken_name() == name)
+    }
+
     pub fn aiken_name(&self) -> String {
         use DefaultFunction::*;
 
@@ -0,0 +1,472 @@
+//! Lift programs back into a readable, Aiken-like pseudo-code; to help with reviewing (or
+//! reverse-engineering) deployed scripts. The output isn't meant to compile, nor to be
+//! exhaustive: let-bindings, functions, conditionals and builtin applications are shown in their
+//! usual syntax, helpers generated by the compiler are named back, and anything else is left
+//! as a plain application.
+
+use crate::{
+    ast::{Constant, Name, Program, Term, Unique},
+    builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER},
+    builtins::DefaultFunction,
+};
+use std::collections::HashMap;
+
+const INDENT: &str = "  ";
+
+impl Program<Name> {
+    pub fn decompile(&self) -> String {
+        let mut decompiler = Decompiler::default();
+        decompiler.block(&self.term, 0)
+    }
+}
+
+/// What a let-bound variable was recognized as.
+#[derive(Debug, Clone)]
+enum Alias {
+    Builtin(DefaultFunction),
+    Helper(&'static str),
+}
+
+#[derive(Default)]
+struct Decompiler {
+    aliases: HashMap<(String, Unique), Alias>,
+}
+
+impl Decompiler {
+    /// A sequence of let-bindings followed by an expression, one per line.
+    fn block(&mut self, term: &Term<Name>, depth: usize) -> String {
+        let indent = INDENT.repeat(depth);
+
+        let mut lines = Vec::new();
+
+        let mut term = term;
+
+        while let Term::Apply { function, argument } = term {
+            let Term::Lambda {
+                parameter_name,
+                body,
+            } = function.as_ref()
+            else {
+                break;
+            };
+
+            match recognize(argument) {
+                Some(alias) => {
+                    self.aliases.insert(key(parameter_name), alias);
+                }
+                None => lines.push(format!(
+                    "{indent}let {} = {}",
+                    self.name(parameter_name),
+                    self.expr(argument, depth)
+                )),
+            }
+
+            term = body;
+        }
+
+        lines.push(format!("{indent}{}", self.expr(term, depth)));
+
+        lines.join("\n")
+    }
+
+    fn expr(&mut self, term: &Term<Name>, depth: usize) -> String {
+        match term {
+            Term::Var(name) => self.name(name),
+
+            Term::Constant(constant) => constant_to_string(constant),
+
+            Term::Error => "fail".to_string(),
+
+            Term::Builtin(builtin) => builtin.aiken_name(),
+
+            Term::Lambda { .. } => {
+                let mut parameters = Vec::new();
+                let mut body = term;
+
+                while let Term::Lambda {
+                    parameter_name,
+                    body: inner,
+                } = body
+                {
+                    parameters.push(self.name(parameter_name));
+                    body = inner;
+                }
+
+                format!("fn({}) {}", parameters.join(", "), self.braced(body, depth))
+            }
+
+            Term::Apply { function, .. } if matches!(function.as_ref(), Term::Lambda { .. }) => {
+                self.braced(term, depth)
+            }
+
+            Term::Apply { .. } => self.application(term, depth),
+
+            Term::Delay(body) => format!("fn() {}", self.braced(body, depth)),
+
+            Term::Force(body) => match strip_forces(body) {
+                Term::Builtin(builtin) => builtin.aiken_name(),
+                _ => match self.as_if(term) {
+                    Some((condition, then, otherwise)) => {
+                        self.conditional(condition, then, otherwise, depth)
+                    }
+                    None => format!("{}()", self.atom(body, depth)),
+                },
+            },
+
+            Term::Constr { tag, fields } => format!(
+                "Constr{tag}({})",
+                fields
+                    .iter()
+                    .map(|field| self.expr(field, depth))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            Term::Case { constr, branches } => {
+                let indent = INDENT.repeat(depth + 1);
+
+                let branches = branches
+                    .iter()
+                    .enumerate()
+                    .map(|(tag, branch)| {
+                        format!("{indent}{tag} -> {}", self.expr(branch, depth + 1))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                format!(
+                    "when {} is {{\n{branches}\n{}}}",
+                    self.atom(constr, depth),
+                    INDENT.repeat(depth)
+                )
+            }
+        }
+    }
+
+    fn application(&mut self, term: &Term<Name>, depth: usize) -> String {
+        let mut arguments = Vec::new();
+        let mut head = term;
+
+        while let Term::Apply { function, argument } = head {
+            arguments.push(argument.as_ref());
+            head = function;
+        }
+
+        arguments.reverse();
+
+        let builtin = self.builtin(head);
+
+        match (builtin, arguments.as_slice()) {
+            (Some(builtin), [left, right]) if operator(builtin).is_some() => format!(
+                "{} {} {}",
+                self.atom(left, depth),
+                operator(builtin).unwrap_or_default(),
+                self.atom(right, depth)
+            ),
+            (Some(DefaultFunction::IfThenElse), [condition, then, otherwise]) => {
+                self.conditional(condition, then, otherwise, depth)
+            }
+            _ => {
+                let head = match builtin {
+                    Some(builtin) => builtin.aiken_name(),
+                    None => self.atom(head, depth),
+                };
+
+                format!(
+                    "{head}({})",
+                    arguments
+                        .iter()
+                        .map(|argument| self.expr(argument, depth))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        }
+    }
+
+    fn conditional(
+        &mut self,
+        condition: &Term<Name>,
+        then: &Term<Name>,
+        otherwise: &Term<Name>,
+        depth: usize,
+    ) -> String {
+        format!(
+            "if {} {} else {}",
+            self.expr(condition, depth),
+            self.braced(undelay(then), depth),
+            self.braced(undelay(otherwise), depth)
+        )
+    }
+
+    fn braced(&mut self, term: &Term<Name>, depth: usize) -> String {
+        format!(
+            "{{\n{}\n{}}}",
+            self.block(term, depth + 1),
+            INDENT.repeat(depth)
+        )
+    }
+
+    /// An expression, parenthesized unless it's atomic.
+    fn atom(&mut self, term: &Term<Name>, depth: usize) -> String {
+        match term {
+            Term::Var(..) | Term::Constant(..) | Term::Builtin(..) | Term::Error => {
+                self.expr(term, depth)
+            }
+            Term::Apply { .. } if self.as_operator(term).is_none() => self.expr(term, depth),
+            _ => format!("({})", self.expr(term, depth)),
+        }
+    }
+
+    /// `force (ifThenElse condition (delay then) (delay otherwise))`
+    fn as_if<'a>(
+        &self,
+        term: &'a Term<Name>,
+    ) -> Option<(&'a Term<Name>, &'a Term<Name>, &'a Term<Name>)> {
+        let Term::Force(body) = term else {
+            return None;
+        };
+
+        let Term::Apply {
+            function,
+            argument: otherwise,
+        } = body.as_ref()
+        else {
+            return None;
+        };
+
+        let Term::Apply {
+            function,
+            argument: then,
+        } = function.as_ref()
+        else {
+            return None;
+        };
+
+        let Term::Apply {
+            function,
+            argument: condition,
+        } = function.as_ref()
+        else {
+            return None;
+        };
+
+        match (self.builtin(function), then.as_ref(), otherwise.as_ref()) {
+            (Some(DefaultFunction::IfThenElse), Term::Delay(_), Term::Delay(_)) => {
+                Some((condition.as_ref(), then.as_ref(), otherwise.as_ref()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The builtin a term stands for, possibly forced or through a let-bound variable.
+    fn builtin(&self, term: &Term<Name>) -> Option<DefaultFunction> {
+        match strip_forces(term) {
+            Term::Builtin(builtin) => Some(*builtin),
+            Term::Var(name) => match self.aliases.get(&key(name)) {
+                Some(Alias::Builtin(builtin)) => Some(*builtin),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The infix operator a term applies, when rendered as such by 'application'.
+    fn as_operator(&self, term: &Term<Name>) -> Option<&'static str> {
+        match term {
+            Term::Apply { function, .. } => match function.as_ref() {
+                Term::Apply { function, .. } => self.builtin(function).and_then(operator),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn name(&self, name: &Name) -> String {
+        match self.aliases.get(&key(name)) {
+            Some(Alias::Builtin(builtin)) => builtin.aiken_name(),
+            Some(Alias::Helper(helper)) => helper.to_string(),
+            None => name.text.clone(),
+        }
+    }
+}
+
+fn key(name: &Name) -> (String, Unique) {
+    (name.text.clone(), name.unique)
+}
+
+/// Recognize a let-bound term as a (forced) builtin, or one of the compiler's own helpers.
+fn recognize(term: &Term<Name>) -> Option<Alias> {
+    if let Term::Builtin(builtin) = strip_forces(term) {
+        return Some(Alias::Builtin(*builtin));
+    }
+
+    let Term::Lambda {
+        parameter_name,
+        body,
+    } = term
+    else {
+        return None;
+    };
+
+    let Term::Apply { function, argument } = body.as_ref() else {
+        return None;
+    };
+
+    let Term::Apply {
+        function: unconstr,
+        argument: subject,
+    } = argument.as_ref()
+    else {
+        return None;
+    };
+
+    match (
+        strip_forces(function),
+        strip_forces(unconstr),
+        subject.as_ref(),
+    ) {
+        (Term::Builtin(pair), Term::Builtin(DefaultFunction::UnConstrData), Term::Var(var))
+            if var == parameter_name =>
+        {
+            match pair {
+                DefaultFunction::SndPair => {
+                    Some(Alias::Helper(CONSTR_FIELDS_EXPOSER.trim_start_matches('_')))
+                }
+                DefaultFunction::FstPair => {
+                    Some(Alias::Helper(CONSTR_INDEX_EXPOSER.trim_start_matches('_')))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn operator(builtin: DefaultFunction) -> Option<&'static str> {
+    match builtin {
+        DefaultFunction::AddInteger => Some("+"),
+        DefaultFunction::SubtractInteger => Some("-"),
+        DefaultFunction::MultiplyInteger => Some("*"),
+        DefaultFunction::DivideInteger => Some("/"),
+        DefaultFunction::ModInteger => Some("%"),
+        DefaultFunction::EqualsInteger
+        | DefaultFunction::EqualsByteString
+        | DefaultFunction::EqualsString
+        | DefaultFunction::EqualsData => Some("=="),
+        DefaultFunction::LessThanInteger | DefaultFunction::LessThanByteString => Some("<"),
+        DefaultFunction::LessThanEqualsInteger | DefaultFunction::LessThanEqualsByteString => {
+            Some("<=")
+        }
+        _ => None,
+    }
+}
+
+fn strip_forces(term: &Term<Name>) -> &Term<Name> {
+    match term {
+        Term::Force(body) => strip_forces(body),
+        _ => term,
+    }
+}
+
+fn undelay(term: &Term<Name>) -> &Term<Name> {
+    match term {
+        Term::Delay(body) => body,
+        _ => term,
+    }
+}
+
+fn constant_to_string(constant: &Constant) -> String {
+    match constant {
+        Constant::Integer(n) => n.to_string(),
+        Constant::ByteString(bytes) => format!("#\"{}\"", hex::encode(bytes)),
+        Constant::String(s) => format!("@{s:?}"),
+        Constant::Bool(true) => "True".to_string(),
+        Constant::Bool(false) => "False".to_string(),
+        Constant::Unit => "Void".to_string(),
+        _ => constant.to_pretty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser;
+    use indoc::indoc;
+
+    fn decompile(src: &str) -> String {
+        parser::program(src).unwrap().decompile()
+    }
+
+    #[test]
+    fn lets_operators_and_conditionals() {
+        assert_eq!(
+            decompile(indoc! {r#"
+                (program 1.0.0
+                  [
+                    (lam x
+                      (force [ [ [ (force (builtin ifThenElse)) [ [ (builtin lessThanInteger) x ] (con integer 0) ] ] (delay (error)) ] (delay [ [ (builtin addInteger) x ] (con integer 1) ]) ])
+                    )
+                    (con integer 41)
+                  ]
+                )
+            "#}),
+            indoc! {r#"
+                let x = 41
+                if x < 0 {
+                  fail
+                } else {
+                  x + 1
+                }"#}
+        );
+    }
+
+    #[test]
+    fn compiler_helpers() {
+        assert_eq!(
+            decompile(indoc! {r#"
+                (program 1.0.0
+                  [
+                    (lam fields
+                      [
+                        (lam head
+                          (lam datum [ head [ fields datum ] ])
+                        )
+                        (force (builtin headList))
+                      ]
+                    )
+                    (lam d [ (force (force (builtin sndPair))) [ (builtin unConstrData) d ] ])
+                  ]
+                )
+            "#}),
+            indoc! {r#"
+                fn(datum) {
+                  head_list(constr_fields_exposer(datum))
+                }"#}
+        );
+    }
+
+    #[test]
+    fn aliased_operators_precedence() {
+        assert_eq!(
+            decompile(indoc! {r#"
+                (program 1.0.0
+                  [
+                    (lam add
+                      [
+                        (lam mul
+                          (lam a (lam b (lam c [ [ mul [ [ add a ] b ] ] c ])))
+                        )
+                        (builtin multiplyInteger)
+                      ]
+                    )
+                    (builtin addInteger)
+                  ]
+                )
+            "#}),
+            indoc! {r#"
+                fn(a, b, c) {
+                  (a + b) * c
+                }"#}
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Structural comparison of programs. Both sides are first brought to a canonical naming, by
+//! going through De Bruijn indices and naming every binder after its depth (`v1` for the
+//! outermost lambda, `v2` for the one right under, ...); so that programs only differing in the
+//! names of their variables (i.e. alpha-equivalent) have no differences, and binders at the same
+//! position always compare equal regardless of what surrounds them.
+
+use crate::{
+    ast::{DeBruijn, Name, Program, Term},
+    debruijn,
+};
+use std::{fmt, rc::Rc};
+
+const MAX_WIDTH: usize = 80;
+
+/// Two subterms found at the same position, yet different.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Steps from the root of the program, e.g. `fun > lam i_0 > arg`.
+    pub path: Vec<String>,
+    pub left: Term<Name>,
+    pub right: Term<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    pub versions: Option<((usize, usize, usize), (usize, usize, usize))>,
+    pub differences: Vec<Difference>,
+}
+
+impl Diff {
+    pub fn new(left: &Program<Name>, right: &Program<Name>) -> Result<Self, debruijn::Error> {
+        let left = canonical(left)?;
+        let right = canonical(right)?;
+
+        let mut differences = Vec::new();
+
+        compare(&left.term, &right.term, &mut Vec::new(), &mut differences);
+
+        Ok(Diff {
+            versions: (left.version != right.version).then_some((left.version, right.version)),
+            differences,
+        })
+    }
+
+    pub fn is_alpha_equivalent(&self) -> bool {
+        self.versions.is_none() && self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((left, right)) = self.versions {
+            writeln!(
+                f,
+                "version\n- {}.{}.{}\n+ {}.{}.{}\n",
+                left.0, left.1, left.2, right.0, right.1, right.2
+            )?;
+        }
+
+        for difference in self.differences.iter() {
+            writeln!(
+                f,
+                "{}\n- {}\n+ {}\n",
+                if difference.path.is_empty() {
+                    "(root)".to_string()
+                } else {
+                    difference.path.join(" > ")
+                },
+                excerpt(&difference.left),
+                excerpt(&difference.right)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn canonical(program: &Program<Name>) -> Result<Program<Name>, debruijn::Error> {
+    let program: Program<DeBruijn> = program.clone().try_into()?;
+
+    Ok(Program {
+        version: program.version,
+        term: named_by_depth(&program.term, 0),
+    })
+}
+
+/// Name binders after the number of lambdas enclosing them (themselves included). A variable
+/// with index `i` under `depth` lambdas refers to the binder at depth `depth - i + 1`.
+fn named_by_depth(term: &Term<DeBruijn>, depth: usize) -> Term<Name> {
+    let name = |depth: usize| Rc::new(Name::text(format!("v{depth}")));
+
+    match term {
+        Term::Var(index) => Term::Var(name(depth + 1 - index.inner())),
+        Term::Lambda { body, .. } => Term::Lambda {
+            parameter_name: name(depth + 1),
+            body: named_by_depth(body, depth + 1).into(),
+        },
+        Term::Apply { function, argument } => Term::Apply {
+            function: named_by_depth(function, depth).into(),
+            argument: named_by_depth(argument, depth).into(),
+        },
+        Term::Delay(term) => Term::Delay(named_by_depth(term, depth).into()),
+        Term::Force(term) => Term::Force(named_by_depth(term, depth).into()),
+        Term::Constr { tag, fields } => Term::Constr {
+            tag: *tag,
+            fields: fields
+                .iter()
+                .map(|field| named_by_depth(field, depth))
+                .collect(),
+        },
+        Term::Case { constr, branches } => Term::Case {
+            constr: named_by_depth(constr, depth).into(),
+            branches: branches
+                .iter()
+                .map(|branch| named_by_depth(branch, depth))
+                .collect(),
+        },
+        Term::Constant(constant) => Term::Constant(constant.clone()),
+        Term::Builtin(builtin) => Term::Builtin(*builtin),
+        Term::Error => Term::Error,
+    }
+}
+
+fn compare(
+    left: &Term<Name>,
+    right: &Term<Name>,
+    path: &mut Vec<String>,
+    differences: &mut Vec<Difference>,
+) {
+    let mut within = |step: String, left: &Term<Name>, right: &Term<Name>| {
+        path.push(step);
+        compare(left, right, path, differences);
+        path.pop();
+    };
+
+    match (left, right) {
+        (
+            Term::Lambda {
+                parameter_name,
+                body: left_body,
+            },
+            Term::Lambda {
+                body: right_body, ..
+            },
+        ) => within(
+            format!("lam {}", parameter_name.text),
+            left_body,
+            right_body,
+        ),
+        (
+            Term::Apply {
+                function: left_function,
+                argument: left_argument,
+            },
+            Term::Apply {
+                function: right_function,
+                argument: right_argument,
+            },
+        ) => {
+            within("fun".to_string(), left_function, right_function);
+            within("arg".to_string(), left_argument, right_argument);
+        }
+        (Term::Delay(left), Term::Delay(right)) => within("delay".to_string(), left, right),
+        (Term::Force(left), Term::Force(right)) => within("force".to_string(), left, right),
+        (
+            Term::Constr {
+                tag: left_tag,
+                fields: left_fields,
+            },
+            Term::Constr {
+                tag: right_tag,
+                fields: right_fields,
+            },
+        ) if left_tag == right_tag && left_fields.len() == right_fields.len() => {
+            for (ix, (left, right)) in left_fields.iter().zip(right_fields).enumerate() {
+                within(format!("constr {left_tag} field {ix}"), left, right);
+            }
+        }
+        (
+            Term::Case {
+                constr: left_constr,
+                branches: left_branches,
+            },
+            Term::Case {
+                constr: right_constr,
+                branches: right_branches,
+            },
+        ) if left_branches.len() == right_branches.len() => {
+            within("case".to_string(), left_constr, right_constr);
+            for (ix, (left, right)) in left_branches.iter().zip(right_branches).enumerate() {
+                within(format!("branch {ix}"), left, right);
+            }
+        }
+        _ if left == right => (),
+        _ => differences.push(Difference {
+            path: path.clone(),
+            left: left.clone(),
+            right: right.clone(),
+        }),
+    }
+}
+
+/// The pretty-printed term on a single line, shortened if too long.
+fn excerpt(term: &Term<Name>) -> String {
+    let flat = term
+        .to_pretty()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if flat.chars().count() > MAX_WIDTH {
+        format!("{}…", flat.chars().take(MAX_WIDTH - 1).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Diff;
+    use crate::parser;
+
+    fn diff(left: &str, right: &str) -> Diff {
+        Diff::new(
+            &parser::program(left).unwrap(),
+            &parser::program(right).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn alpha_equivalent() {
+        let diff = diff(
+            "(program 1.0.0 (lam x (lam y [ x y ])))",
+            "(program 1.0.0 (lam a (lam b [ a b ])))",
+        );
+
+        assert!(diff.is_alpha_equivalent());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn structural_differences() {
+        let diff = diff(
+            "(program 1.0.0 (lam x [ [ (builtin addInteger) x ] (con integer 1) ]))",
+            "(program 1.1.0 (lam x [ [ (builtin subtractInteger) x ] (con integer 1) ]))",
+        );
+
+        assert!(!diff.is_alpha_equivalent());
+        assert_eq!(diff.versions, Some(((1, 0, 0), (1, 1, 0))));
+        assert_eq!(diff.differences.len(), 1);
+        assert_eq!(diff.differences[0].path, ["lam v1", "fun", "fun"]);
+        assert!(diff
+            .to_string()
+            .contains("> fun > fun\n- (builtin addInteger)\n+ (builtin subtractInteger)\n"));
+    }
+
+    #[test]
+    fn binders_named_by_depth() {
+        let diff = diff(
+            "(program 1.0.0 [ (lam a a) (lam b b) ])",
+            "(program 1.0.0 [ (lam a (lam c a)) (lam b b) ])",
+        );
+
+        assert_eq!(diff.differences.len(), 1);
+        assert_eq!(diff.differences[0].path, ["fun", "lam v1"]);
+        assert_eq!(diff.to_string(), "fun > lam v1\n- v1\n+ (lam v2 v1)\n\n");
+    }
+
+    #[test]
+    fn shadowing() {
+        let diff = diff(
+            "(program 1.0.0 (lam x (lam x x)))",
+            "(program 1.0.0 (lam x (lam y x)))",
+        );
+
+        assert_eq!(diff.differences.len(), 1);
+        assert_eq!(diff.differences[0].path, ["lam v1", "lam v2"]);
+    }
+}
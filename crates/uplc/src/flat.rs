@@ -26,6 +26,8 @@ pub trait Binder<'b>: Encode + Decode<'b> {
 
 impl<'b, T> Flat<'b> for Program<T> where T: Binder<'b> + Debug {}
 
+impl Flat<'_> for Constant {}
+
 impl<'b, T> Program<T>
 where
     T: Binder<'b> + Debug,
@@ -173,6 +175,12 @@ where
         let term_option = Term::decode_debug(d, &mut state_log);
 
         match term_option {
+            Ok(term) if version < (1, 1, 0) && term.has_sums_of_products() => {
+                Err(de::Error::Message(format!(
+                    "constr and case terms are only allowed from program version 1.1.0, but found version {}.{}.{}",
+                    version.0, version.1, version.2
+                )))
+            }
             Ok(term) => Ok(Program { version, term }),
             Err(error) => Err(de::Error::Message(format!(
                 "{} {error}",
@@ -1154,6 +1162,30 @@ mod tests {
         assert_eq!(actual_program, expected_program)
     }
 
+    #[test]
+    fn unflat_rejects_constr_before_1_1_0() {
+        let program = Program::<Name> {
+            version: (1, 0, 0),
+            term: Term::Constr {
+                tag: 0,
+                fields: vec![],
+            },
+        };
+
+        let bytes = program.to_flat().unwrap();
+
+        assert!(Program::<Name>::unflat(&bytes).is_err());
+
+        let program = Program::<Name> {
+            version: (1, 1, 0),
+            ..program
+        };
+
+        let bytes = program.to_flat().unwrap();
+
+        assert_eq!(Program::<Name>::unflat(&bytes).unwrap(), program);
+    }
+
     #[test]
     fn unflat_string_escape() {
         let cbor = "490000004901015c0001";
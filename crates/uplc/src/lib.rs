@@ -2,12 +2,15 @@ pub mod ast;
 pub mod builder;
 pub mod builtins;
 mod debruijn;
+pub mod decompile;
+pub mod diff;
 pub mod flat;
 pub mod machine;
 pub mod optimize;
 pub mod parser;
 mod pretty;
 pub mod tx;
+pub mod verify;
 
 pub use pallas_codec::utils::KeyValuePairs;
 pub use pallas_crypto::hash::Hash;
@@ -1,11 +1,15 @@
-use std::rc::Rc;
+use std::{rc::Rc, time::Instant};
 
-use crate::ast::{Constant, NamedDeBruijn, Term, Type};
+use crate::{
+    ast::{Constant, NamedDeBruijn, Term, Type},
+    builtins::DefaultFunction,
+};
 
 pub mod cost_model;
 mod discharge;
 mod error;
 pub mod eval_result;
+mod pool;
 pub mod runtime;
 pub mod value;
 
@@ -15,6 +19,7 @@ use pallas_primitives::conway::Language;
 
 use self::{
     cost_model::CostModel,
+    pool::EnvPool,
     runtime::BuiltinRuntime,
     value::{Env, Value},
 };
@@ -45,6 +50,13 @@ enum Context {
 pub const TERM_COUNT: usize = 9;
 pub const BUILTIN_COUNT: usize = 87;
 
+/// A saturated builtin application, and the budget it spent on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinCall {
+    pub fun: DefaultFunction,
+    pub cost: ExBudget,
+}
+
 pub struct Machine {
     costs: CostModel,
     pub ex_budget: ExBudget,
@@ -52,7 +64,10 @@ pub struct Machine {
     unbudgeted_steps: [u32; 10],
     pub spend_counter: Option<[i64; (TERM_COUNT + BUILTIN_COUNT) * 2]>,
     pub logs: Vec<String>,
+    pub builtin_calls: Option<Vec<BuiltinCall>>,
     version: Language,
+    deadline: Option<Instant>,
+    pool: Option<EnvPool>,
 }
 
 impl Machine {
@@ -69,7 +84,10 @@ impl Machine {
             unbudgeted_steps: [0; 10],
             spend_counter: None,
             logs: vec![],
+            builtin_calls: None,
             version,
+            deadline: None,
+            pool: None,
         }
     }
 
@@ -86,7 +104,51 @@ impl Machine {
             unbudgeted_steps: [0; 10],
             spend_counter: Some([0; (TERM_COUNT + BUILTIN_COUNT) * 2]),
             logs: vec![],
+            builtin_calls: None,
             version,
+            deadline: None,
+            pool: None,
+        }
+    }
+
+    /// A machine for the given language, using the language's default cost model unless costs
+    /// are given.
+    pub fn for_language(
+        version: &Language,
+        costs: Option<&[i64]>,
+        initial_budget: ExBudget,
+    ) -> Machine {
+        Machine::new(
+            version.clone(),
+            match costs {
+                Some(costs) => cost_model::initialize_cost_model(version, costs),
+                None => CostModel::for_language(version),
+            },
+            initial_budget,
+            200, //slippage
+        )
+    }
+
+    /// Abort evaluation with 'Error::TimedOut' once past the given instant. The clock is only
+    /// checked when spending unbudgeted steps, so evaluation may overrun it by a few steps.
+    pub fn with_deadline(self, deadline: Option<Instant>) -> Self {
+        Machine { deadline, ..self }
+    }
+
+    /// Record every builtin application along with its cost, in evaluation order.
+    pub fn trace_builtins(self) -> Self {
+        Machine {
+            builtin_calls: Some(vec![]),
+            ..self
+        }
+    }
+
+    /// Recycle environment buffers within this evaluation, and across evaluations made by
+    /// pooled machines on the same thread.
+    pub fn pooled(self) -> Self {
+        Machine {
+            pool: Some(EnvPool::acquire()),
+            ..self
         }
     }
 
@@ -122,6 +184,8 @@ impl Machine {
 
                 let val = self.lookup_var(name.as_ref(), &env)?;
 
+                self.recycle(env);
+
                 Ok(MachineState::Return(context, val))
             }
             Term::Delay(body) => {
@@ -160,6 +224,8 @@ impl Machine {
             Term::Constant(x) => {
                 self.step_and_maybe_spend(StepKind::Constant)?;
 
+                self.recycle(env);
+
                 Ok(MachineState::Return(context, Value::Con(x)))
             }
             Term::Force(body) => {
@@ -177,6 +243,8 @@ impl Machine {
 
                 let runtime: BuiltinRuntime = fun.into();
 
+                self.recycle(env);
+
                 Ok(MachineState::Return(
                     context,
                     Value::Builtin { fun, runtime },
@@ -308,15 +376,18 @@ impl Machine {
     ) -> Result<MachineState, Error> {
         match function {
             Value::Lambda { body, mut env, .. } => {
-                let e = Rc::make_mut(&mut env);
+                let env = match &mut self.pool {
+                    Some(pool) => pool.extend(env, argument),
+                    None => {
+                        let e = Rc::make_mut(&mut env);
 
-                e.push(argument);
+                        e.push(argument);
 
-                Ok(MachineState::Compute(
-                    context,
-                    Rc::new(e.clone()),
-                    body.as_ref().clone(),
-                ))
+                        Rc::new(e.clone())
+                    }
+                };
+
+                Ok(MachineState::Compute(context, env, body.as_ref().clone()))
             }
             Value::Builtin { fun, runtime } => {
                 if runtime.is_arrow() && !runtime.needs_force() {
@@ -353,9 +424,22 @@ impl Machine {
             counter[i + 1] += cost.cpu;
         }
 
+        if let Some(calls) = &mut self.builtin_calls {
+            calls.push(BuiltinCall {
+                fun: runtime.fun,
+                cost,
+            });
+        }
+
         runtime.call(&self.version, &mut self.logs)
     }
 
+    fn recycle(&mut self, env: Env) {
+        if let Some(pool) = &mut self.pool {
+            pool.recycle(env);
+        }
+    }
+
     fn lookup_var(&mut self, name: &NamedDeBruijn, env: &[Value]) -> Result<Value, Error> {
         env.get::<usize>(env.len() - usize::from(name.index))
             .cloned()
@@ -393,6 +477,13 @@ impl Machine {
 
         self.unbudgeted_steps[9] = 0;
 
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(Error::TimedOut);
+        }
+
         Ok(())
     }
 
@@ -442,11 +533,41 @@ impl From<&Constant> for Type {
 mod tests {
     use num_bigint::BigInt;
 
-    use super::{cost_model::ExBudget, runtime::Compressable};
+    use super::{
+        cost_model::{CostModel, ExBudget},
+        runtime::Compressable,
+        Error, Machine,
+    };
     use crate::{
-        ast::{Constant, NamedDeBruijn, Program, Term},
+        ast::{Constant, Data, DeBruijn, Name, NamedDeBruijn, Program, Term},
         builtins::DefaultFunction,
     };
+    use pallas_primitives::conway::Language;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn marker_preserves_behaviour() {
+        let program: Program<Name> = crate::parser::program(
+            "(program 1.1.0 (lam x [(builtin addInteger) x (con integer 1)]))",
+        )
+        .unwrap();
+
+        let program: Program<DeBruijn> = program.try_into().unwrap();
+
+        let argument = Program {
+            version: program.version,
+            term: Term::Constant(Constant::Integer(41.into()).into()),
+        };
+
+        let run = |program: Program<DeBruijn>| {
+            Program::<NamedDeBruijn>::from(program.apply(&argument))
+                .eval(ExBudget::default())
+                .result()
+                .unwrap()
+        };
+
+        assert_eq!(run(program.with_marker("foo/bar@1.0.0")), run(program));
+    }
 
     #[test]
     fn add_big_ints() {
@@ -520,6 +641,127 @@ mod tests {
         }
     }
 
+    #[test]
+    fn deadline_aborts_runaway_evaluation() {
+        let omega = Term::<Name>::var("x").apply(Term::var("x")).lambda("x");
+
+        let program: Program<NamedDeBruijn> = Program {
+            version: (1, 0, 0),
+            term: omega.clone().apply(omega),
+        }
+        .try_into()
+        .unwrap();
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+
+        let eval_result =
+            program.eval_until(&Language::PlutusV3, None, ExBudget::max(), Some(deadline));
+
+        assert_eq!(eval_result.result(), Err(Error::TimedOut));
+    }
+
+    #[test]
+    fn pooled_evaluation_is_unchanged() {
+        // A fold over a list, so that environments are extended, shared and dropped a lot.
+        let sum = Term::<Name>::var("xs")
+            .choose_list(
+                Term::integer(0.into()).delay(),
+                Term::add_integer()
+                    .apply(Term::un_i_data().apply(Term::head_list().apply(Term::var("xs"))))
+                    .apply(
+                        Term::var("self")
+                            .apply(Term::var("self"))
+                            .apply(Term::tail_list().apply(Term::var("xs"))),
+                    )
+                    .delay(),
+            )
+            .force()
+            .lambda("xs")
+            .lambda("self");
+
+        let program: Program<NamedDeBruijn> = Program {
+            version: (1, 0, 0),
+            term: sum.clone().apply(sum).apply(Term::list_values(
+                (0..100)
+                    .map(|n| Constant::Data(Data::integer(n.into())))
+                    .collect(),
+            )),
+        }
+        .try_into()
+        .unwrap();
+
+        let machine = || {
+            Machine::new(
+                Language::PlutusV3,
+                CostModel::default(),
+                ExBudget::max(),
+                200,
+            )
+        };
+
+        let plain = program.clone().eval_with(machine());
+
+        for _ in 0..3 {
+            let pooled = program.clone().eval_with(machine().pooled());
+
+            assert_eq!(pooled.result(), Ok(Term::integer(4950.into())));
+            assert_eq!(pooled.result(), plain.result());
+            assert_eq!(pooled.cost(), plain.cost());
+        }
+    }
+
+    #[test]
+    fn traced_builtin_calls() {
+        let program: Program<NamedDeBruijn> = Program {
+            version: (1, 0, 0),
+            term: Term::<Name>::multiply_integer()
+                .apply(
+                    Term::add_integer()
+                        .apply(Term::integer(1.into()))
+                        .apply(Term::integer(2.into())),
+                )
+                .apply(Term::integer(14.into())),
+        }
+        .try_into()
+        .unwrap();
+
+        let machine = Machine::new(
+            Language::PlutusV3,
+            CostModel::default(),
+            ExBudget::max(),
+            200,
+        );
+
+        let untraced = program.clone().eval_with(machine);
+
+        assert_eq!(untraced.builtin_calls(), None);
+
+        let machine = Machine::new(
+            Language::PlutusV3,
+            CostModel::default(),
+            ExBudget::max(),
+            200,
+        );
+
+        let traced = program.eval_with(machine.trace_builtins());
+
+        assert_eq!(traced.result(), Ok(Term::integer(42.into())));
+        assert_eq!(traced.cost(), untraced.cost());
+
+        let calls = traced.builtin_calls().unwrap();
+
+        assert_eq!(
+            calls.iter().map(|call| call.fun).collect::<Vec<_>>(),
+            vec![
+                DefaultFunction::AddInteger,
+                DefaultFunction::MultiplyInteger
+            ]
+        );
+        assert!(calls
+            .iter()
+            .all(|call| call.cost.cpu > 0 && call.cost.cpu < traced.cost().cpu));
+    }
+
     #[test]
     fn case_constr_case_0() {
         let make_program =
@@ -694,4 +936,53 @@ mod tests {
 
         assert_eq!(final_term, Term::bool(true))
     }
+
+    #[test]
+    fn eval_version_uses_version_cost_model() {
+        let program: Program<NamedDeBruijn> = Program {
+            version: (1, 0, 0),
+            term: Term::Apply {
+                function: Term::Apply {
+                    function: Term::Builtin(DefaultFunction::DivideInteger).into(),
+                    argument: Term::Constant(Constant::Integer(8.into()).into()).into(),
+                }
+                .into(),
+                argument: Term::Constant(Constant::Integer(3.into()).into()).into(),
+            },
+        };
+
+        let cost_with = |version: Language, cost_model: CostModel| {
+            let mut machine = Machine::new(version, cost_model, ExBudget::max(), 200);
+            machine.run(program.term.clone()).unwrap();
+            ExBudget::max() - machine.ex_budget
+        };
+
+        let v1 = cost_with(Language::PlutusV1, CostModel::v1());
+        let v3 = cost_with(Language::PlutusV3, CostModel::v3());
+
+        assert_ne!(v1, v3);
+
+        assert_eq!(
+            program
+                .clone()
+                .eval_version(ExBudget::max(), &Language::PlutusV1)
+                .cost(),
+            v1
+        );
+
+        assert_eq!(
+            program
+                .clone()
+                .eval_debug(ExBudget::max(), &Language::PlutusV1)
+                .cost(),
+            v1
+        );
+
+        assert_eq!(
+            program
+                .eval_version(ExBudget::max(), &Language::PlutusV3)
+                .cost(),
+            v3
+        );
+    }
 }
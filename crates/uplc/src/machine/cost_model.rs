@@ -2,7 +2,7 @@ use super::{Error, Value};
 use crate::builtins::DefaultFunction;
 use num_traits::Signed;
 use pallas_primitives::conway::Language;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use strum::{Display, EnumIter};
 
@@ -85,6 +85,16 @@ impl CostModel {
             builtin_costs: BuiltinCosts::v3(),
         }
     }
+
+    /// The default cost model of a given language version. Each version has its own set of
+    /// parameters, so budgets are only meaningful when measured against the right one.
+    pub fn for_language(version: &Language) -> Self {
+        match version {
+            Language::PlutusV1 => Self::v1(),
+            Language::PlutusV2 => Self::v2(),
+            Language::PlutusV3 => Self::v3(),
+        }
+    }
 }
 
 /// There's no entry for Error since we'll be exiting anyway; also, what would
@@ -2707,6 +2717,123 @@ impl BuiltinCosts {
     }
 }
 
+/// The costing functions of a builtin, as human-readable formulas in terms of the sizes of its
+/// arguments ('x', 'y', 'z', ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostFormula {
+    pub mem: String,
+    pub cpu: String,
+}
+
+impl<T: fmt::Display> CostingFun<T> {
+    pub fn formula(&self) -> CostFormula {
+        CostFormula {
+            mem: self.mem.to_string(),
+            cpu: self.cpu.to_string(),
+        }
+    }
+}
+
+impl BuiltinCosts {
+    pub fn formula(&self, fun: DefaultFunction) -> CostFormula {
+        match fun {
+            DefaultFunction::AddInteger => self.add_integer.formula(),
+            DefaultFunction::SubtractInteger => self.subtract_integer.formula(),
+            DefaultFunction::MultiplyInteger => self.multiply_integer.formula(),
+            DefaultFunction::DivideInteger => self.divide_integer.formula(),
+            DefaultFunction::QuotientInteger => self.quotient_integer.formula(),
+            DefaultFunction::RemainderInteger => self.remainder_integer.formula(),
+            DefaultFunction::ModInteger => self.mod_integer.formula(),
+            DefaultFunction::EqualsInteger => self.equals_integer.formula(),
+            DefaultFunction::LessThanInteger => self.less_than_integer.formula(),
+            DefaultFunction::LessThanEqualsInteger => self.less_than_equals_integer.formula(),
+            DefaultFunction::AppendByteString => self.append_byte_string.formula(),
+            DefaultFunction::ConsByteString => self.cons_byte_string.formula(),
+            DefaultFunction::SliceByteString => self.slice_byte_string.formula(),
+            DefaultFunction::LengthOfByteString => self.length_of_byte_string.formula(),
+            DefaultFunction::IndexByteString => self.index_byte_string.formula(),
+            DefaultFunction::EqualsByteString => self.equals_byte_string.formula(),
+            DefaultFunction::LessThanByteString => self.less_than_byte_string.formula(),
+            DefaultFunction::LessThanEqualsByteString => {
+                self.less_than_equals_byte_string.formula()
+            }
+            DefaultFunction::Sha2_256 => self.sha2_256.formula(),
+            DefaultFunction::Sha3_256 => self.sha3_256.formula(),
+            DefaultFunction::Blake2b_256 => self.blake2b_256.formula(),
+            DefaultFunction::VerifyEd25519Signature => self.verify_ed25519_signature.formula(),
+            DefaultFunction::VerifyEcdsaSecp256k1Signature => {
+                self.verify_ecdsa_secp256k1_signature.formula()
+            }
+            DefaultFunction::VerifySchnorrSecp256k1Signature => {
+                self.verify_schnorr_secp256k1_signature.formula()
+            }
+            DefaultFunction::AppendString => self.append_string.formula(),
+            DefaultFunction::EqualsString => self.equals_string.formula(),
+            DefaultFunction::EncodeUtf8 => self.encode_utf8.formula(),
+            DefaultFunction::DecodeUtf8 => self.decode_utf8.formula(),
+            DefaultFunction::IfThenElse => self.if_then_else.formula(),
+            DefaultFunction::ChooseUnit => self.choose_unit.formula(),
+            DefaultFunction::Trace => self.trace.formula(),
+            DefaultFunction::FstPair => self.fst_pair.formula(),
+            DefaultFunction::SndPair => self.snd_pair.formula(),
+            DefaultFunction::ChooseList => self.choose_list.formula(),
+            DefaultFunction::MkCons => self.mk_cons.formula(),
+            DefaultFunction::HeadList => self.head_list.formula(),
+            DefaultFunction::TailList => self.tail_list.formula(),
+            DefaultFunction::NullList => self.null_list.formula(),
+            DefaultFunction::ChooseData => self.choose_data.formula(),
+            DefaultFunction::ConstrData => self.constr_data.formula(),
+            DefaultFunction::MapData => self.map_data.formula(),
+            DefaultFunction::ListData => self.list_data.formula(),
+            DefaultFunction::IData => self.i_data.formula(),
+            DefaultFunction::BData => self.b_data.formula(),
+            DefaultFunction::UnConstrData => self.un_constr_data.formula(),
+            DefaultFunction::UnMapData => self.un_map_data.formula(),
+            DefaultFunction::UnListData => self.un_list_data.formula(),
+            DefaultFunction::UnIData => self.un_i_data.formula(),
+            DefaultFunction::UnBData => self.un_b_data.formula(),
+            DefaultFunction::EqualsData => self.equals_data.formula(),
+            DefaultFunction::SerialiseData => self.serialise_data.formula(),
+            DefaultFunction::MkPairData => self.mk_pair_data.formula(),
+            DefaultFunction::MkNilData => self.mk_nil_data.formula(),
+            DefaultFunction::MkNilPairData => self.mk_nil_pair_data.formula(),
+            DefaultFunction::Keccak_256 => self.keccak_256.formula(),
+            DefaultFunction::Blake2b_224 => self.blake2b_224.formula(),
+            DefaultFunction::Bls12_381_G1_Add => self.bls12_381_g1_add.formula(),
+            DefaultFunction::Bls12_381_G1_Neg => self.bls12_381_g1_neg.formula(),
+            DefaultFunction::Bls12_381_G1_ScalarMul => self.bls12_381_g1_scalar_mul.formula(),
+            DefaultFunction::Bls12_381_G1_Equal => self.bls12_381_g1_equal.formula(),
+            DefaultFunction::Bls12_381_G1_Compress => self.bls12_381_g1_compress.formula(),
+            DefaultFunction::Bls12_381_G1_Uncompress => self.bls12_381_g1_uncompress.formula(),
+            DefaultFunction::Bls12_381_G1_HashToGroup => self.bls12_381_g1_hash_to_group.formula(),
+            DefaultFunction::Bls12_381_G2_Add => self.bls12_381_g2_add.formula(),
+            DefaultFunction::Bls12_381_G2_Neg => self.bls12_381_g2_neg.formula(),
+            DefaultFunction::Bls12_381_G2_ScalarMul => self.bls12_381_g2_scalar_mul.formula(),
+            DefaultFunction::Bls12_381_G2_Equal => self.bls12_381_g2_equal.formula(),
+            DefaultFunction::Bls12_381_G2_Compress => self.bls12_381_g2_compress.formula(),
+            DefaultFunction::Bls12_381_G2_Uncompress => self.bls12_381_g2_uncompress.formula(),
+            DefaultFunction::Bls12_381_G2_HashToGroup => self.bls12_381_g2_hash_to_group.formula(),
+            DefaultFunction::Bls12_381_MillerLoop => self.bls12_381_miller_loop.formula(),
+            DefaultFunction::Bls12_381_MulMlResult => self.bls12_381_mul_ml_result.formula(),
+            DefaultFunction::Bls12_381_FinalVerify => self.bls12_381_final_verify.formula(),
+            DefaultFunction::IntegerToByteString => self.integer_to_byte_string.formula(),
+            DefaultFunction::ByteStringToInteger => self.byte_string_to_integer.formula(),
+            DefaultFunction::AndByteString => self.and_byte_string.formula(),
+            DefaultFunction::OrByteString => self.or_byte_string.formula(),
+            DefaultFunction::XorByteString => self.xor_byte_string.formula(),
+            DefaultFunction::ComplementByteString => self.complement_byte_string.formula(),
+            DefaultFunction::ReadBit => self.read_bit.formula(),
+            DefaultFunction::WriteBits => self.write_bits.formula(),
+            DefaultFunction::ReplicateByte => self.replicate_byte.formula(),
+            DefaultFunction::ShiftByteString => self.shift_byte_string.formula(),
+            DefaultFunction::RotateByteString => self.rotate_byte_string.formula(),
+            DefaultFunction::CountSetBits => self.count_set_bits.formula(),
+            DefaultFunction::FindFirstSetBit => self.find_first_set_bit.formula(),
+            DefaultFunction::Ripemd_160 => self.ripemd_160.formula(),
+        }
+    }
+}
+
 pub fn initialize_cost_model(version: &Language, costs: &[i64]) -> CostModel {
     let cost_map: HashMap<&str, i64> = match version {
         Language::PlutusV1 => {
@@ -5271,6 +5398,102 @@ impl SixArguments {
     }
 }
 
+fn linear(intercept: i64, slope: i64, size: &str) -> String {
+    format!("{intercept} + {slope} * {size}")
+}
+
+fn quadratic(q: &QuadraticFunction, size: &str) -> String {
+    format!(
+        "{} + {} * {size} + {} * {size}^2",
+        q.coeff_0, q.coeff_1, q.coeff_2
+    )
+}
+
+impl fmt::Display for OneArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OneArgument::ConstantCost(c) => write!(f, "{c}"),
+            OneArgument::LinearCost(l) => write!(f, "{}", linear(l.intercept, l.slope, "x")),
+        }
+    }
+}
+
+impl fmt::Display for TwoArguments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TwoArguments::ConstantCost(c) => write!(f, "{c}"),
+            TwoArguments::LinearInX(l) => write!(f, "{}", linear(l.intercept, l.slope, "x")),
+            TwoArguments::LinearInY(l) => write!(f, "{}", linear(l.intercept, l.slope, "y")),
+            TwoArguments::LinearInXAndY(l) => {
+                write!(f, "{} + {} * x + {} * y", l.intercept, l.slope1, l.slope2)
+            }
+            TwoArguments::AddedSizes(s) => write!(f, "{}", linear(s.intercept, s.slope, "(x + y)")),
+            TwoArguments::SubtractedSizes(s) => write!(
+                f,
+                "{}",
+                linear(s.intercept, s.slope, &format!("max({}, x - y)", s.minimum))
+            ),
+            TwoArguments::MultipliedSizes(s) => {
+                write!(f, "{}", linear(s.intercept, s.slope, "(x * y)"))
+            }
+            TwoArguments::MinSize(s) => write!(f, "{}", linear(s.intercept, s.slope, "min(x, y)")),
+            TwoArguments::MaxSize(s) => write!(f, "{}", linear(s.intercept, s.slope, "max(x, y)")),
+            TwoArguments::LinearOnDiagonal(l) => write!(
+                f,
+                "if x == y then {} else {}",
+                linear(l.intercept, l.slope, "x"),
+                l.constant
+            ),
+            TwoArguments::ConstAboveDiagonal(l) => {
+                write!(f, "if x < y then {} else {}", l.constant, l.model)
+            }
+            TwoArguments::ConstBelowDiagonal(l) => {
+                write!(f, "if x > y then {} else {}", l.constant, l.model)
+            }
+            TwoArguments::QuadraticInY(q) => write!(f, "{}", quadratic(q, "y")),
+            TwoArguments::ConstAboveDiagonalIntoQuadraticXAndY(constant, q) => write!(
+                f,
+                "if x < y then {constant} else max({}, {} + {} * x + {} * y + {} * x^2 + {} * x * y + {} * y^2)",
+                q.minimum, q.coeff_00, q.coeff_10, q.coeff_01, q.coeff_20, q.coeff_11, q.coeff_02
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ThreeArguments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThreeArguments::ConstantCost(c) => write!(f, "{c}"),
+            ThreeArguments::AddedSizes(s) => {
+                write!(f, "{}", linear(s.intercept, s.slope, "(x + y + z)"))
+            }
+            ThreeArguments::LinearInX(l) => write!(f, "{}", linear(l.intercept, l.slope, "x")),
+            ThreeArguments::LinearInY(l) => write!(f, "{}", linear(l.intercept, l.slope, "y")),
+            ThreeArguments::LinearInZ(l) => write!(f, "{}", linear(l.intercept, l.slope, "z")),
+            ThreeArguments::QuadraticInZ(q) => write!(f, "{}", quadratic(q, "z")),
+            ThreeArguments::LiteralInYorLinearInZ(l) => write!(
+                f,
+                "if y == 0 then {} else y",
+                linear(l.intercept, l.slope, "z")
+            ),
+            ThreeArguments::LinearInMaxYZ(l) => {
+                write!(f, "{}", linear(l.intercept, l.slope, "max(y, z)"))
+            }
+            ThreeArguments::LinearInYandZ(l) => {
+                write!(f, "{} + {} * y + {} * z", l.intercept, l.slope1, l.slope2)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SixArguments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SixArguments::ConstantCost(c) => write!(f, "{c}"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct LinearSize {
     pub intercept: i64,
@@ -5484,4 +5707,19 @@ mod tests {
 
         assert_eq!(CostModel::v3(), cost_model);
     }
+
+    #[test]
+    fn builtin_cost_formulas() {
+        let costs = BuiltinCosts::v3();
+
+        assert_eq!(
+            costs.formula(DefaultFunction::AddInteger),
+            CostFormula {
+                mem: "1 + 1 * max(x, y)".to_string(),
+                cpu: "100788 + 420 * max(x, y)".to_string(),
+            }
+        );
+
+        assert_eq!(costs.formula(DefaultFunction::IfThenElse).mem, "1");
+    }
 }
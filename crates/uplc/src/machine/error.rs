@@ -7,6 +7,8 @@ use std::string::FromUtf8Error;
 pub enum Error {
     #[error("execution went over budget\n{:>13} {}\n{:>13} {}", "Mem", .0.mem, "CPU", .0.cpu)]
     OutOfExError(ExBudget),
+    #[error("evaluation took longer than allowed and was aborted")]
+    TimedOut,
     #[error("invalid step kind: {0}")]
     InvalidStepKind(u8),
     #[error(
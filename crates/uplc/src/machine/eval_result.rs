@@ -1,4 +1,4 @@
-use super::{cost_model::ExBudget, Error};
+use super::{cost_model::ExBudget, BuiltinCall, Error};
 use crate::ast::{Constant, NamedDeBruijn, Term};
 
 #[derive(Debug)]
@@ -8,6 +8,7 @@ pub struct EvalResult {
     initial_budget: ExBudget,
     logs: Vec<String>,
     debug_cost: Option<Vec<i64>>,
+    builtin_calls: Option<Vec<BuiltinCall>>,
 }
 
 impl EvalResult {
@@ -24,6 +25,14 @@ impl EvalResult {
             initial_budget,
             logs,
             debug_cost,
+            builtin_calls: None,
+        }
+    }
+
+    pub fn with_builtin_calls(self, builtin_calls: Option<Vec<BuiltinCall>>) -> EvalResult {
+        EvalResult {
+            builtin_calls,
+            ..self
         }
     }
 
@@ -35,8 +44,16 @@ impl EvalResult {
         std::mem::take(&mut self.logs)
     }
 
+    /// Whether evaluation was aborted for running past its deadline.
+    pub fn timed_out(&self) -> bool {
+        matches!(self.result, Err(Error::TimedOut))
+    }
+
+    /// A timeout is a failure even when errors are expected: the program never got to finish.
     pub fn failed(&self, can_error: bool) -> bool {
-        if can_error {
+        if self.timed_out() {
+            true
+        } else if can_error {
             self.result.is_ok()
                 && !matches!(self.result, Ok(Term::Constant(ref con)) if matches!(con.as_ref(), Constant::Bool(false)))
         } else {
@@ -54,6 +71,11 @@ impl EvalResult {
         self.debug_cost.clone()
     }
 
+    /// Builtin applications made during evaluation, when traced by the machine.
+    pub fn builtin_calls(&self) -> Option<&[BuiltinCall]> {
+        self.builtin_calls.as_deref()
+    }
+
     #[allow(clippy::result_unit_err)]
     pub fn unwrap_constant(self) -> Result<Constant, ()> {
         match self.result {
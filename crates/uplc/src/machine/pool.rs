@@ -0,0 +1,69 @@
+use super::value::{Env, Value};
+use std::{cell::RefCell, mem, rc::Rc};
+
+/// Maximum number of buffers kept around for reuse.
+const MAX_POOLED_BUFFERS: usize = 1024;
+
+thread_local! {
+    /// Buffers left over by previous evaluations on this thread, handed over to the next pooled
+    /// machine. Values aren't 'Send', so a pool can't outlive the thread that filled it anyway.
+    static POOL: RefCell<Vec<Vec<Value>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A free-list of the buffers backing environments. Extending an environment otherwise costs a
+/// fresh allocation per application, which adds up quickly when running the same program
+/// thousands of times (e.g. property tests).
+pub(super) struct EnvPool {
+    free: Vec<Vec<Value>>,
+}
+
+impl EnvPool {
+    /// Take over the buffers left on this thread by previous evaluations.
+    pub(super) fn acquire() -> Self {
+        EnvPool {
+            free: POOL.with(|pool| mem::take(&mut *pool.borrow_mut())),
+        }
+    }
+
+    /// Extend an environment with a new binding, in place when nothing else refers to it, or
+    /// copying it into a recycled buffer otherwise.
+    pub(super) fn extend(&mut self, mut env: Env, argument: Value) -> Env {
+        if let Some(values) = Rc::get_mut(&mut env) {
+            values.push(argument);
+            return env;
+        }
+
+        let mut values = self.free.pop().unwrap_or_default();
+
+        values.extend_from_slice(&env);
+        values.push(argument);
+
+        Rc::new(values)
+    }
+
+    /// Give an environment back to the pool, provided it is no longer referenced.
+    pub(super) fn recycle(&mut self, env: Env) {
+        if self.free.len() >= MAX_POOLED_BUFFERS {
+            return;
+        }
+
+        if let Ok(mut values) = Rc::try_unwrap(env) {
+            values.clear();
+            self.free.push(values);
+        }
+    }
+}
+
+impl Drop for EnvPool {
+    fn drop(&mut self) {
+        let free = mem::take(&mut self.free);
+
+        POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+
+            if pool.len() < free.len() {
+                *pool = free;
+            }
+        });
+    }
+}
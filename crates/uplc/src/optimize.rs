@@ -1,42 +1,254 @@
 use crate::ast::{Name, Program};
+use std::{fmt, str::FromStr};
 
 pub mod interner;
+pub mod peephole;
 pub mod shrinker;
 
+/// An optimization pass, as can be listed in a custom optimization pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pass {
+    /// Inline, reduce and fold terms until the program stops shrinking.
+    Shrink,
+    /// A single round of 'Shrink'.
+    ShrinkOnce,
+    /// Local rewrites of well-known patterns into cheaper ones.
+    Peephole,
+    /// Hoist builtins partially applied to the same arguments.
+    Curry,
+    /// Bind constants repeated often enough to be worth it once, at the program root. Since it
+    /// must see the program in its final shape, it runs last wherever it's listed.
+    ShareConstants,
+}
+
+impl Pass {
+    pub const ALL: [Pass; 5] = [
+        Pass::Shrink,
+        Pass::ShrinkOnce,
+        Pass::Peephole,
+        Pass::Curry,
+        Pass::ShareConstants,
+    ];
+
+    pub fn run(self, program: Program<Name>) -> Program<Name> {
+        self.run_from(program, &mut 0)
+    }
+
+    /// Like 'run', with 'node_count' the size of the program after the last 'Shrink' round. A
+    /// 'Shrink' stops as soon as a round leaves the program at that size, including its first.
+    fn run_from(self, program: Program<Name>, node_count: &mut usize) -> Program<Name> {
+        match self {
+            Pass::Shrink => {
+                let mut prog = program;
+
+                loop {
+                    let (current_program, context) = prog.multi_pass();
+
+                    prog = current_program;
+
+                    if context.node_count == *node_count {
+                        break prog;
+                    }
+
+                    *node_count = context.node_count;
+                }
+            }
+            Pass::ShrinkOnce => program.multi_pass().0,
+            Pass::Peephole => program.peephole(),
+            Pass::Curry => program.builtin_curry_reducer(),
+            Pass::ShareConstants => program.share_constants(),
+        }
+    }
+}
+
+impl fmt::Display for Pass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Pass::Shrink => "shrink",
+            Pass::ShrinkOnce => "shrink-once",
+            Pass::Peephole => "peephole",
+            Pass::Curry => "curry",
+            Pass::ShareConstants => "share-constants",
+        })
+    }
+}
+
+impl FromStr for Pass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pass::ALL
+            .into_iter()
+            .find(|pass| pass.to_string() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown optimization pass '{s}', expected one of: {}",
+                    Pass::ALL.map(|pass| pass.to_string()).join(", ")
+                )
+            })
+    }
+}
+
+/// The pipeline run by 'aiken_optimize_and_intern'.
+pub const DEFAULT_PASSES: [Pass; 7] = [
+    Pass::Shrink,
+    Pass::Peephole,
+    Pass::Curry,
+    Pass::ShrinkOnce,
+    Pass::Curry,
+    Pass::Shrink,
+    Pass::ShareConstants,
+];
+
 pub fn aiken_optimize_and_intern(program: Program<Name>) -> Program<Name> {
+    optimize_with(program, &DEFAULT_PASSES, |_, _| {})
+}
+
+/// Optimize a program running the given passes in order, calling 'after_pass' with the state of
+/// the program after each of them. Passes that are necessary for the program to be well-formed
+/// always run, before and after the pipeline.
+pub fn optimize_with(
+    program: Program<Name>,
+    passes: &[Pass],
+    mut after_pass: impl FnMut(Pass, &Program<Name>),
+) -> Program<Name> {
     let mut prog = program.run_once_pass();
 
-    let mut prev_count = 0;
+    let mut node_count = 0;
 
-    loop {
-        let (current_program, context) = prog.multi_pass();
+    for pass in passes.iter().filter(|pass| **pass != Pass::ShareConstants) {
+        prog = pass.run_from(prog, &mut node_count);
+        after_pass(*pass, &prog);
+    }
 
-        if context.node_count == prev_count {
-            prog = current_program;
-            break;
-        } else {
-            prog = current_program;
-            prev_count = context.node_count;
+    prog = prog.clean_up_no_inlines().afterwards();
+
+    if passes.contains(&Pass::ShareConstants) {
+        prog = Pass::ShareConstants.run(prog);
+        after_pass(Pass::ShareConstants, &prog);
+    }
+
+    prog
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aiken_optimize_and_intern, optimize_with, Pass};
+    use crate::{
+        ast::{Name, NamedDeBruijn, Program, Term},
+        optimize::interner::CodeGenInterner,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn program(term: Term<Name>) -> Program<Name> {
+        let mut program = Program {
+            version: (1, 1, 0),
+            term,
+        };
+
+        CodeGenInterner::new().program(&mut program);
+
+        program
+    }
+
+    fn assert_same(left: Program<Name>, right: Program<Name>) {
+        let left: Program<NamedDeBruijn> = left.try_into().unwrap();
+        let right: Program<NamedDeBruijn> = right.try_into().unwrap();
+        assert_eq!(left, right);
+    }
+
+    /// A program which takes a few rounds of shrinking to reduce.
+    fn nested_applications() -> Term<Name> {
+        Term::add_integer()
+            .apply(Term::var("a"))
+            .apply(Term::var("b"))
+            .lambda("b")
+            .apply(Term::var("a"))
+            .lambda("a")
+            .apply(Term::var("x").lambda("x").apply(Term::var("z")))
+            .lambda("f")
+            .apply(Term::var("y").lambda("y"))
+            .lambda("z")
+    }
+
+    #[test]
+    fn pass_names_round_trip() {
+        for pass in Pass::ALL {
+            assert_eq!(pass.to_string().parse::<Pass>(), Ok(pass));
+            assert_eq!(serde_json::to_string(&pass).unwrap(), format!("\"{pass}\""));
         }
+
+        assert!("inline".parse::<Pass>().is_err());
+    }
+
+    #[test]
+    fn share_constants_runs_last() {
+        let program = Program {
+            version: (1, 1, 0),
+            term: Term::var("x").lambda("x"),
+        };
+
+        let mut ran = vec![];
+
+        optimize_with(program, &[Pass::ShareConstants, Pass::Shrink], |pass, _| {
+            ran.push(pass)
+        });
+
+        assert_eq!(ran, vec![Pass::Shrink, Pass::ShareConstants]);
+    }
+
+    #[test]
+    fn shrink_reduces_to_a_fixpoint() {
+        let shrunk = Pass::Shrink.run(program(nested_applications()).run_once_pass());
+
+        assert_same(
+            shrunk,
+            program(
+                Term::add_integer()
+                    .apply(Term::var("z"))
+                    .apply(Term::var("z"))
+                    .lambda("z"),
+            ),
+        );
     }
 
-    prog = prog
-        .builtin_curry_reducer()
-        .multi_pass()
-        .0
-        .builtin_curry_reducer();
+    #[test]
+    fn default_pipeline_matches_historical_optimizations() {
+        let mut prog = program(nested_applications()).run_once_pass();
 
-    loop {
-        let (current_program, context) = prog.multi_pass();
+        let mut prev_count = 0;
 
-        if context.node_count == prev_count {
+        loop {
+            let (current_program, context) = prog.multi_pass();
             prog = current_program;
-            break;
-        } else {
+            if context.node_count == prev_count {
+                break;
+            }
+            prev_count = context.node_count;
+        }
+
+        prog = prog
+            .peephole()
+            .builtin_curry_reducer()
+            .multi_pass()
+            .0
+            .builtin_curry_reducer();
+
+        loop {
+            let (current_program, context) = prog.multi_pass();
             prog = current_program;
+            if context.node_count == prev_count {
+                break;
+            }
             prev_count = context.node_count;
         }
-    }
 
-    prog.clean_up_no_inlines().afterwards()
+        let expected = prog.clean_up_no_inlines().afterwards().share_constants();
+
+        assert_same(
+            aiken_optimize_and_intern(program(nested_applications())),
+            expected,
+        );
+    }
 }
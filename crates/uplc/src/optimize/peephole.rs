@@ -0,0 +1,390 @@
+use crate::{
+    ast::{Constant, Name, Program, Term},
+    builtins::DefaultFunction,
+};
+use std::rc::Rc;
+
+/// A local rewrite over terms. A rule looks at a single node and returns its replacement when it
+/// applies, or 'None' to leave the node untouched. Rules must strictly shrink the term and
+/// preserve its semantics (including the order in which traces are emitted).
+pub struct Rule {
+    pub name: &'static str,
+    pub rewrite: fn(&Term<Name>) -> Option<Term<Name>>,
+}
+
+/// Rules applied by the default peephole pass, in order of precedence.
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "constant_branches",
+        rewrite: constant_branches,
+    },
+    Rule {
+        name: "negated_condition",
+        rewrite: negated_condition,
+    },
+    Rule {
+        name: "if_true_false",
+        rewrite: if_true_false,
+    },
+    Rule {
+        name: "merged_error_branches",
+        rewrite: merged_error_branches,
+    },
+];
+
+impl Program<Name> {
+    pub fn peephole(self) -> Self {
+        self.peephole_with(RULES)
+    }
+
+    pub fn peephole_with(self, rules: &[Rule]) -> Self {
+        let mut term = self.term;
+
+        term.peephole(rules);
+
+        Program {
+            version: self.version,
+            term,
+        }
+    }
+}
+
+impl Term<Name> {
+    /// Rewrite children first, so that rules always see normalized sub-terms, then keep
+    /// rewriting the node itself until no rule applies.
+    fn peephole(&mut self, rules: &[Rule]) {
+        match self {
+            Term::Delay(d) | Term::Force(d) | Term::Lambda { body: d, .. } => {
+                Rc::make_mut(d).peephole(rules)
+            }
+            Term::Apply { function, argument } => {
+                Rc::make_mut(function).peephole(rules);
+                Rc::make_mut(argument).peephole(rules);
+            }
+            Term::Constr { fields, .. } => {
+                for field in fields {
+                    field.peephole(rules);
+                }
+            }
+            Term::Case { constr, branches } => {
+                Rc::make_mut(constr).peephole(rules);
+                for branch in branches {
+                    branch.peephole(rules);
+                }
+            }
+            Term::Var(_) | Term::Constant(_) | Term::Builtin(_) | Term::Error => (),
+        }
+
+        while let Some(rewritten) = rules.iter().find_map(|rule| (rule.rewrite)(self)) {
+            *self = rewritten;
+        }
+    }
+}
+
+/// force (ifThenElse c (delay v1) (delay v2)) ~> ifThenElse c v1 v2, when v1 and v2 are values.
+fn constant_branches(term: &Term<Name>) -> Option<Term<Name>> {
+    let Term::Force(inner) = term else {
+        return None;
+    };
+
+    let (head, condition, then, otherwise) = as_if_then_else(inner)?;
+
+    match (then, otherwise) {
+        (Term::Delay(then), Term::Delay(otherwise)) if is_value(then) && is_value(otherwise) => {
+            Some(
+                head.clone()
+                    .apply(condition.clone())
+                    .apply(then.as_ref().clone())
+                    .apply(otherwise.as_ref().clone()),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// ifThenElse (ifThenElse c False True) t e ~> ifThenElse c e t, when t and e are values.
+fn negated_condition(term: &Term<Name>) -> Option<Term<Name>> {
+    let (head, condition, then, otherwise) = as_if_then_else(term)?;
+
+    let (_, negated, if_true, if_false) = as_if_then_else(condition)?;
+
+    (is_bool(if_true, false) && is_bool(if_false, true) && is_value(then) && is_value(otherwise))
+        .then(|| {
+            head.clone()
+                .apply(negated.clone())
+                .apply(otherwise.clone())
+                .apply(then.clone())
+        })
+}
+
+/// ifThenElse c True False ~> c
+fn if_true_false(term: &Term<Name>) -> Option<Term<Name>> {
+    let (_, condition, then, otherwise) = as_if_then_else(term)?;
+
+    (is_bool(then, true) && is_bool(otherwise, false)).then(|| condition.clone())
+}
+
+/// if c1 { error } else { if c2 { error } else { x } } ~> if (if c1 { True } else { c2 }) { error } else { x }
+/// if c1 { if c2 { x } else { error } } else { error } ~> if (if c1 { c2 } else { False }) { x } else { error }
+///
+/// That is, chains of checks failing the same way are merged into a single one, when the inner
+/// condition is a value (so evaluating it eagerly can't fail or trace).
+fn merged_error_branches(term: &Term<Name>) -> Option<Term<Name>> {
+    let (head, outer, then, otherwise) = as_delayed_if_then_else(term)?;
+
+    let merged = |condition: Term<Name>, then: &Term<Name>, otherwise: &Term<Name>| {
+        head.clone()
+            .apply(condition)
+            .apply(then.clone().delay())
+            .apply(otherwise.clone().delay())
+            .force()
+    };
+
+    match (then, otherwise) {
+        (Term::Error, inner) => {
+            let (_, condition, Term::Error, x) = as_delayed_if_then_else(inner)? else {
+                return None;
+            };
+
+            is_value(condition).then(|| {
+                merged(
+                    head.clone()
+                        .apply(outer.clone())
+                        .apply(Term::bool(true))
+                        .apply(condition.clone()),
+                    &Term::Error,
+                    x,
+                )
+            })
+        }
+        (inner, Term::Error) => {
+            let (_, condition, x, Term::Error) = as_delayed_if_then_else(inner)? else {
+                return None;
+            };
+
+            is_value(condition).then(|| {
+                merged(
+                    head.clone()
+                        .apply(outer.clone())
+                        .apply(condition.clone())
+                        .apply(Term::bool(false)),
+                    x,
+                    &Term::Error,
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Destructure 'force (ifThenElse c (delay t) (delay e))' into its head, condition, 't' and 'e'.
+fn as_delayed_if_then_else(
+    term: &Term<Name>,
+) -> Option<(&Term<Name>, &Term<Name>, &Term<Name>, &Term<Name>)> {
+    let Term::Force(inner) = term else {
+        return None;
+    };
+
+    match as_if_then_else(inner)? {
+        (head, condition, Term::Delay(then), Term::Delay(otherwise)) => {
+            Some((head, condition, then.as_ref(), otherwise.as_ref()))
+        }
+        _ => None,
+    }
+}
+
+/// Destructure a saturated call to 'ifThenElse', either as the forced builtin or through its
+/// wrapped name, into its head, condition and branches.
+fn as_if_then_else(
+    term: &Term<Name>,
+) -> Option<(&Term<Name>, &Term<Name>, &Term<Name>, &Term<Name>)> {
+    let Term::Apply {
+        function,
+        argument: otherwise,
+    } = term
+    else {
+        return None;
+    };
+
+    let Term::Apply {
+        function,
+        argument: then,
+    } = function.as_ref()
+    else {
+        return None;
+    };
+
+    let Term::Apply {
+        function: head,
+        argument: condition,
+    } = function.as_ref()
+    else {
+        return None;
+    };
+
+    let is_if_then_else = match head.as_ref() {
+        Term::Force(builtin) => {
+            matches!(builtin.as_ref(), Term::Builtin(DefaultFunction::IfThenElse))
+        }
+        Term::Var(name) => name.text == DefaultFunction::IfThenElse.wrapped_name(),
+        _ => false,
+    };
+
+    is_if_then_else.then_some((head, condition, then, otherwise))
+}
+
+/// Terms that evaluate in a single step and can't fail.
+fn is_value(term: &Term<Name>) -> bool {
+    matches!(
+        term,
+        Term::Var(_) | Term::Constant(_) | Term::Builtin(_) | Term::Lambda { .. } | Term::Delay(_)
+    )
+}
+
+fn is_bool(term: &Term<Name>, expected: bool) -> bool {
+    matches!(term, Term::Constant(c) if matches!(c.as_ref(), Constant::Bool(b) if *b == expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rule, RULES};
+    use crate::{
+        ast::{Name, Program, Term},
+        builtins::DefaultFunction,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn rule(name: &str) -> &'static [Rule] {
+        let index = RULES
+            .iter()
+            .position(|rule| rule.name == name)
+            .unwrap_or_else(|| panic!("unknown rule {name}"));
+
+        &RULES[index..=index]
+    }
+
+    fn assert_peephole(rules: &[Rule], term: Term<Name>, expected: Term<Name>) {
+        let program = Program {
+            version: (1, 0, 0),
+            term,
+        };
+
+        assert_eq!(program.peephole_with(rules).term, expected);
+    }
+
+    fn wrapped_if_then_else() -> Term<Name> {
+        Term::var(DefaultFunction::IfThenElse.wrapped_name())
+    }
+
+    #[test]
+    fn constant_branches() {
+        assert_peephole(
+            rule("constant_branches"),
+            Term::var("c").delayed_if_then_else(Term::integer(1.into()), Term::var("x")),
+            Term::var("c").if_then_else(Term::integer(1.into()), Term::var("x")),
+        );
+    }
+
+    #[test]
+    fn constant_branches_keeps_computations_delayed() {
+        let term = Term::var("c").delayed_if_then_else(
+            Term::var("f").apply(Term::var("x")),
+            Term::integer(1.into()),
+        );
+
+        assert_peephole(rule("constant_branches"), term.clone(), term);
+    }
+
+    #[test]
+    fn negated_condition() {
+        assert_peephole(
+            rule("negated_condition"),
+            wrapped_if_then_else()
+                .apply(
+                    wrapped_if_then_else()
+                        .apply(Term::var("c"))
+                        .apply(Term::bool(false))
+                        .apply(Term::bool(true)),
+                )
+                .apply(Term::Error.delay())
+                .apply(Term::var("x").delay())
+                .force(),
+            wrapped_if_then_else()
+                .apply(Term::var("c"))
+                .apply(Term::var("x").delay())
+                .apply(Term::Error.delay())
+                .force(),
+        );
+    }
+
+    #[test]
+    fn if_true_false() {
+        assert_peephole(
+            rule("if_true_false"),
+            Term::var("c").if_then_else(Term::bool(true), Term::bool(false)),
+            Term::var("c"),
+        );
+    }
+
+    #[test]
+    fn delayed_if_true_false() {
+        assert_peephole(
+            RULES,
+            Term::var("c").delayed_if_then_else(Term::bool(true), Term::bool(false)),
+            Term::var("c"),
+        );
+    }
+
+    #[test]
+    fn double_negation() {
+        assert_peephole(
+            RULES,
+            Term::var("c")
+                .if_then_else(Term::bool(false), Term::bool(true))
+                .if_then_else(Term::bool(false), Term::bool(true)),
+            Term::var("c"),
+        );
+    }
+
+    #[test]
+    fn merged_error_branches() {
+        assert_peephole(
+            rule("merged_error_branches"),
+            Term::var("c1").delayed_if_then_else(
+                Term::Error,
+                Term::var("c2")
+                    .delayed_if_then_else(Term::Error, Term::var("f").apply(Term::var("x"))),
+            ),
+            Term::var("c1")
+                .if_then_else(Term::bool(true), Term::var("c2"))
+                .delayed_if_then_else(Term::Error, Term::var("f").apply(Term::var("x"))),
+        );
+    }
+
+    #[test]
+    fn merged_error_else_branches() {
+        assert_peephole(
+            rule("merged_error_branches"),
+            Term::var("c1").delayed_if_then_else(
+                Term::var("c2")
+                    .delayed_if_then_else(Term::var("f").apply(Term::var("x")), Term::Error),
+                Term::Error,
+            ),
+            Term::var("c1")
+                .if_then_else(Term::var("c2"), Term::bool(false))
+                .delayed_if_then_else(Term::var("f").apply(Term::var("x")), Term::Error),
+        );
+    }
+
+    #[test]
+    fn merged_error_branches_keeps_computed_conditions() {
+        let term = Term::var("c1").delayed_if_then_else(
+            Term::Error,
+            Term::var("f")
+                .apply(Term::var("x"))
+                .delayed_if_then_else(Term::Error, Term::var("x")),
+        );
+
+        assert_peephole(rule("merged_error_branches"), term.clone(), term);
+    }
+}
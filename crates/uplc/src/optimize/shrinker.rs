@@ -8,6 +8,7 @@ use crate::{
 use blst::{blst_p1, blst_p2};
 use indexmap::IndexMap;
 use itertools::{FoldWhile, Itertools};
+use pallas_codec::flat::Flat;
 use pallas_primitives::conway::{BigInt, PlutusData};
 use std::{cmp::Ordering, iter, ops::Neg, rc::Rc};
 use strum::IntoEnumIterator;
@@ -88,6 +89,12 @@ impl Default for IdGen {
 
 pub const NO_INLINE: &str = "__no_inline__";
 
+/// Serialized size, in bytes, of the lambda and application binding a shared constant.
+const SHARED_CONSTANT_BINDING_SIZE: usize = 1;
+
+/// Serialized size, in bytes, of a variable referring to a shared constant.
+const SHARED_CONSTANT_VAR_SIZE: usize = 2;
+
 #[derive(PartialEq, PartialOrd, Default, Debug, Clone)]
 pub struct VarLookup {
     found: bool,
@@ -1114,6 +1121,65 @@ impl Term<Name> {
         }
     }
 
+    fn count_shareable_constants(&self, counts: &mut IndexMap<Vec<u8>, (Rc<Constant>, usize)>) {
+        match self {
+            Term::Constant(c) => {
+                // Constants that can't be serialized can't be shared either; only BLS elements.
+                if let Ok(key) = c.flat() {
+                    counts.entry(key).or_insert_with(|| (c.clone(), 0)).1 += 1;
+                }
+            }
+            Term::Delay(d) | Term::Force(d) | Term::Lambda { body: d, .. } => {
+                d.count_shareable_constants(counts)
+            }
+            Term::Apply { function, argument } => {
+                function.count_shareable_constants(counts);
+                argument.count_shareable_constants(counts);
+            }
+            Term::Constr { fields, .. } => {
+                for field in fields {
+                    field.count_shareable_constants(counts);
+                }
+            }
+            Term::Case { constr, branches } => {
+                constr.count_shareable_constants(counts);
+                for branch in branches {
+                    branch.count_shareable_constants(counts);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn replace_shared_constants(&mut self, shared: &IndexMap<Vec<u8>, String>) {
+        match self {
+            Term::Constant(c) => {
+                if let Some(name) = c.flat().ok().and_then(|key| shared.get(&key)) {
+                    *self = Term::var(name);
+                }
+            }
+            Term::Delay(d) | Term::Force(d) | Term::Lambda { body: d, .. } => {
+                Rc::make_mut(d).replace_shared_constants(shared)
+            }
+            Term::Apply { function, argument } => {
+                Rc::make_mut(function).replace_shared_constants(shared);
+                Rc::make_mut(argument).replace_shared_constants(shared);
+            }
+            Term::Constr { fields, .. } => {
+                for field in fields {
+                    field.replace_shared_constants(shared);
+                }
+            }
+            Term::Case { constr, branches } => {
+                Rc::make_mut(constr).replace_shared_constants(shared);
+                for branch in branches {
+                    branch.replace_shared_constants(shared);
+                }
+            }
+            _ => (),
+        }
+    }
+
     fn replace_identity_usage(&mut self, original: Rc<Name>) {
         match self {
             Term::Delay(body) => {
@@ -2437,6 +2503,58 @@ impl Program<Name> {
         .0
     }
 
+    /// Bind constants occurring often enough in the program at its root, and refer to them by
+    /// name rather than repeating them at every use site. This includes module constants, which
+    /// code generation inlines as evaluated constants. Binders use the '__' prefix reserved for
+    /// compiler-generated names, so they can't capture a variable of the program.
+    pub fn share_constants(self) -> Self {
+        let mut counts = IndexMap::new();
+
+        self.term.count_shareable_constants(&mut counts);
+
+        let mut constants = vec![];
+
+        let shared = counts
+            .into_iter()
+            .filter(|(key, (_, count))| is_worth_sharing(key.len(), *count))
+            .enumerate()
+            .map(|(index, (key, (constant, _)))| {
+                let name = format!("__constant_{index}");
+                constants.push((constant, name.clone()));
+                (key, name)
+            })
+            .collect::<IndexMap<_, _>>();
+
+        if shared.is_empty() {
+            return self;
+        }
+
+        let mut term = self.term;
+
+        term.replace_shared_constants(&shared);
+
+        for (constant, name) in constants.into_iter().rev() {
+            term = term.lambda(name).apply(Term::Constant(constant));
+        }
+
+        Program {
+            version: self.version,
+            term,
+        }
+        .with_fresh_names()
+    }
+
+    /// Give every binder a unique that's consistent with its scope.
+    fn with_fresh_names(mut self) -> Self {
+        let mut interner = CodeGenInterner::new();
+
+        interner.program(&mut self);
+
+        let program = Program::<NamedDeBruijn>::try_from(self).unwrap();
+
+        Program::<Name>::try_from(program).unwrap()
+    }
+
     pub fn afterwards(self) -> Self {
         let (mut program, context) =
             self.traverse_uplc_with(true, &mut |id, term, arg_stack, scope, context| {
@@ -2454,13 +2572,7 @@ impl Program<Name> {
             program.term = program.term.data_list_to_integer_list();
         }
 
-        let mut interner = CodeGenInterner::new();
-
-        interner.program(&mut program);
-
-        let program = Program::<NamedDeBruijn>::try_from(program).unwrap();
-
-        Program::<Name>::try_from(program).unwrap()
+        program.with_fresh_names()
     }
 
     // This one doesn't use the context since it's complicated and traverses the ast twice
@@ -2702,6 +2814,13 @@ impl Program<Name> {
     }
 }
 
+/// Whether binding a constant of the given serialized size, used 'count' times, makes the
+/// program smaller than repeating it at every use site.
+fn is_worth_sharing(size: usize, count: usize) -> bool {
+    count > 1
+        && size * count > size + SHARED_CONSTANT_BINDING_SIZE + SHARED_CONSTANT_VAR_SIZE * count
+}
+
 fn id_vec_function_to_var(func_name: &str, id_vec: &[usize]) -> String {
     format!(
         "__{}_{}_curried",
@@ -2718,11 +2837,12 @@ fn id_vec_function_to_var(func_name: &str, id_vec: &[usize]) -> String {
 mod tests {
     use super::NO_INLINE;
     use crate::{
-        ast::{Constant, Data, Name, NamedDeBruijn, Program, Term},
+        ast::{Constant, Data, Name, NamedDeBruijn, Program, Term, Type},
         builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER},
         builtins::DefaultFunction,
         optimize::interner::CodeGenInterner,
     };
+    use pallas_codec::flat::Flat;
     use pallas_primitives::conway::{BigInt, PlutusData};
     use pretty_assertions::assert_eq;
 
@@ -3862,6 +3982,129 @@ mod tests {
         });
     }
 
+    #[test]
+    fn share_repeated_constants() {
+        let program: Program<Name> = Program {
+            version: (1, 1, 0),
+            term: Term::equals_bytestring()
+                .apply(
+                    Term::append_bytearray()
+                        .apply(Term::var("x"))
+                        .apply(Term::byte_string(vec![0; 28])),
+                )
+                .apply(
+                    Term::append_bytearray()
+                        .apply(Term::byte_string(vec![0; 28]))
+                        .apply(Term::byte_string(vec![1])),
+                )
+                .lambda("x"),
+        };
+
+        let expected = Program {
+            version: (1, 1, 0),
+            term: Term::equals_bytestring()
+                .apply(
+                    Term::append_bytearray()
+                        .apply(Term::var("x"))
+                        .apply(Term::var("__constant_0")),
+                )
+                .apply(
+                    Term::append_bytearray()
+                        .apply(Term::var("__constant_0"))
+                        .apply(Term::byte_string(vec![1])),
+                )
+                .lambda("x")
+                .lambda("__constant_0")
+                .apply(Term::byte_string(vec![0; 28])),
+        };
+
+        compare_optimization(expected, program, |p| p.share_constants());
+    }
+
+    #[test]
+    fn share_constants_without_capturing_variables() {
+        let program: Program<Name> = Program {
+            version: (1, 1, 0),
+            term: Term::equals_bytestring()
+                .apply(
+                    Term::append_bytearray()
+                        .apply(Term::var("constant_index_0"))
+                        .apply(Term::byte_string(vec![0; 28])),
+                )
+                .apply(Term::byte_string(vec![0; 28]))
+                .lambda("constant_index_0"),
+        };
+
+        let expected = Program {
+            version: (1, 1, 0),
+            term: Term::equals_bytestring()
+                .apply(
+                    Term::append_bytearray()
+                        .apply(Term::var("constant_index_0"))
+                        .apply(Term::var("__constant_0")),
+                )
+                .apply(Term::var("__constant_0"))
+                .lambda("constant_index_0")
+                .lambda("__constant_0")
+                .apply(Term::byte_string(vec![0; 28])),
+        };
+
+        compare_optimization(expected, program, |p| p.share_constants());
+    }
+
+    #[test]
+    fn do_not_share_small_constants() {
+        let small_list = || {
+            Term::Constant(
+                Constant::ProtoList(Type::Integer, vec![Constant::Integer(1.into())]).into(),
+            )
+        };
+
+        let program: Program<Name> = Program {
+            version: (1, 1, 0),
+            term: Term::equals_data()
+                .apply(Term::list_data().apply(small_list()))
+                .apply(Term::list_data().apply(small_list())),
+        };
+
+        compare_optimization(program.clone(), program, |p| p.share_constants());
+    }
+
+    #[test]
+    fn share_small_constants_used_often() {
+        let small_list = || {
+            Term::Constant(
+                Constant::ProtoList(Type::Integer, vec![Constant::Integer(1.into())]).into(),
+            )
+        };
+
+        let term = (0..10).fold(Term::var("x"), |term, _| {
+            Term::mk_cons()
+                .apply(Term::head_list().apply(small_list()))
+                .apply(term)
+        });
+
+        let program: Program<Name> = Program {
+            version: (1, 1, 0),
+            term: term.lambda("x"),
+        };
+
+        let expected = Program {
+            version: (1, 1, 0),
+            term: (0..10)
+                .fold(Term::var("x"), |term, _| {
+                    Term::mk_cons()
+                        .apply(Term::head_list().apply(Term::var("__constant_0")))
+                        .apply(term)
+                })
+                .lambda("x")
+                .lambda("__constant_0")
+                .apply(small_list()),
+        };
+
+        compare_optimization(expected, program, |p| p.share_constants());
+    }
+
     #[test]
     fn case_constr_apply_test_2() {
         let program: Program<Name> = Program {
@@ -17,6 +17,7 @@ pub use script_context::{DataLookupTable, ResolvedInput, SlotConfig};
 
 pub mod error;
 pub mod eval;
+pub mod ledger_spec;
 mod phase_one;
 pub mod script_context;
 #[cfg(test)]
@@ -0,0 +1,40 @@
+//! The on-chain layout of the script context handed over to Plutus V3 scripts, as defined by the
+//! ledger's Plutus API. It is vendored as data ('plutus_v3.json') rather than code, so that both
+//! the script contexts built here and the compiler's own view of them can be checked against a
+//! single description.
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+pub struct Constructor {
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Field {
+    pub name: String,
+    /// The field's type, as written in the ledger's (Haskell) definitions; e.g. 'Maybe Datum'.
+    #[serde(rename = "type")]
+    pub tipo: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Spec {
+    types: BTreeMap<String, Vec<Constructor>>,
+}
+
+static PLUTUS_V3: Lazy<Spec> = Lazy::new(|| {
+    serde_json::from_str(include_str!("plutus_v3.json")).expect("invalid vendored ledger spec")
+});
+
+/// The constructors of a type from the Plutus V3 script context (e.g. 'ScriptInfo'), in
+/// on-chain order; that is, by constructor index.
+pub fn plutus_v3(type_name: &str) -> &'static [Constructor] {
+    PLUTUS_V3
+        .types
+        .get(type_name)
+        .unwrap_or_else(|| panic!("no type '{type_name}' in the vendored ledger spec"))
+}
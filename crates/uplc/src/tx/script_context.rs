@@ -1133,7 +1133,9 @@ pub fn sort_reward_accounts(a: &Bytes, b: &Bytes) -> Ordering {
 mod tests {
     use crate::{
         ast::Data,
+        machine::runtime::convert_tag_to_constr,
         tx::{
+            ledger_spec,
             script_context::{TxInfo, TxInfoV3},
             to_plutus_data::ToPlutusData,
             ResolvedInput, SlotConfig,
@@ -1172,6 +1174,70 @@ mod tests {
         .unwrap()
     }
 
+    /// Check the shape of a script context against the ledger's layout, as vendored in
+    /// 'ledger_spec': constructor indices, number of fields, and the encoding of fields whose
+    /// type tells enough about it.
+    fn assert_follows_ledger_spec(script_context: &PlutusData) {
+        fn fields<'a>(
+            data: &'a PlutusData,
+            constructors: &'a [ledger_spec::Constructor],
+        ) -> (&'a ledger_spec::Constructor, &'a [PlutusData]) {
+            let PlutusData::Constr(constr) = data else {
+                panic!("not a constructor: {data:?}");
+            };
+
+            let index = convert_tag_to_constr(constr.tag)
+                .or(constr.any_constructor)
+                .unwrap() as usize;
+
+            let constructor = &constructors[index];
+
+            assert_eq!(
+                constr.fields.len(),
+                constructor.fields.len(),
+                "fields of {}",
+                constructor.name
+            );
+
+            (constructor, &constr.fields[..])
+        }
+
+        fn assert_shape(field: &ledger_spec::Field, data: &PlutusData) {
+            let tipo = field.tipo.as_str();
+
+            let expected = match tipo {
+                "Integer" | "Lovelace" => matches!(data, PlutusData::BigInt(..)),
+                _ if tipo.starts_with("Map ") => matches!(data, PlutusData::Map(..)),
+                _ if tipo.starts_with('[') => matches!(data, PlutusData::Array(..)),
+                _ if tipo.starts_with("Maybe ") => matches!(
+                    data,
+                    PlutusData::Constr(constr) if constr.tag == 121 || constr.tag == 122
+                ),
+                // Other types don't tell enough about their encoding.
+                _ => true,
+            };
+
+            assert!(expected, "{} :: {tipo} encoded as {data:?}", field.name);
+        }
+
+        let (context, context_fields) =
+            fields(script_context, ledger_spec::plutus_v3("ScriptContext"));
+
+        for (field, data) in context.fields.iter().zip(context_fields) {
+            match field.tipo.as_str() {
+                "TxInfo" | "ScriptInfo" => {
+                    let (constructor, constructor_fields) =
+                        fields(data, ledger_spec::plutus_v3(&field.tipo));
+
+                    for (field, data) in constructor.fields.iter().zip(constructor_fields) {
+                        assert_shape(field, data);
+                    }
+                }
+                _ => assert_shape(field, data),
+            }
+        }
+    }
+
     #[allow(dead_code)]
     fn from_haskell(data: &str) -> PlutusData {
         PlutusData::decode_fragment(hex::decode(data).unwrap().as_slice()).unwrap()
@@ -1213,6 +1279,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data())
     }
 
@@ -1273,6 +1341,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data());
     }
 
@@ -1348,6 +1418,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data());
     }
 
@@ -1397,6 +1469,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data());
     }
 
@@ -1467,6 +1541,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data());
     }
 
@@ -1520,6 +1596,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data());
     }
 
@@ -1578,6 +1656,8 @@ mod tests {
         // transactions. It is meant to control that our construction of the
         // script context and its serialization matches exactly those
         // from the Haskell ledger / cardano node.
+        assert_follows_ledger_spec(&script_context.to_plutus_data());
+
         insta::assert_debug_snapshot!(script_context.to_plutus_data());
     }
 }
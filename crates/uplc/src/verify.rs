@@ -0,0 +1,281 @@
+use crate::ast::{Constant, Name, Program, Term, Type, Unique};
+use std::collections::HashMap;
+
+/// A structural mistake in a program, which would make it fail to serialize or to evaluate no
+/// matter its inputs.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum VerifyError {
+    #[error("variable '{name}' (unique {unique}) is not bound")]
+    UnboundVariable { name: String, unique: Unique },
+
+    #[error(
+        "builtin '{builtin}' is forced {forces} time(s), but takes {expected} type argument(s)"
+    )]
+    WrongForceCount {
+        builtin: String,
+        forces: u32,
+        expected: u32,
+    },
+
+    #[error("constant {constant} is ill-formed: {reason}")]
+    IllFormedConstant { constant: String, reason: String },
+}
+
+impl Program<Name> {
+    /// Check that every variable is bound, that builtins are forced exactly as many times as they
+    /// have type arguments, and that constants agree with their declared types. This is a single
+    /// pass over the program.
+    pub fn verify(&self) -> Result<(), Vec<VerifyError>> {
+        let mut errors = vec![];
+
+        self.term.verify(&mut Scope::default(), 0, &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Variables in scope, counting how many enclosing lambdas bind each of them (a unique may be
+/// shadowed by itself). Lookups are constant-time, regardless of the nesting depth.
+#[derive(Default)]
+struct Scope(HashMap<Unique, usize>);
+
+impl Scope {
+    fn push(&mut self, unique: Unique) {
+        *self.0.entry(unique).or_default() += 1;
+    }
+
+    fn pop(&mut self, unique: Unique) {
+        if let Some(count) = self.0.get_mut(&unique) {
+            *count -= 1;
+            if *count == 0 {
+                self.0.remove(&unique);
+            }
+        }
+    }
+
+    fn contains(&self, unique: &Unique) -> bool {
+        self.0.contains_key(unique)
+    }
+}
+
+impl Term<Name> {
+    /// 'forces' is the number of 'force' directly wrapping this term.
+    fn verify(&self, scope: &mut Scope, forces: u32, errors: &mut Vec<VerifyError>) {
+        match self {
+            Term::Var(name) => {
+                if !scope.contains(&name.unique) {
+                    errors.push(VerifyError::UnboundVariable {
+                        name: name.text.clone(),
+                        unique: name.unique,
+                    });
+                }
+            }
+            Term::Lambda {
+                parameter_name,
+                body,
+            } => {
+                scope.push(parameter_name.unique);
+                body.verify(scope, 0, errors);
+                scope.pop(parameter_name.unique);
+            }
+            Term::Force(inner) => inner.verify(scope, forces + 1, errors),
+            Term::Builtin(builtin) => {
+                if forces != builtin.force_count() {
+                    errors.push(VerifyError::WrongForceCount {
+                        builtin: builtin.to_string(),
+                        forces,
+                        expected: builtin.force_count(),
+                    });
+                }
+            }
+            Term::Apply { function, argument } => {
+                function.verify(scope, 0, errors);
+                argument.verify(scope, 0, errors);
+            }
+            Term::Delay(inner) => inner.verify(scope, 0, errors),
+            Term::Constant(constant) => verify_constant(constant, errors),
+            Term::Constr { fields, .. } => {
+                for field in fields {
+                    field.verify(scope, 0, errors);
+                }
+            }
+            Term::Case { constr, branches } => {
+                constr.verify(scope, 0, errors);
+                for branch in branches {
+                    branch.verify(scope, 0, errors);
+                }
+            }
+            Term::Error => (),
+        }
+    }
+}
+
+fn verify_constant(constant: &Constant, errors: &mut Vec<VerifyError>) {
+    let ill_formed = |reason: String| VerifyError::IllFormedConstant {
+        constant: constant.to_pretty(),
+        reason,
+    };
+
+    match constant {
+        Constant::ProtoList(tipo, elems) => {
+            for elem in elems {
+                let elem_type = Type::from(elem);
+
+                if &elem_type != tipo {
+                    errors.push(ill_formed(format!(
+                        "list of {tipo} contains an element of type {elem_type}"
+                    )));
+                }
+
+                verify_constant(elem, errors);
+            }
+        }
+        Constant::ProtoPair(fst_type, snd_type, fst, snd) => {
+            for (expected, value) in [(fst_type, fst), (snd_type, snd)] {
+                let value_type = Type::from(value.as_ref());
+
+                if &value_type != expected {
+                    errors.push(ill_formed(format!(
+                        "pair component of type {value_type} declared as {expected}"
+                    )));
+                }
+
+                verify_constant(value, errors);
+            }
+        }
+        Constant::Integer(_)
+        | Constant::ByteString(_)
+        | Constant::String(_)
+        | Constant::Unit
+        | Constant::Bool(_)
+        | Constant::Data(_)
+        | Constant::Bls12_381G1Element(_)
+        | Constant::Bls12_381G2Element(_)
+        | Constant::Bls12_381MlResult(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerifyError;
+    use crate::{
+        ast::{Constant, Data, Name, Program, Term, Type},
+        builtins::DefaultFunction,
+        optimize::interner::CodeGenInterner,
+    };
+
+    fn verify(term: Term<Name>) -> Result<(), Vec<VerifyError>> {
+        let mut program = Program {
+            version: (1, 0, 0),
+            term,
+        };
+
+        CodeGenInterner::new().program(&mut program);
+
+        program.verify()
+    }
+
+    #[test]
+    fn well_formed() {
+        assert_eq!(
+            verify(
+                Term::var("x")
+                    .lambda("x")
+                    .apply(
+                        Term::head_list().apply(Term::list_values(vec![Constant::Data(
+                            Data::integer(1.into())
+                        )]))
+                    )
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn unbound_variable() {
+        assert!(matches!(
+            &verify(Term::var("y").lambda("x")).unwrap_err()[..],
+            [VerifyError::UnboundVariable { name, .. }] if name == "y"
+        ));
+    }
+
+    #[test]
+    fn missing_force() {
+        assert!(matches!(
+            &verify(Term::Builtin(DefaultFunction::HeadList).apply(Term::empty_list()))
+                .unwrap_err()[..],
+            [VerifyError::WrongForceCount {
+                forces: 0,
+                expected: 1,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn extra_force() {
+        assert!(matches!(
+            &verify(Term::add_integer().force()).unwrap_err()[..],
+            [VerifyError::WrongForceCount {
+                forces: 1,
+                expected: 0,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn unforced_builtin() {
+        assert!(matches!(
+            &verify(
+                Term::var("f")
+                    .lambda("f")
+                    .apply(Term::Builtin(DefaultFunction::HeadList))
+            )
+            .unwrap_err()[..],
+            [VerifyError::WrongForceCount {
+                forces: 0,
+                expected: 1,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn forced_builtin_as_value() {
+        assert_eq!(
+            verify(Term::var("f").lambda("f").apply(Term::head_list())),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn shadowed_variable() {
+        assert_eq!(
+            verify(Term::var("x").lambda("x").apply(Term::var("x")).lambda("x")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn deeply_nested_program() {
+        let term = (0..1_000).fold(Term::var("x0"), |body, i| body.lambda(format!("x{i}")));
+
+        assert_eq!(verify(term), Ok(()));
+    }
+
+    #[test]
+    fn ill_formed_list() {
+        assert!(matches!(
+            &verify(Term::Constant(
+                Constant::ProtoList(Type::Integer, vec![Constant::Bool(true)]).into()
+            ))
+            .unwrap_err()[..],
+            [VerifyError::IllFormedConstant { .. }]
+        ));
+    }
+}